@@ -0,0 +1,82 @@
+//! Guard for commands that check out chunk branches in the main working tree.
+//! Checking out over uncommitted changes either fails half-way through a run
+//! or carries the changes onto chunk branches, so classic (non-worktree) mode
+//! refuses to start on a dirty tree unless `--autostash` is given (or the
+//! `autostash` setting is on), in which case the changes are stashed for the
+//! run and re-applied afterwards.
+
+use anyhow::{bail, Result};
+use colored::Colorize;
+use std::path::{Path, PathBuf};
+
+use crate::{git, say, state::MergesState};
+
+pub struct Autostash {
+    root: PathBuf,
+    branch: String,
+    stashed: bool,
+}
+
+impl Autostash {
+    /// Check the working tree before `operation` starts checking out branches.
+    /// Worktree mode never touches it, so the check only applies in classic mode.
+    pub fn begin(root: &Path, state: &MergesState, operation: &str, enabled: bool) -> Result<Self> {
+        let mut guard = Autostash { root: root.to_path_buf(), branch: String::new(), stashed: false };
+        if state.use_worktrees || !git::is_dirty(root)? {
+            return Ok(guard);
+        }
+        if !enabled && !state.autostash {
+            bail!(
+                "You have uncommitted changes; `merges {}` checks out chunk branches in this working tree. \
+                 Commit or stash them first, or pass --autostash (`merges init --autostash` makes it the default).",
+                operation
+            );
+        }
+
+        guard.branch = git::current_branch(root)?;
+        git::stash_push(root, &format!("merges autostash ({})", operation))?;
+        guard.stashed = true;
+        say!("{} Stashed uncommitted changes on '{}'.", "→".blue().bold(), guard.branch.cyan());
+        Ok(guard)
+    }
+
+    /// Return to the starting branch and re-apply the stash, then hand back
+    /// the operation's `result`. If the stash cannot be re-applied it is left
+    /// in place and the user is told how to recover it.
+    pub fn finish<T>(self, result: Result<T>) -> Result<T> {
+        if !self.stashed {
+            return result;
+        }
+
+        let restored = self.restore();
+        match (result, restored) {
+            (Ok(value), Ok(())) => Ok(value),
+            (Ok(_), Err(e)) => Err(e),
+            (Err(e), Ok(())) => Err(e),
+            (Err(e), Err(restore_err)) => {
+                eprintln!("{} {:#}", "!".yellow().bold(), restore_err);
+                Err(e)
+            }
+        }
+    }
+
+    fn restore(&self) -> Result<()> {
+        let on_branch = git::current_branch(&self.root).is_ok_and(|b| b == self.branch);
+        if !on_branch && git::checkout(&self.root, &self.branch).is_err() {
+            bail!(
+                "Your uncommitted changes are still stashed. Once the working tree is clean, run \
+                 `git checkout {}` and `git stash pop`.",
+                self.branch
+            );
+        }
+        if let Err(e) = git::stash_pop(&self.root) {
+            bail!(
+                "Could not re-apply your stashed changes on '{}' ({:#}). They are kept in `git stash list`.",
+                self.branch,
+                e
+            );
+        }
+        say!("{} Restored your uncommitted changes.", "✓".green().bold());
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+//! Cooperative cancellation for long-running commands. The MCP server gives
+//! each tool call a token and trips it when the client sends
+//! `notifications/cancelled`; `push` and `sync` check it between chunks, so a
+//! cancelled run stops with every chunk it finished intact and none half-done.
+
+use anyhow::{bail, Result};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Fail if the operation was cancelled. `progress` says how far it got
+    /// and how to finish, for the error message.
+    pub fn check(&self, progress: &str) -> Result<()> {
+        if self.is_cancelled() {
+            bail!("Cancelled {}", progress);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clones_share_cancellation() {
+        let token = CancelToken::default();
+        let handed_out = token.clone();
+        assert!(handed_out.check("").is_ok());
+        token.cancel();
+        assert!(handed_out.is_cancelled());
+        assert_eq!(
+            handed_out.check("after 2 of 3 chunks").unwrap_err().to_string(),
+            "Cancelled after 2 of 3 chunks"
+        );
+    }
+}
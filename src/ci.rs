@@ -0,0 +1,447 @@
+//! Which GitHub Actions workflows each chunk's PR will run. A workflow with
+//! `paths:` or `paths-ignore:` filters only runs for PRs changing matching
+//! files, and one with `branches:` or `branches-ignore:` only for PRs into
+//! matching branches — which, stacked, most chunk PRs are not: they target
+//! the chunk below. So a chunk can trigger no CI at all — and never report
+//! the checks branch protection requires — or set off every workflow there is.
+//!
+//! The filters are read from `.github/workflows` on the source branch. Only
+//! the `pull_request` and `pull_request_target` triggers count, since those
+//! are what report checks on a PR, and only the block and flow forms of YAML
+//! workflow files use for them are understood.
+
+use serde::Serialize;
+use std::path::Path;
+
+use crate::{
+    commands::push::expected_pr_base,
+    git,
+    state::{MergesState, Strategy},
+};
+
+pub const WORKFLOWS_DIR: &str = ".github/workflows";
+
+/// Events whose runs report checks on a PR.
+const PR_EVENTS: &[&str] = &["pull_request", "pull_request_target"];
+
+/// The branch and path filters of one PR trigger; all empty when it has none.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Filter {
+    pub branches: Vec<String>,
+    pub branches_ignore: Vec<String>,
+    pub paths: Vec<String>,
+    pub paths_ignore: Vec<String>,
+}
+
+impl Filter {
+    fn is_empty(&self) -> bool {
+        *self == Filter::default()
+    }
+
+    /// True when a PR into `base` changing `files` passes the filter, by
+    /// GitHub's rules: `base` must match `branches` or none of
+    /// `branches-ignore`, and some file must match `paths` (a later
+    /// `!pattern` taking a match back) or none of `paths-ignore`.
+    fn passes(&self, base: &str, files: &[String]) -> bool {
+        let branch = if !self.branches.is_empty() {
+            matched(&self.branches, base)
+        } else {
+            !matched(&self.branches_ignore, base)
+        };
+        let paths = if !self.paths.is_empty() {
+            files.iter().any(|f| matched(&self.paths, f))
+        } else if !self.paths_ignore.is_empty() {
+            files.iter().any(|f| !matched(&self.paths_ignore, f))
+        } else {
+            true
+        };
+        branch && paths
+    }
+}
+
+/// A workflow that runs on PRs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Workflow {
+    /// Its `name:`, or its file name.
+    pub name: String,
+    /// One per PR trigger; the workflow runs when any of them passes.
+    pub triggers: Vec<Filter>,
+}
+
+impl Workflow {
+    /// True when some PRs do not run it.
+    pub fn is_filtered(&self) -> bool {
+        self.triggers.iter().all(|t| !t.is_empty())
+    }
+
+    /// True when it runs on a PR into `base` changing `files`.
+    pub fn runs_for(&self, base: &str, files: &[String]) -> bool {
+        self.triggers.iter().any(|t| t.passes(base, files))
+    }
+}
+
+/// The workflows predicted to run on one chunk's PR.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChunkCi {
+    pub chunk: String,
+    pub workflows: Vec<String>,
+}
+
+/// The workflows in `.github/workflows` at `rev` that run on PRs. A missing
+/// directory or an unreadable file counts as no workflow.
+pub fn workflows(root: &Path, rev: &str) -> Vec<Workflow> {
+    git::files_at_ref(root, rev, WORKFLOWS_DIR)
+        .unwrap_or_default()
+        .iter()
+        .filter(|f| f.ends_with(".yml") || f.ends_with(".yaml"))
+        .filter_map(|f| parse_workflow(f.rsplit('/').next().unwrap_or(f), &git::read_file_at_ref(root, rev, f)?))
+        .collect()
+}
+
+/// The workflows each chunk's PR should run, in stack order, with each PR
+/// targeting the branch `strategy` gives it.
+pub fn predict(state: &MergesState, strategy: &Strategy, workflows: &[Workflow]) -> Vec<ChunkCi> {
+    state
+        .chunks
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            let base = expected_pr_base(state, i, strategy);
+            ChunkCi {
+                chunk: c.name.clone(),
+                workflows: workflows.iter().filter(|w| w.runs_for(&base, &c.files)).map(|w| w.name.clone()).collect(),
+            }
+        })
+        .collect()
+}
+
+/// Chunks whose PR runs no workflow, and — when some workflows are
+/// filtered — chunks whose PR runs every one of them.
+pub fn warnings(state: &MergesState, strategy: &Strategy, workflows: &[Workflow]) -> Vec<String> {
+    if workflows.is_empty() {
+        return vec![];
+    }
+    let filtered = workflows.iter().any(Workflow::is_filtered);
+    predict(state, strategy, workflows)
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, p)| {
+            let base = expected_pr_base(state, i, strategy);
+            let files = &state.chunks[i].files;
+            if p.workflows.is_empty() && workflows.iter().any(|w| w.runs_for(&state.base_branch, files)) {
+                Some(format!(
+                    "Chunk '{}' triggers no CI workflow, so its PR will never report the required checks: it \
+                     targets '{}', which the workflows' `branches:` filters leave out. Add the chunk branches to \
+                     the filters, or push the stack as independent PRs.",
+                    p.chunk, base
+                ))
+            } else if p.workflows.is_empty() {
+                Some(format!(
+                    "Chunk '{}' triggers no CI workflow, so its PR will never report the required checks. \
+                     Move a file the workflows' `paths:` filters match into it, or widen the filters.",
+                    p.chunk
+                ))
+            } else if filtered && workflows.len() > 1 && p.workflows.len() == workflows.len() {
+                Some(format!(
+                    "Chunk '{}' triggers every CI workflow ({}); its files match all the path filters.",
+                    p.chunk,
+                    p.workflows.join(", ")
+                ))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// True when the filter `patterns` include `file`: the last pattern matching
+/// it decides, and `!pattern` excludes.
+fn matched(patterns: &[String], file: &str) -> bool {
+    patterns.iter().fold(false, |included, pattern| match pattern.strip_prefix('!') {
+        Some(negated) => included && !glob(negated, file),
+        None => included || glob(pattern, file),
+    })
+}
+
+/// One piece of a filter pattern.
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// `*`: anything within a path component.
+    Star,
+    /// `**`: anything, across components (`**/` also matching none).
+    AnyDepth,
+    /// A character or `[...]` set, `?` making it optional and `+` repeatable.
+    Char(Vec<(u8, u8)>, Repeat),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Repeat {
+    Once,
+    Optional,
+    OneOrMore,
+}
+
+/// GitHub filter globbing (see the "Filter pattern cheat sheet" in its
+/// workflow syntax docs): `*` and `**` as in [`Token`], `?` and `+` after a
+/// character or set for zero-or-one and one-or-more of it, `[abc]` and
+/// `[a-z]` for one of a set, and `\` to take the next character literally.
+fn glob(pattern: &str, path: &str) -> bool {
+    glob_tokens(&tokenize(pattern.as_bytes()), path.as_bytes())
+}
+
+fn tokenize(mut pattern: &[u8]) -> Vec<Token> {
+    let mut tokens = vec![];
+    while let Some(&c) = pattern.first() {
+        pattern = &pattern[1..];
+        let set = match c {
+            b'*' if pattern.first() == Some(&b'*') => {
+                pattern = &pattern[1..];
+                pattern = pattern.strip_prefix(b"/").unwrap_or(pattern);
+                tokens.push(Token::AnyDepth);
+                continue;
+            }
+            b'*' => {
+                tokens.push(Token::Star);
+                continue;
+            }
+            b'\\' if !pattern.is_empty() => {
+                let escaped = pattern[0];
+                pattern = &pattern[1..];
+                vec![(escaped, escaped)]
+            }
+            b'[' => match pattern.iter().position(|&b| b == b']') {
+                Some(end) if end > 0 => {
+                    let set = class(&pattern[..end]);
+                    pattern = &pattern[end + 1..];
+                    set
+                }
+                _ => vec![(c, c)],
+            },
+            _ => vec![(c, c)],
+        };
+        let repeat = match pattern.first() {
+            Some(b'?') => Repeat::Optional,
+            Some(b'+') => Repeat::OneOrMore,
+            _ => Repeat::Once,
+        };
+        if repeat != Repeat::Once {
+            pattern = &pattern[1..];
+        }
+        tokens.push(Token::Char(set, repeat));
+    }
+    tokens
+}
+
+/// The ranges of a `[...]` set's contents: `a-z` and single characters.
+fn class(mut inner: &[u8]) -> Vec<(u8, u8)> {
+    let mut ranges = vec![];
+    while let Some(&c) = inner.first() {
+        match inner {
+            [from, b'-', to, rest @ ..] => {
+                ranges.push((*from, *to));
+                inner = rest;
+            }
+            _ => {
+                ranges.push((c, c));
+                inner = &inner[1..];
+            }
+        }
+    }
+    ranges
+}
+
+fn glob_tokens(tokens: &[Token], path: &[u8]) -> bool {
+    match tokens {
+        [] => path.is_empty(),
+        [Token::AnyDepth, rest @ ..] => (0..=path.len()).any(|i| glob_tokens(rest, &path[i..])),
+        [Token::Star, rest @ ..] => {
+            (0..=path.len()).take_while(|&i| i == 0 || path[i - 1] != b'/').any(|i| glob_tokens(rest, &path[i..]))
+        }
+        [Token::Char(set, repeat), rest @ ..] => {
+            let matching = path.iter().take_while(|&&b| set.iter().any(|&(from, to)| (from..=to).contains(&b))).count();
+            match repeat {
+                Repeat::Once => matching >= 1 && glob_tokens(rest, &path[1..]),
+                Repeat::Optional => glob_tokens(rest, path) || (matching >= 1 && glob_tokens(rest, &path[1..])),
+                Repeat::OneOrMore => (1..=matching).any(|i| glob_tokens(rest, &path[i..])),
+            }
+        }
+    }
+}
+
+/// Read the PR triggers of a workflow file. `None` when it does not run on PRs.
+pub fn parse_workflow(file: &str, text: &str) -> Option<Workflow> {
+    let lines = yaml_lines(text);
+    let top = entries(&lines);
+    let name = top
+        .iter()
+        .find(|(key, _, _)| *key == "name")
+        .map(|(_, value, _)| unquote(value).to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| file.to_string());
+    let (_, value, body) = top.iter().find(|(key, _, _)| *key == "on")?;
+
+    // `on: pull_request`, `on: [push, pull_request]`, or a list of events
+    let events: Vec<(String, Filter)> = if body.iter().all(|(_, line)| line.starts_with('-')) {
+        sequence(value, body).into_iter().map(|event| (event, Filter::default())).collect()
+    } else {
+        entries(body)
+            .into_iter()
+            .map(|(event, _, settings)| {
+                let mut filter = Filter::default();
+                for (key, value, items) in entries(&settings) {
+                    match key {
+                        "branches" => filter.branches = sequence(value, &items),
+                        "branches-ignore" => filter.branches_ignore = sequence(value, &items),
+                        "paths" => filter.paths = sequence(value, &items),
+                        "paths-ignore" => filter.paths_ignore = sequence(value, &items),
+                        _ => {}
+                    }
+                }
+                (event.to_string(), filter)
+            })
+            .collect()
+    };
+    let triggers: Vec<Filter> =
+        events.into_iter().filter(|(event, _)| PR_EVENTS.contains(&event.as_str())).map(|(_, f)| f).collect();
+    if triggers.is_empty() {
+        return None;
+    }
+    Some(Workflow { name, triggers })
+}
+
+/// The non-blank lines of a YAML document without comments, as (indent, text).
+fn yaml_lines(text: &str) -> Vec<(usize, &str)> {
+    text.lines()
+        .filter_map(|line| {
+            let line = strip_comment(line).trim_end();
+            let content = line.trim_start();
+            if content.is_empty() { None } else { Some((line.len() - content.len(), content)) }
+        })
+        .collect()
+}
+
+/// `line` up to a `#` that starts a comment: one at the start or after
+/// whitespace, outside quotes.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    for (i, c) in line.char_indices() {
+        match (quote, c) {
+            (None, '\'' | '"') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '#') if i == 0 || line[..i].ends_with([' ', '\t']) => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// A mapping key, its inline value, and the lines nested under it.
+type Entry<'a> = (&'a str, &'a str, Vec<(usize, &'a str)>);
+
+/// The entries of the block mapping in `lines`. List items may sit at the
+/// keys' own indent, as YAML allows.
+fn entries<'a>(lines: &[(usize, &'a str)]) -> Vec<Entry<'a>> {
+    let Some(&(indent, _)) = lines.first() else { return vec![] };
+    let mut entries: Vec<Entry> = vec![];
+    for &(depth, text) in lines {
+        if depth == indent && !text.starts_with('-') {
+            let (key, value) = text.split_once(':').unwrap_or((text, ""));
+            entries.push((unquote(key.trim()), value.trim(), vec![]));
+        } else if let Some((_, _, nested)) = entries.last_mut() {
+            nested.push((depth, text));
+        }
+    }
+    entries
+}
+
+/// The items of a list written inline (`[a, b]` or a lone scalar) or as
+/// `- item` lines.
+fn sequence(value: &str, lines: &[(usize, &str)]) -> Vec<String> {
+    if let Some(inner) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+        return inner.split(',').map(|item| unquote(item.trim()).to_string()).filter(|i| !i.is_empty()).collect();
+    }
+    if !value.is_empty() {
+        return vec![unquote(value).to_string()];
+    }
+    lines.iter().filter_map(|(_, line)| line.strip_prefix('-')).map(|item| unquote(item.trim()).to_string()).collect()
+}
+
+fn unquote(s: &str) -> &str {
+    ['"', '\'']
+        .iter()
+        .find_map(|&q| s.strip_prefix(q).and_then(|s| s.strip_suffix(q)))
+        .unwrap_or(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files(list: &[&str]) -> Vec<String> {
+        list.iter().map(|f| f.to_string()).collect()
+    }
+
+    #[test]
+    fn test_glob_follows_github_filter_rules() {
+        assert!(glob("src/**", "src/api/routes.rs"));
+        assert!(glob("**.md", "docs/guide/intro.md"));
+        assert!(glob("src/**/mod.rs", "src/mod.rs"));
+        assert!(glob("*.toml", "Cargo.toml"));
+        assert!(!glob("*.toml", "crates/a/Cargo.toml"));
+        assert!(glob("*.jsx?", "app.js") && glob("*.jsx?", "app.jsx") && !glob("*.jsx?", "app.jsxx"));
+        assert!(glob("v[0-9]+.x", "v12.x") && !glob("v[0-9]+.x", "v.x"));
+        assert!(glob("release/[ab]*", "release/beta") && !glob("release/[ab]*", "release/rc"));
+        assert!(glob("docs/\\*.md", "docs/*.md") && !glob("docs/\\*.md", "docs/a.md"));
+        assert!(matched(&files(&["docs/**", "!docs/api/**"]), "docs/intro.md"));
+        assert!(!matched(&files(&["docs/**", "!docs/api/**"]), "docs/api/index.md"));
+    }
+
+    #[test]
+    fn test_parse_workflow_reads_pr_path_filters() {
+        let text = "\
+name: Backend
+on:
+  push:
+    branches: [main]
+  pull_request:
+    paths:
+      - 'src/**'   # the crate
+      - \"Cargo.*\"
+    paths-ignore: []
+jobs:
+  test:
+    runs-on: ubuntu-latest
+";
+        let workflow = parse_workflow("backend.yml", text).unwrap();
+        assert_eq!(workflow.name, "Backend");
+        assert_eq!(workflow.triggers, vec![Filter { paths: files(&["src/**", "Cargo.*"]), ..Default::default() }]);
+        assert!(workflow.runs_for("main", &files(&["src/lib.rs"])));
+        assert!(!workflow.runs_for("main", &files(&["web/app.ts"])));
+    }
+
+    #[test]
+    fn test_parse_workflow_inline_triggers_and_ignored_paths() {
+        let all = parse_workflow("lint.yaml", "on: [push, pull_request]\n").unwrap();
+        assert_eq!((all.name.as_str(), all.is_filtered()), ("lint.yaml", false));
+        assert!(parse_workflow("release.yml", "on:\n  push:\n    tags: ['v*']\n").is_none());
+
+        let docs = parse_workflow("code.yml", "on:\n  pull_request:\n    paths-ignore:\n    - 'docs/**'\n").unwrap();
+        assert!(!docs.runs_for("main", &files(&["docs/a.md"])));
+        assert!(docs.runs_for("main", &files(&["docs/a.md", "src/main.rs"])));
+    }
+
+    #[test]
+    fn test_parse_workflow_reads_pr_branch_filters() {
+        let text = "on:\n  pull_request:\n    branches: [main, 'release/**']\n";
+        let main_only = parse_workflow("ci.yml", text).unwrap();
+        assert!(main_only.is_filtered());
+        assert!(main_only.runs_for("main", &files(&["src/lib.rs"])));
+        assert!(main_only.runs_for("release/1.2", &files(&["src/lib.rs"])));
+        assert!(!main_only.runs_for("feat/big-chunk-1-models", &files(&["src/lib.rs"])));
+
+        let text = "on:\n  pull_request:\n    branches-ignore:\n      - 'feat/**'\n";
+        let ignore = parse_workflow("ci.yml", text).unwrap();
+        assert!(!ignore.runs_for("feat/big-chunk-1-models", &files(&["src/lib.rs"])));
+        assert!(ignore.runs_for("main", &files(&["src/lib.rs"])));
+    }
+}
@@ -0,0 +1,106 @@
+//! Routing small fixes made after the split to the chunks they belong to.
+//! Each uncommitted change on the source branch goes to the chunk that owns
+//! its file, as a commit on that chunk's branch and the same commit on the
+//! source branch, so both keep describing the same change.
+
+use anyhow::{bail, Result};
+use colored::Colorize;
+use std::path::Path;
+
+use crate::{
+    autostash::Autostash,
+    commands::squash,
+    git, journal, say,
+    state::{MergesState, Strategy},
+};
+
+/// Commit the uncommitted changes to chunk files — on the source branch, and
+/// onto each owning chunk's branch — returning the names of the chunks that
+/// got a commit. Changes to files no chunk owns are left uncommitted. Each
+/// commit gets `message`, or one naming its files. In a classic-mode stack,
+/// the chunks above a fixed one are checked out to restack them, so changes
+/// left uncommitted are refused unless `autostash` is set.
+pub fn run(root: &Path, message: Option<&str>, autostash: bool) -> Result<Vec<String>> {
+    let state = MergesState::load(root)?;
+    let current = git::current_branch(root)?;
+    if current != state.source_branch {
+        bail!(
+            "`merges absorb` takes changes made on the source branch '{}', but '{}' is checked out.",
+            state.source_branch,
+            current
+        );
+    }
+
+    let changed = git::uncommitted_files(root)?;
+    let mut routed: Vec<(usize, Vec<String>)> = vec![];
+    let mut unowned = vec![];
+    for file in changed {
+        match state.chunks.iter().position(|c| c.files.contains(&file)) {
+            Some(i) => match routed.iter_mut().find(|(owner, _)| *owner == i) {
+                Some((_, files)) => files.push(file),
+                None => routed.push((i, vec![file])),
+            },
+            None => unowned.push(file),
+        }
+    }
+    if routed.is_empty() {
+        bail!("No uncommitted changes to files in any chunk — nothing to absorb.");
+    }
+    routed.sort_by_key(|(i, _)| *i);
+    let restacks = !state.use_worktrees && state.strategy == Strategy::Stacked;
+    if restacks && !unowned.is_empty() && !autostash && !state.autostash {
+        bail!(
+            "Changes to files no chunk owns ({}) would be in the way while chunk branches are restacked. \
+             Commit or stash them first, or pass --autostash.",
+            unowned.join(", ")
+        );
+    }
+
+    // Build every chunk commit before moving anything, so a change that does
+    // not apply to its chunk leaves everything as it was.
+    let mut commits = vec![];
+    for (i, files) in &routed {
+        let chunk = &state.chunks[*i];
+        let message = message.map(String::from).unwrap_or_else(|| format!("Fix {}", files.join(", ")));
+        let tip = git::rev_parse(root, &chunk.branch)?;
+        let commit = git::commit_changes_onto(root, &tip, files, &message).map_err(|e| {
+            anyhow::anyhow!("The changes to {} do not apply to chunk '{}': {:#}", files.join(", "), chunk.name, e)
+        })?;
+        commits.push((*i, files, message, commit));
+    }
+
+    for (_, files, message, _) in &commits {
+        git::commit_only(root, files, message)?;
+    }
+
+    // Top of the stack first: moving a chunk restacks the ones above it, and
+    // those must already hold their own new commits.
+    let move_chunks = || -> Result<()> {
+        for (i, _, _, commit) in commits.iter().rev() {
+            squash::replace_tip(root, &state, *i, &current, commit)?;
+        }
+        Ok(())
+    };
+    if restacks {
+        // Any changes left over were allowed to be stashed above.
+        Autostash::begin(root, &state, "absorb", true)?.finish(move_chunks())?;
+    } else {
+        move_chunks()?;
+    }
+
+    let mut absorbed = vec![];
+    for (i, files, _, _) in &commits {
+        let chunk = &state.chunks[*i];
+        say!("{} {} → '{}'", "✓".green().bold(), files.join(", "), chunk.name.cyan());
+        absorbed.push(chunk.clone());
+    }
+    if !unowned.is_empty() {
+        say!(
+            "{} Left uncommitted, no chunk owns them: {} — `merges add <chunk> <file>` assigns them.",
+            "·".dimmed(),
+            unowned.join(", ")
+        );
+    }
+    journal::record(root, "absorb", &format!("fixes into {} chunk(s)", absorbed.len()), &absorbed)?;
+    Ok(absorbed.into_iter().map(|c| c.name).collect())
+}
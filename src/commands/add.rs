@@ -1,14 +1,21 @@
 use anyhow::{bail, Result};
 use colored::Colorize;
 
-use crate::{git, state::MergesState};
+use crate::{autostash::Autostash, git, journal, pathspec, say, split, state::MergesState};
 
-/// Add `files` to the named chunk.
+/// Add `files` to the named chunk. Each entry may be a path, a directory, or a
+/// glob pattern, expanded against the changed-file list.
 ///
 /// When `use_worktrees` is enabled, operations happen inside the chunk's
 /// worktree directory — the main working tree branch never changes.
-/// In classic mode, the chunk branch is checked out and then restored.
-pub fn run(root: &std::path::Path, chunk_name: &str, files: &[String]) -> Result<()> {
+/// In classic mode, the chunk branch is checked out and then restored, and a
+/// dirty working tree is refused unless `autostash` is set.
+pub fn run(root: &std::path::Path, chunk_name: &str, files: &[String], autostash: bool) -> Result<()> {
+    let stash = Autostash::begin(root, &MergesState::load(root)?, "add", autostash)?;
+    stash.finish(add(root, chunk_name, files))
+}
+
+fn add(root: &std::path::Path, chunk_name: &str, files: &[String]) -> Result<()> {
     let mut state = MergesState::load(root)?;
 
     // Find the chunk
@@ -28,16 +35,45 @@ pub fn run(root: &std::path::Path, chunk_name: &str, files: &[String]) -> Result
         bail!("No files provided.");
     }
 
-    // Validate all files are in the diff
+    // Expand directories and globs over the files no other chunk owns
+    // (skipping excluded files); every argument must match something in the diff
     let changed = git::changed_files(root, &state.base_branch)?;
-    for file in files {
-        if !changed.contains(file) {
+    let (included, _) = pathspec::partition_excluded(changed.clone(), &state.exclude);
+    let owner = |file: &str| {
+        state.chunks.iter().enumerate().find(|(i, c)| *i != chunk_idx && c.files.iter().any(|f| f == file))
+    };
+    let scope: Vec<String> = included.iter().filter(|f| owner(f).is_none()).cloned().collect();
+    let expansions = pathspec::expand_in(files, &changed, &scope)?;
+    for expansion in &expansions {
+        if expansion.files.is_empty() {
+            if !pathspec::resolve(&expansion.pattern, &included)?.files.is_empty() {
+                bail!(
+                    "Every file '{}' matches already belongs to another chunk; use `merges move` to move them.",
+                    expansion.pattern
+                );
+            }
             bail!(
                 "File '{}' is not in the diff between '{}' and HEAD.",
-                file,
+                expansion.pattern,
                 state.base_branch
             );
         }
+        if !expansion.is_literal() {
+            say!("{} {}", "·".dimmed(), expansion.describe());
+        }
+    }
+    let files = pathspec::flatten(&expansions);
+    for file in &files {
+        if let Some((_, other)) = owner(file) {
+            bail!(
+                "'{}' already belongs to chunk '{}'; use `merges move {} --from {} --to {}` to move it.",
+                file,
+                other.name,
+                file,
+                other.name,
+                chunk_name
+            );
+        }
     }
 
     // Deduplicate: only add files not already in the chunk
@@ -52,7 +88,7 @@ pub fn run(root: &std::path::Path, chunk_name: &str, files: &[String]) -> Result
     let chunk_branch = state.chunks[chunk_idx].branch.clone();
 
     if new_files.is_empty() {
-        println!(
+        say!(
             "{} All specified files are already in chunk '{}' — nothing to do.",
             "·".dimmed(),
             chunk_name.cyan()
@@ -69,18 +105,27 @@ pub fn run(root: &std::path::Path, chunk_name: &str, files: &[String]) -> Result
         root.to_path_buf()
     };
 
-    let result = (|| -> Result<()> {
-        git::checkout_files_from(&work_dir, &source_branch, &new_files)?;
+    // Reword the chunk commit so its file list includes the new files.
+    let mut chunk_files = state.chunks[chunk_idx].files.clone();
+    chunk_files.extend(new_files.iter().cloned());
+    let message = split::commit_message(
+        &state,
+        chunk_idx + 1,
+        chunk_name,
+        &chunk_files,
+        state.chunks[chunk_idx].custom_commit_message.as_deref(),
+    );
 
-        let amend_status = std::process::Command::new("git")
-            .args(["-C", work_dir.to_str().unwrap(), "add", "-A"])
-            .status()?;
-        if !amend_status.success() {
-            bail!("git add failed");
+    let result = (|| -> Result<()> {
+        if state.preserve_history {
+            return split::recommit_chunk(root, &work_dir, &state, chunk_idx, &chunk_files);
         }
+        // Staged exactly as on the source; see `git::take_files`.
+        git::checkout_files_from(&work_dir, &source_branch, &new_files)?;
 
-        let amend_status = std::process::Command::new("git")
-            .args(["-C", work_dir.to_str().unwrap(), "commit", "--amend", "--no-edit"])
+        let amend_status = git::command()
+            .args(["-C", work_dir.to_str().unwrap(), "commit", "--amend", "-m", &message])
+            .stdout(crate::output::child_stdout())
             .status()?;
         if !amend_status.success() {
             bail!("git commit --amend failed");
@@ -96,7 +141,7 @@ pub fn run(root: &std::path::Path, chunk_name: &str, files: &[String]) -> Result
 
     result?;
 
-    println!(
+    say!(
         "{} Added {} file(s) to chunk '{}'",
         "✓".green().bold(),
         new_files.len().to_string().yellow(),
@@ -104,8 +149,10 @@ pub fn run(root: &std::path::Path, chunk_name: &str, files: &[String]) -> Result
     );
 
     // Update state
+    let summary = format!("+{} file(s)", new_files.len());
     state.chunks[chunk_idx].files.extend(new_files);
     state.save(root)?;
+    journal::record(root, "add", &summary, &state.chunks[chunk_idx..=chunk_idx])?;
 
     Ok(())
 }
@@ -0,0 +1,144 @@
+use anyhow::{bail, Result};
+use colored::Colorize;
+use dialoguer::{theme::ColorfulTheme, MultiSelect, Select};
+use serde::{Deserialize, Serialize};
+
+use crate::{autostash::Autostash, commands::add, pathspec, say, state::MergesState};
+
+/// Changed files (after excludes) that no chunk contains yet, in diff order.
+pub fn unassigned_files(root: &std::path::Path, state: &MergesState) -> Result<Vec<String>> {
+    let (included, _) = crate::split::changed_files(root, state)?;
+    Ok(included
+        .into_iter()
+        .filter(|f| !state.chunks.iter().any(|c| c.files.contains(f)))
+        .collect())
+}
+
+/// Files to place in one existing chunk.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Assignment {
+    pub chunk: String,
+    pub files: Vec<String>,
+}
+
+/// Apply several assignments at once, as `merges_refresh` does. Every entry is
+/// checked before any branch changes: the chunk must exist, each path,
+/// directory, or glob must match an unassigned file, and no file may go to two
+/// chunks. Returns the assignments with patterns expanded to the files added.
+pub fn apply(root: &std::path::Path, assignments: &[Assignment], autostash: bool) -> Result<Vec<Assignment>> {
+    let state = MergesState::load(root)?;
+    let unassigned = unassigned_files(root, &state)?;
+    let mut resolved: Vec<Assignment> = vec![];
+    for assignment in assignments {
+        if !state.chunks.iter().any(|c| c.name == assignment.chunk) {
+            bail!("No chunk named '{}'.", assignment.chunk);
+        }
+        if assignment.files.is_empty() {
+            bail!("No files given for chunk '{}'.", assignment.chunk);
+        }
+        let expansions = pathspec::expand_in(&assignment.files, &unassigned, &unassigned)?;
+        if let Some(e) = expansions.iter().find(|e| e.files.is_empty()) {
+            bail!("'{}' does not match any unassigned file.", e.pattern);
+        }
+        let files = pathspec::flatten(&expansions);
+        if let Some((file, other)) = files
+            .iter()
+            .find_map(|f| resolved.iter().find(|a| a.files.contains(f)).map(|a| (f, &a.chunk)))
+        {
+            bail!("'{}' is assigned to both '{}' and '{}'.", file, other, assignment.chunk);
+        }
+        match resolved.iter_mut().find(|a| a.chunk == assignment.chunk) {
+            Some(existing) => existing.files.extend(files),
+            None => resolved.push(Assignment { chunk: assignment.chunk.clone(), files }),
+        }
+    }
+
+    let stash = Autostash::begin(root, &state, "assign", autostash)?;
+    stash.finish((|| -> Result<Vec<Assignment>> {
+        for assignment in &resolved {
+            add::run(root, &assignment.chunk, &assignment.files, false)?;
+        }
+        Ok(resolved)
+    })())
+}
+
+/// Triage changed files that no chunk owns yet.
+///
+/// - `to`: assign `files` (paths, directories, or globs among the unassigned
+///   files — all of them when empty) to that chunk without prompting.
+/// - Otherwise, repeatedly pick files and a destination chunk interactively.
+///
+/// Each assignment goes through `merges add`, so chunk branches are amended the
+/// same way; uncommitted changes are stashed once around all of them with `autostash`.
+pub fn run(root: &std::path::Path, to: Option<&str>, files: &[String], autostash: bool) -> Result<()> {
+    let stash = Autostash::begin(root, &MergesState::load(root)?, "assign", autostash)?;
+    stash.finish(assign(root, to, files))
+}
+
+fn assign(root: &std::path::Path, to: Option<&str>, files: &[String]) -> Result<()> {
+    let state = MergesState::load(root)?;
+    if state.chunks.is_empty() {
+        bail!("No chunks defined. Run `merges split` first.");
+    }
+
+    let unassigned = unassigned_files(root, &state)?;
+    if unassigned.is_empty() {
+        say!("{} Every changed file is already assigned to a chunk.", "✓".green().bold());
+        return Ok(());
+    }
+
+    say!("{} {} unassigned file(s):", "→".blue().bold(), unassigned.len().to_string().yellow());
+    for f in &unassigned {
+        say!("  {}", f.dimmed());
+    }
+
+    if let Some(chunk) = to {
+        let selected = if files.is_empty() {
+            unassigned
+        } else {
+            let expansions = pathspec::expand_in(files, &unassigned, &unassigned)?;
+            if let Some(e) = expansions.iter().find(|e| e.files.is_empty()) {
+                bail!("'{}' does not match any unassigned file.", e.pattern);
+            }
+            for e in expansions.iter().filter(|e| !e.is_literal()) {
+                say!("{} {}", "·".dimmed(), e.describe());
+            }
+            pathspec::flatten(&expansions)
+        };
+        return add::run(root, chunk, &selected, false);
+    }
+
+    crate::prompt::require("where to assign these files", "Pass --to <chunk> [<file>...].")?;
+    let chunk_names: Vec<&str> = state.chunks.iter().map(|c| c.name.as_str()).collect();
+    let mut remaining = unassigned;
+    while !remaining.is_empty() {
+        let selections = MultiSelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("Select files to assign (Space = toggle, Enter = confirm, none = stop)")
+            .items(&remaining)
+            .interact()?;
+        if selections.is_empty() {
+            break;
+        }
+        let selected: Vec<String> = selections.iter().map(|&i| remaining[i].clone()).collect();
+
+        let target = Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("Assign {} file(s) to chunk", selected.len()))
+            .items(&chunk_names)
+            .default(0)
+            .interact()?;
+
+        add::run(root, chunk_names[target], &selected, false)?;
+        remaining.retain(|f| !selected.contains(f));
+    }
+
+    if !remaining.is_empty() {
+        say!(
+            "\n{} {} file(s) left unassigned. Run {} again or exclude them.",
+            "!".yellow().bold(),
+            remaining.len(),
+            "merges assign".bold()
+        );
+    }
+
+    Ok(())
+}
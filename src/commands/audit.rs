@@ -0,0 +1,101 @@
+//! `merges audit`: check the stack for CI without changing anything. It
+//! makes the state checks `merges doctor` makes and the [`diffcheck`]
+//! invariant, never writes a file, ref, or branch, and reads branches from
+//! origin's remote-tracking refs where a CI checkout has no local ones.
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use std::path::Path;
+
+use crate::{
+    commands::{
+        diffcheck::{self, DiffCheck},
+        state::{validate, STATE_REF},
+    },
+    doctor, git,
+    state::{MergesState, STATE_FILE},
+};
+
+/// What `merges audit` found.
+#[derive(Debug, Serialize)]
+pub struct Audit {
+    /// Where the state was read from: a file, `.merges.json`, or [`STATE_REF`].
+    pub state: String,
+    /// Problems with the state and its branches.
+    pub issues: Vec<String>,
+    /// How the chunks compare with the source branch; `None` when they could
+    /// not be compared, with the reason among `issues`.
+    pub diffcheck: Option<DiffCheck>,
+}
+
+impl Audit {
+    pub fn ok(&self) -> bool {
+        self.issues.is_empty() && self.diffcheck.as_ref().is_some_and(DiffCheck::is_consistent)
+    }
+}
+
+/// Audit the stack in `root`, with the state from `state_file`, else
+/// `.merges.json`, else the state last fetched into [`STATE_REF`].
+pub fn run(root: &Path, state_file: Option<&Path>) -> Result<Audit> {
+    let (state, from) = load(root, state_file)?;
+    let mut issues = validate(root, &state);
+    if state.chunks.is_empty() {
+        issues.push("No chunks are defined.".to_string());
+    }
+
+    let view = on_origin(root, &state);
+    issues.extend(doctor::source_divergence(root, &view));
+    let diffcheck = match diffcheck::check(root, &view) {
+        Ok(report) => Some(report),
+        Err(e) => {
+            issues.push(format!("Could not compare the chunks with the source branch: {:#}", e));
+            None
+        }
+    };
+    Ok(Audit { state: from, issues, diffcheck })
+}
+
+fn load(root: &Path, state_file: Option<&Path>) -> Result<(MergesState, String)> {
+    let (content, from) = match state_file {
+        Some(file) => (
+            std::fs::read_to_string(file).with_context(|| format!("Could not read {}", file.display()))?,
+            file.display().to_string(),
+        ),
+        None if MergesState::path(root).exists() => {
+            (std::fs::read_to_string(MergesState::path(root))?, STATE_FILE.to_string())
+        }
+        None => match git::read_file_at_ref(root, STATE_REF, STATE_FILE) {
+            Some(content) => (content, STATE_REF.to_string()),
+            None => bail!(
+                "No {} here and no shared state in {}. In CI, fetch it first (`git fetch origin {}:{}`) or pass \
+                 --state with a file from `merges state export`.",
+                STATE_FILE,
+                STATE_REF,
+                STATE_REF,
+                STATE_REF
+            ),
+        },
+    };
+    let state = serde_json::from_str(&content).with_context(|| format!("{} is not a merges state file", from))?;
+    Ok((state, from))
+}
+
+/// `state` with each branch that exists only on origin named by its
+/// remote-tracking ref (`origin/<branch>`), as a CI clone has them.
+fn on_origin(root: &Path, state: &MergesState) -> MergesState {
+    let resolve = |branch: &str| {
+        let remote = format!("origin/{}", branch);
+        if !git::branch_exists(root, branch) && git::rev_parse(root, &remote).is_ok() {
+            remote
+        } else {
+            branch.to_string()
+        }
+    };
+    let mut view = state.clone();
+    view.base_branch = resolve(&state.base_branch);
+    view.source_branch = resolve(&state.source_branch);
+    for chunk in &mut view.chunks {
+        chunk.branch = resolve(&chunk.branch);
+    }
+    view
+}
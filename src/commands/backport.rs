@@ -0,0 +1,100 @@
+//! Bringing commits made directly on a chunk branch — review fixes pushed to
+//! the chunk's PR, say — back into the source branch, so the source branch
+//! and the chunks keep describing the same change.
+
+use anyhow::{bail, Result};
+use colored::Colorize;
+use std::path::Path;
+
+use crate::{autostash::Autostash, git, journal, say, split, state::MergesState};
+
+/// Cherry-pick the commits on chunk `name` that the source branch lacks onto
+/// the source branch, returning them. In classic mode a dirty working tree is
+/// refused unless `autostash` is set.
+pub fn run(root: &Path, name: &str, autostash: bool) -> Result<Vec<String>> {
+    let stash = Autostash::begin(root, &MergesState::load(root)?, "backport", autostash)?;
+    stash.finish(backport(root, name))
+}
+
+fn backport(root: &Path, name: &str) -> Result<Vec<String>> {
+    let state = MergesState::load(root)?;
+    let Some(i) = state.chunks.iter().position(|c| c.name == name) else {
+        bail!("No chunk named '{}'", name);
+    };
+    let chunk = &state.chunks[i];
+    let commits = missing_from_source(root, &state, i)?;
+    if commits.is_empty() {
+        say!("{} '{}' has no commits the source branch lacks — nothing to backport.", "·".dimmed(), chunk.name.cyan());
+        return Ok(vec![]);
+    }
+
+    // Pick where the source branch is checked out: here, a worktree of its
+    // own in worktree mode, or this working tree after switching to it.
+    let current = git::current_branch(root)?;
+    let source = &state.source_branch;
+    let dir = if current == *source {
+        root.to_path_buf()
+    } else if state.use_worktrees {
+        git::attach_worktree(root, source)?;
+        git::worktree_path(root, source)
+    } else {
+        git::checkout(root, source)?;
+        root.to_path_buf()
+    };
+    let picked = git::cherry_pick(&dir, &commits);
+    if current != *source {
+        if state.use_worktrees {
+            git::remove_worktree(root, source)?;
+        } else {
+            git::checkout(root, &current)?;
+        }
+    }
+    if let Err(e) = picked {
+        bail!(
+            "{:#}\nThe commits from '{}' do not apply cleanly to '{}'; nothing was changed. Cherry-pick them by hand: \
+             git cherry-pick -x {}",
+            e,
+            chunk.branch,
+            source,
+            commits.join(" ")
+        );
+    }
+
+    say!(
+        "{} Backported {} commit(s) from '{}' into '{}'",
+        "✓".green().bold(),
+        commits.len(),
+        chunk.name.cyan(),
+        source.cyan()
+    );
+    journal::record(
+        root,
+        "backport",
+        &format!("{} commit(s) from '{}' into '{}'", commits.len(), chunk.name, source),
+        std::slice::from_ref(chunk),
+    )?;
+    Ok(commits)
+}
+
+/// Commits on chunk `i`'s branch since it forked off its parent that the
+/// source branch lacks, oldest first. Commits with an equivalent on the source
+/// branch (the same change, e.g. one backported before) do not count, nor do
+/// the ones merges built the chunk from: every file they touch reads the same
+/// there as on the source branch, now or when the chunks were built. Merge
+/// commits are left out.
+pub fn missing_from_source(root: &Path, state: &MergesState, i: usize) -> Result<Vec<String>> {
+    let chunk = &state.chunks[i];
+    let fork = split::fork_point(root, state, i)?;
+    let built_from = state.source_sha.as_deref().unwrap_or(&state.source_branch);
+    let mut missing = vec![];
+    for line in git::commits_only_on(root, &state.source_branch, &chunk.branch, &[&fork], "%H %P")? {
+        let mut shas = line.split_whitespace();
+        let (Some(commit), Some(_), None) = (shas.next(), shas.next(), shas.next()) else { continue };
+        let touched = git::commit_touched_files(root, commit)?;
+        let differs = |rev: &str| -> Result<bool> { Ok(!git::differing_files(root, commit, rev, &touched)?.is_empty()) };
+        if differs(&state.source_branch)? && differs(built_from)? {
+            missing.push(commit.to_string());
+        }
+    }
+    Ok(missing)
+}
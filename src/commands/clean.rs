@@ -2,29 +2,83 @@ use anyhow::Result;
 use colored::Colorize;
 use dialoguer::Confirm;
 
-use crate::{config, git, github, state::MergesState};
+use std::path::Path;
+
+use crate::{
+    forge::{self, Forge},
+    git, journal, say,
+    state::MergesState,
+    warning,
+};
 
 pub async fn run(merged_only: bool, yes: bool) -> Result<()> {
     let root = git::repo_root()?;
-    let mut state = MergesState::load(&root)?;
+    let forge = if merged_only { forge::connect(&MergesState::load(&root)?).await.ok() } else { None };
+    run_with(&root, merged_only, yes, forge.as_deref()).await?;
+    Ok(())
+}
+
+/// Delete chunk branches in the repository at `root` — with `merged_only`,
+/// only those whose PR `forge` reports as merged (none without a forge) —
+/// and return the deleted branches. Unless `yes`, the user confirms first.
+pub async fn run_with(root: &Path, merged_only: bool, yes: bool, forge: Option<&dyn Forge>) -> Result<Vec<String>> {
+    let mut state = MergesState::load(root)?;
 
     if state.chunks.is_empty() {
-        println!("No chunks defined.");
-        return Ok(());
+        say!("No chunks defined.");
+        return Ok(vec![]);
     }
 
-    // Optionally check GitHub to find merged PRs
+    // Optionally check the forge to find merged PRs. A PR only counts when it was
+    // actually merged into the base branch and the base branch's required
+    // status checks passed — a PR that merely looks closed keeps its branch.
     let merged_pr_numbers: Vec<u64> = if merged_only {
-        let token = config::github_token().ok();
-        if let Some(tok) = token {
-            let gh = github::client(&tok)?;
+        if let Some(forge) = forge {
+            let required = match forge.required_status_checks(&state.base_branch).await {
+                Ok(required) => required,
+                Err(e) => {
+                    warning!("Could not read the checks '{}' requires: {:#}", state.base_branch, e);
+                    vec![]
+                }
+            };
             let mut merged = vec![];
             for chunk in &state.chunks {
                 let Some(pr_num) = chunk.pr_number else { continue };
-                let Ok(info) = github::get_pr_info(&gh, &state.repo_owner, &state.repo_name, pr_num).await else { continue };
-                if info.state == "closed" || info.state == "merged" {
-                    merged.push(pr_num);
+                let Ok(info) = forge.get_pr_info(pr_num).await else { continue };
+                if !info.merged {
+                    if info.state == "closed" {
+                        say!(
+                            "{} PR #{} ('{}') was closed without merging — keeping its branch.",
+                            "·".dimmed(),
+                            pr_num,
+                            chunk.name.cyan()
+                        );
+                    }
+                    continue;
                 }
+                if info.base != state.base_branch {
+                    say!(
+                        "{} PR #{} ('{}') was merged into '{}', not '{}' — keeping its branch.",
+                        "·".dimmed(),
+                        pr_num,
+                        chunk.name.cyan(),
+                        info.base,
+                        state.base_branch
+                    );
+                    continue;
+                }
+                let unmet = forge.unmet_required_checks(&info.head_sha, &required).await?;
+                if !unmet.is_empty() {
+                    warning!(
+                        "PR #{} ('{}') is missing required checks for '{}': {} — keeping its branch.",
+                        pr_num,
+                        chunk.name.cyan(),
+                        state.base_branch,
+                        unmet.join(", ")
+                    );
+                    continue;
+                }
+                merged.push(pr_num);
             }
             merged
         } else {
@@ -50,7 +104,7 @@ pub async fn run(merged_only: bool, yes: bool) -> Result<()> {
         .collect();
 
     if to_clean.is_empty() {
-        println!(
+        say!(
             "{}",
             if merged_only {
                 "No merged chunks found to clean.".to_string()
@@ -58,30 +112,31 @@ pub async fn run(merged_only: bool, yes: bool) -> Result<()> {
                 "No chunks to clean.".to_string()
             }
         );
-        return Ok(());
+        return Ok(vec![]);
     }
 
-    println!(
+    say!(
         "{} {} chunk branch(es) will be deleted:",
         "→".blue().bold(),
         to_clean.len().to_string().yellow()
     );
     for &i in &to_clean {
-        println!("  • {}", state.chunks[i].branch.cyan());
+        say!("  • {}", state.chunks[i].branch.cyan());
     }
 
     if !yes {
+        crate::prompt::require("for confirmation", "Pass --yes to delete these branches.")?;
         let confirmed = Confirm::new()
             .with_prompt("Delete these branches?")
             .default(false)
             .interact()?;
         if !confirmed {
-            println!("Aborted.");
-            return Ok(());
+            say!("Aborted.");
+            return Ok(vec![]);
         }
     }
 
-    let current = git::current_branch(&root)?;
+    let current = git::current_branch(root)?;
 
     // Delete in reverse order so indices remain valid
     let mut removed_branches = vec![];
@@ -90,36 +145,41 @@ pub async fn run(merged_only: bool, yes: bool) -> Result<()> {
 
         // Switch away if we're on this branch
         if current == *branch {
-            git::checkout(&root, &state.base_branch)?;
+            git::checkout(root, &state.base_branch)?;
         }
 
-        match git::delete_branch(&root, branch) {
+        match git::delete_branch(root, branch) {
             Ok(_) => {
                 // Also remove worktree if worktrees mode is enabled
                 if state.use_worktrees {
-                    let _ = git::remove_worktree(&root, branch);
+                    let _ = git::remove_worktree(root, branch);
                 }
-                println!("{} Deleted local branch '{}'", "✓".green(), branch.cyan());
+                say!("{} Deleted local branch '{}'", "✓".green(), branch.cyan());
                 removed_branches.push(branch.clone());
             }
             Err(e) => {
-                println!("{} Failed to delete '{}': {}", "!".yellow(), branch.cyan(), e);
+                warning!("Failed to delete '{}': {}", branch.cyan(), e);
             }
         }
     }
 
     // Remove cleaned chunks from state
+    let removed: Vec<_> = state.chunks.iter().filter(|c| removed_branches.contains(&c.branch)).cloned().collect();
     state
         .chunks
         .retain(|c| !removed_branches.contains(&c.branch));
-    state.save(&root)?;
+    state.save(root)?;
+    if !removed.is_empty() {
+        let summary = format!("deleted {} branch(es){}", removed.len(), if merged_only { " (merged)" } else { "" });
+        journal::record(root, "clean", &summary, &removed)?;
+    }
 
-    println!(
+    say!(
         "\n{} Cleaned {} chunk(s). {} chunk(s) remain.",
         "✓".green().bold(),
         removed_branches.len().to_string().yellow(),
         state.chunks.len().to_string().yellow()
     );
 
-    Ok(())
+    Ok(removed_branches)
 }
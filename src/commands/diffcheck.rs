@@ -0,0 +1,130 @@
+//! The invariant the whole tool rests on: landing every chunk changes the
+//! base exactly as the source branch does. Each file the source branch
+//! changes must be changed by exactly one chunk, to the same content, and no
+//! chunk may change anything else.
+
+use anyhow::{bail, Result};
+use colored::Colorize;
+use serde::Serialize;
+use std::path::Path;
+
+use crate::{git, pathspec, split, state::MergesState};
+
+/// A file one chunk changes differently from the source branch.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Mismatch {
+    pub chunk: String,
+    pub file: String,
+    /// Hunk headers of the diff from the chunk's copy to the source's.
+    pub hunks: Vec<String>,
+}
+
+/// Everything that breaks the invariant.
+#[derive(Debug, Default, Serialize)]
+pub struct DiffCheck {
+    /// Files the source branch changes that no chunk does.
+    pub missing: Vec<String>,
+    /// Files a chunk changes that the source branch does not, by chunk.
+    pub extra: Vec<(String, Vec<String>)>,
+    /// Files changed by more than one chunk, with those chunks.
+    pub overlapping: Vec<(String, Vec<String>)>,
+    pub mismatched: Vec<Mismatch>,
+    /// Files the source branch changes that are excluded by the `exclude`
+    /// setting; chunks are expected to leave them out.
+    pub excluded: Vec<String>,
+    /// Files the source branch changes whose paths are not UTF-8, shown
+    /// lossily; merges leaves them out, so no chunk can hold them.
+    pub unsupported: Vec<String>,
+}
+
+impl DiffCheck {
+    pub fn is_consistent(&self) -> bool {
+        self.missing.is_empty()
+            && self.extra.is_empty()
+            && self.overlapping.is_empty()
+            && self.mismatched.is_empty()
+            && self.unsupported.is_empty()
+    }
+}
+
+/// Compare what each chunk changes — since it forked off its parent, so a
+/// stacked chunk is not blamed for the ones below it — with what the source
+/// branch changes against the base.
+pub fn check(root: &Path, state: &MergesState) -> Result<DiffCheck> {
+    let changed = git::diff_files(root, &state.base_branch, &state.source_branch)?;
+    let (source, mut excluded) = pathspec::partition_excluded(changed, &state.exclude);
+    let unsupported = git::non_utf8_diff_files(root, &state.base_branch, &state.source_branch)?;
+    let (unsupported, skipped) = pathspec::partition_excluded(unsupported, &state.exclude);
+    excluded.extend(skipped);
+
+    let mut report = DiffCheck { excluded, unsupported, ..Default::default() };
+    let mut owners: Vec<(String, Vec<String>)> = vec![];
+    for i in 0..state.chunks.len() {
+        let chunk = &state.chunks[i];
+        let fork = split::fork_point(root, state, i)?;
+        let files = git::diff_files(root, &fork, &chunk.branch)?;
+        let extra: Vec<String> = files.iter().filter(|f| !source.contains(f)).cloned().collect();
+        if !extra.is_empty() {
+            report.extra.push((chunk.name.clone(), extra));
+        }
+        let ours: Vec<String> = files.into_iter().filter(|f| source.contains(f)).collect();
+        for file in git::differing_files(root, &chunk.branch, &state.source_branch, &ours)? {
+            let hunks = git::hunk_headers(root, &chunk.branch, &state.source_branch, &file)?;
+            report.mismatched.push(Mismatch { chunk: chunk.name.clone(), file, hunks });
+        }
+        for file in ours {
+            match owners.iter_mut().find(|(f, _)| *f == file) {
+                Some((_, chunks)) => chunks.push(chunk.name.clone()),
+                None => owners.push((file, vec![chunk.name.clone()])),
+            }
+        }
+    }
+    report.missing = source.iter().filter(|f| !owners.iter().any(|(o, _)| o == *f)).cloned().collect();
+    report.overlapping = owners.into_iter().filter(|(_, chunks)| chunks.len() > 1).collect();
+    Ok(report)
+}
+
+/// Print the check and fail if the chunks do not add up to the source branch.
+pub fn run() -> Result<()> {
+    let root = git::repo_root()?;
+    let state = MergesState::load(&root)?;
+    if state.chunks.is_empty() {
+        println!("No chunks defined yet. Run {} first.", "merges split".bold());
+        return Ok(());
+    }
+
+    let report = check(&root, &state)?;
+    for file in &report.missing {
+        println!("{} {} is changed on the source branch but in no chunk", "✗".red().bold(), file);
+    }
+    for (chunk, files) in &report.extra {
+        println!("{} [{}] changes files the source branch does not: {}", "✗".red().bold(), chunk.cyan(), files.join(", "));
+    }
+    for (file, chunks) in &report.overlapping {
+        println!("{} {} is changed by several chunks: {}", "✗".red().bold(), file, chunks.join(", "));
+    }
+    for m in &report.mismatched {
+        println!("{} [{}] {} differs from the source branch: {}", "✗".red().bold(), m.chunk.cyan(), m.file, m.hunks.join(" "));
+    }
+    for file in &report.unsupported {
+        println!("{} {} is changed on the source branch but its path is not UTF-8; rename it", "✗".red().bold(), file);
+    }
+    if !report.excluded.is_empty() {
+        println!("{} {} excluded file(s) left out as configured.", "·".dimmed(), report.excluded.len());
+    }
+
+    if !report.is_consistent() {
+        bail!(
+            "The chunks do not add up to '{}'. `merges assign` places missing files; `merges refresh --rebuild` rebuilds \
+             the chunks from the source branch.",
+            state.source_branch
+        );
+    }
+    println!(
+        "{} The {} chunk(s) add up to '{}' exactly.",
+        "✓".green().bold(),
+        state.chunks.len(),
+        state.source_branch.cyan()
+    );
+    Ok(())
+}
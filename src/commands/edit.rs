@@ -0,0 +1,72 @@
+//! `merges edit`: open a chunk in the editor, to address review feedback on
+//! it without hunting for its worktree or files. The editor comes from
+//! [`config::editor`] and is run the way git runs `core.editor`, through
+//! the shell, so it may carry arguments.
+
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::{commands::exec::chunk_env, config, git, say, state::MergesState};
+
+/// Open chunk `name` in the editor and wait for it: its worktree, or with
+/// `files` (always in classic mode) each file assigned to it.
+pub fn run(root: &Path, name: &str, files: bool) -> Result<()> {
+    let state = MergesState::load(root)?;
+    let Some(i) = state.chunks.iter().position(|c| c.name == name) else {
+        bail!("No chunk named '{}'", name);
+    };
+    let (dir, targets) = targets(root, &state, i, files)?;
+    let editor = config::editor(root)?;
+    let what = match &targets[..] {
+        [one] => one.display().to_string(),
+        many => format!("{} files", many.len()),
+    };
+    say!("{} [{}] Opening {} in {}", "→".blue().bold(), name.cyan(), what, editor);
+
+    let status = editor_command(&editor, &targets)
+        .current_dir(&dir)
+        .envs(chunk_env(&state, i, &dir))
+        .status()
+        .with_context(|| format!("Failed to start `{}`", editor))?;
+    if !status.success() {
+        bail!("`{}` exited with {}", editor, status);
+    }
+    Ok(())
+}
+
+/// The directory the editor runs in for chunk `i`, and what it opens there:
+/// the chunk's worktree, or its files that exist (a deleted file has nothing
+/// to open) when `files` is set or worktrees are off.
+pub fn targets(root: &Path, state: &MergesState, i: usize, files: bool) -> Result<(PathBuf, Vec<PathBuf>)> {
+    let chunk = &state.chunks[i];
+    let dir = if state.use_worktrees { git::worktree_path(root, &chunk.branch) } else { root.to_path_buf() };
+    if !dir.is_dir() {
+        bail!("The worktree of '{}' is missing at {}; see `merges doctor`.", chunk.name, dir.display());
+    }
+    if state.use_worktrees && !files {
+        return Ok((dir.clone(), vec![dir]));
+    }
+    let targets: Vec<PathBuf> = chunk.files.iter().map(|f| dir.join(f)).filter(|f| f.is_file()).collect();
+    if targets.is_empty() {
+        bail!("Chunk '{}' has no files to open.", chunk.name);
+    }
+    Ok((dir, targets))
+}
+
+/// `editor` with `paths` appended, through `sh -c` (`cmd /C` on Windows) so
+/// an editor setting like `code --wait` works.
+fn editor_command(editor: &str, paths: &[PathBuf]) -> Command {
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.arg("/C").arg(editor);
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.args(["-c", &format!("{} \"$@\"", editor), editor]);
+        command
+    };
+    command.args(paths);
+    command
+}
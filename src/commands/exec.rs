@@ -0,0 +1,98 @@
+//! Running a command once per chunk, on the chunk's branch. The command gets
+//! the chunk it is running for in its environment (see [`chunk_env`]), so
+//! scripts can act per chunk without reading `.merges.json` themselves.
+
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::{autostash::Autostash, git, say, state::MergesState};
+
+/// The environment a per-chunk command runs with, for chunk `i` checked out
+/// in `dir`:
+///
+/// - `MERGES_CHUNK_NAME`: the chunk's name
+/// - `MERGES_CHUNK_BRANCH`: its branch
+/// - `MERGES_CHUNK_FILES`: the files assigned to it, one per line
+/// - `MERGES_BASE_BRANCH`: the branch the chunks target
+/// - `MERGES_WORKTREE`: the directory the chunk is checked out in
+pub fn chunk_env(state: &MergesState, i: usize, dir: &Path) -> Vec<(&'static str, String)> {
+    let chunk = &state.chunks[i];
+    vec![
+        ("MERGES_CHUNK_NAME", chunk.name.clone()),
+        ("MERGES_CHUNK_BRANCH", chunk.branch.clone()),
+        ("MERGES_CHUNK_FILES", chunk.files.join("\n")),
+        ("MERGES_BASE_BRANCH", state.base_branch.clone()),
+        ("MERGES_WORKTREE", dir.to_string_lossy().into_owned()),
+    ]
+}
+
+/// Run `command` (a program and its arguments) once for each of the named
+/// chunks — every chunk when `chunks` is empty — in the chunk's worktree, or
+/// with its branch checked out in classic mode. Every chunk gets its turn;
+/// returns the names of those the command failed for. In classic mode a dirty
+/// working tree is refused unless `autostash` is set.
+pub fn run(root: &Path, command: &[String], chunks: &[String], autostash: bool) -> Result<Vec<String>> {
+    let stash = Autostash::begin(root, &MergesState::load(root)?, "exec", autostash)?;
+    stash.finish(exec(root, command, chunks))
+}
+
+fn exec(root: &Path, command: &[String], only: &[String]) -> Result<Vec<String>> {
+    let state = MergesState::load(root)?;
+    let Some((program, args)) = command.split_first() else { bail!("No command given.") };
+
+    let mut failed = vec![];
+    for_each_chunk(root, &state, only, |i, dir| {
+        let chunk = &state.chunks[i];
+        say!("{} [{}] {}", "→".blue().bold(), chunk.name.cyan(), command.join(" "));
+        let status = Command::new(program)
+            .args(args)
+            .current_dir(dir)
+            .envs(chunk_env(&state, i, dir))
+            .status()
+            .with_context(|| format!("Failed to run `{}`", program))?;
+        if !status.success() {
+            say!("{} [{}] exited with {}", "✗".red().bold(), chunk.name.cyan(), status);
+            failed.push(chunk.name.clone());
+        }
+        Ok(())
+    })?;
+    Ok(failed)
+}
+
+/// Call `f` with the index of each of the named chunks — every chunk when
+/// `only` is empty — and the directory it is checked out in: its worktree, or
+/// the repository with its branch checked out in classic mode, switching back
+/// to the current branch afterwards.
+pub(crate) fn for_each_chunk(
+    root: &Path,
+    state: &MergesState,
+    only: &[String],
+    mut f: impl FnMut(usize, &Path) -> Result<()>,
+) -> Result<()> {
+    if let Some(unknown) = only.iter().find(|name| !state.chunks.iter().any(|c| c.name == **name)) {
+        bail!("No chunk named '{}'", unknown);
+    }
+    let current = git::current_branch(root)?;
+
+    let result = (|| -> Result<()> {
+        for (i, chunk) in state.chunks.iter().enumerate() {
+            if !only.is_empty() && !only.contains(&chunk.name) {
+                continue;
+            }
+            let dir: PathBuf = if state.use_worktrees {
+                git::worktree_path(root, &chunk.branch)
+            } else {
+                git::checkout(root, &chunk.branch)?;
+                root.to_path_buf()
+            };
+            f(i, &dir)?;
+        }
+        Ok(())
+    })();
+    if !state.use_worktrees && git::current_branch(root)? != current {
+        git::checkout(root, &current)?;
+    }
+    result
+}
@@ -0,0 +1,81 @@
+use anyhow::{bail, Result};
+use colored::Colorize;
+use std::path::{Path, PathBuf};
+
+use crate::{git, say, split, state::MergesState};
+
+/// Export the stack as a numbered `git format-patch` series in `out_dir`.
+///
+/// Each chunk becomes exactly one patch — review commits on a chunk branch are
+/// squashed — and patches are numbered in stack order so they apply in sequence.
+/// With `cover_letter`, a `0000-cover-letter.patch` summarising the stack from
+/// state is written as well. Returns the paths written, in series order.
+pub fn run(root: &Path, out_dir: &Path, cover_letter: bool) -> Result<Vec<PathBuf>> {
+    let state = MergesState::load(root)?;
+
+    if state.chunks.is_empty() {
+        bail!("No chunks defined. Run `merges split` first.");
+    }
+
+    // `git -C <root>` would resolve a relative output dir against the repo root.
+    let out_dir = &std::path::absolute(out_dir)?;
+    std::fs::create_dir_all(out_dir)?;
+
+    let prefix = state.message_prefix().unwrap_or_default();
+    let total = state.chunks.len();
+    let mut written = vec![];
+
+    if cover_letter {
+        let path = out_dir.join("0000-cover-letter.patch");
+        std::fs::write(&path, cover_letter_text(root, &state, &prefix))?;
+        written.push(path);
+    }
+
+    for (i, chunk) in state.chunks.iter().enumerate() {
+        let parent = git::merge_base_of(root, &state.base_branch, &chunk.branch)?;
+        let msg = split::commit_message(&state, i + 1, &chunk.name, &chunk.files, chunk.custom_commit_message.as_deref());
+        let commit = git::squash_commit(root, &chunk.branch, &parent, &msg)?;
+        let path = git::format_patch(root, &commit, out_dir, i + 1, total)?;
+        say!("{} [{}] {}", "✓".green(), chunk.name.cyan(), path.display().to_string().dimmed());
+        written.push(path);
+    }
+
+    say!(
+        "\n{} Exported {} patch(es) to {}",
+        "✓".green().bold(),
+        total.to_string().yellow(),
+        out_dir.display()
+    );
+
+    Ok(written)
+}
+
+/// Build an mbox-style cover letter (`[PATCH 0/N]`) listing every chunk.
+fn cover_letter_text(root: &Path, state: &MergesState, prefix: &str) -> String {
+    let name = git::config_value(root, "user.name").unwrap_or_else(|| "merges".to_string());
+    let email = git::config_value(root, "user.email").unwrap_or_default();
+    let subject = if prefix.is_empty() {
+        state.source_branch.clone()
+    } else {
+        format!("{} {}", prefix, state.source_branch)
+    };
+    let chunks = state
+        .chunks
+        .iter()
+        .enumerate()
+        .map(|(i, c)| format!("  {}/{} {} ({} file(s))", i + 1, state.chunks.len(), c.name, c.files.len()))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        "From: {} <{}>\nSubject: [PATCH 0/{}] {}\n\nThis series splits {} into {} patch(es) against {}:\n\n{}\n\n-- \nGenerated by merges\n",
+        name,
+        email,
+        state.chunks.len(),
+        subject,
+        state.source_branch,
+        state.chunks.len(),
+        state.base_branch,
+        chunks
+    )
+}
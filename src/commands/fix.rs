@@ -0,0 +1,104 @@
+//! `merges fix`: carry review fixes committed on the source branch to the
+//! chunks they belong to. Where `merges absorb` adds a commit per fix, this
+//! folds the source branch's new version of each file into the owning
+//! chunk's commit, so the PR's branch reads as if it had been right all along.
+
+use anyhow::{anyhow, Result};
+use colored::Colorize;
+use std::path::Path;
+
+use crate::{
+    autostash::Autostash,
+    commands::squash,
+    git, journal, pathspec, say, split,
+    state::{MergesState, Strategy},
+    warning,
+};
+
+/// Amend each chunk's commit with the source branch's version of the chunk
+/// files that differ from it, returning the names of the chunks amended.
+/// With `only`, just that chunk is fixed, and files the source branch changes
+/// that no chunk owns join it. Chunks stacked on an amended chunk are rebased
+/// onto it; in classic mode that checks them out, so a dirty working tree is
+/// refused unless `autostash` is set.
+pub fn run(root: &Path, only: Option<&str>, autostash: bool) -> Result<Vec<String>> {
+    let mut state = MergesState::load(root)?;
+    let target = match only {
+        Some(name) => Some(
+            state.chunks.iter().position(|c| c.name == name).ok_or_else(|| anyhow!("No chunk named '{}'", name))?,
+        ),
+        None => None,
+    };
+    let current = git::current_branch(root)?;
+    if current == state.source_branch && !git::uncommitted_files(root)?.is_empty() {
+        warning!(
+            "Uncommitted changes on '{}' are left out — commit them first, or use `merges absorb`.",
+            state.source_branch
+        );
+    }
+
+    let mut fixes: Vec<(usize, Vec<String>)> = vec![];
+    for (i, chunk) in state.chunks.iter().enumerate() {
+        if target.is_some_and(|t| t != i) {
+            continue;
+        }
+        let files = git::differing_files(root, &chunk.branch, &state.source_branch, &chunk.files)?;
+        if !files.is_empty() {
+            fixes.push((i, files));
+        }
+    }
+    let mut added = vec![];
+    if let Some(t) = target {
+        let changed = git::diff_files(root, &state.base_branch, &state.source_branch)?;
+        let (included, _) = pathspec::partition_excluded(changed, &state.exclude);
+        added = included.into_iter().filter(|f| !state.chunks.iter().any(|c| c.files.contains(f))).collect();
+        if !added.is_empty() {
+            match fixes.first_mut() {
+                Some((_, files)) => files.extend(added.iter().cloned()),
+                None => fixes.push((t, added.clone())),
+            }
+        }
+    }
+    if fixes.is_empty() {
+        say!("{} Every chunk matches the source branch — nothing to fix.", "·".dimmed());
+        return Ok(vec![]);
+    }
+
+    // Build every amended commit before moving any branch, so a failure
+    // leaves the stack as it was.
+    let mut commits = vec![];
+    for (i, files) in &fixes {
+        let chunk = &state.chunks[*i];
+        // New files change the file list the commit message names.
+        let message = (target == Some(*i) && !added.is_empty()).then(|| {
+            let all: Vec<String> = chunk.files.iter().chain(&added).cloned().collect();
+            split::commit_message(&state, i + 1, &chunk.name, &all, chunk.custom_commit_message.as_deref())
+        });
+        let tip = git::rev_parse(root, &chunk.branch)?;
+        commits.push((*i, git::amend_with_files(root, &tip, &state.source_branch, files, message.as_deref())?));
+    }
+
+    // Top of the stack first, as `merges absorb` does.
+    let move_chunks = || -> Result<()> {
+        for (i, commit) in commits.iter().rev() {
+            squash::replace_tip(root, &state, *i, &current, commit)?;
+        }
+        Ok(())
+    };
+    if !state.use_worktrees && state.strategy == Strategy::Stacked {
+        Autostash::begin(root, &state, "fix", autostash)?.finish(move_chunks())?;
+    } else {
+        move_chunks()?;
+    }
+
+    for (i, files) in &fixes {
+        say!("{} {} → '{}'", "✓".green().bold(), files.join(", "), state.chunks[*i].name.cyan());
+    }
+    if let Some(t) = target {
+        state.chunks[t].files.extend(added);
+        state.save(root)?;
+    }
+    let fixed: Vec<_> = fixes.iter().map(|(i, _)| state.chunks[*i].clone()).collect();
+    journal::record(root, "fix", &format!("review fixes into {} chunk(s)", fixed.len()), &fixed)?;
+    Ok(fixed.into_iter().map(|c| c.name).collect())
+}
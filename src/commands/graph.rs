@@ -0,0 +1,185 @@
+use anyhow::Result;
+use std::collections::BTreeSet;
+
+use crate::{
+    git, imports,
+    state::{MergesState, Strategy},
+};
+
+/// Output format for `merges graph`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, clap::ValueEnum)]
+pub enum GraphFormat {
+    /// A tree for the terminal
+    #[default]
+    Ascii,
+    /// A Mermaid flowchart, for PR descriptions and Markdown docs
+    Mermaid,
+    /// Graphviz DOT
+    Dot,
+}
+
+/// One chunk in the graph.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    pub name: String,
+    pub branch: String,
+    pub pr_number: Option<u64>,
+    pub merged: bool,
+}
+
+/// The stack as a DAG: each chunk branches off its parent (the base branch
+/// when `None`), and `depends_on` holds (chunk, dependency) pairs where a file
+/// in the first chunk imports a file in the second.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Graph {
+    pub base: String,
+    pub nodes: Vec<Node>,
+    pub parents: Vec<Option<usize>>,
+    pub depends_on: Vec<(usize, usize)>,
+}
+
+/// Print the stack in `format`.
+pub fn run(root: &std::path::Path, format: GraphFormat) -> Result<()> {
+    let state = MergesState::load(root)?;
+    if state.chunks.is_empty() {
+        println!("No chunks defined yet. Run `merges split` first.");
+        return Ok(());
+    }
+    let files: Vec<String> = state.chunks.iter().flat_map(|c| c.files.iter().cloned()).collect();
+    let edges = imports::graph(root, &state.source_branch, &files);
+    let graph = build(&state, &edges);
+    let out = match format {
+        GraphFormat::Ascii => ascii(&graph),
+        GraphFormat::Mermaid => mermaid(&graph),
+        GraphFormat::Dot => dot(&graph),
+    };
+    print!("{}", out);
+    Ok(())
+}
+
+/// Build the graph of `state`'s chunks, taking dependencies from the file
+/// import `edges` (importer, imported).
+pub fn build(state: &MergesState, edges: &[(String, String)]) -> Graph {
+    let chunk_of = |file: &str| state.chunks.iter().position(|c| c.files.iter().any(|f| f == file));
+    let depends_on: BTreeSet<(usize, usize)> = edges
+        .iter()
+        .filter_map(|(from, to)| Some((chunk_of(from)?, chunk_of(to)?)))
+        .filter(|(from, to)| from != to)
+        .collect();
+    Graph {
+        base: state.base_branch.clone(),
+        nodes: state
+            .chunks
+            .iter()
+            .map(|c| Node {
+                name: c.name.clone(),
+                branch: c.branch.clone(),
+                pr_number: c.pr_number,
+                merged: c.merged_at.is_some(),
+            })
+            .collect(),
+        parents: (0..state.chunks.len())
+            .map(|i| match state.strategy {
+                Strategy::Stacked if i > 0 => Some(i - 1),
+                _ => None,
+            })
+            .collect(),
+        depends_on: depends_on.into_iter().collect(),
+    }
+}
+
+/// PR number and merge state, as shown next to each chunk.
+fn pr_label(node: &Node) -> String {
+    match (node.pr_number, node.merged) {
+        (Some(n), true) => format!("#{} merged", n),
+        (Some(n), false) => format!("#{}", n),
+        (None, _) => "no PR".to_string(),
+    }
+}
+
+/// Names of the chunks chunk `i` imports from.
+fn dependencies(graph: &Graph, i: usize) -> Vec<&str> {
+    graph.depends_on.iter().filter(|(from, _)| *from == i).map(|(_, to)| graph.nodes[*to].name.as_str()).collect()
+}
+
+/// The stack as a tree under the base branch, one chunk per line with its
+/// branch, PR, and the chunks it imports from.
+pub fn ascii(graph: &Graph) -> String {
+    fn children(graph: &Graph, parent: Option<usize>, indent: &str, out: &mut String) {
+        let kids: Vec<usize> = (0..graph.nodes.len()).filter(|&i| graph.parents[i] == parent).collect();
+        for (k, &i) in kids.iter().enumerate() {
+            let last = k + 1 == kids.len();
+            let node = &graph.nodes[i];
+            let deps = dependencies(graph, i);
+            out.push_str(&format!(
+                "{}{} {} {}  {}  ({}){}\n",
+                indent,
+                if last { "└──" } else { "├──" },
+                git::stack_position(i, graph.nodes.len()),
+                node.name,
+                node.branch,
+                pr_label(node),
+                if deps.is_empty() { String::new() } else { format!("  imports: {}", deps.join(", ")) }
+            ));
+            children(graph, Some(i), &format!("{}{}", indent, if last { "    " } else { "│   " }), out);
+        }
+    }
+
+    let mut out = format!("{}\n", graph.base);
+    children(graph, None, "", &mut out);
+    out
+}
+
+/// The stack as a Mermaid flowchart: solid arrows from each branch to the
+/// chunks built on it, dashed arrows from a chunk to the chunks it imports.
+pub fn mermaid(graph: &Graph) -> String {
+    let quote = |s: &str| s.replace('"', "#quot;");
+    let mut lines = vec!["flowchart TD".to_string(), format!("    base[\"{}\"]", quote(&graph.base))];
+    for (i, node) in graph.nodes.iter().enumerate() {
+        lines.push(format!("    c{}[\"{}. {}<br/>{}\"]", i + 1, i + 1, quote(&node.name), pr_label(node)));
+    }
+    for (i, parent) in graph.parents.iter().enumerate() {
+        let from = parent.map_or("base".to_string(), |p| format!("c{}", p + 1));
+        lines.push(format!("    {} --> c{}", from, i + 1));
+    }
+    for (from, to) in &graph.depends_on {
+        lines.push(format!("    c{} -.->|imports| c{}", from + 1, to + 1));
+    }
+    let merged: Vec<String> =
+        graph.nodes.iter().enumerate().filter(|(_, n)| n.merged).map(|(i, _)| format!("c{}", i + 1)).collect();
+    if !merged.is_empty() {
+        lines.push("    classDef merged fill:#d4edda,stroke:#28a745".to_string());
+        lines.push(format!("    class {} merged", merged.join(",")));
+    }
+    lines.join("\n") + "\n"
+}
+
+/// The stack as a Graphviz digraph, with the same edges as [`mermaid`].
+pub fn dot(graph: &Graph) -> String {
+    let quote = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+    let mut lines = vec![
+        "digraph stack {".to_string(),
+        "    node [shape=box];".to_string(),
+        format!("    base [label=\"{}\", shape=ellipse];", quote(&graph.base)),
+    ];
+    for (i, node) in graph.nodes.iter().enumerate() {
+        let fill = if node.merged { ", style=filled, fillcolor=\"#d4edda\"" } else { "" };
+        lines.push(format!(
+            "    c{} [label=\"{}. {}\\n{}\"{}];",
+            i + 1,
+            i + 1,
+            quote(&node.name),
+            pr_label(node),
+            fill
+        ));
+    }
+    for (i, parent) in graph.parents.iter().enumerate() {
+        let from = parent.map_or("base".to_string(), |p| format!("c{}", p + 1));
+        lines.push(format!("    {} -> c{};", from, i + 1));
+    }
+    for (from, to) in &graph.depends_on {
+        lines.push(format!("    c{} -> c{} [style=dashed, label=\"imports\"];", from + 1, to + 1));
+    }
+    lines.push("}".to_string());
+    lines.join("\n") + "\n"
+}
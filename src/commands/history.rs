@@ -0,0 +1,58 @@
+use anyhow::Result;
+use chrono::Local;
+use colored::Colorize;
+use comfy_table::{presets::UTF8_FULL, Attribute, Cell, Color, ContentArrangement, Table};
+
+use crate::journal;
+
+/// Print the operation journal, newest first. `limit` caps the number of
+/// entries shown (0 = all).
+pub fn run(root: &std::path::Path, limit: usize) -> Result<()> {
+    let entries = journal::load(root)?;
+    if entries.is_empty() {
+        println!("No operations recorded yet.");
+        return Ok(());
+    }
+
+    let shown = if limit == 0 { entries.len() } else { limit.min(entries.len()) };
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            Cell::new("When").add_attribute(Attribute::Bold),
+            Cell::new("Operation").add_attribute(Attribute::Bold),
+            Cell::new("Chunks").add_attribute(Attribute::Bold),
+            Cell::new("Branches").add_attribute(Attribute::Bold),
+            Cell::new("Summary").add_attribute(Attribute::Bold),
+        ]);
+
+    for entry in entries.iter().rev().take(shown) {
+        let branches = entry
+            .branches
+            .iter()
+            .map(|(branch, sha)| format!("{} {}", &sha[..sha.len().min(7)], branch))
+            .collect::<Vec<_>>()
+            .join("\n");
+        table.add_row(vec![
+            Cell::new(entry.at.with_timezone(&Local).format("%Y-%m-%d %H:%M")),
+            Cell::new(&entry.operation).fg(Color::Cyan),
+            Cell::new(entry.chunks.join(", ")),
+            Cell::new(branches),
+            Cell::new(&entry.summary),
+        ]);
+    }
+
+    println!("{}", table);
+    if shown < entries.len() {
+        println!(
+            "  {} of {} operations shown — pass {} to see all.",
+            shown,
+            entries.len(),
+            "--limit 0".bold()
+        );
+    }
+
+    Ok(())
+}
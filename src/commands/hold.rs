@@ -0,0 +1,37 @@
+//! `merges hold` / `merges unhold`: keep a chunk branch-only. A held chunk —
+//! generated code or vendored dependencies, say, that should wait until the
+//! rest has merged — is still split, rebased, and pushed like any other, but
+//! `merges push` opens no PR for it (and leaves one it already has alone)
+//! until it is released.
+
+use anyhow::{bail, Result};
+use colored::Colorize;
+use std::path::Path;
+
+use crate::{say, state::MergesState, warning};
+
+/// Hold chunk `name` when `hold` is set, release it otherwise. Returns
+/// whether anything changed.
+pub fn run(root: &Path, name: &str, hold: bool) -> Result<bool> {
+    let mut state = MergesState::load(root)?;
+    let Some(chunk) = state.chunks.iter_mut().find(|c| c.name == name) else {
+        bail!("No chunk named '{}'", name);
+    };
+    if chunk.hold == hold {
+        say!("{} '{}' is already {}.", "·".dimmed(), name.cyan(), if hold { "held" } else { "released" });
+        return Ok(false);
+    }
+    chunk.hold = hold;
+    let pr_number = chunk.pr_number;
+    state.save(root)?;
+
+    if !hold {
+        say!("{} Released '{}': the next `merges push` opens or updates its PR.", "✓".green(), name.cyan());
+        return Ok(true);
+    }
+    say!("{} Holding '{}': `merges push` keeps pushing its branch but skips its PR.", "✓".green(), name.cyan());
+    if let Some(pr) = pr_number {
+        warning!("PR #{} stays open as it is; close it or make it a draft if it should not be reviewed yet.", pr);
+    }
+    Ok(true)
+}
@@ -1,69 +1,415 @@
 use anyhow::{bail, Result};
 use colored::Colorize;
-use dialoguer::{Confirm, Input};
+use dialoguer::{Input, Select};
 
 use crate::{
-    git,
-    state::{MergesState, Strategy},
+    forge, git, pathspec,
+    project::{self, Project},
+    prompt,
+    routing::Route,
+    say,
+    size::SizeLimits,
+    split::RootFiles,
+    state::{ForgeKind, MergesState, Strategy},
 };
 
-pub fn run(base_branch: Option<String>, use_worktrees: bool, commit_prefix: Option<String>) -> Result<()> {
+/// Smallest accepted `--max-subject-length` / `--max-title-length`.
+const MIN_SUBJECT_LENGTH: usize = 20;
+
+/// Settings for `merges init`; unset fields fall back to detection or defaults.
+#[derive(Debug, Clone, Default)]
+pub struct InitOptions {
+    pub base_branch: Option<String>,
+    pub strategy: Option<Strategy>,
+    pub use_worktrees: bool,
+    pub commit_prefix: Option<String>,
+    pub forge_kind: Option<ForgeKind>,
+    pub exclude: Vec<String>,
+    /// Routing rules, as `<pattern> -> label:<name>, reviewer:@<login>`.
+    pub routes: Vec<String>,
+    pub no_title_position: bool,
+    pub max_subject_length: Option<usize>,
+    pub max_title_length: Option<usize>,
+    pub auto_depth: Option<usize>,
+    /// Where `split --auto` puts files at the repository root.
+    pub root_files: Option<RootFiles>,
+    pub no_stack_comments: bool,
+    pub branch_namespace: Option<String>,
+    pub autostash: bool,
+    pub preserve_history: bool,
+    pub squash_on_push: bool,
+    pub sync_merge: bool,
+    pub post_summary: bool,
+    /// Project chunk PRs are added to, as `owner/number` or its URL.
+    pub project: Option<String>,
+    /// Project status option per PR stage, as `merged=Done`.
+    pub project_statuses: Vec<String>,
+    pub milestone: Option<String>,
+    /// Size limits to set; limits left unset keep their current value.
+    pub size_limits: SizeLimits,
+    /// Take defaults without prompting; an existing `.merges.json` is updated,
+    /// not reset.
+    pub yes: bool,
+    /// Replace an existing `.merges.json`, discarding its chunks and PR tracking.
+    pub force: bool,
+    /// Never prompt: fail with a hint where an answer would be needed. Implied
+    /// when prompts are unavailable (no terminal, `--no-input`, MCP).
+    pub non_interactive: bool,
+}
+
+pub fn run(opts: InitOptions) -> Result<()> {
+    let InitOptions {
+        base_branch,
+        strategy,
+        use_worktrees,
+        commit_prefix,
+        forge_kind,
+        exclude,
+        routes,
+        no_title_position,
+        max_subject_length,
+        max_title_length,
+        auto_depth,
+        root_files,
+        no_stack_comments,
+        branch_namespace,
+        autostash,
+        preserve_history,
+        squash_on_push,
+        sync_merge,
+        post_summary,
+        project,
+        project_statuses,
+        milestone,
+        size_limits,
+        yes,
+        force,
+        non_interactive,
+    } = opts.clone();
+    let interactive = !yes && !non_interactive && prompt::allowed();
+
+    pathspec::validate_patterns(&exclude)?;
+    let routes = routes.iter().map(|r| Route::parse(r)).collect::<Result<Vec<_>>>()?;
+    project::parse_statuses(&project_statuses)?;
+    let project = project.map(|p| Project::parse(&p, &project_statuses)).transpose()?;
+    for (flag, max) in [("--max-subject-length", max_subject_length), ("--max-title-length", max_title_length)] {
+        if max.is_some_and(|m| m < MIN_SUBJECT_LENGTH) {
+            bail!("{} must be at least {} — the ticket prefix and stack position need room.", flag, MIN_SUBJECT_LENGTH);
+        }
+    }
+    validate_size_limits(&size_limits)?;
+    if auto_depth == Some(0) {
+        bail!("--auto-depth must be at least 1 (1 groups by top-level directory).");
+    }
+    if let Some(namespace) = &branch_namespace {
+        validate_namespace(namespace)?;
+    }
     let root = git::repo_root()?;
     let state_path = crate::state::MergesState::path(&root);
 
-    if state_path.exists() {
-        let overwrite = Confirm::new()
-            .with_prompt(".merges.json already exists — overwrite?")
-            .default(false)
-            .interact()?;
-        if !overwrite {
-            bail!("Aborted.");
+    // Re-running init keeps chunks and PR tracking unless a reset is asked for.
+    if state_path.exists() && !force {
+        let reset = interactive && {
+            let choice = Select::new()
+                .with_prompt(".merges.json already exists")
+                .items(&[
+                    "Update settings, keep chunks and PRs",
+                    "Reset — discard chunks and PR tracking",
+                    "Abort",
+                ])
+                .default(0)
+                .interact()?;
+            if choice == 2 {
+                bail!("Aborted.");
+            }
+            choice == 1
+        };
+        if !reset {
+            return reinit(&root, &opts);
         }
     }
 
     let source_branch = git::current_branch(&root)?;
 
-    let base: String = if let Some(b) = base_branch {
-        b
-    } else {
-        Input::new()
+    // Pre-fill from origin/HEAD so `master`/`develop`/`trunk` repos don't
+    // silently diff against a stale or missing `main`.
+    let detected_base = git::default_branch(&root);
+    let base: String = match (base_branch, detected_base) {
+        (Some(b), _) => b,
+        (None, detected) if interactive => Input::new()
             .with_prompt("Base branch (target for PRs)")
-            .default("main".to_string())
-            .interact_text()?
+            .default(detected.unwrap_or_else(|| "main".to_string()))
+            .interact_text()?,
+        (None, Some(detected)) => detected,
+        (None, None) if yes => "main".to_string(),
+        (None, None) => bail!(
+            "Could not detect the remote's default branch. Pass --base <branch> (or --yes to use 'main')."
+        ),
     };
+    let strategy = strategy.unwrap_or(Strategy::Stacked);
 
-    let (owner, repo) = git::remote_owner_repo(&root)?;
+    let remote = forge::detect(&git::remote_url(&root)?, forge_kind)?;
+    let (owner, repo) = (remote.owner.clone(), remote.repo.clone());
 
     let state = MergesState {
         base_branch: base.clone(),
         source_branch: source_branch.clone(),
+        source_sha: None,
         repo_owner: owner.clone(),
         repo_name: repo.clone(),
-        strategy: Strategy::Stacked,
+        forge: remote.kind,
+        forge_api_url: remote.api_url.clone(),
+        strategy: strategy.clone(),
         use_worktrees,
+        exclude: exclude.clone(),
+        routes: routes.clone(),
         commit_prefix: commit_prefix.clone(),
+        no_title_position,
+        max_subject_length,
+        max_title_length,
+        auto_depth,
+        root_files: root_files.clone(),
+        no_stack_comments,
+        branch_namespace: branch_namespace.clone(),
+        autostash,
+        preserve_history,
+        squash_on_push,
+        sync_merge,
+        post_summary,
+        project: project.clone(),
+        milestone: milestone.clone(),
+        size_limits: size_limits.clone(),
+        tracking_issue: None,
+        source_pr: None,
+        push_interrupted: false,
         chunks: vec![],
+        merged: vec![],
     };
 
     state.save(&root)?;
     git::ensure_gitignored(&root, ".merges.json")?;
     git::enable_rerere(&root)?;
 
-    println!(
-        "{} Initialised merges for {}/{} — source: {}, base: {}{}{}",
+    say!(
+        "{} Initialised merges for {}/{}{} — source: {}, base: {}, {} PRs{}{}",
         "✓".green().bold(),
         owner.cyan(),
         repo.cyan(),
+        if remote.kind.is_github() { String::new() } else { format!(" on {}", remote.kind) },
         source_branch.yellow(),
         base.yellow(),
+        strategy,
         if use_worktrees { " (worktrees enabled)" } else { "" },
         commit_prefix.as_deref().map(|p| format!(" (commit prefix: {})", p)).unwrap_or_default()
     );
-    println!("  {} rerere enabled — conflict resolutions will be replayed automatically.", "·".dimmed());
-    println!(
+    if let Some(how) = &root_files {
+        say!("  {} root-level files in auto splits: {}", "·".dimmed(), how);
+    }
+    if let Some(namespace) = &branch_namespace {
+        say!("  {} chunk branches: {}/{}/<n>-<name>", "·".dimmed(), namespace, source_branch);
+    }
+    if !exclude.is_empty() {
+        say!("  {} excluding: {}", "·".dimmed(), exclude.join(", "));
+    }
+    for route in &routes {
+        say!("  {} routing: {}", "·".dimmed(), route);
+    }
+    if let Some(project) = &project {
+        say!("  {} chunk PRs go on project {} ({})", "·".dimmed(), project, project.describe_statuses());
+    }
+    if let Some(milestone) = &milestone {
+        say!("  {} chunk PRs get milestone '{}'", "·".dimmed(), milestone);
+    }
+    if !size_limits.is_empty() {
+        say!("  {} chunk size limits: {}", "·".dimmed(), size_limits.describe());
+    }
+    say!("  {} rerere enabled — conflict resolutions will be replayed automatically.", "·".dimmed());
+    say!(
         "  Next: run {} to assign files to chunks.",
         "merges split".bold()
     );
 
     Ok(())
 }
+
+/// Update an existing state file with the settings given on this run,
+/// preserving its chunks and PR tracking.
+fn reinit(root: &std::path::Path, opts: &InitOptions) -> Result<()> {
+    let mut state = MergesState::load(root)?;
+    if opts.use_worktrees && !state.use_worktrees {
+        bail!("Switching an existing split to worktrees is not supported. Pass --force to reset and split again.");
+    }
+
+    let mut changes = apply_settings(&mut state, opts);
+    if let Some(kind) = opts.forge_kind.filter(|k| *k != state.forge) {
+        let remote = forge::detect(&git::remote_url(root)?, Some(kind))?;
+        state.forge = remote.kind;
+        state.forge_api_url = remote.api_url;
+        state.repo_owner = remote.owner;
+        state.repo_name = remote.repo;
+        changes.push(format!("forge: {}", kind));
+    }
+
+    if changes.is_empty() {
+        say!("{} .merges.json is already up to date — nothing changed.", "✓".green().bold());
+        return Ok(());
+    }
+    state.save(root)?;
+
+    say!(
+        "{} Updated .merges.json — kept {} chunk(s):",
+        "✓".green().bold(),
+        state.chunks.len().to_string().yellow()
+    );
+    for change in &changes {
+        say!("  {} {}", "·".dimmed(), change);
+    }
+    if opts.base_branch.is_some() && state.chunks.iter().any(|c| c.pr_number.is_some()) {
+        say!("  Run {} to retarget open PRs.", "merges push".bold());
+    }
+    Ok(())
+}
+
+/// Apply the explicitly given settings in `opts` to `state`; returns a
+/// description of each field that changed. Unset options leave fields alone.
+pub fn apply_settings(state: &mut MergesState, opts: &InitOptions) -> Vec<String> {
+    let mut changes = vec![];
+    if let Some(base) = opts.base_branch.as_ref().filter(|b| **b != state.base_branch) {
+        changes.push(format!("base: {} → {}", state.base_branch, base));
+        state.base_branch = base.clone();
+    }
+    if let Some(strategy) = opts.strategy.as_ref().filter(|s| **s != state.strategy) {
+        changes.push(format!("strategy: {} → {}", state.strategy, strategy));
+        state.strategy = strategy.clone();
+    }
+    if let Some(prefix) = opts.commit_prefix.as_ref().filter(|p| state.commit_prefix.as_ref() != Some(*p)) {
+        changes.push(format!("commit prefix: {}", prefix));
+        state.commit_prefix = Some(prefix.clone());
+    }
+    for pattern in &opts.exclude {
+        if !state.exclude.contains(pattern) {
+            changes.push(format!("exclude: +{}", pattern));
+            state.exclude.push(pattern.clone());
+        }
+    }
+    // `run` has already refused rules that do not parse.
+    for route in opts.routes.iter().filter_map(|r| Route::parse(r).ok()) {
+        if !state.routes.contains(&route) {
+            changes.push(format!("routing: +{}", route));
+            state.routes.push(route);
+        }
+    }
+    if opts.no_title_position && !state.no_title_position {
+        changes.push("title positions: off".to_string());
+        state.no_title_position = true;
+    }
+    if let Some(max) = opts.max_subject_length.filter(|m| state.max_subject_length != Some(*m)) {
+        changes.push(format!("max subject length: {}", max));
+        state.max_subject_length = Some(max);
+    }
+    if let Some(max) = opts.max_title_length.filter(|m| state.max_title_length != Some(*m)) {
+        changes.push(format!("max title length: {}", max));
+        state.max_title_length = Some(max);
+    }
+    if let Some(depth) = opts.auto_depth.filter(|d| state.auto_depth != Some(*d)) {
+        changes.push(format!("auto-split depth: {}", depth));
+        state.auto_depth = Some(depth);
+    }
+    if let Some(how) = opts.root_files.as_ref().filter(|h| state.root_files.as_ref() != Some(*h)) {
+        changes.push(format!("root-level files in auto splits: {}", how));
+        state.root_files = Some(how.clone());
+    }
+    if opts.no_stack_comments && !state.no_stack_comments {
+        changes.push("stack comments: off".to_string());
+        state.no_stack_comments = true;
+    }
+    if let Some(namespace) = opts.branch_namespace.as_ref().filter(|n| state.branch_namespace.as_ref() != Some(*n)) {
+        changes.push(format!(
+            "branch namespace: {} (run `merges doctor --repair` to move existing chunk branches)",
+            namespace
+        ));
+        state.branch_namespace = Some(namespace.clone());
+    }
+    if opts.autostash && !state.autostash {
+        changes.push("autostash: on".to_string());
+        state.autostash = true;
+    }
+    if opts.preserve_history && !state.preserve_history {
+        changes.push("chunk commits: replayed from the source history (applies to new and rebuilt chunks)".to_string());
+        state.preserve_history = true;
+    }
+    if opts.squash_on_push && !state.squash_on_push {
+        changes.push("squash on push: on".to_string());
+        state.squash_on_push = true;
+    }
+    if opts.sync_merge && !state.sync_merge {
+        changes.push("sync: merge the base into chunk branches (no rebases or force-pushes)".to_string());
+        state.sync_merge = true;
+    }
+    if opts.post_summary && !state.post_summary {
+        changes.push("review summary: posted on push".to_string());
+        state.post_summary = true;
+    }
+    // Statuses alone update the project already set.
+    let project = match (&opts.project, &state.project) {
+        (Some(p), _) => Project::parse(p, &opts.project_statuses).ok(),
+        (None, Some(current)) if !opts.project_statuses.is_empty() => {
+            let statuses = project::parse_statuses(&opts.project_statuses).ok();
+            statuses.map(|statuses| Project { statuses, ..current.clone() })
+        }
+        _ => None,
+    };
+    if let Some(project) = project.filter(|p| state.project.as_ref() != Some(p)) {
+        changes.push(format!("project: {} ({})", project, project.describe_statuses()));
+        // Items and statuses were for the old project; the next push redoes them.
+        for chunk in state.chunks.iter_mut().chain(state.merged.iter_mut()) {
+            chunk.project_item_id = None;
+            chunk.project_status = None;
+        }
+        state.project = Some(project);
+    }
+    if let Some(milestone) = opts.milestone.as_ref().filter(|m| state.milestone.as_ref() != Some(*m)) {
+        changes.push(format!("milestone: {}", milestone));
+        state.milestone = Some(milestone.clone());
+    }
+    let given = &opts.size_limits;
+    let size_limits = SizeLimits {
+        warn_lines: given.warn_lines.or(state.size_limits.warn_lines),
+        max_lines: given.max_lines.or(state.size_limits.max_lines),
+        warn_files: given.warn_files.or(state.size_limits.warn_files),
+        max_files: given.max_files.or(state.size_limits.max_files),
+    };
+    if size_limits != state.size_limits {
+        changes.push(format!("size limits: {}", size_limits.describe()));
+        state.size_limits = size_limits;
+    }
+    changes
+}
+
+/// Check that no soft size limit is above its hard one.
+fn validate_size_limits(limits: &SizeLimits) -> Result<()> {
+    let pairs = [
+        ("lines", limits.warn_lines, limits.max_lines),
+        ("files", limits.warn_files, limits.max_files),
+    ];
+    for (unit, warn, max) in pairs {
+        if let Some((warn, max)) = warn.zip(max).filter(|(warn, max)| warn > max) {
+            bail!("--warn-{} ({}) is above --max-{} ({}); push would refuse before it warned.", unit, warn, unit, max);
+        }
+    }
+    Ok(())
+}
+
+/// Check that `namespace` can start a branch name.
+fn validate_namespace(namespace: &str) -> Result<()> {
+    let invalid = namespace.is_empty()
+        || namespace.starts_with('/')
+        || namespace.ends_with('/')
+        || namespace.contains("..")
+        || namespace.contains("//")
+        || namespace.chars().any(|c| c.is_whitespace() || "~^:?*[\\".contains(c));
+    if invalid {
+        bail!("--branch-namespace '{}' is not a valid branch prefix; use something like 'merges' or 'team/merges'.", namespace);
+    }
+    Ok(())
+}
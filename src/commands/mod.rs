@@ -1,8 +1,30 @@
+pub mod absorb;
 pub mod add;
+pub mod assign;
+pub mod audit;
+pub mod backport;
 pub mod clean;
+pub mod diffcheck;
+pub mod edit;
+pub mod exec;
+pub mod export;
+pub mod fix;
+pub mod graph;
+pub mod history;
+pub mod hold;
 pub mod init;
 pub mod r#move;
+pub mod pr_body;
 pub mod push;
+pub mod recover;
+pub mod refresh;
+pub mod rerere;
+pub mod reviews;
+pub mod setup;
+pub mod shell;
 pub mod split;
+pub mod squash;
+pub mod state;
 pub mod status;
 pub mod sync;
+pub mod verify;
@@ -1,17 +1,30 @@
 use anyhow::{bail, Result};
 use colored::Colorize;
 
-use crate::{git, state::MergesState};
+use crate::{autostash::Autostash, git, journal, pathspec, say, split, state::MergesState};
 
-/// Move `file` from `from_chunk` to `to_chunk`.
+/// Move `file` from `from_chunk` to `to_chunk`. `file` may also be a directory
+/// or glob pattern, expanded against the files in `from_chunk`.
 ///
 /// When `use_worktrees` is enabled, all operations happen inside each chunk's
-/// worktree directory — the main working tree branch never changes.
+/// worktree directory — the main working tree branch never changes. In
+/// classic mode a dirty working tree is refused unless `autostash` is set.
 pub fn run(
     root: &std::path::Path,
     file: &str,
     from_chunk: &str,
     to_chunk: &str,
+    autostash: bool,
+) -> Result<()> {
+    let stash = Autostash::begin(root, &MergesState::load(root)?, "move", autostash)?;
+    stash.finish(move_files(root, file, from_chunk, to_chunk))
+}
+
+fn move_files(
+    root: &std::path::Path,
+    file: &str,
+    from_chunk: &str,
+    to_chunk: &str,
 ) -> Result<()> {
     let mut state = MergesState::load(root)?;
 
@@ -29,7 +42,8 @@ pub fn run(
         })?;
 
     // Validate file is in from-chunk
-    if !state.chunks[from_idx].files.contains(&file.to_string()) {
+    let expansion = pathspec::resolve(file, &state.chunks[from_idx].files)?;
+    if expansion.files.is_empty() {
         bail!(
             "File '{}' is not in chunk '{}'. Files in chunk: {}",
             file,
@@ -37,6 +51,10 @@ pub fn run(
             state.chunks[from_idx].files.join(", ")
         );
     }
+    if !expansion.is_literal() {
+        say!("{} {}", "·".dimmed(), expansion.describe());
+    }
+    let files = expansion.files;
 
     // Validate to-chunk
     let to_idx = state
@@ -64,8 +82,23 @@ pub fn run(
         root.to_path_buf()
     };
 
-    // ── Step 1: Remove file from the from-chunk ───────────────────────────
-    remove_file_from_branch(&from_dir, file, &source_branch)?;
+    // Both chunk commits are reworded to list the files they carry after the move.
+    let from_files: Vec<String> =
+        state.chunks[from_idx].files.iter().filter(|f| !files.contains(f)).cloned().collect();
+    let from_message = split::commit_message(
+        &state,
+        from_idx + 1,
+        from_chunk,
+        &from_files,
+        state.chunks[from_idx].custom_commit_message.as_deref(),
+    );
+
+    // ── Step 1: Remove files from the from-chunk ──────────────────────────
+    if state.preserve_history {
+        split::recommit_chunk(root, &from_dir, &state, from_idx, &from_files)?;
+    } else {
+        remove_files_from_branch(&from_dir, &files, &from_message)?;
+    }
 
     // Switch to to-chunk dir
     let to_dir = if use_worktrees {
@@ -75,10 +108,28 @@ pub fn run(
         root.to_path_buf()
     };
 
-    // ── Step 2: Add file to the to-chunk ─────────────────────────────────
-    if !state.chunks[to_idx].files.contains(&file.to_string()) {
-        git::checkout_files_from(&to_dir, &source_branch, &[file.to_string()])?;
-        amend_commit(&to_dir, &source_branch)?;
+    // ── Step 2: Add files to the to-chunk ────────────────────────────────
+    let to_add: Vec<String> = files
+        .iter()
+        .filter(|f| !state.chunks[to_idx].files.contains(f))
+        .cloned()
+        .collect();
+    if !to_add.is_empty() {
+        let mut to_files = state.chunks[to_idx].files.clone();
+        to_files.extend(to_add.iter().cloned());
+        if state.preserve_history {
+            split::recommit_chunk(root, &to_dir, &state, to_idx, &to_files)?;
+        } else {
+            git::checkout_files_from(&to_dir, &source_branch, &to_add)?;
+            let to_message = split::commit_message(
+                &state,
+                to_idx + 1,
+                to_chunk,
+                &to_files,
+                state.chunks[to_idx].custom_commit_message.as_deref(),
+            );
+            amend_commit(&to_dir, &to_message)?;
+        }
     }
 
     // ── Step 3: Restore source branch (classic mode only) ─────────────────
@@ -87,16 +138,17 @@ pub fn run(
     }
 
     // ── Step 4: Update state ──────────────────────────────────────────────
-    state.chunks[from_idx].files.retain(|f| f != file);
-    if !state.chunks[to_idx].files.contains(&file.to_string()) {
-        state.chunks[to_idx].files.push(file.to_string());
-    }
+    state.chunks[from_idx].files.retain(|f| !files.contains(f));
+    state.chunks[to_idx].files.extend(to_add);
     state.save(root)?;
 
-    println!(
+    let moved = if files.len() == 1 { files[0].clone() } else { format!("{} files", files.len()) };
+    let touched = [state.chunks[from_idx].clone(), state.chunks[to_idx].clone()];
+    journal::record(root, "move", &format!("{}: {} → {}", moved, from_chunk, to_chunk), &touched)?;
+    say!(
         "{} Moved '{}' from '{}' → '{}'",
         "✓".green().bold(),
-        file.yellow(),
+        moved.yellow(),
         from_chunk.cyan(),
         to_chunk.cyan()
     );
@@ -104,45 +156,53 @@ pub fn run(
     Ok(())
 }
 
-/// Remove `file` from the tip commit of the branch in `work_dir`.
-fn remove_file_from_branch(work_dir: &std::path::Path, file: &str, source_branch: &str) -> Result<()> {
+/// Remove `files` from the tip commit of the branch in `work_dir`, recommitting
+/// the rest as `message`.
+fn remove_files_from_branch(work_dir: &std::path::Path, files: &[String], message: &str) -> Result<()> {
     let dir = work_dir.to_str().unwrap();
 
-    let status = std::process::Command::new("git")
+    let status = git::command()
         .args(["-C", dir, "reset", "--soft", "HEAD~1"])
+        .stdout(crate::output::child_stdout())
         .status()?;
     if !status.success() {
         bail!("git reset --soft HEAD~1 failed");
     }
 
-    let status = std::process::Command::new("git")
-        .args(["-C", dir, "reset", "HEAD", "--", file])
+    let status = git::command()
+        .args(["-C", dir, "--literal-pathspecs", "reset", "HEAD", "--"])
+        .args(files)
+        .stdout(crate::output::child_stdout())
         .status()?;
     if !status.success() {
-        bail!("git reset HEAD -- {} failed", file);
+        bail!("git reset HEAD -- {} failed", files.join(" "));
     }
 
-    let _ = std::process::Command::new("git")
-        .args(["-C", dir, "checkout", "--", file])
-        .status();
+    // Per file: paths that don't exist on the base make `git checkout` fail.
+    for file in files {
+        let _ = git::command()
+            .args(["-C", dir, "--literal-pathspecs", "checkout", "--", file])
+            .stdout(crate::output::child_stdout())
+            .status();
+    }
 
-    let out = std::process::Command::new("git")
+    let out = git::command()
         .args(["-C", dir, "diff", "--cached", "--name-only"])
         .output()?;
     let staged = String::from_utf8_lossy(&out.stdout);
 
     if staged.trim().is_empty() {
-        let msg = crate::git::commit_message(source_branch, "chunk: (empty after move)");
-        let status = std::process::Command::new("git")
-            .args(["-C", dir, "commit", "--allow-empty", "-m", &msg])
+        let status = git::command()
+            .args(["-C", dir, "commit", "--allow-empty", "-m", message])
+            .stdout(crate::output::child_stdout())
             .status()?;
         if !status.success() {
             bail!("git commit --allow-empty failed");
         }
     } else {
-        let msg = crate::git::commit_message(source_branch, "chunk: update files");
-        let status = std::process::Command::new("git")
-            .args(["-C", dir, "commit", "--no-edit", "-m", &msg])
+        let status = git::command()
+            .args(["-C", dir, "commit", "-m", message])
+            .stdout(crate::output::child_stdout())
             .status()?;
         if !status.success() {
             bail!("git commit failed after removing file");
@@ -152,19 +212,15 @@ fn remove_file_from_branch(work_dir: &std::path::Path, file: &str, source_branch
     Ok(())
 }
 
-/// Stage everything and amend the tip commit in `work_dir`.
-fn amend_commit(work_dir: &std::path::Path, _source_branch: &str) -> Result<()> {
+/// Amend the tip commit in `work_dir` with what is staged, rewording it to
+/// `message`. The working tree is not re-staged, so files taken from the
+/// source keep its exact bytes whatever `.gitattributes` says.
+fn amend_commit(work_dir: &std::path::Path, message: &str) -> Result<()> {
     let dir = work_dir.to_str().unwrap();
 
-    let status = std::process::Command::new("git")
-        .args(["-C", dir, "add", "-A"])
-        .status()?;
-    if !status.success() {
-        bail!("git add failed");
-    }
-
-    let status = std::process::Command::new("git")
-        .args(["-C", dir, "commit", "--amend", "--no-edit"])
+    let status = git::command()
+        .args(["-C", dir, "commit", "--amend", "-m", message])
+        .stdout(crate::output::child_stdout())
         .status()?;
     if !status.success() {
         bail!("git commit --amend failed");
@@ -0,0 +1,79 @@
+//! `merges pr-body edit`: rewrite the prose of a chunk's PR description
+//! while keeping the stack table merges maintains below it.
+//!
+//! Editing starts from the description the forge has now, so changes made
+//! in the web UI are kept. What is saved becomes the chunk's PR body, which
+//! `merges push --update-prs` puts above a fresh stack table instead of
+//! regenerating the summary.
+
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use dialoguer::Editor;
+use std::io::Read;
+use std::path::Path;
+
+use crate::{
+    commands::push,
+    forge::{self, Forge},
+    git, say,
+    state::MergesState,
+};
+
+/// Edit the prose of chunk `name`'s PR in `$EDITOR`, or replace it with
+/// standard input when `stdin` is set.
+pub async fn edit(name: &str, stdin: bool) -> Result<()> {
+    let root = git::repo_root()?;
+    let forge = forge::connect(&MergesState::load(&root)?).await?;
+    run_with(&root, name, forge.as_ref(), |prose| {
+        if stdin {
+            let mut text = String::new();
+            std::io::stdin().read_to_string(&mut text).context("Failed to read the description from stdin")?;
+            return Ok(Some(text));
+        }
+        Editor::new().extension(".md").edit(prose).context("Failed to open $EDITOR")
+    })
+    .await?;
+    Ok(())
+}
+
+/// Replace the prose of chunk `name`'s PR with what `edit` makes of the
+/// current prose (`None` to leave it), keeping the stack table, and record
+/// it as the chunk's PR body. Returns the body written to the PR, or `None`
+/// when nothing changed.
+pub async fn run_with(
+    root: &Path,
+    name: &str,
+    forge: &dyn Forge,
+    edit: impl FnOnce(&str) -> Result<Option<String>>,
+) -> Result<Option<String>> {
+    let mut state = MergesState::load(root)?;
+    let Some(i) = state.chunks.iter().position(|c| c.name == name) else {
+        bail!("No chunk named '{}'", name);
+    };
+    let Some(pr_number) = state.chunks[i].pr_number else {
+        bail!("Chunk '{}' has no PR yet — run `merges push` first.", name);
+    };
+
+    let info = forge.get_pr_info(pr_number).await?;
+    let current = push::body_prose(&info.body);
+    let Some(edited) = edit(current)? else {
+        say!("Description of PR #{} left as is.", pr_number);
+        return Ok(None);
+    };
+    // A pasted copy of the whole body brings its stack table along; drop it.
+    let prose = push::body_prose(&edited);
+    if prose == current {
+        say!("Description of PR #{} left as is.", pr_number);
+        return Ok(None);
+    }
+    if prose.is_empty() {
+        bail!("The new description is empty — PR #{} left as is.", pr_number);
+    }
+
+    state.chunks[i].custom_pr_body = Some(prose.to_string());
+    let body = push::pr_body(&state, i, &state.strategy, &[]);
+    forge.update_pr(pr_number, &info.title, Some(&body)).await?;
+    state.save(root)?;
+    say!("{} Updated the description of PR #{} ('{}').", "✓".green(), pr_number, name.cyan());
+    Ok(Some(body))
+}
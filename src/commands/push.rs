@@ -1,15 +1,183 @@
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
 use colored::Colorize;
-use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
 
 use crate::{
-    config, git, github,
-    state::{MergesState, Strategy},
+    forge::{self, Access, Forge, MergeMethod},
+    autostash::Autostash,
+    cancel::CancelToken,
+    ci,
+    empty, git, journal, output,
+    project::{Project, Stage},
+    routing::{self, Routed},
+    say,
+    size::{self, Breach, Size},
+    split,
+    state::{Chunk, MergesState, SourcePr, Strategy},
+    summary, warning,
 };
 
-pub async fn run(stacked: bool, independent: bool) -> Result<()> {
+/// What `merges push --source-pr` does to the original PR from the source
+/// branch, besides commenting on it with an index of the chunk PRs.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum SourcePrAction {
+    /// Only comment
+    Comment,
+    /// Comment and convert the PR to a draft
+    Draft,
+    /// Comment and close the PR
+    Close,
+}
+
+/// Commit trailer that tells GitHub Actions to skip the workflows a push or
+/// PR would otherwise run.
+pub const SKIP_CI_TRAILER: &str = "skip-checks: true";
+
+/// Flags for `merges push`.
+#[derive(Debug, Clone, Default)]
+pub struct PushOptions {
+    pub stacked: bool,
+    pub independent: bool,
+    pub auto_merge: Option<MergeMethod>,
+    pub tracking_issue: bool,
+    /// Point the source branch's own PR at the chunk PRs.
+    pub source_pr: Option<SourcePrAction>,
+    pub resume: bool,
+    pub update_prs: bool,
+    pub autostash: bool,
+    /// Open PRs for chunks whose branch has no changes instead of skipping them.
+    pub allow_empty: bool,
+    /// Drop chunks whose branch has no changes from the stack without asking.
+    pub drop_empty: bool,
+    /// Squash each chunk branch into one commit first.
+    pub squash: bool,
+    /// Overwrite commits on origin that the local chunk branches lack.
+    pub force: bool,
+    /// Print what would be pushed, and the CI each PR should run, instead.
+    pub dry_run: bool,
+    /// Leave at least this long between the CI runs the push sets off.
+    pub stagger: Option<Duration>,
+    /// Mark the tip commit of chunks flagged `skip_ci` so CI skips their PRs.
+    pub skip_ci: bool,
+    /// Push chunks over the hard size limits anyway.
+    pub allow_large: bool,
+}
+
+/// Push every chunk and create or update its PR.
+///
+/// Progress is saved per chunk as it goes. With `resume`, chunks whose branch
+/// is unchanged since an interrupted run pushed it are skipped, and PRs already
+/// open for a chunk branch are adopted instead of duplicated. With
+/// `update_prs`, PRs that already exist get their title and body regenerated.
+/// In classic mode a dirty working tree is refused unless `autostash` is set.
+///
+/// Chunks left with no changes after rebasing get no PR work this run, unless
+/// `allow_empty` is set; they are dropped from the stack with `drop_empty` or
+/// when the user agrees at the prompt. With `squash` (or the `squash_on_push`
+/// setting), chunk branches are squashed into one commit each first.
+///
+/// Before anything is pushed, every chunk branch on origin is checked for
+/// commits someone else pushed there (see [`foreign_commits`]); the push is
+/// refused with a summary of them unless `force` is set. So is a push the
+/// forge credentials lack permissions for (see [`Forge::missing_permissions`]).
+///
+/// With `stagger`, pushes to branches with an open PR and new PRs — each of
+/// which sets off a CI run — are spaced out, bottom of the stack first.
+/// With `skip_ci`, chunks marked as needing no CI get a `skip-checks: true`
+/// trailer on their tip commit first, and are not waited for.
+///
+/// In merge mode (`sync_merge`), chunk branches are pushed as they are, without
+/// rebasing or force: only fast-forwards reach origin.
+///
+/// Held chunks (see [`Chunk::hold`]) are pushed, but their PR is neither
+/// opened nor updated.
+///
+/// Chunks over the size limits (see [`crate::size`]) are warned about; one
+/// over a hard limit stops the push before anything is pushed, unless
+/// `allow_large` is set.
+///
+/// A chunk branch rewritten since merges last pushed it gets a comment on its
+/// PR with the `git range-diff` between the two tips.
+///
+/// Once `cancel` is tripped the push stops before the next chunk, as if
+/// interrupted, so `--resume` finishes it. With `dry_run`, nothing is pushed:
+/// each chunk's PR, base, title and predicted CI workflows are printed.
+pub async fn run(opts: PushOptions, cancel: &CancelToken) -> Result<()> {
     let root = git::repo_root()?;
-    let mut state = MergesState::load(&root)?;
+    let forge = forge::connect(&MergesState::load(&root)?).await?;
+    run_with(&root, opts, forge.as_ref(), cancel).await
+}
+
+/// [`run`] for the repository at `root`, making PR changes through `forge`
+/// instead of the backend configured in `.merges.json`.
+pub async fn run_with(
+    root: &std::path::Path,
+    opts: PushOptions,
+    forge: &dyn Forge,
+    cancel: &CancelToken,
+) -> Result<()> {
+    if opts.dry_run {
+        return push(root, opts, forge, cancel).await;
+    }
+    let stash = Autostash::begin(root, &MergesState::load(root)?, "push", opts.autostash)?;
+    let result = push(root, opts, forge, cancel).await;
+    stash.finish(result)
+}
+
+/// Describe the commits on `origin/<branch>` that are neither in the local
+/// chunk branch — not even as rebased copies — nor in what merges last pushed
+/// there, and a diffstat of what they change; pushing would discard them.
+/// `None` when there are none or the branch is not on origin.
+pub fn foreign_commits(root: &std::path::Path, chunk: &Chunk) -> Result<Option<String>> {
+    let remote = format!("origin/{}", chunk.branch);
+    if git::rev_parse(root, &remote).is_err() {
+        return Ok(None);
+    }
+    // What merges pushed last is ours, as long as that commit is still around.
+    let pushed: Vec<&str> = chunk
+        .pushed_sha
+        .as_deref()
+        .filter(|sha| git::rev_parse(root, &format!("{}^{{commit}}", sha)).is_ok())
+        .into_iter()
+        .collect();
+    let commits = git::commits_only_on(root, &chunk.branch, &remote, &pushed, "%h %s (%an)")?;
+    if commits.is_empty() {
+        return Ok(None);
+    }
+    let fork = git::merge_base_of(root, &chunk.branch, &remote)?;
+    Ok(Some(format!(
+        "'{}' has {} commit(s) on origin that your branch does not:\n  {}\n{}",
+        chunk.branch,
+        commits.len(),
+        commits.join("\n  "),
+        git::diff_stat(root, &fork, &remote)?
+    )))
+}
+
+/// Stop between chunks if `cancel` was tripped, back on the starting `branch`
+/// in classic mode. `push_interrupted` stays set for `--resume`.
+fn stop_if_cancelled(
+    cancel: &CancelToken,
+    root: &std::path::Path,
+    state: &MergesState,
+    branch: &str,
+    progress: &str,
+) -> Result<()> {
+    if cancel.is_cancelled() {
+        output::step_completed("push");
+        if !state.use_worktrees {
+            git::checkout(root, branch)?;
+        }
+    }
+    cancel.check(&format!("{} — run `merges push --resume` to finish", progress))
+}
+
+async fn push(root: &std::path::Path, opts: PushOptions, forge: &dyn Forge, cancel: &CancelToken) -> Result<()> {
+    let PushOptions { stacked, independent, auto_merge, tracking_issue, source_pr, resume, update_prs, .. } = opts;
+    let mut state = MergesState::load(root)?;
 
     if state.chunks.is_empty() {
         bail!("No chunks defined. Run `merges split` first.");
@@ -23,122 +191,1007 @@ pub async fn run(stacked: bool, independent: bool) -> Result<()> {
     } else {
         state.strategy.clone()
     };
+    if opts.dry_run {
+        dry_run(root, &state, &strategy);
+        return Ok(());
+    }
+
+    if resume && !state.push_interrupted {
+        warning!("No interrupted push recorded — pushing every chunk.");
+    } else if !resume && state.push_interrupted {
+        warning!(
+            "The last push was interrupted — pushing every chunk again ({} skips chunks already pushed).",
+            "--resume".bold()
+        );
+    }
+    let resume = resume && state.push_interrupted;
 
-    println!(
+    // Find out now, not halfway through the stack, if the token falls short.
+    let mut needs = vec![Access::PullRequests];
+    if tracking_issue || state.tracking_issue.is_some() {
+        needs.push(Access::Issues);
+    }
+    if auto_merge.is_some() {
+        needs.push(Access::AutoMerge);
+    }
+    // Fetch origin meanwhile: force-with-lease only protects against changes
+    // made since the last fetch, so commits a teammate pushed are checked for
+    // below, before anything is rewritten.
+    let fetch = tokio::task::spawn_blocking({
+        let root = root.to_path_buf();
+        move || git::fetch(&root)
+    });
+    let (missing, fetched) = tokio::join!(forge.missing_permissions(&needs), fetch);
+    let missing = missing?;
+    if !missing.is_empty() {
+        bail!(
+            "Your {} credentials cannot do everything this push needs:\n  {}\nNothing was pushed.",
+            forge.name(),
+            missing.join("\n  ")
+        );
+    }
+    fetched.context("git fetch did not finish")??;
+    let foreign: Vec<String> =
+        state.chunks.iter().map(|c| foreign_commits(root, c)).filter_map(Result::transpose).collect::<Result<_>>()?;
+    if !foreign.is_empty() && !opts.force {
+        bail!(
+            "{}\nPull those commits into the chunk branch first (e.g. `git pull --rebase` in it), or pass `--force` to \
+             overwrite them.",
+            foreign.join("\n\n")
+        );
+    }
+    for summary in &foreign {
+        warning!("Overwriting with --force: {}", summary);
+    }
+
+    // Merge mode pushes without force, so nothing may rewrite the branches.
+    if state.sync_merge && (opts.squash || state.squash_on_push) {
+        bail!("Squashing rewrites the chunk branches, which merge mode (`sync_merge`) never does. Nothing was pushed.");
+    }
+    if opts.squash || state.squash_on_push {
+        crate::commands::squash::squash(root, &[])?;
+    }
+    if opts.skip_ci && state.sync_merge {
+        warning!("--skip-ci rewrites chunk commits, which merge mode (`sync_merge`) never does; ignoring it.");
+    } else if opts.skip_ci {
+        mark_skip_ci(root, &state)?;
+    }
+
+    check_sizes(root, &state, &strategy, opts.allow_large)?;
+
+    say!(
         "{} Pushing {} chunk(s) as {} PRs",
         "→".blue().bold(),
         state.chunks.len().to_string().yellow(),
         format!("{}", strategy).cyan()
     );
 
-    let token = config::github_token()?;
-    let gh = github::client(&token)?;
-
-    let current_branch = git::current_branch(&root)?;
-    let mp = MultiProgress::new();
-    let spinner_style = ProgressStyle::default_spinner()
-        .template("{spinner} {msg}")
-        .unwrap();
+    let current_branch = git::current_branch(root)?;
+    let mut stagger = Stagger { every: opts.stagger, last: None };
+    // What each chunk was last pushed as, before this run records new tips.
+    let previous: Vec<Option<String>> = state.chunks.iter().map(|c| c.pushed_sha.clone()).collect();
+    let mut range_diffs: Vec<(u64, String)> = vec![];
+    // Where each stacked chunk forks off its parent, read before any branch
+    // moves, so each is rebased onto its parent with only its own commits
+    // replayed, as `merges sync` does.
+    let forks: Vec<String> = if strategy == Strategy::Stacked && !state.sync_merge {
+        let stacked = MergesState { strategy: Strategy::Stacked, ..state.clone() };
+        (0..state.chunks.len()).map(|i| split::fork_point(root, &stacked, i)).collect::<Result<_>>()?
+    } else {
+        vec![]
+    };
 
+    // ── Pass 1: rebase and push every chunk branch ───────────────────────
+    // All branches must exist on origin before any PR is created or retargeted,
+    // otherwise a stacked PR could point at a branch that is not pushed yet.
+    state.push_interrupted = true;
+    state.save(root)?;
+    output::step_started("push", state.chunks.len());
     for i in 0..state.chunks.len() {
+        let progress = format!("after pushing {} of {} branch(es)", i, state.chunks.len());
+        stop_if_cancelled(cancel, root, &state, &current_branch, &progress)?;
         let chunk = state.chunks[i].clone();
-        let pb = mp.add(ProgressBar::new_spinner());
-        pb.set_style(spinner_style.clone());
-        pb.enable_steady_tick(std::time::Duration::from_millis(80));
-        pb.set_message(format!("Processing chunk '{}'…", chunk.name));
+        output::chunk_progress(&chunk.name, format!("Processing chunk '{}'…", chunk.name));
+
+        if resume && already_pushed(&chunk, &git::rev_parse(root, &chunk.branch)?) {
+            output::chunk_done(&chunk.name, format!("{} [{}] Already pushed", "·".dimmed(), chunk.name.cyan()));
+            continue;
+        }
 
         // Switch to chunk branch and sync with base
         // In worktree mode, operate in the chunk's worktree dir — no branch checkout needed.
         let work_dir = if state.use_worktrees {
-            git::worktree_path(&root, &chunk.branch)
+            git::worktree_path(root, &chunk.branch)
         } else {
-            git::checkout(&root, &chunk.branch)?;
-            root.clone()
+            git::checkout(root, &chunk.branch)?;
+            root.to_path_buf()
         };
-        pb.set_message(format!("[{}] Rebasing onto '{}'…", chunk.name, state.base_branch));
-        match strategy {
-            Strategy::Stacked => git::fetch_and_rebase_stacked(&work_dir, &state.base_branch)?,
-            Strategy::Independent => git::fetch_and_rebase(&work_dir, &state.base_branch)?,
+        // In merge mode the base comes in through `merges sync`; the branch is
+        // pushed as it is.
+        if !state.sync_merge {
+            let onto = match strategy {
+                Strategy::Stacked if i > 0 => state.chunks[i - 1].branch.clone(),
+                _ => state.base_branch.clone(),
+            };
+            output::chunk_progress(&chunk.name, format!("[{}] Rebasing onto '{}'…", chunk.name, onto));
+            match strategy {
+                Strategy::Stacked => {
+                    let onto = if i > 0 { onto } else { git::base_ref(root, &onto) };
+                    git::rebase_onto(&work_dir, &onto, &forks[i]).with_context(|| {
+                        format!("Rebasing '{}' stopped — resolve the conflicts, then run `merges sync`", chunk.branch)
+                    })?
+                }
+                Strategy::Independent => git::fetch_and_rebase(&work_dir, &state.base_branch)?,
+            }
+        }
+
+        // Pushing to a branch with an open PR sets off its CI.
+        let skips_ci = opts.skip_ci && chunk.skip_ci;
+        let remote = git::rev_parse(&work_dir, &format!("origin/{}", chunk.branch)).ok();
+        if chunk.pr_number.is_some() && !skips_ci && remote != Some(git::rev_parse(&work_dir, &chunk.branch)?) {
+            stagger.wait(&chunk.name, cancel).await;
+            stop_if_cancelled(cancel, root, &state, &current_branch, &progress)?;
         }
 
         // Push
-        pb.set_message(format!("[{}] Pushing…", chunk.name));
-        git::push_branch(&work_dir, &chunk.branch)?;
+        output::chunk_progress(&chunk.name, format!("[{}] Pushing…", chunk.name));
+        git::push_branch(&work_dir, &chunk.branch, !state.sync_merge)?;
+        let pushed = git::rev_parse(&work_dir, &format!("origin/{}", chunk.branch))?;
+        state.chunks[i].pushed_sha = Some(pushed.clone());
+        state.chunks[i].last_pushed_at = Some(Utc::now());
+        state.save(root)?;
 
-        // Determine base for this PR
-        let pr_base = match strategy {
-            Strategy::Stacked if i > 0 => state.chunks[i - 1].branch.clone(),
-            _ => state.base_branch.clone(),
-        };
+        // A rewritten branch loses reviewers their place; tell them what changed.
+        let rewritten = previous[i].as_deref().filter(|old| !git::is_ancestor(root, old, &pushed));
+        if let (Some(pr_number), Some(old), false) = (chunk.pr_number, rewritten, chunk.hold) {
+            match push_range_diff(root, &state, &strategy, &previous, i, old) {
+                Ok(range_diff) => range_diffs.push((pr_number, range_diff_comment(old, &pushed, &range_diff))),
+                Err(e) => warning!("No range-diff for PR #{}: {:#}", pr_number, e),
+            }
+        }
+        output::chunk_done(&chunk.name, "");
+    }
+    output::step_completed("push");
 
-        // Build PR body
-        let files_list = chunk.files.iter().map(|f| format!("- `{}`", f)).collect::<Vec<_>>().join("\n");
-        let body = format!(
-            "## Chunk {}/{}: {}\n\n**Strategy:** {}\n\n### Files included\n{}\n\n---\n*Generated by [merges](https://github.com/merges-cli/merges)*",
-            i + 1,
-            state.chunks.len(),
-            chunk.name,
-            strategy,
-            files_list
-        );
+    // Chunks whose changes already landed on the base now have empty branches.
+    let mut empty_chunks = if opts.allow_empty { vec![] } else { empty::find(root, &state, &strategy)? };
+    if !empty_chunks.is_empty() {
+        empty::warn(&state, &empty_chunks, &strategy);
+        if empty::confirm_drop(empty_chunks.len(), opts.drop_empty)? {
+            empty::drop_chunks(root, &mut state, &empty_chunks)?;
+            empty_chunks.clear();
+        }
+    }
 
-        let title = {
-            let body = format!("[{}/{}] {}", i + 1, state.chunks.len(), chunk.name);
-            // Explicit commit_prefix overrides auto-detection
-            match &state.commit_prefix {
-                Some(p) => format!("{} {}", p, body),
-                None => git::pr_title(&state.source_branch, &body),
-            }
-        };
+    // ── Pass 2: verify the whole PR chain, then create missing PRs ──────
+    // Every existing PR is checked against its expected base on each push, so
+    // inserted or reordered chunks never leave the stack pointing at stale branches.
+    let total = state.chunks.len();
+    output::step_started("prs", total);
+    for i in 0..total {
+        let progress = format!("after updating {} of {} PR(s)", i, total);
+        stop_if_cancelled(cancel, root, &state, &current_branch, &progress)?;
+        let chunk = state.chunks[i].clone();
+        let pr_base = expected_pr_base(&state, i, &strategy);
 
-        if let Some(pr_number) = chunk.pr_number {
-            // PR exists — update base if strategy changed
-            pb.set_message(format!("[{}] Updating PR #{}…", chunk.name, pr_number));
-            github::update_pr_base(&gh, &state.repo_owner, &state.repo_name, pr_number, &pr_base)
-                .await?;
-            pb.finish_with_message(format!(
-                "{} [{}] PR #{} updated → {}",
-                "✓".green(),
-                chunk.name.cyan(),
-                pr_number,
-                chunk.pr_url.as_deref().unwrap_or("").dimmed()
-            ));
+        if empty_chunks.contains(&i) {
+            output::chunk_done(
+                &chunk.name,
+                format!(
+                    "{} [{}] No changes — PR skipped ({} drops the chunk)",
+                    "!".yellow(),
+                    chunk.name.cyan(),
+                    "--drop-empty".bold()
+                ),
+            );
+            continue;
+        }
+        if chunk.hold {
+            let pr = chunk.pr_number.map_or_else(|| "PR skipped".to_string(), |n| format!("PR #{} left as is", n));
+            output::chunk_done(
+                &chunk.name,
+                format!("{} [{}] Held — {} (`merges unhold` releases it)", "·".dimmed(), chunk.name.cyan(), pr),
+            );
+            continue;
+        }
+
+        let title = pr_title(&state, i);
+        // Line counts are a nicety; a body without them is still correct.
+        let stats = git::diff_numstat(root, &empty::parent_ref(&state, i, &strategy), &chunk.branch)
+            .unwrap_or_default();
+        let body = pr_body(&state, i, &strategy, &stats);
+
+        let done = if let Some(pr_number) = chunk.pr_number {
+            // PR exists — retarget it only if it has drifted from the expected base
+            output::chunk_progress(&chunk.name, format!("[{}] Verifying base of PR #{}…", chunk.name, pr_number));
+            let current_base = forge.get_pr_base(pr_number).await?;
+            let refreshed = if update_prs {
+                let message = format!("[{}] Refreshing title and body of PR #{}…", chunk.name, pr_number);
+                output::chunk_progress(&chunk.name, message);
+                forge.update_pr(pr_number, &title, Some(&body)).await?;
+                state.chunks[i].pr_title = Some(title.clone());
+                format!(" {}", "(title/body refreshed)".dimmed())
+            } else if chunk.pr_title.as_ref().is_some_and(|t| *t != title) {
+                // The stack changed shape since merges last titled this PR
+                output::chunk_progress(&chunk.name, format!("[{}] Retitling PR #{}…", chunk.name, pr_number));
+                forge.update_pr(pr_number, &title, None).await?;
+                state.chunks[i].pr_title = Some(title.clone());
+                format!(" {}", format!("(retitled: {})", title).dimmed())
+            } else {
+                String::new()
+            };
+            if current_base != pr_base {
+                forge.update_pr_base(pr_number, &pr_base).await?;
+                format!(
+                    "{} [{}] PR #{} retargeted {} → {}{}",
+                    "✓".green(),
+                    chunk.name.cyan(),
+                    pr_number,
+                    current_base.dimmed(),
+                    pr_base.yellow(),
+                    refreshed
+                )
+            } else {
+                format!(
+                    "{} [{}] PR #{} updated → {}{}",
+                    "✓".green(),
+                    chunk.name.cyan(),
+                    pr_number,
+                    chunk.pr_url.as_deref().unwrap_or("").dimmed(),
+                    refreshed
+                )
+            }
         } else {
-            // Create new PR
-            pb.set_message(format!("[{}] Creating PR…", chunk.name));
-            let (pr_number, pr_url) = github::create_pr(
-                &gh,
-                &state.repo_owner,
-                &state.repo_name,
-                &title,
-                &chunk.branch,
-                &pr_base,
-                &body,
-            )
-            .await?;
+            // An interrupted run may have opened the PR without recording it —
+            // adopt and refresh it rather than failing on a duplicate.
+            if !(opts.skip_ci && chunk.skip_ci) {
+                stagger.wait(&chunk.name, cancel).await;
+                stop_if_cancelled(cancel, root, &state, &current_branch, &progress)?;
+            }
+            output::chunk_progress(&chunk.name, format!("[{}] Creating PR…", chunk.name));
+            let (pr_number, pr_url, created) =
+                forge.create_or_update_pr(&title, &chunk.branch, &pr_base, &body).await?;
+            let action = if created { "created" } else { "found existing" };
 
             state.chunks[i].pr_number = Some(pr_number);
             state.chunks[i].pr_url = Some(pr_url.clone());
+            state.chunks[i].pr_title = Some(title.clone());
             state.strategy = strategy.clone();
-            state.save(&root)?;
+            state.save(root)?;
 
-            pb.finish_with_message(format!(
-                "{} [{}] PR #{} created → {}",
+            format!(
+                "{} [{}] PR #{} {} → {}",
                 "✓".green(),
                 chunk.name.cyan(),
                 pr_number,
+                action,
                 pr_url.dimmed()
-            ));
+            )
+        };
+
+        // Auto-merge failures (e.g. disabled in repo settings) don't undo the push.
+        // A stacked PR would auto-merge into the chunk below it, so only PRs
+        // into the base get it; the others get it from a later push, once
+        // they are retargeted at the base.
+        let pr_base = expected_pr_base(&state, i, &strategy);
+        let done = match (auto_merge, state.chunks[i].pr_number) {
+            (Some(_), Some(_)) if pr_base != state.base_branch => {
+                format!("{} {}", done, format!("(no auto-merge while it targets '{}')", pr_base).dimmed())
+            }
+            (Some(method), Some(pr_number)) => {
+                output::chunk_progress(&chunk.name, format!("[{}] Enabling auto-merge ({})…", chunk.name, method));
+                match forge.enable_auto_merge(pr_number, method).await {
+                    Ok(()) => format!("{} {}", done, format!("(auto-merge: {})", method).dimmed()),
+                    Err(e) => format!("{} {} {}", done, "!".yellow(), e),
+                }
+            }
+            _ => done,
+        };
+        output::chunk_done(&chunk.name, done);
+    }
+    output::step_completed("prs");
+
+    state.strategy = strategy.clone();
+    state.push_interrupted = false;
+    state.save(root)?;
+    journal::record(root, "push", &format!("{} PRs", strategy), &state.chunks)?;
+
+    if !state.routes.is_empty() {
+        apply_routes(forge, root, &mut state).await?;
+    }
+    if state.project.is_some() || state.milestone.is_some() {
+        sync_planning(forge, root, &mut state).await?;
+    }
+
+    // Every chunk PR carries a comment linking its neighbours and the whole stack.
+    if !state.no_stack_comments {
+        sync_stack_comments(forge, root, &mut state).await?;
+    }
+
+    for (pr_number, body) in &range_diffs {
+        match forge.comment_on_pr(*pr_number, body).await {
+            Ok(_) => say!("{} What changed since the last push posted on PR #{}", "✓".green(), pr_number),
+            Err(e) => warning!("Range-diff comment not posted on PR #{}: {:#}", pr_number, e),
         }
     }
 
+    // With `post_summary`, the review summary goes in the issue and the comment below.
+    let posted = state.post_summary.then(|| summary::details(&state, &summary::review_order(root, &state)));
+    // Once a tracking issue exists, every push keeps it current.
+    if tracking_issue || state.tracking_issue.is_some() {
+        sync_tracking_issue(forge, root, &mut state, posted.as_deref()).await?;
+    }
+    // Likewise for the comment on the source branch's PR.
+    if source_pr.is_some() || state.source_pr.is_some() {
+        sync_source_pr(forge, root, &mut state, source_pr, posted.as_deref()).await?;
+    }
+    let summary = summary::write(root, &state)?;
+
     // In classic mode, return to the original branch
     if !state.use_worktrees {
-        git::checkout(&root, &current_branch)?;
+        git::checkout(root, &current_branch)?;
+    }
+
+    say!("\n{} All chunks pushed successfully!", "✓".green().bold());
+    say!("  Run {} to see PR status.", "merges status".bold());
+    say!("  Review summary: {}", summary.display());
+
+    Ok(())
+}
+
+/// Spaces out the CI runs a push sets off.
+struct Stagger {
+    every: Option<Duration>,
+    last: Option<Instant>,
+}
+
+impl Stagger {
+    /// Wait until `every` has passed since the last run was set off — in
+    /// short sleeps, so a cancelled push does not sit the wait out — and
+    /// count the run about to start.
+    async fn wait(&mut self, chunk: &str, cancel: &CancelToken) {
+        if let (Some(every), Some(last)) = (self.every, self.last) {
+            let until = last + every;
+            if until > Instant::now() {
+                let message = format!(
+                    "[{}] Waiting {}s before setting off more CI (--stagger)…",
+                    chunk,
+                    until.saturating_duration_since(Instant::now()).as_secs()
+                );
+                output::chunk_progress(chunk, message);
+            }
+            while !cancel.is_cancelled() && until > Instant::now() {
+                tokio::time::sleep(until.saturating_duration_since(Instant::now()).min(Duration::from_secs(1))).await;
+            }
+        }
+        self.last = Some(Instant::now());
+    }
+}
+
+/// Give the tip commit of every chunk marked `skip_ci` a `skip-checks: true`
+/// trailer, which GitHub Actions takes as an instruction not to run
+/// workflows for the PR. Chunks stacked on a reworded chunk are rebased onto
+/// it.
+fn mark_skip_ci(root: &std::path::Path, state: &MergesState) -> Result<()> {
+    let current = git::current_branch(root)?;
+    for (i, chunk) in state.chunks.iter().enumerate() {
+        if !chunk.skip_ci {
+            continue;
+        }
+        let tip = git::rev_parse(root, &chunk.branch)?;
+        let marked = git::add_trailer(root, &tip, SKIP_CI_TRAILER)?;
+        if marked != tip {
+            crate::commands::squash::replace_tip(root, state, i, &current, &marked)?;
+            say!("{} [{}] Marked to skip CI", "·".dimmed(), chunk.name.cyan());
+        }
+    }
+    Ok(())
+}
+
+/// Parse a `--stagger` interval: a number of seconds, or a number followed by
+/// `s`, `m`, or `h` (`90s`, `2m`, `1h`).
+pub fn parse_duration(text: &str) -> std::result::Result<Duration, String> {
+    let text = text.trim();
+    let (number, unit) = text.split_at(text.find(|c: char| !c.is_ascii_digit()).unwrap_or(text.len()));
+    let number: u64 = number.parse().map_err(|_| format!("'{}' is not a duration like 90s, 2m, or 1h", text))?;
+    let seconds = match unit {
+        "" | "s" => number,
+        "m" => number * 60,
+        "h" => number * 3600,
+        _ => return Err(format!("Unknown unit '{}' in '{}' — use s, m, or h", unit, text)),
+    };
+    Ok(Duration::from_secs(seconds))
+}
+
+/// Print the PR each chunk would get and the workflows its files should
+/// trigger, then warn about chunks whose CI looks wrong (see [`ci::warnings`]).
+fn dry_run(root: &std::path::Path, state: &MergesState, strategy: &Strategy) {
+    let workflows = ci::workflows(root, &state.source_branch);
+    say!("{} Dry run — nothing will be pushed.\n", "·".dimmed());
+    for (i, (chunk, predicted)) in state.chunks.iter().zip(ci::predict(state, strategy, &workflows)).enumerate() {
+        let pr = match chunk.pr_number {
+            _ if chunk.hold => "held, no PR update".to_string(),
+            Some(n) => format!("PR #{}", n),
+            None => "new PR".to_string(),
+        };
+        say!(
+            "  {} {} → {} ({})",
+            git::stack_position(i, state.chunks.len()),
+            chunk.branch.cyan(),
+            expected_pr_base(state, i, strategy),
+            pr
+        );
+        say!("      {}", pr_title(state, i));
+        let routed = routing::route(&state.routes, &chunk.files);
+        if !routed.is_empty() {
+            say!("      {} {}", "Routes:".dimmed(), routed.targets().join(", "));
+        }
+        let planning: Vec<String> = [
+            state.milestone.as_ref().map(|m| format!("milestone '{}'", m)),
+            state.project.as_ref().map(|p| format!("project {}", p)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        if !planning.is_empty() && !chunk.hold {
+            say!("      {} {}", "Planning:".dimmed(), planning.join(", "));
+        }
+        if !workflows.is_empty() {
+            let runs = if predicted.workflows.is_empty() { "none".to_string() } else { predicted.workflows.join(", ") };
+            say!("      {} {}", "CI:".dimmed(), runs);
+        }
+    }
+    for warning in ci::warnings(state, strategy, &workflows) {
+        warning!("{}", warning);
     }
+    for (i, chunk) in state.chunks.iter().enumerate().filter(|(_, c)| !c.hold) {
+        for breach in size_breaches(root, state, i, strategy) {
+            let limit = if breach.hard { "over the hard limit; push refuses" } else { "over the soft limit" };
+            warning!("'{}' is {} — {}", chunk.name, breach.detail, limit);
+        }
+    }
+}
 
-    println!("\n{} All chunks pushed successfully!", "✓".green().bold());
-    println!("  Run {} to see PR status.", "merges status".bold());
+/// The size limits chunk `i` goes over, measured from its parent.
+pub fn size_breaches(root: &std::path::Path, state: &MergesState, i: usize, strategy: &Strategy) -> Vec<Breach> {
+    if state.size_limits.is_empty() {
+        return vec![];
+    }
+    let stats = git::diff_numstat(root, &empty::parent_ref(state, i, strategy), &state.chunks[i].branch);
+    size::check(&state.size_limits, Size::of(&stats.unwrap_or_default()))
+}
 
+/// Warn about chunks over a soft size limit, and refuse the push for those
+/// over a hard one unless `allow_large` is set. Held chunks get no PR to
+/// review, so no limit applies to them.
+fn check_sizes(root: &std::path::Path, state: &MergesState, strategy: &Strategy, allow_large: bool) -> Result<()> {
+    let mut oversized = vec![];
+    for (i, chunk) in state.chunks.iter().enumerate().filter(|(_, c)| !c.hold) {
+        for breach in size_breaches(root, state, i, strategy) {
+            let message = format!("'{}' is {}", chunk.name, breach.detail);
+            if breach.hard && !allow_large {
+                oversized.push(message);
+            } else {
+                warning!("{}{}", message, if breach.hard { " — pushing anyway (--allow-large)" } else { "" });
+            }
+        }
+    }
+    if !oversized.is_empty() {
+        bail!(
+            "Chunks over the hard size limit:\n  {}\nSplit them further — `merges move` some of their files into \
+             another chunk — or pass `--allow-large` to push them anyway. Nothing was pushed.",
+            oversized.join("\n  ")
+        );
+    }
     Ok(())
 }
+
+/// Return the branch that chunk `i`'s PR should target.
+///
+/// Stacked: chunk 1 targets the base branch and chunk N targets chunk N-1's branch.
+/// Independent: every chunk targets the base branch.
+pub fn expected_pr_base(state: &MergesState, i: usize, strategy: &Strategy) -> String {
+    match strategy {
+        Strategy::Stacked if i > 0 => state.chunks[i - 1].branch.clone(),
+        _ => state.base_branch.clone(),
+    }
+}
+
+/// Title for chunk `i`'s PR: its stack position (unless `no_title_position`)
+/// and name, behind the explicit `commit_prefix` or the ticket detected from
+/// the source branch. Over `max_title_length`, only the name is shortened.
+/// A title from the split plan stands in for the name.
+pub fn pr_title(state: &MergesState, i: usize) -> String {
+    let chunk = &state.chunks[i];
+    let prefix = state.message_prefix();
+    let name = match (&chunk.custom_pr_title, &prefix) {
+        // The prefix goes first either way, so don't repeat it after the position.
+        (Some(title), Some(p)) => title.strip_prefix(p.as_str()).map_or(title.as_str(), str::trim_start),
+        (Some(title), None) => title.as_str(),
+        (None, _) => chunk.name.as_str(),
+    };
+    let mut head: Vec<String> = prefix.into_iter().collect();
+    if !state.no_title_position {
+        head.push(git::stack_position(i, state.chunks.len()));
+    }
+    git::fit_subject(&head.join(" "), name, state.max_title_length)
+}
+
+/// Markers around the stack table in a chunk PR body, so the prose above it
+/// can be edited (see `merges pr-body edit`) without touching the table.
+pub const STACK_START: &str = "<!-- merges:stack -->";
+pub const STACK_END: &str = "<!-- /merges:stack -->";
+
+/// Body for chunk `i`'s PR: its position and description, a link to the whole
+/// source branch's changes, a collapsible tree of the files it carries (with
+/// line counts from `stats`), and a table of the whole stack with this chunk
+/// in bold. A body from the split plan replaces everything but the stack table.
+pub fn pr_body(state: &MergesState, i: usize, strategy: &Strategy, stats: &[git::FileStat]) -> String {
+    let chunk = &state.chunks[i];
+    let stack_rows = state
+        .chunks
+        .iter()
+        .enumerate()
+        .map(|(j, c)| {
+            let pr = match c.pr_number {
+                Some(n) => format!("#{}", n),
+                None if c.hold => "held".to_string(),
+                None => "—".to_string(),
+            };
+            if j == i {
+                format!("| **{}** | **{}** | **{}** |", j + 1, c.name, pr)
+            } else {
+                format!("| {} | {} | {} |", j + 1, c.name, pr)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let summary = match &chunk.custom_pr_body {
+        Some(body) => body.trim_end().to_string(),
+        None => format!(
+            "## Chunk {}/{}: {}\n\n{}**Strategy:** {}{}\n\n### Files included\n{}",
+            i + 1,
+            state.chunks.len(),
+            chunk.name,
+            chunk.description.as_deref().map(|d| format!("{}\n\n", d.trim())).unwrap_or_default(),
+            strategy,
+            forge::compare_url(state, &state.base_branch, &state.source_branch)
+                .map(|url| format!("\n\n**Whole change:** [`{}...{}`]({})", state.base_branch, state.source_branch, url))
+                .unwrap_or_default(),
+            file_tree(&chunk.files, stats)
+        ),
+    };
+    format!(
+        "{}\n\n{}\n### Stack\n| # | Chunk | PR |\n|---|---|---|\n{}\n{}\n\n---\n*Generated by [merges](https://github.com/merges-cli/merges)*",
+        summary, STACK_START, stack_rows, STACK_END
+    )
+}
+
+/// The prose of a chunk PR body: everything above its stack table. Bodies
+/// written before the table had markers end at its `### Stack` heading.
+pub fn body_prose(body: &str) -> &str {
+    let end = body.find(STACK_START).or_else(|| body.find("\n### Stack\n")).unwrap_or(body.len());
+    body[..end].trim()
+}
+
+/// `files` as an indented directory tree inside a collapsed `<details>` block,
+/// each file followed by its added/removed line counts where `stats` has them.
+fn file_tree(files: &[String], stats: &[git::FileStat]) -> String {
+    let mut sorted: Vec<&String> = files.iter().collect();
+    sorted.sort();
+
+    let mut lines = vec![];
+    let mut open_dirs: Vec<&str> = vec![];
+    for path in sorted {
+        let parts: Vec<&str> = path.split('/').collect();
+        let (dirs, name) = parts.split_at(parts.len() - 1);
+        let shared = open_dirs.iter().zip(dirs).take_while(|(a, b)| a == b).count();
+        open_dirs.truncate(shared);
+        for dir in &dirs[shared..] {
+            lines.push(format!("{}{}/", "  ".repeat(open_dirs.len()), dir));
+            open_dirs.push(*dir);
+        }
+        let counts = match stats.iter().find(|s| s.path == *path) {
+            Some(git::FileStat { added: Some(a), removed: Some(r), .. }) => format!("  +{} -{}", a, r),
+            Some(_) => "  binary".to_string(),
+            None => String::new(),
+        };
+        lines.push(format!("{}{}{}", "  ".repeat(open_dirs.len()), name[0], counts));
+    }
+
+    let in_chunk: Vec<&git::FileStat> = stats.iter().filter(|s| files.contains(&s.path)).collect();
+    let totals = if in_chunk.is_empty() {
+        String::new()
+    } else {
+        let added: usize = in_chunk.iter().filter_map(|s| s.added).sum();
+        let removed: usize = in_chunk.iter().filter_map(|s| s.removed).sum();
+        format!(", +{} -{}", added, removed)
+    };
+    format!(
+        "<details>\n<summary>{} file(s){}</summary>\n\n```\n{}\n```\n\n</details>",
+        files.len(),
+        totals,
+        lines.join("\n")
+    )
+}
+
+/// True when `chunk` was last pushed at `head_sha`, so a resumed push can skip
+/// rebasing and pushing it again.
+pub fn already_pushed(chunk: &Chunk, head_sha: &str) -> bool {
+    chunk.pushed_sha.as_deref() == Some(head_sha)
+}
+
+/// Create or refresh the umbrella issue that tracks the whole stack, closing it
+/// once every chunk PR has merged. Chunks already taken off the stack count as
+/// merged.
+pub async fn sync_tracking_issue(
+    forge: &dyn Forge,
+    root: &std::path::Path,
+    state: &mut MergesState,
+    summary: Option<&str>,
+) -> Result<()> {
+    let mut merged = vec![];
+    for chunk in &mut state.chunks {
+        let Some(pr_number) = chunk.pr_number else { continue };
+        let info = forge.get_pr_info(pr_number).await?;
+        if info.merged {
+            merged.push(pr_number);
+            chunk.merged_at = chunk.merged_at.or(info.merged_at).or_else(|| Some(Utc::now()));
+        }
+    }
+    let complete = state
+        .chunks
+        .iter()
+        .all(|c| c.pr_number.is_some_and(|n| merged.contains(&n)));
+    let body = with_summary(tracking_issue_body(state, &merged), summary);
+
+    match state.tracking_issue {
+        Some(number) => {
+            forge.update_issue(number, &body, complete).await?;
+            state.save(root)?; // keep any merged_at recorded above
+            say!(
+                "{} Tracking issue #{} {}",
+                "✓".green(),
+                number,
+                if complete { "closed — stack complete" } else { "updated" }
+            );
+        }
+        None => {
+            let title = {
+                let body = format!("Stack: {}", state.source_branch);
+                match &state.commit_prefix {
+                    Some(p) => format!("{} {}", p, body),
+                    None => git::pr_title(&state.source_branch, &body),
+                }
+            };
+            let (number, url) = forge.create_issue(&title, &body).await?;
+            state.tracking_issue = Some(number);
+            state.save(root)?;
+            say!("{} Tracking issue #{} created → {}", "✓".green(), number, url.dimmed());
+        }
+    }
+    Ok(())
+}
+
+/// `git range-diff` between what chunk `i` was when `old` was pushed and what
+/// it is now, each side starting where the chunk leaves its parent — the
+/// parent's own tip at the time, in a stack — so only the chunk's commits show.
+fn push_range_diff(
+    root: &std::path::Path,
+    state: &MergesState,
+    strategy: &Strategy,
+    previous: &[Option<String>],
+    i: usize,
+    old: &str,
+) -> Result<String> {
+    let old_parent = match (strategy, i.checked_sub(1)) {
+        (Strategy::Stacked, Some(p)) => previous[p].clone().unwrap_or_else(|| state.chunks[p].branch.clone()),
+        _ => format!("origin/{}", state.base_branch),
+    };
+    let old_base = git::merge_base_of(root, old, &old_parent)?;
+    let branch = &state.chunks[i].branch;
+    let new_base = git::merge_base_of(root, branch, &empty::parent_ref(state, i, strategy))?;
+    git::range_diff(root, &format!("{}..{}", old_base, old), &format!("{}..{}", new_base, branch))
+}
+
+/// Comments longer than this are cut, well inside GitHub's 65536-character limit.
+const MAX_RANGE_DIFF: usize = 60_000;
+
+/// Render the comment telling reviewers what a force-push from `old` to `new`
+/// changed, from its `range_diff`.
+pub fn range_diff_comment(old: &str, new: &str, range_diff: &str) -> String {
+    let short = |sha: &str| sha.chars().take(7).collect::<String>();
+    let head = format!("**Force-pushed** `{}` → `{}`", short(old), short(new));
+    // Pair lines start in column 0; the interdiff under them is indented.
+    let unchanged = range_diff
+        .lines()
+        .filter(|l| !l.is_empty() && !l.starts_with(' '))
+        .all(|l| l.split_whitespace().nth(2) == Some("="));
+    if unchanged {
+        return format!("{}: rebased only, every commit is unchanged.\n\n```\n{}\n```", head, range_diff);
+    }
+    let shown = match range_diff.char_indices().nth(MAX_RANGE_DIFF) {
+        Some((cut, _)) => format!("{}\n… (truncated)", &range_diff[..cut]),
+        None => range_diff.to_string(),
+    };
+    format!("{}. What changed since the last push (`git range-diff`):\n\n````\n{}\n````", head, shown)
+}
+
+/// Label each chunk PR and request its reviewers by the routing rules,
+/// leaving out what earlier pushes did so nobody is asked twice. Like the
+/// stack comments, this comes once the PRs are done, so a failure only warns.
+async fn apply_routes(forge: &dyn Forge, root: &std::path::Path, state: &mut MergesState) -> Result<()> {
+    for i in 0..state.chunks.len() {
+        let chunk = &state.chunks[i];
+        let Some(pr_number) = chunk.pr_number.filter(|_| !chunk.hold) else { continue };
+        let routed = routing::route(&state.routes, &chunk.files).without(&chunk.routed);
+        if routed.is_empty() {
+            continue;
+        }
+        if let Err(e) = route_pr(forge, &mut state.chunks[i], pr_number, routed).await {
+            warning!("Routing rules not applied: {:#} (the next push tries again)", e);
+            break;
+        }
+    }
+    state.save(root)
+}
+
+/// Give `chunk`'s PR the labels and reviewers in `routed`, recording each
+/// kind once the forge has taken it.
+async fn route_pr(forge: &dyn Forge, chunk: &mut Chunk, pr_number: u64, routed: Routed) -> Result<()> {
+    if !routed.labels.is_empty() {
+        forge.add_labels(pr_number, &routed.labels).await?;
+        chunk.routed.extend(routed.labels.iter().map(|l| routing::label(l)));
+    }
+    if !routed.reviewers.is_empty() {
+        forge.request_reviewers(pr_number, &routed.reviewers).await?;
+        chunk.routed.extend(routed.reviewers.iter().map(|r| routing::reviewer(r)));
+    }
+    say!("{} [{}] PR #{} routed: {}", "✓".green(), chunk.name.cyan(), pr_number, routed.targets().join(", "));
+    Ok(())
+}
+
+/// Give each chunk PR the configured milestone and put it on the project,
+/// with its item's status following the PR: open, approved, merged. Landed
+/// chunks count too, so their cards reach the merged status. A failure only
+/// warns, like the other steps after the PRs.
+async fn sync_planning(forge: &dyn Forge, root: &std::path::Path, state: &mut MergesState) -> Result<()> {
+    let (project, milestone) = (state.project.as_ref(), state.milestone.as_deref());
+    let stack = state.chunks.iter_mut().map(|chunk| (chunk, false));
+    for (chunk, landed) in stack.chain(state.merged.iter_mut().map(|chunk| (chunk, true))) {
+        let Some(pr_number) = chunk.pr_number.filter(|_| !chunk.hold) else { continue };
+        let merged = landed || chunk.merged_at.is_some();
+        if let Err(e) = plan_pr(forge, chunk, pr_number, merged, project, milestone).await {
+            warning!("Project and milestone not updated: {:#} (the next push tries again)", e);
+            break;
+        }
+    }
+    state.save(root)
+}
+
+/// Bring one chunk PR's milestone and project item up to date, recording
+/// each change once the forge has taken it.
+async fn plan_pr(
+    forge: &dyn Forge,
+    chunk: &mut Chunk,
+    pr_number: u64,
+    merged: bool,
+    project: Option<&Project>,
+    milestone: Option<&str>,
+) -> Result<()> {
+    if let Some(title) = milestone.filter(|m| chunk.milestone.as_deref() != Some(*m)) {
+        forge.set_milestone(pr_number, title).await?;
+        chunk.milestone = Some(title.to_string());
+        say!("{} [{}] PR #{} milestone: {}", "✓".green(), chunk.name.cyan(), pr_number, title);
+    }
+    let Some(project) = project else { return Ok(()) };
+    let item_id = match &chunk.project_item_id {
+        Some(item_id) => item_id.clone(),
+        None => {
+            let item_id = forge.add_to_project(pr_number, project).await?;
+            chunk.project_item_id = Some(item_id.clone());
+            say!("{} [{}] PR #{} added to project {}", "✓".green(), chunk.name.cyan(), pr_number, project);
+            item_id
+        }
+    };
+    let stage = if merged { Stage::Merged } else { pr_stage(forge, pr_number).await? };
+    let Some(status) = project.statuses.get(&stage).filter(|s| chunk.project_status.as_ref() != Some(*s)) else {
+        return Ok(());
+    };
+    forge.set_project_status(project, &item_id, status).await?;
+    chunk.project_status = Some(status.clone());
+    say!("{} [{}] PR #{} project status: {}", "✓".green(), chunk.name.cyan(), pr_number, status);
+    Ok(())
+}
+
+/// Where a PR still in the stack is: merged on the forge already, approved, or open.
+async fn pr_stage(forge: &dyn Forge, pr_number: u64) -> Result<Stage> {
+    let info = forge.get_pr_info(pr_number).await?;
+    Ok(if info.merged {
+        Stage::Merged
+    } else if info.review_state == "approved" {
+        Stage::Approved
+    } else {
+        Stage::Open
+    })
+}
+
+/// Post or refresh the navigation comment on every chunk PR. The pushes and
+/// PRs are already done, so a forge that cannot comment only earns a warning.
+async fn sync_stack_comments(forge: &dyn Forge, root: &std::path::Path, state: &mut MergesState) -> Result<()> {
+    for i in 0..state.chunks.len() {
+        let Some(pr_number) = state.chunks[i].pr_number else { continue };
+        let body = stack_comment(state, i);
+        let result = match state.chunks[i].stack_comment_id {
+            Some(comment_id) => forge.update_pr_comment(pr_number, comment_id, &body).await,
+            None => match forge.comment_on_pr(pr_number, &body).await {
+                Ok(comment_id) => {
+                    state.chunks[i].stack_comment_id = Some(comment_id);
+                    Ok(())
+                }
+                Err(e) => Err(e),
+            },
+        };
+        if let Err(e) = result {
+            warning!(
+                "Stack navigation comments not updated: {:#} ({} turns them off)",
+                e,
+                "merges init --no-stack-comments".bold()
+            );
+            break;
+        }
+    }
+    state.save(root)
+}
+
+/// Render the navigation comment for chunk `i`'s PR: links to the previous and
+/// next chunk PRs and the whole stack in order, merged PRs marked.
+pub fn stack_comment(state: &MergesState, i: usize) -> String {
+    let link = |c: &Chunk| match c.pr_number {
+        Some(n) => format!("#{} {}", n, c.name),
+        None => format!("{} (no PR yet)", c.name),
+    };
+    let previous = match i.checked_sub(1) {
+        Some(p) => format!("← Previous: {}", link(&state.chunks[p])),
+        None => "← Previous: none, this is the first".to_string(),
+    };
+    let next = match state.chunks.get(i + 1) {
+        Some(c) => format!("Next: {} →", link(c)),
+        None => "Next: none, this is the last →".to_string(),
+    };
+    let items = state
+        .chunks
+        .iter()
+        .enumerate()
+        .map(|(j, c)| {
+            let merged = if c.merged_at.is_some() { " ✓ merged" } else { "" };
+            if j == i {
+                format!("{}. **{}** (this PR){}", j + 1, link(c), merged)
+            } else {
+                format!("{}. {}{}", j + 1, link(c), merged)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        "**Part of a stack of {} PRs** splitting `{}` ({} into `{}`)\n\n{} | {}\n\n{}\n\n---\n*Kept up to date by [merges](https://github.com/merges-cli/merges) on every push*",
+        state.chunks.len(),
+        state.source_branch,
+        state.strategy,
+        state.base_branch,
+        previous,
+        next,
+        items
+    )
+}
+
+/// Comment on the source branch's PR with an index of the chunk PRs, or
+/// refresh the comment left by an earlier push, then apply `action` to the PR.
+async fn sync_source_pr(
+    forge: &dyn Forge,
+    root: &std::path::Path,
+    state: &mut MergesState,
+    action: Option<SourcePrAction>,
+    summary: Option<&str>,
+) -> Result<()> {
+    let body = with_summary(source_pr_comment(state), summary);
+    let number = match state.source_pr {
+        Some(source_pr) => {
+            forge.update_pr_comment(source_pr.number, source_pr.comment_id, &body).await?;
+            say!("{} Chunk index on source PR #{} updated", "✓".green(), source_pr.number);
+            source_pr.number
+        }
+        None => {
+            let Some((number, url)) = forge.find_open_pr(&state.source_branch).await? else {
+                warning!(
+                    "No open PR from '{}' — nothing to point at the chunk PRs.",
+                    state.source_branch.cyan()
+                );
+                return Ok(());
+            };
+            let comment_id = forge.comment_on_pr(number, &body).await?;
+            state.source_pr = Some(SourcePr { number, comment_id });
+            state.save(root)?;
+            say!("{} Listed the chunk PRs on source PR #{} → {}", "✓".green(), number, url.dimmed());
+            number
+        }
+    };
+
+    match action {
+        Some(SourcePrAction::Draft) => {
+            forge.convert_pr_to_draft(number).await?;
+            say!("{} Source PR #{} converted to a draft", "✓".green(), number);
+        }
+        Some(SourcePrAction::Close) => {
+            forge.close_pr(number).await?;
+            say!("{} Source PR #{} closed", "✓".green(), number);
+        }
+        Some(SourcePrAction::Comment) | None => {}
+    }
+    Ok(())
+}
+
+/// `body` with the review summary added before its footer.
+fn with_summary(body: String, summary: Option<&str>) -> String {
+    match (summary, body.rsplit_once("\n---\n")) {
+        (Some(summary), Some((content, footer))) => {
+            format!("{}\n\n{}\n\n---\n{}", content.trim_end(), summary.trim_end(), footer)
+        }
+        _ => body,
+    }
+}
+
+/// Render the comment left on the source branch's PR: the chunk PRs in stack
+/// order, so reviewers move to them instead of the original PR.
+pub fn source_pr_comment(state: &MergesState) -> String {
+    let items = state
+        .chunks
+        .iter()
+        .enumerate()
+        .map(|(i, c)| match c.pr_number {
+            Some(n) => format!("{}. #{} {}", i + 1, n, c.name),
+            None => format!("{}. {} (no PR yet)", i + 1, c.name),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let order = match state.strategy {
+        Strategy::Stacked => " They are stacked, so they merge in this order.",
+        Strategy::Independent => "",
+    };
+    let tracking = state.tracking_issue.map(|n| format!("Progress is tracked in #{}.\n\n", n)).unwrap_or_default();
+    format!(
+        "This PR has been split into {} smaller PRs. Please review those instead — this one is kept for reference.{}\n\n{}\n\n{}---\n*Generated by [merges](https://github.com/merges-cli/merges)*",
+        state.chunks.len(),
+        order,
+        items,
+        tracking
+    )
+}
+
+/// Render the tracking issue body: a checklist of chunk PRs in stack order,
+/// starting with the chunks already taken off the stack, with those and the
+/// PRs listed in `merged` checked off.
+pub fn tracking_issue_body(state: &MergesState, merged: &[u64]) -> String {
+    let landed = state.merged.iter().map(|c| (c, true));
+    let stack: Vec<(&Chunk, bool)> = landed
+        .chain(state.chunks.iter().map(|c| (c, c.pr_number.is_some_and(|n| merged.contains(&n)))))
+        .collect();
+    let items = stack
+        .iter()
+        .map(|&(c, done)| match (c.pr_number, done) {
+            (Some(n), _) => format!("- [{}] #{} {}", if done { "x" } else { " " }, n, c.name),
+            (None, true) => format!("- [x] {}", c.name),
+            (None, false) => format!("- [ ] {} (no PR yet)", c.name),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        "## Stack: `{}` → `{}`\n\n**Strategy:** {}\n**Progress:** {}/{} merged\n\n### Chunks\n{}\n\n---\n*Generated by [merges](https://github.com/merges-cli/merges)*",
+        state.source_branch,
+        state.base_branch,
+        state.strategy,
+        stack.iter().filter(|(_, done)| *done).count(),
+        stack.len(),
+        items
+    )
+}
@@ -0,0 +1,175 @@
+use anyhow::{bail, Result};
+use colored::Colorize;
+use std::path::Path;
+
+use crate::{
+    commands, forge, git, say,
+    state::{chunk_branch_prefix, Chunk, ForgeKind, MergesState, Strategy},
+    warning,
+};
+
+/// Rebuild `.merges.json` from what is on origin — chunk branches named
+/// `<source>-chunk-<n>-<name>` (or `<namespace>/<source>/<n>-<name>`) and the
+/// PRs open for them — so a teammate can take over a stack without the
+/// original state file. Only refs already fetched are scanned; run `git fetch` first.
+pub async fn run(
+    root: &Path,
+    source_branch: Option<String>,
+    base_branch: Option<String>,
+    branch_namespace: Option<String>,
+    forge_kind: Option<ForgeKind>,
+    force: bool,
+) -> Result<MergesState> {
+    if MergesState::path(root).exists() && !force {
+        bail!(".merges.json already exists. Pass --force to replace it with the recovered state.");
+    }
+
+    let source = match source_branch {
+        Some(source) => source.trim_start_matches("origin/").to_string(),
+        None => git::current_branch(root)?,
+    };
+    let base = base_branch.or_else(|| git::default_branch(root)).unwrap_or_else(|| "main".to_string());
+    let remote = forge::detect(&git::remote_url(root)?, forge_kind)?;
+
+    let mut state = discover(root, &source, &base, branch_namespace.as_deref(), remote)?;
+    find_prs(&mut state).await;
+
+    for (i, chunk) in state.chunks.iter().enumerate() {
+        say!(
+            "  {} {} {} — {} file(s){}",
+            git::stack_position(i, state.chunks.len()).dimmed(),
+            chunk.name.cyan(),
+            chunk.branch.dimmed(),
+            chunk.files.len(),
+            chunk.pr_number.map(|n| format!(", PR #{}", n)).unwrap_or_default()
+        );
+    }
+    commands::state::restore(root, state, "recover", "origin", true)
+}
+
+/// Build a state for `source` from its chunk branches on origin, in stack
+/// order. A chunk whose branch contains the previous chunk's branch is stacked
+/// on it; its files are what it changes on top of that parent (or of `base`).
+pub fn discover(
+    root: &Path,
+    source: &str,
+    base: &str,
+    namespace: Option<&str>,
+    remote: forge::RemoteRepo,
+) -> Result<MergesState> {
+    let prefix = chunk_branch_prefix(source, namespace);
+    let mut found: Vec<(usize, String, String)> = git::remote_branches(root, &prefix)?
+        .into_iter()
+        .filter_map(|branch| {
+            let (n, name) = branch.strip_prefix(&prefix)?.split_once('-')?;
+            Some((n.parse().ok()?, name.to_string(), branch))
+        })
+        .filter(|(_, name, _)| !name.is_empty())
+        .collect();
+    if found.is_empty() {
+        bail!(
+            "No chunk branches found on origin matching '{}*'. Run `git fetch` first, or pass --source.",
+            prefix
+        );
+    }
+    found.sort_by_key(|(n, _, _)| *n);
+
+    let base_ref = if git::rev_parse(root, &format!("refs/remotes/origin/{}", base)).is_ok() {
+        format!("origin/{}", base)
+    } else {
+        base.to_string()
+    };
+
+    let mut chunks: Vec<Chunk> = vec![];
+    let mut stacked = false;
+    for (_, name, branch) in found {
+        let tip = format!("origin/{}", branch);
+        let parent = match chunks.last() {
+            Some(prev) if git::is_ancestor(root, &format!("origin/{}", prev.branch), &tip) => {
+                stacked = true;
+                format!("origin/{}", prev.branch)
+            }
+            _ => base_ref.clone(),
+        };
+        chunks.push(Chunk {
+            name,
+            branch,
+            files: git::diff_files(root, &parent, &tip)?,
+            description: None,
+            pr_number: None,
+            pr_url: None,
+            pushed_sha: None,
+            pr_title: None,
+            custom_commit_message: None,
+            custom_pr_title: None,
+            custom_pr_body: None,
+            skip_ci: false,
+            hold: false,
+            created_at: None,
+            last_pushed_at: None,
+            merged_at: None,
+            stack_comment_id: None,
+            routed: vec![],
+            milestone: None,
+            project_item_id: None,
+            project_status: None,
+        });
+    }
+
+    Ok(MergesState {
+        base_branch: base.to_string(),
+        source_branch: source.to_string(),
+        source_sha: None,
+        repo_owner: remote.owner,
+        repo_name: remote.repo,
+        forge: remote.kind,
+        forge_api_url: remote.api_url,
+        strategy: if stacked { Strategy::Stacked } else { Strategy::Independent },
+        use_worktrees: false,
+        exclude: vec![],
+        routes: vec![],
+        commit_prefix: None,
+        no_title_position: false,
+        max_subject_length: None,
+        max_title_length: None,
+        auto_depth: None,
+        root_files: None,
+        no_stack_comments: false,
+        branch_namespace: namespace.map(String::from),
+        autostash: false,
+        preserve_history: false,
+        squash_on_push: false,
+        sync_merge: false,
+        post_summary: false,
+        project: None,
+        milestone: None,
+        size_limits: Default::default(),
+        tracking_issue: None,
+        source_pr: None,
+        push_interrupted: false,
+        chunks,
+        merged: vec![],
+    })
+}
+
+/// Fill in the open PR of each chunk, best effort: without forge access the
+/// PRs are left blank and `merges push` picks them up later. A PR that
+/// targets the previous chunk's branch marks the stack as stacked.
+async fn find_prs(state: &mut MergesState) {
+    let Ok(forge) = forge::connect(state).await else {
+        warning!(
+            "Could not connect to {} — PRs not recovered; `merges push` will find them.",
+            state.forge
+        );
+        return;
+    };
+
+    for i in 0..state.chunks.len() {
+        let Ok(Some((number, url))) = forge.find_open_pr(&state.chunks[i].branch).await else { continue };
+        if i > 0 && forge.get_pr_base(number).await.is_ok_and(|b| b == state.chunks[i - 1].branch) {
+            state.strategy = Strategy::Stacked;
+        }
+        state.chunks[i].pr_number = Some(number);
+        state.chunks[i].pr_url = Some(url);
+    }
+}
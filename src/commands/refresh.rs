@@ -0,0 +1,94 @@
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::{autostash::Autostash, doctor, git, say, split, state::MergesState, warning};
+
+/// Compare the chunk branches with the current source branch, or with
+/// `rebuild`, reconstruct them from it (see `split::rebuild_chunks`). In
+/// classic mode a dirty working tree is refused unless `autostash` is set.
+pub fn run(rebuild: bool, autostash: bool) -> Result<()> {
+    let root = git::repo_root()?;
+    let state = MergesState::load(&root)?;
+
+    if state.chunks.is_empty() {
+        say!("No chunks defined yet. Run {} first.", "merges split".bold());
+        return Ok(());
+    }
+
+    if !rebuild {
+        return compare(&root, &state);
+    }
+
+    let stash = Autostash::begin(&root, &state, "refresh", autostash)?;
+    let report = stash.finish(split::rebuild_chunks(&root))?;
+
+    for (name, files) in &report.removed {
+        say!(
+            "{} Dropped from '{}' (no longer changed on the source): {}",
+            "·".dimmed(),
+            name.cyan(),
+            files.join(", ")
+        );
+    }
+    say!(
+        "{} Rebuilt {} chunk branch(es) from '{}'. Run {} to update the PRs.",
+        "✓".green().bold(),
+        state.chunks.len().to_string().yellow(),
+        state.source_branch.cyan(),
+        "merges push".bold()
+    );
+    if !report.unassigned.is_empty() {
+        say!(
+            "  {} changed file(s) are in no chunk — run {} to place them.",
+            report.unassigned.len().to_string().yellow(),
+            "merges assign".bold()
+        );
+    }
+    Ok(())
+}
+
+/// Report which chunk files no longer match the source branch.
+fn compare(root: &std::path::Path, state: &MergesState) -> Result<()> {
+    match doctor::source_divergence(root, state) {
+        Some(divergence) => warning!("{}", divergence),
+        None => say!(
+            "{} Source branch '{}' has not been rewritten since the split.",
+            "·".dimmed(),
+            state.source_branch.cyan()
+        ),
+    }
+
+    let (included, _) = split::changed_files(root, state)?;
+    let mut stale = 0;
+    for chunk in &state.chunks {
+        let differing = git::differing_files(root, &chunk.branch, &state.source_branch, &chunk.files)?;
+        if !differing.is_empty() {
+            stale += 1;
+            warning!(
+                "[{}] differs from the source: {}",
+                chunk.name.cyan(),
+                differing.join(", ")
+            );
+        }
+    }
+    let unassigned = included.iter().filter(|f| !state.chunks.iter().any(|c| c.files.contains(f))).count();
+    if unassigned > 0 {
+        say!(
+            "{} {} changed file(s) are in no chunk — run {} to place them.",
+            "·".dimmed(),
+            unassigned.to_string().yellow(),
+            "merges assign".bold()
+        );
+    }
+
+    if stale == 0 {
+        say!("{} Every chunk matches '{}'.", "✓".green().bold(), state.source_branch.cyan());
+    } else {
+        say!(
+            "\n  Run {} to rebuild the chunk branches from '{}' (commits added to them since the split are replaced).",
+            "merges refresh --rebuild".bold(),
+            state.source_branch
+        );
+    }
+    Ok(())
+}
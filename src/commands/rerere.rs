@@ -0,0 +1,86 @@
+//! Sharing recorded conflict resolutions. rerere keeps them in `rr-cache`
+//! under the common git directory, so every chunk worktree already reads and
+//! records into one cache; `export` and `import` carry that cache to other
+//! clones as a gzipped tarball, so a conflict is resolved once for everyone.
+
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::git;
+
+/// The rerere cache shared by the repository and all its worktrees.
+pub fn cache_dir(root: &Path) -> Result<PathBuf> {
+    Ok(git::common_dir(root)?.join("rr-cache"))
+}
+
+/// Conflict ids in `cache` that have a recorded resolution, sorted.
+pub fn resolutions(cache: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(cache) else { return vec![] };
+    let mut ids: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().join("postimage").is_file())
+        .filter_map(|e| e.file_name().into_string().ok())
+        .filter(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_hexdigit()))
+        .collect();
+    ids.sort();
+    ids
+}
+
+/// Write every recorded resolution to the tarball `file`; returns how many.
+pub fn export(root: &Path, file: &Path) -> Result<usize> {
+    let ids = resolutions(&cache_dir(root)?);
+    if ids.is_empty() {
+        bail!("No recorded conflict resolutions to export — rerere records one when you resolve a conflict and commit.");
+    }
+    let file = std::path::absolute(file)?;
+    let mut args = vec!["-czf".to_string(), file.to_string_lossy().into_owned()];
+    args.extend(ids.iter().map(|id| format!("rr-cache/{}", id)));
+    tar(&git::common_dir(root)?, &args)?;
+    Ok(ids.len())
+}
+
+/// Add the resolutions in the tarball `file` that this repository does not
+/// have yet, and turn rerere on so they are used; returns how many were added.
+/// Resolutions already recorded here are kept as they are.
+pub fn import(root: &Path, file: &Path) -> Result<usize> {
+    let file = std::path::absolute(file)?;
+    if !file.is_file() {
+        bail!("'{}' does not exist.", file.display());
+    }
+    let cache = cache_dir(root)?;
+    let staging = git::common_dir(root)?.join("merges-rerere-import");
+    let _ = std::fs::remove_dir_all(&staging);
+    std::fs::create_dir_all(&staging)?;
+
+    let result = (|| -> Result<usize> {
+        tar(&staging, &["-xzf".to_string(), file.to_string_lossy().into_owned()])?;
+        if !staging.join("rr-cache").is_dir() {
+            bail!("'{}' is not a rerere export (no rr-cache in it).", file.display());
+        }
+        std::fs::create_dir_all(&cache)?;
+        let mut added = 0;
+        for id in resolutions(&staging.join("rr-cache")) {
+            if cache.join(&id).exists() {
+                continue;
+            }
+            std::fs::rename(staging.join("rr-cache").join(&id), cache.join(&id))
+                .with_context(|| format!("Failed to add resolution {}", id))?;
+            added += 1;
+        }
+        Ok(added)
+    })();
+    let _ = std::fs::remove_dir_all(&staging);
+
+    let added = result?;
+    git::enable_rerere(root)?;
+    Ok(added)
+}
+
+fn tar(dir: &Path, args: &[String]) -> Result<()> {
+    let output = Command::new("tar").args(args).current_dir(dir).output().context("Failed to run `tar`")?;
+    if !output.status.success() {
+        bail!("tar failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
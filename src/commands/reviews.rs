@@ -0,0 +1,76 @@
+//! `merges reviews`: the review comments still waiting for an answer across
+//! the whole stack, grouped by chunk and file, so the feedback on every
+//! chunk PR can be worked through as one list.
+
+use anyhow::Result;
+use colored::Colorize;
+use serde::Serialize;
+use std::path::Path;
+
+use crate::{
+    forge::{self, Forge, ReviewThread},
+    git, say,
+    state::MergesState,
+};
+
+/// The unresolved review threads on one chunk's PR.
+#[derive(Debug, Serialize)]
+pub struct ChunkReviews {
+    pub chunk: String,
+    pub pr_number: u64,
+    pub pr_url: Option<String>,
+    /// Ordered by file, then line.
+    pub threads: Vec<ReviewThread>,
+}
+
+pub async fn run() -> Result<()> {
+    let root = git::repo_root()?;
+    let forge = forge::connect(&MergesState::load(&root)?).await?;
+    print(&collect(&root, forge.as_ref()).await?);
+    Ok(())
+}
+
+/// Unresolved review threads on every chunk PR, in stack order. Chunks
+/// without a PR or without open threads are left out.
+pub async fn collect(root: &Path, forge: &dyn Forge) -> Result<Vec<ChunkReviews>> {
+    let state = MergesState::load(root)?;
+    let mut reviews = vec![];
+    for chunk in &state.chunks {
+        let Some(pr_number) = chunk.pr_number else { continue };
+        let mut threads = forge.unresolved_review_threads(pr_number).await?;
+        if threads.is_empty() {
+            continue;
+        }
+        threads.sort_by(|a, b| a.path.cmp(&b.path).then(a.line.cmp(&b.line)));
+        reviews.push(ChunkReviews { chunk: chunk.name.clone(), pr_number, pr_url: chunk.pr_url.clone(), threads });
+    }
+    Ok(reviews)
+}
+
+fn print(reviews: &[ChunkReviews]) {
+    if reviews.is_empty() {
+        say!("{} No unresolved review comments on the chunk PRs.", "✓".green());
+        return;
+    }
+    for chunk in reviews {
+        say!(
+            "\n{} PR #{} — {} unresolved thread(s)",
+            chunk.chunk.cyan().bold(),
+            chunk.pr_number,
+            chunk.threads.len()
+        );
+        let mut file = None;
+        for thread in &chunk.threads {
+            if file != Some(&thread.path) {
+                say!("  {}", thread.path.bold());
+                file = Some(&thread.path);
+            }
+            let at = thread.line.map_or_else(|| "outdated".to_string(), |line| format!("line {}", line));
+            let url = thread.comments.first().map(|c| c.url.as_str()).unwrap_or_default();
+            say!("    {} {}", at.yellow(), url.dimmed());
+            for comment in &thread.comments {
+                say!("      {}: {}", comment.author.bold(), comment.body.trim().replace('\n', "\n        "));
+            }
+        }
+    }
+}
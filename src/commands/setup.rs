@@ -0,0 +1,221 @@
+//! `merges setup`: a guided first run. Checks the tools and credentials merges
+//! relies on, offers to create `.merges.json` and to register the MCP server
+//! with an LLM client, and reports every step the way `merges doctor` does.
+
+use anyhow::Result;
+use colored::Colorize;
+use dialoguer::{Confirm, Select};
+use serde::Serialize;
+use std::process::Command;
+
+use crate::{
+    commands::init::{self, InitOptions},
+    config,
+    forge::{self, Access},
+    git,
+    mcp::install::{self, Client},
+    prompt, say,
+    state::{ForgeKind, MergesState},
+};
+
+/// Outcome of one setup step.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Ok,
+    /// Not needed to get going, or skipped; the detail says what to do.
+    Warning,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SetupCheck {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+/// Every step setup went through, in order.
+#[derive(Debug, Default)]
+pub struct SetupReport {
+    pub checks: Vec<SetupCheck>,
+}
+
+impl SetupReport {
+    pub fn all_ok(&self) -> bool {
+        self.checks.iter().all(|c| c.status != CheckStatus::Failed)
+    }
+
+    pub fn failed(&self) -> usize {
+        self.checks.iter().filter(|c| c.status == CheckStatus::Failed).count()
+    }
+
+    /// Print a step as it finishes and keep it for the report.
+    fn record(&mut self, name: &'static str, status: CheckStatus, detail: impl Into<String>) {
+        let detail = detail.into();
+        let mark = match status {
+            CheckStatus::Ok => "✓".green().bold(),
+            CheckStatus::Warning => "!".yellow().bold(),
+            CheckStatus::Failed => "✗".red().bold(),
+        };
+        say!("{} {:<12} {}", mark, name, detail);
+        self.checks.push(SetupCheck { name, status, detail });
+    }
+}
+
+/// Walk through first-run setup. Without a terminal nothing is offered: the
+/// checks run and the steps that would have asked are reported as skipped.
+pub async fn run() -> Result<SetupReport> {
+    let interactive = prompt::allowed();
+    let mut report = SetupReport::default();
+
+    // 1. Tools
+    match tool_version("git") {
+        Some(version) => report.record("git", CheckStatus::Ok, version),
+        None => {
+            report.record("git", CheckStatus::Failed, "not found on PATH — install git first");
+            return Ok(report);
+        }
+    }
+    match tool_version("gh") {
+        Some(version) => report.record("gh", CheckStatus::Ok, version),
+        None => report.record(
+            "gh",
+            CheckStatus::Warning,
+            "not installed — optional; GITHUB_TOKEN or a token in git's credential helper work too",
+        ),
+    }
+
+    // 2. Repository and forge
+    let root = match git::repo_root() {
+        Ok(root) => {
+            report.record("repository", CheckStatus::Ok, root.display().to_string());
+            root
+        }
+        Err(e) => {
+            let detail = format!("{} — run `merges setup` inside the repository to split", e);
+            report.record("repository", CheckStatus::Failed, detail);
+            return Ok(report);
+        }
+    };
+    let state = MergesState::load(&root).ok();
+    let kind = match &state {
+        Some(state) => Some(state.forge),
+        None => match git::remote_url(&root).and_then(|url| forge::detect(&url, None)) {
+            Ok(remote) => {
+                let detail = format!("{}/{} on {}", remote.owner, remote.repo, remote.kind);
+                report.record("remote", CheckStatus::Ok, detail);
+                Some(remote.kind)
+            }
+            Err(e) => {
+                report.record("remote", CheckStatus::Warning, format!("{} — `merges init --forge` can name it", e));
+                None
+            }
+        },
+    };
+
+    // 3. Credentials
+    if let Some(kind) = kind {
+        match credentials(kind) {
+            Ok(found) => report.record("credentials", CheckStatus::Ok, found),
+            Err(e) => report.record("credentials", CheckStatus::Failed, e.to_string()),
+        }
+    }
+
+    // 4. Config file
+    if state.is_some() {
+        report.record("config", CheckStatus::Ok, ".merges.json found");
+    } else if interactive
+        && Confirm::new()
+            .with_prompt(format!("No .merges.json yet — run `merges init` on '{}' now?", git::current_branch(&root)?))
+            .default(true)
+            .interact()?
+    {
+        match init::run(InitOptions::default()) {
+            Ok(()) => report.record("config", CheckStatus::Ok, ".merges.json created"),
+            Err(e) => report.record("config", CheckStatus::Failed, format!("{:#}", e)),
+        }
+    } else {
+        let detail = "no .merges.json — run `merges init` on the branch you want to split";
+        report.record("config", CheckStatus::Warning, detail);
+    }
+
+    // 5. API access
+    match MergesState::load(&root) {
+        Ok(state) => match api_access(&state).await {
+            Ok(missing) if missing.is_empty() => {
+                let detail = format!("{}/{} reachable", state.repo_owner, state.repo_name);
+                report.record("API access", CheckStatus::Ok, detail)
+            }
+            Ok(missing) => report.record("API access", CheckStatus::Warning, missing.join("; ")),
+            Err(e) => report.record("API access", CheckStatus::Failed, format!("{:#}", e)),
+        },
+        Err(_) => report.record("API access", CheckStatus::Warning, "not tested — needs .merges.json"),
+    }
+
+    // 6. MCP client
+    let how = "`merges help mcp` shows how to register it";
+    if interactive {
+        let mut items: Vec<String> = Client::ALL.iter().map(|c| c.to_string()).collect();
+        items.push("Skip".to_string());
+        let choice = Select::new()
+            .with_prompt("Register the merges MCP server with an LLM client?")
+            .items(&items)
+            .default(items.len() - 1)
+            .interact()?;
+        match Client::ALL.get(choice) {
+            Some(&client) => match install::install(client, &root) {
+                Ok(path) => {
+                    report.record("MCP", CheckStatus::Ok, format!("{} configured in {}", client, path.display()))
+                }
+                Err(e) => report.record("MCP", CheckStatus::Failed, format!("{:#}", e)),
+            },
+            None => report.record("MCP", CheckStatus::Warning, format!("skipped — {}", how)),
+        }
+    } else {
+        report.record("MCP", CheckStatus::Warning, format!("skipped without a terminal — {}", how));
+    }
+
+    if report.all_ok() {
+        say!("\n{} Ready. Next: run {} to assign files to chunks.", "✓".green().bold(), "merges split".bold());
+    }
+    Ok(report)
+}
+
+/// First line of `<tool> --version`, or `None` when it does not run.
+fn tool_version(tool: &str) -> Option<String> {
+    let output = Command::new(tool).arg("--version").output().ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let line = stdout.lines().next()?.trim();
+    (output.status.success() && !line.is_empty()).then(|| line.to_string())
+}
+
+/// Describe the credentials found for `kind`, or why there are none.
+fn credentials(kind: ForgeKind) -> Result<String> {
+    Ok(match kind {
+        ForgeKind::Github => match config::github_credentials()? {
+            config::GithubCredentials::App { app_id, .. } => format!("GitHub App {}", app_id),
+            config::GithubCredentials::Token(_) => "GitHub token found".to_string(),
+        },
+        ForgeKind::Bitbucket | ForgeKind::BitbucketServer => {
+            config::bitbucket_credentials()?;
+            "Bitbucket credentials found".to_string()
+        }
+        ForgeKind::Gitea => {
+            config::gitea_token()?;
+            "GITEA_TOKEN set".to_string()
+        }
+        ForgeKind::AzureDevops => {
+            config::azure_devops_token()?;
+            "AZURE_DEVOPS_EXT_PAT set".to_string()
+        }
+    })
+}
+
+/// Connect to the forge and make a read-only call, then check what the
+/// credentials may do. Returns what they lack.
+async fn api_access(state: &MergesState) -> Result<Vec<String>> {
+    let forge = forge::connect(state).await?;
+    forge.find_open_pr(&state.source_branch).await?;
+    forge.missing_permissions(&[Access::PullRequests, Access::Issues]).await
+}
@@ -0,0 +1,63 @@
+//! `merges path` / `merges shell`: get into a chunk's worktree without typing
+//! its `.git/merges-worktrees/…` path. `path` prints it for shell aliases
+//! (`mcd() { cd "$(merges path "$1")"; }`); `shell` starts a subshell there
+//! with the chunk's environment, as `merges exec` gives its commands.
+
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus};
+
+use crate::{commands::exec::chunk_env, git, say, state::MergesState};
+
+/// The worktree chunk `name` is checked out in.
+pub fn path(root: &Path, name: &str) -> Result<PathBuf> {
+    let state = MergesState::load(root)?;
+    worktree(root, &state, name).map(|(_, dir)| dir)
+}
+
+/// Start the user's shell (`$SHELL`, else `sh`; `%COMSPEC%` on Windows) in
+/// chunk `name`'s worktree with the [`chunk_env`] variables set, and wait
+/// for it to exit.
+pub fn run(root: &Path, name: &str) -> Result<ExitStatus> {
+    let state = MergesState::load(root)?;
+    let (i, dir) = worktree(root, &state, name)?;
+    let program = shell_program();
+    say!(
+        "{} [{}] {} in {} — exit to return.",
+        "→".blue().bold(),
+        name.cyan(),
+        program,
+        dir.display().to_string().dimmed()
+    );
+    Command::new(&program)
+        .current_dir(&dir)
+        .envs(chunk_env(&state, i, &dir))
+        .status()
+        .with_context(|| format!("Failed to start `{}`", program))
+}
+
+/// The index of chunk `name` and its worktree, which must exist.
+fn worktree(root: &Path, state: &MergesState, name: &str) -> Result<(usize, PathBuf)> {
+    let Some(i) = state.chunks.iter().position(|c| c.name == name) else {
+        bail!("No chunk named '{}'", name);
+    };
+    let branch = &state.chunks[i].branch;
+    if !state.use_worktrees {
+        bail!("Worktrees are off, so '{}' has none; its branch is {} in this checkout.", name, branch);
+    }
+    let dir = git::worktree_path(root, branch);
+    if !dir.is_dir() {
+        bail!("The worktree of '{}' is missing at {}; see `merges doctor`.", name, dir.display());
+    }
+    Ok((i, dir))
+}
+
+fn shell_program() -> String {
+    let var = if cfg!(windows) { "COMSPEC" } else { "SHELL" };
+    match std::env::var(var) {
+        Ok(shell) if !shell.is_empty() => shell,
+        _ if cfg!(windows) => "cmd.exe".to_string(),
+        _ => "sh".to_string(),
+    }
+}
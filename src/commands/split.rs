@@ -1,63 +1,200 @@
 use anyhow::{bail, Result};
 use colored::Colorize;
-use dialoguer::{theme::ColorfulTheme, Confirm, Input, MultiSelect};
-use indicatif::{ProgressBar, ProgressStyle};
+use dialoguer::{theme::ColorfulTheme, Confirm, Input, MultiSelect, Select};
 
-use crate::{git, split::{auto_group_files, ChunkPlan}, state::MergesState};
+use crate::{
+    autostash::Autostash,
+    git, imports, pathspec,
+    preset::Preset,
+    say,
+    split::{auto_group, merge_chunks, move_files, ChunkPlan, Grouping},
+    state::MergesState,
+    warning,
+};
+
+/// Flags for `merges split`.
+#[derive(Debug, Clone, Default)]
+pub struct SplitOptions {
+    /// JSON chunk plan, e.g. `[{"name":"models","files":["src/models/user.rs"]}]`.
+    pub plan: Option<String>,
+    pub auto: bool,
+    pub by: Grouping,
+    /// Adjust the auto groups to the import graph between the changed files.
+    pub imports: bool,
+    /// Directory level to auto-group on; falls back to the `auto_depth` setting.
+    pub depth: Option<usize>,
+    /// Print the auto grouping without creating any chunks.
+    pub preview: bool,
+    /// Let the user rename, merge, and move files between the auto groups
+    /// before they are applied.
+    pub edit: bool,
+    pub exclude: Vec<String>,
+    /// Split preset from `merges.preset.<name>` to assign files with first;
+    /// what it leaves goes to `auto` or the interactive TUI.
+    pub preset: Option<String>,
+    pub autostash: bool,
+}
 
 /// Entry point for `merges split`.
 ///
-/// - `plan_json`: if `Some`, parse chunk assignments from JSON and apply non-interactively.
-///   Format: `[{"name":"models","files":["src/models/user.rs"]}]`
-/// - `auto`: if `true`, automatically group files by directory structure.
+/// - `plan`: if `Some`, parse chunk assignments from JSON and apply non-interactively.
+/// - `auto`: if `true`, automatically group files the way `by` says (by
+///   directory structure unless told otherwise). With `imports`, the groups
+///   are then adjusted to the import graph between the changed files. With
+///   `preview`, the groups are only printed; with `edit`, the user adjusts
+///   them before they are applied.
 /// - Otherwise, fall through to the interactive TUI.
-pub fn run(plan_json: Option<String>, auto: bool) -> Result<()> {
+///
+/// With a `preset`, the files its patterns match become its chunks first;
+/// only the rest are auto-grouped or offered in the TUI.
+///
+/// `exclude` patterns are added to the state's exclude list before splitting
+/// (for a preview, only for that run).
+/// In classic mode a dirty working tree is refused unless `autostash` is set.
+pub fn run(opts: SplitOptions) -> Result<()> {
+    let root = git::repo_root()?;
+    if opts.preview {
+        // Nothing is checked out, so uncommitted changes can stay where they are.
+        return split(opts);
+    }
+    let stash = Autostash::begin(&root, &MergesState::load(&root)?, "split", opts.autostash)?;
+    stash.finish(split(opts))
+}
+
+fn split(opts: SplitOptions) -> Result<()> {
+    let SplitOptions { plan: plan_json, auto, by, imports: with_imports, depth, preview, edit, exclude, preset, .. } =
+        opts;
     let root = git::repo_root()?;
-    let state = MergesState::load(&root)?;
+    let mut state = MergesState::load(&root)?;
 
-    let all_files = git::changed_files(&root, &state.base_branch)?;
+    if !exclude.is_empty() {
+        pathspec::validate_patterns(&exclude)?;
+        for pattern in exclude {
+            if !state.exclude.contains(&pattern) {
+                state.exclude.push(pattern);
+            }
+        }
+        if !preview {
+            state.save(&root)?;
+        }
+    }
+
+    let (all_files, excluded) = crate::split::changed_files(&root, &state)?;
     if all_files.is_empty() {
         bail!(
-            "No changed files found between HEAD and '{}'",
-            state.base_branch
+            "No changed files found between HEAD and '{}'{}",
+            state.base_branch,
+            if excluded.is_empty() { String::new() } else { format!(" ({} excluded)", excluded.len()) }
         );
     }
 
-    println!(
+    say!(
         "{} Found {} changed file(s) on '{}' vs '{}'",
         "→".blue().bold(),
         all_files.len().to_string().yellow(),
         state.source_branch.cyan(),
         state.base_branch.cyan()
     );
+    if !excluded.is_empty() {
+        say!(
+            "{} {} file(s) excluded by {}:",
+            "·".dimmed(),
+            excluded.len().to_string().yellow(),
+            state.exclude.join(", ")
+        );
+        for f in &excluded {
+            say!("  {}", f.dimmed());
+        }
+    }
+
+    // ── Preset ────────────────────────────────────────────────────────────
+    let (preset_plan, all_files) = match &preset {
+        Some(name) => {
+            let preset = Preset::load(&root, name)?;
+            let unassigned: Vec<String> =
+                all_files.into_iter().filter(|f| !state.chunks.iter().any(|c| c.files.contains(f))).collect();
+            let (plan, rest) = preset.assign(&unassigned);
+            say!(
+                "{} Preset '{}' matched {} file(s) into {} chunk(s); {} left over",
+                "→".blue().bold(),
+                name.cyan(),
+                (unassigned.len() - rest.len()).to_string().yellow(),
+                plan.len().to_string().yellow(),
+                rest.len().to_string().yellow()
+            );
+            (plan, rest)
+        }
+        None => (vec![], all_files),
+    };
 
     if auto {
         // ── Auto-group path ───────────────────────────────────────────────
-        let plan = auto_group_files(&all_files);
-        println!(
-            "{} Auto-grouped into {} chunk(s):",
+        let mut plan = auto_group(&root, &state, &all_files, by, depth.or(state.auto_depth))?;
+        let mut cross = vec![];
+        if with_imports {
+            let edges = imports::graph(&root, &state.source_branch, &all_files);
+            (plan, cross) = imports::regroup(plan, &edges);
+        }
+        plan = after_preset(preset_plan, plan);
+        say!(
+            "{} {} by {}{} into {} chunk(s):",
             "→".blue().bold(),
+            if preset.is_some() { "Took the preset's chunks and auto-grouped the rest" } else { "Auto-grouped" },
+            by,
+            if with_imports { " and imports" } else { "" },
             plan.len().to_string().yellow()
         );
         for (i, chunk) in plan.iter().enumerate() {
-            println!(
+            say!(
                 "  {}. {} ({} files)",
                 i + 1,
                 chunk.name.cyan(),
                 chunk.files.len().to_string().yellow()
             );
         }
+        if !cross.is_empty() {
+            warning!(
+                "{} import(s) cross chunks — the chunks are ordered so a stacked push satisfies them:",
+                cross.len().to_string().yellow()
+            );
+            for import in &cross {
+                say!(
+                    "  {} → {}  {}",
+                    import.from_chunk.cyan(),
+                    import.to_chunk.cyan(),
+                    format!("({} imports {})", import.from_file, import.to_file).dimmed()
+                );
+            }
+        }
 
-        let pb = ProgressBar::new(plan.len() as u64);
-        pb.set_style(ProgressStyle::default_bar()
-            .template("{bar:40.cyan/blue} {pos}/{len} chunks {msg}")
-            .unwrap());
+        if preview {
+            print_files(&plan);
+            say!(
+                "\n{} Preview only — nothing was created. Run without {} to apply, add {} to adjust \
+                 the chunks first, or edit this plan and pass it to {}:",
+                "·".dimmed(),
+                "--preview".bold(),
+                "--edit".bold(),
+                "--plan".bold()
+            );
+            println!("{}", serde_json::to_string(&plan)?);
+            return Ok(());
+        }
+        if edit {
+            crate::prompt::require("how to adjust the chunks", "Drop --edit, or use --preview and pass the adjusted plan to --plan.")?;
+            match adjust_plan(plan)? {
+                Some(adjusted) => plan = adjusted,
+                None => {
+                    warning!("Aborted — no chunks created.");
+                    return Ok(());
+                }
+            }
+        }
 
         crate::split::apply_plan(&root, plan)?;
-        pb.finish_with_message("done");
 
         let state = MergesState::load(&root)?;
-        println!(
+        say!(
             "{} {} chunk(s) created. Run {} to push.",
             "✓".green().bold(),
             state.chunks.len().to_string().yellow(),
@@ -71,18 +208,13 @@ pub fn run(plan_json: Option<String>, auto: bool) -> Result<()> {
         let plan: Vec<ChunkPlan> = serde_json::from_str(&json)
             .map_err(|e| anyhow::anyhow!("Invalid --plan JSON: {}", e))?;
 
-        let pb = ProgressBar::new(plan.len() as u64);
-        pb.set_style(
-            ProgressStyle::default_bar()
-                .template("{bar:40.cyan/blue} {pos}/{len} chunks {msg}")
-                .unwrap(),
-        );
-
-        crate::split::apply_plan(&root, plan)?;
-        pb.finish_with_message("done");
+        let expansions = crate::split::apply_plan(&root, plan)?;
 
+        for expansion in &expansions {
+            say!("{} {}", "·".dimmed(), expansion.describe());
+        }
         let state = MergesState::load(&root)?;
-        println!(
+        say!(
             "{} {} chunk(s) created. Run {} to push.",
             "✓".green().bold(),
             state.chunks.len().to_string().yellow(),
@@ -90,12 +222,134 @@ pub fn run(plan_json: Option<String>, auto: bool) -> Result<()> {
         );
     } else {
         // ── Interactive TUI path ──────────────────────────────────────────
+        if preset.is_none() {
+            crate::prompt::require("which files go in each chunk", "Pass --plan '<json>' or --auto.")?;
+            return run_interactive(&root, &state, &all_files);
+        }
+        if !preset_plan.is_empty() {
+            crate::split::apply_plan(&root, preset_plan)?;
+        }
+        let state = MergesState::load(&root)?;
+        if all_files.is_empty() || !crate::prompt::allowed() {
+            for f in &all_files {
+                say!("  {}", f.dimmed());
+            }
+            say!(
+                "{} {} chunk(s) defined{}. Run {} to push.",
+                "✓".green().bold(),
+                state.chunks.len().to_string().yellow(),
+                if all_files.is_empty() {
+                    String::new()
+                } else {
+                    format!("; assign the {} file(s) above with `merges assign`", all_files.len())
+                },
+                "merges push".bold()
+            );
+            return Ok(());
+        }
         run_interactive(&root, &state, &all_files)?;
     }
 
     Ok(())
 }
 
+/// `preset` chunks followed by the `auto` ones, an auto chunk named like a
+/// preset chunk joining it instead.
+fn after_preset(mut preset: Vec<ChunkPlan>, auto: Vec<ChunkPlan>) -> Vec<ChunkPlan> {
+    for chunk in auto {
+        match preset.iter_mut().find(|c| c.name == chunk.name) {
+            Some(existing) => existing.files.extend(chunk.files),
+            None => preset.push(chunk),
+        }
+    }
+    preset
+}
+
+/// Print each chunk of `plan` with its files underneath.
+fn print_files(plan: &[ChunkPlan]) {
+    for chunk in plan {
+        say!("\n  {}", chunk.name.cyan().bold());
+        for f in &chunk.files {
+            say!("    {}", f.dimmed());
+        }
+    }
+}
+
+/// Let the user rename chunks, merge them, and move files between them until
+/// they choose to apply the plan. Returns `None` if they abort.
+fn adjust_plan(mut plan: Vec<ChunkPlan>) -> Result<Option<Vec<ChunkPlan>>> {
+    let theme = ColorfulTheme::default();
+    loop {
+        let names: Vec<String> = plan.iter().map(|c| format!("{} ({} files)", c.name, c.files.len())).collect();
+        let action = Select::with_theme(&theme)
+            .with_prompt(format!("{} chunk(s) — adjust or apply?", plan.len()))
+            .items(&["Apply this plan", "Rename a chunk", "Merge chunks", "Move files to another chunk", "Show files", "Abort"])
+            .default(0)
+            .interact()?;
+
+        match action {
+            0 => return Ok(Some(plan)),
+            1 => {
+                let i = Select::with_theme(&theme).with_prompt("Chunk to rename").items(&names).interact()?;
+                let name: String = Input::with_theme(&theme)
+                    .with_prompt("New name")
+                    .with_initial_text(plan[i].name.clone())
+                    .interact_text()?;
+                if plan.iter().enumerate().any(|(j, c)| j != i && c.name == name) {
+                    warning!("There is already a chunk called '{}'.", name);
+                    continue;
+                }
+                plan[i].name = name;
+            }
+            2 => {
+                let picked = MultiSelect::with_theme(&theme)
+                    .with_prompt("Chunks to merge (Space = toggle, Enter = confirm)")
+                    .items(&names)
+                    .interact()?;
+                if picked.len() < 2 {
+                    warning!("Pick at least two chunks to merge.");
+                    continue;
+                }
+                let default = picked.iter().map(|&i| plan[i].name.as_str()).collect::<Vec<_>>().join("-and-");
+                let name: String = Input::with_theme(&theme)
+                    .with_prompt("Name of the merged chunk")
+                    .with_initial_text(default)
+                    .interact_text()?;
+                merge_chunks(&mut plan, &picked, name);
+            }
+            3 => {
+                let from = Select::with_theme(&theme).with_prompt("Move files from").items(&names).interact()?;
+                let picked = MultiSelect::with_theme(&theme)
+                    .with_prompt("Files to move (Space = toggle, Enter = confirm)")
+                    .items(&plan[from].files)
+                    .interact()?;
+                if picked.is_empty() {
+                    continue;
+                }
+                let files: Vec<String> = picked.iter().map(|&i| plan[from].files[i].clone()).collect();
+
+                let mut targets = names.clone();
+                targets.push("New chunk…".to_string());
+                let to = Select::with_theme(&theme).with_prompt("Move them to").items(&targets).interact()?;
+                if to == plan.len() {
+                    let name: String = Input::with_theme(&theme).with_prompt("New chunk name").interact_text()?;
+                    if plan.iter().any(|c| c.name == name) {
+                        warning!("There is already a chunk called '{}'.", name);
+                        continue;
+                    }
+                    plan.push(ChunkPlan { name, ..Default::default() });
+                }
+                move_files(&mut plan, &files, to);
+            }
+            4 => {
+                print_files(&plan);
+                println!();
+            }
+            _ => return Ok(None),
+        }
+    }
+}
+
 fn run_interactive(
     root: &std::path::Path,
     state: &MergesState,
@@ -117,11 +371,11 @@ fn run_interactive(
             .collect();
 
         if remaining.is_empty() {
-            println!("{} All files have been assigned to chunks.", "✓".green().bold());
+            say!("{} All files have been assigned to chunks.", "✓".green().bold());
             break;
         }
 
-        println!(
+        say!(
             "\n{} remaining file(s) unassigned. Define a new chunk (or Ctrl-C to stop):",
             remaining.len().to_string().yellow()
         );
@@ -148,7 +402,7 @@ fn run_interactive(
 
         let selected_files: Vec<String> = selections.iter().map(|&i| remaining[i].clone()).collect();
         assigned.extend(selected_files.clone());
-        new_plans.push(ChunkPlan { name: chunk_name, files: selected_files });
+        new_plans.push(ChunkPlan { name: chunk_name, files: selected_files, ..Default::default() });
 
         let more = Confirm::new()
             .with_prompt("Add another chunk?")
@@ -160,7 +414,7 @@ fn run_interactive(
     }
 
     if new_plans.is_empty() {
-        println!("{} No new chunks defined.", "!".yellow().bold());
+        warning!("No new chunks defined.");
         return Ok(());
     }
 
@@ -169,18 +423,18 @@ fn run_interactive(
 
     let unassigned: Vec<_> = all_files.iter().filter(|f| !assigned.contains(f)).collect();
     if !unassigned.is_empty() {
-        println!(
+        say!(
             "\n{} {} file(s) not assigned to any chunk:",
             "!".yellow().bold(),
             unassigned.len()
         );
         for f in &unassigned {
-            println!("  {}", f.dimmed());
+            say!("  {}", f.dimmed());
         }
     }
 
     let final_state = MergesState::load(root)?;
-    println!(
+    say!(
         "\n{} {} chunk(s) defined. Run {} to push.",
         "✓".green().bold(),
         final_state.chunks.len().to_string().yellow(),
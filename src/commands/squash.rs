@@ -0,0 +1,114 @@
+use anyhow::{bail, Result};
+use colored::Colorize;
+
+use crate::{autostash::Autostash, git, journal, say, split, state::MergesState};
+
+/// Squash the named chunks — every chunk when `chunks` is empty — into one
+/// commit each, returning the names of the chunks that were rewritten. In
+/// classic mode a dirty working tree is refused unless `autostash` is set.
+pub fn run(root: &std::path::Path, chunks: &[String], autostash: bool) -> Result<Vec<String>> {
+    let stash = Autostash::begin(root, &MergesState::load(root)?, "squash", autostash)?;
+    stash.finish(squash(root, chunks))
+}
+
+/// Rewrite each selected chunk branch with more than one commit of its own
+/// into a single commit holding the same tree, with [`squash_message`] as its
+/// message. Chunk branches built on a rewritten one are rebased onto the new
+/// commit so the stack stays connected.
+pub fn squash(root: &std::path::Path, only: &[String]) -> Result<Vec<String>> {
+    let state = MergesState::load(root)?;
+    if let Some(unknown) = only.iter().find(|name| !state.chunks.iter().any(|c| c.name == **name)) {
+        bail!("No chunk named '{}'", unknown);
+    }
+    let current = git::current_branch(root)?;
+
+    let mut squashed = vec![];
+    for i in 0..state.chunks.len() {
+        let chunk = &state.chunks[i];
+        if !only.is_empty() && !only.contains(&chunk.name) {
+            continue;
+        }
+        let fork = split::fork_point(root, &state, i)?;
+        let subjects = git::commit_subjects(root, &fork, &chunk.branch)?;
+        if subjects.len() < 2 {
+            continue;
+        }
+
+        let new = git::squash_commit(root, &chunk.branch, &fork, &squash_message(&state, i, &subjects))?;
+        replace_tip(root, &state, i, &current, &new)?;
+
+        say!("{} Squashed {} commits on '{}'", "✓".green().bold(), subjects.len(), chunk.name.cyan());
+        squashed.push(chunk.clone());
+    }
+
+    if squashed.is_empty() {
+        say!("{} Every chunk is already a single commit — nothing to squash.", "·".dimmed());
+    } else {
+        journal::record(root, "squash", &format!("{} chunk(s) squashed", squashed.len()), &squashed)?;
+    }
+    Ok(squashed.into_iter().map(|c| c.name).collect())
+}
+
+/// Point chunk `i`'s branch at the commit `new`, and rebase the chunks stacked
+/// on it — which still carry its old commits — onto it. In classic mode the
+/// user is put back on `current` afterwards.
+pub fn replace_tip(root: &std::path::Path, state: &MergesState, i: usize, current: &str, new: &str) -> Result<()> {
+    let old = git::rev_parse(root, &state.chunks[i].branch)?;
+    move_branch(root, state, &state.chunks[i].branch, current, new)?;
+
+    let (mut below_old, mut below_new) = (old, new.to_string());
+    for above in &state.chunks[i + 1..] {
+        if !git::is_ancestor(root, &below_old, &above.branch) {
+            break;
+        }
+        let above_old = git::rev_parse(root, &above.branch)?;
+        let dir = if state.use_worktrees {
+            git::worktree_path(root, &above.branch)
+        } else {
+            git::checkout(root, &above.branch)?;
+            root.to_path_buf()
+        };
+        if let Err(e) = git::rebase_onto(&dir, &below_new, &below_old) {
+            let _ = git::rebase_abort(&dir);
+            return Err(e);
+        }
+        below_new = git::rev_parse(root, &above.branch)?;
+        below_old = above_old;
+    }
+    if !state.use_worktrees && git::current_branch(root)? != current {
+        git::checkout(root, current)?;
+    }
+    Ok(())
+}
+
+/// Point `branch` at `sha`, resetting the working tree where it is checked out.
+fn move_branch(root: &std::path::Path, state: &MergesState, branch: &str, current: &str, sha: &str) -> Result<()> {
+    if state.use_worktrees {
+        git::reset_hard(&git::worktree_path(root, branch), sha)
+    } else if branch == current {
+        git::reset_hard(root, sha)
+    } else {
+        git::update_ref(root, &format!("refs/heads/{}", branch), sha)
+    }
+}
+
+/// Message for chunk `i` squashed from commits with `subjects`: the chunk's
+/// usual commit message, then a list of the other subjects so what the
+/// fixups were about is not lost.
+pub fn squash_message(state: &MergesState, i: usize, subjects: &[String]) -> String {
+    let chunk = &state.chunks[i];
+    let message =
+        split::commit_message(state, i + 1, &chunk.name, &chunk.files, chunk.custom_commit_message.as_deref());
+    let subject = message.lines().next().unwrap_or_default();
+    let mut folded: Vec<&str> = vec![];
+    for s in subjects.iter().map(String::as_str).filter(|s| *s != subject) {
+        if !folded.contains(&s) {
+            folded.push(s);
+        }
+    }
+    if folded.is_empty() {
+        return message;
+    }
+    let list: Vec<String> = folded.iter().map(|s| format!("- {}", s)).collect();
+    format!("{}\n\nSquashed commits:\n{}", message, list.join("\n"))
+}
@@ -0,0 +1,212 @@
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::{
+    forge, git, journal, say,
+    state::{MergesState, STATE_FILE},
+};
+
+/// Ref that `merges state push` / `merges state pull` share the state
+/// through. It points at a commit holding `.merges.json`, so the state
+/// travels with `origin` without being committed to any branch.
+pub const STATE_REF: &str = "refs/merges/state";
+
+/// Where a branch named in an imported state can be found.
+#[derive(Debug, PartialEq)]
+enum BranchSource {
+    Local,
+    /// Only `origin/<branch>` exists; a local tracking branch will be created.
+    Remote,
+    Missing,
+}
+
+fn branch_source(root: &Path, branch: &str) -> BranchSource {
+    if git::rev_parse(root, &format!("refs/heads/{}", branch)).is_ok() {
+        BranchSource::Local
+    } else if git::rev_parse(root, &format!("refs/remotes/origin/{}", branch)).is_ok() {
+        BranchSource::Remote
+    } else {
+        BranchSource::Missing
+    }
+}
+
+/// The current `.merges.json`, pretty-printed for `merges state export`.
+pub fn export(root: &Path) -> Result<String> {
+    let state = MergesState::load(root)?;
+    Ok(serde_json::to_string_pretty(&state)?)
+}
+
+/// Check an imported state against the repository at `root`: it must describe
+/// the repo `origin` points at, every branch it names must exist locally or
+/// on `origin`, and no chunk name, branch, or file may appear twice.
+/// Returns one human-readable problem per failed check.
+pub fn validate(root: &Path, state: &MergesState) -> Vec<String> {
+    let mut problems = vec![];
+
+    if let Ok(url) = git::remote_url(root)
+        && let Ok(remote) = forge::detect(&url, Some(state.forge))
+        && (remote.owner.as_str(), remote.repo.as_str()) != (state.repo_owner.as_str(), state.repo_name.as_str())
+    {
+        problems.push(format!(
+            "State is for {}/{} but origin points at {}/{}.",
+            state.repo_owner, state.repo_name, remote.owner, remote.repo
+        ));
+    }
+
+    if branch_source(root, &state.base_branch) == BranchSource::Missing {
+        problems.push(format!("Base branch '{}' does not exist locally or on origin.", state.base_branch));
+    }
+    if branch_source(root, &state.source_branch) == BranchSource::Missing {
+        problems.push(format!("Source branch '{}' does not exist locally or on origin.", state.source_branch));
+    }
+
+    let (mut names, mut branches, mut files) = (HashSet::new(), HashSet::new(), HashSet::new());
+    for chunk in &state.chunks {
+        if !names.insert(chunk.name.as_str()) {
+            problems.push(format!("Chunk name '{}' appears more than once.", chunk.name));
+        }
+        if !branches.insert(chunk.branch.as_str()) {
+            problems.push(format!("Branch '{}' is used by more than one chunk.", chunk.branch));
+        }
+        if branch_source(root, &chunk.branch) == BranchSource::Missing {
+            problems.push(format!(
+                "Branch '{}' of chunk '{}' does not exist locally or on origin.",
+                chunk.branch, chunk.name
+            ));
+        }
+        for file in &chunk.files {
+            if !files.insert(file.as_str()) {
+                problems.push(format!("File '{}' appears in multiple chunks.", file));
+            }
+        }
+    }
+
+    problems
+}
+
+/// Replace `.merges.json` with the state exported to `file`, after validating
+/// it against this repository. An existing state file is only replaced with
+/// `force`.
+pub fn import(root: &Path, file: &Path, force: bool) -> Result<MergesState> {
+    let content = std::fs::read_to_string(file).with_context(|| format!("Could not read {}", file.display()))?;
+    let state: MergesState = serde_json::from_str(&content)
+        .with_context(|| format!("{} is not a merges state file", file.display()))?;
+    restore(root, state, "import", &file.display().to_string(), force)
+}
+
+/// Commit the current state on top of the last one pushed or pulled and push
+/// it to origin's `STATE_REF`. The push is rejected when another machine
+/// pushed since this one last synced, unless `force`; the local ref only
+/// moves once origin accepted the commit. Returns the pushed commit SHA.
+pub fn push(root: &Path, force: bool) -> Result<String> {
+    let state = MergesState::load(root)?;
+    let content = serde_json::to_string_pretty(&state)?;
+
+    let commit = if same_state(git::read_file_at_ref(root, STATE_REF, STATE_FILE).as_deref(), &content) {
+        git::rev_parse(root, STATE_REF)?
+    } else {
+        let parent = git::rev_parse(root, STATE_REF).ok();
+        let message = format!("merges state: {} chunk(s) of {}", state.chunks.len(), state.source_branch);
+        git::commit_file(root, parent.as_deref(), STATE_FILE, &content, &message)?
+    };
+
+    git::push_ref(root, &commit, STATE_REF, force).context(
+        "If origin has newer state, run `merges state pull` first, or pass --force to overwrite it",
+    )?;
+    git::update_ref(root, STATE_REF, &commit)?;
+
+    say!(
+        "{} Pushed state ({} chunk(s)) to {} on origin",
+        "✓".green().bold(),
+        state.chunks.len().to_string().yellow(),
+        STATE_REF.cyan()
+    );
+    Ok(commit)
+}
+
+/// Fetch `STATE_REF` from origin and make it the local state. Local changes
+/// made since the last push or pull are kept unless `force`.
+pub fn pull(root: &Path, force: bool) -> Result<MergesState> {
+    let local = std::fs::read_to_string(MergesState::path(root)).ok();
+    let last_synced = git::read_file_at_ref(root, STATE_REF, STATE_FILE);
+
+    git::fetch_ref(root, STATE_REF)
+        .context("No shared state found on origin — run `merges state push` on the other machine first")?;
+    let content = git::read_file_at_ref(root, STATE_REF, STATE_FILE)
+        .with_context(|| format!("{} on origin does not contain {}", STATE_REF, STATE_FILE))?;
+    let state: MergesState = serde_json::from_str(&content)
+        .with_context(|| format!("{} on origin is not a merges state file", STATE_REF))?;
+
+    if let Some(local) = &local {
+        if same_state(Some(local), &content) {
+            say!("{} State is already up to date with origin.", "✓".green().bold());
+            return Ok(state);
+        }
+        if !force && !same_state(last_synced.as_deref(), local) {
+            bail!(
+                "{} has changes that were never pushed. Run `merges state push` to publish them, \
+                 or pass --force to replace them with the state from origin.",
+                STATE_FILE
+            );
+        }
+    }
+
+    restore(root, state, "pull", "origin", true)
+}
+
+/// Whether two serialised states describe the same state, ignoring formatting.
+fn same_state(a: Option<&str>, b: &str) -> bool {
+    let parse = |s: &str| serde_json::from_str::<serde_json::Value>(s).ok();
+    a.and_then(parse).is_some_and(|a| Some(a) == parse(b))
+}
+
+/// Validate `state` against this repository and write it as `.merges.json`.
+/// Branches that only exist on `origin` get local tracking branches (and
+/// worktrees, when the state uses them), so a teammate can pick up the stack
+/// from a fresh clone. `operation` names the journal entry.
+pub fn restore(root: &Path, state: MergesState, operation: &str, source: &str, force: bool) -> Result<MergesState> {
+    let problems = validate(root, &state);
+    if !problems.is_empty() {
+        bail!(
+            "State from {} does not match this repository:\n{}",
+            source,
+            problems.iter().map(|p| format!("  ✗ {}", p)).collect::<Vec<_>>().join("\n")
+        );
+    }
+
+    if MergesState::path(root).exists() && !force {
+        bail!(
+            "{} already exists. Pass --force to replace it (run `merges state export` first to keep a copy).",
+            STATE_FILE
+        );
+    }
+
+    for branch in std::iter::once(&state.source_branch).chain(state.chunks.iter().map(|c| &c.branch)) {
+        if branch_source(root, branch) == BranchSource::Remote {
+            git::track_remote_branch(root, branch)?;
+            say!("  {} Created '{}' from origin", "+".green(), branch.cyan());
+        }
+    }
+    if state.use_worktrees {
+        for chunk in &state.chunks {
+            if !git::worktree_path(root, &chunk.branch).exists() {
+                git::attach_worktree(root, &chunk.branch)?;
+            }
+        }
+    }
+
+    git::ensure_gitignored(root, STATE_FILE)?;
+    state.save(root)?;
+    journal::record(root, operation, &format!("from {}", source), &state.chunks)?;
+
+    say!(
+        "{} Restored {} chunk(s) of '{}' from {}",
+        "✓".green().bold(),
+        state.chunks.len().to_string().yellow(),
+        state.source_branch.cyan(),
+        source
+    );
+    Ok(state)
+}
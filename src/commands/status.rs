@@ -1,17 +1,93 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
 use colored::Colorize;
 use comfy_table::{presets::UTF8_FULL, Attribute, Cell, Color, ContentArrangement, Table};
+use std::path::Path;
 
-use crate::{config, git, github, state::MergesState};
+use crate::{
+    ci, doctor,
+    forge::{self, Forge},
+    git,
+    state::MergesState,
+};
 
-pub async fn run() -> Result<()> {
+/// Unmerged chunks created or last pushed longer ago than this are flagged as stale.
+pub const STALE_AFTER_DAYS: i64 = 7;
+
+/// How long status waits for its fetch of the base branch before counting
+/// against what was fetched last.
+pub const PREFETCH_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Which chunks `merges status --filter` keeps, written `state=<state>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateFilter {
+    /// Not merged yet.
+    Open,
+    Merged,
+    /// Would conflict with the base branch.
+    Conflict,
+}
+
+impl std::str::FromStr for StateFilter {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<StateFilter> {
+        match s.split_once('=').map(|(key, value)| (key.trim(), value.trim())) {
+            Some(("state", "open")) => Ok(StateFilter::Open),
+            Some(("state", "merged")) => Ok(StateFilter::Merged),
+            Some(("state", "conflict")) => Ok(StateFilter::Conflict),
+            _ => bail!("Unknown filter '{}'; use state=open, state=merged, or state=conflict", s),
+        }
+    }
+}
+
+/// How `merges status --sort` orders the chunks; ties keep stack order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SortKey {
+    /// Furthest behind the base branch first
+    Behind,
+    /// Most files first
+    Size,
+    /// Oldest first
+    Age,
+}
+
+/// Flags for `merges status`.
+#[derive(Debug, Clone, Default)]
+pub struct StatusOptions {
+    /// List descriptions, failing checks, and predicted CI below the table.
+    pub verbose: bool,
+    pub filter: Option<StateFilter>,
+    pub sort: Option<SortKey>,
+    /// Show only the chunk with this name.
+    pub chunk: Option<String>,
+    /// One line per chunk instead of the table, for narrow terminals.
+    pub compact: bool,
+}
+
+/// Print the chunk/PR status table. With `verbose`, also list chunk
+/// descriptions and the names of failing checks for each chunk below the table.
+pub async fn run(opts: StatusOptions) -> Result<()> {
     let root = git::repo_root()?;
-    let state = MergesState::load(&root)?;
+    let forge = forge::connect(&MergesState::load(&root)?).await.ok();
+    run_with(&root, opts, forge.as_deref()).await
+}
+
+/// [`run`] for the repository at `root`, reading PRs through `forge`; without
+/// one the PR columns are left blank.
+pub async fn run_with(root: &Path, opts: StatusOptions, forge: Option<&dyn Forge>) -> Result<()> {
+    let mut state = MergesState::load(root)?;
+    let now = Utc::now();
+    let mut newly_merged = false;
 
     if state.chunks.is_empty() {
         println!("No chunks defined yet. Run {} first.", "merges split".bold());
         return Ok(());
     }
+    if let Some(name) = opts.chunk.as_deref().filter(|name| !state.chunks.iter().any(|c| c.name == *name)) {
+        bail!("No chunk named '{}'", name);
+    }
+    let prefetch = git::Prefetch::start(root, &state.base_branch);
 
     println!(
         "{} Status for {}/{} — source: {}, base: {}",
@@ -21,9 +97,9 @@ pub async fn run() -> Result<()> {
         state.source_branch.yellow(),
         state.base_branch.yellow()
     );
-
-    let token = config::github_token().ok();
-    let gh = token.as_deref().and_then(|t| github::client(t).ok());
+    if let Some(divergence) = doctor::source_divergence(root, &state) {
+        println!("{} {}", "!".yellow().bold(), divergence);
+    }
 
     let mut table = Table::new();
     table
@@ -34,32 +110,71 @@ pub async fn run() -> Result<()> {
             Cell::new("Chunk").add_attribute(Attribute::Bold),
             Cell::new("Branch").add_attribute(Attribute::Bold),
             Cell::new("Sync").add_attribute(Attribute::Bold),
+            Cell::new("Remote").add_attribute(Attribute::Bold),
             Cell::new("PR").add_attribute(Attribute::Bold),
             Cell::new("CI").add_attribute(Attribute::Bold),
             Cell::new("Review").add_attribute(Attribute::Bold),
+            Cell::new("Age").add_attribute(Attribute::Bold),
+            Cell::new("Last push").add_attribute(Attribute::Bold),
             Cell::new("Files").add_attribute(Attribute::Bold),
         ]);
 
-    for (i, chunk) in state.chunks.iter().enumerate() {
-        let pr_cell = if let Some(num) = chunk.pr_number {
-            format!("#{}", num)
-        } else {
-            "—".to_string()
+    // Look the PRs up while the fetch runs; only the counts need to wait for it.
+    let mut failing: Vec<(String, Vec<String>)> = vec![];
+    let mut pr_cells = vec![];
+    for chunk in state.chunks.iter_mut() {
+        let cells = match (forge, chunk.pr_number) {
+            (Some(forge), Some(pr_num)) if opts.chunk.as_ref().is_none_or(|name| *name == chunk.name) => {
+                match forge.get_pr_info(pr_num).await {
+                    Ok(info) => {
+                        if info.merged && chunk.merged_at.is_none() {
+                            chunk.merged_at = info.merged_at.or(Some(now));
+                            newly_merged = true;
+                        }
+                        if !info.failing_checks.is_empty() {
+                            failing.push((chunk.name.clone(), info.failing_checks));
+                        }
+                        (info.ci_status, info.review_state)
+                    }
+                    Err(_) => ("error".to_string(), "error".to_string()),
+                }
+            }
+            _ => ("—".to_string(), "—".to_string()),
         };
+        pr_cells.push(cells);
+    }
 
-        let (ci_cell, review_cell) = if let (Some(gh_client), Some(pr_num)) = (&gh, chunk.pr_number) {
-            match github::get_pr_info(gh_client, &state.repo_owner, &state.repo_name, pr_num).await {
-                Ok(info) => (info.ci_status, info.review_state),
-                Err(_) => ("error".to_string(), "error".to_string()),
-            }
-        } else {
-            ("—".to_string(), "—".to_string())
+    let fetched = prefetch.finish(PREFETCH_TIMEOUT).await;
+    if !fetched && git::rev_parse(root, &format!("origin/{}", state.base_branch)).is_ok() {
+        println!("{}", format!("Could not fetch origin/{}; counts are as of the last fetch.", state.base_branch).dimmed());
+    }
+    let branches: Vec<String> = state.chunks.iter().map(|c| c.branch.clone()).collect();
+    let base = git::base_ref(root, &state.base_branch);
+    let counts = git::branch_counts(root, &branches, &base).unwrap_or_default();
+
+    let mut rows = vec![];
+    for ((i, chunk), (ci_cell, review_cell)) in state.chunks.iter().enumerate().zip(pr_cells) {
+        if opts.chunk.as_ref().is_some_and(|name| *name != chunk.name) {
+            continue;
+        }
+        let pr_cell = match chunk.pr_number {
+            Some(num) if chunk.hold => format!("#{} (held)", num),
+            Some(num) => format!("#{}", num),
+            None if chunk.hold => "held".to_string(),
+            None => "—".to_string(),
         };
 
-        let behind = git::commits_behind(&root, &chunk.branch, &state.base_branch).unwrap_or(0);
+        let git::BranchCounts { behind_base: behind, tracking, .. } = counts.get(i).cloned().unwrap_or_default();
         let sync_label = git::sync_status(behind);
         let sync_color = if behind == 0 { Color::Green } else { Color::Yellow };
 
+        let remote_label = git::remote_status(tracking);
+        let remote_color = match tracking {
+            Some((0, 0)) => Color::Green,
+            Some(_) => Color::Yellow,
+            None => Color::Reset,
+        };
+
         let ci_color = match ci_cell.as_str() {
             "success" => Color::Green,
             "failure" | "error" => Color::Red,
@@ -73,19 +188,90 @@ pub async fn run() -> Result<()> {
             _ => Color::Reset,
         };
 
-        table.add_row(vec![
-            Cell::new(i + 1),
-            Cell::new(&chunk.name),
-            Cell::new(&chunk.branch).fg(Color::Cyan),
-            Cell::new(&sync_label).fg(sync_color),
-            Cell::new(&pr_cell),
-            Cell::new(&ci_cell).fg(ci_color),
-            Cell::new(&review_cell).fg(review_color),
-            Cell::new(chunk.files.len()),
-        ]);
+        let (age_cell, push_cell, age_color) = match chunk.merged_at {
+            Some(merged_at) => (format!("merged {}", format_age(merged_at, now)), String::new(), Color::Green),
+            None => {
+                let last_activity = chunk.last_pushed_at.or(chunk.created_at);
+                let stale = last_activity.is_some_and(|t| (now - t).num_days() >= STALE_AFTER_DAYS);
+                (
+                    chunk.created_at.map(|t| format_age(t, now)).unwrap_or_else(|| "—".to_string()),
+                    chunk.last_pushed_at.map(|t| format_age(t, now)).unwrap_or_else(|| "never".to_string()),
+                    if stale { Color::Yellow } else { Color::Reset },
+                )
+            }
+        };
+
+        rows.push(Row {
+            index: i,
+            cells: vec![
+                (chunk.name.clone(), Color::Reset),
+                (chunk.branch.clone(), Color::Cyan),
+                (sync_label, sync_color),
+                (remote_label, remote_color),
+                (pr_cell, Color::Reset),
+                (ci_cell, ci_color),
+                (review_cell, review_color),
+                (age_cell, age_color),
+                (push_cell, age_color),
+                (chunk.files.len().to_string(), Color::Reset),
+            ],
+        });
+    }
+
+    if newly_merged {
+        state.save(root)?;
+    }
+
+    let shown = select(root, &state, &counts, &opts)?;
+    rows.retain(|row| shown.contains(&row.index));
+    rows.sort_by_key(|row| shown.iter().position(|i| *i == row.index));
+    if rows.is_empty() {
+        println!("No chunks match.");
+    } else if opts.compact {
+        for row in &rows {
+            println!("{}", row.compact());
+        }
+    } else {
+        for row in &rows {
+            let mut cells = vec![Cell::new(row.index + 1)];
+            cells.extend(row.cells.iter().map(|(text, color)| Cell::new(text).fg(*color)));
+            table.add_row(cells);
+        }
+        println!("{}", table);
+    }
+
+    let verbose = opts.verbose;
+    let described: Vec<_> = rows
+        .iter()
+        .map(|row| &state.chunks[row.index])
+        .filter_map(|c| Some((&c.name, c.description.as_deref()?)))
+        .collect();
+    if verbose && !described.is_empty() {
+        println!("\n  Descriptions:");
+        for (name, description) in &described {
+            println!("  {} [{}] {}", "·".dimmed(), name.cyan(), description.trim());
+        }
     }
 
-    println!("{}", table);
+    failing.retain(|(name, _)| rows.iter().any(|row| state.chunks[row.index].name == *name));
+    if verbose && !failing.is_empty() {
+        println!("\n  Failing checks:");
+        for (name, checks) in &failing {
+            println!("  {} [{}] {}", "✗".red(), name.cyan(), checks.join(", "));
+        }
+    }
+
+    let workflows = ci::workflows(root, &state.source_branch);
+    if verbose && !workflows.is_empty() {
+        println!("\n  Predicted CI:");
+        for chunk in ci::predict(&state, &state.strategy, &workflows) {
+            let runs = if chunk.workflows.is_empty() { "none".to_string() } else { chunk.workflows.join(", ") };
+            println!("  {} [{}] {}", "·".dimmed(), chunk.chunk.cyan(), runs);
+        }
+    }
+    for warning in ci::warnings(&state, &state.strategy, &workflows) {
+        println!("\n  {} {}", "!".yellow().bold(), warning);
+    }
 
     if let Some(url) = state.chunks.first().and_then(|c| c.pr_url.as_deref()) {
         println!(
@@ -96,3 +282,93 @@ pub async fn run() -> Result<()> {
 
     Ok(())
 }
+
+/// One chunk's line: its stack position and the table's cells after `#`.
+struct Row {
+    index: usize,
+    cells: Vec<(String, Color)>,
+}
+
+impl Row {
+    /// The row on one line — position, chunk, sync, PR, CI, review, age, and
+    /// file count — leaving out the branch, remote, and last push.
+    fn compact(&self) -> String {
+        let cell = |n: usize| {
+            let (text, color) = &self.cells[n];
+            match color {
+                Color::Green => text.green().to_string(),
+                Color::Yellow => text.yellow().to_string(),
+                Color::Red => text.red().to_string(),
+                _ => text.clone(),
+            }
+        };
+        format!(
+            "{:>2} {}  {}  {}  {} {}  {} {}  {}  {} file(s)",
+            self.index + 1,
+            self.cells[0].0.cyan(),
+            cell(2),
+            cell(4),
+            "ci".dimmed(),
+            cell(5),
+            "review".dimmed(),
+            cell(6),
+            cell(7),
+            self.cells[9].0
+        )
+    }
+}
+
+/// Positions of the chunks `opts` shows, in the order it shows them: the
+/// named chunk, or those passing the filter, sorted. `counts` are the chunks'
+/// [`git::branch_counts`]. Merged means `merged_at` is recorded, as status
+/// itself records it from the forge.
+pub fn select(
+    root: &Path,
+    state: &MergesState,
+    counts: &[git::BranchCounts],
+    opts: &StatusOptions,
+) -> Result<Vec<usize>> {
+    let base = git::base_ref(root, &state.base_branch);
+    let mut shown = vec![];
+    for (i, chunk) in state.chunks.iter().enumerate() {
+        if opts.chunk.as_ref().is_some_and(|name| *name != chunk.name) {
+            continue;
+        }
+        let keep = match opts.filter {
+            None => true,
+            Some(StateFilter::Open) => chunk.merged_at.is_none(),
+            Some(StateFilter::Merged) => chunk.merged_at.is_some(),
+            Some(StateFilter::Conflict) => !git::merge_conflicts(root, &chunk.branch, &base)?.is_empty(),
+        };
+        if keep {
+            shown.push(i);
+        }
+    }
+    let behind = |i: &usize| counts.get(*i).map_or(0, |c| c.behind_base);
+    let created = |i: &usize| state.chunks[*i].created_at;
+    match opts.sort {
+        None => {}
+        Some(SortKey::Behind) => shown.sort_by_key(|i| std::cmp::Reverse(behind(i))),
+        Some(SortKey::Size) => shown.sort_by_key(|i| std::cmp::Reverse(state.chunks[*i].files.len())),
+        // Chunks without a creation time (older state files) go last.
+        Some(SortKey::Age) => shown.sort_by_key(|i| (created(i).is_none(), created(i))),
+    }
+    Ok(shown)
+}
+
+/// Compact relative time from `since` to `now`: `just now`, `45m ago`, `3h ago`,
+/// `2d ago`, or `5w ago`.
+pub fn format_age(since: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let elapsed = now - since;
+    if elapsed.num_minutes() < 1 {
+        "just now".to_string()
+    } else if elapsed.num_hours() < 1 {
+        format!("{}m ago", elapsed.num_minutes())
+    } else if elapsed.num_days() < 1 {
+        format!("{}h ago", elapsed.num_hours())
+    } else if elapsed.num_weeks() < 2 {
+        format!("{}d ago", elapsed.num_days())
+    } else {
+        format!("{}w ago", elapsed.num_weeks())
+    }
+}
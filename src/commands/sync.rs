@@ -1,84 +1,542 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
+use chrono::Utc;
 use colored::Colorize;
-use indicatif::{ProgressBar, ProgressStyle};
+use serde::Serialize;
+use std::path::Path;
 
-use crate::{git, state::{MergesState, Strategy}};
+use crate::{
+    autostash::Autostash,
+    cancel::CancelToken,
+    commands::push,
+    empty,
+    forge::{self, Forge},
+    git, journal, output, say, split,
+    state::{MergesState, Strategy},
+    warning,
+};
 
-pub fn run() -> Result<()> {
+/// What happened to one chunk during a sync.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum SyncOutcome {
+    Rebased,
+    /// The base (or, stacked, the chunk below) was merged into the branch
+    /// instead, in merge mode.
+    Updated,
+    /// The chunk's PR merged into the base branch, so it was taken off the
+    /// stack rather than rebased.
+    Merged,
+    /// The rebase stopped on conflicts in `files` and was aborted, leaving the
+    /// branch as it was.
+    Conflicted { files: Vec<String> },
+    /// Not attempted, e.g. because the chunk it is stacked on conflicted.
+    Skipped { reason: String },
+    Failed { error: String },
+}
+
+impl SyncOutcome {
+    /// True when the chunk is current with the base: brought up to date, or
+    /// off the stack because its PR merged.
+    pub fn is_up_to_date(&self) -> bool {
+        matches!(self, SyncOutcome::Rebased | SyncOutcome::Updated | SyncOutcome::Merged)
+    }
+}
+
+/// One chunk's result from [`run`].
+#[derive(Debug, Clone, Serialize)]
+pub struct ChunkSync {
+    pub name: String,
+    pub branch: String,
+    #[serde(flatten)]
+    pub outcome: SyncOutcome,
+}
+
+/// Rebase every chunk — or only those named in `chunks` — onto the base
+/// branch, returning what happened to each. Stacked chunks are rebased in
+/// order, each onto the chunk below it, so the stack keeps its shape. In
+/// classic mode a dirty working tree is refused unless `autostash` is set.
+///
+/// Chunks whose PRs have merged into the base branch (see
+/// [`merged_upstream`]) are taken off the stack into the state's merged list
+/// instead, and their branches deleted with `delete_merged`. Other chunks left
+/// with no changes are reported, and dropped from the stack with `drop_empty`
+/// or when the user agrees at the prompt.
+///
+/// A chunk whose rebase conflicts is reported with the conflicting files and
+/// left as it was; in a stacked sync the chunks above it are skipped, since
+/// they carry its commits. Use [`check`] to turn such results into an error.
+///
+/// With `merge` (or the `sync_merge` setting), nothing is rebased: the base
+/// branch — or in a stacked sync the chunk below — is merged into each chunk
+/// branch, so published history is never rewritten.
+///
+/// Progress goes into the journal as it is made, so a sync that stops
+/// halfway — cancelled, or killed outright — is finished with `resume`: the
+/// chunks it had not rebased yet are rebased onto the same base commit, from
+/// where they forked before it started. Once `cancel` is tripped no further
+/// chunk starts rebasing; chunks already rebased stay rebased.
+pub fn run(
+    autostash: bool,
+    drop_empty: bool,
+    delete_merged: bool,
+    resume: bool,
+    merge: bool,
+    chunks: &[String],
+    cancel: &CancelToken,
+) -> Result<Vec<ChunkSync>> {
+    let root = git::repo_root()?;
+    let stash = Autostash::begin(&root, &MergesState::load(&root)?, "sync", autostash)?;
+    stash.finish(sync(drop_empty, delete_merged, resume, merge, chunks, cancel))
+}
+
+/// Close the stack's tracking issue once `results` took its last chunks off
+/// the stack: `merges push`, which otherwise keeps the issue up to date, has
+/// nothing left to push. The sync itself is done by then, so a forge failure
+/// only warns.
+pub async fn close_tracking_issue(results: &[ChunkSync]) -> Result<()> {
     let root = git::repo_root()?;
     let state = MergesState::load(&root)?;
+    if !emptied_stack(&state, results) {
+        return Ok(());
+    }
+    let closed = match forge::connect(&state).await {
+        Ok(forge) => close_tracking_issue_with(&root, results, forge.as_ref()).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = closed {
+        warning!("Tracking issue not closed: {:#}", e);
+    }
+    Ok(())
+}
 
-    if state.chunks.is_empty() {
-        println!("No chunks defined yet.");
+/// [`close_tracking_issue`] against `forge`.
+pub async fn close_tracking_issue_with(root: &Path, results: &[ChunkSync], forge: &dyn Forge) -> Result<()> {
+    let mut state = MergesState::load(root)?;
+    if !emptied_stack(&state, results) {
         return Ok(());
     }
+    push::sync_tracking_issue(forge, root, &mut state, None).await
+}
+
+fn emptied_stack(state: &MergesState, results: &[ChunkSync]) -> bool {
+    state.tracking_issue.is_some()
+        && state.chunks.is_empty()
+        && results.iter().any(|r| r.outcome == SyncOutcome::Merged)
+}
+
+/// A sync the journal shows starting but not finishing: its `sync-start`
+/// entry, holding the chunks it set out to rebase and every branch's SHA
+/// (and the base's) before it began, and the chunks it rebased since.
+#[derive(Debug)]
+pub struct Interrupted {
+    pub start: journal::Entry,
+    pub done: Vec<String>,
+}
+
+/// The last sync, if it never finished.
+pub fn interrupted(root: &Path) -> Result<Option<Interrupted>> {
+    let entries = journal::load(root)?;
+    let Some(start) = entries.iter().rposition(|e| e.operation == "sync-start" || e.operation == "sync") else {
+        return Ok(None);
+    };
+    if entries[start].operation != "sync-start" {
+        return Ok(None);
+    }
+    let done = entries[start + 1..]
+        .iter()
+        .filter(|e| e.operation == "sync-chunk")
+        .flat_map(|e| e.chunks.iter().cloned())
+        .collect();
+    Ok(Some(Interrupted { start: entries[start].clone(), done }))
+}
+
+/// Fail if any chunk in `results` could not be rebased, saying how to finish.
+pub fn check(results: &[ChunkSync]) -> Result<()> {
+    let stuck: Vec<String> = results
+        .iter()
+        .filter_map(|r| match &r.outcome {
+            SyncOutcome::Conflicted { files } => Some(format!("{}: conflicts in {}", r.name, files.join(", "))),
+            SyncOutcome::Failed { error } => Some(format!("{}: {}", r.name, error)),
+            _ => None,
+        })
+        .collect();
+    if !stuck.is_empty() {
+        bail!(
+            "{} chunk(s) could not be brought up to date:\n  {}\nCheck out the branch, rebase it onto (or merge in) \
+             the base branch and resolve the conflicts, then run `merges sync` again.",
+            stuck.len(),
+            stuck.join("\n  ")
+        );
+    }
+    Ok(())
+}
+
+/// Rebase the chunk branch checked out in `dir` with `rebase`. A conflicted
+/// rebase is aborted so the branch stays as it was.
+fn rebase_chunk(dir: &Path, rebase: impl FnOnce(&Path) -> Result<()>) -> SyncOutcome {
+    match rebase(dir) {
+        Ok(()) => SyncOutcome::Rebased,
+        Err(e) => {
+            let files = git::conflicted_files(dir);
+            if files.is_empty() {
+                return SyncOutcome::Failed { error: format!("{:#}", e) };
+            }
+            if let Err(abort) = git::rebase_abort(dir) {
+                return SyncOutcome::Failed { error: format!("{:#}", abort) };
+            }
+            SyncOutcome::Conflicted { files }
+        }
+    }
+}
+
+/// Merge `onto` into the chunk branch checked out in `dir`. A conflicted merge
+/// is aborted so the branch stays as it was.
+fn merge_chunk(dir: &Path, onto: &str) -> SyncOutcome {
+    match git::merge(dir, onto) {
+        Ok(()) => SyncOutcome::Updated,
+        Err(e) => {
+            let files = git::conflicted_files(dir);
+            if files.is_empty() {
+                return SyncOutcome::Failed { error: format!("{:#}", e) };
+            }
+            if let Err(abort) = git::merge_abort(dir) {
+                return SyncOutcome::Failed { error: format!("{:#}", abort) };
+            }
+            SyncOutcome::Conflicted { files }
+        }
+    }
+}
+
+fn sync(
+    drop_empty: bool,
+    delete_merged: bool,
+    resume: bool,
+    merge: bool,
+    only: &[String],
+    cancel: &CancelToken,
+) -> Result<Vec<ChunkSync>> {
+    let root = git::repo_root()?;
+    let mut state = MergesState::load(&root)?;
+    let merge = merge || state.sync_merge;
+    let (verb, doing) = if merge { ("merge", "merging") } else { ("rebase", "rebasing") };
+
+    if state.chunks.is_empty() {
+        say!("No chunks defined yet.");
+        return Ok(vec![]);
+    }
+    if let Some(unknown) = only.iter().find(|name| !state.chunks.iter().any(|c| c.name == **name)) {
+        bail!("No chunk named '{}'", unknown);
+    }
+    let remote_base = format!("origin/{}", state.base_branch);
+    let unfinished = interrupted(&root)?;
+    let resumed = match unfinished {
+        Some(last) if resume => {
+            if !only.is_empty() {
+                bail!("`--resume` finishes the chunks the interrupted sync set out to rebase; drop `--chunk`.");
+            }
+            Some(last)
+        }
+        Some(last) if !last.done.is_empty() => bail!(
+            "The last sync stopped after rebasing {} of {} chunk(s) ({}). Run `merges sync --resume` to finish it — \
+             the chunks above those have not moved yet and must be rebased from where they were.",
+            last.done.len(),
+            last.start.chunks.len(),
+            last.done.join(", ")
+        ),
+        _ => {
+            if resume {
+                warning!("No interrupted sync recorded — syncing every chunk.");
+            }
+            None
+        }
+    };
+
+    let selected: Vec<usize> = (0..state.chunks.len())
+        .filter(|&i| match &resumed {
+            Some(last) => last.start.chunks.contains(&state.chunks[i].name) && !last.done.contains(&state.chunks[i].name),
+            None => only.is_empty() || only.contains(&state.chunks[i].name),
+        })
+        .collect();
+    let cancelled = |done: usize| match done {
+        0 => format!("after rebasing 0 of {} chunk(s) — run `merges sync` again to finish", selected.len()),
+        _ => format!("after rebasing {} of {} chunk(s) — run `merges sync --resume` to finish", done, selected.len()),
+    };
 
     let current = git::current_branch(&root)?;
 
-    println!(
-        "{} Syncing {} chunk branch(es) onto '{}'{}",
+    // Where each chunk forked off its parent and which chunks have merged are
+    // both read before anything moves: rebasing rewrites the branches. A
+    // resumed sync reads them from the branches as they were when it started,
+    // and rebases onto the base commit it fetched then.
+    cancel.check(&cancelled(0))?;
+    let (base, forks) = match &resumed {
+        Some(last) => resume_point(&root, &state, &last.start)?,
+        None => {
+            git::fetch(&root)?;
+            let forks = (0..state.chunks.len()).map(|i| split::fork_point(&root, &state, i)).collect::<Result<_>>()?;
+            (remote_base.clone(), forks)
+        }
+    };
+    let mut merged = vec![];
+    for (i, fork) in forks.iter().enumerate() {
+        if merged_upstream(&root, &state, i, fork)? {
+            merged.push(i);
+        }
+    }
+
+    let stacked = state.strategy == Strategy::Stacked;
+    say!(
+        "{} Syncing {} chunk branch(es) {} '{}'{}",
         "→".blue().bold(),
-        state.chunks.len().to_string().yellow(),
+        selected.len().to_string().yellow(),
+        if merge { "by merging in" } else { "onto" },
         state.base_branch.cyan(),
-        if state.use_worktrees { " (parallel)" } else { "" }
+        if state.use_worktrees && !stacked { " (parallel)" } else { "" }
     );
 
-    let pb = ProgressBar::new(state.chunks.len() as u64);
-    pb.set_style(
-        ProgressStyle::default_bar()
-            .template("{bar:40.cyan/blue} {pos}/{len} {msg}")
-            .unwrap(),
-    );
+    if resumed.is_none() {
+        let mut branches: std::collections::BTreeMap<String, String> = state
+            .chunks
+            .iter()
+            .filter_map(|c| Some((c.branch.clone(), git::rev_parse(&root, &c.branch).ok()?)))
+            .collect();
+        branches.insert(remote_base.clone(), git::rev_parse(&root, &remote_base)?);
+        journal::append(&root, &journal::Entry {
+            at: Utc::now(),
+            operation: "sync-start".to_string(),
+            chunks: selected.iter().map(|&i| state.chunks[i].name.clone()).collect(),
+            branches,
+            summary: format!("onto {}", remote_base),
+        })?;
+    }
 
-    if state.use_worktrees {
+    let mut results: Vec<ChunkSync> = vec![];
+    output::step_started("sync", selected.len());
+    if state.use_worktrees && !stacked {
         // Parallel rebase: each chunk has its own worktree dir — no serialization needed
         use std::sync::{Arc, Mutex};
-        let errors: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(vec![]));
-        let pb = Arc::new(pb);
+        let outcomes: Arc<Mutex<Vec<(usize, SyncOutcome)>>> =
+            Arc::new(Mutex::new(merged.iter().filter(|i| selected.contains(i)).map(|&i| (i, SyncOutcome::Merged)).collect()));
 
         std::thread::scope(|s| {
-            for chunk in &state.chunks {
+            for &i in selected.iter().filter(|i| !merged.contains(i)) {
+                let chunk = &state.chunks[i];
                 let wt = git::worktree_path(&root, &chunk.branch);
-                let base = state.base_branch.clone();
-                let strategy = state.strategy.clone();
-                let name = chunk.branch.clone();
-                let pb = Arc::clone(&pb);
-                let errors = Arc::clone(&errors);
+                let (root, base, fork) = (&root, &base, &forks[i]);
+                let outcomes = Arc::clone(&outcomes);
 
                 s.spawn(move || {
-                    let result = match strategy {
-                        Strategy::Stacked => git::fetch_and_rebase_stacked(&wt, &base),
-                        Strategy::Independent => git::fetch_and_rebase(&wt, &base),
+                    if cancel.is_cancelled() {
+                        return;
+                    }
+                    output::chunk_progress(&chunk.name, format!("{} '{}'…", doing, chunk.branch));
+                    let outcome = if merge {
+                        merge_chunk(&wt, base)
+                    } else {
+                        rebase_chunk(&wt, |dir| git::rebase_onto(dir, base, fork))
                     };
-                    if let Err(e) = result {
-                        errors.lock().unwrap().push(format!("{}: {}", name, e));
+                    if matches!(outcome, SyncOutcome::Rebased | SyncOutcome::Updated) {
+                        // Best effort: an independent chunk rebased again
+                        // after a crash just ends up where it already is.
+                        let summary = match outcome {
+                            SyncOutcome::Updated => format!("merged {}", base),
+                            _ => format!("rebased onto {}", base),
+                        };
+                        let _ = journal::record(root, "sync-chunk", &summary, std::slice::from_ref(chunk));
                     }
-                    pb.inc(1);
+                    outcomes.lock().unwrap().push((i, outcome));
+                    output::chunk_done(&chunk.name, "");
                 });
             }
         });
+        output::step_completed("sync");
 
-        pb.finish_with_message("done");
-
-        let errs = errors.lock().unwrap();
-        if !errs.is_empty() {
-            anyhow::bail!("Some chunks failed to rebase:\n{}", errs.join("\n"));
+        let mut outcomes = std::mem::take(&mut *outcomes.lock().unwrap());
+        outcomes.sort_by_key(|(i, _)| *i);
+        let rebased =
+            outcomes.iter().filter(|(_, o)| matches!(o, SyncOutcome::Rebased | SyncOutcome::Updated)).count();
+        cancel.check(&cancelled(rebased))?;
+        for (i, outcome) in outcomes {
+            let chunk = &state.chunks[i];
+            results.push(ChunkSync { name: chunk.name.clone(), branch: chunk.branch.clone(), outcome });
         }
     } else {
-        // Classic mode: sequential, requires branch checkout
-        for chunk in &state.chunks {
-            pb.set_message(format!("rebasing '{}'…", chunk.branch));
-            git::checkout(&root, &chunk.branch)?;
-            match state.strategy {
-                Strategy::Stacked => git::fetch_and_rebase_stacked(&root, &state.base_branch)?,
-                Strategy::Independent => git::fetch_and_rebase(&root, &state.base_branch)?,
+        // Sequential: classic mode checks each branch out, and a stacked chunk
+        // can only move once the chunk below it has. Each stacked chunk is
+        // rebased onto the nearest unmerged chunk below it from where it
+        // forked off its parent, so only its own commits are replayed.
+        let mut blocked_by: Option<String> = None;
+        for (done, &i) in selected.iter().enumerate() {
+            if cancel.is_cancelled() {
+                output::step_completed("sync");
+                git::checkout(&root, &current)?;
+                cancel.check(&cancelled(done))?;
             }
-            pb.inc(1);
+            let chunk = &state.chunks[i];
+            let outcome = match &blocked_by {
+                _ if merged.contains(&i) => SyncOutcome::Merged,
+                Some(below) => SyncOutcome::Skipped { reason: format!("stacked on '{}', which was not rebased", below) },
+                None => {
+                    output::chunk_progress(&chunk.name, format!("{} '{}'…", doing, chunk.branch));
+                    let dir = if state.use_worktrees {
+                        git::worktree_path(&root, &chunk.branch)
+                    } else {
+                        git::checkout(&root, &chunk.branch)?;
+                        root.clone()
+                    };
+                    let onto = match (0..i).rev().find(|j| !merged.contains(j)) {
+                        Some(j) if stacked => state.chunks[j].branch.clone(),
+                        _ => base.clone(),
+                    };
+                    let outcome = if merge {
+                        merge_chunk(&dir, &onto)
+                    } else {
+                        rebase_chunk(&dir, |dir| git::rebase_onto(dir, &onto, &forks[i]))
+                    };
+                    let summary = match outcome {
+                        SyncOutcome::Rebased => Some(format!("rebased onto {}", onto)),
+                        SyncOutcome::Updated => Some(format!("merged {}", onto)),
+                        _ => None,
+                    };
+                    if let Some(summary) = summary {
+                        journal::record(&root, "sync-chunk", &summary, std::slice::from_ref(chunk))?;
+                    }
+                    outcome
+                }
+            };
+            if !outcome.is_up_to_date() && stacked && blocked_by.is_none() {
+                blocked_by = Some(chunk.name.clone());
+            }
+            results.push(ChunkSync { name: chunk.name.clone(), branch: chunk.branch.clone(), outcome });
+            output::chunk_done(&chunk.name, "");
         }
-        pb.finish_with_message("done");
+        output::step_completed("sync");
         git::checkout(&root, &current)?;
     }
 
-    println!("{} All chunks are up to date with '{}'.", "✓".green().bold(), state.base_branch.cyan());
+    for result in &results {
+        match &result.outcome {
+            SyncOutcome::Rebased | SyncOutcome::Updated | SyncOutcome::Merged => {}
+            SyncOutcome::Conflicted { files } => say!(
+                "{} '{}' conflicts in {} — {} aborted, branch unchanged.",
+                "✗".red().bold(),
+                result.name.cyan(),
+                files.join(", "),
+                verb
+            ),
+            SyncOutcome::Skipped { reason } => say!("{} '{}' skipped: {}", "·".dimmed(), result.name.cyan(), reason),
+            SyncOutcome::Failed { error } => say!("{} '{}' failed: {}", "✗".red().bold(), result.name.cyan(), error),
+        }
+    }
+
+    // Recorded even when nothing was rebased: it marks the sync finished.
+    let rebased = results.iter().filter(|r| matches!(r.outcome, SyncOutcome::Rebased | SyncOutcome::Updated)).count();
+    let summary = if merge {
+        format!("{} of {} chunk(s) merged {}", rebased, selected.len(), remote_base)
+    } else {
+        format!("{} of {} chunk(s) rebased onto {}", rebased, selected.len(), remote_base)
+    };
+    journal::record(&root, "sync", &summary, &state.chunks)?;
+    if !merged.is_empty() {
+        take_merged(&root, &mut state, &merged, delete_merged)?;
+    }
+
+    let strategy = state.strategy.clone();
+    let empty_chunks = empty::find(&root, &state, &strategy)?;
+    if !empty_chunks.is_empty() {
+        empty::warn(&state, &empty_chunks, &strategy);
+        if empty::confirm_drop(empty_chunks.len(), drop_empty)? {
+            empty::drop_chunks(&root, &mut state, &empty_chunks)?;
+        } else {
+            say!(
+                "  Their PRs would show no changes: {} skips them, {} drops them.",
+                "merges push".bold(),
+                "merges sync --drop-empty".bold()
+            );
+        }
+    }
+    if results.iter().all(|r| r.outcome.is_up_to_date()) {
+        say!("{} All chunks are up to date with '{}'.", "✓".green().bold(), state.base_branch.cyan());
+    }
+    Ok(results)
+}
+
+/// Where an interrupted sync that began with `start` left off: the base commit
+/// it was rebasing onto, and where each chunk forked off its parent before it
+/// began — read from the SHAs it recorded, since the chunks it already
+/// rebased no longer lead back to the fork points of the ones above them.
+fn resume_point(root: &Path, state: &MergesState, start: &journal::Entry) -> Result<(String, Vec<String>)> {
+    let remote_base = format!("origin/{}", state.base_branch);
+    let Some(base) = start.branches.get(&remote_base).cloned() else {
+        bail!("The interrupted sync did not record the base commit it was rebasing onto.");
+    };
+    let tip = |i: usize| match start.branches.get(&state.chunks[i].branch) {
+        Some(sha) => sha.clone(),
+        None => state.chunks[i].branch.clone(),
+    };
+    let forks = (0..state.chunks.len())
+        .map(|i| match state.strategy {
+            Strategy::Stacked if i > 0 => git::merge_base_of(root, &tip(i), &tip(i - 1)),
+            _ => git::merge_base_of(root, &tip(i), &base),
+        })
+        .collect::<Result<_>>()?;
+    Ok((base, forks))
+}
+
+/// Whether chunk `i`, forked off its parent at `fork`, has its PR merged into
+/// the base branch: its commits are on `origin/<base>` as they are (a merge
+/// commit or fast-forward), or its PR is recorded as merged and every file
+/// the chunk changes already reads the same there (a squash or rebase merge).
+/// Chunks without a PR never count — their changes landing upstream on their
+/// own is what empty-chunk handling is for.
+pub fn merged_upstream(root: &Path, state: &MergesState, i: usize, fork: &str) -> Result<bool> {
+    let chunk = &state.chunks[i];
+    let base = format!("origin/{}", state.base_branch);
+    if chunk.pr_number.is_none() {
+        return Ok(false);
+    }
+    if git::is_ancestor(root, &chunk.branch, &base) {
+        return Ok(true);
+    }
+    if chunk.merged_at.is_none() {
+        return Ok(false);
+    }
+    let changed = git::diff_files(root, fork, &chunk.branch)?;
+    Ok(!changed.is_empty() && git::differing_files(root, &base, &chunk.branch, &changed)?.is_empty())
+}
+
+/// Take the chunks at `merged` off the stack into the state's merged list,
+/// recording when they merged, and renumber what is left. Their branches and
+/// worktrees are deleted with `delete`; the branch names keep their old
+/// numbers, since open PRs are tied to them.
+fn take_merged(root: &Path, state: &mut MergesState, merged: &[usize], delete: bool) -> Result<()> {
+    let current = git::current_branch(root)?;
+    let mut taken = vec![];
+    for &i in merged.iter().rev() {
+        let mut chunk = state.chunks.remove(i);
+        chunk.merged_at = chunk.merged_at.or_else(|| Some(Utc::now()));
+        if delete {
+            if current == chunk.branch {
+                git::checkout(root, &state.source_branch)?;
+            }
+            if state.use_worktrees {
+                let _ = git::remove_worktree(root, &chunk.branch);
+            }
+            let _ = git::delete_branch(root, &chunk.branch);
+        }
+        let pr = chunk.pr_number.map(|n| format!(" (PR #{})", n)).unwrap_or_default();
+        say!("{} '{}' merged upstream{} — taken off the stack.", "✓".green(), chunk.name.cyan(), pr);
+        taken.insert(0, chunk);
+    }
+    if !state.chunks.is_empty() {
+        say!(
+            "  {} chunk(s) left; {} retargets and retitles their PRs to match.",
+            state.chunks.len().to_string().yellow(),
+            "merges push".bold()
+        );
+    }
+    state.merged.extend(taken.iter().cloned());
+    state.save(root)?;
+    journal::record(root, "merged", &format!("{} chunk(s) merged upstream", taken.len()), &taken)?;
     Ok(())
 }
@@ -0,0 +1,251 @@
+//! `merges verify`: build every chunk on its own with a command such as
+//! `cargo check`, and read the compiler errors of the chunks that fail for
+//! modules and files they cannot find because another chunk owns them.
+//!
+//! Only the errors that name a missing module or file are read — rustc's
+//! unresolved imports (E0432, E0433) and missing module files (E0583), and
+//! TypeScript's "Cannot find module" (TS2307) — and each is resolved the way
+//! [`imports`] resolves source, against the files of the other chunks.
+
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use serde::Serialize;
+use std::path::Path;
+use std::process::Command;
+
+use crate::{
+    autostash::Autostash,
+    commands::{exec, r#move},
+    imports, say,
+    state::MergesState,
+};
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VerifyOptions {
+    /// Suggest the moves that would make failing chunks self-contained.
+    pub suggest_moves: bool,
+    /// Apply the suggested moves.
+    pub fix: bool,
+    pub autostash: bool,
+}
+
+/// A file a failing chunk needs that another chunk owns.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SuggestedMove {
+    pub file: String,
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Default)]
+pub struct VerifyReport {
+    /// Chunks the command failed for.
+    pub failed: Vec<String>,
+    /// Moves suggested (or, with `fix`, made) for them.
+    pub moves: Vec<SuggestedMove>,
+}
+
+/// Run `command` for the named chunks — every chunk when `chunks` is empty —
+/// as `merges exec` does, and report the chunks it fails for. With
+/// `suggest_moves` or `fix`, their errors are read for references to files
+/// in other chunks; `fix` then moves each such file into the chunk needing it.
+pub fn run(root: &Path, command: &[String], chunks: &[String], opts: VerifyOptions) -> Result<VerifyReport> {
+    let state = MergesState::load(root)?;
+    let stash = Autostash::begin(root, &state, "verify", opts.autostash)?;
+    let failures = stash.finish(build(root, &state, command, chunks))?;
+
+    let mut report = VerifyReport {
+        failed: failures.iter().map(|(i, _)| state.chunks[*i].name.clone()).collect(),
+        moves: vec![],
+    };
+    if failures.is_empty() || !(opts.suggest_moves || opts.fix) {
+        return Ok(report);
+    }
+
+    report.moves = suggest_moves(&state, &failures);
+    if report.moves.is_empty() {
+        say!("No error names a file another chunk owns — the failures need fixing in the chunks themselves.");
+        return Ok(report);
+    }
+    say!("\nMoves that would make the failing chunks self-contained:");
+    for m in &report.moves {
+        say!("  merges move {} --from {} --to {}", m.file, m.from, m.to);
+    }
+    if opts.fix {
+        for m in &report.moves {
+            r#move::run(root, &m.file, &m.from, &m.to, opts.autostash)?;
+        }
+        say!(
+            "{} Moved {} file(s). Run `merges verify` again to check the chunks build.",
+            "✓".green().bold(),
+            report.moves.len()
+        );
+    }
+    Ok(report)
+}
+
+/// Run the command in each chunk, capturing its output. Returns the index of
+/// every chunk it failed for with that output, paths made relative to the
+/// chunk's checkout.
+fn build(root: &Path, state: &MergesState, command: &[String], only: &[String]) -> Result<Vec<(usize, String)>> {
+    let Some((program, args)) = command.split_first() else { bail!("No command given.") };
+
+    let mut failures = vec![];
+    exec::for_each_chunk(root, state, only, |i, dir| {
+        let chunk = &state.chunks[i];
+        say!("{} [{}] {}", "→".blue().bold(), chunk.name.cyan(), command.join(" "));
+        let output = Command::new(program)
+            .args(args)
+            .current_dir(dir)
+            .envs(exec::chunk_env(state, i, dir))
+            .output()
+            .with_context(|| format!("Failed to run `{}`", program))?;
+        if output.status.success() {
+            say!("{} [{}] builds", "✓".green().bold(), chunk.name.cyan());
+        } else {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let text = format!("{}{}", stdout, String::from_utf8_lossy(&output.stderr));
+            say!("{} [{}] exited with {}\n{}", "✗".red().bold(), chunk.name.cyan(), output.status, text.trim_end());
+            failures.push((i, text.replace(&format!("{}/", dir.display()), "")));
+        }
+        Ok(())
+    })?;
+    Ok(failures)
+}
+
+/// For each failing chunk, the files of other chunks its errors refer to.
+/// A file two chunks need is suggested for the first of them.
+fn suggest_moves(state: &MergesState, failures: &[(usize, String)]) -> Vec<SuggestedMove> {
+    let mut moves: Vec<SuggestedMove> = vec![];
+    for (i, output) in failures {
+        let elsewhere: Vec<String> = state
+            .chunks
+            .iter()
+            .enumerate()
+            .filter(|&(j, _)| j != *i)
+            .flat_map(|(_, c)| c.files.iter().cloned())
+            .collect();
+        for (file, reference) in unresolved_references(output) {
+            for target in imports::imported_files(&file, &reference, &elsewhere) {
+                if moves.iter().any(|m| m.file == target) {
+                    continue;
+                }
+                let Some(owner) = state.chunks.iter().find(|c| c.files.contains(&target)) else { continue };
+                moves.push(SuggestedMove { file: target, from: owner.name.clone(), to: state.chunks[*i].name.clone() });
+            }
+        }
+    }
+    moves
+}
+
+/// References a build could not resolve, each as the file making it and a
+/// line of source restating it (`use …;`, `mod …;`, `import '…';`).
+fn unresolved_references(output: &str) -> Vec<(String, String)> {
+    let mut found = vec![];
+    // A rustc error names the reference first and its file on a later `-->` line.
+    let mut pending: Option<String> = None;
+    for line in output.lines().map(str::trim) {
+        if let Some(reference) = rust_reference(line) {
+            pending = Some(reference);
+        } else if let Some(location) = line.strip_prefix("--> ") {
+            found.extend(pending.take().map(|reference| (location_file(location).to_string(), reference)));
+        } else if let Some(reference) = script_reference(line) {
+            found.push(reference);
+        }
+    }
+    found
+}
+
+/// The reference a rustc error names, restated as Rust source.
+fn rust_reference(line: &str) -> Option<String> {
+    if let Some(rest) = line.strip_prefix("error[E0432]: unresolved import ") {
+        return Some(format!("use {};", backticked(rest)?));
+    }
+    if let Some(rest) = line.strip_prefix("error[E0583]: file not found for module ") {
+        return Some(format!("mod {};", backticked(rest)?));
+    }
+    // "use of undeclared crate or module", or "of unresolved module or unlinked crate" on newer rustc
+    let rest = line.strip_prefix("error[E0433]: failed to resolve: use of ")?;
+    if !rest.contains("module") {
+        return None;
+    }
+    Some(format!("use {};", backticked(rest)?))
+}
+
+/// The file and import a TypeScript "Cannot find module" error names, in
+/// either the `file(line,col): error …` or the `file:line:col - error …` form.
+fn script_reference(line: &str) -> Option<(String, String)> {
+    let (location, message) =
+        line.split_once(": error TS2307: ").or_else(|| line.split_once(" - error TS2307: "))?;
+    let spec = message.strip_prefix("Cannot find module '")?.split('\'').next()?;
+    let file = match location.strip_suffix(')') {
+        Some(location) => location.rsplit_once('(')?.0.trim_start_matches("./"),
+        None => location_file(location),
+    };
+    Some((file.to_string(), format!("import '{}';", spec)))
+}
+
+/// `src/lib.rs:3:5` → `src/lib.rs`.
+fn location_file(location: &str) -> &str {
+    location.rsplitn(3, ':').nth(2).unwrap_or(location).trim_start_matches("./")
+}
+
+/// The first name a compiler message puts in backticks.
+fn backticked(message: &str) -> Option<&str> {
+    message.split('`').nth(1).filter(|name| !name.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unresolved_references_from_rustc() {
+        let output = "\
+error[E0583]: file not found for module `payment`
+ --> src/models/mod.rs:3:1
+  |
+3 | mod payment;
+  | ^^^^^^^^^^^^
+error[E0432]: unresolved import `crate::models::payment`
+ --> src/api.rs:1:5
+error[E0433]: failed to resolve: use of undeclared crate or module `db`
+ --> src/main.rs:9:5
+error[E0425]: cannot find value `x` in this scope
+ --> src/api.rs:4:9
+";
+        assert_eq!(
+            unresolved_references(output),
+            vec![
+                ("src/models/mod.rs".to_string(), "mod payment;".to_string()),
+                ("src/api.rs".to_string(), "use crate::models::payment;".to_string()),
+                ("src/main.rs".to_string(), "use db;".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unresolved_references_from_tsc() {
+        let output = "\
+web/api/client.ts(3,22): error TS2307: Cannot find module '../models/user' or its corresponding type declarations.
+./web/form.tsx:1:18 - error TS2307: Cannot find module './client'.
+web/form.tsx(2,1): error TS2304: Cannot find name 'post'.
+";
+        assert_eq!(
+            unresolved_references(output),
+            vec![
+                ("web/api/client.ts".to_string(), "import '../models/user';".to_string()),
+                ("web/form.tsx".to_string(), "import './client';".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unresolved_references_resolve_to_changed_files() {
+        let files = vec!["src/models/payment.rs".to_string(), "web/models/user.ts".to_string()];
+        let rust = imports::imported_files("src/models/mod.rs", "mod payment;", &files);
+        assert_eq!(rust, vec!["src/models/payment.rs"]);
+        let script = imports::imported_files("web/api/client.ts", "import '../models/user';", &files);
+        assert_eq!(script, vec!["web/models/user.ts"]);
+    }
+}
@@ -1,7 +1,63 @@
 use anyhow::{Context, Result};
-use std::process::Command;
+use std::io::Write;
+use std::process::{Command, Stdio};
 
-/// Resolve a GitHub token — tries `gh auth token` first, then GITHUB_TOKEN env var.
+/// How merges authenticates to GitHub.
+#[derive(Debug, Clone)]
+pub enum GithubCredentials {
+    /// A personal access token (classic or fine-grained) or an OAuth token.
+    Token(String),
+    /// A GitHub App, acting through its installation on the repository.
+    App {
+        app_id: u64,
+        /// PEM-encoded RSA private key of the app.
+        private_key: String,
+        /// Installation to act as; looked up from the repository when unset.
+        installation_id: Option<u64>,
+    },
+}
+
+/// Resolve GitHub credentials: a GitHub App when one is configured (see
+/// [`github_app`]), otherwise a token from [`github_token`].
+pub fn github_credentials() -> Result<GithubCredentials> {
+    match github_app()? {
+        Some(app) => Ok(app),
+        None => github_token().map(GithubCredentials::Token),
+    }
+}
+
+/// Read GitHub App settings from `MERGES_GITHUB_APP_ID`,
+/// `MERGES_GITHUB_APP_KEY`, and `MERGES_GITHUB_APP_INSTALLATION`, or the git
+/// config keys `merges.githubAppId`, `merges.githubAppKey`, and
+/// `merges.githubAppInstallation`. The key is a path to the app's PEM file,
+/// or the PEM itself. `None` when no app id is set.
+pub fn github_app() -> Result<Option<GithubCredentials>> {
+    let root = crate::git::repo_root().unwrap_or_else(|_| ".".into());
+    let setting = |env: &str, key: &str| {
+        std::env::var(env).ok().filter(|v| !v.is_empty()).or_else(|| crate::git::config_value(&root, key))
+    };
+    let Some(app_id) = setting("MERGES_GITHUB_APP_ID", "merges.githubAppId") else {
+        return Ok(None);
+    };
+    let app_id = app_id.trim().parse().with_context(|| format!("GitHub App id '{}' is not a number", app_id))?;
+    let key = setting("MERGES_GITHUB_APP_KEY", "merges.githubAppKey").context(
+        "A GitHub App id is configured without a private key. Set MERGES_GITHUB_APP_KEY or `git config \
+         merges.githubAppKey` to the app's .pem file.",
+    )?;
+    let private_key = if key.trim_start().starts_with("-----BEGIN") {
+        key
+    } else {
+        std::fs::read_to_string(&key).with_context(|| format!("Failed to read GitHub App private key '{}'", key))?
+    };
+    let installation_id = setting("MERGES_GITHUB_APP_INSTALLATION", "merges.githubAppInstallation")
+        .map(|id| id.trim().parse().with_context(|| format!("GitHub App installation id '{}' is not a number", id)))
+        .transpose()?;
+    Ok(Some(GithubCredentials::App { app_id, private_key, installation_id }))
+}
+
+/// Resolve a GitHub token — tries `gh auth token` first, then the GITHUB_TOKEN
+/// env var, then whatever git's credential helper (a system keychain, Git
+/// Credential Manager, …) stores for github.com.
 pub fn github_token() -> Result<String> {
     // 1. Try gh CLI
     if let Ok(output) = Command::new("gh").args(["auth", "token"]).output() {
@@ -12,15 +68,108 @@ pub fn github_token() -> Result<String> {
     }
 
     // 2. Fall back to environment variable
-    std::env::var("GITHUB_TOKEN").context(
-        "No GitHub token found. Run `gh auth login` or set the GITHUB_TOKEN environment variable.",
+    if let Ok(token) = std::env::var("GITHUB_TOKEN") {
+        return Ok(token);
+    }
+
+    // 3. Ask git's credential helper, without letting it prompt
+    credential_helper_token("github.com").context(
+        "No GitHub token found. Run `gh auth login`, set the GITHUB_TOKEN environment variable, or store a token \
+         for github.com in git's credential helper.",
     )
 }
 
+/// The password git's credential helper has stored for `https://<host>`.
+/// Prompts are turned off, so a missing credential is `None` rather than a
+/// question on the terminal.
+fn credential_helper_token(host: &str) -> Option<String> {
+    let mut child = Command::new("git")
+        .args(["-c", "credential.interactive=never", "credential", "fill"])
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("GIT_ASKPASS", "true")
+        .env("GCM_INTERACTIVE", "never")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    child.stdin.take()?.write_all(format!("protocol=https\nhost={}\n\n", host).as_bytes()).ok()?;
+    let output = child.wait_with_output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    credential_password(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// The `password=` value of `git credential fill` output, if not empty.
+fn credential_password(output: &str) -> Option<String> {
+    output.lines().find_map(|l| l.strip_prefix("password=")).filter(|p| !p.is_empty()).map(String::from)
+}
+
+/// Credentials for a forge other than GitHub.
+#[derive(Debug, Clone)]
+pub enum Credentials {
+    /// Sent as `Authorization: Bearer <token>`.
+    Token(String),
+    /// Sent as HTTP Basic auth.
+    Basic { username: String, password: String },
+}
+
+/// Resolve Bitbucket credentials — `BITBUCKET_TOKEN` (a Cloud repository/workspace
+/// access token or a Server HTTP access token), else `BITBUCKET_USERNAME` plus
+/// `BITBUCKET_APP_PASSWORD`.
+pub fn bitbucket_credentials() -> Result<Credentials> {
+    if let Ok(token) = std::env::var("BITBUCKET_TOKEN") {
+        return Ok(Credentials::Token(token));
+    }
+    match (std::env::var("BITBUCKET_USERNAME"), std::env::var("BITBUCKET_APP_PASSWORD")) {
+        (Ok(username), Ok(password)) => Ok(Credentials::Basic { username, password }),
+        _ => anyhow::bail!(
+            "No Bitbucket credentials found. Set BITBUCKET_TOKEN, or BITBUCKET_USERNAME and BITBUCKET_APP_PASSWORD."
+        ),
+    }
+}
+
+/// Resolve a Gitea/Forgejo access token from `GITEA_TOKEN`.
+pub fn gitea_token() -> Result<String> {
+    std::env::var("GITEA_TOKEN")
+        .context("No Gitea token found. Set the GITEA_TOKEN environment variable to an access token.")
+}
+
+/// Resolve an Azure DevOps personal access token from `AZURE_DEVOPS_EXT_PAT`
+/// (the variable the `az devops` CLI extension reads).
+pub fn azure_devops_token() -> Result<String> {
+    std::env::var("AZURE_DEVOPS_EXT_PAT").context(
+        "No Azure DevOps token found. Set AZURE_DEVOPS_EXT_PAT to a personal access token with Code (Read & write) scope.",
+    )
+}
+
+/// The editor `merges edit` opens chunks in: `git config merges.editor`,
+/// else `$VISUAL`, else `$EDITOR`. It may carry arguments (`code --wait`).
+pub fn editor(root: &std::path::Path) -> Result<String> {
+    let env = |var: &str| std::env::var(var).ok().filter(|v| !v.trim().is_empty());
+    crate::git::config_value(root, "merges.editor")
+        .or_else(|| env("VISUAL"))
+        .or_else(|| env("EDITOR"))
+        .context("No editor configured. Set `git config merges.editor` (e.g. `code`), or $VISUAL or $EDITOR.")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_credential_password_reads_the_password_line() {
+        let output = "protocol=https\nhost=github.com\nusername=alice\npassword=ghp_abc123\n";
+        assert_eq!(credential_password(output).as_deref(), Some("ghp_abc123"));
+    }
+
+    #[test]
+    fn test_credential_password_missing_or_empty_is_none() {
+        assert_eq!(credential_password("protocol=https\nhost=github.com\n"), None);
+        assert_eq!(credential_password("username=\npassword=\n"), None);
+    }
+
     /// ❌ RED: When GITHUB_TOKEN is set and gh is unavailable/fails, should use env var.
     /// This test unsets any existing gh session and relies on env var only.
     #[test]
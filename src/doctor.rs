@@ -2,7 +2,14 @@ use anyhow::Result;
 use std::collections::HashSet;
 use std::path::Path;
 
-use crate::{git, state::MergesState};
+use crate::{
+    forge::{self, Access, Forge},
+    git, journal, split,
+    state::{chunk_branch_prefix, MergesState},
+};
+
+/// Oldest git merges works with: `git rebase --update-refs` arrived in 2.38.
+pub const MIN_GIT_VERSION: (u32, u32) = (2, 38);
 
 /// Result of a doctor run: a list of human-readable issues found.
 #[derive(Debug)]
@@ -18,17 +25,12 @@ impl DoctorReport {
 
 /// Validate state consistency. If `repair` is true, attempt to fix issues in place.
 pub fn run(root: &Path, repair: bool) -> Result<DoctorReport> {
-    let state = MergesState::load(root)?;
+    let mut state = MergesState::load(root)?;
     let mut issues = Vec::new();
 
     // 1. Check each chunk branch exists locally
     for chunk in &state.chunks {
-        let out = std::process::Command::new("git")
-            .args(["branch", "--list", &chunk.branch])
-            .current_dir(root)
-            .output()?;
-        let output = String::from_utf8_lossy(&out.stdout);
-        if output.trim().is_empty() {
+        if !git::branch_exists(root, &chunk.branch) {
             issues.push(format!("Chunk branch '{}' does not exist locally.", chunk.branch));
         }
     }
@@ -71,5 +73,180 @@ pub fn run(root: &Path, repair: bool) -> Result<DoctorReport> {
         }
     }
 
+    // 5. Check the source branch wasn't rewritten under the chunks
+    if let Some(issue) = source_divergence(root, &state) {
+        issues.push(issue);
+    }
+
+    // 6. Check chunk branches live under the branch namespace, if one is set
+    if let Some(namespace) = state.branch_namespace.clone() {
+        let prefix = chunk_branch_prefix(&state.source_branch, Some(&namespace));
+        let legacy = chunk_branch_prefix(&state.source_branch, None);
+        let mut renamed = vec![];
+        for i in 0..state.chunks.len() {
+            let chunk = &state.chunks[i];
+            if chunk.branch.starts_with(&prefix) {
+                continue;
+            }
+            if let Some(pr) = chunk.pr_number {
+                issues.push(format!(
+                    "Chunk branch '{}' is outside namespace '{}' but has PR #{}; it is left where it is so the PR keeps its branch.",
+                    chunk.branch, namespace, pr
+                ));
+                continue;
+            }
+            let from = chunk.branch.clone();
+            let to = match from.strip_prefix(&legacy) {
+                Some(rest) => format!("{}{}", prefix, rest),
+                None => state.chunk_branch(i + 1, &chunk.name),
+            };
+            issues.push(format!("Chunk branch '{}' is outside namespace '{}' (repair renames it to '{}').", from, namespace, to));
+            let exists = git::rev_parse(root, &format!("refs/heads/{}", from)).is_ok();
+            if repair && exists && split::branch_collision(root, &to).is_none() {
+                git::rename_branch(root, &from, &to)?;
+                if state.use_worktrees {
+                    git::move_worktree(root, &from, &to)?;
+                }
+                state.chunks[i].branch = to;
+                renamed.push(state.chunks[i].clone());
+                issues.pop(); // resolved
+            }
+        }
+        if !renamed.is_empty() {
+            state.save(root)?;
+            journal::record(root, "rename", &format!("{} chunk branch(es) into '{}'", renamed.len(), namespace), &renamed)?;
+        }
+    }
+
+    // 7. Check every chunk worktree has rerere on when the repo does, so
+    //    they all record into and replay from the shared rr-cache
+    let rerere = |dir: &Path| git::config_value(dir, "rerere.enabled").as_deref() == Some("true");
+    if state.use_worktrees && rerere(root) {
+        for chunk in &state.chunks {
+            let wt = git::worktree_path(root, &chunk.branch);
+            if wt.exists() && !rerere(&wt) {
+                issues.push(format!(
+                    "rerere is disabled in the worktree of '{}' — it neither records nor replays conflict resolutions.",
+                    chunk.branch
+                ));
+                if repair {
+                    git::enable_worktree_rerere(&wt)?;
+                    issues.pop(); // resolved
+                }
+            }
+        }
+    }
+
     Ok(DoctorReport { issues })
 }
+
+/// [`run`], then [`environment`]: the state and everything merges needs
+/// around it, as `merges doctor` reports them.
+pub async fn run_with_environment(root: &Path, repair: bool) -> Result<DoctorReport> {
+    let mut report = run(root, repair)?;
+    report.issues.extend(environment(root, repair).await?);
+    Ok(report)
+}
+
+/// Check what merges needs from the machine, the remote, and the forge
+/// rather than from the state: a recent enough git, rerere turned on,
+/// origin reachable with the base branch on it, and forge credentials that
+/// work and may open PRs. With `repair`, rerere is turned on.
+pub async fn environment(root: &Path, repair: bool) -> Result<Vec<String>> {
+    let state = MergesState::load(root)?;
+    let mut issues = local_environment(root, &state, repair)?;
+    match forge::connect(&state).await {
+        Ok(forge) => issues.extend(forge_access(forge.as_ref(), &state).await),
+        Err(e) => issues.push(credentials_issue(&state, &e)),
+    }
+    Ok(issues)
+}
+
+/// [`environment`], checking credentials through `forge` instead of the
+/// backend configured in `.merges.json`.
+pub async fn environment_with(root: &Path, repair: bool, forge: &dyn Forge) -> Result<Vec<String>> {
+    let state = MergesState::load(root)?;
+    let mut issues = local_environment(root, &state, repair)?;
+    issues.extend(forge_access(forge, &state).await);
+    Ok(issues)
+}
+
+fn local_environment(root: &Path, state: &MergesState, repair: bool) -> Result<Vec<String>> {
+    let mut issues = Vec::new();
+
+    let (major, minor) = git::version()?;
+    if (major, minor) < MIN_GIT_VERSION {
+        issues.push(format!(
+            "git {}.{} is older than {}.{} — `merges sync` needs `git rebase --update-refs`. Upgrade git.",
+            major, minor, MIN_GIT_VERSION.0, MIN_GIT_VERSION.1
+        ));
+    }
+
+    if git::config_value(root, "rerere.enabled").as_deref() != Some("true") {
+        issues.push(
+            "rerere is disabled — conflict resolutions are not replayed on the next sync (repair enables it).".to_string(),
+        );
+        if repair {
+            git::enable_rerere(root)?;
+            issues.pop(); // resolved
+        }
+    }
+
+    match git::remote_has_branch(root, &state.base_branch) {
+        Ok(true) => {}
+        Ok(false) => issues.push(format!(
+            "Base branch '{}' does not exist on origin — push it, or pick another with `merges init --base`.",
+            state.base_branch
+        )),
+        Err(e) => {
+            issues.push(format!("origin is unreachable: {} — check the remote URL, network, and git credentials.", e))
+        }
+    }
+
+    Ok(issues)
+}
+
+/// Make a read-only call to confirm the credentials are accepted, then check
+/// they may open and update PRs.
+async fn forge_access(forge: &dyn Forge, state: &MergesState) -> Vec<String> {
+    if let Err(e) = forge.find_open_pr(&state.source_branch).await {
+        return vec![format!(
+            "{} rejected the request: {:#} — check the token and that it can see {}/{}.",
+            forge.name(),
+            e,
+            state.repo_owner,
+            state.repo_name
+        )];
+    }
+    match forge.missing_permissions(&[Access::PullRequests]).await {
+        Ok(missing) => missing.into_iter().map(|m| format!("Credentials cannot {}.", m)).collect(),
+        Err(e) => vec![format!("Could not check {} permissions: {:#}", forge.name(), e)],
+    }
+}
+
+/// Explain why no forge client could be built, pointing at the GitHub CLI
+/// when it is missing.
+fn credentials_issue(state: &MergesState, error: &anyhow::Error) -> String {
+    let gh_missing = state.forge.is_github() && std::process::Command::new("gh").arg("--version").output().is_err();
+    if gh_missing {
+        format!("{:#} The GitHub CLI (gh) is not installed — install it and run `gh auth login`.", error)
+    } else {
+        format!("{:#}", error)
+    }
+}
+
+/// Describe how the source branch diverged from the commit the chunks were
+/// built from, if it was rebased or force-pushed since. New commits on top
+/// of that commit are not divergence.
+pub fn source_divergence(root: &Path, state: &MergesState) -> Option<String> {
+    let built_from = state.source_sha.as_deref()?;
+    if git::rev_parse(root, &state.source_branch).is_err() || git::is_ancestor(root, built_from, &state.source_branch) {
+        return None;
+    }
+    Some(format!(
+        "Source branch '{}' was rewritten since the chunks were built from {} — their contents may no longer match it. \
+         Run `merges refresh` to compare, or `merges refresh --rebuild` to rebuild them.",
+        state.source_branch,
+        &built_from[..built_from.len().min(7)]
+    ))
+}
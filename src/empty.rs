@@ -0,0 +1,93 @@
+//! Chunks left without changes. When the base branch picks up a chunk's
+//! changes on its own (say, the same fix merged upstream), rebasing drops the
+//! chunk's commit and its branch ends up identical to its parent, so its PR
+//! shows an empty diff. `push` and `sync` look for such chunks after
+//! rebasing and either skip or drop them.
+
+use anyhow::Result;
+use colored::Colorize;
+use dialoguer::Confirm;
+use std::path::Path;
+
+use crate::{
+    git, journal, prompt, say,
+    state::{Chunk, MergesState, Strategy},
+    warning,
+};
+
+/// What chunk `i`'s changes are measured against: the previous chunk's branch
+/// when stacked, otherwise the base branch on origin.
+pub fn parent_ref(state: &MergesState, i: usize, strategy: &Strategy) -> String {
+    match strategy {
+        Strategy::Stacked if i > 0 => state.chunks[i - 1].branch.clone(),
+        _ => format!("origin/{}", state.base_branch),
+    }
+}
+
+/// Indices of chunks whose branch changes nothing against its parent.
+pub fn find(root: &Path, state: &MergesState, strategy: &Strategy) -> Result<Vec<usize>> {
+    let mut empty = vec![];
+    for (i, chunk) in state.chunks.iter().enumerate() {
+        if git::diff_files(root, &parent_ref(state, i, strategy), &chunk.branch)?.is_empty() {
+            empty.push(i);
+        }
+    }
+    Ok(empty)
+}
+
+/// Print one warning line per empty chunk.
+pub fn warn(state: &MergesState, empty: &[usize], strategy: &Strategy) {
+    for &i in empty {
+        warning!(
+            "Chunk '{}' has no changes against '{}' — its files are already there.",
+            state.chunks[i].name.cyan(),
+            parent_ref(state, i, strategy)
+        );
+    }
+}
+
+/// Whether to drop the empty chunks: always with `drop`, otherwise only if
+/// the user agrees when asked.
+pub fn confirm_drop(count: usize, drop: bool) -> Result<bool> {
+    if drop {
+        return Ok(true);
+    }
+    if !prompt::allowed() || crate::output::json() {
+        return Ok(false);
+    }
+    Ok(Confirm::new()
+        .with_prompt(format!("Drop {} empty chunk(s) from the stack?", count))
+        .default(false)
+        .interact()?)
+}
+
+/// Remove the chunks at `empty` from the stack and delete their local
+/// branches (and worktrees). Remote branches and open PRs are left for the
+/// user to close. Returns the dropped chunks.
+pub fn drop_chunks(root: &Path, state: &mut MergesState, empty: &[usize]) -> Result<Vec<Chunk>> {
+    let current = git::current_branch(root)?;
+    let mut dropped = vec![];
+    for &i in empty.iter().rev() {
+        let chunk = state.chunks.remove(i);
+        if current == chunk.branch {
+            git::checkout(root, &state.source_branch)?;
+        }
+        if state.use_worktrees {
+            let _ = git::remove_worktree(root, &chunk.branch);
+        }
+        let _ = git::delete_branch(root, &chunk.branch);
+        match chunk.pr_number {
+            Some(pr) => say!(
+                "{} Dropped empty chunk '{}' — close PR #{} by hand.",
+                "✓".green(),
+                chunk.name.cyan(),
+                pr
+            ),
+            None => say!("{} Dropped empty chunk '{}'", "✓".green(), chunk.name.cyan()),
+        }
+        dropped.insert(0, chunk);
+    }
+    state.save(root)?;
+    journal::record(root, "drop", &format!("{} empty chunk(s)", dropped.len()), &dropped)?;
+    Ok(dropped)
+}
@@ -0,0 +1,261 @@
+//! Azure DevOps backend (Git REST API 7.1). Repositories live under an
+//! organisation and project: `owner` holds the project and the API base URL
+//! carries the organisation.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use octocrab::Octocrab;
+use serde_json::{json, Value};
+
+use super::{aggregate_checks, parse_timestamp, CheckOutcome, CheckResult, Forge, PrInfo};
+
+const API_VERSION: &str = "api-version=7.1";
+
+pub struct AzureDevOpsForge {
+    client: Octocrab,
+    /// `https://dev.azure.com/{org}` (or the legacy `https://{org}.visualstudio.com`).
+    org_url: String,
+    project: String,
+    repo: String,
+}
+
+impl AzureDevOpsForge {
+    /// Authenticates with a personal access token sent as Basic auth with an
+    /// empty username, as Azure DevOps expects.
+    pub fn new(org_url: &str, token: String, project: &str, repo: &str) -> Result<Self> {
+        let org_url = org_url.trim_end_matches('/').to_string();
+        let client = Octocrab::builder()
+            .base_uri(org_url.as_str())
+            .with_context(|| format!("Invalid Azure DevOps URL: {}", org_url))?
+            .basic_auth(String::new(), token)
+            .build()
+            .context("Failed to build Azure DevOps client")?;
+        Ok(Self { client, org_url, project: project.to_string(), repo: repo.to_string() })
+    }
+
+    fn prs_route(&self) -> String {
+        format!("/{}/_apis/git/repositories/{}/pullrequests", self.project, self.repo)
+    }
+
+    fn pr_route(&self, pr_number: u64, suffix: &str) -> String {
+        format!("{}/{}{}?{}", self.prs_route(), pr_number, suffix, API_VERSION)
+    }
+
+    /// Web URL of a PR — the API only returns its own REST URL.
+    fn web_url(&self, pr_number: u64) -> String {
+        format!("{}/{}/_git/{}/pullrequest/{}", self.org_url, self.project, self.repo, pr_number)
+    }
+}
+
+#[async_trait]
+impl Forge for AzureDevOpsForge {
+    fn name(&self) -> &'static str {
+        "Azure DevOps"
+    }
+
+    async fn find_open_pr(&self, head: &str) -> Result<Option<(u64, String)>> {
+        let params = [
+            ("searchCriteria.sourceRefName", format!("refs/heads/{}", head)),
+            ("searchCriteria.status", "active".to_string()),
+            ("$top", "1".to_string()),
+            ("api-version", "7.1".to_string()),
+        ];
+        let page: Value = self
+            .client
+            .get(self.prs_route(), Some(&params))
+            .await
+            .with_context(|| format!("Failed to look up open PRs for '{}'", head))?;
+        Ok(page["value"]
+            .as_array()
+            .and_then(|prs| prs.first())
+            .and_then(|pr| pr["pullRequestId"].as_u64())
+            .map(|number| (number, self.web_url(number))))
+    }
+
+    async fn create_pr(&self, title: &str, head: &str, base: &str, body: &str) -> Result<(u64, String)> {
+        let payload = json!({
+            "title": title,
+            "description": body,
+            "sourceRefName": format!("refs/heads/{}", head),
+            "targetRefName": format!("refs/heads/{}", base),
+        });
+        let pr: Value = self
+            .client
+            .post(format!("{}?{}", self.prs_route(), API_VERSION), Some(&payload))
+            .await
+            .with_context(|| format!("Failed to create PR '{}'", title))?;
+
+        let number = pr["pullRequestId"].as_u64().context("Azure DevOps response has no pullRequestId")?;
+        Ok((number, self.web_url(number)))
+    }
+
+    async fn update_pr_base(&self, pr_number: u64, new_base: &str) -> Result<()> {
+        let payload = json!({ "targetRefName": format!("refs/heads/{}", new_base) });
+        let _: Value = self
+            .client
+            .patch(self.pr_route(pr_number, ""), Some(&payload))
+            .await
+            .with_context(|| format!("Failed to update base for PR #{}", pr_number))?;
+        Ok(())
+    }
+
+    async fn update_pr(&self, pr_number: u64, title: &str, body: Option<&str>) -> Result<()> {
+        let mut payload = json!({ "title": title });
+        if let Some(body) = body {
+            payload["description"] = json!(body);
+        }
+        let _: Value = self
+            .client
+            .patch(self.pr_route(pr_number, ""), Some(&payload))
+            .await
+            .with_context(|| format!("Failed to update PR #{}", pr_number))?;
+        Ok(())
+    }
+
+    async fn get_pr_info(&self, pr_number: u64) -> Result<PrInfo> {
+        let pr: Value = self
+            .client
+            .get(self.pr_route(pr_number, ""), None::<&()>)
+            .await
+            .with_context(|| format!("Failed to fetch PR #{}", pr_number))?;
+        let mut info = pr_info(&pr, pr_number);
+        info.url = self.web_url(pr_number);
+
+        let statuses: Result<Value> = self
+            .client
+            .get(self.pr_route(pr_number, "/statuses"), None::<&()>)
+            .await
+            .context("Failed to fetch PR statuses");
+        (info.ci_status, info.failing_checks) = match statuses {
+            Ok(v) => aggregate_checks(&pr_statuses(&v)),
+            Err(_) => ("unknown".to_string(), vec![]),
+        };
+        Ok(info)
+    }
+}
+
+/// Map an Azure DevOps PR payload to `PrInfo` (URL and CI fields are filled in separately).
+pub fn pr_info(pr: &Value, pr_number: u64) -> PrInfo {
+    let raw_status = pr["status"].as_str().unwrap_or("unknown");
+    let state = match raw_status {
+        "active" => "open",
+        "completed" => "merged",
+        "abandoned" => "closed",
+        _ => "unknown",
+    }
+    .to_string();
+
+    PrInfo {
+        number: pr_number,
+        url: String::new(),
+        title: pr["title"].as_str().unwrap_or_default().to_string(),
+        body: pr["description"].as_str().unwrap_or_default().to_string(),
+        state,
+        merged: raw_status == "completed",
+        merged_at: if raw_status == "completed" { parse_timestamp(&pr["closedDate"]) } else { None },
+        base: pr["targetRefName"]
+            .as_str()
+            .unwrap_or_default()
+            .trim_start_matches("refs/heads/")
+            .to_string(),
+        head_sha: pr["lastMergeSourceCommit"]["commitId"].as_str().unwrap_or_default().to_string(),
+        ci_status: "unknown".to_string(),
+        failing_checks: vec![],
+        review_state: review_state(pr),
+    }
+}
+
+/// Collapse reviewer votes (10 approved, 5 approved with suggestions, -5 waiting
+/// for author, -10 rejected) into the same labels the GitHub backend uses.
+fn review_state(pr: &Value) -> String {
+    let empty = vec![];
+    let votes: Vec<i64> = pr["reviewers"]
+        .as_array()
+        .unwrap_or(&empty)
+        .iter()
+        .filter_map(|r| r["vote"].as_i64())
+        .collect();
+
+    if votes.iter().any(|v| *v < 0) {
+        "changes_requested".to_string()
+    } else if votes.iter().any(|v| *v > 0) {
+        "approved".to_string()
+    } else {
+        "pending".to_string()
+    }
+}
+
+/// Map a PR status listing (`{"value": [...]}`) to check results. Statuses that
+/// don't apply to the PR are dropped.
+pub fn pr_statuses(v: &Value) -> Vec<CheckResult> {
+    let empty = vec![];
+    v["value"]
+        .as_array()
+        .unwrap_or(&empty)
+        .iter()
+        .filter(|s| s["state"].as_str() != Some("notApplicable"))
+        .map(|s| {
+            let context = &s["context"];
+            let name = match (context["genre"].as_str(), context["name"].as_str()) {
+                (Some(genre), Some(name)) if !genre.is_empty() => format!("{}/{}", genre, name),
+                (_, name) => name.unwrap_or_default().to_string(),
+            };
+            CheckResult {
+                name,
+                outcome: match s["state"].as_str() {
+                    Some("succeeded") => CheckOutcome::Success,
+                    Some("failed" | "error") => CheckOutcome::Failure,
+                    _ => CheckOutcome::Pending,
+                },
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pr_info_maps_fields() {
+        let pr = json!({
+            "pullRequestId": 21,
+            "title": "[1/2] models",
+            "status": "completed",
+            "targetRefName": "refs/heads/main",
+            "sourceRefName": "refs/heads/feat/big-chunk-1-models",
+            "lastMergeSourceCommit": { "commitId": "abc123" },
+            "reviewers": [{ "vote": 10 }, { "vote": 0 }]
+        });
+        let info = pr_info(&pr, 21);
+        assert_eq!(info.state, "merged");
+        assert!(info.merged);
+        assert_eq!(info.base, "main");
+        assert_eq!(info.head_sha, "abc123");
+        assert_eq!(info.review_state, "approved");
+    }
+
+    /// An abandoned PR looks closed but must not count as merged.
+    #[test]
+    fn test_abandoned_pr_is_closed_not_merged() {
+        let pr = json!({ "status": "abandoned", "reviewers": [{ "vote": -10 }, { "vote": 10 }] });
+        let info = pr_info(&pr, 3);
+        assert_eq!(info.state, "closed");
+        assert!(!info.merged);
+        assert_eq!(info.review_state, "changes_requested");
+    }
+
+    #[test]
+    fn test_pr_statuses_map_to_check_outcomes() {
+        let v = json!({ "value": [
+            { "state": "succeeded", "context": { "genre": "ci", "name": "build" } },
+            { "state": "failed", "context": { "genre": "ci", "name": "test" } },
+            { "state": "notApplicable", "context": { "name": "docs" } }
+        ]});
+        let checks = pr_statuses(&v);
+        assert_eq!(checks.len(), 2);
+        let (state, failing) = aggregate_checks(&checks);
+        assert_eq!(state, "failure");
+        assert_eq!(failing, vec!["ci/test"]);
+    }
+}
@@ -0,0 +1,357 @@
+//! Bitbucket backend for both Bitbucket Cloud (REST 2.0) and Bitbucket
+//! Server/Data Center (REST 1.0). The two APIs differ in routes and payload
+//! shapes, so each operation branches on `server`.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use octocrab::Octocrab;
+use serde_json::{json, Value};
+
+use super::{aggregate_checks, parse_timestamp, CheckOutcome, CheckResult, Forge, PrInfo};
+use crate::config::Credentials;
+
+/// API base used for Bitbucket Cloud.
+pub const CLOUD_API_URL: &str = "https://api.bitbucket.org/2.0";
+
+pub struct BitbucketForge {
+    client: Octocrab,
+    owner: String,
+    repo: String,
+    server: bool,
+}
+
+impl BitbucketForge {
+    /// `api_url` is `https://api.bitbucket.org/2.0` for Cloud, or the server's
+    /// base URL (including any context path) for Bitbucket Server.
+    pub fn new(api_url: &str, credentials: Credentials, owner: &str, repo: &str, server: bool) -> Result<Self> {
+        let builder = Octocrab::builder()
+            .base_uri(api_url)
+            .with_context(|| format!("Invalid Bitbucket API URL: {}", api_url))?;
+        let builder = match credentials {
+            Credentials::Token(token) => builder.personal_token(token),
+            Credentials::Basic { username, password } => builder.basic_auth(username, password),
+        };
+        let client = builder.build().context("Failed to build Bitbucket client")?;
+        Ok(Self { client, owner: owner.to_string(), repo: repo.to_string(), server })
+    }
+
+    fn prs_route(&self) -> String {
+        if self.server {
+            format!("/rest/api/1.0/projects/{}/repos/{}/pull-requests", self.owner, self.repo)
+        } else {
+            format!("/repositories/{}/{}/pullrequests", self.owner, self.repo)
+        }
+    }
+
+    fn pr_route(&self, pr_number: u64) -> String {
+        format!("{}/{}", self.prs_route(), pr_number)
+    }
+
+    fn statuses_route(&self, sha: &str) -> String {
+        if self.server {
+            format!("/rest/build-status/1.0/commits/{}", sha)
+        } else {
+            format!("/repositories/{}/{}/commit/{}/statuses", self.owner, self.repo, sha)
+        }
+    }
+
+    async fn fetch_pr(&self, pr_number: u64) -> Result<Value> {
+        self.client
+            .get(self.pr_route(pr_number), None::<&()>)
+            .await
+            .with_context(|| format!("Failed to fetch PR #{}", pr_number))
+    }
+}
+
+#[async_trait]
+impl Forge for BitbucketForge {
+    fn name(&self) -> &'static str {
+        if self.server { "Bitbucket Server" } else { "Bitbucket" }
+    }
+
+    async fn find_open_pr(&self, head: &str) -> Result<Option<(u64, String)>> {
+        let params: Vec<(&str, String)> = if self.server {
+            vec![
+                ("at", format!("refs/heads/{}", head)),
+                ("direction", "OUTGOING".to_string()),
+                ("state", "OPEN".to_string()),
+                ("limit", "1".to_string()),
+            ]
+        } else {
+            vec![
+                ("q", format!("source.branch.name=\"{}\" AND state=\"OPEN\"", head)),
+                ("pagelen", "1".to_string()),
+            ]
+        };
+        let page: Value = self
+            .client
+            .get(self.prs_route(), Some(&params))
+            .await
+            .with_context(|| format!("Failed to look up open PRs for '{}'", head))?;
+        Ok(page["values"]
+            .as_array()
+            .and_then(|prs| prs.first())
+            .and_then(|pr| Some((pr["id"].as_u64()?, pr_url(pr, self.server)))))
+    }
+
+    async fn create_pr(&self, title: &str, head: &str, base: &str, body: &str) -> Result<(u64, String)> {
+        let payload = if self.server {
+            json!({
+                "title": title,
+                "description": body,
+                "fromRef": { "id": format!("refs/heads/{}", head) },
+                "toRef": { "id": format!("refs/heads/{}", base) },
+            })
+        } else {
+            json!({
+                "title": title,
+                "description": body,
+                "source": { "branch": { "name": head } },
+                "destination": { "branch": { "name": base } },
+            })
+        };
+        let pr: Value = self
+            .client
+            .post(self.prs_route(), Some(&payload))
+            .await
+            .with_context(|| format!("Failed to create PR '{}'", title))?;
+
+        let number = pr["id"].as_u64().context("Bitbucket response has no PR id")?;
+        Ok((number, pr_url(&pr, self.server)))
+    }
+
+    async fn update_pr_base(&self, pr_number: u64, new_base: &str) -> Result<()> {
+        // Both APIs expect the title alongside the change; Server also needs the
+        // current version for optimistic locking.
+        let pr = self.fetch_pr(pr_number).await?;
+        let payload = if self.server {
+            json!({
+                "version": pr["version"],
+                "title": pr["title"],
+                "toRef": { "id": format!("refs/heads/{}", new_base) },
+            })
+        } else {
+            json!({
+                "title": pr["title"],
+                "destination": { "branch": { "name": new_base } },
+            })
+        };
+        let _: Value = self
+            .client
+            .put(self.pr_route(pr_number), Some(&payload))
+            .await
+            .with_context(|| format!("Failed to update base for PR #{}", pr_number))?;
+        Ok(())
+    }
+
+    async fn update_pr(&self, pr_number: u64, title: &str, body: Option<&str>) -> Result<()> {
+        let mut payload = if self.server {
+            let pr = self.fetch_pr(pr_number).await?;
+            json!({ "version": pr["version"], "title": title })
+        } else {
+            json!({ "title": title })
+        };
+        if let Some(body) = body {
+            payload["description"] = json!(body);
+        }
+        let _: Value = self
+            .client
+            .put(self.pr_route(pr_number), Some(&payload))
+            .await
+            .with_context(|| format!("Failed to update PR #{}", pr_number))?;
+        Ok(())
+    }
+
+    async fn get_pr_info(&self, pr_number: u64) -> Result<PrInfo> {
+        let pr = self.fetch_pr(pr_number).await?;
+        let mut info = pr_info(&pr, pr_number, self.server);
+
+        let statuses: Result<Value> = self
+            .client
+            .get(self.statuses_route(&info.head_sha), None::<&()>)
+            .await
+            .context("Failed to fetch build statuses");
+        (info.ci_status, info.failing_checks) = match statuses {
+            Ok(v) => aggregate_checks(&build_statuses(&v)),
+            Err(_) => ("unknown".to_string(), vec![]),
+        };
+        Ok(info)
+    }
+}
+
+/// Web URL of a PR from either API's `links` object.
+fn pr_url(pr: &Value, server: bool) -> String {
+    let url = if server {
+        pr["links"]["self"][0]["href"].as_str()
+    } else {
+        pr["links"]["html"]["href"].as_str()
+    };
+    url.unwrap_or_default().to_string()
+}
+
+/// Map a Bitbucket PR payload to `PrInfo` (CI fields are filled in separately).
+pub fn pr_info(pr: &Value, pr_number: u64, server: bool) -> PrInfo {
+    let raw_state = pr["state"].as_str().unwrap_or("unknown");
+    let state = match raw_state {
+        "OPEN" => "open",
+        "MERGED" => "merged",
+        "DECLINED" | "SUPERSEDED" => "closed",
+        _ => "unknown",
+    }
+    .to_string();
+
+    // Neither API has a dedicated merge time; a merged PR's last update is its merge.
+    let merged_at = match (raw_state, server) {
+        ("MERGED", true) => parse_timestamp(&pr["closedDate"]),
+        ("MERGED", false) => parse_timestamp(&pr["updated_on"]),
+        _ => None,
+    };
+
+    let (base, head_sha) = if server {
+        (
+            pr["toRef"]["displayId"].as_str().unwrap_or_default(),
+            pr["fromRef"]["latestCommit"].as_str().unwrap_or_default(),
+        )
+    } else {
+        (
+            pr["destination"]["branch"]["name"].as_str().unwrap_or_default(),
+            pr["source"]["commit"]["hash"].as_str().unwrap_or_default(),
+        )
+    };
+
+    PrInfo {
+        number: pr_number,
+        url: pr_url(pr, server),
+        title: pr["title"].as_str().unwrap_or_default().to_string(),
+        body: pr["description"].as_str().unwrap_or_default().to_string(),
+        merged: raw_state == "MERGED",
+        merged_at,
+        state,
+        base: base.to_string(),
+        head_sha: head_sha.to_string(),
+        ci_status: "unknown".to_string(),
+        failing_checks: vec![],
+        review_state: review_state(pr, server),
+    }
+}
+
+/// Collapse reviewer decisions into the same labels the GitHub backend uses.
+fn review_state(pr: &Value, server: bool) -> String {
+    let empty = vec![];
+    let decisions: Vec<&str> = if server {
+        pr["reviewers"].as_array().unwrap_or(&empty).iter().map(|r| {
+            match r["status"].as_str() {
+                Some("APPROVED") => "approved",
+                Some("NEEDS_WORK") => "changes_requested",
+                _ => "pending",
+            }
+        }).collect()
+    } else {
+        pr["participants"].as_array().unwrap_or(&empty).iter().map(|p| {
+            if p["approved"].as_bool() == Some(true) {
+                "approved"
+            } else if p["state"].as_str() == Some("changes_requested") {
+                "changes_requested"
+            } else {
+                "pending"
+            }
+        }).collect()
+    };
+
+    if decisions.contains(&"approved") {
+        "approved".to_string()
+    } else if decisions.contains(&"changes_requested") {
+        "changes_requested".to_string()
+    } else {
+        "pending".to_string()
+    }
+}
+
+/// Map a build-status listing (`{"values": [...]}`) to check results.
+pub fn build_statuses(v: &Value) -> Vec<CheckResult> {
+    let empty = vec![];
+    v["values"]
+        .as_array()
+        .unwrap_or(&empty)
+        .iter()
+        .map(|s| CheckResult {
+            name: s["name"].as_str().or(s["key"].as_str()).unwrap_or_default().to_string(),
+            outcome: match s["state"].as_str() {
+                Some("SUCCESSFUL") => CheckOutcome::Success,
+                Some("FAILED") | Some("STOPPED") => CheckOutcome::Failure,
+                _ => CheckOutcome::Pending,
+            },
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cloud_pr_info_maps_fields() {
+        let pr = json!({
+            "id": 7,
+            "title": "[1/2] models",
+            "state": "MERGED",
+            "updated_on": "2026-03-02T10:15:00.123456+00:00",
+            "links": { "html": { "href": "https://bitbucket.org/acme/myrepo/pull-requests/7" } },
+            "source": { "branch": { "name": "feat/big-chunk-1-models" }, "commit": { "hash": "abc123" } },
+            "destination": { "branch": { "name": "main" } },
+            "participants": [{ "role": "REVIEWER", "approved": true, "state": "approved" }]
+        });
+        let info = pr_info(&pr, 7, false);
+        assert_eq!(info.state, "merged");
+        assert!(info.merged);
+        assert!(info.merged_at.is_some());
+        assert_eq!(info.base, "main");
+        assert_eq!(info.head_sha, "abc123");
+        assert_eq!(info.url, "https://bitbucket.org/acme/myrepo/pull-requests/7");
+        assert_eq!(info.review_state, "approved");
+    }
+
+    /// A declined PR looks closed but must not count as merged.
+    #[test]
+    fn test_declined_pr_is_closed_not_merged() {
+        let pr = json!({ "state": "DECLINED", "participants": [] });
+        let info = pr_info(&pr, 3, false);
+        assert_eq!(info.state, "closed");
+        assert!(!info.merged);
+        assert_eq!(info.review_state, "pending");
+    }
+
+    #[test]
+    fn test_server_pr_info_maps_fields() {
+        let pr = json!({
+            "id": 12,
+            "version": 3,
+            "title": "[2/2] api",
+            "state": "OPEN",
+            "links": { "self": [{ "href": "https://git.corp.example/projects/PROJ/repos/myrepo/pull-requests/12" }] },
+            "fromRef": { "displayId": "feat/big-chunk-2-api", "latestCommit": "def456" },
+            "toRef": { "displayId": "feat/big-chunk-1-models" },
+            "reviewers": [{ "status": "NEEDS_WORK" }, { "status": "UNAPPROVED" }]
+        });
+        let info = pr_info(&pr, 12, true);
+        assert_eq!(info.state, "open");
+        assert_eq!(info.base, "feat/big-chunk-1-models");
+        assert_eq!(info.head_sha, "def456");
+        assert!(info.url.ends_with("/pull-requests/12"));
+        assert_eq!(info.review_state, "changes_requested");
+    }
+
+    #[test]
+    fn test_build_statuses_map_to_check_outcomes() {
+        let v = json!({ "values": [
+            { "key": "build", "name": "Build", "state": "SUCCESSFUL" },
+            { "key": "lint", "name": "Lint", "state": "FAILED" },
+            { "key": "e2e", "state": "INPROGRESS" }
+        ]});
+        let checks = build_statuses(&v);
+        let (state, failing) = aggregate_checks(&checks);
+        assert_eq!(state, "failure");
+        assert_eq!(failing, vec!["Lint"]);
+        assert_eq!(checks[2].name, "e2e");
+    }
+}
@@ -0,0 +1,225 @@
+//! Gitea backend, also covering Forgejo and Codeberg — they share Gitea's
+//! REST API (`/api/v1`), whose PR routes mirror GitHub's closely.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use octocrab::Octocrab;
+use serde_json::{json, Value};
+
+use super::{aggregate_checks, parse_timestamp, CheckOutcome, CheckResult, Forge, PrInfo};
+
+pub struct GiteaForge {
+    client: Octocrab,
+    owner: String,
+    repo: String,
+}
+
+impl GiteaForge {
+    /// `base_url` is the instance root (e.g. `https://codeberg.org`); the API
+    /// lives under `/api/v1`.
+    pub fn new(base_url: &str, token: String, owner: &str, repo: &str) -> Result<Self> {
+        let api_url = format!("{}/api/v1", base_url.trim_end_matches('/'));
+        let client = Octocrab::builder()
+            .base_uri(api_url.as_str())
+            .with_context(|| format!("Invalid Gitea API URL: {}", api_url))?
+            .personal_token(token)
+            .build()
+            .context("Failed to build Gitea client")?;
+        Ok(Self { client, owner: owner.to_string(), repo: repo.to_string() })
+    }
+
+    fn repo_route(&self) -> String {
+        format!("/repos/{}/{}", self.owner, self.repo)
+    }
+}
+
+#[async_trait]
+impl Forge for GiteaForge {
+    fn name(&self) -> &'static str {
+        "Gitea"
+    }
+
+    async fn find_open_pr(&self, head: &str) -> Result<Option<(u64, String)>> {
+        // The list endpoint has no head filter, so match on `head.ref` locally.
+        let prs: Value = self
+            .client
+            .get(format!("{}/pulls", self.repo_route()), Some(&[("state", "open"), ("limit", "50")]))
+            .await
+            .with_context(|| format!("Failed to look up open PRs for '{}'", head))?;
+        Ok(prs
+            .as_array()
+            .and_then(|prs| prs.iter().find(|pr| pr["head"]["ref"].as_str() == Some(head)))
+            .and_then(|pr| {
+                Some((pr["number"].as_u64()?, pr["html_url"].as_str().unwrap_or_default().to_string()))
+            }))
+    }
+
+    async fn create_pr(&self, title: &str, head: &str, base: &str, body: &str) -> Result<(u64, String)> {
+        let payload = json!({ "title": title, "head": head, "base": base, "body": body });
+        let pr: Value = self
+            .client
+            .post(format!("{}/pulls", self.repo_route()), Some(&payload))
+            .await
+            .with_context(|| format!("Failed to create PR '{}'", title))?;
+
+        let number = pr["number"].as_u64().context("Gitea response has no PR number")?;
+        Ok((number, pr["html_url"].as_str().unwrap_or_default().to_string()))
+    }
+
+    async fn update_pr_base(&self, pr_number: u64, new_base: &str) -> Result<()> {
+        let _: Value = self
+            .client
+            .patch(
+                format!("{}/pulls/{}", self.repo_route(), pr_number),
+                Some(&json!({ "base": new_base })),
+            )
+            .await
+            .with_context(|| format!("Failed to update base for PR #{}", pr_number))?;
+        Ok(())
+    }
+
+    async fn update_pr(&self, pr_number: u64, title: &str, body: Option<&str>) -> Result<()> {
+        let mut payload = json!({ "title": title });
+        if let Some(body) = body {
+            payload["body"] = json!(body);
+        }
+        let _: Value = self
+            .client
+            .patch(format!("{}/pulls/{}", self.repo_route(), pr_number), Some(&payload))
+            .await
+            .with_context(|| format!("Failed to update PR #{}", pr_number))?;
+        Ok(())
+    }
+
+    async fn get_pr_info(&self, pr_number: u64) -> Result<PrInfo> {
+        let pr: Value = self
+            .client
+            .get(format!("{}/pulls/{}", self.repo_route(), pr_number), None::<&()>)
+            .await
+            .with_context(|| format!("Failed to fetch PR #{}", pr_number))?;
+        let mut info = pr_info(&pr, pr_number);
+
+        let status: Result<Value> = self
+            .client
+            .get(format!("{}/commits/{}/status", self.repo_route(), info.head_sha), None::<&()>)
+            .await
+            .context("Failed to fetch commit status");
+        (info.ci_status, info.failing_checks) = match status {
+            Ok(v) => aggregate_checks(&commit_statuses(&v)),
+            Err(_) => ("unknown".to_string(), vec![]),
+        };
+
+        let reviews: Value = self
+            .client
+            .get(format!("{}/pulls/{}/reviews", self.repo_route(), pr_number), None::<&()>)
+            .await
+            .unwrap_or_default();
+        info.review_state = review_state(&reviews);
+        Ok(info)
+    }
+}
+
+/// Map a Gitea PR payload to `PrInfo` (CI and review fields are filled in separately).
+pub fn pr_info(pr: &Value, pr_number: u64) -> PrInfo {
+    PrInfo {
+        number: pr_number,
+        url: pr["html_url"].as_str().unwrap_or_default().to_string(),
+        title: pr["title"].as_str().unwrap_or_default().to_string(),
+        body: pr["body"].as_str().unwrap_or_default().to_string(),
+        state: pr["state"].as_str().unwrap_or("unknown").to_string(),
+        merged: pr["merged"].as_bool().unwrap_or(false),
+        merged_at: parse_timestamp(&pr["merged_at"]),
+        base: pr["base"]["ref"].as_str().unwrap_or_default().to_string(),
+        head_sha: pr["head"]["sha"].as_str().unwrap_or_default().to_string(),
+        ci_status: "unknown".to_string(),
+        failing_checks: vec![],
+        review_state: "pending".to_string(),
+    }
+}
+
+/// Map a combined-status payload (`{"statuses": [...]}`) to check results.
+/// Gitea's `warning` state does not block.
+pub fn commit_statuses(v: &Value) -> Vec<CheckResult> {
+    let empty = vec![];
+    v["statuses"]
+        .as_array()
+        .unwrap_or(&empty)
+        .iter()
+        .map(|s| CheckResult {
+            name: s["context"].as_str().unwrap_or_default().to_string(),
+            outcome: match s["status"].as_str().unwrap_or_default() {
+                "warning" => CheckOutcome::Success,
+                state => CheckOutcome::from_status_state(state),
+            },
+        })
+        .collect()
+}
+
+/// Collapse PR reviews into the same labels the GitHub backend uses.
+fn review_state(reviews: &Value) -> String {
+    let empty = vec![];
+    let states: Vec<&str> = reviews
+        .as_array()
+        .unwrap_or(&empty)
+        .iter()
+        .filter_map(|r| r["state"].as_str())
+        .collect();
+
+    if states.contains(&"APPROVED") {
+        "approved".to_string()
+    } else if states.contains(&"REQUEST_CHANGES") {
+        "changes_requested".to_string()
+    } else if states.is_empty() {
+        "pending".to_string()
+    } else {
+        "commented".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pr_info_maps_fields() {
+        let pr = json!({
+            "number": 4,
+            "title": "[1/2] models",
+            "state": "closed",
+            "merged": true,
+            "merged_at": "2026-03-02T10:15:00Z",
+            "html_url": "https://codeberg.org/acme/myrepo/pulls/4",
+            "base": { "ref": "main" },
+            "head": { "ref": "feat/big-chunk-1-models", "sha": "abc123" }
+        });
+        let info = pr_info(&pr, 4);
+        assert_eq!(info.state, "closed");
+        assert!(info.merged);
+        assert_eq!(info.merged_at.unwrap().to_rfc3339(), "2026-03-02T10:15:00+00:00");
+        assert_eq!(info.base, "main");
+        assert_eq!(info.head_sha, "abc123");
+        assert_eq!(info.url, "https://codeberg.org/acme/myrepo/pulls/4");
+    }
+
+    #[test]
+    fn test_commit_statuses_map_to_check_outcomes() {
+        let v = json!({ "state": "failure", "statuses": [
+            { "context": "ci/build", "status": "success" },
+            { "context": "ci/lint", "status": "warning" },
+            { "context": "ci/test", "status": "failure" }
+        ]});
+        let (state, failing) = aggregate_checks(&commit_statuses(&v));
+        assert_eq!(state, "failure");
+        assert_eq!(failing, vec!["ci/test"]);
+    }
+
+    #[test]
+    fn test_review_state_labels() {
+        assert_eq!(review_state(&json!([])), "pending");
+        assert_eq!(review_state(&json!([{ "state": "COMMENT" }])), "commented");
+        assert_eq!(
+            review_state(&json!([{ "state": "REQUEST_CHANGES" }, { "state": "APPROVED" }])),
+            "approved"
+        );
+    }
+}
@@ -0,0 +1,128 @@
+//! GitHub backend — delegates to the REST/GraphQL helpers in `crate::github`.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use octocrab::Octocrab;
+use std::collections::HashMap;
+
+use super::{Access, Forge, MergeMethod, PrInfo, ReviewThread};
+use crate::{github, project::Project};
+
+pub struct GitHubForge {
+    client: Octocrab,
+    owner: String,
+    repo: String,
+    /// Permissions of the GitHub App installation `client` acts as, when it does.
+    app_permissions: Option<HashMap<String, String>>,
+}
+
+impl GitHubForge {
+    pub fn new(client: Octocrab, owner: &str, repo: &str) -> Self {
+        Self { client, owner: owner.to_string(), repo: repo.to_string(), app_permissions: None }
+    }
+
+    /// Record the permissions of the app installation the client acts as.
+    pub fn with_app_permissions(mut self, permissions: Option<HashMap<String, String>>) -> Self {
+        self.app_permissions = permissions;
+        self
+    }
+}
+
+#[async_trait]
+impl Forge for GitHubForge {
+    fn name(&self) -> &'static str {
+        "GitHub"
+    }
+
+    async fn find_open_pr(&self, head: &str) -> Result<Option<(u64, String)>> {
+        github::find_open_pr(&self.client, &self.owner, &self.repo, head).await
+    }
+
+    async fn create_pr(&self, title: &str, head: &str, base: &str, body: &str) -> Result<(u64, String)> {
+        github::create_pr(&self.client, &self.owner, &self.repo, title, head, base, body).await
+    }
+
+    async fn update_pr_base(&self, pr_number: u64, new_base: &str) -> Result<()> {
+        github::update_pr_base(&self.client, &self.owner, &self.repo, pr_number, new_base).await
+    }
+
+    async fn update_pr(&self, pr_number: u64, title: &str, body: Option<&str>) -> Result<()> {
+        github::update_pr(&self.client, &self.owner, &self.repo, pr_number, title, body).await
+    }
+
+    async fn get_pr_info(&self, pr_number: u64) -> Result<PrInfo> {
+        github::get_pr_info(&self.client, &self.owner, &self.repo, pr_number).await
+    }
+
+    async fn get_pr_base(&self, pr_number: u64) -> Result<String> {
+        github::get_pr_base(&self.client, &self.owner, &self.repo, pr_number).await
+    }
+
+    async fn required_status_checks(&self, branch: &str) -> Result<Vec<String>> {
+        github::required_status_checks(&self.client, &self.owner, &self.repo, branch).await
+    }
+
+    async fn unmet_required_checks(&self, sha: &str, required: &[String]) -> Result<Vec<String>> {
+        github::unmet_required_checks(&self.client, &self.owner, &self.repo, sha, required).await
+    }
+
+    async fn missing_permissions(&self, needs: &[Access]) -> Result<Vec<String>> {
+        github::missing_permissions(&self.client, &self.owner, &self.repo, self.app_permissions.as_ref(), needs).await
+    }
+
+    async fn merge_pr(&self, pr_number: u64, method: MergeMethod) -> Result<()> {
+        github::merge_pr(&self.client, &self.owner, &self.repo, pr_number, method).await
+    }
+
+    async fn enable_auto_merge(&self, pr_number: u64, method: MergeMethod) -> Result<()> {
+        github::enable_auto_merge(&self.client, &self.owner, &self.repo, pr_number, method).await
+    }
+
+    async fn create_issue(&self, title: &str, body: &str) -> Result<(u64, String)> {
+        github::create_issue(&self.client, &self.owner, &self.repo, title, body).await
+    }
+
+    async fn update_issue(&self, number: u64, body: &str, close: bool) -> Result<()> {
+        github::update_issue(&self.client, &self.owner, &self.repo, number, body, close).await
+    }
+
+    async fn comment_on_pr(&self, pr_number: u64, body: &str) -> Result<u64> {
+        github::comment_on_pr(&self.client, &self.owner, &self.repo, pr_number, body).await
+    }
+
+    async fn update_pr_comment(&self, _pr_number: u64, comment_id: u64, body: &str) -> Result<()> {
+        github::update_comment(&self.client, &self.owner, &self.repo, comment_id, body).await
+    }
+
+    async fn convert_pr_to_draft(&self, pr_number: u64) -> Result<()> {
+        github::convert_pr_to_draft(&self.client, &self.owner, &self.repo, pr_number).await
+    }
+
+    async fn close_pr(&self, pr_number: u64) -> Result<()> {
+        github::close_pr(&self.client, &self.owner, &self.repo, pr_number).await
+    }
+
+    async fn unresolved_review_threads(&self, pr_number: u64) -> Result<Vec<ReviewThread>> {
+        github::unresolved_review_threads(&self.client, &self.owner, &self.repo, pr_number).await
+    }
+
+    async fn add_labels(&self, pr_number: u64, labels: &[String]) -> Result<()> {
+        github::add_labels(&self.client, &self.owner, &self.repo, pr_number, labels).await
+    }
+
+    async fn request_reviewers(&self, pr_number: u64, reviewers: &[String]) -> Result<()> {
+        github::request_reviewers(&self.client, &self.owner, &self.repo, pr_number, reviewers).await
+    }
+
+    async fn set_milestone(&self, pr_number: u64, title: &str) -> Result<()> {
+        github::set_milestone(&self.client, &self.owner, &self.repo, pr_number, title).await
+    }
+
+    async fn add_to_project(&self, pr_number: u64, project: &Project) -> Result<String> {
+        github::add_to_project(&self.client, &self.owner, &self.repo, pr_number, project).await
+    }
+
+    async fn set_project_status(&self, project: &Project, item_id: &str, status: &str) -> Result<()> {
+        github::set_project_status(&self.client, project, item_id, status).await
+    }
+}
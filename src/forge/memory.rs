@@ -0,0 +1,373 @@
+//! In-memory backend: PRs, comments, and issues kept in a `Mutex` instead of
+//! on a hosting service, so `push`, `status`, and `clean` can run offline —
+//! in tests, or in a program that embeds merges and wants a dry run.
+//!
+//! PRs and issues share one number sequence starting at 1, as on GitHub.
+//! PRs have no commits of their own here: a PR's head is reported as the
+//! stand-in sha `pr-<number>`, which check lookups resolve back to the PR.
+
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::{Mutex, MutexGuard};
+
+use super::{Access, Forge, MergeMethod, PrInfo, ReviewThread};
+use crate::project::Project;
+
+/// A pull request as the in-memory forge records it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemoryPr {
+    pub number: u64,
+    pub title: String,
+    pub head: String,
+    pub base: String,
+    pub body: String,
+    /// `"open"` or `"closed"`; merged PRs are closed with `merged` set.
+    pub state: String,
+    pub merged: bool,
+    pub merged_at: Option<DateTime<Utc>>,
+    pub draft: bool,
+    /// Merge method auto-merge was enabled with, if it was.
+    pub auto_merge: Option<MergeMethod>,
+    pub ci_status: String,
+    pub failing_checks: Vec<String>,
+    /// Checks that succeeded on the PR's head.
+    pub passed_checks: Vec<String>,
+    pub review_state: String,
+    /// (comment id, body) in the order they were left.
+    pub comments: Vec<(u64, String)>,
+    /// Review threads nobody has resolved.
+    pub review_threads: Vec<ReviewThread>,
+    pub labels: Vec<String>,
+    /// Every review request made, repeats included.
+    pub requested_reviewers: Vec<String>,
+    pub milestone: Option<String>,
+    /// The project the PR was added to, as `owner/number`.
+    pub project: Option<String>,
+    /// Every status the PR's project item was set to, in order.
+    pub project_statuses: Vec<String>,
+}
+
+/// An issue as the in-memory forge records it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemoryIssue {
+    pub number: u64,
+    pub title: String,
+    pub body: String,
+    pub closed: bool,
+}
+
+#[derive(Default)]
+struct Store {
+    prs: Vec<MemoryPr>,
+    issues: Vec<MemoryIssue>,
+    /// Last PR or issue number handed out.
+    last_number: u64,
+    last_comment_id: u64,
+    /// Access `missing_permissions` reports as lacking.
+    denied: Vec<Access>,
+    /// (branch, checks) branch protection requires.
+    required_checks: Vec<(String, Vec<String>)>,
+}
+
+#[derive(Default)]
+pub struct MemoryForge {
+    store: Mutex<Store>,
+}
+
+impl MemoryForge {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn store(&self) -> MutexGuard<'_, Store> {
+        self.store.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Every PR opened so far, in order of creation.
+    pub fn prs(&self) -> Vec<MemoryPr> {
+        self.store().prs.clone()
+    }
+
+    pub fn pr(&self, number: u64) -> Option<MemoryPr> {
+        self.store().prs.iter().find(|p| p.number == number).cloned()
+    }
+
+    /// Every issue opened so far, in order of creation.
+    pub fn issues(&self) -> Vec<MemoryIssue> {
+        self.store().issues.clone()
+    }
+
+    /// Set the CI state `get_pr_info` reports for PR `number`.
+    pub fn set_checks(&self, number: u64, ci_status: &str, failing: &[&str]) -> Result<()> {
+        self.with_pr(number, |pr| {
+            pr.ci_status = ci_status.to_string();
+            pr.failing_checks = failing.iter().map(|c| c.to_string()).collect();
+            Ok(())
+        })
+    }
+
+    /// Mark `checks` as succeeded on PR `number`'s head.
+    pub fn pass_checks(&self, number: u64, checks: &[&str]) -> Result<()> {
+        self.with_pr(number, |pr| {
+            pr.passed_checks.extend(checks.iter().map(|c| c.to_string()));
+            Ok(())
+        })
+    }
+
+    /// Require `checks` on `branch`, as branch protection would.
+    pub fn require_checks(&self, branch: &str, checks: &[&str]) {
+        let checks = checks.iter().map(|c| c.to_string()).collect();
+        self.store().required_checks.push((branch.to_string(), checks));
+    }
+
+    /// Set the review state `get_pr_info` reports for PR `number`.
+    pub fn set_review(&self, number: u64, review_state: &str) -> Result<()> {
+        self.with_pr(number, |pr| {
+            pr.review_state = review_state.to_string();
+            Ok(())
+        })
+    }
+
+    /// Open a review thread on PR `number`, as a reviewer would.
+    pub fn add_review_thread(&self, number: u64, thread: ReviewThread) -> Result<()> {
+        self.with_pr(number, |pr| {
+            pr.review_threads.push(thread);
+            Ok(())
+        })
+    }
+
+    /// Report `access` as missing from the credentials, as a token without
+    /// the right scope would.
+    pub fn deny(&self, access: Access) {
+        self.store().denied.push(access);
+    }
+
+    fn with_pr<T>(&self, number: u64, f: impl FnOnce(&mut MemoryPr) -> Result<T>) -> Result<T> {
+        let mut store = self.store();
+        let Some(pr) = store.prs.iter_mut().find(|p| p.number == number) else {
+            bail!("PR #{} does not exist", number)
+        };
+        f(pr)
+    }
+
+    fn url(number: u64) -> String {
+        format!("https://forge.invalid/pull/{}", number)
+    }
+}
+
+#[async_trait]
+impl Forge for MemoryForge {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    async fn find_open_pr(&self, head: &str) -> Result<Option<(u64, String)>> {
+        let store = self.store();
+        Ok(store.prs.iter().find(|p| p.head == head && p.state == "open").map(|p| (p.number, Self::url(p.number))))
+    }
+
+    async fn create_pr(&self, title: &str, head: &str, base: &str, body: &str) -> Result<(u64, String)> {
+        let mut store = self.store();
+        if store.prs.iter().any(|p| p.head == head && p.base == base && p.state == "open") {
+            bail!("A PR from {} into {} is already open", head, base);
+        }
+        store.last_number += 1;
+        let number = store.last_number;
+        store.prs.push(MemoryPr {
+            number,
+            title: title.to_string(),
+            head: head.to_string(),
+            base: base.to_string(),
+            body: body.to_string(),
+            state: "open".to_string(),
+            merged: false,
+            merged_at: None,
+            draft: false,
+            auto_merge: None,
+            ci_status: "none".to_string(),
+            failing_checks: vec![],
+            passed_checks: vec![],
+            review_state: "none".to_string(),
+            comments: vec![],
+            review_threads: vec![],
+            labels: vec![],
+            requested_reviewers: vec![],
+            milestone: None,
+            project: None,
+            project_statuses: vec![],
+        });
+        Ok((number, Self::url(number)))
+    }
+
+    async fn update_pr_base(&self, pr_number: u64, new_base: &str) -> Result<()> {
+        self.with_pr(pr_number, |pr| {
+            pr.base = new_base.to_string();
+            Ok(())
+        })
+    }
+
+    async fn update_pr(&self, pr_number: u64, title: &str, body: Option<&str>) -> Result<()> {
+        self.with_pr(pr_number, |pr| {
+            pr.title = title.to_string();
+            if let Some(body) = body {
+                pr.body = body.to_string();
+            }
+            Ok(())
+        })
+    }
+
+    async fn get_pr_info(&self, pr_number: u64) -> Result<PrInfo> {
+        self.with_pr(pr_number, |pr| {
+            Ok(PrInfo {
+                number: pr.number,
+                url: Self::url(pr.number),
+                title: pr.title.clone(),
+                body: pr.body.clone(),
+                state: pr.state.clone(),
+                merged: pr.merged,
+                merged_at: pr.merged_at,
+                base: pr.base.clone(),
+                head_sha: format!("pr-{}", pr.number),
+                ci_status: pr.ci_status.clone(),
+                failing_checks: pr.failing_checks.clone(),
+                review_state: pr.review_state.clone(),
+            })
+        })
+    }
+
+    async fn required_status_checks(&self, branch: &str) -> Result<Vec<String>> {
+        let store = self.store();
+        Ok(store.required_checks.iter().filter(|(b, _)| b == branch).flat_map(|(_, c)| c.clone()).collect())
+    }
+
+    async fn unmet_required_checks(&self, sha: &str, required: &[String]) -> Result<Vec<String>> {
+        let number = sha.strip_prefix("pr-").and_then(|n| n.parse().ok());
+        let store = self.store();
+        let passed = store.prs.iter().find(|p| Some(p.number) == number).map(|p| &p.passed_checks);
+        Ok(required.iter().filter(|r| !passed.is_some_and(|p| p.contains(r))).cloned().collect())
+    }
+
+    async fn missing_permissions(&self, needs: &[Access]) -> Result<Vec<String>> {
+        let store = self.store();
+        Ok(needs.iter().filter(|a| store.denied.contains(a)).map(|a| format!("{} — denied", a)).collect())
+    }
+
+    async fn merge_pr(&self, pr_number: u64, _method: MergeMethod) -> Result<()> {
+        self.with_pr(pr_number, |pr| {
+            if pr.state != "open" {
+                bail!("PR #{} is not open", pr_number);
+            }
+            pr.state = "closed".to_string();
+            pr.merged = true;
+            pr.merged_at = Some(Utc::now());
+            Ok(())
+        })
+    }
+
+    async fn enable_auto_merge(&self, pr_number: u64, method: MergeMethod) -> Result<()> {
+        self.with_pr(pr_number, |pr| {
+            pr.auto_merge = Some(method);
+            Ok(())
+        })
+    }
+
+    async fn create_issue(&self, title: &str, body: &str) -> Result<(u64, String)> {
+        let mut store = self.store();
+        store.last_number += 1;
+        let number = store.last_number;
+        store.issues.push(MemoryIssue { number, title: title.to_string(), body: body.to_string(), closed: false });
+        Ok((number, format!("https://forge.invalid/issues/{}", number)))
+    }
+
+    async fn update_issue(&self, number: u64, body: &str, close: bool) -> Result<()> {
+        let mut store = self.store();
+        let Some(issue) = store.issues.iter_mut().find(|i| i.number == number) else {
+            bail!("Issue #{} does not exist", number)
+        };
+        issue.body = body.to_string();
+        issue.closed |= close;
+        Ok(())
+    }
+
+    async fn comment_on_pr(&self, pr_number: u64, body: &str) -> Result<u64> {
+        let mut store = self.store();
+        store.last_comment_id += 1;
+        let id = store.last_comment_id;
+        let Some(pr) = store.prs.iter_mut().find(|p| p.number == pr_number) else {
+            bail!("PR #{} does not exist", pr_number)
+        };
+        pr.comments.push((id, body.to_string()));
+        Ok(id)
+    }
+
+    async fn update_pr_comment(&self, pr_number: u64, comment_id: u64, body: &str) -> Result<()> {
+        self.with_pr(pr_number, |pr| {
+            let Some(comment) = pr.comments.iter_mut().find(|(id, _)| *id == comment_id) else {
+                bail!("PR #{} has no comment {}", pr_number, comment_id)
+            };
+            comment.1 = body.to_string();
+            Ok(())
+        })
+    }
+
+    async fn convert_pr_to_draft(&self, pr_number: u64) -> Result<()> {
+        self.with_pr(pr_number, |pr| {
+            pr.draft = true;
+            Ok(())
+        })
+    }
+
+    async fn close_pr(&self, pr_number: u64) -> Result<()> {
+        self.with_pr(pr_number, |pr| {
+            pr.state = "closed".to_string();
+            Ok(())
+        })
+    }
+
+    async fn unresolved_review_threads(&self, pr_number: u64) -> Result<Vec<ReviewThread>> {
+        self.with_pr(pr_number, |pr| Ok(pr.review_threads.clone()))
+    }
+
+    async fn add_labels(&self, pr_number: u64, labels: &[String]) -> Result<()> {
+        self.with_pr(pr_number, |pr| {
+            for label in labels {
+                if !pr.labels.contains(label) {
+                    pr.labels.push(label.clone());
+                }
+            }
+            Ok(())
+        })
+    }
+
+    async fn request_reviewers(&self, pr_number: u64, reviewers: &[String]) -> Result<()> {
+        self.with_pr(pr_number, |pr| {
+            pr.requested_reviewers.extend(reviewers.iter().cloned());
+            Ok(())
+        })
+    }
+
+    async fn set_milestone(&self, pr_number: u64, title: &str) -> Result<()> {
+        self.with_pr(pr_number, |pr| {
+            pr.milestone = Some(title.to_string());
+            Ok(())
+        })
+    }
+
+    async fn add_to_project(&self, pr_number: u64, project: &Project) -> Result<String> {
+        self.with_pr(pr_number, |pr| {
+            pr.project = Some(project.to_string());
+            Ok(format!("item-{}", pr_number))
+        })
+    }
+
+    async fn set_project_status(&self, _project: &Project, item_id: &str, status: &str) -> Result<()> {
+        let Some(number) = item_id.strip_prefix("item-").and_then(|n| n.parse().ok()) else {
+            bail!("Project item {} does not exist", item_id)
+        };
+        self.with_pr(number, |pr| {
+            pr.project_statuses.push(status.to_string());
+            Ok(())
+        })
+    }
+}
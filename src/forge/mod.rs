@@ -0,0 +1,947 @@
+//! Forge abstraction: the pull-request operations `push`, `status`, and `clean`
+//! need, implemented once per hosting service (GitHub, Bitbucket, …).
+//!
+//! Commands obtain a backend with [`connect`], which picks the implementation
+//! from the `forge` recorded in `.merges.json` by `merges init`. Tests pass
+//! a [`memory::MemoryForge`] to the commands' `run_with` instead.
+
+pub mod azure;
+pub mod bitbucket;
+pub mod gitea;
+pub mod github;
+pub mod memory;
+
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config,
+    output::Level,
+    project::Project,
+    state::{ForgeKind, MergesState},
+};
+
+/// Merge method the forge uses when auto-merge lands a PR.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeMethod {
+    Squash,
+    Merge,
+    Rebase,
+}
+
+impl std::fmt::Display for MergeMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MergeMethod::Squash => write!(f, "squash"),
+            MergeMethod::Merge => write!(f, "merge"),
+            MergeMethod::Rebase => write!(f, "rebase"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[allow(dead_code)] // fields used selectively; kept for future display/serialisation
+pub struct PrInfo {
+    pub number: u64,
+    pub url: String,
+    pub title: String,
+    /// Description of the PR, as Markdown.
+    pub body: String,
+    pub state: String,
+    /// True only when the forge actually merged the PR (a closed PR may be unmerged).
+    pub merged: bool,
+    /// When the PR was merged, if the forge reports it.
+    pub merged_at: Option<DateTime<Utc>>,
+    /// Branch the PR targets.
+    pub base: String,
+    /// SHA of the PR's head commit.
+    pub head_sha: String,
+    pub ci_status: String,
+    /// Names of checks whose latest run failed.
+    pub failing_checks: Vec<String>,
+    pub review_state: String,
+}
+
+/// Parse a timestamp from a forge payload: an RFC 3339 string, or epoch
+/// milliseconds as Bitbucket Server sends them.
+pub fn parse_timestamp(v: &serde_json::Value) -> Option<DateTime<Utc>> {
+    match v {
+        serde_json::Value::String(s) => DateTime::parse_from_rfc3339(s).ok().map(|t| t.with_timezone(&Utc)),
+        serde_json::Value::Number(n) => DateTime::from_timestamp_millis(n.as_i64()?),
+        _ => None,
+    }
+}
+
+/// Something a run needs the forge credentials to be allowed to do, for
+/// checking them up front with [`Forge::missing_permissions`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Access {
+    /// Create, retarget, and update PRs and comment on them.
+    PullRequests,
+    /// Open and update the tracking issue.
+    Issues,
+    /// Enable auto-merge on PRs.
+    AutoMerge,
+}
+
+impl std::fmt::Display for Access {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Access::PullRequests => write!(f, "create and update PRs"),
+            Access::Issues => write!(f, "open the tracking issue"),
+            Access::AutoMerge => write!(f, "enable auto-merge"),
+        }
+    }
+}
+
+/// An unresolved review thread on a PR: the comments left on one spot of a
+/// file, oldest first.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReviewThread {
+    pub path: String,
+    /// Line in the PR's version of the file; `None` once the code it was left
+    /// on has changed (the thread is outdated).
+    pub line: Option<u64>,
+    pub comments: Vec<ReviewComment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReviewComment {
+    pub author: String,
+    pub body: String,
+    pub url: String,
+}
+
+/// Normalised result of a single commit status or check run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CheckOutcome {
+    Success,
+    Pending,
+    Failure,
+}
+
+impl CheckOutcome {
+    /// Map a commit status `state` (success/pending/failure/error).
+    pub fn from_status_state(state: &str) -> Self {
+        match state {
+            "success" => CheckOutcome::Success,
+            "failure" | "error" => CheckOutcome::Failure,
+            _ => CheckOutcome::Pending,
+        }
+    }
+
+    /// Map a check run's `status` and `conclusion`. Runs that have not completed
+    /// are pending; neutral and skipped conclusions don't block.
+    pub fn from_check_run(status: &str, conclusion: Option<&str>) -> Self {
+        if status != "completed" {
+            return CheckOutcome::Pending;
+        }
+        match conclusion {
+            Some("success" | "neutral" | "skipped") => CheckOutcome::Success,
+            None => CheckOutcome::Pending,
+            Some(_) => CheckOutcome::Failure,
+        }
+    }
+}
+
+/// A named CI signal on a commit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckResult {
+    pub name: String,
+    pub outcome: CheckOutcome,
+}
+
+/// Collapse individual checks into one CI state plus the names of failing checks.
+///
+/// - any failure → `"failure"`
+/// - otherwise any pending → `"pending"`
+/// - all succeeded → `"success"`
+/// - no checks at all → `"none"`
+pub fn aggregate_checks(checks: &[CheckResult]) -> (String, Vec<String>) {
+    let failing: Vec<String> = checks
+        .iter()
+        .filter(|c| c.outcome == CheckOutcome::Failure)
+        .map(|c| c.name.clone())
+        .collect();
+
+    let state = if checks.is_empty() {
+        "none"
+    } else if !failing.is_empty() {
+        "failure"
+    } else if checks.iter().any(|c| c.outcome == CheckOutcome::Pending) {
+        "pending"
+    } else {
+        "success"
+    };
+
+    (state.to_string(), failing)
+}
+
+/// Pull-request operations shared by every forge backend.
+///
+/// Optional capabilities (auto-merge, issues, branch protection) have default
+/// implementations so a backend only overrides what its service supports.
+#[async_trait]
+pub trait Forge: Send + Sync {
+    /// Human-readable service name used in messages.
+    fn name(&self) -> &'static str;
+
+    /// Find an open PR from branch `head`; returns (pr_number, pr_url).
+    async fn find_open_pr(&self, head: &str) -> Result<Option<(u64, String)>>;
+
+    /// Create a pull request; returns (pr_number, pr_url).
+    async fn create_pr(&self, title: &str, head: &str, base: &str, body: &str) -> Result<(u64, String)>;
+
+    /// Retarget an existing PR at `new_base`.
+    async fn update_pr_base(&self, pr_number: u64, new_base: &str) -> Result<()>;
+
+    /// Replace an existing PR's title, and its body when `body` is given.
+    async fn update_pr(&self, pr_number: u64, title: &str, body: Option<&str>) -> Result<()>;
+
+    /// Retrieve PR state including CI and review state.
+    async fn get_pr_info(&self, pr_number: u64) -> Result<PrInfo>;
+
+    /// Create a PR from `head` into `base`, unless one is already open from
+    /// `head` — then retarget it at `base` if needed and refresh its title and
+    /// body instead, since forges reject a second PR for the same branches.
+    /// Returns (pr_number, pr_url, created).
+    async fn create_or_update_pr(
+        &self,
+        title: &str,
+        head: &str,
+        base: &str,
+        body: &str,
+    ) -> Result<(u64, String, bool)> {
+        match self.find_open_pr(head).await? {
+            Some((pr_number, pr_url)) => {
+                if self.get_pr_base(pr_number).await? != base {
+                    self.update_pr_base(pr_number, base).await?;
+                }
+                self.update_pr(pr_number, title, Some(body)).await?;
+                Ok((pr_number, pr_url, false))
+            }
+            None => {
+                let (pr_number, pr_url) = self.create_pr(title, head, base, body).await?;
+                Ok((pr_number, pr_url, true))
+            }
+        }
+    }
+
+    /// Return the branch an existing PR currently targets.
+    async fn get_pr_base(&self, pr_number: u64) -> Result<String> {
+        Ok(self.get_pr_info(pr_number).await?.base)
+    }
+
+    /// Status check names that branch protection requires on `branch`.
+    async fn required_status_checks(&self, _branch: &str) -> Result<Vec<String>> {
+        Ok(vec![])
+    }
+
+    /// Return the subset of `required` checks that have not succeeded on commit `sha`.
+    async fn unmet_required_checks(&self, _sha: &str, _required: &[String]) -> Result<Vec<String>> {
+        Ok(vec![])
+    }
+
+    /// The parts of `needs` the credentials are not allowed to do, each
+    /// described with what is missing. Empty when everything is allowed or
+    /// the backend cannot tell.
+    async fn missing_permissions(&self, _needs: &[Access]) -> Result<Vec<String>> {
+        Ok(vec![])
+    }
+
+    /// Merge the PR now, with `method`.
+    async fn merge_pr(&self, _pr_number: u64, _method: MergeMethod) -> Result<()> {
+        bail!("Merging PRs is not supported on {}", self.name())
+    }
+
+    /// Enable auto-merge so the PR lands once approvals and checks pass.
+    async fn enable_auto_merge(&self, _pr_number: u64, _method: MergeMethod) -> Result<()> {
+        bail!("Auto-merge is not supported on {}", self.name())
+    }
+
+    /// Create an issue; returns (issue_number, issue_url).
+    async fn create_issue(&self, _title: &str, _body: &str) -> Result<(u64, String)> {
+        bail!("Issues are not supported on {}", self.name())
+    }
+
+    /// Replace an issue's body, closing it when `close` is true.
+    async fn update_issue(&self, _number: u64, _body: &str, _close: bool) -> Result<()> {
+        bail!("Issues are not supported on {}", self.name())
+    }
+
+    /// Comment on a PR; returns the comment id.
+    async fn comment_on_pr(&self, _pr_number: u64, _body: &str) -> Result<u64> {
+        bail!("Commenting on PRs is not supported on {}", self.name())
+    }
+
+    /// Replace the body of a comment left by `comment_on_pr`.
+    async fn update_pr_comment(&self, _pr_number: u64, _comment_id: u64, _body: &str) -> Result<()> {
+        bail!("Commenting on PRs is not supported on {}", self.name())
+    }
+
+    /// Turn an open PR back into a draft.
+    async fn convert_pr_to_draft(&self, _pr_number: u64) -> Result<()> {
+        bail!("Draft PRs are not supported on {}", self.name())
+    }
+
+    /// Close a PR without merging it.
+    async fn close_pr(&self, _pr_number: u64) -> Result<()> {
+        bail!("Closing PRs is not supported on {}", self.name())
+    }
+
+    /// Review threads on the PR that nobody has resolved yet.
+    async fn unresolved_review_threads(&self, _pr_number: u64) -> Result<Vec<ReviewThread>> {
+        bail!("Review threads are not supported on {}", self.name())
+    }
+
+    /// Add `labels` to a PR.
+    async fn add_labels(&self, _pr_number: u64, _labels: &[String]) -> Result<()> {
+        bail!("Labels are not supported on {}", self.name())
+    }
+
+    /// Ask `reviewers` — logins, or `org/team` for a team — to review a PR.
+    async fn request_reviewers(&self, _pr_number: u64, _reviewers: &[String]) -> Result<()> {
+        bail!("Requesting reviewers is not supported on {}", self.name())
+    }
+
+    /// Give a PR the milestone titled `title`.
+    async fn set_milestone(&self, _pr_number: u64, _title: &str) -> Result<()> {
+        bail!("Milestones are not supported on {}", self.name())
+    }
+
+    /// Add a PR to `project`, returning its item id there; a PR already in the
+    /// project keeps its item.
+    async fn add_to_project(&self, _pr_number: u64, _project: &Project) -> Result<String> {
+        bail!("Projects are not supported on {}", self.name())
+    }
+
+    /// Set the Status field of `item_id` in `project` to the option named `status`.
+    async fn set_project_status(&self, _project: &Project, _item_id: &str, _status: &str) -> Result<()> {
+        bail!("Projects are not supported on {}", self.name())
+    }
+}
+
+/// Wraps a backend so each call is reported to MCP clients as a log message:
+/// changes at info level, lookups at debug level.
+struct Logged(Box<dyn Forge>);
+
+impl Logged {
+    fn log(&self, level: Level, message: String) {
+        crate::output::log(level, self.0.name(), &message);
+    }
+}
+
+#[async_trait]
+impl Forge for Logged {
+    fn name(&self) -> &'static str {
+        self.0.name()
+    }
+
+    async fn find_open_pr(&self, head: &str) -> Result<Option<(u64, String)>> {
+        self.log(Level::Debug, format!("Looking for an open PR from {}", head));
+        self.0.find_open_pr(head).await
+    }
+
+    async fn create_pr(&self, title: &str, head: &str, base: &str, body: &str) -> Result<(u64, String)> {
+        self.log(Level::Info, format!("Creating PR {} → {}: {}", head, base, title));
+        self.0.create_pr(title, head, base, body).await
+    }
+
+    async fn update_pr_base(&self, pr_number: u64, new_base: &str) -> Result<()> {
+        self.log(Level::Info, format!("Retargeting PR #{} at {}", pr_number, new_base));
+        self.0.update_pr_base(pr_number, new_base).await
+    }
+
+    async fn update_pr(&self, pr_number: u64, title: &str, body: Option<&str>) -> Result<()> {
+        self.log(Level::Info, format!("Updating PR #{}: {}", pr_number, title));
+        self.0.update_pr(pr_number, title, body).await
+    }
+
+    async fn get_pr_info(&self, pr_number: u64) -> Result<PrInfo> {
+        self.log(Level::Debug, format!("Reading PR #{}", pr_number));
+        self.0.get_pr_info(pr_number).await
+    }
+
+    async fn get_pr_base(&self, pr_number: u64) -> Result<String> {
+        self.log(Level::Debug, format!("Reading the base of PR #{}", pr_number));
+        self.0.get_pr_base(pr_number).await
+    }
+
+    async fn required_status_checks(&self, branch: &str) -> Result<Vec<String>> {
+        self.log(Level::Debug, format!("Reading required checks on {}", branch));
+        self.0.required_status_checks(branch).await
+    }
+
+    async fn unmet_required_checks(&self, sha: &str, required: &[String]) -> Result<Vec<String>> {
+        self.log(Level::Debug, format!("Reading check results for {}", sha));
+        self.0.unmet_required_checks(sha, required).await
+    }
+
+    async fn missing_permissions(&self, needs: &[Access]) -> Result<Vec<String>> {
+        self.log(Level::Debug, "Checking token permissions".to_string());
+        self.0.missing_permissions(needs).await
+    }
+
+    async fn merge_pr(&self, pr_number: u64, method: MergeMethod) -> Result<()> {
+        self.log(Level::Info, format!("Merging PR #{} ({})", pr_number, method));
+        self.0.merge_pr(pr_number, method).await
+    }
+
+    async fn enable_auto_merge(&self, pr_number: u64, method: MergeMethod) -> Result<()> {
+        self.log(Level::Info, format!("Enabling auto-merge ({}) on PR #{}", method, pr_number));
+        self.0.enable_auto_merge(pr_number, method).await
+    }
+
+    async fn create_issue(&self, title: &str, body: &str) -> Result<(u64, String)> {
+        self.log(Level::Info, format!("Creating issue: {}", title));
+        self.0.create_issue(title, body).await
+    }
+
+    async fn update_issue(&self, number: u64, body: &str, close: bool) -> Result<()> {
+        let action = if close { "Closing" } else { "Updating" };
+        self.log(Level::Info, format!("{} issue #{}", action, number));
+        self.0.update_issue(number, body, close).await
+    }
+
+    async fn comment_on_pr(&self, pr_number: u64, body: &str) -> Result<u64> {
+        self.log(Level::Info, format!("Commenting on PR #{}", pr_number));
+        self.0.comment_on_pr(pr_number, body).await
+    }
+
+    async fn update_pr_comment(&self, pr_number: u64, comment_id: u64, body: &str) -> Result<()> {
+        self.log(Level::Info, format!("Updating comment {} on PR #{}", comment_id, pr_number));
+        self.0.update_pr_comment(pr_number, comment_id, body).await
+    }
+
+    async fn convert_pr_to_draft(&self, pr_number: u64) -> Result<()> {
+        self.log(Level::Info, format!("Converting PR #{} to a draft", pr_number));
+        self.0.convert_pr_to_draft(pr_number).await
+    }
+
+    async fn close_pr(&self, pr_number: u64) -> Result<()> {
+        self.log(Level::Info, format!("Closing PR #{}", pr_number));
+        self.0.close_pr(pr_number).await
+    }
+
+    async fn unresolved_review_threads(&self, pr_number: u64) -> Result<Vec<ReviewThread>> {
+        self.log(Level::Debug, format!("Reading review threads on PR #{}", pr_number));
+        self.0.unresolved_review_threads(pr_number).await
+    }
+
+    async fn add_labels(&self, pr_number: u64, labels: &[String]) -> Result<()> {
+        self.log(Level::Info, format!("Labelling PR #{} {}", pr_number, labels.join(", ")));
+        self.0.add_labels(pr_number, labels).await
+    }
+
+    async fn request_reviewers(&self, pr_number: u64, reviewers: &[String]) -> Result<()> {
+        self.log(Level::Info, format!("Requesting review of PR #{} from {}", pr_number, reviewers.join(", ")));
+        self.0.request_reviewers(pr_number, reviewers).await
+    }
+
+    async fn set_milestone(&self, pr_number: u64, title: &str) -> Result<()> {
+        self.log(Level::Info, format!("Setting the milestone of PR #{} to {}", pr_number, title));
+        self.0.set_milestone(pr_number, title).await
+    }
+
+    async fn add_to_project(&self, pr_number: u64, project: &Project) -> Result<String> {
+        self.log(Level::Info, format!("Adding PR #{} to project {}", pr_number, project));
+        self.0.add_to_project(pr_number, project).await
+    }
+
+    async fn set_project_status(&self, project: &Project, item_id: &str, status: &str) -> Result<()> {
+        self.log(Level::Info, format!("Setting item {} in project {} to {}", item_id, project, status));
+        self.0.set_project_status(project, item_id, status).await
+    }
+}
+
+/// Build the forge backend for the repository described by `state`. A GitHub
+/// App is exchanged for its installation on the repository here.
+pub async fn connect(state: &MergesState) -> Result<Box<dyn Forge>> {
+    let backend: Box<dyn Forge> = match state.forge {
+        ForgeKind::Github => {
+            let credentials = config::github_credentials()?;
+            let (client, app_permissions) =
+                crate::github::connect(&credentials, &state.repo_owner, &state.repo_name).await?;
+            Box::new(
+                github::GitHubForge::new(client, &state.repo_owner, &state.repo_name)
+                    .with_app_permissions(app_permissions),
+            )
+        }
+        ForgeKind::Bitbucket | ForgeKind::BitbucketServer => {
+            let server = state.forge == ForgeKind::BitbucketServer;
+            let api_url = match (&state.forge_api_url, server) {
+                (Some(url), _) => url.clone(),
+                (None, false) => bitbucket::CLOUD_API_URL.to_string(),
+                (None, true) => bail!("Bitbucket Server needs `forge_api_url` in .merges.json"),
+            };
+            Box::new(bitbucket::BitbucketForge::new(
+                &api_url,
+                config::bitbucket_credentials()?,
+                &state.repo_owner,
+                &state.repo_name,
+                server,
+            )?)
+        }
+        ForgeKind::Gitea => {
+            let Some(base_url) = &state.forge_api_url else {
+                bail!("Gitea needs `forge_api_url` in .merges.json")
+            };
+            Box::new(gitea::GiteaForge::new(
+                base_url,
+                config::gitea_token()?,
+                &state.repo_owner,
+                &state.repo_name,
+            )?)
+        }
+        ForgeKind::AzureDevops => {
+            let Some(org_url) = &state.forge_api_url else {
+                bail!("Azure DevOps needs `forge_api_url` in .merges.json")
+            };
+            Box::new(azure::AzureDevOpsForge::new(
+                org_url,
+                config::azure_devops_token()?,
+                &state.repo_owner,
+                &state.repo_name,
+            )?)
+        }
+    };
+    Ok(Box::new(Logged(backend)))
+}
+
+/// Web page comparing `head` against `base`, for linking from PR bodies.
+/// `None` when the state lacks the URL a self-hosted forge needs.
+pub fn compare_url(state: &MergesState, base: &str, head: &str) -> Option<String> {
+    let (owner, repo) = (&state.repo_owner, &state.repo_name);
+    let web = state.forge_api_url.as_deref().map(|u| u.trim_end_matches('/'));
+    match state.forge {
+        ForgeKind::Github => Some(format!("https://github.com/{}/{}/compare/{}...{}", owner, repo, base, head)),
+        ForgeKind::Bitbucket => {
+            Some(format!("https://bitbucket.org/{}/{}/branches/compare/{}%0D{}", owner, repo, head, base))
+        }
+        ForgeKind::BitbucketServer => Some(format!(
+            "{}/projects/{}/repos/{}/compare/diff?sourceBranch=refs/heads/{}&targetBranch=refs/heads/{}",
+            web?, owner, repo, head, base
+        )),
+        ForgeKind::Gitea => Some(format!("{}/{}/{}/compare/{}...{}", web?, owner, repo, base, head)),
+        ForgeKind::AzureDevops => Some(format!(
+            "{}/{}/_git/{}/branchCompare?baseVersion=GB{}&targetVersion=GB{}",
+            web?, owner, repo, base, head
+        )),
+    }
+}
+
+/// Repository coordinates parsed from a git remote URL.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteRepo {
+    pub kind: ForgeKind,
+    pub owner: String,
+    pub repo: String,
+    /// API base URL for self-hosted forges; `None` for the public services.
+    pub api_url: Option<String>,
+}
+
+/// Work out which forge hosts `url` and the owner/repo it points at.
+///
+/// - `github.com` → GitHub
+/// - `bitbucket.org` → Bitbucket Cloud
+/// - `https://host[/context]/scm/PROJECT/repo.git` or SSH on port 7999 → Bitbucket Server
+/// - `codeberg.org`, `gitea.com`, or a `gitea.*`/`forgejo.*` host → Gitea
+/// - `dev.azure.com/{org}/{project}/_git/{repo}`, `ssh.dev.azure.com:v3/{org}/{project}/{repo}`,
+///   or `{org}.visualstudio.com` → Azure DevOps (`owner` is the project)
+///
+/// `forge` overrides host-based detection for self-hosted instances on other domains.
+pub fn detect(url: &str, forge: Option<ForgeKind>) -> Result<RemoteRepo> {
+    let Some(parts) = split_remote_url(url) else {
+        bail!("Cannot parse remote URL: {}", url)
+    };
+    let segments: Vec<&str> = parts.path.split('/').filter(|s| !s.is_empty()).collect();
+
+    let owner_repo = |segs: &[&str]| -> Result<(String, String)> {
+        match segs {
+            [owner, repo] => Ok((owner.to_string(), repo.to_string())),
+            _ => bail!("Cannot parse owner/repo from remote URL: {}", url),
+        }
+    };
+
+    let Some(kind) = forge.or_else(|| guess_kind(&parts, &segments)) else {
+        bail!(
+            "Unsupported forge for remote URL: {} (supported: GitHub, Bitbucket, Gitea, Azure DevOps; pass --forge for self-hosted instances)",
+            url
+        )
+    };
+
+    match kind {
+        ForgeKind::Github => {
+            let (owner, repo) = crate::git::parse_github_owner_repo(url)?;
+            Ok(RemoteRepo { kind, owner, repo, api_url: None })
+        }
+        ForgeKind::Bitbucket => {
+            let (owner, repo) = owner_repo(&segments)?;
+            Ok(RemoteRepo { kind, owner, repo, api_url: None })
+        }
+        ForgeKind::BitbucketServer => {
+            // Bitbucket Server/Data Center serves HTTP clones under `<context>/scm/`.
+            let (owner, repo, api_url) = match segments.iter().position(|s| *s == "scm") {
+                Some(scm) => {
+                    let (owner, repo) = owner_repo(&segments[scm + 1..])?;
+                    (owner, repo, format!("{}{}", parts.http_origin(), context_path(&segments[..scm])))
+                }
+                None => {
+                    let (owner, repo) = owner_repo(&segments)?;
+                    (owner, repo, parts.http_origin())
+                }
+            };
+            Ok(RemoteRepo { kind, owner, repo, api_url: Some(api_url) })
+        }
+        ForgeKind::Gitea => {
+            // Gitea may be served under a sub-path; owner/repo are the last two segments.
+            let split = segments.len().saturating_sub(2);
+            let (owner, repo) = owner_repo(&segments[split..])?;
+            let context = if parts.is_http() { context_path(&segments[..split]) } else { String::new() };
+            let api_url = format!("{}{}", parts.http_origin(), context);
+            Ok(RemoteRepo { kind, owner, repo, api_url: Some(api_url) })
+        }
+        ForgeKind::AzureDevops => {
+            // HTTPS: `{org}/{project}/_git/{repo}` on dev.azure.com, `[collection/]{project}/_git/{repo}`
+            // on visualstudio.com. SSH: `v3/{org}/{project}/{repo}`.
+            let (org, project, repo) = match segments.iter().position(|s| *s == "_git") {
+                Some(git) if git >= 1 && segments.len() == git + 2 => {
+                    let org = if parts.host.ends_with(".visualstudio.com") { None } else { segments.first() };
+                    (org.copied(), segments[git - 1], segments[git + 1])
+                }
+                _ => match segments.as_slice() {
+                    ["v3", org, project, repo] => (Some(*org), *project, *repo),
+                    _ => bail!("Cannot parse Azure DevOps project/repo from remote URL: {}", url),
+                },
+            };
+            let api_url = match parts.host.strip_prefix("vs-ssh.") {
+                Some(_) => format!("https://{}.visualstudio.com", org.unwrap_or_default()),
+                None if parts.host.ends_with(".visualstudio.com") => format!("https://{}", parts.host),
+                None => match org {
+                    Some(org) => format!("https://dev.azure.com/{}", org),
+                    None => bail!("Cannot parse Azure DevOps organisation from remote URL: {}", url),
+                },
+            };
+            Ok(RemoteRepo { kind, owner: project.to_string(), repo: repo.to_string(), api_url: Some(api_url) })
+        }
+    }
+}
+
+/// Pick a forge from well-known hosts and URL shapes.
+fn guess_kind(parts: &RemoteParts, segments: &[&str]) -> Option<ForgeKind> {
+    let first_label = parts.host.split('.').next().unwrap_or_default();
+    match parts.host.as_str() {
+        "github.com" => Some(ForgeKind::Github),
+        "bitbucket.org" => Some(ForgeKind::Bitbucket),
+        "codeberg.org" | "gitea.com" => Some(ForgeKind::Gitea),
+        "dev.azure.com" | "ssh.dev.azure.com" => Some(ForgeKind::AzureDevops),
+        host if host.ends_with(".visualstudio.com") => Some(ForgeKind::AzureDevops),
+        _ if segments.contains(&"scm") || (parts.scheme == "ssh" && parts.port == Some(7999)) => {
+            Some(ForgeKind::BitbucketServer)
+        }
+        _ if matches!(first_label, "gitea" | "forgejo") => Some(ForgeKind::Gitea),
+        _ => None,
+    }
+}
+
+/// `/a/b` for the URL segments preceding the repository path.
+fn context_path(segments: &[&str]) -> String {
+    segments.iter().map(|s| format!("/{}", s)).collect()
+}
+
+/// Components of a remote URL. scp-style `git@host:path` is reported as `ssh`.
+#[derive(Debug, Clone, PartialEq)]
+struct RemoteParts {
+    scheme: String,
+    host: String,
+    port: Option<u16>,
+    /// Repository path without leading/trailing slashes or `.git` suffix.
+    path: String,
+}
+
+impl RemoteParts {
+    fn is_http(&self) -> bool {
+        matches!(self.scheme.as_str(), "http" | "https")
+    }
+
+    /// `scheme://host[:port]` for HTTP(S) remotes; SSH remotes map to `https://host`.
+    fn http_origin(&self) -> String {
+        match (self.scheme.as_str(), self.port) {
+            ("http" | "https", Some(port)) => format!("{}://{}:{}", self.scheme, self.host, port),
+            ("http" | "https", None) => format!("{}://{}", self.scheme, self.host),
+            _ => format!("https://{}", self.host),
+        }
+    }
+}
+
+fn split_remote_url(url: &str) -> Option<RemoteParts> {
+    let url = url.trim();
+    let clean_path = |p: &str| p.trim_matches('/').trim_end_matches(".git").trim_end_matches('/').to_string();
+
+    if let Ok(parsed) = url::Url::parse(url) {
+        let host = parsed.host_str()?.to_string();
+        return Some(RemoteParts {
+            scheme: parsed.scheme().to_string(),
+            host,
+            port: parsed.port(),
+            path: clean_path(parsed.path()),
+        });
+    }
+
+    // scp-style: [user@]host:path, also seen as `ssh://git@host:owner/repo`,
+    // which `Url` rejects for its non-numeric port.
+    let scp = url.strip_prefix("ssh://").unwrap_or(url);
+    let (authority, path) = scp.split_once(':')?;
+    let host = authority.rsplit('@').next()?.to_string();
+    if host.is_empty() || path.is_empty() {
+        return None;
+    }
+    Some(RemoteParts { scheme: "ssh".to_string(), host, port: None, path: clean_path(path) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(name: &str, outcome: CheckOutcome) -> CheckResult {
+        CheckResult { name: name.to_string(), outcome }
+    }
+
+    // ── CheckOutcome ──────────────────────────────────────────────────────
+
+    #[test]
+    fn test_check_run_in_progress_is_pending() {
+        assert_eq!(CheckOutcome::from_check_run("in_progress", None), CheckOutcome::Pending);
+        assert_eq!(CheckOutcome::from_check_run("queued", None), CheckOutcome::Pending);
+    }
+
+    #[test]
+    fn test_check_run_conclusions() {
+        assert_eq!(CheckOutcome::from_check_run("completed", Some("success")), CheckOutcome::Success);
+        assert_eq!(CheckOutcome::from_check_run("completed", Some("skipped")), CheckOutcome::Success);
+        assert_eq!(CheckOutcome::from_check_run("completed", Some("neutral")), CheckOutcome::Success);
+        assert_eq!(CheckOutcome::from_check_run("completed", Some("failure")), CheckOutcome::Failure);
+        assert_eq!(CheckOutcome::from_check_run("completed", Some("timed_out")), CheckOutcome::Failure);
+        assert_eq!(CheckOutcome::from_check_run("completed", Some("cancelled")), CheckOutcome::Failure);
+    }
+
+    #[test]
+    fn test_status_states() {
+        assert_eq!(CheckOutcome::from_status_state("success"), CheckOutcome::Success);
+        assert_eq!(CheckOutcome::from_status_state("error"), CheckOutcome::Failure);
+        assert_eq!(CheckOutcome::from_status_state("pending"), CheckOutcome::Pending);
+    }
+
+    // ── aggregate_checks ──────────────────────────────────────────────────
+
+    /// Repos using only Actions report no commit statuses — that must not read as pending.
+    #[test]
+    fn test_aggregate_all_check_runs_succeeded() {
+        let (state, failing) = aggregate_checks(&[
+            check("build", CheckOutcome::Success),
+            check("lint", CheckOutcome::Success),
+        ]);
+        assert_eq!(state, "success");
+        assert!(failing.is_empty());
+    }
+
+    #[test]
+    fn test_aggregate_failure_wins_and_lists_names() {
+        let (state, failing) = aggregate_checks(&[
+            check("build", CheckOutcome::Success),
+            check("lint", CheckOutcome::Failure),
+            check("test", CheckOutcome::Pending),
+        ]);
+        assert_eq!(state, "failure");
+        assert_eq!(failing, vec!["lint"]);
+    }
+
+    #[test]
+    fn test_aggregate_pending_when_any_running() {
+        let (state, _) = aggregate_checks(&[
+            check("build", CheckOutcome::Success),
+            check("test", CheckOutcome::Pending),
+        ]);
+        assert_eq!(state, "pending");
+    }
+
+    #[test]
+    fn test_aggregate_no_checks_is_none() {
+        let (state, failing) = aggregate_checks(&[]);
+        assert_eq!(state, "none");
+        assert!(failing.is_empty());
+    }
+
+    // ── detect ────────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_detect_github_https_and_ssh() {
+        for url in ["https://github.com/acme/myrepo.git", "git@github.com:acme/myrepo.git"] {
+            let remote = detect(url, None).unwrap();
+            assert_eq!(remote.kind, ForgeKind::Github);
+            assert_eq!((remote.owner.as_str(), remote.repo.as_str()), ("acme", "myrepo"));
+            assert_eq!(remote.api_url, None);
+        }
+    }
+
+    #[test]
+    fn test_detect_github_ssh_variants() {
+        for url in [
+            "ssh://git@github.com/acme/myrepo.git",
+            "ssh://git@github.com:22/acme/myrepo.git",
+            "ssh://git@github.com:acme/myrepo.git",
+            "org-12345@github.com:acme/myrepo.git",
+        ] {
+            let remote = detect(url, None).unwrap();
+            assert_eq!(remote.kind, ForgeKind::Github, "{}", url);
+            assert_eq!((remote.owner.as_str(), remote.repo.as_str()), ("acme", "myrepo"), "{}", url);
+        }
+    }
+
+    #[test]
+    fn test_detect_bitbucket_cloud() {
+        for url in [
+            "https://alice@bitbucket.org/acme/myrepo.git",
+            "git@bitbucket.org:acme/myrepo.git",
+        ] {
+            let remote = detect(url, None).unwrap();
+            assert_eq!(remote.kind, ForgeKind::Bitbucket, "{}", url);
+            assert_eq!((remote.owner.as_str(), remote.repo.as_str()), ("acme", "myrepo"));
+        }
+    }
+
+    #[test]
+    fn test_detect_bitbucket_server_http_with_context_path() {
+        let remote = detect("https://git.corp.example/bitbucket/scm/PROJ/myrepo.git", None).unwrap();
+        assert_eq!(remote.kind, ForgeKind::BitbucketServer);
+        assert_eq!((remote.owner.as_str(), remote.repo.as_str()), ("PROJ", "myrepo"));
+        assert_eq!(remote.api_url.as_deref(), Some("https://git.corp.example/bitbucket"));
+    }
+
+    #[test]
+    fn test_detect_bitbucket_server_ssh() {
+        let remote = detect("ssh://git@git.corp.example:7999/proj/myrepo.git", None).unwrap();
+        assert_eq!(remote.kind, ForgeKind::BitbucketServer);
+        assert_eq!((remote.owner.as_str(), remote.repo.as_str()), ("proj", "myrepo"));
+        assert_eq!(remote.api_url.as_deref(), Some("https://git.corp.example"));
+    }
+
+    #[test]
+    fn test_detect_unknown_host_is_error() {
+        let result = detect("https://example.com/acme/myrepo.git", None);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("Unsupported"));
+    }
+
+    #[test]
+    fn test_detect_codeberg() {
+        for url in ["https://codeberg.org/acme/myrepo.git", "git@codeberg.org:acme/myrepo.git"] {
+            let remote = detect(url, None).unwrap();
+            assert_eq!(remote.kind, ForgeKind::Gitea, "{}", url);
+            assert_eq!((remote.owner.as_str(), remote.repo.as_str()), ("acme", "myrepo"));
+            assert_eq!(remote.api_url.as_deref(), Some("https://codeberg.org"));
+        }
+    }
+
+    #[test]
+    fn test_detect_self_hosted_gitea_with_sub_path() {
+        let remote = detect("https://gitea.corp.example:3000/git/acme/myrepo.git", None).unwrap();
+        assert_eq!(remote.kind, ForgeKind::Gitea);
+        assert_eq!((remote.owner.as_str(), remote.repo.as_str()), ("acme", "myrepo"));
+        assert_eq!(remote.api_url.as_deref(), Some("https://gitea.corp.example:3000/git"));
+    }
+
+    /// Instances on arbitrary domains are selected explicitly.
+    #[test]
+    fn test_detect_forced_gitea_on_custom_host() {
+        let remote = detect("git@git.corp.example:acme/myrepo.git", Some(ForgeKind::Gitea)).unwrap();
+        assert_eq!(remote.kind, ForgeKind::Gitea);
+        assert_eq!(remote.api_url.as_deref(), Some("https://git.corp.example"));
+    }
+
+    #[test]
+    fn test_detect_azure_devops_https_and_ssh() {
+        for url in [
+            "https://acme@dev.azure.com/acme/Payments/_git/myrepo",
+            "git@ssh.dev.azure.com:v3/acme/Payments/myrepo",
+        ] {
+            let remote = detect(url, None).unwrap();
+            assert_eq!(remote.kind, ForgeKind::AzureDevops, "{}", url);
+            assert_eq!((remote.owner.as_str(), remote.repo.as_str()), ("Payments", "myrepo"));
+            assert_eq!(remote.api_url.as_deref(), Some("https://dev.azure.com/acme"));
+        }
+    }
+
+    #[test]
+    fn test_detect_azure_devops_legacy_visualstudio_host() {
+        for url in [
+            "https://acme.visualstudio.com/DefaultCollection/Payments/_git/myrepo",
+            "acme@vs-ssh.visualstudio.com:v3/acme/Payments/myrepo",
+        ] {
+            let remote = detect(url, None).unwrap();
+            assert_eq!(remote.kind, ForgeKind::AzureDevops, "{}", url);
+            assert_eq!((remote.owner.as_str(), remote.repo.as_str()), ("Payments", "myrepo"));
+            assert_eq!(remote.api_url.as_deref(), Some("https://acme.visualstudio.com"));
+        }
+    }
+
+    // ── parse_timestamp ───────────────────────────────────────────────────
+
+    #[test]
+    fn test_parse_timestamp_accepts_rfc3339_and_epoch_millis() {
+        let rfc = parse_timestamp(&serde_json::json!("2026-03-02T11:15:00+01:00")).unwrap();
+        let millis = parse_timestamp(&serde_json::json!(rfc.timestamp_millis())).unwrap();
+        assert_eq!(rfc, millis);
+        assert_eq!(rfc.to_rfc3339(), "2026-03-02T10:15:00+00:00");
+        assert!(parse_timestamp(&serde_json::Value::Null).is_none());
+    }
+
+    // ── compare_url ───────────────────────────────────────────────────────
+
+    #[test]
+    fn test_compare_url_per_forge() {
+        let mut state: MergesState = serde_json::from_value(serde_json::json!({
+            "base_branch": "main",
+            "source_branch": "feat/big",
+            "repo_owner": "acme",
+            "repo_name": "myrepo",
+            "strategy": "stacked",
+            "chunks": []
+        }))
+        .unwrap();
+        assert_eq!(
+            compare_url(&state, "main", "feat/big").as_deref(),
+            Some("https://github.com/acme/myrepo/compare/main...feat/big")
+        );
+
+        state.forge = ForgeKind::Gitea;
+        assert_eq!(compare_url(&state, "main", "feat/big"), None);
+        state.forge_api_url = Some("https://codeberg.org/".to_string());
+        assert_eq!(
+            compare_url(&state, "main", "feat/big").as_deref(),
+            Some("https://codeberg.org/acme/myrepo/compare/main...feat/big")
+        );
+    }
+
+    // ── create_or_update_pr ───────────────────────────────────────────────
+
+    /// A second push after a transient error must reuse the PR it already opened.
+    #[tokio::test]
+    async fn test_create_or_update_pr_reuses_open_pr() {
+        let forge = memory::MemoryForge::new();
+        let (number, url, created) =
+            forge.create_or_update_pr("[1/2] db", "feat/big-chunk-1-db", "main", "").await.unwrap();
+        assert_eq!((number, created), (1, true));
+
+        let second = forge.create_or_update_pr("[1/3] db", "feat/big-chunk-1-db", "develop", "").await.unwrap();
+        assert_eq!(second, (1, url, false));
+
+        let prs = forge.prs();
+        assert_eq!(prs.len(), 1);
+        assert_eq!(prs[0].base, "develop", "existing PR should be retargeted");
+        assert_eq!(prs[0].title, "[1/3] db", "existing PR title should be refreshed");
+    }
+}
@@ -2,16 +2,72 @@ use anyhow::{bail, Context, Result};
 use git2::Repository;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::{Mutex, Once};
+
+/// A `git` command whose messages are in English whatever the user's locale
+/// (`LC_ALL=C`), so the output merges reads — and the git errors it passes on
+/// — mean the same everywhere. System-wide config is still read: it can hold
+/// the credential helper and `safe.directory` entries git needs.
+pub fn command() -> Command {
+    let mut command = Command::new("git");
+    command.env("LC_ALL", "C");
+    command
+}
+
+/// Repository named with the global `--repo` flag.
+static REPO: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// Work on the repository containing `path` for the rest of the process,
+/// instead of the one around the current directory.
+pub fn set_repo(path: &Path) {
+    *REPO.lock().unwrap_or_else(|e| e.into_inner()) = Some(path.to_path_buf());
+}
 
-/// Find the git repository root from the current directory.
+/// Find the git repository root: the repository containing the path given
+/// with `--repo` or in `MERGES_REPO`, else the one git itself would pick —
+/// `GIT_DIR`/`GIT_WORK_TREE` when set, otherwise found from the current
+/// directory. When that one is a submodule, it is used with a warning (once
+/// per process) naming the repository around it.
 pub fn repo_root() -> Result<PathBuf> {
-    let repo = Repository::discover(".")
-        .context("Not inside a git repository")?;
+    let chosen = REPO
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .or_else(|| std::env::var_os("MERGES_REPO").filter(|v| !v.is_empty()).map(PathBuf::from));
+    let repo = match &chosen {
+        Some(path) => Repository::discover(path)
+            .with_context(|| format!("'{}' is not inside a git repository", path.display()))?,
+        None => Repository::open_from_env().context("Not inside a git repository")?,
+    };
     let workdir = repo.workdir()
         .context("Bare repositories are not supported")?;
+    static WARNED: Once = Once::new();
+    let outer = if chosen.is_none() && !WARNED.is_completed() { superproject(workdir) } else { None };
+    if let Some(outer) = outer {
+        WARNED.call_once(|| {
+            crate::warning!(
+                "'{}' is a submodule of '{}' — merges works on the submodule. Pass --repo {} (or set MERGES_REPO) \
+                 to use the outer repository.",
+                workdir.display(),
+                outer.display(),
+                outer.display()
+            )
+        });
+    }
     Ok(workdir.to_path_buf())
 }
 
+/// The working tree of the repository that has the one at `root` as a
+/// submodule, if it is one.
+pub fn superproject(root: &Path) -> Option<PathBuf> {
+    let output = command()
+        .args(["-C", root.to_str()?, "rev-parse", "--show-superproject-working-tree"])
+        .output()
+        .ok()?;
+    let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !path.is_empty()).then(|| PathBuf::from(path))
+}
+
 /// Return the name of the currently checked-out branch.
 pub fn current_branch(root: &Path) -> Result<String> {
     let repo = Repository::open(root)?;
@@ -24,12 +80,13 @@ pub fn current_branch(root: &Path) -> Result<String> {
 /// List files changed between `base_branch` and HEAD (working-tree aware).
 pub fn changed_files(root: &Path, base_branch: &str) -> Result<Vec<String>> {
     // Use git diff --name-only for reliability across merge-base scenarios.
-    let output = Command::new("git")
+    let output = command()
         .args([
             "-C",
             root.to_str().unwrap(),
             "diff",
             "--name-only",
+            "-z",
             &format!("{}...HEAD", base_branch),
         ])
         .output()
@@ -40,18 +97,253 @@ pub fn changed_files(root: &Path, base_branch: &str) -> Result<Vec<String>> {
         bail!("git diff failed: {}", stderr);
     }
 
-    let files = String::from_utf8_lossy(&output.stdout)
+    Ok(nul_paths(&output.stdout))
+}
+
+/// Split `-z` output — paths each ended by a NUL, as git prints them with no
+/// quoting or escaping — into paths. They are kept as UTF-8 since chunks and
+/// `.merges.json` hold them as strings; a path that is not is left out, with
+/// a warning the first time it is seen, rather than being mangled into one
+/// that does not exist or failing the whole command over one file.
+fn nul_paths(output: &[u8]) -> Vec<String> {
+    static SKIPPED: Mutex<Vec<Vec<u8>>> = Mutex::new(Vec::new());
+    output
+        .split(|&b| b == 0)
+        .filter(|p| !p.is_empty())
+        .filter_map(|p| match std::str::from_utf8(p) {
+            Ok(path) => Some(path.to_string()),
+            Err(_) => {
+                let mut skipped = SKIPPED.lock().unwrap_or_else(|e| e.into_inner());
+                if !skipped.iter().any(|s| s == p) {
+                    skipped.push(p.to_vec());
+                    crate::warning!(
+                        "Leaving out '{}': it is not a UTF-8 path, which merges does not support. Rename it to \
+                         put it in a chunk.",
+                        String::from_utf8_lossy(p)
+                    );
+                }
+                None
+            }
+        })
+        .collect()
+}
+
+/// List files changed on `to` since it diverged from `from` (`from...to`).
+pub fn diff_files(root: &Path, from: &str, to: &str) -> Result<Vec<String>> {
+    Ok(nul_paths(&diff_names(root, from, to)?))
+}
+
+/// The files [`diff_files`] leaves out because their paths are not UTF-8,
+/// decoded lossily so they can be shown.
+pub fn non_utf8_diff_files(root: &Path, from: &str, to: &str) -> Result<Vec<String>> {
+    Ok(diff_names(root, from, to)?
+        .split(|&b| b == 0)
+        .filter(|p| !p.is_empty() && std::str::from_utf8(p).is_err())
+        .map(|p| String::from_utf8_lossy(p).into_owned())
+        .collect())
+}
+
+fn diff_names(root: &Path, from: &str, to: &str) -> Result<Vec<u8>> {
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "diff", "--name-only", "-z", &format!("{}...{}", from, to)])
+        .output()
+        .context("Failed to run `git diff`")?;
+
+    if !output.status.success() {
+        bail!("git diff {}...{} failed: {}", from, to, String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(output.stdout)
+}
+
+/// Lines added and removed in one file of a diff; both `None` for binary files.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileStat {
+    pub path: String,
+    pub added: Option<usize>,
+    pub removed: Option<usize>,
+}
+
+/// Hunk headers (`@@ -3,2 +3,4 @@`) of the diff of `file` from `from` to `to`.
+pub fn hunk_headers(root: &Path, from: &str, to: &str, file: &str) -> Result<Vec<String>> {
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "--literal-pathspecs", "diff", "--unified=0", from, to, "--", file])
+        .output()
+        .context("Failed to run `git diff`")?;
+
+    if !output.status.success() {
+        bail!("git diff {} {} failed: {}", from, to, String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
         .lines()
-        .map(|l| l.to_string())
-        .filter(|l| !l.is_empty())
-        .collect();
+        .filter(|l| l.starts_with("@@"))
+        .map(|l| l.split(' ').take(4).collect::<Vec<_>>().join(" "))
+        .collect())
+}
+
+/// Per-file line counts for the changes on `to` since it diverged from `from`.
+pub fn diff_numstat(root: &Path, from: &str, to: &str) -> Result<Vec<FileStat>> {
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "diff", "--numstat", "--no-renames", "-z", &format!("{}...{}", from, to)])
+        .output()
+        .context("Failed to run `git diff`")?;
 
-    Ok(files)
+    if !output.status.success() {
+        bail!("git diff {}...{} failed: {}", from, to, String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(nul_paths(&output.stdout)
+        .into_iter()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\t');
+            let (added, removed, path) = (fields.next()?, fields.next()?, fields.next()?);
+            Some(FileStat { path: path.to_string(), added: added.parse().ok(), removed: removed.parse().ok() })
+        })
+        .collect())
+}
+
+/// The files changed by each of the last `limit` non-merge commits reachable
+/// from `rev`, newest first.
+pub fn commit_files(root: &Path, rev: &str, limit: usize) -> Result<Vec<Vec<String>>> {
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "log", "--no-merges", "--name-only", "-z", "--format=%x01"])
+        .arg(format!("-n{}", limit))
+        .arg(rev)
+        .arg("--")
+        .output()
+        .context("Failed to run `git log`")?;
+
+    if !output.status.success() {
+        bail!("git log {} failed: {}", rev, String::from_utf8_lossy(&output.stderr).trim());
+    }
+    // Each commit is a \x01 line, then its paths NUL-terminated.
+    let mut commits = vec![];
+    for commit in output.stdout.split(|&b| b == 1) {
+        let files = nul_paths(commit.strip_prefix(b"\0\n").unwrap_or(commit));
+        if !files.is_empty() {
+            commits.push(files);
+        }
+    }
+    Ok(commits)
+}
+
+/// Which of `files` differ between `from` and `to`.
+pub fn differing_files(root: &Path, from: &str, to: &str, files: &[String]) -> Result<Vec<String>> {
+    if files.is_empty() {
+        return Ok(vec![]);
+    }
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "--literal-pathspecs", "diff", "--name-only", "-z", from, to, "--"])
+        .args(files)
+        .output()
+        .context("Failed to run `git diff`")?;
+
+    if !output.status.success() {
+        bail!("git diff {} {} failed: {}", from, to, String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(nul_paths(&output.stdout))
+}
+
+/// Branches on origin (as last fetched) whose names start with `prefix`,
+/// without the `origin/` part.
+pub fn remote_branches(root: &Path, prefix: &str) -> Result<Vec<String>> {
+    let output = command()
+        .args([
+            "-C",
+            root.to_str().unwrap(),
+            "for-each-ref",
+            "--format=%(refname:strip=3)",
+            &format!("refs/remotes/origin/{}*", prefix),
+        ])
+        .output()
+        .context("Failed to run `git for-each-ref`")?;
+
+    if !output.status.success() {
+        bail!("git for-each-ref failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).lines().filter(|l| !l.is_empty()).map(String::from).collect())
+}
+
+/// Whether the local branch `branch` exists.
+pub fn branch_exists(root: &Path, branch: &str) -> bool {
+    command()
+        .args(["-C", root.to_str().unwrap(), "show-ref", "--verify", "--quiet", &format!("refs/heads/{}", branch)])
+        .status()
+        .is_ok_and(|s| s.success())
+}
+
+/// Whether `ancestor` is reachable from `rev`.
+pub fn is_ancestor(root: &Path, ancestor: &str, rev: &str) -> bool {
+    command()
+        .args(["-C", root.to_str().unwrap(), "merge-base", "--is-ancestor", ancestor, rev])
+        .stdout(crate::output::child_stdout())
+        .status()
+        .is_ok_and(|s| s.success())
+}
+
+/// Whether the working tree or index has uncommitted changes to tracked files.
+pub fn is_dirty(root: &Path) -> Result<bool> {
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "status", "--porcelain", "--untracked-files=no"])
+        .output()
+        .context("Failed to run `git status`")?;
+
+    if !output.status.success() {
+        bail!("git status failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(!output.stdout.is_empty())
+}
+
+/// Tell MCP clients about a git command that changes the repository or the
+/// remote (read-only commands are not reported).
+fn report(args: &[&str]) {
+    crate::output::log(crate::output::Level::Info, "git", &format!("git {}", args.join(" ")));
+}
+
+/// Stash uncommitted changes to tracked files under `message`.
+pub fn stash_push(root: &Path, message: &str) -> Result<()> {
+    report(&["stash", "push", "--message", message]);
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "stash", "push", "--quiet", "--message", message])
+        .output()
+        .context("Failed to run `git stash push`")?;
+
+    if !output.status.success() {
+        bail!("git stash push failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+/// Re-apply and drop the most recent stash.
+pub fn stash_pop(root: &Path) -> Result<()> {
+    report(&["stash", "pop"]);
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "stash", "pop", "--quiet"])
+        .output()
+        .context("Failed to run `git stash pop`")?;
+
+    if !output.status.success() {
+        bail!("git stash pop failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+/// Reset the branch checked out in `dir` (and its index and files) to `rev`.
+pub fn reset_hard(dir: &Path, rev: &str) -> Result<()> {
+    report(&["reset", "--hard", rev]);
+    let output = command()
+        .args(["-C", dir.to_str().unwrap(), "reset", "--quiet", "--hard", rev])
+        .output()
+        .context("Failed to run `git reset --hard`")?;
+
+    if !output.status.success() {
+        bail!("git reset --hard {} failed: {}", rev, String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
 }
 
 /// Create a new branch pointing at `base_ref` (e.g. the merge-base with main).
 pub fn create_branch(root: &Path, branch_name: &str, base_ref: &str) -> Result<()> {
-    let status = Command::new("git")
+    report(&["checkout", "-b", branch_name, base_ref]);
+    let status = command()
         .args([
             "-C",
             root.to_str().unwrap(),
@@ -60,6 +352,7 @@ pub fn create_branch(root: &Path, branch_name: &str, base_ref: &str) -> Result<(
             branch_name,
             base_ref,
         ])
+        .stdout(crate::output::child_stdout())
         .status()
         .context("Failed to run `git checkout -b`")?;
 
@@ -71,8 +364,10 @@ pub fn create_branch(root: &Path, branch_name: &str, base_ref: &str) -> Result<(
 
 /// Checkout an existing branch.
 pub fn checkout(root: &Path, branch_name: &str) -> Result<()> {
-    let status = Command::new("git")
+    report(&["checkout", branch_name]);
+    let status = command()
         .args(["-C", root.to_str().unwrap(), "checkout", branch_name])
+        .stdout(crate::output::child_stdout())
         .status()
         .context("Failed to run `git checkout`")?;
 
@@ -84,7 +379,7 @@ pub fn checkout(root: &Path, branch_name: &str) -> Result<()> {
 
 /// Find the merge-base commit between `base_branch` and HEAD.
 pub fn merge_base(root: &Path, base_branch: &str) -> Result<String> {
-    let output = Command::new("git")
+    let output = command()
         .args([
             "-C",
             root.to_str().unwrap(),
@@ -96,143 +391,967 @@ pub fn merge_base(root: &Path, base_branch: &str) -> Result<String> {
         .context("Failed to run `git merge-base`")?;
 
     if !output.status.success() {
-        bail!("git merge-base failed");
+        bail!("git merge-base failed");
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Find the merge-base commit between two arbitrary refs.
+pub fn merge_base_of(root: &Path, a: &str, b: &str) -> Result<String> {
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "merge-base", a, b])
+        .output()
+        .context("Failed to run `git merge-base`")?;
+
+    if !output.status.success() {
+        bail!("git merge-base {} {} failed", a, b);
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Resolve `rev` (a branch, tag, or expression) to a full commit SHA.
+pub fn rev_parse(root: &Path, rev: &str) -> Result<String> {
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "rev-parse", "--verify", "--quiet", rev])
+        .output()
+        .context("Failed to run `git rev-parse`")?;
+
+    if !output.status.success() {
+        bail!("git rev-parse {} failed", rev);
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Create a local branch `branch_name` tracking `origin/<branch_name>`,
+/// without checking it out.
+pub fn track_remote_branch(root: &Path, branch_name: &str) -> Result<()> {
+    report(&["branch", "--track", branch_name, &format!("origin/{}", branch_name)]);
+    let status = command()
+        .args([
+            "-C",
+            root.to_str().unwrap(),
+            "branch",
+            "--quiet",
+            "--track",
+            branch_name,
+            &format!("origin/{}", branch_name),
+        ])
+        .stdout(crate::output::child_stdout())
+        .status()
+        .context("Failed to run `git branch --track`")?;
+
+    if !status.success() {
+        bail!("Failed to create branch '{}' from origin", branch_name);
+    }
+    Ok(())
+}
+
+/// Take `files` from the source branch into the index and working tree,
+/// exactly as they are there (see [`take_files`]).
+pub fn checkout_files_from(root: &Path, source_branch: &str, files: &[String]) -> Result<()> {
+    if files.is_empty() {
+        return Ok(());
+    }
+    report(&["checkout", source_branch, "--", &format!("({} files)", files.len())]);
+    take_files(root, source_branch, files).with_context(|| format!("Failed to checkout files from '{}'", source_branch))
+}
+
+/// Set `files` in the index and working tree of `dir` to their entries at
+/// `rev`: content, mode, symlink, or submodule, whichever `rev` has. Files
+/// `rev` does not have are removed. The index is written from `rev`'s tree
+/// directly rather than re-staged from disk, so an executable bit or a
+/// symlink survives a checkout that cannot represent it (`core.fileMode` or
+/// `core.symlinks` off), a file replaced by a symlink or a directory (or the
+/// other way around) changes type instead of keeping the old one, and content
+/// that `.gitattributes` or `core.autocrlf` would convert on the way back in
+/// (line endings, clean filters) stays byte-for-byte what `rev` has. Commit
+/// the result with [`commit_staged`], not [`commit_all`].
+pub fn take_files(dir: &Path, rev: &str, files: &[String]) -> Result<()> {
+    let output = command()
+        .args(["-C", dir.to_str().unwrap(), "--literal-pathspecs", "ls-tree", "-r", "-z", rev, "--"])
+        .args(files)
+        .output()
+        .context("Failed to run `git ls-tree`")?;
+    if !output.status.success() {
+        bail!("git ls-tree {} failed: {}", rev, String::from_utf8_lossy(&output.stderr).trim());
+    }
+    let entries = String::from_utf8_lossy(&output.stdout).into_owned();
+    let present: Vec<&str> =
+        entries.split('\0').filter_map(|entry| entry.split_once('\t')).map(|(_, path)| path).collect();
+    let removed: Vec<&String> = files.iter().filter(|f| !present.contains(&f.as_str())).collect();
+
+    // Removals go first so a path that turned from a file into a directory
+    // (or back) is free for its new entries.
+    let mut index_info: String =
+        removed.iter().map(|f| format!("0 0000000000000000000000000000000000000000\t{}\0", f)).collect();
+    index_info.push_str(&entries);
+    git_with_input(dir, &["update-index", "-z", "--index-info"], &index_info)?;
+
+    for file in removed {
+        let path = dir.join(file);
+        if path.symlink_metadata().is_ok_and(|m| !m.is_dir()) {
+            std::fs::remove_file(&path).with_context(|| format!("Failed to remove '{}'", file))?;
+        }
+    }
+    if !present.is_empty() {
+        use std::io::Write;
+        let mut checkout = command();
+        // `-u` records the written files' stat data, so a file the checkout
+        // converted is not taken for a modification.
+        checkout.args(["-C", dir.to_str().unwrap(), "checkout-index", "-u", "-f", "-z", "--stdin"]);
+        if is_chunk_worktree(dir) {
+            skip_lfs_smudge(&mut checkout);
+        }
+        let mut child = checkout
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .context("Failed to run `git checkout-index`")?;
+        child.stdin.take().unwrap().write_all(present.iter().map(|p| format!("{}\0", p)).collect::<String>().as_bytes())?;
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            bail!("git checkout-index failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+        }
+    }
+    Ok(())
+}
+
+/// The first line of every Git LFS pointer file.
+const LFS_POINTER_PREFIX: &str = "version https://git-lfs.github.com/spec/";
+
+/// Which of `files` are stored as Git LFS pointers at `rev`. Pointers are a
+/// few hundred bytes, so only blobs that small are read.
+pub fn lfs_pointers(root: &Path, rev: &str, files: &[String]) -> Result<Vec<String>> {
+    if files.is_empty() {
+        return Ok(vec![]);
+    }
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "--literal-pathspecs", "ls-tree", "-r", "-l", "-z", rev, "--"])
+        .args(files)
+        .output()
+        .context("Failed to run `git ls-tree`")?;
+    if !output.status.success() {
+        bail!("git ls-tree {} failed: {}", rev, String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    let mut pointers = vec![];
+    for entry in String::from_utf8_lossy(&output.stdout).split('\0') {
+        let Some((meta, path)) = entry.split_once('\t') else { continue };
+        let fields: Vec<&str> = meta.split_whitespace().collect();
+        let &[_, "blob", sha, size] = fields.as_slice() else { continue };
+        if !size.parse::<u64>().is_ok_and(|size| size <= 1024) {
+            continue;
+        }
+        let blob = command()
+            .args(["-C", root.to_str().unwrap(), "cat-file", "blob", sha])
+            .output()
+            .context("Failed to run `git cat-file`")?;
+        if blob.stdout.starts_with(LFS_POINTER_PREFIX.as_bytes()) {
+            pointers.push(path.to_string());
+        }
+    }
+    Ok(pointers)
+}
+
+/// Whether `dir` is one of the chunk worktrees under `.git/merges-worktrees`.
+fn is_chunk_worktree(dir: &Path) -> bool {
+    dir.parent().and_then(|p| p.file_name()).is_some_and(|name| name == "merges-worktrees")
+}
+
+/// Leave Git LFS files as pointers in what `cmd` checks out. Chunk worktrees
+/// only hold files to commit, so downloading every large file into each of
+/// them is wasted time; the pointers are what gets committed anyway.
+fn skip_lfs_smudge(cmd: &mut Command) -> &mut Command {
+    cmd.env("GIT_LFS_SKIP_SMUDGE", "1")
+}
+
+/// Stage all files and create a commit.
+pub fn commit_all(root: &Path, message: &str) -> Result<()> {
+    report(&["commit", "-m", message]);
+    let add_out = command()
+        .args(["-C", root.to_str().unwrap(), "add", "-A"])
+        .output()?;
+    if !add_out.status.success() {
+        bail!("git add failed: {}", String::from_utf8_lossy(&add_out.stderr).trim());
+    }
+
+    let commit_out = command()
+        .args(["-C", root.to_str().unwrap(), "commit", "-m", message])
+        .output()?;
+    if !commit_out.status.success() {
+        // Ask the index rather than read git's message, which may be translated.
+        let detail = if !has_staged_changes(root).unwrap_or(true) {
+            "nothing to commit, working tree clean".to_string()
+        } else {
+            format!(
+                "{}{}",
+                String::from_utf8_lossy(&commit_out.stderr).trim(),
+                String::from_utf8_lossy(&commit_out.stdout).trim()
+            )
+        };
+        bail!("git commit failed: {}", detail);
+    }
+    Ok(())
+}
+
+/// Commit what is staged in `root`, leaving the working tree out: unlike
+/// [`commit_all`], files are not re-staged through `.gitattributes`.
+pub fn commit_staged(root: &Path, message: &str) -> Result<()> {
+    report(&["commit", "-m", message]);
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "commit", "-m", message])
+        .output()
+        .context("Failed to run `git commit`")?;
+    if !output.status.success() {
+        let detail = if !has_staged_changes(root).unwrap_or(true) {
+            "nothing to commit".to_string()
+        } else {
+            format!(
+                "{}{}",
+                String::from_utf8_lossy(&output.stderr).trim(),
+                String::from_utf8_lossy(&output.stdout).trim()
+            )
+        };
+        bail!("git commit failed: {}", detail);
+    }
+    Ok(())
+}
+
+/// Whether the index in `root` differs from `HEAD`.
+pub fn has_staged_changes(root: &Path) -> Result<bool> {
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "diff", "--cached", "--quiet"])
+        .output()
+        .context("Failed to run `git diff --cached`")?;
+    match output.status.code() {
+        Some(0) => Ok(false),
+        Some(1) => Ok(true),
+        _ => bail!("git diff --cached failed: {}", String::from_utf8_lossy(&output.stderr).trim()),
+    }
+}
+
+/// Non-merge commits in `from..to` that touch any of `files`, oldest first.
+pub fn commits_touching(root: &Path, from: &str, to: &str, files: &[String]) -> Result<Vec<String>> {
+    if files.is_empty() {
+        return Ok(vec![]);
+    }
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "--literal-pathspecs", "log", "--reverse", "--no-merges", "--format=%H"])
+        .arg(format!("{}..{}", from, to))
+        .arg("--")
+        .args(files)
+        .output()
+        .context("Failed to run `git log`")?;
+
+    if !output.status.success() {
+        bail!("git log {}..{} failed: {}", from, to, String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).lines().filter(|l| !l.is_empty()).map(String::from).collect())
+}
+
+/// Replay `commit` onto the branch checked out in `dir`, keeping only its
+/// changes to `files`: each of them it touched is set to its content at
+/// `commit` (or removed), then committed with `commit`'s message and author.
+/// Returns false, committing nothing, when that leaves nothing to commit.
+pub fn replay_commit(dir: &Path, commit: &str, files: &[String]) -> Result<bool> {
+    let git = |args: &[&str]| command().args(["-C", dir.to_str().unwrap()]).args(args).output();
+
+    let output = command()
+        .args(["-C", dir.to_str().unwrap(), "--literal-pathspecs", "diff-tree", "-r", "-z", "--no-commit-id", "--name-only"])
+        .args(["--no-renames", commit, "--"])
+        .args(files)
+        .output()
+        .context("Failed to run `git diff-tree`")?;
+    if !output.status.success() {
+        bail!("git diff-tree {} failed: {}", commit, String::from_utf8_lossy(&output.stderr).trim());
+    }
+    let touched = nul_paths(&output.stdout);
+
+    report(&["commit", "-C", commit, "--", &format!("({} files)", touched.len())]);
+    take_files(dir, commit, &touched).with_context(|| format!("Failed to take files from {}", commit))?;
+
+    if git(&["diff", "--cached", "--quiet"])?.status.success() {
+        return Ok(false);
+    }
+    let output = git(&["commit", "--quiet", "-C", commit])?;
+    if !output.status.success() {
+        bail!("git commit -C {} failed: {}", commit, String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(true)
+}
+
+/// Files `commit` changes against its first parent.
+pub fn commit_touched_files(root: &Path, commit: &str) -> Result<Vec<String>> {
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "diff-tree", "-r", "-z", "--root", "--no-commit-id", "--name-only", "--no-renames", commit])
+        .output()
+        .context("Failed to run `git diff-tree`")?;
+
+    if !output.status.success() {
+        bail!("git diff-tree {} failed: {}", commit, String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(nul_paths(&output.stdout))
+}
+
+/// Cherry-pick `commits` onto the branch checked out in `dir`, noting in each
+/// message which commit it came from. A cherry-pick that stops on conflicts
+/// is aborted, leaving the branch as it was.
+pub fn cherry_pick(dir: &Path, commits: &[String]) -> Result<()> {
+    report(&["cherry-pick", "-x", &format!("({} commits)", commits.len())]);
+    let output = command()
+        .args(["-C", dir.to_str().unwrap(), "cherry-pick", "-x"])
+        .args(commits)
+        .output()
+        .context("Failed to run `git cherry-pick`")?;
+
+    if !output.status.success() {
+        let _ = command().args(["-C", dir.to_str().unwrap(), "cherry-pick", "--abort"]).output();
+        bail!("git cherry-pick failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+/// Subjects of the commits in `from..to`, oldest first.
+pub fn commit_subjects(root: &Path, from: &str, to: &str) -> Result<Vec<String>> {
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "log", "--reverse", "--format=%s", &format!("{}..{}", from, to)])
+        .output()
+        .context("Failed to run `git log`")?;
+
+    if !output.status.success() {
+        bail!("git log {}..{} failed: {}", from, to, String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).lines().map(String::from).collect())
+}
+
+/// Move the commits after `upstream` on the branch checked out in `dir` onto
+/// `onto`. A rebase that stops on conflicts is left in progress.
+pub fn rebase_onto(dir: &Path, onto: &str, upstream: &str) -> Result<()> {
+    report(&["rebase", "--onto", onto, upstream]);
+    let output = command()
+        .args(["-C", dir.to_str().unwrap(), "rebase", "--quiet", "--onto", onto, upstream])
+        .output()
+        .context("Failed to run `git rebase --onto`")?;
+
+    if !output.status.success() {
+        bail!("git rebase --onto {} {} failed: {}", onto, upstream, String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+/// Fetch latest origin and rebase current branch onto `base_branch`.
+pub fn fetch_and_rebase(root: &Path, base_branch: &str) -> Result<()> {
+    fetch(root)?;
+    rebase(root, base_branch)
+}
+
+/// Fetch from origin.
+pub fn fetch(root: &Path) -> Result<()> {
+    report(&["fetch", "origin"]);
+    let status = command()
+        .args(["-C", root.to_str().unwrap(), "fetch", "origin"])
+        .stdout(crate::output::child_stdout())
+        .status()
+        .context("git fetch failed")?;
+    if !status.success() {
+        bail!("git fetch origin failed");
+    }
+    Ok(())
+}
+
+fn rebase(root: &Path, base_branch: &str) -> Result<()> {
+    let onto = format!("origin/{}", base_branch);
+    report(&["rebase", &onto]);
+
+    let status = command()
+        .args(["-C", root.to_str().unwrap(), "rebase", &onto])
+        .stdout(crate::output::child_stdout())
+        .status()
+        .context("git rebase failed")?;
+    if !status.success() {
+        bail!(
+            "Rebase onto origin/{} failed — resolve conflicts then run `merges sync` again",
+            base_branch
+        );
+    }
+    Ok(())
+}
+
+/// Files with unresolved conflicts in the working tree at `root`.
+pub fn conflicted_files(root: &Path) -> Vec<String> {
+    command()
+        .args(["-C", root.to_str().unwrap(), "diff", "--name-only", "-z", "--diff-filter=U"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| nul_paths(&o.stdout))
+        .unwrap_or_default()
+}
+
+/// Merge `rev` into the branch checked out in `dir`, with git's default
+/// merge message.
+pub fn merge(dir: &Path, rev: &str) -> Result<()> {
+    report(&["merge", "--no-edit", rev]);
+    let output = command()
+        .args(["-C", dir.to_str().unwrap(), "merge", "--quiet", "--no-edit", rev])
+        .output()
+        .context("Failed to run `git merge`")?;
+
+    if !output.status.success() {
+        bail!("git merge {} failed: {}", rev, String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+/// Abandon a merge in progress, restoring the branch to where it was.
+pub fn merge_abort(root: &Path) -> Result<()> {
+    report(&["merge", "--abort"]);
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "merge", "--abort"])
+        .output()
+        .context("Failed to run `git merge --abort`")?;
+    if !output.status.success() {
+        bail!("git merge --abort failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+/// Abandon a rebase in progress, restoring the branch to where it was.
+pub fn rebase_abort(root: &Path) -> Result<()> {
+    report(&["rebase", "--abort"]);
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "rebase", "--abort"])
+        .output()
+        .context("Failed to run `git rebase --abort`")?;
+    if !output.status.success() {
+        bail!("git rebase --abort failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+/// Push a branch to origin and make `origin/<branch>` its upstream, so plain
+/// `git status`, `git pull`, and `git push` work on it. With `force` the push
+/// uses `--force-with-lease`, to handle rebases safely; without it origin
+/// only accepts a fast-forward.
+pub fn push_branch(root: &Path, branch_name: &str, force: bool) -> Result<()> {
+    let mut args = vec!["push", "--set-upstream", "origin", branch_name];
+    if force {
+        args.push("--force-with-lease");
+    }
+    report(&args);
+    let status = command()
+        .args(["-C", root.to_str().unwrap()])
+        .args(&args)
+        .stdout(crate::output::child_stdout())
+        .status()
+        .context("git push failed")?;
+    if !status.success() {
+        bail!("Failed to push branch '{}'", branch_name);
+    }
+    Ok(())
+}
+
+/// Commits on `theirs` that have no equivalent on `ours` — the same change,
+/// even if rebased — and are not reachable from any of `excluding`, oldest
+/// first, one line each in the `git log` `format`.
+pub fn commits_only_on(root: &Path, ours: &str, theirs: &str, excluding: &[&str], format: &str) -> Result<Vec<String>> {
+    let output = command()
+        .args([
+            "-C",
+            root.to_str().unwrap(),
+            "log",
+            "--reverse",
+            &format!("--format={}", format),
+            "--cherry-pick",
+            "--right-only",
+            &format!("{}...{}", ours, theirs),
+        ])
+        .args(excluding.iter().map(|rev| format!("^{}", rev)))
+        .output()
+        .context("Failed to run `git log`")?;
+
+    if !output.status.success() {
+        bail!("git log failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).lines().filter(|l| !l.is_empty()).map(String::from).collect())
+}
+
+/// `git diff --stat` between `from` and `to`, indented for messages.
+pub fn diff_stat(root: &Path, from: &str, to: &str) -> Result<String> {
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "diff", "--stat", from, to])
+        .output()
+        .context("Failed to run `git diff --stat`")?;
+
+    if !output.status.success() {
+        bail!("git diff --stat failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    let lines: Vec<String> = String::from_utf8_lossy(&output.stdout).lines().map(|l| format!("  {}", l.trim_start())).collect();
+    Ok(lines.join("\n"))
+}
+
+/// `git range-diff` between the commit ranges `old` and `new` (each
+/// `base..tip`): which commits were kept, changed, dropped, or added, and how.
+/// Commits are paired generously, since a small commit amended in place — a
+/// chunk commit after `merges fix`, say — otherwise reads as one dropped and
+/// one added, with no interdiff.
+pub fn range_diff(root: &Path, old: &str, new: &str) -> Result<String> {
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "range-diff", "--no-color", "--creation-factor=100", old, new])
+        .output()
+        .context("Failed to run `git range-diff`")?;
+
+    if !output.status.success() {
+        bail!("git range-diff failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim_end().to_string())
+}
+
+/// Tracked files with uncommitted changes (staged or not) against `HEAD`.
+pub fn uncommitted_files(root: &Path) -> Result<Vec<String>> {
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "diff", "HEAD", "--name-only", "-z", "--no-renames"])
+        .output()
+        .context("Failed to run `git diff`")?;
+
+    if !output.status.success() {
+        bail!("git diff HEAD failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(nul_paths(&output.stdout))
+}
+
+/// Create a dangling commit on top of `parent` holding the uncommitted
+/// changes to `files` in `root` (against `HEAD`), applied with a scratch
+/// index so no branch, index, or working tree is touched.
+pub fn commit_changes_onto(root: &Path, parent: &str, files: &[String], message: &str) -> Result<String> {
+    let index = common_dir(root)?.join("merges-scratch-index");
+    let git = |args: &[&str], input: Option<&[u8]>| scratch_git(root, &index, args, input.unwrap_or_default());
+
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "--literal-pathspecs", "diff", "--binary", "HEAD", "--"])
+        .args(files)
+        .output()
+        .context("Failed to run `git diff`")?;
+    if !output.status.success() {
+        bail!("git diff HEAD failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    let patch = output.stdout;
+
+    let result = (|| -> Result<String> {
+        git(&["read-tree", parent], None)?;
+        git(&["apply", "--cached"], Some(&patch))?;
+        let tree = String::from_utf8_lossy(&git(&["write-tree"], None)?).trim().to_string();
+        let commit = git(&["commit-tree", &tree, "-p", parent, "-m", message], None)?;
+        Ok(String::from_utf8_lossy(&commit).trim().to_string())
+    })();
+    let _ = std::fs::remove_file(&index);
+    result
+}
+
+/// Create a dangling commit replacing `tip` — same parent, and its message
+/// unless `message` is given — with `files` as `rev` has them, removed where
+/// `rev` has none. Built in a scratch index like [`commit_changes_onto`], so
+/// nothing else is touched.
+pub fn amend_with_files(root: &Path, tip: &str, rev: &str, files: &[String], message: Option<&str>) -> Result<String> {
+    let index = common_dir(root)?.join("merges-scratch-index");
+    let git = |args: &[&str], input: &[u8]| scratch_git(root, &index, args, input);
+
+    let result = (|| -> Result<String> {
+        git(&["read-tree", tip], b"")?;
+        let mut remove = vec!["update-index", "--force-remove", "--"];
+        remove.extend(files.iter().map(String::as_str));
+        git(&remove, b"")?;
+        let mut list = vec!["ls-tree", "-z", rev, "--"];
+        list.extend(files.iter().map(String::as_str));
+        let entries = git(&list, b"")?;
+        git(&["update-index", "-z", "--index-info"], &entries)?;
+        let tree = String::from_utf8_lossy(&git(&["write-tree"], b"")?).trim().to_string();
+        let message = match message {
+            Some(message) => message.as_bytes().to_vec(),
+            None => git(&["log", "-1", "--format=%B", tip], b"")?,
+        };
+        let commit = git(&["commit-tree", &tree, "-p", &format!("{}^", tip), "-F", "-"], &message)?;
+        Ok(String::from_utf8_lossy(&commit).trim().to_string())
+    })();
+    let _ = std::fs::remove_file(&index);
+    result
+}
+
+/// Run git in `root` against the index file `index`, with `input` on stdin,
+/// and return its stdout.
+fn scratch_git(root: &Path, index: &Path, args: &[&str], input: &[u8]) -> Result<Vec<u8>> {
+    use std::io::Write;
+    let mut child = command()
+        .args(["-C", root.to_str().unwrap()])
+        .args(args)
+        .env("GIT_INDEX_FILE", index)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run `git {}`", args[0]))?;
+    child.stdin.take().unwrap().write_all(input)?;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        bail!("git {} failed: {}", args[0], String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(output.stdout)
+}
+
+/// Commit the working-tree state of just `files`, whatever else is staged.
+pub fn commit_only(root: &Path, files: &[String], message: &str) -> Result<()> {
+    report(&["commit", "-m", message, "--", &format!("({} files)", files.len())]);
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "--literal-pathspecs", "commit", "--quiet", "-m", message, "--"])
+        .args(files)
+        .output()
+        .context("Failed to run `git commit`")?;
+
+    if !output.status.success() {
+        bail!("git commit failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+/// Run git with `input` on stdin and return its trimmed stdout.
+fn git_with_input(root: &Path, args: &[&str], input: &str) -> Result<String> {
+    use std::io::Write;
+    let mut child = command()
+        .args(["-C", root.to_str().unwrap()])
+        .args(args)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run `git {}`", args[0]))?;
+    child.stdin.take().unwrap().write_all(input.as_bytes())?;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        bail!("git {} failed: {}", args[0], String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Create a dangling commit whose tree holds just `file` with `content`, on
+/// top of `parent` if given. No ref, branch, or working tree is touched.
+pub fn commit_file(root: &Path, parent: Option<&str>, file: &str, content: &str, message: &str) -> Result<String> {
+    let blob = git_with_input(root, &["hash-object", "-w", "--stdin"], content)?;
+    let tree = git_with_input(root, &["mktree"], &format!("100644 blob {}\t{}\n", blob, file))?;
+
+    let mut args = vec!["commit-tree", tree.as_str(), "-m", message];
+    if let Some(parent) = parent {
+        args.extend(["-p", parent]);
+    }
+    git_with_input(root, &args, "")
+}
+
+/// Point `refname` at `sha`, creating the ref if needed.
+pub fn update_ref(root: &Path, refname: &str, sha: &str) -> Result<()> {
+    report(&["update-ref", refname, sha]);
+    let status = command()
+        .args(["-C", root.to_str().unwrap(), "update-ref", refname, sha])
+        .stdout(crate::output::child_stdout())
+        .status()
+        .context("Failed to run `git update-ref`")?;
+    if !status.success() {
+        bail!("Failed to update {}", refname);
+    }
+    Ok(())
+}
+
+/// Contents of `file` in the commit `refname` points at, or `None` when the
+/// ref or file does not exist.
+pub fn read_file_at_ref(root: &Path, refname: &str, file: &str) -> Option<String> {
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "show", &format!("{}:{}", refname, file)])
+        .output()
+        .ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Paths of the entries directly inside `dir` in the commit `rev` points at;
+/// empty when the directory does not exist there.
+pub fn files_at_ref(root: &Path, rev: &str, dir: &str) -> Result<Vec<String>> {
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "ls-tree", "-z", "--name-only", rev, "--", &format!("{}/", dir)])
+        .output()
+        .context("Failed to run `git ls-tree`")?;
+
+    if !output.status.success() {
+        bail!("git ls-tree {} failed: {}", rev, String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(nul_paths(&output.stdout))
+}
+
+/// Push `rev` to `refname` on origin. Without `force` git rejects the push
+/// unless it fast-forwards origin's ref.
+pub fn push_ref(root: &Path, rev: &str, refname: &str, force: bool) -> Result<()> {
+    let refspec = format!("{}{}:{}", if force { "+" } else { "" }, rev, refname);
+    report(&["push", "origin", &refspec]);
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "push", "--quiet", "origin", &refspec])
+        .output()
+        .context("git push failed")?;
+    if !output.status.success() {
+        bail!("Failed to push {}: {}", refname, String::from_utf8_lossy(&output.stderr).trim());
     }
-    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    Ok(())
 }
 
-/// Cherry-pick (copy) specific files from `source_branch` into the current branch
-/// by checking out those files from `source_branch` and committing.
-pub fn checkout_files_from(root: &Path, source_branch: &str, files: &[String]) -> Result<()> {
-    if files.is_empty() {
-        return Ok(());
+/// A fetch of the base branch from origin, running in the background while a
+/// command gets on with other work. It is best effort: with no origin, a
+/// failed fetch, or one still running at the deadline, the remote-tracking
+/// branch is simply left as of the last fetch.
+pub struct Prefetch(Option<std::process::Child>);
+
+impl Prefetch {
+    pub fn start(root: &Path, base_branch: &str) -> Prefetch {
+        report(&["fetch", "--prune", "origin", base_branch]);
+        let child = command()
+            .args(["-C", root.to_str().unwrap(), "fetch", "--prune", "--quiet", "origin", base_branch])
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .spawn()
+            .ok();
+        Prefetch(child)
+    }
+
+    /// Wait up to `timeout` for the fetch, yielding to other tasks meanwhile;
+    /// true when it finished and worked. A fetch still running is left to
+    /// finish on its own rather than killed, which could leave a stale ref
+    /// lock behind.
+    pub async fn finish(mut self, timeout: std::time::Duration) -> bool {
+        let Some(mut child) = self.0.take() else { return false };
+        let deadline = std::time::Instant::now() + timeout;
+        loop {
+            match child.try_wait() {
+                Ok(Some(status)) => return status.success(),
+                Ok(None) if std::time::Instant::now() < deadline => {
+                    tokio::time::sleep(std::time::Duration::from_millis(20)).await
+                }
+                _ => return false,
+            }
+        }
     }
+}
 
-    let mut args = vec![
-        "-C".to_string(),
-        root.to_str().unwrap().to_string(),
-        "checkout".to_string(),
-        source_branch.to_string(),
-        "--".to_string(),
-    ];
-    args.extend(files.iter().cloned());
+/// Whether `origin` has `branch`, asking the remote itself
+/// (`git ls-remote --exit-code --heads`). Fails with git's message when
+/// origin cannot be reached.
+pub fn remote_has_branch(root: &Path, branch: &str) -> Result<bool> {
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "ls-remote", "--exit-code", "--heads", "origin", branch])
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(std::process::Stdio::null())
+        .output()
+        .context("Failed to run `git ls-remote`")?;
+    match output.status.code() {
+        Some(0) => Ok(true),
+        Some(2) => Ok(false),
+        _ => bail!("{}", String::from_utf8_lossy(&output.stderr).trim()),
+    }
+}
 
-    let status = Command::new("git")
-        .args(&args)
-        .status()
-        .context("Failed to checkout files from source branch")?;
+/// The installed git's version as (major, minor).
+pub fn version() -> Result<(u32, u32)> {
+    let output = command().arg("--version").output().context("Failed to run `git --version`")?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    parse_version(&text).with_context(|| format!("Unrecognised `git --version` output: {}", text.trim()))
+}
 
-    if !status.success() {
-        bail!("Failed to checkout files from '{}'", source_branch);
+/// Parse `git version 2.39.3 (Apple Git-146)` or `git version
+/// 2.45.1.windows.1` into (major, minor).
+pub(crate) fn parse_version(text: &str) -> Option<(u32, u32)> {
+    let version = text.trim().strip_prefix("git version ")?.split_whitespace().next()?;
+    let mut parts = version.split('.');
+    Some((parts.next()?.parse().ok()?, parts.next()?.parse().ok()?))
+}
+
+/// `origin/<base_branch>` once it has been fetched, otherwise `base_branch`:
+/// what `merges sync` rebases chunks onto, so what they are behind.
+pub fn base_ref(root: &Path, base_branch: &str) -> String {
+    let remote = format!("origin/{}", base_branch);
+    if rev_parse(root, &remote).is_ok() { remote } else { base_branch.to_string() }
+}
+
+/// Fetch `refname` from origin, overwriting the local copy.
+pub fn fetch_ref(root: &Path, refname: &str) -> Result<()> {
+    report(&["fetch", "origin", refname]);
+    let refspec = format!("+{}:{}", refname, refname);
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "fetch", "--quiet", "origin", &refspec])
+        .output()
+        .context("git fetch failed")?;
+    if !output.status.success() {
+        bail!("Failed to fetch {}: {}", refname, String::from_utf8_lossy(&output.stderr).trim());
     }
     Ok(())
 }
 
-/// Stage all files and create a commit.
-pub fn commit_all(root: &Path, message: &str) -> Result<()> {
-    let add_out = Command::new("git")
-        .args(["-C", root.to_str().unwrap(), "add", "-A"])
-        .output()?;
-    if !add_out.status.success() {
-        bail!("git add failed: {}", String::from_utf8_lossy(&add_out.stderr).trim());
-    }
+/// Delete a local branch (must not be currently checked out).
+pub fn delete_branch(root: &Path, branch_name: &str) -> Result<()> {
+    report(&["branch", "-D", branch_name]);
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "branch", "-D", branch_name])
+        .output()
+        .context("Failed to run `git branch -D`")?;
 
-    let commit_out = Command::new("git")
-        .args(["-C", root.to_str().unwrap(), "commit", "-m", message])
-        .output()?;
-    if !commit_out.status.success() {
-        let stderr = String::from_utf8_lossy(&commit_out.stderr);
-        let stdout = String::from_utf8_lossy(&commit_out.stdout);
-        // git prints "nothing to commit" on stdout, not stderr
-        let detail = if stdout.contains("nothing to commit") || stderr.contains("nothing to commit") {
-            "nothing to commit, working tree clean".to_string()
-        } else {
-            format!("{}{}", stderr.trim(), stdout.trim())
-        };
-        bail!("git commit failed: {}", detail);
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("git branch -D failed: {}", stderr.trim());
     }
     Ok(())
 }
 
-/// Fetch latest origin and rebase current branch onto `base_branch`.
-pub fn fetch_and_rebase(root: &Path, base_branch: &str) -> Result<()> {
-    fetch(root)?;
-    rebase(root, base_branch, false)
-}
+/// Rename local branch `from` to `to`, carrying its reflog and upstream.
+pub fn rename_branch(root: &Path, from: &str, to: &str) -> Result<()> {
+    report(&["branch", "-m", from, to]);
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "branch", "-m", from, to])
+        .output()
+        .context("Failed to run `git branch -m`")?;
 
-/// Like `fetch_and_rebase` but passes `--update-refs` so stacked chunk branches
-/// that point at commits in the rebased history are automatically updated.
-pub fn fetch_and_rebase_stacked(root: &Path, base_branch: &str) -> Result<()> {
-    fetch(root)?;
-    rebase(root, base_branch, true)
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("git branch -m failed: {}", stderr.trim());
+    }
+    Ok(())
 }
 
-fn fetch(root: &Path) -> Result<()> {
-    let status = Command::new("git")
-        .args(["-C", root.to_str().unwrap(), "fetch", "origin"])
-        .status()
-        .context("git fetch failed")?;
-    if !status.success() {
-        bail!("git fetch origin failed");
+/// Create a dangling commit holding `branch`'s tree on top of `parent`,
+/// collapsing everything between them into one commit. No ref is moved.
+pub fn squash_commit(root: &Path, branch: &str, parent: &str, message: &str) -> Result<String> {
+    let tree = format!("{}^{{tree}}", branch);
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "commit-tree", &tree, "-p", parent, "-m", message])
+        .output()
+        .context("Failed to run `git commit-tree`")?;
+
+    if !output.status.success() {
+        bail!(
+            "git commit-tree failed for '{}': {}",
+            branch,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
     }
-    Ok(())
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
 }
 
-fn rebase(root: &Path, base_branch: &str, update_refs: bool) -> Result<()> {
-    let mut args = vec![
-        "-C".to_string(),
-        root.to_str().unwrap().to_string(),
-        "rebase".to_string(),
-    ];
-    if update_refs {
-        args.push("--update-refs".to_string());
+/// Rewrite commit `rev` with `trailer` (`Key: value`) at the end of its
+/// message, keeping its tree, parents, and author. Returns the new commit, or
+/// `rev` itself when its message already carries the trailer.
+pub fn add_trailer(root: &Path, rev: &str, trailer: &str) -> Result<String> {
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "log", "-1", "--date=raw"])
+        .args(["--format=%H%x00%P%x00%an%x00%ae%x00%ad%x00%B", rev])
+        .output()
+        .context("Failed to run `git log`")?;
+    if !output.status.success() {
+        bail!("git log {} failed: {}", rev, String::from_utf8_lossy(&output.stderr).trim());
     }
-    args.push(format!("origin/{}", base_branch));
+    let text = String::from_utf8_lossy(&output.stdout).to_string();
+    let fields: Vec<&str> = text.splitn(6, '\0').collect();
+    let [sha, parents, name, email, date, message] = fields[..] else {
+        bail!("Unexpected `git log` output for {}", rev);
+    };
+    let Some(message) = with_trailer(message, trailer) else {
+        return Ok(sha.to_string());
+    };
 
-    let status = Command::new("git")
+    let mut args = vec!["-C", root.to_str().unwrap(), "commit-tree", "-m", message.as_str()];
+    for parent in parents.split_whitespace() {
+        args.extend(["-p", parent]);
+    }
+    let tree = format!("{}^{{tree}}", sha);
+    args.push(tree.as_str());
+    let output = command()
         .args(&args)
-        .status()
-        .context("git rebase failed")?;
-    if !status.success() {
-        bail!(
-            "Rebase onto origin/{} failed — resolve conflicts then run `merges sync` again",
-            base_branch
-        );
+        .env("GIT_AUTHOR_NAME", name)
+        .env("GIT_AUTHOR_EMAIL", email)
+        .env("GIT_AUTHOR_DATE", date)
+        .output()
+        .context("Failed to run `git commit-tree`")?;
+    if !output.status.success() {
+        bail!("git commit-tree failed for {}: {}", rev, String::from_utf8_lossy(&output.stderr).trim());
     }
-    Ok(())
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// `message` with `trailer` appended — to its trailer block when it ends in
+/// one — or `None` when it already has it.
+fn with_trailer(message: &str, trailer: &str) -> Option<String> {
+    let message = message.trim_end();
+    if message.lines().any(|line| line.trim().eq_ignore_ascii_case(trailer)) {
+        return None;
+    }
+    let last = message.rsplit("\n\n").next().unwrap_or_default();
+    let is_trailer = |line: &str| {
+        let key = line.split_once(": ").map_or("", |(key, _)| key);
+        !key.is_empty() && key.chars().all(|c| c.is_alphanumeric() || c == '-')
+    };
+    let separator = if message.contains("\n\n") && last.lines().all(is_trailer) { "\n" } else { "\n\n" };
+    Some(format!("{}{}{}\n", message, separator, trailer))
 }
 
-/// Push a branch to origin (force-with-lease to handle rebases safely).
-pub fn push_branch(root: &Path, branch_name: &str) -> Result<()> {
-    let status = Command::new("git")
+/// Write `commit` as patch `number` of `total` into `out_dir` using
+/// `git format-patch`. Returns the path of the written patch file.
+pub fn format_patch(
+    root: &Path,
+    commit: &str,
+    out_dir: &Path,
+    number: usize,
+    total: usize,
+) -> Result<PathBuf> {
+    let output = command()
         .args([
             "-C",
             root.to_str().unwrap(),
-            "push",
-            "origin",
-            branch_name,
-            "--force-with-lease",
+            "format-patch",
+            "-1",
+            commit,
+            "--start-number",
+            &number.to_string(),
+            &format!("--subject-prefix=PATCH {}/{}", number, total),
+            "-o",
+            out_dir.to_str().unwrap(),
         ])
-        .status()
-        .context("git push failed")?;
-    if !status.success() {
-        bail!("Failed to push branch '{}'", branch_name);
+        .output()
+        .context("Failed to run `git format-patch`")?;
+
+    if !output.status.success() {
+        bail!(
+            "git format-patch failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
     }
-    Ok(())
+    let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Ok(PathBuf::from(path))
 }
 
-/// Delete a local branch (must not be currently checked out).
-pub fn delete_branch(root: &Path, branch_name: &str) -> Result<()> {
-    let output = Command::new("git")
-        .args(["-C", root.to_str().unwrap(), "branch", "-D", branch_name])
+/// The repository's common git directory, shared by the main working tree and
+/// every linked worktree.
+pub fn common_dir(root: &Path) -> Result<PathBuf> {
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "rev-parse", "--path-format=absolute", "--git-common-dir"])
         .output()
-        .context("Failed to run `git branch -D`")?;
-
+        .context("Failed to run `git rev-parse --git-common-dir`")?;
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        bail!("git branch -D failed: {}", stderr.trim());
+        bail!("Could not locate the git directory for {}", root.display());
     }
-    Ok(())
+    Ok(PathBuf::from(String::from_utf8_lossy(&output.stdout).trim()))
+}
+
+/// Read a git config value, returning `None` when it is unset.
+pub fn config_value(root: &Path, key: &str) -> Option<String> {
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "config", "--get", key])
+        .output()
+        .ok()?;
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !value.is_empty()).then_some(value)
 }
 
 // ── Worktree helpers ──────────────────────────────────────────────────────────
@@ -248,10 +1367,11 @@ pub fn worktree_path(root: &Path, branch_name: &str) -> PathBuf {
 /// Create a new branch `branch_name` at `base_ref` and add a worktree for it.
 /// The main worktree (and current branch) is untouched.
 pub fn add_worktree(root: &Path, branch_name: &str, base_ref: &str) -> Result<()> {
+    report(&["worktree", "add", "-b", branch_name, base_ref]);
     let wt_path = worktree_path(root, branch_name);
     std::fs::create_dir_all(wt_path.parent().unwrap())?;
 
-    let status = Command::new("git")
+    let status = skip_lfs_smudge(&mut command())
         .args([
             "-C",
             root.to_str().unwrap(),
@@ -262,6 +1382,26 @@ pub fn add_worktree(root: &Path, branch_name: &str, base_ref: &str) -> Result<()
             wt_path.to_str().unwrap(),
             base_ref,
         ])
+        .stdout(crate::output::child_stdout())
+        .status()
+        .context("git worktree add failed")?;
+
+    if !status.success() {
+        bail!("Failed to create worktree for branch '{}'", branch_name);
+    }
+    Ok(())
+}
+
+/// Add a worktree for the existing branch `branch_name` at its usual
+/// `worktree_path`.
+pub fn attach_worktree(root: &Path, branch_name: &str) -> Result<()> {
+    report(&["worktree", "add", branch_name]);
+    let wt_path = worktree_path(root, branch_name);
+    std::fs::create_dir_all(wt_path.parent().unwrap())?;
+
+    let status = skip_lfs_smudge(&mut command())
+        .args(["-C", root.to_str().unwrap(), "worktree", "add", wt_path.to_str().unwrap(), branch_name])
+        .stdout(crate::output::child_stdout())
         .status()
         .context("git worktree add failed")?;
 
@@ -271,14 +1411,31 @@ pub fn add_worktree(root: &Path, branch_name: &str, base_ref: &str) -> Result<()
     Ok(())
 }
 
+/// Move the worktree of a branch renamed from `from` to `to` to the
+/// directory named after `to`.
+pub fn move_worktree(root: &Path, from: &str, to: &str) -> Result<()> {
+    let (old_path, new_path) = (worktree_path(root, from), worktree_path(root, to));
+    report(&["worktree", "move", old_path.to_str().unwrap(), new_path.to_str().unwrap()]);
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "worktree", "move", old_path.to_str().unwrap(), new_path.to_str().unwrap()])
+        .output()
+        .context("Failed to run `git worktree move`")?;
+
+    if !output.status.success() {
+        bail!("git worktree move failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
 /// Remove the worktree for `branch_name` and delete the directory.
 pub fn remove_worktree(root: &Path, branch_name: &str) -> Result<()> {
     let wt_path = worktree_path(root, branch_name);
     if !wt_path.exists() {
         return Ok(());
     }
+    report(&["worktree", "remove", "--force", branch_name]);
 
-    let status = Command::new("git")
+    let status = command()
         .args([
             "-C",
             root.to_str().unwrap(),
@@ -287,6 +1444,7 @@ pub fn remove_worktree(root: &Path, branch_name: &str) -> Result<()> {
             "--force",
             wt_path.to_str().unwrap(),
         ])
+        .stdout(crate::output::child_stdout())
         .status()
         .context("git worktree remove failed")?;
 
@@ -336,9 +1494,23 @@ pub fn ensure_gitignored(root: &Path, pattern: &str) -> Result<()> {
 ///   git config rerere.enabled true
 ///   git config rerere.autoupdate true
 pub fn enable_rerere(root: &Path) -> Result<()> {
+    set_rerere(root, &[])
+}
+
+/// Turn rerere on in the worktree at `dir` only, overriding a worktree-specific
+/// setting that turned it off. Such settings exist only with
+/// `extensions.worktreeConfig`, which `--worktree` needs too.
+pub fn enable_worktree_rerere(dir: &Path) -> Result<()> {
+    set_rerere(dir, &["--worktree"])
+}
+
+fn set_rerere(root: &Path, scope: &[&str]) -> Result<()> {
     for (key, val) in [("rerere.enabled", "true"), ("rerere.autoupdate", "true")] {
-        let status = Command::new("git")
-            .args(["-C", root.to_str().unwrap(), "config", key, val])
+        let status = command()
+            .args(["-C", root.to_str().unwrap(), "config"])
+            .args(scope)
+            .args([key, val])
+            .stdout(crate::output::child_stdout())
             .status()
             .context("Failed to run `git config`")?;
         if !status.success() {
@@ -348,10 +1520,12 @@ pub fn enable_rerere(root: &Path) -> Result<()> {
     Ok(())
 }
 
-/// Parse `owner/repo` from `git remote get-url origin`.
-pub fn remote_owner_repo(root: &Path) -> Result<(String, String)> {
-    let output = Command::new("git")
-        .args(["-C", root.to_str().unwrap(), "remote", "get-url", "origin"])
+/// Return the URL `origin` pushes to, with `url.<base>.insteadOf` and
+/// `pushInsteadOf` rewrites applied, so aliases like `gh:owner/repo` resolve
+/// to a URL the forge can be detected from.
+pub fn remote_url(root: &Path) -> Result<String> {
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "remote", "get-url", "--push", "origin"])
         .output()
         .context("Failed to get remote URL")?;
 
@@ -359,40 +1533,85 @@ pub fn remote_owner_repo(root: &Path) -> Result<(String, String)> {
         bail!("No 'origin' remote found");
     }
 
-    let url = String::from_utf8_lossy(&output.stdout).trim().to_string();
-    parse_github_owner_repo(&url)
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// The remote's default branch (`master`, `develop`, `trunk`, …) as recorded in
+/// `refs/remotes/origin/HEAD`, or `None` when the clone never recorded it.
+pub fn default_branch(root: &Path) -> Option<String> {
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "symbolic-ref", "--quiet", "--short", "refs/remotes/origin/HEAD"])
+        .output()
+        .ok()?;
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let branch = value.strip_prefix("origin/")?;
+    (output.status.success() && !branch.is_empty()).then(|| branch.to_string())
 }
 
 pub(crate) fn parse_github_owner_repo(url: &str) -> Result<(String, String)> {
-    // Handles both https://github.com/owner/repo.git and git@github.com:owner/repo.git
+    // Handles https://github.com/owner/repo.git, scp-style [user@]github.com:owner/repo.git
+    // (SSO remotes use a user such as `org-12345@`), and ssh://[user@]github.com[:port]/owner/repo.git.
     // Trim surrounding whitespace first so shell output with trailing newlines works.
     let stripped = url
         .trim()
+        .trim_end_matches('/')
         .trim_end_matches(".git")
         .trim_end_matches('/');
 
-    if let Some(path) = stripped.strip_prefix("git@github.com:") {
-        let parts: Vec<&str> = path.splitn(2, '/').collect();
-        if parts.len() == 2 {
-            return Ok((parts[0].to_string(), parts[1].to_string()));
+    let path = match ["https://", "http://", "ssh://", "git+ssh://"].iter().find_map(|p| stripped.strip_prefix(p)) {
+        Some(rest) => {
+            let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+            let host_port = authority.rsplit('@').next().unwrap_or(authority);
+            match host_port.split_once(':') {
+                // `ssh://git@github.com:owner/repo` — scp syntax behind an ssh:// prefix.
+                Some(("github.com", owner)) if !owner.is_empty() && !owner.bytes().all(|b| b.is_ascii_digit()) => {
+                    Some(format!("{}/{}", owner, path))
+                }
+                Some(("github.com", _)) => Some(path.to_string()),
+                None if host_port == "github.com" => Some(path.to_string()),
+                _ => None,
+            }
         }
+        None => stripped
+            .split_once(':')
+            .filter(|(authority, _)| authority.rsplit('@').next() == Some("github.com"))
+            .map(|(_, path)| path.to_string()),
+    };
+
+    let path = path.unwrap_or_default();
+    match path.trim_matches('/').split('/').collect::<Vec<_>>()[..] {
+        [owner, repo] if !owner.is_empty() && !repo.is_empty() => Ok((owner.to_string(), repo.to_string())),
+        _ => bail!("Cannot parse GitHub owner/repo from remote URL: {}", url),
     }
+}
 
-    if let Some(rest) = stripped.strip_prefix("https://github.com/") {
-        let parts: Vec<&str> = rest.splitn(2, '/').collect();
-        if parts.len() == 2 {
-            return Ok((parts[0].to_string(), parts[1].to_string()));
+/// Files that would conflict if `branch` were merged into `base_branch`,
+/// found with `git merge-tree --write-tree` without touching any worktree.
+/// Empty when they merge cleanly.
+pub fn merge_conflicts(root: &Path, branch: &str, base_branch: &str) -> Result<Vec<String>> {
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "merge-tree", "--write-tree", "--name-only", "--no-messages", "-z"])
+        .args([base_branch, branch])
+        .output()
+        .context("Failed to run git merge-tree")?;
+    // 0 is a clean merge, 1 a conflicted one; anything else is an error.
+    match output.status.code() {
+        Some(0) => Ok(vec![]),
+        Some(1) => {
+            // The tree id, then each conflicted file.
+            let mut files: Vec<String> = nul_paths(&output.stdout).into_iter().skip(1).collect();
+            files.dedup();
+            Ok(files)
         }
+        _ => bail!("git merge-tree failed: {}", String::from_utf8_lossy(&output.stderr).trim()),
     }
-
-    bail!("Cannot parse GitHub owner/repo from remote URL: {}", url)
 }
 
 /// Count how many commits `base_branch` has that `branch` does not.
 /// Uses `git rev-list --count <branch>..<base_branch>`.
 pub fn commits_behind(root: &Path, branch: &str, base_branch: &str) -> Result<u64> {
     let range = format!("{}..{}", branch, base_branch);
-    let output = Command::new("git")
+    let output = command()
         .args(["-C", root.to_str().unwrap(), "rev-list", "--count", &range])
         .output()
         .context("Failed to run git rev-list")?;
@@ -410,6 +1629,106 @@ pub fn commits_behind(root: &Path, branch: &str, base_branch: &str) -> Result<u6
         .context("Failed to parse rev-list count")
 }
 
+/// The remote-tracking branch `branch` pushes to: its configured upstream, or
+/// `origin/<branch>` for branches pushed before upstreams were set. `None`
+/// when the branch has never been pushed.
+pub fn upstream(root: &Path, branch: &str) -> Option<String> {
+    let output = command()
+        .args([
+            "-C",
+            root.to_str().unwrap(),
+            "rev-parse",
+            "--abbrev-ref",
+            "--symbolic-full-name",
+            &format!("{}@{{upstream}}", branch),
+        ])
+        .output()
+        .ok()?;
+    if output.status.success() {
+        return Some(String::from_utf8_lossy(&output.stdout).trim().to_string());
+    }
+    let remote = format!("origin/{}", branch);
+    rev_parse(root, &remote).is_ok().then_some(remote)
+}
+
+/// Count the commits `branch` has that `upstream` does not, and the reverse.
+/// Uses `git rev-list --left-right --count <branch>...<upstream>`.
+pub fn ahead_behind(root: &Path, branch: &str, upstream: &str) -> Result<(u64, u64)> {
+    let range = format!("{}...{}", branch, upstream);
+    let output = command()
+        .args(["-C", root.to_str().unwrap(), "rev-list", "--left-right", "--count", &range])
+        .output()
+        .context("Failed to run git rev-list")?;
+
+    if !output.status.success() {
+        bail!("git rev-list failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let counts: Vec<u64> =
+        stdout.split_whitespace().map(str::parse).collect::<Result<_, _>>().context("Failed to parse rev-list counts")?;
+    match counts[..] {
+        [ahead, behind] => Ok((ahead, behind)),
+        _ => bail!("Unexpected rev-list output: {}", stdout.trim()),
+    }
+}
+
+/// Where a branch stands against the base branch and its upstream.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BranchCounts {
+    /// Commits the base branch has that the branch does not (0 when either
+    /// is missing), as [`commits_behind`] counts them.
+    pub behind_base: u64,
+    /// The branch's upstream, as [`upstream`] finds it.
+    pub upstream: Option<String>,
+    /// Commits ahead of and behind the upstream, as [`ahead_behind`] counts them.
+    pub tracking: Option<(u64, u64)>,
+}
+
+/// [`BranchCounts`] for each of `branches`, in order. The counts come from
+/// libgit2 graph walks in this process — one against the base and one against
+/// the upstream per branch — where counting with `git rev-list` takes two
+/// processes per branch: seconds on a long stack in a big repo.
+pub fn branch_counts(root: &Path, branches: &[String], base_branch: &str) -> Result<Vec<BranchCounts>> {
+    let repo = Repository::open(root)?;
+    let base = repo.revparse_single(base_branch).and_then(|o| o.peel_to_commit()).map(|c| c.id()).ok();
+
+    Ok(branches
+        .iter()
+        .map(|name| {
+            let Ok(branch) = repo.find_branch(name, git2::BranchType::Local) else { return BranchCounts::default() };
+            let Some(tip) = branch.get().target() else { return BranchCounts::default() };
+            let behind_base =
+                base.and_then(|base| repo.graph_ahead_behind(tip, base).ok()).map_or(0, |(_, behind)| behind);
+
+            let remote = branch
+                .upstream()
+                .ok()
+                .or_else(|| repo.find_branch(&format!("origin/{}", name), git2::BranchType::Remote).ok());
+            let upstream = remote.as_ref().and_then(|r| r.name().ok().flatten().map(String::from));
+            let tracking = remote
+                .and_then(|r| r.get().target())
+                .and_then(|theirs| repo.graph_ahead_behind(tip, theirs).ok());
+            BranchCounts {
+                behind_base: behind_base as u64,
+                upstream,
+                tracking: tracking.map(|(ahead, behind)| (ahead as u64, behind as u64)),
+            }
+        })
+        .collect())
+}
+
+/// Format a "remote" label for the status table from a branch's commits
+/// ahead of and behind its upstream, if it has one.
+pub fn remote_status(tracking: Option<(u64, u64)>) -> String {
+    match tracking {
+        None => "not pushed".to_string(),
+        Some((0, 0)) => "✓ pushed".to_string(),
+        Some((ahead, 0)) => format!("↑ {} ahead", ahead),
+        Some((0, behind)) => format!("↓ {} behind", behind),
+        Some((ahead, behind)) => format!("↑ {} ↓ {}", ahead, behind),
+    }
+}
+
 /// Format a "sync" label for the status table.
 pub fn sync_status(behind: u64) -> String {
     if behind == 0 {
@@ -487,6 +1806,42 @@ pub fn pr_title(source_branch: &str, body: &str) -> String {
     commit_message(source_branch, body)
 }
 
+/// Stack position prefix for chunk PR titles, e.g. `[2/5]` for the second of five.
+pub fn stack_position(index: usize, total: usize) -> String {
+    format!("[{}/{}]", index + 1, total)
+}
+
+/// Join `head` (ticket prefix, stack position) and `name` with a space, keeping
+/// the result within `max` characters. Only `name` is shortened — cut at a
+/// word boundary where one is close and marked with `...` — so the head always
+/// survives intact, even if it alone is longer than `max`.
+///
+/// Examples with `max = 33`:
+/// - `PAY-12 [2/5]` + `payments-webhook-retry-handling` → `PAY-12 [2/5] payments-webhook...`
+/// - `PAY-12 [2/5]` + `models` → `PAY-12 [2/5] models`
+pub fn fit_subject(head: &str, name: &str, max: Option<usize>) -> String {
+    let join = |name: &str| if head.is_empty() { name.to_string() } else { format!("{} {}", head, name) };
+    let full = join(name);
+    let Some(max) = max else { return full };
+    if full.chars().count() <= max {
+        return full;
+    }
+
+    const ELLIPSIS: &str = "...";
+    const SEPARATORS: [char; 4] = [' ', '-', '_', '/'];
+    let used = head.chars().count() + usize::from(!head.is_empty()) + ELLIPSIS.len();
+    let room = max.saturating_sub(used);
+    let cut: String = name.chars().take(room).collect();
+    // Back off to the last separator unless the cut already ends a word or
+    // backing off would throw away most of the room.
+    let ends_word = name.chars().nth(room).is_some_and(|c| SEPARATORS.contains(&c));
+    let cut = match cut.rfind(SEPARATORS) {
+        Some(i) if !ends_word && i > 0 && i >= room / 2 => &cut[..i],
+        _ => cut.as_str(),
+    };
+    join(&format!("{}{}", cut, ELLIPSIS))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -518,6 +1873,16 @@ mod tests {
 
     // ── parse_github_owner_repo ────────────────────────────────────────────
 
+    #[test]
+    fn test_with_trailer_joins_the_trailer_block() {
+        assert_eq!(with_trailer("Add models\n", "skip-checks: true").unwrap(), "Add models\n\nskip-checks: true\n");
+        assert_eq!(
+            with_trailer("Add models\n\nBody text.\n\nSigned-off-by: A <a@x>", "skip-checks: true").unwrap(),
+            "Add models\n\nBody text.\n\nSigned-off-by: A <a@x>\nskip-checks: true\n"
+        );
+        assert_eq!(with_trailer("Add models\n\nskip-checks: true\n", "skip-checks: true"), None);
+    }
+
     #[test]
     fn test_parse_https_with_git_suffix() {
         let (owner, repo) = parse_github_owner_repo("https://github.com/acme/myrepo.git").unwrap();
@@ -553,6 +1918,42 @@ mod tests {
         assert_eq!(repo, "myrepo");
     }
 
+    #[test]
+    fn test_parse_ssh_scheme_with_port() {
+        let (owner, repo) = parse_github_owner_repo("ssh://git@github.com:443/acme/myrepo.git").unwrap();
+        assert_eq!(owner, "acme");
+        assert_eq!(repo, "myrepo");
+    }
+
+    #[test]
+    fn test_parse_ssh_scheme_without_port() {
+        let (owner, repo) = parse_github_owner_repo("ssh://git@github.com/acme/myrepo.git").unwrap();
+        assert_eq!(owner, "acme");
+        assert_eq!(repo, "myrepo");
+    }
+
+    /// `ssh://` in front of scp syntax is invalid as a URL but git accepts it.
+    #[test]
+    fn test_parse_ssh_scheme_with_scp_path() {
+        let (owner, repo) = parse_github_owner_repo("ssh://git@github.com:acme/myrepo.git").unwrap();
+        assert_eq!(owner, "acme");
+        assert_eq!(repo, "myrepo");
+    }
+
+    /// GitHub Enterprise Cloud SSO remotes use the org id as the SSH user.
+    #[test]
+    fn test_parse_sso_scp_user() {
+        let (owner, repo) = parse_github_owner_repo("org-12345@github.com:acme/myrepo.git").unwrap();
+        assert_eq!(owner, "acme");
+        assert_eq!(repo, "myrepo");
+    }
+
+    #[test]
+    fn test_parse_ssh_scheme_other_host_returns_error() {
+        assert!(parse_github_owner_repo("ssh://git@gitlab.com:22/acme/myrepo.git").is_err());
+        assert!(parse_github_owner_repo("git@github.com.evil.example:acme/myrepo.git").is_err());
+    }
+
     #[test]
     fn test_parse_url_with_hyphens_and_dots_in_names() {
         let (owner, repo) = parse_github_owner_repo("https://github.com/my-org/my.repo_name.git").unwrap();
@@ -597,6 +1998,43 @@ mod tests {
         assert!(result.is_err(), "URL without repo should be rejected");
     }
 
+    #[test]
+    fn test_remote_url_applies_insteadof_rewrites() {
+        let (_dir, root) = make_repo();
+        for args in [
+            vec!["config", "url.git@github.com:.insteadOf", "gh:"],
+            vec!["remote", "add", "origin", "gh:acme/myrepo.git"],
+        ] {
+            StdCommand::new("git").args(&args).current_dir(&root).output().unwrap();
+        }
+        let url = remote_url(&root).unwrap();
+        assert_eq!(url, "git@github.com:acme/myrepo.git");
+        let (owner, repo) = parse_github_owner_repo(&url).unwrap();
+        assert_eq!((owner.as_str(), repo.as_str()), ("acme", "myrepo"));
+    }
+
+    #[test]
+    fn test_remote_url_prefers_push_rewrite() {
+        let (_dir, root) = make_repo();
+        for args in [
+            vec!["config", "url.ssh://git@github.com/.pushInsteadOf", "https://github.com/"],
+            vec!["remote", "add", "origin", "https://github.com/acme/myrepo.git"],
+        ] {
+            StdCommand::new("git").args(&args).current_dir(&root).output().unwrap();
+        }
+        assert_eq!(remote_url(&root).unwrap(), "ssh://git@github.com/acme/myrepo.git");
+    }
+
+    // ── parse_version ─────────────────────────────────────────────────────
+
+    #[test]
+    fn test_parse_version_variants() {
+        assert_eq!(parse_version("git version 2.43.0\n"), Some((2, 43)));
+        assert_eq!(parse_version("git version 2.39.3 (Apple Git-146)"), Some((2, 39)));
+        assert_eq!(parse_version("git version 2.45.1.windows.1"), Some((2, 45)));
+        assert_eq!(parse_version("hub version 2.14.2"), None);
+    }
+
     // ── current_branch ────────────────────────────────────────────────────
 
     #[test]
@@ -653,6 +2091,15 @@ mod tests {
         assert_eq!(files, vec!["a.rs", "b.rs", "c.rs"]);
     }
 
+    /// git's messages stay untranslated, so what merges reads does not depend
+    /// on the user's language.
+    #[test]
+    fn test_command_runs_git_in_the_c_locale() {
+        let command = command();
+        let lc_all = command.get_envs().find(|(key, _)| *key == "LC_ALL").and_then(|(_, value)| value);
+        assert_eq!(lc_all, Some(std::ffi::OsStr::new("C")));
+    }
+
     // ── commit_all ────────────────────────────────────────────────────────
 
     /// Committing with nothing staged should return a descriptive error mentioning
@@ -866,6 +2313,26 @@ mod tests {
         assert_eq!(String::from_utf8_lossy(&out.stdout).trim(), "true");
     }
 
+    // ── default_branch ────────────────────────────────────────────────────
+
+    #[test]
+    fn test_default_branch_reads_origin_head() {
+        let (_dir, root) = make_repo();
+        for args in [
+            vec!["update-ref", "refs/remotes/origin/develop", "HEAD"],
+            vec!["symbolic-ref", "refs/remotes/origin/HEAD", "refs/remotes/origin/develop"],
+        ] {
+            StdCommand::new("git").args(&args).current_dir(&root).output().unwrap();
+        }
+        assert_eq!(default_branch(&root).as_deref(), Some("develop"));
+    }
+
+    #[test]
+    fn test_default_branch_none_without_origin_head() {
+        let (_dir, root) = make_repo();
+        assert_eq!(default_branch(&root), None);
+    }
+
     // ── ticket_prefix / commit_message ────────────────────────────────────
 
     #[test]
@@ -943,4 +2410,36 @@ mod tests {
         let title = pr_title("SOL-123-fix-auth", "[2/4] api");
         assert_eq!(title, "SOL-123 [2/4] api");
     }
+
+    #[test]
+    fn test_stack_position_is_one_based() {
+        assert_eq!(stack_position(0, 4), "[1/4]");
+        assert_eq!(stack_position(3, 4), "[4/4]");
+    }
+
+    // ── fit_subject ───────────────────────────────────────────────────────
+
+    #[test]
+    fn test_fit_subject_without_limit_or_within_it_is_unchanged() {
+        assert_eq!(fit_subject("PAY-12 [2/5]", "models", None), "PAY-12 [2/5] models");
+        assert_eq!(fit_subject("PAY-12 [2/5]", "models", Some(19)), "PAY-12 [2/5] models");
+        assert_eq!(fit_subject("", "models", Some(10)), "models");
+    }
+
+    #[test]
+    fn test_fit_subject_cuts_name_at_word_boundary() {
+        let title = fit_subject("PAY-12 [2/5]", "payments-webhook-retry-handling", Some(33));
+        assert_eq!(title, "PAY-12 [2/5] payments-webhook...");
+        assert!(title.chars().count() <= 33);
+    }
+
+    #[test]
+    fn test_fit_subject_hard_cuts_names_without_boundaries() {
+        assert_eq!(fit_subject("[1/2]", "abcdefghijklmnopqrstuvwxyz", Some(16)), "[1/2] abcdefg...");
+    }
+
+    #[test]
+    fn test_fit_subject_keeps_head_when_it_alone_is_too_long() {
+        assert_eq!(fit_subject("PAY-12 [2/5]", "models", Some(8)), "PAY-12 [2/5] ...");
+    }
 }
@@ -1,6 +1,13 @@
 use anyhow::{Context, Result};
 use octocrab::Octocrab;
 use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::config::GithubCredentials;
+use crate::project::Project;
+use crate::forge::{
+    aggregate_checks, Access, CheckOutcome, CheckResult, MergeMethod, PrInfo, ReviewComment, ReviewThread,
+};
 
 /// Build an authenticated Octocrab client.
 pub fn client(token: &str) -> Result<Octocrab> {
@@ -10,15 +17,150 @@ pub fn client(token: &str) -> Result<Octocrab> {
         .context("Failed to build GitHub client")
 }
 
-#[derive(Debug, Clone)]
-#[allow(dead_code)] // fields used selectively; kept for future display/serialisation
-pub struct PrInfo {
-    pub number: u64,
-    pub url: String,
-    pub title: String,
-    pub state: String,
-    pub ci_status: String,
-    pub review_state: String,
+/// Build a client for `credentials` on `owner/repo`. A GitHub App acts as its
+/// installation on the repository (or the configured installation), and the
+/// permissions that installation was granted are returned alongside.
+pub async fn connect(
+    credentials: &GithubCredentials,
+    owner: &str,
+    repo: &str,
+) -> Result<(Octocrab, Option<HashMap<String, String>>)> {
+    let (app_id, private_key, installation_id) = match credentials {
+        GithubCredentials::Token(token) => return Ok((client(token)?, None)),
+        GithubCredentials::App { app_id, private_key, installation_id } => (*app_id, private_key, *installation_id),
+    };
+
+    #[derive(Deserialize)]
+    struct Installation {
+        id: u64,
+        #[serde(default)]
+        permissions: HashMap<String, String>,
+    }
+
+    let key = jsonwebtoken::EncodingKey::from_rsa_pem(private_key.as_bytes())
+        .context("The GitHub App private key is not a PEM-encoded RSA key")?;
+    let app = Octocrab::builder()
+        .app(octocrab::models::AppId(app_id), key)
+        .build()
+        .context("Failed to build GitHub App client")?;
+    let route = match installation_id {
+        Some(id) => format!("/app/installations/{}", id),
+        None => format!("/repos/{}/{}/installation", owner, repo),
+    };
+    let installation: Installation = app
+        .get(route, None::<&()>)
+        .await
+        .with_context(|| format!("Failed to find the installation of GitHub App {} on {}/{}", app_id, owner, repo))?;
+    let client = app
+        .installation(octocrab::models::InstallationId(installation.id))
+        .context("Failed to authenticate as the GitHub App installation")?;
+    Ok((client, Some(installation.permissions)))
+}
+
+/// The parts of `needs` the client's credentials cannot do on `owner/repo`.
+///
+/// App installations are checked against the permissions they were granted
+/// and classic tokens against their OAuth scopes. GitHub does not expose what
+/// a fine-grained token may do, so for those only write access to the
+/// repository is checked.
+pub async fn missing_permissions(
+    client: &Octocrab,
+    owner: &str,
+    repo: &str,
+    app_permissions: Option<&HashMap<String, String>>,
+    needs: &[Access],
+) -> Result<Vec<String>> {
+    if let Some(granted) = app_permissions {
+        return Ok(missing_app_permissions(granted, needs));
+    }
+    let response = client
+        ._get(format!("/repos/{}/{}", owner, repo))
+        .await
+        .with_context(|| format!("Failed to look up {}/{}", owner, repo))?;
+    let status = response.status();
+    let scopes = response.headers().get("x-oauth-scopes").and_then(|v| v.to_str().ok()).map(str::to_string);
+    let body = client.body_to_string(response).await.with_context(|| format!("Failed to read {}/{}", owner, repo))?;
+    if !status.is_success() {
+        let reason = format!("the token cannot read {}/{} ({})", owner, repo, status);
+        return Ok(needs.iter().map(|a| format!("{} — {}", a, reason)).collect());
+    }
+    let repository: serde_json::Value = serde_json::from_str(&body).context("Unexpected repository response")?;
+    let private = repository["private"].as_bool().unwrap_or(true);
+    Ok(match scopes {
+        Some(scopes) => missing_scopes(&scopes, private, needs),
+        None if repository["permissions"]["push"].as_bool() == Some(false) => {
+            needs.iter().map(|a| format!("{} — you have no write access to {}/{}", a, owner, repo)).collect()
+        }
+        None => vec![],
+    })
+}
+
+/// App permissions (name, level) each kind of access needs.
+fn app_permissions_for(access: Access) -> &'static [(&'static str, &'static str)] {
+    match access {
+        Access::PullRequests => &[("pull_requests", "write")],
+        Access::Issues => &[("issues", "write")],
+        Access::AutoMerge => &[("pull_requests", "write"), ("contents", "write")],
+    }
+}
+
+/// `needs` an app installation granted `granted` lacks, naming the
+/// permissions to add.
+fn missing_app_permissions(granted: &HashMap<String, String>, needs: &[Access]) -> Vec<String> {
+    let allows = |name: &str, level: &str| match granted.get(name).map(String::as_str) {
+        Some("write" | "admin") => true,
+        Some("read") => level == "read",
+        _ => false,
+    };
+    needs
+        .iter()
+        .filter_map(|&access| {
+            let missing: Vec<String> = app_permissions_for(access)
+                .iter()
+                .filter(|(name, level)| !allows(name, level))
+                .map(|(name, level)| format!("{}: {}", name, level))
+                .collect();
+            (!missing.is_empty()).then(|| format!("{} — the GitHub App needs {}", access, missing.join(", ")))
+        })
+        .collect()
+}
+
+/// `needs` a classic token with OAuth `scopes` (the `X-OAuth-Scopes` header)
+/// cannot do. `repo` covers everything; `public_repo` is enough on a public
+/// repository.
+fn missing_scopes(scopes: &str, private: bool, needs: &[Access]) -> Vec<String> {
+    let scopes: Vec<&str> = scopes.split(',').map(str::trim).collect();
+    if scopes.contains(&"repo") || (!private && scopes.contains(&"public_repo")) {
+        return vec![];
+    }
+    let scope = if private { "repo" } else { "public_repo" };
+    needs.iter().map(|a| format!("{} — the token needs the `{}` scope", a, scope)).collect()
+}
+
+/// Find an open PR whose head is `head`; returns (pr_number, pr_url).
+pub async fn find_open_pr(
+    client: &Octocrab,
+    owner: &str,
+    repo: &str,
+    head: &str,
+) -> Result<Option<(u64, String)>> {
+    let page = client
+        .pulls(owner, repo)
+        .list()
+        .state(octocrab::params::State::Open)
+        .head(format!("{}:{}", owner, head))
+        .per_page(1)
+        .send()
+        .await
+        .with_context(|| format!("Failed to look up open PRs for '{}'", head))?;
+
+    Ok(page.items.into_iter().next().map(|pr| {
+        let url = pr
+            .html_url
+            .map(|u| u.to_string())
+            .unwrap_or_else(|| format!("https://github.com/{}/{}/pull/{}", owner, repo, pr.number));
+        (pr.number, url)
+    }))
 }
 
 /// Create a pull request; returns (pr_number, pr_url).
@@ -48,6 +190,45 @@ pub async fn create_pr(
     Ok((number, url))
 }
 
+/// Create an issue; returns (issue_number, issue_url).
+pub async fn create_issue(
+    client: &Octocrab,
+    owner: &str,
+    repo: &str,
+    title: &str,
+    body: &str,
+) -> Result<(u64, String)> {
+    let issue = client
+        .issues(owner, repo)
+        .create(title)
+        .body(body)
+        .send()
+        .await
+        .with_context(|| format!("Failed to create issue '{}'", title))?;
+    Ok((issue.number, issue.html_url.to_string()))
+}
+
+/// Replace an issue's body, closing it when `close` is true.
+pub async fn update_issue(
+    client: &Octocrab,
+    owner: &str,
+    repo: &str,
+    number: u64,
+    body: &str,
+    close: bool,
+) -> Result<()> {
+    let issues = client.issues(owner, repo);
+    let mut update = issues.update(number).body(body);
+    if close {
+        update = update.state(octocrab::models::IssueState::Closed);
+    }
+    update
+        .send()
+        .await
+        .with_context(|| format!("Failed to update issue #{}", number))?;
+    Ok(())
+}
+
 /// Update the base branch of an existing PR (used in stacked mode after lower chunk merges).
 pub async fn update_pr_base(
     client: &Octocrab,
@@ -66,6 +247,374 @@ pub async fn update_pr_base(
     Ok(())
 }
 
+/// Replace the title of an existing PR, and its body when `body` is given.
+pub async fn update_pr(
+    client: &Octocrab,
+    owner: &str,
+    repo: &str,
+    pr_number: u64,
+    title: &str,
+    body: Option<&str>,
+) -> Result<()> {
+    let pulls = client.pulls(owner, repo);
+    let mut update = pulls.update(pr_number).title(title);
+    if let Some(body) = body {
+        update = update.body(body);
+    }
+    update
+        .send()
+        .await
+        .with_context(|| format!("Failed to update PR #{}", pr_number))?;
+    Ok(())
+}
+
+/// Return the branch an existing PR currently targets.
+pub async fn get_pr_base(
+    client: &Octocrab,
+    owner: &str,
+    repo: &str,
+    pr_number: u64,
+) -> Result<String> {
+    let pr = client
+        .pulls(owner, repo)
+        .get(pr_number)
+        .await
+        .with_context(|| format!("Failed to fetch PR #{}", pr_number))?;
+    Ok(pr.base.ref_field)
+}
+
+/// The `PullRequestMergeMethod` enum value expected by the GraphQL API.
+fn graphql_merge_method(method: MergeMethod) -> &'static str {
+    match method {
+        MergeMethod::Squash => "SQUASH",
+        MergeMethod::Merge => "MERGE",
+        MergeMethod::Rebase => "REBASE",
+    }
+}
+
+/// Merge a PR now with `method`; GitHub refuses while required checks or
+/// reviews are missing.
+pub async fn merge_pr(client: &Octocrab, owner: &str, repo: &str, pr_number: u64, method: MergeMethod) -> Result<()> {
+    let method = match method {
+        MergeMethod::Squash => octocrab::params::pulls::MergeMethod::Squash,
+        MergeMethod::Merge => octocrab::params::pulls::MergeMethod::Merge,
+        MergeMethod::Rebase => octocrab::params::pulls::MergeMethod::Rebase,
+    };
+    client
+        .pulls(owner, repo)
+        .merge(pr_number)
+        .method(method)
+        .send()
+        .await
+        .with_context(|| format!("Failed to merge PR #{}", pr_number))?;
+    Ok(())
+}
+
+/// Enable GitHub auto-merge on a PR via the `enablePullRequestAutoMerge` GraphQL
+/// mutation, so it lands as soon as approvals and required checks pass.
+pub async fn enable_auto_merge(
+    client: &Octocrab,
+    owner: &str,
+    repo: &str,
+    pr_number: u64,
+    method: MergeMethod,
+) -> Result<()> {
+    pr_mutation(
+        client,
+        owner,
+        repo,
+        pr_number,
+        "mutation($id: ID!, $method: PullRequestMergeMethod!) { \
+         enablePullRequestAutoMerge(input: {pullRequestId: $id, mergeMethod: $method}) { \
+         clientMutationId } }",
+        serde_json::json!({ "method": graphql_merge_method(method) }),
+        &format!("Failed to enable auto-merge for PR #{}", pr_number),
+    )
+    .await
+}
+
+/// Turn an open PR back into a draft via the `convertPullRequestToDraft`
+/// GraphQL mutation (REST cannot do it).
+pub async fn convert_pr_to_draft(client: &Octocrab, owner: &str, repo: &str, pr_number: u64) -> Result<()> {
+    pr_mutation(
+        client,
+        owner,
+        repo,
+        pr_number,
+        "mutation($id: ID!) { convertPullRequestToDraft(input: {pullRequestId: $id}) { clientMutationId } }",
+        serde_json::json!({}),
+        &format!("Failed to convert PR #{} to a draft", pr_number),
+    )
+    .await
+}
+
+/// Run a GraphQL `mutation` on a PR, passing its node id as `$id` alongside
+/// `variables`. `failure` starts the error message.
+async fn pr_mutation(
+    client: &Octocrab,
+    owner: &str,
+    repo: &str,
+    pr_number: u64,
+    mutation: &str,
+    mut variables: serde_json::Value,
+    failure: &str,
+) -> Result<()> {
+    let pr = client
+        .pulls(owner, repo)
+        .get(pr_number)
+        .await
+        .with_context(|| format!("Failed to fetch PR #{}", pr_number))?;
+    let node_id = pr
+        .node_id
+        .with_context(|| format!("PR #{} has no GraphQL node id", pr_number))?;
+    variables["id"] = serde_json::json!(node_id);
+
+    graphql(client, mutation, variables, failure).await?;
+    Ok(())
+}
+
+/// Run a GraphQL `query` with `variables` and return the response. `failure`
+/// starts the error message.
+async fn graphql(
+    client: &Octocrab,
+    query: &str,
+    variables: serde_json::Value,
+    failure: &str,
+) -> Result<serde_json::Value> {
+    let payload = serde_json::json!({ "query": query, "variables": variables });
+    let response: serde_json::Value = client.graphql(&payload).await.context(failure.to_string())?;
+
+    // GraphQL reports failures in the body with a 200 status.
+    if let Some(errors) = response.get("errors").and_then(|e| e.as_array()) {
+        let messages: Vec<&str> = errors
+            .iter()
+            .filter_map(|e| e.get("message").and_then(|m| m.as_str()))
+            .collect();
+        anyhow::bail!("{}: {}", failure, messages.join("; "));
+    }
+    Ok(response)
+}
+
+/// Comment on a PR (through the issues API); returns the comment id.
+pub async fn comment_on_pr(client: &Octocrab, owner: &str, repo: &str, pr_number: u64, body: &str) -> Result<u64> {
+    let comment = client
+        .issues(owner, repo)
+        .create_comment(pr_number, body)
+        .await
+        .with_context(|| format!("Failed to comment on PR #{}", pr_number))?;
+    Ok(comment.id.into_inner())
+}
+
+/// Replace the body of an issue or PR comment.
+pub async fn update_comment(client: &Octocrab, owner: &str, repo: &str, comment_id: u64, body: &str) -> Result<()> {
+    client
+        .issues(owner, repo)
+        .update_comment(octocrab::models::CommentId(comment_id), body)
+        .await
+        .with_context(|| format!("Failed to update comment {}", comment_id))?;
+    Ok(())
+}
+
+/// Close a PR without merging it.
+pub async fn close_pr(client: &Octocrab, owner: &str, repo: &str, pr_number: u64) -> Result<()> {
+    client
+        .pulls(owner, repo)
+        .update(pr_number)
+        .state(octocrab::params::pulls::State::Closed)
+        .send()
+        .await
+        .with_context(|| format!("Failed to close PR #{}", pr_number))?;
+    Ok(())
+}
+
+/// Add `labels` to a PR; GitHub creates labels the repository lacks.
+pub async fn add_labels(client: &Octocrab, owner: &str, repo: &str, pr_number: u64, labels: &[String]) -> Result<()> {
+    client
+        .issues(owner, repo)
+        .add_labels(pr_number, labels)
+        .await
+        .with_context(|| format!("Failed to label PR #{}", pr_number))?;
+    Ok(())
+}
+
+/// Request reviews of a PR from `reviewers`: logins, and teams as
+/// `org/team` (GitHub takes the team's slug).
+pub async fn request_reviewers(
+    client: &Octocrab,
+    owner: &str,
+    repo: &str,
+    pr_number: u64,
+    reviewers: &[String],
+) -> Result<()> {
+    let (teams, users): (Vec<String>, Vec<String>) = reviewers.iter().cloned().partition(|r| r.contains('/'));
+    let teams: Vec<String> = teams.iter().filter_map(|t| t.split_once('/')).map(|(_, slug)| slug.to_string()).collect();
+    client
+        .pulls(owner, repo)
+        .request_reviews(pr_number, users, teams)
+        .await
+        .with_context(|| format!("Failed to request reviewers on PR #{}", pr_number))?;
+    Ok(())
+}
+
+/// Give a PR the milestone titled `title`, open or closed.
+pub async fn set_milestone(client: &Octocrab, owner: &str, repo: &str, pr_number: u64, title: &str) -> Result<()> {
+    let milestones: Vec<serde_json::Value> = client
+        .get(
+            format!("/repos/{}/{}/milestones", owner, repo),
+            Some(&[("state", "all"), ("per_page", "100")]),
+        )
+        .await
+        .with_context(|| format!("Failed to list the milestones of {}/{}", owner, repo))?;
+    let number = milestones
+        .iter()
+        .find(|m| m["title"].as_str() == Some(title))
+        .and_then(|m| m["number"].as_u64())
+        .with_context(|| format!("{}/{} has no milestone '{}'", owner, repo, title))?;
+    client
+        .issues(owner, repo)
+        .update(pr_number)
+        .milestone(number)
+        .send()
+        .await
+        .with_context(|| format!("Failed to set the milestone of PR #{}", pr_number))?;
+    Ok(())
+}
+
+/// Add a PR to a Projects (v2) board through GraphQL and return its item id.
+/// GitHub hands back the existing item for a PR already on the board.
+pub async fn add_to_project(
+    client: &Octocrab,
+    owner: &str,
+    repo: &str,
+    pr_number: u64,
+    project: &Project,
+) -> Result<String> {
+    let board = project_board(client, project).await?;
+    let pr = client
+        .pulls(owner, repo)
+        .get(pr_number)
+        .await
+        .with_context(|| format!("Failed to fetch PR #{}", pr_number))?;
+    let node_id = pr
+        .node_id
+        .with_context(|| format!("PR #{} has no GraphQL node id", pr_number))?;
+    let response = graphql(
+        client,
+        "mutation($project: ID!, $content: ID!) { \
+         addProjectV2ItemById(input: {projectId: $project, contentId: $content}) { item { id } } }",
+        serde_json::json!({ "project": board.id, "content": node_id }),
+        &format!("Failed to add PR #{} to project {}", pr_number, project),
+    )
+    .await?;
+    response["data"]["addProjectV2ItemById"]["item"]["id"]
+        .as_str()
+        .map(String::from)
+        .with_context(|| format!("Project {} returned no item for PR #{}", project, pr_number))
+}
+
+/// Set the Status field of a project item to the option named `status`.
+pub async fn set_project_status(client: &Octocrab, project: &Project, item_id: &str, status: &str) -> Result<()> {
+    let board = project_board(client, project).await?;
+    let field = board
+        .status_field
+        .with_context(|| format!("Project {} has no single-select Status field", project))?;
+    let option = board
+        .status_options
+        .iter()
+        .find(|(_, name)| name.eq_ignore_ascii_case(status))
+        .map(|(id, _)| id.clone())
+        .with_context(|| {
+            let names: Vec<&str> = board.status_options.iter().map(|(_, name)| name.as_str()).collect();
+            format!("Project {} has no Status '{}' (it has {})", project, status, names.join(", "))
+        })?;
+    graphql(
+        client,
+        "mutation($project: ID!, $item: ID!, $field: ID!, $option: String!) { \
+         updateProjectV2ItemFieldValue(input: {projectId: $project, itemId: $item, fieldId: $field, \
+         value: {singleSelectOptionId: $option}}) { projectV2Item { id } } }",
+        serde_json::json!({ "project": board.id, "item": item_id, "field": field, "option": option }),
+        &format!("Failed to set the status of item {} in project {}", item_id, project),
+    )
+    .await?;
+    Ok(())
+}
+
+/// A Projects (v2) board: its node id and its Status field's id and options.
+struct ProjectBoard {
+    id: String,
+    status_field: Option<String>,
+    /// (option id, option name)
+    status_options: Vec<(String, String)>,
+}
+
+/// Look up `project` by its owner — an organisation or a user — and number.
+async fn project_board(client: &Octocrab, project: &Project) -> Result<ProjectBoard> {
+    let query = "query($owner: String!, $number: Int!) { repositoryOwner(login: $owner) { \
+        ... on ProjectV2Owner { projectV2(number: $number) { id \
+        field(name: \"Status\") { ... on ProjectV2SingleSelectField { id options { id name } } } } } } }";
+    let variables = serde_json::json!({ "owner": project.owner, "number": project.number });
+    let response = graphql(client, query, variables, &format!("Failed to read project {}", project)).await?;
+    let board = &response["data"]["repositoryOwner"]["projectV2"];
+    let id = board["id"]
+        .as_str()
+        .with_context(|| format!("Project {} not found, or the token cannot read it", project))?;
+    let empty = vec![];
+    Ok(ProjectBoard {
+        id: id.to_string(),
+        status_field: board["field"]["id"].as_str().map(String::from),
+        status_options: board["field"]["options"]
+            .as_array()
+            .unwrap_or(&empty)
+            .iter()
+            .filter_map(|o| Some((o["id"].as_str()?.to_string(), o["name"].as_str()?.to_string())))
+            .collect(),
+    })
+}
+
+/// Unresolved review threads on a PR, through GraphQL (REST does not say
+/// which threads are resolved). Reads the first 100 threads and the first
+/// 50 comments of each.
+pub async fn unresolved_review_threads(
+    client: &Octocrab,
+    owner: &str,
+    repo: &str,
+    pr_number: u64,
+) -> Result<Vec<ReviewThread>> {
+    let query = "query($owner: String!, $repo: String!, $number: Int!) { \
+        repository(owner: $owner, name: $repo) { pullRequest(number: $number) { \
+        reviewThreads(first: 100) { nodes { isResolved path line \
+        comments(first: 50) { nodes { author { login } body url } } } } } } }";
+    let variables = serde_json::json!({ "owner": owner, "repo": repo, "number": pr_number });
+    let failure = format!("Failed to read the review threads of PR #{}", pr_number);
+    let response = graphql(client, query, variables, &failure).await?;
+    Ok(review_threads(&response))
+}
+
+/// Map a `reviewThreads` GraphQL response to its unresolved threads.
+fn review_threads(response: &serde_json::Value) -> Vec<ReviewThread> {
+    let empty = vec![];
+    let nodes = response["data"]["repository"]["pullRequest"]["reviewThreads"]["nodes"].as_array().unwrap_or(&empty);
+    nodes
+        .iter()
+        .filter(|t| !t["isResolved"].as_bool().unwrap_or(false))
+        .map(|t| ReviewThread {
+            path: t["path"].as_str().unwrap_or_default().to_string(),
+            line: t["line"].as_u64(),
+            comments: t["comments"]["nodes"]
+                .as_array()
+                .unwrap_or(&empty)
+                .iter()
+                .map(|c| ReviewComment {
+                    // A deleted account has no author.
+                    author: c["author"]["login"].as_str().unwrap_or("ghost").to_string(),
+                    body: c["body"].as_str().unwrap_or_default().to_string(),
+                    url: c["url"].as_str().unwrap_or_default().to_string(),
+                })
+                .collect(),
+        })
+        .collect()
+}
+
 /// Retrieve basic PR info including CI and review state.
 pub async fn get_pr_info(
     client: &Octocrab,
@@ -90,47 +639,182 @@ pub async fn get_pr_info(
         .unwrap_or_else(|| format!("https://github.com/{}/{}/pull/{}", owner, repo, pr_number));
 
     let title = pr.title.unwrap_or_default();
+    let body = pr.body.unwrap_or_default();
+    let merged_at = pr.merged_at;
+    let merged = merged_at.is_some();
+    let base = pr.base.ref_field.clone();
+    let head_sha = pr.head.sha.clone();
 
-    // Fetch combined commit status
-    let ci_status = get_ci_status(client, owner, repo, pr_number).await.unwrap_or_else(|_| "unknown".to_string());
+    // Fetch commit statuses and check runs
+    let (ci_status, failing_checks) = get_ci_status(client, owner, repo, &head_sha)
+        .await
+        .unwrap_or_else(|_| ("unknown".to_string(), vec![]));
     let review_state = get_review_state(client, owner, repo, pr_number).await.unwrap_or_else(|_| "unknown".to_string());
 
     Ok(PrInfo {
         number: pr_number,
         url,
         title,
+        body,
         state,
+        merged,
+        merged_at,
+        base,
+        head_sha,
         ci_status,
+        failing_checks,
         review_state,
     })
 }
 
-async fn get_ci_status(
+/// Status check names that branch protection requires on `branch`.
+/// Returns an empty list when the branch is unprotected or has no required
+/// checks, and when the token may not read its protection (which takes admin
+/// rights) — GitHub still enforces the checks on merge.
+pub async fn required_status_checks(
     client: &Octocrab,
     owner: &str,
     repo: &str,
-    pr_number: u64,
-) -> Result<String> {
-    // Get the PR's head SHA via the checks API
-    let pr = client.pulls(owner, repo).get(pr_number).await?;
-    let sha = pr
-        .head
-        .sha;
+    branch: &str,
+) -> Result<Vec<String>> {
+    #[derive(Deserialize)]
+    struct Check {
+        context: String,
+    }
 
     #[derive(Deserialize)]
-    struct CombinedStatus {
+    struct RequiredStatusChecks {
+        #[serde(default)]
+        contexts: Vec<String>,
+        #[serde(default)]
+        checks: Vec<Check>,
+    }
+
+    let result: octocrab::Result<RequiredStatusChecks> = client
+        .get(
+            format!(
+                "/repos/{}/{}/branches/{}/protection/required_status_checks",
+                owner, repo, branch
+            ),
+            None::<&()>,
+        )
+        .await;
+
+    let required = match result {
+        Ok(r) => r,
+        // 404: branch is not protected or has no required checks configured;
+        // 403: protection settings are only readable with admin rights
+        Err(octocrab::Error::GitHub { source, .. }) if matches!(source.status_code.as_u16(), 403 | 404) => {
+            return Ok(vec![]);
+        }
+        Err(e) => {
+            return Err(e).with_context(|| {
+                format!("Failed to read branch protection for '{}'", branch)
+            });
+        }
+    };
+
+    let mut names = required.contexts;
+    for check in required.checks {
+        if !names.contains(&check.context) {
+            names.push(check.context);
+        }
+    }
+    Ok(names)
+}
+
+/// Return the subset of `required` checks that have not succeeded on commit `sha`.
+/// Both commit statuses and check runs are consulted.
+pub async fn unmet_required_checks(
+    client: &Octocrab,
+    owner: &str,
+    repo: &str,
+    sha: &str,
+    required: &[String],
+) -> Result<Vec<String>> {
+    if required.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let checks = commit_checks(client, owner, repo, sha).await?;
+    let passed = |name: &str| {
+        checks
+            .iter()
+            .any(|c| c.name == name && c.outcome == CheckOutcome::Success)
+    };
+
+    Ok(required.iter().filter(|r| !passed(r)).cloned().collect())
+}
+
+/// Fetch every commit status and check run reported on `sha`.
+async fn commit_checks(
+    client: &Octocrab,
+    owner: &str,
+    repo: &str,
+    sha: &str,
+) -> Result<Vec<CheckResult>> {
+    #[derive(Deserialize)]
+    struct Status {
+        context: String,
         state: String,
     }
 
+    #[derive(Deserialize)]
+    struct CombinedStatus {
+        #[serde(default)]
+        statuses: Vec<Status>,
+    }
+
+    #[derive(Deserialize)]
+    struct CheckRun {
+        name: String,
+        status: String,
+        conclusion: Option<String>,
+    }
+
+    #[derive(Deserialize)]
+    struct CheckRuns {
+        #[serde(default)]
+        check_runs: Vec<CheckRun>,
+    }
+
     let combined: CombinedStatus = client
+        .get(format!("/repos/{}/{}/commits/{}/status", owner, repo, sha), None::<&()>)
+        .await
+        .with_context(|| format!("Failed to fetch commit status for {}", sha))?;
+    let runs: CheckRuns = client
         .get(
-            format!("/repos/{}/{}/commits/{}/status", owner, repo, sha),
+            format!("/repos/{}/{}/commits/{}/check-runs?per_page=100", owner, repo, sha),
             None::<&()>,
         )
         .await
-        .unwrap_or(CombinedStatus { state: "pending".to_string() });
+        .with_context(|| format!("Failed to fetch check runs for {}", sha))?;
+
+    let mut checks: Vec<CheckResult> = combined
+        .statuses
+        .into_iter()
+        .map(|s| CheckResult {
+            outcome: CheckOutcome::from_status_state(&s.state),
+            name: s.context,
+        })
+        .collect();
+    checks.extend(runs.check_runs.into_iter().map(|r| CheckResult {
+        outcome: CheckOutcome::from_check_run(&r.status, r.conclusion.as_deref()),
+        name: r.name,
+    }));
+    Ok(checks)
+}
 
-    Ok(combined.state)
+/// Aggregate CI state for the PR's head commit from both the combined status
+/// API and the Checks API (GitHub Actions only reports through the latter).
+async fn get_ci_status(
+    client: &Octocrab,
+    owner: &str,
+    repo: &str,
+    head_sha: &str,
+) -> Result<(String, Vec<String>)> {
+    let checks = commit_checks(client, owner, repo, head_sha).await?;
+    Ok(aggregate_checks(&checks))
 }
 
 async fn get_review_state(
@@ -163,3 +847,55 @@ async fn get_review_state(
         Ok("commented".to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classic_repo_scope_allows_everything() {
+        let needs = [Access::PullRequests, Access::Issues, Access::AutoMerge];
+        assert!(missing_scopes("repo, workflow", true, &needs).is_empty());
+        assert!(missing_scopes("public_repo", false, &needs).is_empty());
+    }
+
+    #[test]
+    fn test_classic_token_without_repo_scope_names_it() {
+        let missing = missing_scopes("public_repo, read:org", true, &[Access::PullRequests]);
+        assert_eq!(missing, vec!["create and update PRs — the token needs the `repo` scope"]);
+        assert_eq!(missing_scopes("", false, &[Access::Issues]).len(), 1);
+    }
+
+    #[test]
+    fn test_app_permissions_are_checked_per_access() {
+        let granted: HashMap<String, String> =
+            [("pull_requests", "write"), ("contents", "read"), ("issues", "read")]
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect();
+        assert!(missing_app_permissions(&granted, &[Access::PullRequests]).is_empty());
+        assert_eq!(
+            missing_app_permissions(&granted, &[Access::PullRequests, Access::Issues, Access::AutoMerge]),
+            vec![
+                "open the tracking issue — the GitHub App needs issues: write",
+                "enable auto-merge — the GitHub App needs contents: write",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_review_threads_keep_unresolved_ones() {
+        let response = serde_json::json!({ "data": { "repository": { "pullRequest": { "reviewThreads": { "nodes": [
+            { "isResolved": true, "path": "src/db.rs", "line": 3, "comments": { "nodes": [] } },
+            { "isResolved": false, "path": "src/api.rs", "line": null, "comments": { "nodes": [
+                { "author": { "login": "ana" }, "body": "Rename this.", "url": "https://github.com/a/b/pull/2#r1" },
+                { "author": null, "body": "+1", "url": "https://github.com/a/b/pull/2#r2" }
+            ] } }
+        ] } } } } });
+        let threads = review_threads(&response);
+        assert_eq!(threads.len(), 1);
+        assert_eq!((threads[0].path.as_str(), threads[0].line), ("src/api.rs", None));
+        assert_eq!(threads[0].comments[0].author, "ana");
+        assert_eq!(threads[0].comments[1].author, "ghost");
+    }
+}
@@ -0,0 +1,194 @@
+//! Long-form guides shown by `merges help <topic>`, for concepts that don't
+//! fit in a `--help` one-liner.
+//!
+//! Guide text uses a small markdown subset: `# ` starts a heading, lines
+//! indented by four spaces are commands or examples, and everything else is
+//! prose.
+
+use colored::Colorize;
+
+pub struct Topic {
+    pub name: &'static str,
+    pub summary: &'static str,
+    pub body: &'static str,
+}
+
+pub const TOPICS: &[Topic] = &[
+    Topic { name: "stacking", summary: "Stacked vs independent PRs, and keeping a stack in sync", body: STACKING },
+    Topic { name: "worktrees", summary: "Worktree mode: chunk branches without switching yours", body: WORKTREES },
+    Topic { name: "mcp", summary: "Driving merges from an LLM client over MCP", body: MCP },
+];
+
+/// Look up a guide by name, ignoring case.
+pub fn topic(name: &str) -> Option<&'static Topic> {
+    TOPICS.iter().find(|t| t.name.eq_ignore_ascii_case(name))
+}
+
+/// One line per guide, for `merges help` without a topic.
+pub fn topic_list() -> String {
+    let mut out = format!("{}\n", "Guides (merges help <topic>):".bold().underline());
+    for t in TOPICS {
+        out.push_str(&format!("  {}{}\n", format!("{:<11}", t.name).bold(), t.summary));
+    }
+    out
+}
+
+/// Render guide text for the terminal: bold headings, coloured examples.
+pub fn render(body: &str) -> String {
+    let mut out = String::new();
+    for line in body.trim().lines() {
+        if let Some(heading) = line.strip_prefix("# ") {
+            out.push_str(&heading.bold().underline().to_string());
+        } else if let Some(example) = line.strip_prefix("    ") {
+            out.push_str(&format!("    {}", example.cyan()));
+        } else {
+            out.push_str(line);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+const STACKING: &str = "
+# Stacked and independent PRs
+
+`merges split` turns one large branch into chunks, and `merges push` opens one
+PR per chunk. How those PRs relate depends on the strategy:
+
+    merges push --stacked
+    merges push --independent
+
+Stacked (the default): chunk 1 targets the base branch, chunk 2 targets chunk
+1's branch, and so on. Each PR shows only its own diff, and later chunks may
+build on earlier ones. PRs must merge in order; when one merges, the next is
+retargeted to the base branch on the following `merges push`.
+
+Independent: every PR targets the base branch. Chunks must not depend on each
+other, but reviewers can approve and merge them in any order.
+
+The strategy is remembered in .merges.json; set it up front with
+`merges init --strategy independent`.
+
+# Keeping the stack current
+
+When the base branch moves on, rebase every chunk in stack order and re-push:
+
+    merges sync
+    merges push
+
+rerere is enabled at init, so a conflict resolved once is replayed on later
+syncs. `merges status` shows how far each chunk is behind the base branch.
+
+# PR titles
+
+Titles carry the stack position, e.g. `[2/5] models`, so reviewers see where a
+PR sits. When chunks are added or dropped, `merges push` retitles the PRs;
+`merges push --update-prs` also regenerates their bodies. Leave the position
+out with `merges init --no-title-position`.
+
+# If a push is interrupted
+
+    merges push --resume
+
+skips chunks already pushed and reuses PRs already open for chunk branches.
+";
+
+const WORKTREES: &str = "
+# Worktree mode
+
+By default merges checks out each chunk branch in your working tree while it
+splits, syncs, and pushes, then returns to your source branch. That needs a
+clean working tree and rewrites files under your editor.
+
+    merges init --worktrees
+
+gives every chunk branch its own git worktree instead, under
+.git/merges-worktrees/<branch>. Your checkout never changes, so you can keep
+editing while merges works, and each chunk can be built or tested in isolation:
+
+    cd .git/merges-worktrees/feat-payments-v2-chunk-1-db
+    cargo test
+
+# Fixing review feedback
+
+Commit directly in the chunk's worktree, then push as usual:
+
+    merges push
+
+# Housekeeping
+
+`merges clean` removes the worktrees along with their branches.
+`merges doctor` reports chunks whose worktree is missing.
+
+An existing split cannot be switched to worktrees in place; run
+`merges init --force --worktrees` and split again.
+";
+
+const MCP: &str = "
+# Using merges from an LLM client
+
+    merges mcp
+
+starts a Model Context Protocol server on stdio. Register it with your client
+(VS Code, Claude Desktop, ...) as a stdio server running `merges mcp` in the
+repository; the client can then split, push, and maintain stacks itself.
+`merges mcp install --client claude|vscode|cursor` writes that entry into the
+client's configuration for you (`--print` shows it instead); `merges setup`
+offers the same.
+
+# Tools
+
+merges_init, merges_split, merges_push, merges_sync, merges_status,
+merges_add, merges_move, merges_clean, merges_doctor, and merges_history
+mirror the CLI commands of the same name and return JSON. merges_plan_preview
+returns the plan `merges split --auto` would apply, with per-file line counts,
+without changing anything.
+merges_unassigned lists changed files that no chunk contains yet, and
+merges_refresh assigns them to existing chunks, like `merges assign --to`.
+
+# The two-call split
+
+The client calls merges_split without a plan to list the changed files, decides
+on a grouping, then calls it again with a plan:
+
+    merges_split {}
+    merges_split { \"plan\": [{ \"name\": \"db\", \"files\": [\"db/001.sql\"] }] }
+
+merges_init never prompts over MCP; pass `overwrite: true` to replace an
+existing state file.
+
+# Logging
+
+While a tool runs, the server sends MCP log notifications for every git
+command that changes the repository or the remote and every pull-request
+change, at info level. Clients choose the level with logging/setLevel.
+
+merges_push and merges_sync honour notifications/cancelled: they stop before
+the next chunk, and a push stopped that way finishes with `resume: true`.
+";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_topic_lookup_ignores_case() {
+        assert_eq!(topic("Stacking").map(|t| t.name), Some("stacking"));
+        assert!(topic("rebasing").is_none());
+    }
+
+    #[test]
+    fn test_every_topic_starts_with_a_heading() {
+        for t in TOPICS {
+            assert!(t.body.trim_start().starts_with("# "), "{}", t.name);
+        }
+    }
+
+    #[test]
+    fn test_render_strips_heading_markers() {
+        let out = render("# Title\nprose\n    merges sync\n");
+        assert!(out.contains("Title") && !out.contains("# "), "{}", out);
+        assert_eq!(out.lines().nth(1), Some("prose"));
+        assert!(out.lines().nth(2).unwrap().starts_with("    "));
+    }
+}
@@ -0,0 +1,401 @@
+//! Import graph between changed files, for `merges split --auto --imports`.
+//!
+//! Rust `use` and `mod` items and TypeScript/JavaScript `import`, `export …
+//! from`, and `require` specifiers are read with a light line-based scan (no
+//! full parser) and resolved to files in the changed set. Anything that does
+//! not resolve there — external crates and packages, files outside the diff —
+//! is ignored: only dependencies between changed files can cross chunks.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::{git, split::ChunkPlan};
+
+/// Edges `(importer, imported)` between `files`, reading each file as it is at `rev`.
+pub fn graph(root: &Path, rev: &str, files: &[String]) -> Vec<(String, String)> {
+    let mut edges = vec![];
+    for file in files {
+        let Some(source) = git::read_file_at_ref(root, rev, file) else { continue };
+        for target in imported_files(file, &source, files) {
+            edges.push((file.clone(), target));
+        }
+    }
+    edges
+}
+
+/// Files among `files` that `path` (with contents `source`) imports.
+pub fn imported_files(path: &str, source: &str, files: &[String]) -> Vec<String> {
+    let ext = Path::new(path).extension().and_then(|e| e.to_str()).unwrap_or_default();
+    let mut targets = match ext {
+        "rs" => rust_imports(path, source, files),
+        "ts" | "tsx" | "mts" | "cts" | "js" | "jsx" | "mjs" | "cjs" => script_imports(path, source, files),
+        _ => vec![],
+    };
+    targets.retain(|t| t != path);
+    targets.sort();
+    targets.dedup();
+    targets
+}
+
+// ── Rust ─────────────────────────────────────────────────────────────────────
+
+fn rust_imports(path: &str, source: &str, files: &[String]) -> Vec<String> {
+    let mut targets = vec![];
+    let mut statement: Option<String> = None;
+    for line in source.lines() {
+        let line = line.split("//").next().unwrap_or_default().trim();
+        if let Some(partial) = statement.as_mut() {
+            partial.push(' ');
+            partial.push_str(line);
+        } else {
+            let item = strip_visibility(line);
+            if let Some(tree) = item.strip_prefix("use ") {
+                statement = Some(tree.to_string());
+            } else if let Some(name) = item.strip_prefix("mod ").and_then(|m| m.strip_suffix(';')) {
+                targets.extend(resolve_rust(path, &["self", name.trim()], files));
+                continue;
+            } else {
+                continue;
+            }
+        }
+        let Some(done) = statement.as_deref().filter(|s| s.contains(';')) else { continue };
+        let tree = done.split(';').next().unwrap_or_default();
+        for use_path in expand_use_tree(tree) {
+            let segments: Vec<&str> = use_path.split("::").map(str::trim).filter(|s| !s.is_empty()).collect();
+            targets.extend(resolve_rust(path, &segments, files));
+        }
+        statement = None;
+    }
+    targets
+}
+
+/// `pub use …` / `pub(crate) mod …` → `use …` / `mod …`.
+fn strip_visibility(line: &str) -> &str {
+    let Some(rest) = line.strip_prefix("pub") else { return line };
+    let rest = match rest.strip_prefix('(') {
+        Some(scoped) => scoped.split_once(')').map_or(rest, |(_, after)| after),
+        None => rest,
+    };
+    rest.trim_start()
+}
+
+/// Flatten a use tree into paths: `a::{b, c::{d, e as f}}` → `a::b`, `a::c::d`, `a::c::e`.
+fn expand_use_tree(tree: &str) -> Vec<String> {
+    let tree = tree.trim();
+    let Some(open) = tree.find('{') else {
+        let single = tree.split(" as ").next().unwrap_or_default().trim();
+        return vec![single.trim_end_matches("::*").to_string()];
+    };
+    let prefix = tree[..open].trim().trim_end_matches("::");
+    let inner = &tree[open + 1..tree.rfind('}').unwrap_or(tree.len())];
+    split_top_level(inner)
+        .into_iter()
+        .flat_map(expand_use_tree)
+        .filter(|p| !p.is_empty())
+        .map(|p| match (prefix.is_empty(), p.as_str()) {
+            (true, _) => p,
+            (false, "self") => prefix.to_string(),
+            (false, _) => format!("{}::{}", prefix, p),
+        })
+        .collect()
+}
+
+/// Split on commas that are not nested inside braces.
+fn split_top_level(s: &str) -> Vec<&str> {
+    let mut parts = vec![];
+    let (mut depth, mut start) = (0, 0);
+    for (i, c) in s.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&s[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+/// The file a Rust path names: the longest module prefix of `segments` that
+/// is one of `files`, as `<dir>/<module>.rs` or `<dir>/<module>/mod.rs`.
+fn resolve_rust(path: &str, segments: &[&str], files: &[String]) -> Vec<String> {
+    let (dir, rest) = match segments.first() {
+        Some(&"crate") => (crate_root(path), &segments[1..]),
+        Some(&"self") => (module_dir(path), &segments[1..]),
+        Some(&"super") => {
+            let mut dir = module_dir(path);
+            let mut rest = segments;
+            while rest.first() == Some(&"super") {
+                dir = parent(&dir);
+                rest = &rest[1..];
+            }
+            (dir, rest)
+        }
+        // 2018-style paths into the crate's own modules (`use models::User` in main.rs).
+        Some(_) => (crate_root(path), segments),
+        None => return vec![],
+    };
+    // Path prefixes from longest to shortest; items after the module are ignored.
+    for len in (1..=rest.len()).rev() {
+        let module = rest[..len].join("/");
+        for candidate in [join(&dir, &format!("{}.rs", module)), join(&dir, &format!("{}/mod.rs", module))] {
+            if files.contains(&candidate) {
+                return vec![candidate];
+            }
+        }
+    }
+    // `use super::Item` names the parent module file itself.
+    if segments.first() == Some(&"super") {
+        for candidate in [format!("{}.rs", dir), join(&dir, "mod.rs")] {
+            if files.contains(&candidate) {
+                return vec![candidate];
+            }
+        }
+    }
+    vec![]
+}
+
+/// Directory holding the crate root: everything up to and including `src`,
+/// or the file's own directory outside a `src` tree.
+fn crate_root(path: &str) -> String {
+    let parts: Vec<&str> = path.split('/').collect();
+    match parts.iter().position(|p| *p == "src") {
+        Some(src) => parts[..=src].join("/"),
+        None => parent(path),
+    }
+}
+
+/// Directory where a file's child modules live: its own directory for
+/// `lib.rs`/`main.rs`/`mod.rs`, otherwise `<dir>/<stem>`.
+fn module_dir(path: &str) -> String {
+    let file = path.rsplit('/').next().unwrap_or(path);
+    match file {
+        "lib.rs" | "main.rs" | "mod.rs" => parent(path),
+        _ => path.trim_end_matches(".rs").to_string(),
+    }
+}
+
+fn parent(path: &str) -> String {
+    path.rsplit_once('/').map(|(dir, _)| dir.to_string()).unwrap_or_default()
+}
+
+fn join(dir: &str, rest: &str) -> String {
+    if dir.is_empty() { rest.to_string() } else { format!("{}/{}", dir, rest) }
+}
+
+// ── TypeScript / JavaScript ──────────────────────────────────────────────────
+
+const SCRIPT_EXTENSIONS: [&str; 8] = ["ts", "tsx", "mts", "cts", "js", "jsx", "mjs", "cjs"];
+
+fn script_imports(path: &str, source: &str, files: &[String]) -> Vec<String> {
+    let dir = parent(path);
+    let mut targets = vec![];
+    for line in source.lines() {
+        let line = line.trim();
+        if line.starts_with("//") {
+            continue;
+        }
+        for marker in ["from ", "import ", "import(", "require("] {
+            let mut rest = line;
+            while let Some(at) = rest.find(marker) {
+                rest = &rest[at + marker.len()..];
+                if let Some(spec) = quoted(rest).filter(|s| s.starts_with('.')) {
+                    targets.extend(resolve_script(&dir, spec, files));
+                }
+            }
+        }
+    }
+    targets
+}
+
+/// The string literal `s` starts with, if any.
+fn quoted(s: &str) -> Option<&str> {
+    let quote = s.chars().next().filter(|c| matches!(c, '\'' | '"' | '`'))?;
+    let body = &s[1..];
+    body.find(quote).map(|end| &body[..end])
+}
+
+/// The changed file a relative specifier points at, trying the usual
+/// extensions and `index` files; a `.js` specifier may name a `.ts` source.
+fn resolve_script(dir: &str, spec: &str, files: &[String]) -> Option<String> {
+    let base = normalize(&join(dir, spec))?;
+    let mut candidates = vec![base.clone()];
+    let stem = base.strip_suffix(".js").or_else(|| base.strip_suffix(".mjs")).unwrap_or(&base);
+    for ext in SCRIPT_EXTENSIONS {
+        candidates.push(format!("{}.{}", stem, ext));
+        candidates.push(format!("{}/index.{}", base, ext));
+    }
+    candidates.into_iter().find(|c| files.contains(c))
+}
+
+/// Resolve `.` and `..` segments; `None` if the path climbs above the root.
+fn normalize(path: &str) -> Option<String> {
+    let mut parts: Vec<&str> = vec![];
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop()?;
+            }
+            _ => parts.push(part),
+        }
+    }
+    Some(parts.join("/"))
+}
+
+// ── Regrouping ───────────────────────────────────────────────────────────────
+
+/// An import between two files that ended up in different chunks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrossImport {
+    pub from_chunk: String,
+    pub from_file: String,
+    pub to_chunk: String,
+    pub to_file: String,
+}
+
+/// Adjust an auto plan to the import graph `edges`: chunks whose files import
+/// each other in a cycle are merged (no order of separate PRs could satisfy
+/// them), and chunks are ordered so imported chunks come before the chunks
+/// importing them, which is the order a stack needs. Returns the new plan and
+/// the imports still crossing chunks.
+pub fn regroup(plan: Vec<ChunkPlan>, edges: &[(String, String)]) -> (Vec<ChunkPlan>, Vec<CrossImport>) {
+    let n = plan.len();
+    let chunk_of = |plan: &[ChunkPlan], file: &str| plan.iter().position(|c| c.files.iter().any(|f| f == file));
+
+    // reach[i][j]: chunk i (transitively) imports chunk j.
+    let mut reach = vec![vec![false; n]; n];
+    for (from, to) in edges {
+        if let (Some(i), Some(j)) = (chunk_of(&plan, from), chunk_of(&plan, to)) {
+            reach[i][j] |= i != j;
+        }
+    }
+    for k in 0..n {
+        let via_k = reach[k].clone();
+        for row in reach.iter_mut().filter(|row| row[k]) {
+            for (cell, via) in row.iter_mut().zip(&via_k) {
+                *cell |= *via;
+            }
+        }
+    }
+
+    // Merge each import cycle into the chunk that comes first in the plan.
+    let mut merged: Vec<ChunkPlan> = vec![];
+    let mut into: HashMap<usize, usize> = HashMap::new();
+    for (i, chunk) in plan.iter().enumerate() {
+        match (0..i).find(|&j| reach[i][j] && reach[j][i]) {
+            Some(j) => {
+                let target = into[&j];
+                into.insert(i, target);
+                merged[target].name = format!("{}-and-{}", merged[target].name, chunk.name);
+                merged[target].files.extend(chunk.files.iter().cloned());
+            }
+            None => {
+                into.insert(i, merged.len());
+                merged.push(chunk.clone());
+            }
+        }
+    }
+    for chunk in &mut merged {
+        chunk.files.sort();
+    }
+
+    // Order so that every chunk comes after the chunks it imports; the graph
+    // is acyclic now, and ties keep the plan's order.
+    let m = merged.len();
+    let mut deps = vec![vec![false; m]; m];
+    for (from, to) in edges {
+        if let (Some(i), Some(j)) = (chunk_of(&merged, from), chunk_of(&merged, to)) {
+            deps[i][j] |= i != j;
+        }
+    }
+    let mut placed = vec![false; m];
+    let mut order = vec![];
+    while order.len() < m {
+        let next = (0..m)
+            .find(|&i| !placed[i] && (0..m).all(|j| !deps[i][j] || placed[j]))
+            .unwrap_or_else(|| (0..m).find(|&i| !placed[i]).unwrap_or_default());
+        placed[next] = true;
+        order.push(next);
+    }
+    let ordered: Vec<ChunkPlan> = order.into_iter().map(|i| merged[i].clone()).collect();
+
+    let mut cross: Vec<CrossImport> = edges
+        .iter()
+        .filter_map(|(from, to)| {
+            let (i, j) = (chunk_of(&ordered, from)?, chunk_of(&ordered, to)?);
+            (i != j).then(|| CrossImport {
+                from_chunk: ordered[i].name.clone(),
+                from_file: from.clone(),
+                to_chunk: ordered[j].name.clone(),
+                to_file: to.clone(),
+            })
+        })
+        .collect();
+    cross.sort_by(|a, b| (&a.from_file, &a.to_file).cmp(&(&b.from_file, &b.to_file)));
+    cross.dedup();
+    (ordered, cross)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_rust_use_and_mod_resolve_to_changed_files() {
+        let changed = files(&["src/lib.rs", "src/models/mod.rs", "src/models/payment.rs", "src/api/payments.rs"]);
+        let source = "use std::fmt;\nuse crate::models::{payment::Payment, self};\npub mod api;\n";
+        assert_eq!(imported_files("src/lib.rs", source, &changed), vec!["src/models/mod.rs", "src/models/payment.rs"]);
+
+        let source = "use super::super::models::payment::{\n    Payment,\n    Refund,\n};\n";
+        assert_eq!(imported_files("src/api/payments.rs", source, &changed), vec!["src/models/payment.rs"]);
+    }
+
+    #[test]
+    fn test_expand_use_tree_flattens_nested_groups() {
+        assert_eq!(
+            expand_use_tree("crate::a::{b, c::{d, e as f}, self}"),
+            vec!["crate::a::b", "crate::a::c::d", "crate::a::c::e", "crate::a"]
+        );
+    }
+
+    #[test]
+    fn test_script_imports_resolve_relative_specifiers() {
+        let changed = files(&["web/api/client.ts", "web/components/Form.tsx", "web/components/index.ts"]);
+        let source = "import React from 'react';\nimport { post } from '../api/client.js';\nexport * from \"./index\";\nconst f = require('./Form');\n";
+        assert_eq!(
+            imported_files("web/components/Form.tsx", source, &changed),
+            vec!["web/api/client.ts", "web/components/index.ts"]
+        );
+        assert_eq!(imported_files("web/x.ts", "import '../../outside';", &changed), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_regroup_merges_cycles_and_orders_dependencies_first() {
+        let plan = vec![
+            ChunkPlan { name: "api".into(), files: files(&["api.rs"]), ..Default::default() },
+            ChunkPlan { name: "models".into(), files: files(&["models.rs"]), ..Default::default() },
+            ChunkPlan { name: "db".into(), files: files(&["db.rs"]), ..Default::default() },
+            ChunkPlan { name: "schema".into(), files: files(&["schema.rs"]), ..Default::default() },
+        ];
+        let edges = vec![
+            ("api.rs".to_string(), "models.rs".to_string()),
+            ("models.rs".to_string(), "db.rs".to_string()),
+            ("db.rs".to_string(), "schema.rs".to_string()),
+            ("schema.rs".to_string(), "db.rs".to_string()),
+        ];
+        let (plan, cross) = regroup(plan, &edges);
+        let names: Vec<&str> = plan.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["db-and-schema", "models", "api"]);
+        assert_eq!(plan[0].files, vec!["db.rs", "schema.rs"]);
+        assert_eq!(cross.len(), 2);
+        assert_eq!((cross[0].from_chunk.as_str(), cross[0].to_chunk.as_str()), ("api", "models"));
+    }
+}
@@ -0,0 +1,83 @@
+//! Operation journal: one JSON line per state-changing command (split, add,
+//! move, push, sync, clean, recover, and state import/pull), recording when it ran, which chunks it touched,
+//! and the SHAs their branches ended up at. Read back by `merges history`.
+//!
+//! The journal lives in the git directory rather than the working tree, so
+//! `git add -A` on a chunk branch can never commit it, and it is shared by
+//! every worktree of the repository.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::{git, state::Chunk};
+
+pub const JOURNAL_FILE: &str = "merges-journal.jsonl";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entry {
+    pub at: DateTime<Utc>,
+    /// Command that ran: `split`, `add`, `move`, `push`, `sync`, `clean`,
+    /// `import`, `pull`, or `recover`. A sync also records its progress:
+    /// `sync-start` before the first rebase and `sync-chunk` after each one.
+    pub operation: String,
+    /// Names of the chunks the operation touched.
+    pub chunks: Vec<String>,
+    /// Resulting SHA of each touched chunk branch that still exists.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub branches: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub summary: String,
+}
+
+/// Path of the journal inside the repository's common git directory.
+pub fn path(root: &Path) -> Result<PathBuf> {
+    Ok(git::common_dir(root)?.join(JOURNAL_FILE))
+}
+
+/// Append an entry for `operation` over `chunks`, resolving each chunk
+/// branch's current SHA.
+pub fn record(root: &Path, operation: &str, summary: &str, chunks: &[Chunk]) -> Result<()> {
+    let entry = Entry {
+        at: Utc::now(),
+        operation: operation.to_string(),
+        chunks: chunks.iter().map(|c| c.name.clone()).collect(),
+        branches: chunks
+            .iter()
+            .filter_map(|c| Some((c.branch.clone(), git::rev_parse(root, &c.branch).ok()?)))
+            .collect(),
+        summary: summary.to_string(),
+    };
+    append(root, &entry)
+}
+
+/// Append `entry` as it is, for operations that record more than the chunk
+/// branches' current SHAs.
+pub fn append(root: &Path, entry: &Entry) -> Result<()> {
+    let path = path(root)?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Every journal entry, oldest first. A missing journal is empty.
+pub fn load(root: &Path) -> Result<Vec<Entry>> {
+    let path = path(root)?;
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let content = std::fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(i, l)| serde_json::from_str(l).with_context(|| format!("Corrupt journal entry on line {}", i + 1)))
+        .collect()
+}
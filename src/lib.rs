@@ -1,9 +1,56 @@
-// Re-export internal modules for integration tests and external crate use.
+//! merges as a library: everything the `merges` binary does is available
+//! here, and the binary is a thin shell that parses arguments and prints
+//! results.
+//!
+//! - [`commands`] has one module per command. Its `run` takes the
+//!   repository root (or finds it from the working directory) and reports
+//!   failure as an error rather than exiting; most return what they did,
+//!   such as the chunks or branches they changed.
+//! - [`split::apply_plan`] builds chunk branches from a plan without asking
+//!   anything; [`state::MergesState`] reads and writes `.merges.json`.
+//! - Commands that talk to a forge have a `run_with` variant taking a
+//!   [`forge::Forge`] trait object, so an embedding program can supply its
+//!   own client — or a [`forge::memory::MemoryForge`], which keeps PRs in
+//!   memory — instead of the backend from `.merges.json`:
+//!   [`commands::push::run_with`], [`commands::status::run_with`] and
+//!   [`commands::clean::run_with`].
+//! - Progress is reported as [`output::Event`]s — messages, warnings, steps
+//!   and per-chunk progress — to the [`output::Reporter`] set with
+//!   [`output::set_reporter`] (any `Fn(&Event)` will do);
+//!   call [`prompt::disable`] so commands never wait for an answer, and pass
+//!   a [`cancel::CancelToken`] to stop long operations between chunks.
+//!
+//! ```no_run
+//! # async fn example(forge: &dyn merges::forge::Forge) -> anyhow::Result<()> {
+//! merges::prompt::disable();
+//! merges::output::set_reporter(|event: &merges::output::Event| eprintln!("{:?}", event));
+//! let root = std::path::Path::new("/path/to/repo");
+//! let deleted = merges::commands::clean::run_with(root, true, true, Some(forge)).await?;
+//! println!("deleted {}", deleted.join(", "));
+//! # Ok(())
+//! # }
+//! ```
+pub mod autostash;
+pub mod cancel;
+pub mod ci;
 pub mod commands;
 pub mod config;
 pub mod doctor;
+pub mod empty;
+pub mod forge;
 pub mod git;
 pub mod github;
+pub mod help;
+pub mod imports;
+pub mod journal;
 pub mod mcp;
+pub mod output;
+pub mod pathspec;
+pub mod project;
+pub mod preset;
+pub mod prompt;
+pub mod routing;
+pub mod size;
 pub mod split;
 pub mod state;
+pub mod summary;
@@ -1,16 +1,12 @@
-mod commands;
-mod config;
-mod doctor;
-mod git;
-mod github;
-mod mcp;
-mod split;
-mod state;
+//! The `merges` command line: parses arguments and hands each command to
+//! the library in `lib.rs`, then prints its result.
 
 use anyhow::Result;
 use clap::{CommandFactory, Parser, Subcommand};
 use clap_complete::generate;
 
+use merges::{cancel, commands, doctor, forge, git, help, journal, mcp, output, prompt, size, split, state};
+
 #[derive(Parser)]
 #[command(
     name = "merges",
@@ -18,18 +14,39 @@ use clap_complete::generate;
     about = "Break down large PRs into smaller reviewable chunks",
     long_about = "merges helps you split a large feature branch into small, independently\n\
                   mergeable PRs. It keeps chunk branches rebased on main, creates GitHub PRs\n\
-                  automatically, and exposes everything as an MCP server for LLM clients."
+                  automatically, and exposes everything as an MCP server for LLM clients.",
+    after_long_help = "Guides: merges help stacking | worktrees | mcp",
+    disable_help_subcommand = true
 )]
 struct Cli {
+    /// Print one JSON result object on stdout instead of human-readable output
+    /// (push, sync, clean, doctor, diffcheck, audit, add, move, status, history)
+    #[arg(long, global = true)]
+    json: bool,
+
+    /// Never prompt: use defaults, or fail with a hint when an answer is
+    /// needed (implied when stdin or stderr is not a terminal)
+    #[arg(long, global = true)]
+    no_input: bool,
+
+    /// Work on the repository containing this path instead of the one around
+    /// the current directory (also read from MERGES_REPO)
+    #[arg(long, global = true, value_name = "PATH")]
+    repo: Option<std::path::PathBuf>,
+
     #[command(subcommand)]
     command: Commands,
 }
 
 #[derive(Subcommand)]
 enum Commands {
+    /// Guided first run: check git, gh, and credentials, create .merges.json,
+    /// test API access, and register the MCP server with an LLM client
+    Setup,
+
     /// Initialise merges for the current repository
     Init {
-        /// Base branch PRs will target (default: main)
+        /// Base branch PRs will target (default: the remote's default branch, else main)
         #[arg(short, long)]
         base: Option<String>,
 
@@ -42,11 +59,136 @@ enum Commands {
         /// (e.g. --commit-prefix JCLARK-97246 for repos with strict hook formats)
         #[arg(long, value_name = "PREFIX")]
         commit_prefix: Option<String>,
+
+        /// Forge hosting the remote, for self-hosted instances on custom domains
+        /// (default: detected from the `origin` URL)
+        #[arg(long, value_name = "FORGE")]
+        forge: Option<state::ForgeKind>,
+
+        /// Keep matching files out of every split (path, directory, or glob;
+        /// patterns without '/' match at any depth). Repeatable.
+        #[arg(long, value_name = "PATTERN")]
+        exclude: Vec<String>,
+
+        /// Label chunk PRs and request reviewers by the files they change, as
+        /// 'migrations/** -> label:db, reviewer:@acme/db-team'. Repeatable.
+        #[arg(long, value_name = "RULE")]
+        route: Vec<String>,
+
+        /// Leave the `[n/total]` stack position out of chunk PR titles
+        #[arg(long)]
+        no_title_position: bool,
+
+        /// Shorten chunk commit subjects to at most N characters (the ticket
+        /// prefix and chunk number are kept; the chunk name is cut)
+        #[arg(long, value_name = "N")]
+        max_subject_length: Option<usize>,
+
+        /// Shorten chunk PR titles to at most N characters (the ticket prefix
+        /// and stack position are kept; the chunk name is cut)
+        #[arg(long, value_name = "N")]
+        max_title_length: Option<usize>,
+
+        /// Directory level `split --auto` groups on by default (1 = top-level
+        /// directories); `split --depth` overrides it
+        #[arg(long, value_name = "N")]
+        auto_depth: Option<usize>,
+
+        /// Where `split --auto` puts files at the repository root instead of a
+        /// `root` chunk: last (the last chunk), related (the chunk most often
+        /// committed with each), or chunk:NAME
+        #[arg(long, value_name = "PLACEMENT")]
+        root_files: Option<split::RootFiles>,
+
+        /// Don't keep a comment on each chunk PR linking the rest of the stack
+        #[arg(long)]
+        no_stack_comments: bool,
+
+        /// Create chunk branches as <NAMESPACE>/<source>/<n>-<name> so they
+        /// are easy to filter and cannot clash with other branches
+        #[arg(long, value_name = "NAMESPACE")]
+        branch_namespace: Option<String>,
+
+        /// Always stash uncommitted changes around commands that check out
+        /// chunk branches (split, add, move, push, sync) in classic mode
+        #[arg(long)]
+        autostash: bool,
+
+        /// Build chunk branches by replaying the source commits that touch
+        /// each chunk's files instead of squashing each chunk into one commit
+        #[arg(long)]
+        preserve_history: bool,
+
+        /// Squash each chunk branch into one commit before every push, as if
+        /// `merges push --squash` were always passed
+        #[arg(long)]
+        squash_on_push: bool,
+
+        /// Bring chunk branches up to date by merging the base branch into
+        /// them instead of rebasing, and push without force, for repos that
+        /// forbid rewriting published branches
+        #[arg(long)]
+        sync_merge: bool,
+
+        /// Post the review summary (chunks, rationale, files, review order)
+        /// in the source PR comment and the tracking issue on every push
+        #[arg(long)]
+        post_summary: bool,
+
+        /// Add every chunk PR to this GitHub Project (v2), as OWNER/NUMBER or
+        /// the project's URL
+        #[arg(long, value_name = "PROJECT")]
+        project: Option<String>,
+
+        /// Project Status option for chunk PRs at STAGE (open, approved, or
+        /// merged), as merged=Done (default: open=In Progress, merged=Done). Repeatable.
+        #[arg(long, value_name = "STAGE=OPTION")]
+        project_status: Vec<String>,
+
+        /// Give every chunk PR this milestone (by title)
+        #[arg(long, value_name = "TITLE")]
+        milestone: Option<String>,
+
+        /// Warn at push about chunks changing more than N lines
+        #[arg(long, value_name = "N")]
+        warn_lines: Option<u64>,
+
+        /// Refuse to push chunks changing more than N lines (push --allow-large
+        /// overrides)
+        #[arg(long, value_name = "N")]
+        max_lines: Option<u64>,
+
+        /// Warn at push about chunks changing more than N files
+        #[arg(long, value_name = "N")]
+        warn_files: Option<u64>,
+
+        /// Refuse to push chunks changing more than N files (push --allow-large
+        /// overrides)
+        #[arg(long, value_name = "N")]
+        max_files: Option<u64>,
+
+        /// PR topology for `merges push` (default: stacked)
+        #[arg(long, value_enum)]
+        strategy: Option<state::Strategy>,
+
+        /// Accept defaults without prompting (an existing .merges.json is updated,
+        /// keeping its chunks)
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Replace an existing .merges.json, discarding its chunks and PR tracking
+        #[arg(long)]
+        force: bool,
+
+        /// Never prompt; fail instead when an answer is needed (same as the
+        /// global --no-input)
+        #[arg(long)]
+        non_interactive: bool,
     },
 
     /// Assign changed files to named chunks and create branches.
     /// Pass --plan to run non-interactively (useful for scripting and MCP/LLM clients).
-    /// Pass --auto to group files by directory structure automatically.
+    /// Pass --auto to group files by directory structure (or --by=language) automatically.
     Split {
         /// JSON chunk plan: '[{"name":"models","files":["src/models/user.rs"]}]'
         #[arg(long, value_name = "JSON", conflicts_with = "auto")]
@@ -55,6 +197,46 @@ enum Commands {
         /// Automatically group files by top-level directory structure
         #[arg(long, conflicts_with = "plan")]
         auto: bool,
+
+        /// How --auto groups files: by directory (default), by language
+        /// (file extension), by history (files often committed together), or
+        /// two-level (the first two directories, in chunks like `backend-api`)
+        #[arg(long, value_enum, value_name = "GROUPING", requires = "auto")]
+        by: Option<split::Grouping>,
+
+        /// With --auto, keep files that import each other (Rust `use`/`mod`,
+        /// TypeScript/JavaScript `import`) in the same chunk and order chunks
+        /// so imported ones come first
+        #[arg(long, requires = "auto")]
+        imports: bool,
+
+        /// With --auto, group on the N-th directory level (1 = top-level
+        /// directories) instead of the one-or-two-level heuristic
+        #[arg(long, value_name = "N", requires = "auto")]
+        depth: Option<usize>,
+
+        /// With --auto, print the grouping without creating any chunks
+        #[arg(long, requires = "auto")]
+        preview: bool,
+
+        /// With --auto, rename, merge, and move files between the chunks
+        /// interactively before they are created
+        #[arg(long, requires = "auto", conflicts_with = "preview")]
+        edit: bool,
+
+        /// Add an exclude pattern (saved to .merges.json) before splitting. Repeatable.
+        #[arg(long, value_name = "PATTERN")]
+        exclude: Vec<String>,
+
+        /// Assign files with the chunks of the split preset in git config
+        /// `merges.preset.<NAME>` first; the rest go to --auto or the prompts
+        #[arg(long, value_name = "NAME", conflicts_with = "plan")]
+        preset: Option<String>,
+
+        /// Stash uncommitted changes while chunk branches are checked out and
+        /// re-apply them afterwards
+        #[arg(long)]
+        autostash: bool,
     },
 
     /// Push chunk branches and create/update GitHub PRs
@@ -66,20 +248,167 @@ enum Commands {
         /// Use independent PR strategy (all PRs target the base branch)
         #[arg(long, conflicts_with = "stacked")]
         independent: bool,
+
+        /// Enable GitHub auto-merge on each chunk PR so it lands once approvals
+        /// and required checks pass (default method: squash)
+        #[arg(long, value_name = "METHOD", num_args = 0..=1, require_equals = true, default_missing_value = "squash")]
+        auto_merge: Option<forge::MergeMethod>,
+
+        /// Create (and keep updated) a GitHub issue with a checklist of all chunk PRs
+        #[arg(long)]
+        tracking_issue: bool,
+
+        /// Comment on the source branch's own PR with an index of the chunk
+        /// PRs (kept updated by later pushes), and optionally turn it into a
+        /// draft or close it
+        #[arg(long, value_name = "ACTION", num_args = 0..=1, require_equals = true, default_missing_value = "comment")]
+        source_pr: Option<commands::push::SourcePrAction>,
+
+        /// Continue an interrupted push: skip chunks already pushed and reuse
+        /// PRs already open for chunk branches
+        #[arg(long)]
+        resume: bool,
+
+        /// Regenerate the title and body of PRs that already exist (stack
+        /// position, file list, stack table)
+        #[arg(long)]
+        update_prs: bool,
+
+        /// Stash uncommitted changes for the push and re-apply them afterwards
+        /// (classic mode checks out chunk branches in this working tree)
+        #[arg(long)]
+        autostash: bool,
+
+        /// Create and update PRs for chunks that have no changes left after
+        /// rebasing, instead of skipping them
+        #[arg(long, conflicts_with = "drop_empty")]
+        allow_empty: bool,
+
+        /// Drop chunks that have no changes left after rebasing from the stack
+        /// without asking
+        #[arg(long)]
+        drop_empty: bool,
+
+        /// Squash each chunk branch into one commit before pushing it (see
+        /// `merges squash`)
+        #[arg(long)]
+        squash: bool,
+
+        /// Push even when a chunk branch on origin has commits your local
+        /// branch lacks (e.g. fixes a teammate pushed), discarding them
+        #[arg(long)]
+        force: bool,
+
+        /// Show each chunk's PR, base, title and predicted CI workflows
+        /// without pushing anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Leave at least this long (e.g. 90s, 2m, 1h) between the CI runs the
+        /// push sets off, bottom of the stack first, so they do not all queue
+        /// for runners at once
+        #[arg(long, value_name = "INTERVAL", value_parser = commands::push::parse_duration)]
+        stagger: Option<std::time::Duration>,
+
+        /// Give chunks marked `skip_ci` in the split plan a `skip-checks: true`
+        /// trailer so CI does not run on their PRs
+        #[arg(long)]
+        skip_ci: bool,
+
+        /// Push chunks over the hard size limits set with `merges init
+        /// --max-lines`/`--max-files` anyway
+        #[arg(long)]
+        allow_large: bool,
     },
 
     /// Rebase all chunk branches onto the latest base branch
-    Sync,
+    Sync {
+        /// Stash uncommitted changes for the sync and re-apply them afterwards
+        /// (classic mode checks out chunk branches in this working tree)
+        #[arg(long)]
+        autostash: bool,
+
+        /// Drop chunks that have no changes left after rebasing from the stack
+        /// without asking
+        #[arg(long)]
+        drop_empty: bool,
+
+        /// Delete the local branches and worktrees of chunks whose PRs have
+        /// merged, as well as taking them off the stack
+        #[arg(long)]
+        delete_merged: bool,
+
+        /// Finish a sync that stopped halfway (cancelled, crashed, or killed):
+        /// rebase the chunks it had not reached onto the base it was using
+        #[arg(long, conflicts_with = "chunks")]
+        resume: bool,
+
+        /// Merge the base branch (stacked: the chunk below) into each chunk
+        /// branch instead of rebasing it, so published history is never
+        /// rewritten (always on after `merges init --sync-merge`)
+        #[arg(long)]
+        merge: bool,
+
+        /// Only rebase this chunk (repeatable; default: every chunk)
+        #[arg(long = "chunk", value_name = "NAME")]
+        chunks: Vec<String>,
+    },
 
     /// Show chunk and PR status table
-    Status,
+    Status {
+        /// Show chunk descriptions and list the names of failing checks for each chunk
+        #[arg(short, long)]
+        verbose: bool,
+
+        /// Show only chunks in a state: state=open (not merged yet),
+        /// state=merged, or state=conflict (would conflict with the base branch)
+        #[arg(long, value_name = "state=STATE")]
+        filter: Option<commands::status::StateFilter>,
+
+        /// Order chunks by how far behind the base they are, their file count,
+        /// or their age instead of stack order
+        #[arg(long, value_enum)]
+        sort: Option<commands::status::SortKey>,
+
+        /// Show only this chunk
+        #[arg(long, value_name = "NAME")]
+        chunk: Option<String>,
+
+        /// One line per chunk instead of the table, for narrow terminals
+        #[arg(long)]
+        compact: bool,
+    },
+
+    /// List the unresolved review comments on every chunk PR, grouped by
+    /// chunk and file (GitHub only)
+    Reviews,
+
+    /// List past operations (split, add, move, squash, absorb, fix, backport, push, sync, drop, clean) with the
+    /// chunks they touched and the branch SHAs they left behind
+    History {
+        /// Number of most recent operations to show (0 = all)
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+    },
+
+    /// Draw the stack: the branch each chunk builds on, its PR, and which
+    /// chunks import from which
+    Graph {
+        /// ascii for the terminal, mermaid for Markdown, dot for Graphviz
+        #[arg(long, value_enum, default_value_t = commands::graph::GraphFormat::Ascii)]
+        format: commands::graph::GraphFormat,
+    },
 
     /// Start the MCP stdio server (for LLM clients like Claude or GitHub Copilot)
-    Mcp,
+    Mcp {
+        #[command(subcommand)]
+        action: Option<McpAction>,
+    },
 
     /// Delete local chunk branches (optionally only those whose PRs are merged)
     Clean {
-        /// Only delete branches for chunks whose GitHub PRs are merged/closed
+        /// Only delete branches for chunks whose GitHub PRs were merged into the
+        /// base branch with all required status checks passing
         #[arg(long)]
         merged: bool,
 
@@ -93,14 +422,37 @@ enum Commands {
         /// Name of the chunk to add files to
         chunk: String,
 
-        /// Files to add (relative paths)
+        /// Files to add (relative paths, directories, or quoted glob patterns
+        /// such as 'src/api/*.rs')
         #[arg(required = true)]
         files: Vec<String>,
+
+        /// Stash uncommitted changes while chunk branches are checked out and
+        /// re-apply them afterwards
+        #[arg(long)]
+        autostash: bool,
+    },
+
+    /// Assign changed files that no chunk contains yet to existing chunks
+    Assign {
+        /// Files to assign (paths, directories, or quoted globs among the
+        /// unassigned files; default: all of them). Requires --to.
+        #[arg(requires = "to")]
+        files: Vec<String>,
+
+        /// Assign to this chunk without prompting
+        #[arg(long, value_name = "CHUNK")]
+        to: Option<String>,
+
+        /// Stash uncommitted changes while chunk branches are checked out and
+        /// re-apply them afterwards
+        #[arg(long)]
+        autostash: bool,
     },
 
     /// Move a file from one chunk to another
     Move {
-        /// File to move (relative path)
+        /// File to move (relative path, directory, or quoted glob pattern)
         file: String,
 
         /// Source chunk name
@@ -110,15 +462,263 @@ enum Commands {
         /// Destination chunk name
         #[arg(long = "to")]
         to: String,
+
+        /// Stash uncommitted changes while chunk branches are checked out and
+        /// re-apply them afterwards
+        #[arg(long)]
+        autostash: bool,
+    },
+
+    /// Squash chunk branches into one commit each, folding in the commits
+    /// added since the split (chunks stacked on them are rebased)
+    Squash {
+        /// Chunks to squash (default: every chunk with more than one commit)
+        chunks: Vec<String>,
+
+        /// Stash uncommitted changes while chunk branches are checked out and
+        /// re-apply them afterwards
+        #[arg(long)]
+        autostash: bool,
+    },
+
+    /// Run a command once per chunk, in its worktree or with its branch checked
+    /// out. The command sees MERGES_CHUNK_NAME, MERGES_CHUNK_BRANCH,
+    /// MERGES_CHUNK_FILES (one per line), MERGES_BASE_BRANCH, and MERGES_WORKTREE
+    Exec {
+        /// Only run for this chunk (repeatable; default: every chunk)
+        #[arg(long = "chunk", value_name = "NAME")]
+        chunks: Vec<String>,
+
+        /// Stash uncommitted changes while chunk branches are checked out and
+        /// re-apply them afterwards
+        #[arg(long)]
+        autostash: bool,
+
+        /// The command and its arguments, after `--`
+        #[arg(last = true, required = true)]
+        command: Vec<String>,
+    },
+
+    /// Open a chunk in your editor (git config merges.editor, else $VISUAL or
+    /// $EDITOR): its worktree, or its files
+    Edit {
+        /// Name of the chunk
+        chunk: String,
+
+        /// Open the chunk's files rather than its worktree (always the case
+        /// without worktrees)
+        #[arg(long)]
+        files: bool,
+    },
+
+    /// Print the worktree path of a chunk, for shell aliases like
+    /// `cd "$(merges path api)"` (worktree mode only)
+    Path {
+        /// Name of the chunk
+        chunk: String,
+    },
+
+    /// Start a subshell in a chunk's worktree, with MERGES_CHUNK_NAME,
+    /// MERGES_CHUNK_BRANCH, MERGES_CHUNK_FILES, MERGES_BASE_BRANCH, and
+    /// MERGES_WORKTREE set (worktree mode only)
+    Shell {
+        /// Name of the chunk
+        chunk: String,
+    },
+
+    /// Build each chunk on its own with a command (e.g. `cargo check`) and
+    /// report the chunks it fails for
+    Verify {
+        /// Only build this chunk (repeatable; default: every chunk)
+        #[arg(long = "chunk", value_name = "NAME")]
+        chunks: Vec<String>,
+
+        /// Read the compiler errors of failing chunks for modules and files
+        /// another chunk owns, and print the `merges move` for each
+        #[arg(long)]
+        suggest_moves: bool,
+
+        /// Make the suggested moves (implies --suggest-moves)
+        #[arg(long)]
+        fix: bool,
+
+        /// Stash uncommitted changes while chunk branches are checked out and
+        /// re-apply them afterwards
+        #[arg(long)]
+        autostash: bool,
+
+        /// The build command and its arguments, after `--`
+        #[arg(last = true, required = true)]
+        command: Vec<String>,
+    },
+
+    /// Commit uncommitted fixes on the source branch and onto the chunk that
+    /// owns each changed file
+    Absorb {
+        /// Message for the commits (default: one naming the files)
+        #[arg(short, long)]
+        message: Option<String>,
+
+        /// Stash changes to files no chunk owns while chunk branches are
+        /// restacked, and re-apply them afterwards
+        #[arg(long)]
+        autostash: bool,
+    },
+
+    /// Fold review fixes committed on the source branch into the commits of
+    /// the chunks owning the changed files, then push
+    Fix {
+        /// Fix only this chunk (the one whose PR was reviewed); changed files
+        /// no chunk owns join it
+        #[arg(long, value_name = "NAME", conflicts_with = "auto", required_unless_present = "auto")]
+        chunk: Option<String>,
+
+        /// Fix every chunk whose files changed on the source branch
+        #[arg(long)]
+        auto: bool,
+
+        /// Amend the chunk branches without pushing them
+        #[arg(long)]
+        no_push: bool,
+
+        /// Stash uncommitted changes while chunk branches are restacked
+        #[arg(long)]
+        autostash: bool,
+    },
+
+    /// Cherry-pick commits made on a chunk branch (e.g. review fixes pushed to
+    /// its PR) that the source branch lacks back onto the source branch
+    Backport {
+        /// Chunk whose commits to bring back
+        chunk: String,
+
+        /// Stash uncommitted changes while the source branch is checked out
+        /// and re-apply them afterwards
+        #[arg(long)]
+        autostash: bool,
+    },
+
+    /// Compare chunk branches with the source branch (e.g. after it was rebased
+    /// or force-pushed); --rebuild reconstructs them from it
+    Refresh {
+        /// Rebuild every chunk branch from the current source branch, replacing
+        /// commits added to chunk branches since the split
+        #[arg(long)]
+        rebuild: bool,
+
+        /// Stash uncommitted changes while chunk branches are checked out and
+        /// re-apply them afterwards
+        #[arg(long)]
+        autostash: bool,
     },
 
-    /// Validate state consistency (branch existence, worktrees, gitignore)
+    /// Validate state consistency (branch existence, worktrees, gitignore) and
+    /// the environment (git version, rerere, origin, forge credentials)
     Doctor {
         /// Attempt to repair detected issues
         #[arg(long)]
         repair: bool,
     },
 
+    /// Check that the chunks add up to the source branch: every file it
+    /// changes is changed by exactly one chunk, to the same content, and no
+    /// chunk changes anything else. Exits non-zero when they do not.
+    Diffcheck,
+
+    /// Check the stack for CI without changing anything: the state checks
+    /// `merges doctor` makes plus the diffcheck invariant. Prints a JSON
+    /// report and exits non-zero when the stack is inconsistent.
+    Audit {
+        /// State file to audit (default: .merges.json, else the shared state
+        /// in refs/merges/state)
+        #[arg(long, value_name = "FILE")]
+        state: Option<std::path::PathBuf>,
+    },
+
+    /// Rebuild .merges.json from chunk branches on origin and their open PRs
+    /// (e.g. to take over a teammate's stack). Run `git fetch` first.
+    Recover {
+        /// Source branch the chunks were split from (default: current branch)
+        #[arg(long, value_name = "BRANCH")]
+        source: Option<String>,
+
+        /// Base branch PRs target (default: the remote's default branch, else main)
+        #[arg(short, long)]
+        base: Option<String>,
+
+        /// Look for chunk branches under this namespace, as set with
+        /// `merges init --branch-namespace`
+        #[arg(long, value_name = "NAMESPACE")]
+        branch_namespace: Option<String>,
+
+        /// Forge hosting the remote (default: detected from the `origin` URL)
+        #[arg(long, value_name = "FORGE")]
+        forge: Option<state::ForgeKind>,
+
+        /// Replace an existing .merges.json
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Edit the description of a chunk's PR, keeping the stack table merges
+    /// maintains in it
+    PrBody {
+        #[command(subcommand)]
+        action: PrBodyAction,
+    },
+
+    /// Keep a chunk branch-only (e.g. generated code that should wait for the
+    /// rest to merge): push still pushes its branch but opens or updates no PR
+    Hold {
+        /// Name of the chunk to hold
+        chunk: String,
+    },
+
+    /// Release a held chunk, so the next push opens or updates its PR
+    Unhold {
+        /// Name of the chunk to release
+        chunk: String,
+    },
+
+    /// Share recorded conflict resolutions (git rerere) with teammates, so a
+    /// conflict is resolved once for the whole stack
+    Rerere {
+        #[command(subcommand)]
+        action: RerereAction,
+    },
+
+    /// Back up, restore, or share .merges.json (e.g. to hand a stack to a
+    /// teammate or work on it from another machine)
+    State {
+        #[command(subcommand)]
+        action: StateAction,
+    },
+
+    /// Export the stack as a numbered patch series (one patch per chunk)
+    Export {
+        /// Directory to write `git format-patch` files into
+        #[arg(long, value_name = "DIR")]
+        format_patch: std::path::PathBuf,
+
+        /// Also write a cover letter (`[PATCH 0/N]`) summarising the stack
+        #[arg(long)]
+        cover_letter: bool,
+    },
+
+    /// Show a guide (stacking, worktrees, mcp) or the help of a command
+    Help {
+        /// Guide or command name (default: list everything)
+        topic: Option<String>,
+    },
+
+    /// Generate man pages
+    Man {
+        /// Write one page per command into this directory instead of printing
+        /// the `merges` page to stdout
+        #[arg(long, value_name = "DIR")]
+        out_dir: Option<std::path::PathBuf>,
+    },
+
     /// Generate shell completion scripts
     Completions {
         /// Shell to generate completions for
@@ -127,29 +727,310 @@ enum Commands {
     },
 }
 
+#[derive(Subcommand)]
+enum PrBodyAction {
+    /// Open the PR's current description in $EDITOR and push the result back.
+    /// The stack table is kept out of the editor and refreshed on save, and
+    /// later `merges push --update-prs` runs keep the new wording
+    Edit {
+        /// Name of the chunk whose PR to edit
+        chunk: String,
+
+        /// Read the new description from stdin instead of opening an editor
+        #[arg(long)]
+        stdin: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum RerereAction {
+    /// Write the recorded resolutions to a tarball
+    Export {
+        /// Tarball to write (e.g. rerere.tar.gz)
+        file: std::path::PathBuf,
+    },
+
+    /// Add the resolutions from a tarball written by `merges rerere export`
+    /// (ones already recorded here are kept) and enable rerere
+    Import {
+        /// Tarball written by `merges rerere export`
+        file: std::path::PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum McpAction {
+    /// Register `merges mcp` with an MCP client by writing the server entry
+    /// into its configuration file
+    Install {
+        /// Client to configure
+        #[arg(long, value_enum)]
+        client: mcp::install::Client,
+
+        /// Print the configuration instead of writing it
+        #[arg(long)]
+        print: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum StateAction {
+    /// Print the current state as JSON (`merges state export > backup.json`)
+    Export,
+
+    /// Replace the state with an exported file, after checking it against this
+    /// repository. Chunk branches only on origin get local branches.
+    Import {
+        /// State file written by `merges state export`
+        file: std::path::PathBuf,
+
+        /// Replace an existing .merges.json
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Publish the state to origin under refs/merges/state
+    Push {
+        /// Overwrite state another machine pushed since the last pull
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Replace the state with the one published under refs/merges/state
+    Pull {
+        /// Discard local state changes that were never pushed
+        #[arg(long)]
+        force: bool,
+    },
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
 
+    if cli.no_input {
+        prompt::disable();
+    }
+    if let Some(repo) = &cli.repo {
+        git::set_repo(repo);
+    }
+    if cli.json {
+        output::set_json(true);
+        let result = json_result(cli.command).await.unwrap_or_else(|e| output::failure(&e));
+        output::emit(&result);
+        if result["ok"] != true {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
     match cli.command {
-        Commands::Init { base, worktrees, commit_prefix } => commands::init::run(base, worktrees, commit_prefix)?,
-        Commands::Split { plan, auto } => commands::split::run(plan, auto)?,
-        Commands::Push { stacked, independent } => commands::push::run(stacked, independent).await?,
-        Commands::Sync => commands::sync::run()?,
-        Commands::Status => commands::status::run().await?,
-        Commands::Mcp => mcp::run().await?,
+        Commands::Init {
+            base,
+            worktrees,
+            commit_prefix,
+            forge,
+            exclude,
+            route,
+            no_title_position,
+            max_subject_length,
+            max_title_length,
+            auto_depth,
+            root_files,
+            no_stack_comments,
+            branch_namespace,
+            autostash,
+            preserve_history,
+            squash_on_push,
+            sync_merge,
+            post_summary,
+            project,
+            project_status,
+            milestone,
+            warn_lines,
+            max_lines,
+            warn_files,
+            max_files,
+            strategy,
+            yes,
+            force,
+            non_interactive,
+        } => commands::init::run(commands::init::InitOptions {
+            base_branch: base,
+            strategy,
+            use_worktrees: worktrees,
+            commit_prefix,
+            forge_kind: forge,
+            exclude,
+            routes: route,
+            no_title_position,
+            max_subject_length,
+            max_title_length,
+            auto_depth,
+            root_files,
+            no_stack_comments,
+            branch_namespace,
+            autostash,
+            preserve_history,
+            squash_on_push,
+            sync_merge,
+            post_summary,
+            project,
+            project_statuses: project_status,
+            milestone,
+            size_limits: size::SizeLimits { warn_lines, max_lines, warn_files, max_files },
+            yes,
+            force,
+            non_interactive,
+        })?,
+        Commands::Setup => {
+            let report = commands::setup::run().await?;
+            if !report.all_ok() {
+                anyhow::bail!("{} setup step(s) failed", report.failed());
+            }
+        }
+        Commands::Split { plan, auto, by, imports, depth, preview, edit, exclude, preset, autostash } => {
+            commands::split::run(commands::split::SplitOptions {
+                plan,
+                auto,
+                by: by.unwrap_or_default(),
+                imports,
+                depth,
+                preview,
+                edit,
+                exclude,
+                preset,
+                autostash,
+            })?
+        }
+        Commands::Push {
+            stacked,
+            independent,
+            auto_merge,
+            tracking_issue,
+            source_pr,
+            resume,
+            update_prs,
+            autostash,
+            allow_empty,
+            drop_empty,
+            squash,
+            force,
+            dry_run,
+            stagger,
+            skip_ci,
+            allow_large,
+        } => {
+            commands::push::run(
+                commands::push::PushOptions {
+                    stacked,
+                    independent,
+                    auto_merge,
+                    tracking_issue,
+                    source_pr,
+                    resume,
+                    update_prs,
+                    autostash,
+                    allow_empty,
+                    drop_empty,
+                    squash,
+                    force,
+                    dry_run,
+                    stagger,
+                    skip_ci,
+                    allow_large,
+                },
+                &cancel::CancelToken::default(),
+            )
+            .await?
+        }
+        Commands::Sync { autostash, drop_empty, delete_merged, resume, merge, chunks } => {
+            let cancel = cancel::CancelToken::default();
+            let results = commands::sync::run(autostash, drop_empty, delete_merged, resume, merge, &chunks, &cancel)?;
+            commands::sync::close_tracking_issue(&results).await?;
+            commands::sync::check(&results)?
+        }
+        Commands::Status { verbose, filter, sort, chunk, compact } => {
+            commands::status::run(commands::status::StatusOptions { verbose, filter, sort, chunk, compact }).await?
+        }
+        Commands::Reviews => commands::reviews::run().await?,
+        Commands::Mcp { action: None } => mcp::run().await?,
+        Commands::Mcp { action: Some(McpAction::Install { client, print }) } => {
+            let root = git::repo_root()?;
+            if print {
+                println!("{}", serde_json::to_string_pretty(&client.snippet(&root)?)?);
+                eprintln!("Add this to {}", client.config_path(&root)?.display());
+            } else {
+                let path = mcp::install::install(client, &root)?;
+                println!("✓ Registered merges with {} in {}", client, path.display());
+            }
+        }
         Commands::Clean { merged, yes } => commands::clean::run(merged, yes).await?,
-        Commands::Add { chunk, files } => {
+        Commands::Add { chunk, files, autostash } => {
             let root = git::repo_root()?;
-            commands::add::run(&root, &chunk, &files)?;
+            commands::add::run(&root, &chunk, &files, autostash)?;
         }
-        Commands::Move { file, from, to } => {
+        Commands::Assign { files, to, autostash } => {
             let root = git::repo_root()?;
-            commands::r#move::run(&root, &file, &from, &to)?;
+            commands::assign::run(&root, to.as_deref(), &files, autostash)?;
         }
+        Commands::Move { file, from, to, autostash } => {
+            let root = git::repo_root()?;
+            commands::r#move::run(&root, &file, &from, &to, autostash)?;
+        }
+        Commands::Squash { chunks, autostash } => {
+            let root = git::repo_root()?;
+            commands::squash::run(&root, &chunks, autostash)?;
+        }
+        Commands::Exec { chunks, autostash, command } => {
+            let root = git::repo_root()?;
+            let failed = commands::exec::run(&root, &command, &chunks, autostash)?;
+            if !failed.is_empty() {
+                anyhow::bail!("`{}` failed for {} chunk(s): {}", command.join(" "), failed.len(), failed.join(", "));
+            }
+        }
+        Commands::Edit { chunk, files } => {
+            commands::edit::run(&git::repo_root()?, &chunk, files)?;
+        }
+        Commands::Path { chunk } => {
+            println!("{}", commands::shell::path(&git::repo_root()?, &chunk)?.display());
+        }
+        Commands::Shell { chunk } => {
+            let status = commands::shell::run(&git::repo_root()?, &chunk)?;
+            // The shell's last command decides, as when leaving any subshell.
+            if !status.success() {
+                std::process::exit(status.code().unwrap_or(1));
+            }
+        }
+        Commands::Verify { chunks, suggest_moves, fix, autostash, command } => {
+            let root = git::repo_root()?;
+            let opts = commands::verify::VerifyOptions { suggest_moves, fix, autostash };
+            let report = commands::verify::run(&root, &command, &chunks, opts)?;
+            if !report.failed.is_empty() {
+                let failed = report.failed;
+                anyhow::bail!("`{}` failed for {} chunk(s): {}", command.join(" "), failed.len(), failed.join(", "));
+            }
+        }
+        Commands::Absorb { message, autostash } => {
+            let root = git::repo_root()?;
+            commands::absorb::run(&root, message.as_deref(), autostash)?;
+        }
+        Commands::Fix { chunk, auto: _, no_push, autostash } => {
+            let root = git::repo_root()?;
+            let fixed = commands::fix::run(&root, chunk.as_deref(), autostash)?;
+            if !fixed.is_empty() && !no_push {
+                let opts = commands::push::PushOptions { autostash, ..Default::default() };
+                commands::push::run(opts, &cancel::CancelToken::default()).await?;
+            }
+        }
+        Commands::Backport { chunk, autostash } => {
+            let root = git::repo_root()?;
+            commands::backport::run(&root, &chunk, autostash)?;
+        }
+        Commands::Refresh { rebuild, autostash } => commands::refresh::run(rebuild, autostash)?,
         Commands::Doctor { repair } => {
             let root = git::repo_root()?;
-            let report = doctor::run(&root, repair)?;
+            let report = doctor::run_with_environment(&root, repair).await?;
             if report.all_ok() {
                 println!("✓ All checks passed — state is healthy.");
             } else {
@@ -162,6 +1043,95 @@ async fn main() -> Result<()> {
                 anyhow::bail!("{} issue(s) found", report.issues.len());
             }
         }
+        Commands::Diffcheck => commands::diffcheck::run()?,
+        Commands::Audit { state } => {
+            let result = git::repo_root()
+                .and_then(|root| audit_result(&root, state.as_deref()))
+                .unwrap_or_else(|e| output::failure(&e));
+            output::emit(&result);
+            if result["ok"] != true {
+                std::process::exit(1);
+            }
+        }
+        Commands::History { limit } => {
+            let root = git::repo_root()?;
+            commands::history::run(&root, limit)?;
+        }
+        Commands::Graph { format } => {
+            let root = git::repo_root()?;
+            commands::graph::run(&root, format)?;
+        }
+        Commands::Recover { source, base, branch_namespace, forge, force } => {
+            let root = git::repo_root()?;
+            commands::recover::run(&root, source, base, branch_namespace, forge, force).await?;
+        }
+        Commands::State { action } => {
+            let root = git::repo_root()?;
+            match action {
+                StateAction::Export => println!("{}", commands::state::export(&root)?),
+                StateAction::Import { file, force } => {
+                    commands::state::import(&root, &file, force)?;
+                }
+                StateAction::Push { force } => {
+                    commands::state::push(&root, force)?;
+                }
+                StateAction::Pull { force } => {
+                    commands::state::pull(&root, force)?;
+                }
+            }
+        }
+        Commands::PrBody { action: PrBodyAction::Edit { chunk, stdin } } => {
+            commands::pr_body::edit(&chunk, stdin).await?;
+        }
+        Commands::Hold { chunk } => {
+            commands::hold::run(&git::repo_root()?, &chunk, true)?;
+        }
+        Commands::Unhold { chunk } => {
+            commands::hold::run(&git::repo_root()?, &chunk, false)?;
+        }
+        Commands::Rerere { action } => {
+            let root = git::repo_root()?;
+            match action {
+                RerereAction::Export { file } => {
+                    let count = commands::rerere::export(&root, &file)?;
+                    println!("✓ Exported {} conflict resolution(s) to {}", count, file.display());
+                }
+                RerereAction::Import { file } => {
+                    let count = commands::rerere::import(&root, &file)?;
+                    println!("✓ Imported {} new conflict resolution(s) from {}", count, file.display());
+                }
+            }
+        }
+        Commands::Export { format_patch, cover_letter } => {
+            let root = git::repo_root()?;
+            commands::export::run(&root, &format_patch, cover_letter)?;
+        }
+        Commands::Help { topic: None } => {
+            Cli::command().print_long_help()?;
+            println!("\n{}", help::topic_list());
+        }
+        Commands::Help { topic: Some(name) } => {
+            if let Some(topic) = help::topic(&name) {
+                print!("{}", help::render(topic.body));
+            } else if Cli::command().find_subcommand(&name).is_some() {
+                // Same output (and exit) as `merges <command> --help`.
+                let _ = Cli::parse_from(["merges", name.as_str(), "--help"]);
+            } else {
+                anyhow::bail!(
+                    "No help topic or command named '{}'. Guides: {}",
+                    name,
+                    help::TOPICS.iter().map(|t| t.name).collect::<Vec<_>>().join(", ")
+                );
+            }
+        }
+        Commands::Man { out_dir: Some(dir) } => {
+            std::fs::create_dir_all(&dir)?;
+            clap_mangen::generate_to(Cli::command(), &dir)?;
+            println!("✓ Wrote man pages to {}", dir.display());
+        }
+        Commands::Man { out_dir: None } => {
+            clap_mangen::Man::new(Cli::command()).render(&mut std::io::stdout())?;
+        }
         Commands::Completions { shell } => {
             generate(shell, &mut Cli::command(), "merges", &mut std::io::stdout());
         }
@@ -169,3 +1139,150 @@ async fn main() -> Result<()> {
 
     Ok(())
 }
+
+/// Run a command in `--json` mode and build its result object. Commands keep
+/// their own human output off stdout (see `output`), so this only has to
+/// describe the outcome, mostly from the state the command left behind.
+async fn json_result(command: Commands) -> Result<serde_json::Value> {
+    use serde_json::json;
+
+    let root = git::repo_root()?;
+    match command {
+        Commands::Push {
+            stacked,
+            independent,
+            auto_merge,
+            tracking_issue,
+            source_pr,
+            resume,
+            update_prs,
+            autostash,
+            allow_empty,
+            drop_empty,
+            squash,
+            force,
+            dry_run,
+            stagger,
+            skip_ci,
+            allow_large,
+        } => {
+            commands::push::run(
+                commands::push::PushOptions {
+                    stacked,
+                    independent,
+                    auto_merge,
+                    tracking_issue,
+                    source_pr,
+                    resume,
+                    update_prs,
+                    autostash,
+                    allow_empty,
+                    drop_empty,
+                    squash,
+                    force,
+                    dry_run,
+                    stagger,
+                    skip_ci,
+                    allow_large,
+                },
+                &cancel::CancelToken::default(),
+            )
+            .await?;
+            let state = state::MergesState::load(&root)?;
+            Ok(output::success("push", json!({
+                "strategy": state.strategy,
+                "tracking_issue": state.tracking_issue,
+                "source_pr": state.source_pr.map(|p| p.number),
+                "chunks": output::chunks(&root, &state)
+            })))
+        }
+        Commands::Sync { autostash, drop_empty, delete_merged, resume, merge, chunks } => {
+            let cancel = cancel::CancelToken::default();
+            let results = commands::sync::run(autostash, drop_empty, delete_merged, resume, merge, &chunks, &cancel)?;
+            commands::sync::close_tracking_issue(&results).await?;
+            commands::sync::check(&results)?;
+            let state = state::MergesState::load(&root)?;
+            Ok(output::success("sync", json!({
+                "base_branch": state.base_branch,
+                "results": results,
+                "chunks": output::chunks(&root, &state)
+            })))
+        }
+        Commands::Clean { merged, yes } => {
+            let before = state::MergesState::load(&root)?.chunks;
+            commands::clean::run(merged, yes).await?;
+            let after = state::MergesState::load(&root)?.chunks;
+            let deleted: Vec<&str> = before
+                .iter()
+                .filter(|c| !after.iter().any(|a| a.branch == c.branch))
+                .map(|c| c.branch.as_str())
+                .collect();
+            let kept: Vec<&str> = after.iter().map(|c| c.branch.as_str()).collect();
+            Ok(output::success("clean", json!({ "deleted": deleted, "kept": kept })))
+        }
+        Commands::Doctor { repair } => {
+            let report = doctor::run_with_environment(&root, repair).await?;
+            Ok(json!({
+                "ok": report.all_ok(),
+                "operation": "doctor",
+                "repaired": repair,
+                "issues": report.issues
+            }))
+        }
+        Commands::Diffcheck => {
+            let state = state::MergesState::load(&root)?;
+            let report = commands::diffcheck::check(&root, &state)?;
+            Ok(json!({
+                "ok": report.is_consistent(),
+                "operation": "diffcheck",
+                "missing": report.missing,
+                "extra": report.extra,
+                "overlapping": report.overlapping,
+                "mismatched": report.mismatched,
+                "excluded": report.excluded,
+                "unsupported": report.unsupported
+            }))
+        }
+        Commands::Audit { state } => audit_result(&root, state.as_deref()),
+        Commands::Add { chunk, files, autostash } => {
+            commands::add::run(&root, &chunk, &files, autostash)?;
+            let state = state::MergesState::load(&root)?;
+            let updated = state.chunks.iter().find(|c| c.name == chunk);
+            Ok(output::success("add", json!({
+                "chunk": chunk,
+                "branch": updated.map(|c| &c.branch),
+                "files": updated.map(|c| &c.files)
+            })))
+        }
+        Commands::Move { file, from, to, autostash } => {
+            commands::r#move::run(&root, &file, &from, &to, autostash)?;
+            Ok(output::success("move", json!({ "file": file, "from": from, "to": to })))
+        }
+        Commands::Status { .. } => {
+            let state = state::MergesState::load(&root)?;
+            git::Prefetch::start(&root, &state.base_branch).finish(commands::status::PREFETCH_TIMEOUT).await;
+            Ok(output::success("status", output::status(&root, &state)))
+        }
+        Commands::History { limit } => {
+            let entries = journal::load(&root)?;
+            let limit = if limit == 0 { entries.len() } else { limit };
+            let recent: Vec<_> = entries.iter().rev().take(limit).collect();
+            Ok(output::success("history", json!({ "operations": recent })))
+        }
+        _ => anyhow::bail!(
+            "--json is supported by push, sync, clean, doctor, diffcheck, audit, add, move, status, and history"
+        ),
+    }
+}
+
+/// The report `merges audit` prints, with or without `--json`.
+fn audit_result(root: &std::path::Path, state: Option<&std::path::Path>) -> Result<serde_json::Value> {
+    let audit = commands::audit::run(root, state)?;
+    Ok(serde_json::json!({
+        "ok": audit.ok(),
+        "operation": "audit",
+        "state": audit.state,
+        "issues": audit.issues,
+        "diffcheck": audit.diffcheck
+    }))
+}
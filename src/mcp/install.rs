@@ -0,0 +1,197 @@
+//! Registering `merges mcp` with an LLM client by writing the server entry
+//! into the client's JSON configuration.
+
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+/// MCP clients whose configuration merges can write.
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum Client {
+    /// Claude Desktop (`claude_desktop_config.json`, per user)
+    Claude,
+    /// GitHub Copilot in VS Code (`.vscode/mcp.json`, per workspace)
+    Vscode,
+    /// Cursor (`.cursor/mcp.json`, per project)
+    Cursor,
+}
+
+impl std::fmt::Display for Client {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Client::Claude => write!(f, "Claude Desktop"),
+            Client::Vscode => write!(f, "GitHub Copilot (VS Code)"),
+            Client::Cursor => write!(f, "Cursor"),
+        }
+    }
+}
+
+impl Client {
+    pub const ALL: [Client; 3] = [Client::Claude, Client::Vscode, Client::Cursor];
+
+    /// The configuration file the client reads its servers from, for the
+    /// repository at `root`.
+    pub fn config_path(self, root: &Path) -> Result<PathBuf> {
+        match self {
+            Client::Claude => Ok(claude_config_dir()?.join("claude_desktop_config.json")),
+            Client::Vscode => Ok(root.join(".vscode").join("mcp.json")),
+            Client::Cursor => Ok(root.join(".cursor").join("mcp.json")),
+        }
+    }
+
+    /// Key of the object the client lists its servers under.
+    fn servers_key(self) -> &'static str {
+        match self {
+            Client::Claude | Client::Cursor => "mcpServers",
+            Client::Vscode => "servers",
+        }
+    }
+
+    /// Server entry running `binary` as the MCP server for `root`. A
+    /// per-user client is pinned to the repository with `--repo`; per-project
+    /// clients point it at whichever workspace is open.
+    pub fn server_entry(self, binary: &Path, root: &Path) -> Value {
+        let command = binary.display().to_string();
+        match self {
+            Client::Claude => json!({
+                "command": command,
+                "args": ["--repo", root.display().to_string(), "mcp"]
+            }),
+            Client::Vscode => json!({
+                "type": "stdio",
+                "command": command,
+                "args": ["--repo", "${workspaceFolder}", "mcp"]
+            }),
+            Client::Cursor => json!({
+                "command": command,
+                "args": ["--repo", "${workspaceFolder}", "mcp"]
+            }),
+        }
+    }
+
+    /// The smallest configuration file holding the `merges` server, for
+    /// pasting into the client's own file by hand.
+    pub fn snippet(self, root: &Path) -> Result<Value> {
+        let binary = current_binary()?;
+        Ok(json!({ self.servers_key(): { "merges": self.server_entry(&binary, root) } }))
+    }
+}
+
+/// The merges binary that is running now, which the server entry launches.
+fn current_binary() -> Result<PathBuf> {
+    std::env::current_exe().context("Could not locate the merges binary")
+}
+
+/// Add the `merges` server to `client`'s configuration for the repository at
+/// `root`, running the binary that is executing now. The rest of the file is
+/// kept; an existing `merges` entry is replaced. Returns the file written.
+pub fn install(client: Client, root: &Path) -> Result<PathBuf> {
+    let binary = current_binary()?;
+    let path = client.config_path(root)?;
+    write_entry(&path, client.servers_key(), client.server_entry(&binary, root))?;
+    Ok(path)
+}
+
+/// Set `<servers_key>.merges` in the JSON file at `path` to `entry`,
+/// creating the file (and its directory) when missing.
+fn write_entry(path: &Path, servers_key: &str, entry: Value) -> Result<()> {
+    let mut config = match std::fs::read_to_string(path) {
+        Ok(text) if !text.trim().is_empty() => serde_json::from_str(&text).with_context(|| {
+            format!("{} is not plain JSON (comments?) — add the merges entry by hand", path.display())
+        })?,
+        _ => json!({}),
+    };
+    let Some(object) = config.as_object_mut() else {
+        bail!("{} does not hold a JSON object", path.display())
+    };
+    let Some(servers) = object.entry(servers_key).or_insert_with(|| json!({})).as_object_mut() else {
+        bail!("`{}` in {} is not an object", servers_key, path.display())
+    };
+    servers.insert("merges".to_string(), entry);
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(&config)? + "\n")
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Where Claude Desktop keeps its configuration on this platform.
+fn claude_config_dir() -> Result<PathBuf> {
+    let home = || std::env::var_os("HOME").map(PathBuf::from).context("HOME is not set");
+    if cfg!(target_os = "macos") {
+        Ok(home()?.join("Library/Application Support/Claude"))
+    } else if cfg!(windows) {
+        Ok(PathBuf::from(std::env::var_os("APPDATA").context("APPDATA is not set")?).join("Claude"))
+    } else {
+        let config = match std::env::var_os("XDG_CONFIG_HOME").filter(|v| !v.is_empty()) {
+            Some(dir) => PathBuf::from(dir),
+            None => home()?.join(".config"),
+        };
+        Ok(config.join("Claude"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_write_entry_creates_the_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(".vscode/mcp.json");
+        write_entry(&path, "servers", json!({"command": "merges"})).unwrap();
+        let config: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(config, json!({"servers": {"merges": {"command": "merges"}}}));
+    }
+
+    #[test]
+    fn test_write_entry_keeps_other_servers_and_replaces_merges() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("claude_desktop_config.json");
+        let existing = json!({
+            "theme": "dark",
+            "mcpServers": {"other": {"command": "other"}, "merges": {"command": "/old/merges"}}
+        });
+        std::fs::write(&path, existing.to_string()).unwrap();
+
+        write_entry(&path, "mcpServers", json!({"command": "/new/merges"})).unwrap();
+
+        let config: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(config["theme"], "dark");
+        assert_eq!(config["mcpServers"]["other"]["command"], "other");
+        assert_eq!(config["mcpServers"]["merges"]["command"], "/new/merges");
+    }
+
+    #[test]
+    fn test_write_entry_refuses_to_overwrite_invalid_json() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("mcp.json");
+        std::fs::write(&path, "// my servers\n{}").unwrap();
+        let err = write_entry(&path, "servers", json!({})).unwrap_err();
+        assert!(err.to_string().contains("not plain JSON"), "{}", err);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "// my servers\n{}");
+    }
+
+    #[test]
+    fn test_server_entries_point_at_the_repository() {
+        let binary = Path::new("/usr/local/bin/merges");
+        let root = Path::new("/work/app");
+        assert_eq!(
+            Client::Claude.server_entry(binary, root),
+            json!({"command": "/usr/local/bin/merges", "args": ["--repo", "/work/app", "mcp"]})
+        );
+        for client in [Client::Vscode, Client::Cursor] {
+            assert_eq!(client.server_entry(binary, root)["args"], json!(["--repo", "${workspaceFolder}", "mcp"]));
+        }
+    }
+
+    #[test]
+    fn test_snippet_nests_the_entry_under_the_clients_key() {
+        let root = Path::new("/work/app");
+        let snippet = Client::Cursor.snippet(root).unwrap();
+        assert_eq!(snippet["mcpServers"]["merges"]["args"][2], "mcp");
+        assert!(Client::Vscode.snippet(root).unwrap()["servers"]["merges"].is_object());
+    }
+}
@@ -2,17 +2,24 @@
 //!
 //! The server reads newline-delimited JSON from stdin and writes responses to stdout.
 //! This is compatible with the Model Context Protocol used by Claude, GitHub Copilot, and others.
+//!
+//! Requests are handled one at a time. Stdin is read on its own thread so that
+//! `notifications/cancelled` reaches a tool call while it is still running.
 
+pub mod install;
 pub mod tools;
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use std::io::BufRead;
+use std::sync::{Arc, Mutex};
+use tokio::io::AsyncWriteExt;
 
 use crate::{
-    commands, doctor, git,
-    state::MergesState,
+    cancel::CancelToken,
+    ci, commands, doctor, git, routing,
+    state::{MergesState, Strategy},
 };
 
 #[derive(Debug, Deserialize)]
@@ -54,15 +61,57 @@ impl JsonRpcResponse {
     }
 }
 
+/// Requests the client has cancelled: the one running now (whose token gets
+/// tripped) and any still waiting in the queue (which are then skipped).
+#[derive(Default)]
+struct Cancellations {
+    running: Option<(Value, CancelToken)>,
+    queued: Vec<Value>,
+}
+
+impl Cancellations {
+    fn cancel(&mut self, id: Value) {
+        match &self.running {
+            Some((running, token)) if *running == id => token.cancel(),
+            _ => self.queued.push(id),
+        }
+    }
+}
+
+/// The request id a `notifications/cancelled` line refers to, if it is one.
+fn cancelled_request(line: &str) -> Option<Value> {
+    let req: JsonRpcRequest = serde_json::from_str(line).ok()?;
+    if req.method != "notifications/cancelled" {
+        return None;
+    }
+    req.params?.get("requestId").cloned()
+}
+
 pub async fn run() -> Result<()> {
-    let stdin = tokio::io::stdin();
-    let stdout = tokio::io::stdout();
-    let mut reader = BufReader::new(stdin).lines();
-    let mut stdout = stdout;
+    let mut stdout = tokio::io::stdout();
 
+    // stdout carries the protocol: keep command output and git's off it.
+    crate::output::set_json(true);
+    crate::output::set_log_level(crate::output::Level::Info);
+    crate::prompt::disable();
     eprintln!("merges MCP server running on stdio (JSON-RPC 2.0)");
 
-    while let Some(line) = reader.next_line().await? {
+    let cancellations = Arc::new(Mutex::new(Cancellations::default()));
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+    // Many tools block while they run git, so a plain thread reads stdin.
+    let reader_cancellations = Arc::clone(&cancellations);
+    std::thread::spawn(move || {
+        for line in std::io::stdin().lock().lines() {
+            let Ok(line) = line else { break };
+            match cancelled_request(&line) {
+                Some(id) => reader_cancellations.lock().unwrap().cancel(id),
+                None if tx.send(line).is_err() => break,
+                None => {}
+            }
+        }
+    });
+
+    while let Some(line) = rx.recv().await {
         let line = line.trim().to_string();
         if line.is_empty() {
             continue;
@@ -74,11 +123,31 @@ pub async fn run() -> Result<()> {
                 -32700,
                 &format!("Parse error: {}", e),
             ),
+            // Notifications carry no id and never get a response, not even an error.
+            Ok(req) if req.id.is_none() => {
+                handle_notification(&req);
+                continue;
+            }
             Ok(req) => {
                 let id = req.id.clone().unwrap_or(Value::Null);
-                handle_request(req).await.unwrap_or_else(|e| {
+                let cancel = CancelToken::default();
+                {
+                    let mut cancellations = cancellations.lock().unwrap();
+                    if let Some(pos) = cancellations.queued.iter().position(|q| *q == id) {
+                        cancellations.queued.remove(pos);
+                        continue;
+                    }
+                    cancellations.running = Some((id.clone(), cancel.clone()));
+                }
+                let response = handle_request(req, &cancel).await.unwrap_or_else(|e| {
                     JsonRpcResponse::err(id, -32000, &e.to_string())
-                })
+                });
+                cancellations.lock().unwrap().running = None;
+                // The client has given up on a cancelled request; it gets no response.
+                if cancel.is_cancelled() {
+                    continue;
+                }
+                response
             }
         };
 
@@ -91,7 +160,17 @@ pub async fn run() -> Result<()> {
     Ok(())
 }
 
-async fn handle_request(req: JsonRpcRequest) -> Result<JsonRpcResponse> {
+/// Act on a notification from the client. Unknown ones are ignored.
+fn handle_notification(req: &JsonRpcRequest) {
+    match req.method.as_str() {
+        // Nothing to do once the client is ready; cancellations are picked up
+        // by the stdin reader so they reach a running tool.
+        "notifications/initialized" | "notifications/cancelled" => {}
+        other => eprintln!("Ignoring notification '{}'", other),
+    }
+}
+
+async fn handle_request(req: JsonRpcRequest, cancel: &CancelToken) -> Result<JsonRpcResponse> {
     let id = req.id.unwrap_or(Value::Null);
 
     match req.method.as_str() {
@@ -100,7 +179,7 @@ async fn handle_request(req: JsonRpcRequest) -> Result<JsonRpcResponse> {
             id,
             json!({
                 "protocolVersion": "2024-11-05",
-                "capabilities": { "tools": {} },
+                "capabilities": { "tools": {}, "logging": {} },
                 "serverInfo": {
                     "name": "merges",
                     "version": env!("CARGO_PKG_VERSION")
@@ -108,9 +187,17 @@ async fn handle_request(req: JsonRpcRequest) -> Result<JsonRpcResponse> {
             }),
         )),
 
-        "notifications/initialized" => {
-            // No response needed for notifications
-            Ok(JsonRpcResponse::ok(id, json!({})))
+        "ping" => Ok(JsonRpcResponse::ok(id, json!({}))),
+
+        "logging/setLevel" => {
+            let level = req.params.as_ref().and_then(|p| p.get("level")).cloned().unwrap_or(Value::Null);
+            match serde_json::from_value::<crate::output::Level>(level) {
+                Ok(level) => {
+                    crate::output::set_log_level(level);
+                    Ok(JsonRpcResponse::ok(id, json!({})))
+                }
+                Err(e) => Ok(JsonRpcResponse::err(id, -32602, &format!("Invalid log level: {}", e))),
+            }
         }
 
         "tools/list" => Ok(JsonRpcResponse::ok(
@@ -123,7 +210,7 @@ async fn handle_request(req: JsonRpcRequest) -> Result<JsonRpcResponse> {
             let tool_name = params["name"].as_str().unwrap_or("").to_string();
             let args = params.get("arguments").cloned().unwrap_or(json!({}));
 
-            let result = dispatch_tool(&tool_name, &args).await?;
+            let result = dispatch_tool(&tool_name, &args, cancel).await?;
             Ok(JsonRpcResponse::ok(
                 id,
                 json!({
@@ -143,12 +230,70 @@ async fn handle_request(req: JsonRpcRequest) -> Result<JsonRpcResponse> {
     }
 }
 
-async fn dispatch_tool(name: &str, args: &Value) -> Result<String> {
+async fn dispatch_tool(name: &str, args: &Value, cancel: &CancelToken) -> Result<String> {
     match name {
         "merges_init" => {
             let base = args.get("base_branch").and_then(|v| v.as_str()).map(String::from);
             let commit_prefix = args.get("commit_prefix").and_then(|v| v.as_str()).map(String::from);
-            commands::init::run(base, false, commit_prefix)?;
+            let strategy = args
+                .get("strategy")
+                .cloned()
+                .map(serde_json::from_value::<crate::state::Strategy>)
+                .transpose()
+                .map_err(|e| anyhow::anyhow!("Invalid strategy: {}", e))?;
+            let overwrite = args.get("overwrite").and_then(|v| v.as_bool()).unwrap_or(false);
+            let autostash = args.get("autostash").and_then(|v| v.as_bool()).unwrap_or(false);
+            let max_subject_length = args.get("max_subject_length").and_then(|v| v.as_u64()).map(|n| n as usize);
+            let max_title_length = args.get("max_title_length").and_then(|v| v.as_u64()).map(|n| n as usize);
+            let auto_depth = args.get("auto_depth").and_then(|v| v.as_u64()).map(|n| n as usize);
+            let root_files = args
+                .get("root_files")
+                .and_then(|v| v.as_str())
+                .map(str::parse::<crate::split::RootFiles>)
+                .transpose()?;
+            let branch_namespace = args.get("branch_namespace").and_then(|v| v.as_str()).map(String::from);
+            let preserve_history = args.get("preserve_history").and_then(|v| v.as_bool()).unwrap_or(false);
+            let squash_on_push = args.get("squash_on_push").and_then(|v| v.as_bool()).unwrap_or(false);
+            let sync_merge = args.get("sync_merge").and_then(|v| v.as_bool()).unwrap_or(false);
+            let post_summary = args.get("post_summary").and_then(|v| v.as_bool()).unwrap_or(false);
+            let strings = |key: &str| -> Vec<String> {
+                args.get(key)
+                    .and_then(|v| v.as_array())
+                    .map(|a| a.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+                    .unwrap_or_default()
+            };
+            let project = args.get("project").and_then(|v| v.as_str()).map(String::from);
+            let milestone = args.get("milestone").and_then(|v| v.as_str()).map(String::from);
+            let limit = |key: &str| args.get(key).and_then(|v| v.as_u64());
+            // MCP owns stdio, so init must never prompt here.
+            commands::init::run(commands::init::InitOptions {
+                base_branch: base,
+                strategy,
+                commit_prefix,
+                max_subject_length,
+                max_title_length,
+                auto_depth,
+                root_files,
+                branch_namespace,
+                autostash,
+                preserve_history,
+                squash_on_push,
+                sync_merge,
+                post_summary,
+                routes: strings("routes"),
+                project,
+                project_statuses: strings("project_statuses"),
+                milestone,
+                size_limits: crate::size::SizeLimits {
+                    warn_lines: limit("warn_lines"),
+                    max_lines: limit("max_lines"),
+                    warn_files: limit("warn_files"),
+                    max_files: limit("max_files"),
+                },
+                force: overwrite,
+                non_interactive: true,
+                ..Default::default()
+            })?;
             Ok("Initialised successfully.".to_string())
         }
 
@@ -161,59 +306,251 @@ async fn dispatch_tool(name: &str, args: &Value) -> Result<String> {
                 let plan: Vec<crate::split::ChunkPlan> =
                     serde_json::from_value(plan_val.clone())
                         .map_err(|e| anyhow::anyhow!("Invalid plan format: {}", e))?;
-                crate::split::apply_plan(&root, plan)?;
+                let autostash = args.get("autostash").and_then(|v| v.as_bool()).unwrap_or(false);
+                let stash = crate::autostash::Autostash::begin(&root, &state, "split", autostash)?;
+                let expansions = stash.finish(crate::split::apply_plan(&root, plan))?;
                 let updated = MergesState::load(&root)?;
                 Ok(serde_json::to_string_pretty(&json!({
                     "status": "applied",
                     "chunks_created": updated.chunks.len(),
+                    "expanded_patterns": expansions,
                     "chunks": updated.chunks.iter().map(|c| json!({
                         "name": c.name,
                         "branch": c.branch,
                         "files": c.files
                     })).collect::<Vec<_>>()
                 }))?)
+            } else if let Some(name) = args.get("preset").and_then(|v| v.as_str()) {
+                // Start from a preset: its chunks, and the files it leaves for the LLM
+                let preset = crate::preset::Preset::load(&root, name)?;
+                let unassigned = crate::commands::assign::unassigned_files(&root, &state)?;
+                let (plan, rest) = preset.assign(&unassigned);
+                Ok(serde_json::to_string_pretty(&json!({
+                    "preset": name,
+                    "plan": plan,
+                    "unmatched_files": rest,
+                    "instructions": "Nothing was changed. Add chunks for 'unmatched_files' (or add them to the preset's chunks) and pass the plan to merges_split to create the chunks."
+                }))?)
             } else {
                 // No plan yet — return files so the LLM can decide how to split
-                let files = crate::git::changed_files(&root, &state.base_branch)?;
+                let (files, excluded) = crate::split::changed_files(&root, &state)?;
                 Ok(serde_json::to_string_pretty(&json!({
                     "changed_files": files,
+                    "excluded_files": excluded,
                     "instructions": "Call merges_split again with a 'plan' field: [{\"name\":\"chunk-name\",\"files\":[\"path/to/file.rs\"]}]"
                 }))?)
             }
         }
 
+        "merges_plan_preview" => {
+            let root = git::repo_root()?;
+            let state = MergesState::load(&root)?;
+            let by = args
+                .get("strategy")
+                .cloned()
+                .map(serde_json::from_value::<crate::split::Grouping>)
+                .transpose()
+                .map_err(|e| anyhow::anyhow!("Invalid strategy: {}", e))?
+                .unwrap_or_default();
+            let depth = args.get("depth").and_then(|v| v.as_u64()).map(|n| n as usize);
+            let max_files = args.get("max_files").and_then(|v| v.as_u64()).map(|n| n as usize);
+            let with_imports = args.get("imports").and_then(|v| v.as_bool()).unwrap_or(false);
+
+            // Read-only: nothing below writes state or touches branches.
+            let (files, excluded) = crate::split::changed_files(&root, &state)?;
+            let mut plan = crate::split::auto_group(&root, &state, &files, by, depth.or(state.auto_depth))?;
+            let mut cross = vec![];
+            if with_imports {
+                let edges = crate::imports::graph(&root, &state.source_branch, &files);
+                (plan, cross) = crate::imports::regroup(plan, &edges);
+            }
+            if let Some(max) = max_files {
+                plan = crate::split::cap_chunk_size(plan, max);
+            }
+
+            let stats: std::collections::HashMap<String, git::FileStat> = git::diff_numstat(&root, &state.base_branch, "HEAD")
+                .unwrap_or_default()
+                .into_iter()
+                .map(|s| (s.path.clone(), s))
+                .collect();
+            let chunks: Vec<Value> = plan
+                .iter()
+                .map(|chunk| {
+                    let files: Vec<Value> = chunk
+                        .files
+                        .iter()
+                        .map(|f| {
+                            let stat = stats.get(f);
+                            json!({
+                                "path": f,
+                                "added": stat.and_then(|s| s.added),
+                                "removed": stat.and_then(|s| s.removed),
+                            })
+                        })
+                        .collect();
+                    let total = |pick: fn(&git::FileStat) -> Option<usize>| -> usize {
+                        chunk.files.iter().filter_map(|f| stats.get(f).and_then(pick)).sum()
+                    };
+                    json!({
+                        "name": chunk.name,
+                        "files": files,
+                        "added": total(|s| s.added),
+                        "removed": total(|s| s.removed),
+                    })
+                })
+                .collect();
+            Ok(serde_json::to_string_pretty(&json!({
+                "strategy": by.to_string(),
+                "chunks": chunks,
+                "cross_imports": cross.iter().map(|c| json!({
+                    "from_chunk": c.from_chunk,
+                    "from_file": c.from_file,
+                    "to_chunk": c.to_chunk,
+                    "to_file": c.to_file,
+                })).collect::<Vec<_>>(),
+                "excluded_files": excluded,
+                "plan": plan,
+                "instructions": "Nothing was changed. Adjust 'plan' if needed and pass it to merges_split to create the chunks."
+            }))?)
+        }
+
         "merges_push" => {
             let stacked = args.get("strategy").and_then(|v| v.as_str()) == Some("stacked");
             let independent = args.get("strategy").and_then(|v| v.as_str()) == Some("independent");
-            commands::push::run(stacked, independent).await?;
+            let auto_merge = args
+                .get("auto_merge")
+                .cloned()
+                .map(serde_json::from_value::<crate::forge::MergeMethod>)
+                .transpose()
+                .map_err(|e| anyhow::anyhow!("Invalid auto_merge: {}", e))?;
+            let tracking_issue = args.get("tracking_issue").and_then(|v| v.as_bool()).unwrap_or(false);
+            let source_pr = args
+                .get("source_pr")
+                .cloned()
+                .map(serde_json::from_value::<commands::push::SourcePrAction>)
+                .transpose()
+                .map_err(|e| anyhow::anyhow!("Invalid source_pr: {}", e))?;
+            let resume = args.get("resume").and_then(|v| v.as_bool()).unwrap_or(false);
+            let update_prs = args.get("update_prs").and_then(|v| v.as_bool()).unwrap_or(false);
+            let autostash = args.get("autostash").and_then(|v| v.as_bool()).unwrap_or(false);
+            let allow_empty = args.get("allow_empty").and_then(|v| v.as_bool()).unwrap_or(false);
+            let drop_empty = args.get("drop_empty").and_then(|v| v.as_bool()).unwrap_or(false);
+            let squash = args.get("squash").and_then(|v| v.as_bool()).unwrap_or(false);
+            let force = args.get("force").and_then(|v| v.as_bool()).unwrap_or(false);
+            let dry_run = args.get("dry_run").and_then(|v| v.as_bool()).unwrap_or(false);
+            let stagger = args
+                .get("stagger")
+                .and_then(|v| v.as_str())
+                .map(commands::push::parse_duration)
+                .transpose()
+                .map_err(|e| anyhow::anyhow!("Invalid stagger: {}", e))?;
+            let skip_ci = args.get("skip_ci").and_then(|v| v.as_bool()).unwrap_or(false);
+            let allow_large = args.get("allow_large").and_then(|v| v.as_bool()).unwrap_or(false);
+            if dry_run {
+                let root = git::repo_root()?;
+                let state = MergesState::load(&root)?;
+                let strategy = if stacked {
+                    Strategy::Stacked
+                } else if independent {
+                    Strategy::Independent
+                } else {
+                    state.strategy.clone()
+                };
+                let workflows = ci::workflows(&root, &state.source_branch);
+                let chunks: Vec<Value> = ci::predict(&state, &strategy, &workflows)
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, predicted)| {
+                        json!({
+                            "chunk": predicted.chunk,
+                            "branch": state.chunks[i].branch,
+                            "base": commands::push::expected_pr_base(&state, i, &strategy),
+                            "title": commands::push::pr_title(&state, i),
+                            "pr_number": state.chunks[i].pr_number,
+                            "workflows": predicted.workflows,
+                            "routes": routing::route(&state.routes, &state.chunks[i].files).targets(),
+                            "size_limits_exceeded": commands::push::size_breaches(&root, &state, i, &strategy)
+                                .into_iter()
+                                .map(|b| b.detail)
+                                .collect::<Vec<_>>()
+                        })
+                    })
+                    .collect();
+                return Ok(serde_json::to_string_pretty(&json!({
+                    "dry_run": true,
+                    "chunks": chunks,
+                    "ci_warnings": ci::warnings(&state, &strategy, &workflows)
+                }))?);
+            }
+            commands::push::run(
+                commands::push::PushOptions {
+                    stacked,
+                    independent,
+                    auto_merge,
+                    tracking_issue,
+                    source_pr,
+                    resume,
+                    update_prs,
+                    autostash,
+                    allow_empty,
+                    drop_empty,
+                    squash,
+                    force,
+                    dry_run: false,
+                    stagger,
+                    skip_ci,
+                    allow_large,
+                },
+                cancel,
+            )
+            .await?;
             Ok("Push completed.".to_string())
         }
 
         "merges_sync" => {
-            commands::sync::run()?;
-            Ok("Sync completed.".to_string())
+            let autostash = args.get("autostash").and_then(|v| v.as_bool()).unwrap_or(false);
+            let drop_empty = args.get("drop_empty").and_then(|v| v.as_bool()).unwrap_or(false);
+            let delete_merged = args.get("delete_merged").and_then(|v| v.as_bool()).unwrap_or(false);
+            let resume = args.get("resume").and_then(|v| v.as_bool()).unwrap_or(false);
+            let merge = args.get("merge").and_then(|v| v.as_bool()).unwrap_or(false);
+            let chunks: Vec<String> = args
+                .get("chunks")
+                .and_then(|v| v.as_array())
+                .map(|a| a.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+                .unwrap_or_default();
+            let results = commands::sync::run(autostash, drop_empty, delete_merged, resume, merge, &chunks, cancel)?;
+            commands::sync::close_tracking_issue(&results).await?;
+            let clean = results.iter().all(|r| r.outcome.is_up_to_date());
+            Ok(serde_json::to_string_pretty(&json!({
+                "status": if clean { "synced" } else { "incomplete" },
+                "chunks": results,
+            }))?)
         }
 
         "merges_status" => {
             let root = git::repo_root()?;
             let state = MergesState::load(&root)?;
+            git::Prefetch::start(&root, &state.base_branch).finish(commands::status::PREFETCH_TIMEOUT).await;
+            Ok(serde_json::to_string_pretty(&crate::output::status(&root, &state))?)
+        }
+
+        "merges_reviews" => {
+            let root = git::repo_root()?;
+            let forge = crate::forge::connect(&MergesState::load(&root)?).await?;
+            let reviews = commands::reviews::collect(&root, forge.as_ref()).await?;
+            Ok(serde_json::to_string_pretty(&json!({ "chunks": reviews }))?)
+        }
+
+        "merges_hold" => {
+            let root = git::repo_root()?;
+            let chunk = args["chunk"].as_str().ok_or_else(|| anyhow::anyhow!("'chunk' is required"))?;
+            let release = args.get("release").and_then(|v| v.as_bool()).unwrap_or(false);
+            let changed = commands::hold::run(&root, chunk, !release)?;
             Ok(serde_json::to_string_pretty(&json!({
-                "source_branch": state.source_branch,
-                "base_branch": state.base_branch,
-                "strategy": state.strategy,
-                "chunks": state.chunks.iter().map(|c| {
-                    let behind = git::commits_behind(&root, &c.branch, &state.base_branch)
-                        .unwrap_or(0);
-                    json!({
-                        "name": c.name,
-                        "branch": c.branch,
-                        "files_count": c.files.len(),
-                        "pr_number": c.pr_number,
-                        "pr_url": c.pr_url,
-                        "behind": behind,
-                        "sync": git::sync_status(behind)
-                    })
-                }).collect::<Vec<_>>()
+                "status": "ok",
+                "chunk": chunk,
+                "hold": !release,
+                "changed": changed
             }))?)
         }
 
@@ -229,7 +566,8 @@ async fn dispatch_tool(name: &str, args: &Value) -> Result<String> {
                 .iter()
                 .filter_map(|v| v.as_str().map(String::from))
                 .collect();
-            commands::add::run(&root, &chunk, &files)?;
+            let autostash = args.get("autostash").and_then(|v| v.as_bool()).unwrap_or(false);
+            commands::add::run(&root, &chunk, &files, autostash)?;
             Ok(serde_json::to_string_pretty(&json!({
                 "status": "ok",
                 "chunk": chunk,
@@ -251,7 +589,8 @@ async fn dispatch_tool(name: &str, args: &Value) -> Result<String> {
                 .as_str()
                 .ok_or_else(|| anyhow::anyhow!("'to' is required"))?
                 .to_string();
-            commands::r#move::run(&root, &file, &from, &to)?;
+            let autostash = args.get("autostash").and_then(|v| v.as_bool()).unwrap_or(false);
+            commands::r#move::run(&root, &file, &from, &to, autostash)?;
             Ok(serde_json::to_string_pretty(&json!({
                 "status": "ok",
                 "file": file,
@@ -260,6 +599,49 @@ async fn dispatch_tool(name: &str, args: &Value) -> Result<String> {
             }))?)
         }
 
+        "merges_unassigned" => {
+            let root = git::repo_root()?;
+            let state = MergesState::load(&root)?;
+            let unassigned = commands::assign::unassigned_files(&root, &state)?;
+            let stats: std::collections::HashMap<String, git::FileStat> = git::diff_numstat(&root, &state.base_branch, "HEAD")
+                .unwrap_or_default()
+                .into_iter()
+                .map(|s| (s.path.clone(), s))
+                .collect();
+            Ok(serde_json::to_string_pretty(&json!({
+                "unassigned": unassigned.iter().map(|f| {
+                    let stat = stats.get(f);
+                    json!({
+                        "path": f,
+                        "added": stat.and_then(|s| s.added),
+                        "removed": stat.and_then(|s| s.removed),
+                    })
+                }).collect::<Vec<_>>(),
+                "chunks": state.chunks.iter().map(|c| json!({
+                    "name": c.name,
+                    "files": c.files
+                })).collect::<Vec<_>>(),
+                "source_rewritten": doctor::source_divergence(&root, &state),
+                "instructions": "Call merges_refresh with 'assignments': [{\"chunk\":\"chunk-name\",\"files\":[\"path/to/file.rs\"]}] to place these files."
+            }))?)
+        }
+
+        "merges_refresh" => {
+            let root = git::repo_root()?;
+            let assignments: Vec<commands::assign::Assignment> = serde_json::from_value(
+                args.get("assignments").cloned().ok_or_else(|| anyhow::anyhow!("'assignments' is required"))?,
+            )
+            .map_err(|e| anyhow::anyhow!("Invalid assignments format: {}", e))?;
+            let autostash = args.get("autostash").and_then(|v| v.as_bool()).unwrap_or(false);
+            let applied = commands::assign::apply(&root, &assignments, autostash)?;
+            let state = MergesState::load(&root)?;
+            Ok(serde_json::to_string_pretty(&json!({
+                "status": "ok",
+                "assigned": applied,
+                "unassigned": commands::assign::unassigned_files(&root, &state)?
+            }))?)
+        }
+
         "merges_clean" => {
             let root = git::repo_root()?;
             let state = MergesState::load(&root)?;
@@ -284,13 +666,31 @@ async fn dispatch_tool(name: &str, args: &Value) -> Result<String> {
         "merges_doctor" => {
             let root = git::repo_root()?;
             let repair = args.get("repair").and_then(|v| v.as_bool()).unwrap_or(false);
-            let report = doctor::run(&root, repair)?;
+            let report = doctor::run_with_environment(&root, repair).await?;
             Ok(serde_json::to_string_pretty(&json!({
                 "all_ok": report.all_ok(),
                 "issues": report.issues
             }))?)
         }
 
+        "merges_diffcheck" => {
+            let root = git::repo_root()?;
+            let state = MergesState::load(&root)?;
+            let report = commands::diffcheck::check(&root, &state)?;
+            Ok(serde_json::to_string_pretty(&json!({
+                "consistent": report.is_consistent(),
+                "report": report
+            }))?)
+        }
+
+        "merges_history" => {
+            let root = git::repo_root()?;
+            let entries = crate::journal::load(&root)?;
+            let limit = args.get("limit").and_then(|v| v.as_u64()).map_or(entries.len(), |n| n as usize);
+            let recent: Vec<_> = entries.iter().rev().take(limit).collect();
+            Ok(serde_json::to_string_pretty(&json!({ "operations": recent }))?)
+        }
+
         other => anyhow::bail!("Unknown tool: {}", other),
     }
 }
@@ -299,5 +699,38 @@ async fn dispatch_tool(name: &str, args: &Value) -> Result<String> {
 #[allow(dead_code)]
 pub fn call_tool_sync(name: &str, args: &serde_json::Value) -> anyhow::Result<String> {
     let rt = tokio::runtime::Runtime::new().unwrap();
-    rt.block_on(dispatch_tool(name, args))
+    rt.block_on(dispatch_tool(name, args, &CancelToken::default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(line: &str) -> Value {
+        let req: JsonRpcRequest = serde_json::from_str(line).unwrap();
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let response = rt.block_on(handle_request(req, &CancelToken::default())).unwrap();
+        serde_json::to_value(response).unwrap()
+    }
+
+    #[test]
+    fn test_ping_gets_an_empty_result() {
+        assert_eq!(
+            request(r#"{"jsonrpc":"2.0","id":7,"method":"ping"}"#),
+            json!({ "jsonrpc": "2.0", "id": 7, "result": {} })
+        );
+    }
+
+    #[test]
+    fn test_unknown_method_is_an_error() {
+        let response = request(r#"{"jsonrpc":"2.0","id":"a","method":"resources/list"}"#);
+        assert_eq!(response["error"]["code"], -32601);
+    }
+
+    #[test]
+    fn test_cancelled_request_id_is_read_from_params() {
+        let line = r#"{"jsonrpc":"2.0","method":"notifications/cancelled","params":{"requestId":3}}"#;
+        assert_eq!(cancelled_request(line), Some(json!(3)));
+        assert_eq!(cancelled_request(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#), None);
+    }
 }
@@ -8,6 +8,46 @@ pub struct Tool {
     pub description: String,
     #[serde(rename = "inputSchema")]
     pub input_schema: Value,
+    pub annotations: ToolAnnotations,
+}
+
+/// Behaviour hints clients use to decide when to ask before calling a tool.
+/// All four are sent, since the spec's defaults (destructive, open-world)
+/// assume the worst.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolAnnotations {
+    /// Changes nothing — no state file, branch, or remote writes.
+    pub read_only_hint: bool,
+    /// May rewrite or delete existing branches, state, or PRs.
+    pub destructive_hint: bool,
+    /// Calling again with the same arguments has no further effect.
+    pub idempotent_hint: bool,
+    /// Talks to the remote or the forge, not just the local repository.
+    pub open_world_hint: bool,
+}
+
+impl ToolAnnotations {
+    pub const fn read_only() -> Self {
+        ToolAnnotations { read_only_hint: true, destructive_hint: false, idempotent_hint: true, open_world_hint: false }
+    }
+
+    /// Writes, but only adds to what is there.
+    pub const fn additive() -> Self {
+        ToolAnnotations { read_only_hint: false, destructive_hint: false, idempotent_hint: false, open_world_hint: false }
+    }
+
+    pub const fn destructive() -> Self {
+        ToolAnnotations { read_only_hint: false, destructive_hint: true, idempotent_hint: false, open_world_hint: false }
+    }
+
+    pub const fn idempotent(self) -> Self {
+        ToolAnnotations { idempotent_hint: true, ..self }
+    }
+
+    pub const fn open_world(self) -> Self {
+        ToolAnnotations { open_world_hint: true, ..self }
+    }
 }
 
 pub fn all_tools() -> Vec<Tool> {
@@ -23,14 +63,101 @@ pub fn all_tools() -> Vec<Tool> {
                 "properties": {
                     "base_branch": {
                         "type": "string",
-                        "description": "The base branch PRs will target (default: main)"
+                        "description": "The base branch PRs will target (default: the remote's default branch)"
                     },
                     "commit_prefix": {
                         "type": "string",
                         "description": "Explicit prefix for all commit messages and PR titles (e.g. JCLARK-97246). Auto-detected from branch name if omitted."
+                    },
+                    "strategy": {
+                        "type": "string",
+                        "enum": ["stacked", "independent"],
+                        "description": "PR topology used by merges_push (default: stacked)"
+                    },
+                    "overwrite": {
+                        "type": "boolean",
+                        "description": "Replace an existing .merges.json, discarding its chunks. By default an existing file is updated with the given settings and its chunks are kept."
+                    },
+                    "autostash": {
+                        "type": "boolean",
+                        "description": "Always stash uncommitted changes around split, add, move, push, and sync when worktrees are not used"
+                    },
+                    "max_subject_length": {
+                        "type": "integer",
+                        "minimum": 20,
+                        "description": "Longest chunk commit subject in characters; longer chunk names are shortened, keeping the ticket prefix and chunk number"
+                    },
+                    "max_title_length": {
+                        "type": "integer",
+                        "minimum": 20,
+                        "description": "Longest chunk PR title in characters; longer chunk names are shortened, keeping the ticket prefix and stack position"
+                    },
+                    "auto_depth": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "Directory level that automatic splitting groups files on (1 = top-level directories) instead of picking one or two levels itself"
+                    },
+                    "root_files": {
+                        "type": "string",
+                        "description": "Where automatic splitting puts files at the repository root instead of a 'root' chunk: 'last' (the last chunk), 'related' (the chunk most often committed together with each file), or 'chunk:<name>'"
+                    },
+                    "branch_namespace": {
+                        "type": "string",
+                        "description": "Create chunk branches as <namespace>/<source>/<n>-<name> (e.g. 'merges') instead of <source>-chunk-<n>-<name>"
+                    },
+                    "preserve_history": {
+                        "type": "boolean",
+                        "description": "Build chunk branches from the source commits that touch each chunk's files, keeping their messages and authors, instead of one commit per chunk"
+                    },
+                    "squash_on_push": {
+                        "type": "boolean",
+                        "description": "Squash each chunk branch into one commit before every merges_push"
+                    },
+                    "sync_merge": {
+                        "type": "boolean",
+                        "description": "Bring chunk branches up to date by merging the base branch into them instead of rebasing, and push without force (for repos that forbid rewriting published branches)"
+                    },
+                    "post_summary": {
+                        "type": "boolean",
+                        "description": "Post the review summary (chunks, rationale, files, review order) in the source PR comment and tracking issue on every merges_push"
+                    },
+                    "routes": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Routing rules giving chunk PRs labels and reviewers by the files they change, each written '<pattern> -> label:<name>, reviewer:@<login or org/team>' (e.g. 'migrations/** -> label:db, reviewer:@acme/db-team'). Added to the rules already saved."
+                    },
+                    "project": {
+                        "type": "string",
+                        "description": "GitHub Project (v2) every chunk PR is added to, as 'owner/number' or the project's URL"
+                    },
+                    "project_statuses": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Status field option per PR stage, as 'stage=Option' with stage open, approved, or merged (default: open=In Progress, merged=Done)"
+                    },
+                    "milestone": {
+                        "type": "string",
+                        "description": "Milestone title every chunk PR is given"
+                    },
+                    "warn_lines": {
+                        "type": "integer",
+                        "description": "merges_push warns about chunks changing more lines than this"
+                    },
+                    "max_lines": {
+                        "type": "integer",
+                        "description": "merges_push refuses chunks changing more lines than this (unless allow_large)"
+                    },
+                    "warn_files": {
+                        "type": "integer",
+                        "description": "merges_push warns about chunks changing more files than this"
+                    },
+                    "max_files": {
+                        "type": "integer",
+                        "description": "merges_push refuses chunks changing more files than this (unless allow_large)"
                     }
                 }
             }),
+            annotations: ToolAnnotations::destructive().idempotent(),
         },
         Tool {
             name: "merges_split".to_string(),
@@ -55,13 +182,74 @@ pub fn all_tools() -> Vec<Tool> {
                                 "files": {
                                     "type": "array",
                                     "items": { "type": "string" },
-                                    "description": "Relative file paths to include in this chunk"
+                                    "description": "Relative file paths, directories, or glob patterns (e.g. 'src/api/*.rs') to include in this chunk"
+                                },
+                                "description": {
+                                    "type": "string",
+                                    "description": "Why this chunk exists; becomes the lead paragraph of its PR body and shows in merges_status"
+                                },
+                                "commit_message": {
+                                    "type": "string",
+                                    "description": "Commit message for the chunk branch, replacing the generated 'chunk N - name' message. The ticket or commit prefix is added if missing."
+                                },
+                                "pr_title": {
+                                    "type": "string",
+                                    "description": "PR title, replacing the chunk name in the generated title. The ticket prefix and stack position are still added."
+                                },
+                                "pr_body": {
+                                    "type": "string",
+                                    "description": "PR description (Markdown), replacing the generated file list. A table of the whole stack is appended."
+                                },
+                                "skip_ci": {
+                                    "type": "boolean",
+                                    "description": "The chunk needs no CI (docs, say): merges_push with skip_ci gives its tip commit a `skip-checks: true` trailer."
                                 }
                             }
                         }
+                    },
+                    "autostash": {
+                        "type": "boolean",
+                        "description": "Stash uncommitted changes in the working tree for the split and re-apply them afterwards. Without it, a dirty tree is refused unless the repo uses worktrees or was initialised with autostash."
+                    },
+                    "preset": {
+                        "type": "string",
+                        "description": "Without 'plan': name of a split preset (git config merges.preset.<name>). Returns the plan its chunks give the unassigned files and the files it leaves unmatched, without changing anything."
+                    }
+                }
+            }),
+            annotations: ToolAnnotations::additive(),
+        },
+        Tool {
+            name: "merges_plan_preview".to_string(),
+            description: "Preview an automatic chunk plan without changing anything: returns the chunks \
+                the chosen grouping would produce, with per-file added/removed line counts, ready to \
+                adjust and pass to merges_split."
+                .to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "strategy": {
+                        "type": "string",
+                        "enum": ["directory", "language", "history", "two_level"],
+                        "description": "How to group files: by directory (default), by language (file extension), by history (files often committed together), or two_level (the first two directories, with chunks named like 'backend-api')"
+                    },
+                    "depth": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "Directory level to group on (1 = top-level directories); directory strategy only. Defaults to the repo's auto_depth setting, else one or two levels picked automatically"
+                    },
+                    "max_files": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "Split chunks with more files than this into numbered parts (name-1, name-2, ...)"
+                    },
+                    "imports": {
+                        "type": "boolean",
+                        "description": "Merge chunks whose files import each other in a cycle and order chunks so imported code comes first; cross-chunk imports are listed"
                     }
                 }
             }),
+            annotations: ToolAnnotations::read_only(),
         },
         Tool {
             name: "merges_push".to_string(),
@@ -75,19 +263,107 @@ pub fn all_tools() -> Vec<Tool> {
                         "type": "string",
                         "enum": ["stacked", "independent"],
                         "description": "PR topology: stacked (each PR targets the previous chunk) or independent (all target base)"
+                    },
+                    "auto_merge": {
+                        "type": "string",
+                        "enum": ["squash", "merge", "rebase"],
+                        "description": "Enable GitHub auto-merge on each chunk PR with this merge method"
+                    },
+                    "tracking_issue": {
+                        "type": "boolean",
+                        "description": "Create (and keep updated) a GitHub issue with a checklist of all chunk PRs"
+                    },
+                    "source_pr": {
+                        "type": "string",
+                        "enum": ["comment", "draft", "close"],
+                        "description": "Comment on the source branch's own PR with an index of the chunk PRs (kept updated by later pushes); 'draft' also converts it to a draft, 'close' closes it"
+                    },
+                    "resume": {
+                        "type": "boolean",
+                        "description": "Continue an interrupted push: skip chunks already pushed and reuse PRs already open for chunk branches"
+                    },
+                    "update_prs": {
+                        "type": "boolean",
+                        "description": "Regenerate the title and body of PRs that already exist"
+                    },
+                    "autostash": {
+                        "type": "boolean",
+                        "description": "Stash uncommitted changes in the working tree for the push and re-apply them afterwards. Without it, a dirty tree is refused unless the repo uses worktrees or was initialised with autostash."
+                    },
+                    "allow_empty": {
+                        "type": "boolean",
+                        "description": "Create and update PRs for chunks with no changes left after rebasing (by default they are skipped)"
+                    },
+                    "drop_empty": {
+                        "type": "boolean",
+                        "description": "Drop chunks with no changes left after rebasing from the stack"
+                    },
+                    "squash": {
+                        "type": "boolean",
+                        "description": "Squash each chunk branch into one commit before pushing it"
+                    },
+                    "force": {
+                        "type": "boolean",
+                        "description": "Push even when a chunk branch on origin has commits the local branch lacks, discarding them. Without it such a push is refused with a summary of those commits."
+                    },
+                    "dry_run": {
+                        "type": "boolean",
+                        "description": "Push nothing; return each chunk's PR base, title and the CI workflows its files should trigger (from the path filters in .github/workflows), with warnings for chunks that trigger none or all"
+                    },
+                    "stagger": {
+                        "type": "string",
+                        "description": "Minimum interval between the CI runs the push sets off, bottom of the stack first (e.g. \"90s\", \"2m\", \"1h\")"
+                    },
+                    "skip_ci": {
+                        "type": "boolean",
+                        "description": "Give chunks marked skip_ci a `skip-checks: true` trailer so CI does not run on their PRs"
+                    },
+                    "allow_large": {
+                        "type": "boolean",
+                        "description": "Push chunks over the hard size limits (max_lines/max_files from merges_init) anyway; without it such a push is refused before anything is pushed"
                     }
                 }
             }),
+            annotations: ToolAnnotations::destructive().idempotent().open_world(),
         },
         Tool {
             name: "merges_sync".to_string(),
-            description: "Rebase all chunk branches onto the latest base branch (e.g. main). \
-                Keeps chunks in sync with a fast-moving main."
+            description: "Rebase chunk branches onto the latest base branch (e.g. main). \
+                Returns each chunk's outcome: rebased, merged (its PR merged into the base branch, so it \
+                was taken off the stack), conflicted (with the conflicting files; the rebase is aborted \
+                and the branch left unchanged), skipped, or failed."
                 .to_string(),
             input_schema: json!({
                 "type": "object",
-                "properties": {}
+                "properties": {
+                    "autostash": {
+                        "type": "boolean",
+                        "description": "Stash uncommitted changes in the working tree for the sync and re-apply them afterwards. Without it, a dirty tree is refused unless the repo uses worktrees or was initialised with autostash."
+                    },
+                    "drop_empty": {
+                        "type": "boolean",
+                        "description": "Drop chunks with no changes left after rebasing from the stack"
+                    },
+                    "delete_merged": {
+                        "type": "boolean",
+                        "description": "Delete the local branches and worktrees of chunks whose PRs have merged, as well as taking them off the stack"
+                    },
+                    "resume": {
+                        "type": "boolean",
+                        "description": "Finish a sync that stopped halfway (cancelled or killed): rebase the chunks it had not reached onto the base commit it was using. Required while one is unfinished."
+                    },
+                    "merge": {
+                        "type": "boolean",
+                        "description": "Merge the base branch (stacked: the chunk below) into each chunk branch instead of rebasing, so published history is not rewritten. Always on when the repo was initialised with sync_merge."
+                    },
+                    "chunks": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Names of the chunks to rebase (default: all). In a stacked sync, chunks above one that conflicts are skipped."
+                    }
+                }
             }),
+            annotations: ToolAnnotations::destructive().idempotent().open_world(),
         },
         Tool {
             name: "merges_status".to_string(),
@@ -98,6 +374,41 @@ pub fn all_tools() -> Vec<Tool> {
                 "type": "object",
                 "properties": {}
             }),
+            annotations: ToolAnnotations::read_only().open_world(),
+        },
+        Tool {
+            name: "merges_reviews".to_string(),
+            description: "List the unresolved review threads on every chunk PR, grouped by chunk and ordered by \
+                file and line, each with its comments (author, body, link). Use it to work through the review \
+                feedback across the whole stack. GitHub only."
+                .to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {}
+            }),
+            annotations: ToolAnnotations::read_only().open_world(),
+        },
+        Tool {
+            name: "merges_hold".to_string(),
+            description: "Hold a chunk branch-only, or release it. merges_push still pushes a held chunk's branch \
+                but opens no PR for it and leaves an existing PR alone. Use for chunks (generated code, vendored \
+                dependencies) that should wait until the rest of the stack merges."
+                .to_string(),
+            input_schema: json!({
+                "type": "object",
+                "required": ["chunk"],
+                "properties": {
+                    "chunk": {
+                        "type": "string",
+                        "description": "Name of the chunk"
+                    },
+                    "release": {
+                        "type": "boolean",
+                        "description": "Release the chunk instead, so the next push opens or updates its PR"
+                    }
+                }
+            }),
+            annotations: ToolAnnotations::destructive().idempotent(),
         },
         Tool {
             name: "merges_add".to_string(),
@@ -115,10 +426,15 @@ pub fn all_tools() -> Vec<Tool> {
                     "files": {
                         "type": "array",
                         "items": { "type": "string" },
-                        "description": "Relative file paths to add to this chunk"
+                        "description": "Relative file paths, directories, or glob patterns to add to this chunk"
+                    },
+                    "autostash": {
+                        "type": "boolean",
+                        "description": "Stash uncommitted changes in the working tree for the add and re-apply them afterwards. Without it, a dirty tree is refused unless the repo uses worktrees or was initialised with autostash."
                     }
                 }
             }),
+            annotations: ToolAnnotations::additive(),
         },
         Tool {
             name: "merges_move".to_string(),
@@ -131,7 +447,7 @@ pub fn all_tools() -> Vec<Tool> {
                 "properties": {
                     "file": {
                         "type": "string",
-                        "description": "Relative path of the file to move"
+                        "description": "Relative path, directory, or glob pattern of the file(s) to move"
                     },
                     "from": {
                         "type": "string",
@@ -140,9 +456,59 @@ pub fn all_tools() -> Vec<Tool> {
                     "to": {
                         "type": "string",
                         "description": "Name of the destination chunk"
+                    },
+                    "autostash": {
+                        "type": "boolean",
+                        "description": "Stash uncommitted changes in the working tree for the move and re-apply them afterwards. Without it, a dirty tree is refused unless the repo uses worktrees or was initialised with autostash."
+                    }
+                }
+            }),
+            annotations: ToolAnnotations::destructive(),
+        },
+        Tool {
+            name: "merges_unassigned".to_string(),
+            description: "List changed files that no chunk contains yet — files added to the source branch \
+                since the split — with line counts, alongside the existing chunks. \
+                Place them with merges_refresh."
+                .to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {}
+            }),
+            annotations: ToolAnnotations::read_only(),
+        },
+        Tool {
+            name: "merges_refresh".to_string(),
+            description: "Assign unassigned changed files to existing chunks (amends their branch commits). \
+                All assignments are checked before any branch changes. Returns what was assigned and what is left."
+                .to_string(),
+            input_schema: json!({
+                "type": "object",
+                "required": ["assignments"],
+                "properties": {
+                    "assignments": {
+                        "type": "array",
+                        "description": "Files to place, per chunk",
+                        "items": {
+                            "type": "object",
+                            "required": ["chunk", "files"],
+                            "properties": {
+                                "chunk": { "type": "string", "description": "Name of an existing chunk" },
+                                "files": {
+                                    "type": "array",
+                                    "items": { "type": "string" },
+                                    "description": "Unassigned file paths, directories, or glob patterns"
+                                }
+                            }
+                        }
+                    },
+                    "autostash": {
+                        "type": "boolean",
+                        "description": "Stash uncommitted changes in the working tree for the assignment and re-apply them afterwards. Without it, a dirty tree is refused unless the repo uses worktrees or was initialised with autostash."
                     }
                 }
             }),
+            annotations: ToolAnnotations::additive(),
         },
         Tool {
             name: "merges_clean".to_string(),
@@ -154,7 +520,7 @@ pub fn all_tools() -> Vec<Tool> {
                 "properties": {
                     "merged": {
                         "type": "boolean",
-                        "description": "Only delete branches for merged/closed PRs"
+                        "description": "Only delete branches whose PRs were merged into the base branch with required checks passing"
                     },
                     "dry_run": {
                         "type": "boolean",
@@ -162,11 +528,14 @@ pub fn all_tools() -> Vec<Tool> {
                     }
                 }
             }),
+            annotations: ToolAnnotations::destructive().idempotent().open_world(),
         },
         Tool {
             name: "merges_doctor".to_string(),
             description: "Validate state consistency: branch existence, worktrees, gitignore, \
-                duplicate file assignments. Returns a JSON report. Pass repair:true to auto-fix."
+                duplicate file assignments — and the environment: git version, rerere, origin \
+                reachability, forge credentials. Returns a JSON report. Pass repair:true to auto-fix, which \
+                changes the repository: it rewrites .git/info/exclude, turns on rerere, and renames chunk branches."
                 .to_string(),
             input_schema: json!({
                 "type": "object",
@@ -177,6 +546,36 @@ pub fn all_tools() -> Vec<Tool> {
                     }
                 }
             }),
+            // Checking contacts origin and the forge; repairing renames branches.
+            annotations: ToolAnnotations::destructive().idempotent().open_world(),
+        },
+        Tool {
+            name: "merges_diffcheck".to_string(),
+            description: "Check that the chunks add up to the source branch. Returns the files the source branch \
+                changes that no chunk does (missing), files chunks change that it does not (extra), files changed \
+                by several chunks (overlapping), and files a chunk changes differently, with hunk headers (mismatched)."
+                .to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {}
+            }),
+            annotations: ToolAnnotations::read_only(),
+        },
+        Tool {
+            name: "merges_history".to_string(),
+            description: "List past merges operations (split, add, move, push, sync, drop, clean), newest first, \
+                with timestamps, affected chunks, and the resulting branch SHAs."
+                .to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "limit": {
+                        "type": "integer",
+                        "description": "Number of most recent operations to return (default: all)"
+                    }
+                }
+            }),
+            annotations: ToolAnnotations::read_only(),
         },
     ]
 }
@@ -0,0 +1,351 @@
+//! Output mode for the CLI. With the global `--json` flag, commands keep their
+//! human-readable lines (and the stdout of the git processes they run) off
+//! stdout, and `main` prints a single JSON result object instead.
+//!
+//! Under `merges mcp`, those lines — and every git or forge operation that
+//! changes something — are sent to the client as MCP log notifications.
+//! Either way commands describe what they do as [`Event`]s, which a
+//! [`Reporter`] renders; a program embedding merges as a library installs
+//! its own with [`set_reporter`].
+
+use colored::Colorize;
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::{doctor, git, state::MergesState};
+
+static JSON: AtomicBool = AtomicBool::new(false);
+
+/// Switch JSON mode on or off for the rest of the process. JSON mode also
+/// turns colours off, so anything still written to stderr stays plain text.
+pub fn set_json(on: bool) {
+    JSON.store(on, Ordering::Relaxed);
+    if on {
+        colored::control::set_override(false);
+    }
+}
+
+pub fn json() -> bool {
+    JSON.load(Ordering::Relaxed)
+}
+
+/// Severity of a log message, least severe first (the syslog levels MCP uses).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Debug,
+    Info,
+    Notice,
+    Warning,
+    Error,
+    Critical,
+    Alert,
+    Emergency,
+}
+
+/// Least severe level sent; `None` (the default) sends nothing.
+static LOG_LEVEL: Mutex<Option<Level>> = Mutex::new(None);
+
+/// Send log notifications at `level` and above on stdout from now on. Only
+/// the MCP server turns this on, since stdout carries its protocol.
+pub fn set_log_level(level: Level) {
+    *LOG_LEVEL.lock().unwrap_or_else(|e| e.into_inner()) = Some(level);
+}
+
+/// Something a command reports while it runs. The CLI renders these as
+/// lines and progress bars; JSON mode and the MCP server turn them into log
+/// messages; a program embedding merges receives them as they are.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    /// A line of human-readable output.
+    Message { text: String },
+    /// Something the user should look at; the operation carries on.
+    Warning { text: String },
+    /// A step that goes through `total` chunks began.
+    StepStarted { step: String, total: usize },
+    /// Work on `chunk` in the current step: what is happening while it runs,
+    /// then, with `done`, how it ended (empty when there is nothing to say).
+    ChunkProgress { chunk: String, message: String, done: bool },
+    /// The current step finished.
+    StepCompleted { step: String },
+    /// A git command or forge call that changes something (`info`), or a
+    /// lookup (`debug`), from `logger` ("git" or the forge's name).
+    Log { level: Level, logger: String, message: String },
+}
+
+/// Receives the events commands report.
+pub trait Reporter: Send + Sync {
+    fn report(&self, event: &Event);
+}
+
+impl<F: Fn(&Event) + Send + Sync> Reporter for F {
+    fn report(&self, event: &Event) {
+        self(event)
+    }
+}
+
+/// The terminal renderer: messages and warnings on stdout, steps as a
+/// progress bar with each chunk's outcome printed above it. Log events are
+/// not shown.
+pub struct CliReporter {
+    bar: Mutex<Option<ProgressBar>>,
+}
+
+impl Reporter for CliReporter {
+    fn report(&self, event: &Event) {
+        let mut bar = self.bar.lock().unwrap_or_else(|e| e.into_inner());
+        let line = |text: &str| match bar.as_ref() {
+            Some(bar) => bar.println(text),
+            None => println!("{}", text),
+        };
+        match event {
+            Event::Message { text } => line(text),
+            Event::Warning { text } => line(&format!("{} {}", "!".yellow().bold(), text)),
+            Event::StepStarted { total, .. } => {
+                let pb = ProgressBar::new(*total as u64);
+                pb.set_style(ProgressStyle::default_bar().template("{bar:40.cyan/blue} {pos}/{len} {msg}").unwrap());
+                if let Some(old) = bar.replace(pb) {
+                    old.finish_and_clear();
+                }
+            }
+            Event::ChunkProgress { message, done, .. } => match (bar.as_ref(), done) {
+                (Some(bar), false) => bar.set_message(message.clone()),
+                (Some(bar), true) => {
+                    bar.inc(1);
+                    if !message.is_empty() {
+                        bar.println(message);
+                    }
+                }
+                (None, true) if !message.is_empty() => println!("{}", message),
+                (None, _) => {}
+            },
+            Event::StepCompleted { .. } => {
+                if let Some(bar) = bar.take() {
+                    bar.finish_with_message("done");
+                }
+            }
+            Event::Log { .. } => {}
+        }
+    }
+}
+
+/// The JSON-mode renderer: every event becomes a log message, which the MCP
+/// server sends to its client as a `notifications/message` when logging is
+/// on at that level, and which goes nowhere otherwise.
+pub struct JsonReporter;
+
+impl Reporter for JsonReporter {
+    fn report(&self, event: &Event) {
+        match event {
+            Event::Message { text } => notify(Level::Info, "merges", text),
+            Event::Warning { text } => notify(Level::Warning, "merges", text),
+            Event::StepStarted { step, total } => notify(Level::Info, "merges", &format!("{}: {} chunk(s)", step, total)),
+            Event::ChunkProgress { chunk, message, done: false } => {
+                notify(Level::Debug, "merges", &format!("{}: {}", chunk, message))
+            }
+            Event::ChunkProgress { message, done: true, .. } if !message.is_empty() => {
+                notify(Level::Info, "merges", message)
+            }
+            Event::ChunkProgress { .. } => {}
+            Event::StepCompleted { step } => notify(Level::Debug, "merges", &format!("{}: done", step)),
+            Event::Log { level, logger, message } => notify(*level, logger, message),
+        }
+    }
+}
+
+static CLI: CliReporter = CliReporter { bar: Mutex::new(None) };
+
+static REPORTER: Mutex<Option<Arc<dyn Reporter>>> = Mutex::new(None);
+
+/// Send every event to `reporter` from now on instead of rendering it, and
+/// switch JSON mode on so nothing else reaches stdout. This is how a program
+/// using merges as a library follows progress.
+pub fn set_reporter(reporter: impl Reporter + 'static) {
+    *REPORTER.lock().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(reporter));
+    set_json(true);
+}
+
+/// Hand `event` to the reporter set with [`set_reporter`], or else to the
+/// renderer for the output mode.
+pub fn report(event: Event) {
+    let reporter = REPORTER.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    match reporter {
+        Some(reporter) => reporter.report(&event),
+        None if json() => JsonReporter.report(&event),
+        None => CLI.report(&event),
+    }
+}
+
+/// Report `message` from `logger` ("merges", "git", or the forge) as a
+/// [`Event::Log`].
+pub fn log(level: Level, logger: &str, message: &str) {
+    report(Event::Log { level, logger: logger.to_string(), message: message.to_string() });
+}
+
+/// Send `message` to the MCP client as a `notifications/message`, if
+/// logging is on at that level.
+fn notify(level: Level, logger: &str, message: &str) {
+    let min = *LOG_LEVEL.lock().unwrap_or_else(|e| e.into_inner());
+    if min.is_none_or(|min| level < min) {
+        return;
+    }
+    let note = json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/message",
+        "params": { "level": level, "logger": logger, "data": message }
+    });
+    let mut stdout = std::io::stdout().lock();
+    let _ = writeln!(stdout, "{}", note);
+    let _ = stdout.flush();
+}
+
+/// `println!` for human-readable output, reported as an [`Event::Message`]:
+/// printed on the terminal, a log message in JSON mode.
+#[macro_export]
+macro_rules! say {
+    ($($arg:tt)*) => {
+        $crate::output::report($crate::output::Event::Message { text: format!($($arg)*) })
+    };
+}
+
+/// Like [`say!`], for a warning: the terminal shows it after a yellow `!`.
+#[macro_export]
+macro_rules! warning {
+    ($($arg:tt)*) => {
+        $crate::output::report($crate::output::Event::Warning { text: format!($($arg)*) })
+    };
+}
+
+/// Report the start of a step that goes through `total` chunks.
+pub fn step_started(step: &str, total: usize) {
+    report(Event::StepStarted { step: step.to_string(), total });
+}
+
+/// Report what is happening to `chunk` in the current step.
+pub fn chunk_progress(chunk: &str, message: impl Into<String>) {
+    report(Event::ChunkProgress { chunk: chunk.to_string(), message: message.into(), done: false });
+}
+
+/// Report that `chunk` is finished in the current step, with the line to show
+/// for it (or an empty one).
+pub fn chunk_done(chunk: &str, message: impl Into<String>) {
+    report(Event::ChunkProgress { chunk: chunk.to_string(), message: message.into(), done: true });
+}
+
+pub fn step_completed(step: &str) {
+    report(Event::StepCompleted { step: step.to_string() });
+}
+
+/// Where git subprocesses should write their stdout: inherited normally,
+/// stderr in JSON mode so it cannot corrupt the result object.
+pub fn child_stdout() -> std::process::Stdio {
+    if json() {
+        std::io::stderr().into()
+    } else {
+        std::process::Stdio::inherit()
+    }
+}
+
+/// Result object for a successful `operation`: `{"ok": true, "operation": …}`
+/// plus the fields of `details`.
+pub fn success(operation: &str, details: Value) -> Value {
+    let mut result = json!({ "ok": true, "operation": operation });
+    if let (Some(result), Value::Object(details)) = (result.as_object_mut(), details) {
+        result.extend(details);
+    }
+    result
+}
+
+/// Result object for a failed command.
+pub fn failure(error: &anyhow::Error) -> Value {
+    json!({ "ok": false, "error": format!("{:#}", error) })
+}
+
+/// Print a result object on stdout.
+pub fn emit(result: &Value) {
+    println!("{}", serde_json::to_string_pretty(result).unwrap_or_default());
+}
+
+/// Per-chunk summary shared by the `--json` results and the MCP status tool.
+pub fn chunks(root: &Path, state: &MergesState) -> Vec<Value> {
+    let branches: Vec<String> = state.chunks.iter().map(|c| c.branch.clone()).collect();
+    let counts = git::branch_counts(root, &branches, &git::base_ref(root, &state.base_branch)).unwrap_or_default();
+    state
+        .chunks
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            let git::BranchCounts { behind_base: behind, upstream, tracking } = counts.get(i).cloned().unwrap_or_default();
+            json!({
+                "name": c.name,
+                "description": c.description,
+                "branch": c.branch,
+                "sha": git::rev_parse(root, &c.branch).ok(),
+                "files": c.files,
+                "files_count": c.files.len(),
+                "pr_number": c.pr_number,
+                "pr_url": c.pr_url,
+                "hold": c.hold,
+                "behind": behind,
+                "sync": git::sync_status(behind),
+                "upstream": upstream,
+                "ahead_of_remote": tracking.map(|(ahead, _)| ahead),
+                "behind_remote": tracking.map(|(_, behind)| behind),
+                "created_at": c.created_at,
+                "last_pushed_at": c.last_pushed_at,
+                "merged_at": c.merged_at
+            })
+        })
+        .collect()
+}
+
+/// The whole stack: what `merges status --json` and `merges_status` return.
+pub fn status(root: &Path, state: &MergesState) -> Value {
+    json!({
+        "source_branch": state.source_branch,
+        "source_rewritten": doctor::source_divergence(root, state).is_some(),
+        "base_branch": state.base_branch,
+        "strategy": state.strategy,
+        "chunks": chunks(root, state)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_success_merges_details_into_result() {
+        let result = success("move", json!({ "file": "src/a.rs", "to": "models" }));
+        assert_eq!(result, json!({ "ok": true, "operation": "move", "file": "src/a.rs", "to": "models" }));
+    }
+
+    #[test]
+    fn test_failure_includes_error_chain() {
+        let err = anyhow::anyhow!("root cause").context("Failed to push");
+        assert_eq!(failure(&err), json!({ "ok": false, "error": "Failed to push: root cause" }));
+    }
+
+    #[test]
+    fn test_log_levels_use_mcp_names_and_order() {
+        let level: Level = serde_json::from_value(json!("warning")).unwrap();
+        assert_eq!(level, Level::Warning);
+        assert!(Level::Debug < Level::Info && Level::Error < Level::Emergency);
+        assert_eq!(serde_json::to_value(Level::Notice).unwrap(), json!("notice"));
+    }
+
+    #[test]
+    fn test_events_serialize_tagged_with_their_kind() {
+        let event = Event::ChunkProgress { chunk: "api".to_string(), message: "rebasing".to_string(), done: false };
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            json!({ "event": "chunk_progress", "chunk": "api", "message": "rebasing", "done": false })
+        );
+    }
+}
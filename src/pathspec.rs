@@ -0,0 +1,217 @@
+//! File arguments for `add`, `move`, and chunk plans: exact paths, directories,
+//! or glob patterns, resolved against a list of candidate files (usually the
+//! changed-file list) rather than the shell or the filesystem. Also matches the
+//! exclude patterns that keep generated files out of that list.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+/// The files one argument resolved to.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Expansion {
+    pub pattern: String,
+    pub files: Vec<String>,
+}
+
+impl Expansion {
+    /// True when the argument named exactly one file by its path.
+    pub fn is_literal(&self) -> bool {
+        self.files.len() == 1 && self.files[0] == self.pattern
+    }
+
+    /// One-line report of what a directory or glob argument matched.
+    pub fn describe(&self) -> String {
+        format!("'{}' matched {} file(s): {}", self.pattern, self.files.len(), self.files.join(", "))
+    }
+}
+
+/// True when `arg` contains glob metacharacters.
+pub fn is_glob(arg: &str) -> bool {
+    arg.contains(['*', '?', '['])
+}
+
+/// Strip a leading `./` and trailing `/` so arguments compare against repo-relative paths.
+fn normalise(arg: &str) -> &str {
+    let arg = arg.strip_prefix("./").unwrap_or(arg);
+    arg.trim_end_matches('/')
+}
+
+/// Test `file` against glob `pattern`. `*` and `?` stay within one path
+/// component; `**` spans directories.
+pub fn glob_matches(pattern: &glob::Pattern, file: &str) -> bool {
+    pattern.matches_with(
+        file,
+        glob::MatchOptions {
+            case_sensitive: true,
+            require_literal_separator: true,
+            require_literal_leading_dot: false,
+        },
+    )
+}
+
+/// Resolve one argument against `candidates`:
+///
+/// - a path present in `candidates` → itself
+/// - a glob → every matching candidate
+/// - anything else → every candidate under it, treating it as a directory
+///
+/// Matches keep the order of `candidates`; an empty result means nothing matched.
+pub fn resolve(arg: &str, candidates: &[String]) -> Result<Expansion> {
+    resolve_in(arg, candidates, candidates)
+}
+
+/// Like [`resolve`], but exact paths are looked up in `all` while directories
+/// and globs only match files in `scope` — so an excluded or already-assigned
+/// file can still be named explicitly without patterns sweeping it up.
+pub fn resolve_in(arg: &str, all: &[String], scope: &[String]) -> Result<Expansion> {
+    let path = match normalise(arg) {
+        "." => "",
+        p => p,
+    };
+    let files = if all.iter().any(|c| c == path) {
+        vec![path.to_string()]
+    } else if is_glob(path) {
+        let pattern = glob::Pattern::new(path).with_context(|| format!("Invalid glob pattern '{}'", arg))?;
+        scope.iter().filter(|c| glob_matches(&pattern, c)).cloned().collect()
+    } else {
+        let prefix = format!("{}/", path);
+        scope.iter().filter(|c| path.is_empty() || c.starts_with(&prefix)).cloned().collect()
+    };
+    Ok(Expansion { pattern: normalise(arg).to_string(), files })
+}
+
+/// Resolve every argument in `args` with [`resolve_in`].
+pub fn expand_in(args: &[String], all: &[String], scope: &[String]) -> Result<Vec<Expansion>> {
+    args.iter().map(|a| resolve_in(a, all, scope)).collect()
+}
+
+/// True when exclude `pattern` covers `file`. A pattern containing `/` matches
+/// the path from the repo root (exactly, as a directory, or as a glob); one
+/// without `/` matches any path component, so `*.snap` or `node_modules` apply
+/// at every depth.
+pub fn excludes(pattern: &str, file: &str) -> bool {
+    let rooted = pattern.contains('/');
+    let pattern = normalise(pattern);
+    if file == pattern || file.starts_with(&format!("{}/", pattern)) {
+        return true;
+    }
+    let Ok(glob) = glob::Pattern::new(pattern) else { return false };
+    if rooted {
+        glob_matches(&glob, file)
+    } else {
+        file.split('/').any(|component| glob_matches(&glob, component))
+    }
+}
+
+/// Split `files` into (kept, excluded) by `patterns`, preserving order.
+pub fn partition_excluded(files: Vec<String>, patterns: &[String]) -> (Vec<String>, Vec<String>) {
+    files.into_iter().partition(|f| !patterns.iter().any(|p| excludes(p, f)))
+}
+
+/// Check that every exclude pattern is a valid glob.
+pub fn validate_patterns(patterns: &[String]) -> Result<()> {
+    for p in patterns {
+        glob::Pattern::new(normalise(p)).with_context(|| format!("Invalid exclude pattern '{}'", p))?;
+    }
+    Ok(())
+}
+
+/// Flatten expansions into one list, dropping duplicates but keeping first-seen order.
+pub fn flatten(expansions: &[Expansion]) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
+    expansions
+        .iter()
+        .flat_map(|e| e.files.iter())
+        .filter(|f| seen.insert(f.as_str()))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn changed() -> Vec<String> {
+        ["README.md", "src/api/a.rs", "src/api/b.rs", "src/api/v2/c.rs", "src/models/user.rs", "src/api.rs"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_exact_path_is_literal() {
+        let e = resolve("src/api.rs", &changed()).unwrap();
+        assert_eq!(e.files, vec!["src/api.rs"]);
+        assert!(e.is_literal());
+    }
+
+    #[test]
+    fn test_star_does_not_cross_directories() {
+        let e = resolve("src/api/*.rs", &changed()).unwrap();
+        assert_eq!(e.files, vec!["src/api/a.rs", "src/api/b.rs"]);
+        assert!(!e.is_literal());
+    }
+
+    #[test]
+    fn test_double_star_spans_directories() {
+        let e = resolve("src/**/*.rs", &changed()).unwrap();
+        assert_eq!(e.files.len(), 5);
+    }
+
+    /// `src/api` must not also pick up the sibling file `src/api.rs`.
+    #[test]
+    fn test_directory_matches_files_beneath_it() {
+        for arg in ["src/api", "src/api/", "./src/api"] {
+            let e = resolve(arg, &changed()).unwrap();
+            assert_eq!(e.files, vec!["src/api/a.rs", "src/api/b.rs", "src/api/v2/c.rs"], "{}", arg);
+        }
+    }
+
+    #[test]
+    fn test_unmatched_argument_is_empty() {
+        assert!(resolve("docs", &changed()).unwrap().files.is_empty());
+        assert!(resolve("src/*.py", &changed()).unwrap().files.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_glob_is_error() {
+        assert!(resolve("src/[a", &changed()).is_err());
+    }
+
+    #[test]
+    fn test_flatten_dedupes_overlapping_arguments() {
+        let e = expand_in(&["src/api/a.rs".to_string(), "src/api".to_string()], &changed(), &changed()).unwrap();
+        assert_eq!(flatten(&e), vec!["src/api/a.rs", "src/api/b.rs", "src/api/v2/c.rs"]);
+    }
+
+    /// Patterns sweep only the scope, but exact paths may name anything in `all`.
+    #[test]
+    fn test_resolve_in_limits_patterns_to_scope() {
+        let scope: Vec<String> = vec!["src/api/b.rs".to_string()];
+        assert_eq!(resolve_in("src/api", &changed(), &scope).unwrap().files, vec!["src/api/b.rs"]);
+        assert_eq!(resolve_in("src/api/a.rs", &changed(), &scope).unwrap().files, vec!["src/api/a.rs"]);
+    }
+
+    // ── excludes ──────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_exclude_pattern_without_slash_matches_any_depth() {
+        assert!(excludes("*.snap", "tests/__snapshots__/app.snap"));
+        assert!(excludes("vendor", "web/vendor/lib.js"));
+        assert!(!excludes("*.snap", "src/snap.rs"));
+    }
+
+    #[test]
+    fn test_exclude_pattern_with_slash_is_rooted() {
+        assert!(excludes("dist/", "dist/app.js"));
+        assert!(excludes("assets/**/*.min.js", "assets/js/app.min.js"));
+        assert!(!excludes("dist/", "web/dist/app.js"));
+    }
+
+    #[test]
+    fn test_partition_excluded_keeps_order() {
+        let (kept, excluded) = partition_excluded(changed(), &["src/api/**".to_string()]);
+        assert_eq!(kept, vec!["README.md", "src/models/user.rs", "src/api.rs"]);
+        assert_eq!(excluded.len(), 3);
+    }
+}
@@ -0,0 +1,131 @@
+//! Split presets: a chunk layout a team reaches for on every feature, kept
+//! in git config so each new split can start from it. A preset lists chunks
+//! in stack order, each with the patterns (as `--exclude` takes them) whose
+//! files it gets:
+//!
+//! ```text
+//! git config merges.preset.backend \
+//!   'migrations=migrations/**; models=src/models/**, src/schema.rs; api=src/api/**; tests=tests/**'
+//! ```
+
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+
+use crate::{git, pathspec, split::ChunkPlan};
+
+/// One chunk of a preset.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PresetChunk {
+    pub name: String,
+    pub patterns: Vec<String>,
+}
+
+/// A named preset, from `merges.preset.<name>`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Preset {
+    pub name: String,
+    pub chunks: Vec<PresetChunk>,
+}
+
+impl Preset {
+    /// The preset `name` from git config.
+    pub fn load(root: &Path, name: &str) -> Result<Preset> {
+        let key = format!("merges.preset.{}", name);
+        let Some(value) = git::config_value(root, &key) else {
+            bail!(
+                "No split preset named '{}'. Define it with \
+                 `git config {} 'migrations=migrations/**; api=src/api/**'`.",
+                name,
+                key
+            );
+        };
+        Preset::parse(name, &value)
+    }
+
+    /// Parse a preset written `<chunk>=<pattern>, <pattern>; <chunk>=...`.
+    pub fn parse(name: &str, value: &str) -> Result<Preset> {
+        let mut chunks: Vec<PresetChunk> = vec![];
+        for entry in value.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+            let (chunk, patterns) = entry
+                .split_once('=')
+                .with_context(|| format!("Preset '{}': '{}' is not <chunk>=<patterns>", name, entry))?;
+            let chunk = chunk.trim();
+            let patterns: Vec<String> =
+                patterns.split(',').map(str::trim).filter(|p| !p.is_empty()).map(String::from).collect();
+            if chunk.is_empty() || patterns.is_empty() {
+                bail!("Preset '{}': '{}' needs a chunk name and at least one pattern", name, entry);
+            }
+            if chunks.iter().any(|c| c.name == chunk) {
+                bail!("Preset '{}' names chunk '{}' twice", name, chunk);
+            }
+            for pattern in &patterns {
+                glob::Pattern::new(pattern)
+                    .with_context(|| format!("Preset '{}': '{}' is not a valid pattern", name, pattern))?;
+            }
+            chunks.push(PresetChunk { name: chunk.to_string(), patterns });
+        }
+        if chunks.is_empty() {
+            bail!("Preset '{}' has no chunks", name);
+        }
+        Ok(Preset { name: name.to_string(), chunks })
+    }
+
+    /// Assign `files` to the preset's chunks: each file to the first chunk
+    /// with a pattern covering it. Returns the chunks that got files, in
+    /// preset order, and the files no chunk took.
+    pub fn assign(&self, files: &[String]) -> (Vec<ChunkPlan>, Vec<String>) {
+        let mut plan: Vec<ChunkPlan> = self
+            .chunks
+            .iter()
+            .map(|c| ChunkPlan { name: c.name.clone(), ..Default::default() })
+            .collect();
+        let mut rest = vec![];
+        for file in files {
+            let owner = self.chunks.iter().position(|c| c.patterns.iter().any(|p| pathspec::excludes(p, file)));
+            match owner {
+                Some(i) => plan[i].files.push(file.clone()),
+                None => rest.push(file.clone()),
+            }
+        }
+        plan.retain(|c| !c.files.is_empty());
+        (plan, rest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files(list: &[&str]) -> Vec<String> {
+        list.iter().map(|f| f.to_string()).collect()
+    }
+
+    #[test]
+    fn test_assign_takes_the_first_matching_chunk_and_leaves_the_rest() {
+        let preset = Preset::parse(
+            "backend",
+            "migrations=migrations/**; models=src/models/**, src/schema.rs; api=src/api; tests=tests/**, *_test.rs;",
+        )
+        .unwrap();
+        assert_eq!(preset.chunks.len(), 4);
+        assert_eq!(preset.chunks[1].patterns, vec!["src/models/**", "src/schema.rs"]);
+
+        let (plan, rest) = preset.assign(&files(&[
+            "src/api/users.rs",
+            "migrations/001_users.sql",
+            "src/api/users_test.rs",
+            "README.md",
+            "tests/users.rs",
+        ]));
+        let names: Vec<&str> = plan.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["migrations", "api", "tests"]);
+        assert_eq!(plan[1].files, vec!["src/api/users.rs", "src/api/users_test.rs"]);
+        assert_eq!(rest, vec!["README.md"]);
+
+        assert!(Preset::parse("x", "").is_err());
+        assert!(Preset::parse("x", "api").is_err());
+        assert!(Preset::parse("x", "api=").is_err());
+        assert!(Preset::parse("x", "api=src/api; api=lib/api").is_err());
+        assert!(Preset::parse("x", "api=src/[a").is_err());
+    }
+}
@@ -0,0 +1,128 @@
+//! The GitHub Project (v2) chunk PRs are filed in, and the status each chunk's
+//! item gets as its PR moves along, so planning boards follow the stack
+//! without anyone dragging cards.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Where a chunk's PR is in its life, as far as the project status goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    /// Open and waiting for review.
+    Open,
+    /// Approved, not merged yet.
+    Approved,
+    Merged,
+}
+
+impl std::str::FromStr for Stage {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Stage> {
+        match s.trim() {
+            "open" => Ok(Stage::Open),
+            "approved" => Ok(Stage::Approved),
+            "merged" => Ok(Stage::Merged),
+            other => bail!("Unknown PR stage '{}'; use open, approved, or merged", other),
+        }
+    }
+}
+
+impl std::fmt::Display for Stage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Stage::Open => write!(f, "open"),
+            Stage::Approved => write!(f, "approved"),
+            Stage::Merged => write!(f, "merged"),
+        }
+    }
+}
+
+/// Status field options a project gets when none are configured — those of
+/// GitHub's default board.
+const DEFAULT_STATUSES: &[(Stage, &str)] = &[(Stage::Open, "In Progress"), (Stage::Merged, "Done")];
+
+/// A project chunk PRs are added to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Project {
+    /// Organisation or user owning the project.
+    pub owner: String,
+    pub number: u64,
+    /// Option of the project's Status field set for each stage; a stage left
+    /// out leaves the field alone.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub statuses: BTreeMap<Stage, String>,
+}
+
+impl Project {
+    /// Parse `owner/number` or the project's URL, with `statuses` as
+    /// [`parse_statuses`] takes them.
+    pub fn parse(project: &str, statuses: &[String]) -> Result<Project> {
+        let project = project.trim().trim_end_matches('/');
+        // https://github.com/orgs/acme/projects/12 or https://github.com/users/jo/projects/3
+        let (owner, number) = match project.rsplit_once("/projects/") {
+            Some((owner, number)) => (owner.rsplit('/').next().unwrap_or(owner), number),
+            None => project
+                .split_once('/')
+                .with_context(|| format!("Project '{}' is not owner/number or a project URL", project))?,
+        };
+        let number = number.parse().with_context(|| format!("Project number '{}' is not a number", number))?;
+        if owner.is_empty() {
+            bail!("Project '{}' names no owner", project);
+        }
+        let statuses = parse_statuses(statuses)?;
+        Ok(Project { owner: owner.to_string(), number, statuses })
+    }
+
+    /// The statuses as `merges init --project-status` takes them.
+    pub fn describe_statuses(&self) -> String {
+        self.statuses.iter().map(|(stage, option)| format!("{}={}", stage, option)).collect::<Vec<_>>().join(", ")
+    }
+}
+
+/// Parse status options written `stage=Option`; none gives GitHub's default
+/// In Progress / Done board.
+pub fn parse_statuses(statuses: &[String]) -> Result<BTreeMap<Stage, String>> {
+    if statuses.is_empty() {
+        return Ok(DEFAULT_STATUSES.iter().map(|(stage, option)| (*stage, option.to_string())).collect());
+    }
+    statuses
+        .iter()
+        .map(|s| -> Result<(Stage, String)> {
+            let (stage, option) =
+                s.split_once('=').with_context(|| format!("Project status '{}' is not stage=Option", s))?;
+            Ok((stage.parse()?, option.trim().to_string()))
+        })
+        .collect()
+}
+
+impl std::fmt::Display for Project {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.owner, self.number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_project_forms_and_statuses() {
+        let project = Project::parse("acme/12", &[]).unwrap();
+        assert_eq!((project.owner.as_str(), project.number), ("acme", 12));
+        assert_eq!(project.statuses.get(&Stage::Merged).map(String::as_str), Some("Done"));
+        assert_eq!(project.statuses.get(&Stage::Approved), None);
+
+        let statuses = vec!["approved=Ready to merge".to_string(), "merged = Shipped".to_string()];
+        let project = Project::parse("https://github.com/orgs/acme/projects/7/", &statuses).unwrap();
+        assert_eq!(project.to_string(), "acme/7");
+        assert_eq!(project.statuses.len(), 2);
+        assert_eq!(project.statuses[&Stage::Merged], "Shipped");
+
+        assert!(Project::parse("acme", &[]).is_err());
+        assert!(Project::parse("acme/board", &[]).is_err());
+        assert!(Project::parse("acme/1", &["closed=Done".to_string()]).is_err());
+    }
+}
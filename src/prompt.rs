@@ -0,0 +1,44 @@
+//! Whether commands may ask questions. Prompts need a terminal on stdin (for
+//! the answer) and on stderr (where dialoguer draws them); without one, or
+//! with the global `--no-input` flag, commands use their defaults or fail
+//! fast with a hint instead of hanging.
+
+use anyhow::{bail, Result};
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// Turn prompts off for the rest of the process (`--no-input`, MCP).
+pub fn disable() {
+    DISABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn allowed() -> bool {
+    !DISABLED.load(Ordering::Relaxed) && std::io::stdin().is_terminal() && std::io::stderr().is_terminal()
+}
+
+/// Fail unless prompting is possible. `question` names what would have been
+/// asked; `hint` says how to answer it up front.
+pub fn require(question: &str, hint: &str) -> Result<()> {
+    if !allowed() {
+        bail!("Cannot ask {} without a terminal (or with --no-input). {}", question, hint);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_prompts_fail_with_hint() {
+        disable();
+        assert!(!allowed());
+        let err = require("which files to split", "Pass --plan or --auto.").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Cannot ask which files to split without a terminal (or with --no-input). Pass --plan or --auto."
+        );
+    }
+}
@@ -0,0 +1,156 @@
+//! Routing rules: which labels each chunk PR gets and whom it asks for review,
+//! from the files the chunk changes. A rule is written
+//! `migrations/** -> label:db, reviewer:@db-team` — a path, directory, or glob
+//! as `--exclude` takes them, then what a chunk with any matching file gets.
+//! Teams are named `@org/team`.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::pathspec;
+
+/// One rule: PRs changing a file `pattern` matches get `labels` and ask `reviewers`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Route {
+    pub pattern: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+    /// Logins and `org/team` names, without the `@`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reviewers: Vec<String>,
+}
+
+impl Route {
+    /// Parse a rule written `<pattern> -> label:<name>, reviewer:@<login>, ...`.
+    pub fn parse(rule: &str) -> Result<Route> {
+        let (pattern, targets) =
+            rule.split_once("->").with_context(|| format!("Routing rule '{}' has no '->'", rule))?;
+        let pattern = pattern.trim();
+        if pattern.is_empty() {
+            bail!("Routing rule '{}' has no file pattern", rule);
+        }
+        glob::Pattern::new(pattern).with_context(|| format!("Routing rule '{}' has an invalid pattern", rule))?;
+        let mut route = Route { pattern: pattern.to_string(), labels: vec![], reviewers: vec![] };
+        for target in targets.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+            match target.split_once(':').map(|(kind, name)| (kind.trim(), name.trim())) {
+                Some(("label", name)) if !name.is_empty() => route.labels.push(name.to_string()),
+                Some(("reviewer", name)) if !name.trim_start_matches('@').is_empty() => {
+                    route.reviewers.push(name.trim_start_matches('@').to_string())
+                }
+                _ => bail!("Routing rule '{}': '{}' is not `label:<name>` or `reviewer:@<login>`", rule, target),
+            }
+        }
+        if route.labels.is_empty() && route.reviewers.is_empty() {
+            bail!("Routing rule '{}' gives no label or reviewer", rule);
+        }
+        Ok(route)
+    }
+
+    /// True when the rule covers `file`, by the rules exclude patterns follow.
+    pub fn matches(&self, file: &str) -> bool {
+        pathspec::excludes(&self.pattern, file)
+    }
+}
+
+impl std::fmt::Display for Route {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let labels = self.labels.iter().map(|l| label(l));
+        let targets: Vec<String> = labels.chain(self.reviewers.iter().map(|r| reviewer(r))).collect();
+        write!(f, "{} -> {}", self.pattern, targets.join(", "))
+    }
+}
+
+/// A label as rules write it.
+pub fn label(name: &str) -> String {
+    format!("label:{}", name)
+}
+
+/// A reviewer as rules write it.
+pub fn reviewer(login: &str) -> String {
+    format!("reviewer:@{}", login)
+}
+
+/// The labels and reviewers routing rules give one PR.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Routed {
+    pub labels: Vec<String>,
+    pub reviewers: Vec<String>,
+}
+
+impl Routed {
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty() && self.reviewers.is_empty()
+    }
+
+    /// The labels and reviewers as rules write them, for messages and for
+    /// recording what a PR already got.
+    pub fn targets(&self) -> Vec<String> {
+        self.labels.iter().map(|l| label(l)).chain(self.reviewers.iter().map(|r| reviewer(r))).collect()
+    }
+
+    /// Leave out the targets in `done`.
+    pub fn without(self, done: &[String]) -> Routed {
+        Routed {
+            labels: self.labels.into_iter().filter(|l| !done.contains(&label(l))).collect(),
+            reviewers: self.reviewers.into_iter().filter(|r| !done.contains(&reviewer(r))).collect(),
+        }
+    }
+}
+
+/// What `routes` give a PR changing `files`: each label and reviewer once,
+/// in rule order.
+pub fn route(routes: &[Route], files: &[String]) -> Routed {
+    let mut routed = Routed::default();
+    for rule in routes.iter().filter(|r| files.iter().any(|f| r.matches(f))) {
+        for label in &rule.labels {
+            if !routed.labels.contains(label) {
+                routed.labels.push(label.clone());
+            }
+        }
+        for reviewer in &rule.reviewers {
+            if !routed.reviewers.contains(reviewer) {
+                routed.reviewers.push(reviewer.clone());
+            }
+        }
+    }
+    routed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files(list: &[&str]) -> Vec<String> {
+        list.iter().map(|f| f.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_rule() {
+        let route = Route::parse("migrations/** -> label:db, reviewer:@acme/db-team, reviewer:alice").unwrap();
+        assert_eq!(route.pattern, "migrations/**");
+        assert_eq!(route.labels, vec!["db"]);
+        assert_eq!(route.reviewers, vec!["acme/db-team", "alice"]);
+        assert_eq!(route.to_string(), "migrations/** -> label:db, reviewer:@acme/db-team, reviewer:@alice");
+
+        assert!(Route::parse("migrations/**").is_err());
+        assert!(Route::parse("migrations/** -> owner:bob").is_err());
+        assert!(Route::parse("migrations/** -> ").is_err());
+        assert!(Route::parse("src/[a -> label:x").is_err());
+    }
+
+    #[test]
+    fn test_route_merges_matching_rules_once() {
+        let routes = vec![
+            Route::parse("migrations -> label:db, reviewer:@acme/db-team").unwrap(),
+            Route::parse("*.sql -> label:db, label:sql").unwrap(),
+            Route::parse("web/** -> reviewer:@frontend").unwrap(),
+        ];
+        let routed = route(&routes, &files(&["migrations/001.sql", "src/lib.rs"]));
+        assert_eq!(routed.labels, vec!["db", "sql"]);
+        assert_eq!(routed.reviewers, vec!["acme/db-team"]);
+        assert!(route(&routes, &files(&["src/lib.rs"])).is_empty());
+
+        let rest = routed.without(&["label:db".to_string(), "reviewer:@acme/db-team".to_string()]);
+        assert_eq!(rest.targets(), vec!["label:sql"]);
+    }
+}
@@ -0,0 +1,110 @@
+//! Chunk size limits, so a stack split to keep PRs reviewable stays that
+//! way: past a soft limit `merges push` warns, past a hard one it refuses.
+//! Size is counted as the chunk's PR shows it — lines added plus removed,
+//! and files changed — from where the chunk leaves its parent.
+
+use serde::{Deserialize, Serialize};
+
+use crate::git::FileStat;
+
+/// Limits set with `merges init --warn-lines/--max-lines/--warn-files/--max-files`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SizeLimits {
+    /// Lines changed past which push warns.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warn_lines: Option<u64>,
+    /// Lines changed past which push refuses.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_lines: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warn_files: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_files: Option<u64>,
+}
+
+impl SizeLimits {
+    pub fn is_empty(&self) -> bool {
+        *self == SizeLimits::default()
+    }
+
+    /// The limits set, as `warn 400 / max 1000 lines, max 30 files`.
+    pub fn describe(&self) -> String {
+        let pair = |warn: Option<u64>, max: Option<u64>, unit: &str| match (warn, max) {
+            (Some(warn), Some(max)) => Some(format!("warn {} / max {} {}", warn, max, unit)),
+            (Some(warn), None) => Some(format!("warn {} {}", warn, unit)),
+            (None, Some(max)) => Some(format!("max {} {}", max, unit)),
+            (None, None) => None,
+        };
+        [pair(self.warn_lines, self.max_lines, "lines"), pair(self.warn_files, self.max_files, "files")]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// How big a chunk is.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Size {
+    /// Lines added plus removed; binary files count none.
+    pub lines: u64,
+    pub files: u64,
+}
+
+impl Size {
+    pub fn of(stats: &[FileStat]) -> Size {
+        Size {
+            lines: stats.iter().map(|s| (s.added.unwrap_or(0) + s.removed.unwrap_or(0)) as u64).sum(),
+            files: stats.len() as u64,
+        }
+    }
+}
+
+/// A limit a chunk goes over.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Breach {
+    /// A hard limit, which stops the push.
+    pub hard: bool,
+    /// What went over, as `1520 lines changed (limit 1000)`.
+    pub detail: String,
+}
+
+/// The limits `size` goes over: for lines and for files, the hard limit if
+/// it is passed, else the soft one.
+pub fn check(limits: &SizeLimits, size: Size) -> Vec<Breach> {
+    let measures = [
+        (size.lines, limits.warn_lines, limits.max_lines, "lines changed"),
+        (size.files, limits.warn_files, limits.max_files, "files"),
+    ];
+    measures
+        .into_iter()
+        .filter_map(|(value, warn, max, unit)| {
+            let (hard, limit) = match (warn, max) {
+                (_, Some(max)) if value > max => (true, max),
+                (Some(warn), _) if value > warn => (false, warn),
+                _ => return None,
+            };
+            Some(Breach { hard, detail: format!("{} {} (limit {})", value, unit, limit) })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_reports_the_strictest_limit_passed() {
+        let limits =
+            SizeLimits { warn_lines: Some(400), max_lines: Some(1000), max_files: Some(20), ..Default::default() };
+        assert!(check(&limits, Size { lines: 400, files: 20 }).is_empty());
+
+        let breaches = check(&limits, Size { lines: 401, files: 3 });
+        assert_eq!(breaches, vec![Breach { hard: false, detail: "401 lines changed (limit 400)".to_string() }]);
+
+        let breaches = check(&limits, Size { lines: 1500, files: 21 });
+        assert_eq!(breaches.iter().filter(|b| b.hard).count(), 2);
+        assert_eq!(breaches[1].detail, "21 files (limit 20)");
+        assert_eq!(limits.describe(), "warn 400 / max 1000 lines, max 20 files");
+    }
+}
@@ -2,20 +2,200 @@
 //! Used by both the TUI command and the MCP tool.
 
 use anyhow::{bail, Result};
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
 use crate::{
-    git,
-    state::{Chunk, MergesState},
+    git, journal, output,
+    pathspec::{self, Expansion},
+    say,
+    state::{Chunk, MergesState, Strategy},
+    summary,
 };
 
 /// Describes one chunk in a plan: a name and the files it should contain.
 /// This is the serialisable struct consumed by `apply_plan` and the MCP tool.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// `files` entries may be paths, directories, or glob patterns.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ChunkPlan {
     pub name: String,
     pub files: Vec<String>,
+    /// Why the chunk exists — the lead paragraph of its generated PR body.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Chunk commit message to use instead of "chunk N - name" and the file list.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit_message: Option<String>,
+    /// PR title to use instead of the chunk name (stack position and ticket
+    /// prefix are still added).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pr_title: Option<String>,
+    /// PR description to use instead of the generated chunk summary (the stack
+    /// table is still appended).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pr_body: Option<String>,
+    /// The chunk needs no CI; see [`Chunk::skip_ci`].
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub skip_ci: bool,
+}
+
+/// How `merges split --auto` groups changed files into chunks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum Grouping {
+    /// By directory structure (see [`auto_group_files`]).
+    #[default]
+    Directory,
+    /// By language, from the file extension (see [`group_by_language`]).
+    Language,
+    /// By how often files changed together in past commits (see [`group_by_history`]).
+    History,
+    /// By the first two directories, named after both (see [`group_by_two_levels`]).
+    TwoLevel,
+}
+
+impl std::fmt::Display for Grouping {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Grouping::Directory => write!(f, "directory"),
+            Grouping::Language => write!(f, "language"),
+            Grouping::History => write!(f, "history"),
+            Grouping::TwoLevel => write!(f, "two-level"),
+        }
+    }
+}
+
+/// Commits read from the source branch's history for [`Grouping::History`].
+pub const HISTORY_DEPTH: usize = 1000;
+
+/// Where `merges split --auto` puts files at the repository root
+/// (`Cargo.toml`, `README.md`, …) instead of a chunk of their own named
+/// `root`, which often cannot merge before the code it goes with.
+/// Set via `merges init --root-files`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RootFiles {
+    /// Into the chunk of this name, created last if grouping gave none.
+    Chunk(String),
+    /// Into the last chunk of the stack.
+    Last,
+    /// Each into the chunk most often committed together with it, else the
+    /// first chunk.
+    Related,
+}
+
+impl std::str::FromStr for RootFiles {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<RootFiles> {
+        match s.trim() {
+            "last" => Ok(RootFiles::Last),
+            "related" => Ok(RootFiles::Related),
+            other => match other.strip_prefix("chunk:").map(str::trim) {
+                Some(name) if !name.is_empty() => Ok(RootFiles::Chunk(name.to_string())),
+                _ => bail!("Unknown root file placement '{}'; use last, related, or chunk:<name>", other),
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for RootFiles {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RootFiles::Chunk(name) => write!(f, "chunk:{}", name),
+            RootFiles::Last => write!(f, "last"),
+            RootFiles::Related => write!(f, "related"),
+        }
+    }
+}
+
+/// Group `files` into chunks the way `by` says. History grouping reads the
+/// source branch's log; the other groupings only look at the paths. A `depth`
+/// fixes the directory level grouped on (see [`group_by_depth`]) instead of
+/// the one-or-two-level heuristic; only directory grouping accepts one.
+/// Files at the repository root are then placed as the state's `root_files`
+/// says (see [`place_root_files`]).
+pub fn auto_group(
+    root: &std::path::Path,
+    state: &MergesState,
+    files: &[String],
+    by: Grouping,
+    depth: Option<usize>,
+) -> Result<Vec<ChunkPlan>> {
+    if depth == Some(0) {
+        bail!("--depth must be at least 1 (1 groups by top-level directory).");
+    }
+    if depth.is_some() && by != Grouping::Directory {
+        bail!("--depth only applies to directory grouping, not --by={}.", by);
+    }
+    let plan = match by {
+        Grouping::Directory => match depth {
+            Some(depth) => group_by_depth(files, depth),
+            None => auto_group_files(files),
+        },
+        Grouping::Language => group_by_language(files),
+        Grouping::TwoLevel => group_by_two_levels(files),
+        Grouping::History => {
+            let commits = git::commit_files(root, &state.source_branch, HISTORY_DEPTH)?;
+            group_by_history(files, &commits)
+        }
+    };
+    Ok(match &state.root_files {
+        None => plan,
+        Some(RootFiles::Related) => {
+            let commits = git::commit_files(root, &state.source_branch, HISTORY_DEPTH)?;
+            place_root_files(plan, &RootFiles::Related, &commits)
+        }
+        Some(how) => place_root_files(plan, how, &[]),
+    })
+}
+
+/// Move the files at the repository root out of the `root` chunk of `plan`
+/// the way `how` says; `commits` lists each source commit's files, for
+/// [`RootFiles::Related`]. Files with nowhere else to go stay, and the `root`
+/// chunk is dropped once empty. Pure: no git or filesystem access.
+pub fn place_root_files(mut plan: Vec<ChunkPlan>, how: &RootFiles, commits: &[Vec<String>]) -> Vec<ChunkPlan> {
+    let Some(r) = plan.iter().position(|c| c.name == "root") else {
+        return plan;
+    };
+    let others: Vec<usize> = (0..plan.len()).filter(|&i| i != r).collect();
+    let named = match how {
+        RootFiles::Chunk(name) => Some(plan.iter().position(|c| c.name == *name).unwrap_or_else(|| {
+            plan.push(ChunkPlan { name: name.clone(), ..Default::default() });
+            plan.len() - 1
+        })),
+        _ => None,
+    };
+    let (moving, staying): (Vec<String>, Vec<String>) =
+        std::mem::take(&mut plan[r].files).into_iter().partition(|f| !f.contains('/'));
+    plan[r].files = staying;
+    for file in moving {
+        let to = match how {
+            RootFiles::Chunk(_) => named,
+            RootFiles::Last => others.last().copied(),
+            RootFiles::Related => related_chunk(&plan, &others, &file, commits).or(others.first().copied()),
+        };
+        plan[to.unwrap_or(r)].files.push(file);
+    }
+    plan.retain(|c| !c.files.is_empty());
+    for chunk in &mut plan {
+        chunk.files.sort();
+    }
+    plan
+}
+
+/// Of `candidates`, the chunk in `plan` sharing the most commits with
+/// `file`; ties go to the earlier chunk.
+fn related_chunk(plan: &[ChunkPlan], candidates: &[usize], file: &str, commits: &[Vec<String>]) -> Option<usize> {
+    let with_file: Vec<&Vec<String>> =
+        commits.iter().filter(|c| c.len() <= MAX_COMMIT_FILES && c.iter().any(|f| f == file)).collect();
+    candidates
+        .iter()
+        .map(|&i| (i, with_file.iter().filter(|c| c.iter().any(|f| plan[i].files.contains(f))).count()))
+        .filter(|&(_, shared)| shared > 0)
+        .max_by_key(|&(i, shared)| (shared, std::cmp::Reverse(i)))
+        .map(|(i, _)| i)
 }
 
 /// Automatically group `files` into chunks by directory structure.
@@ -33,12 +213,114 @@ pub struct ChunkPlan {
 ///
 /// This is a pure function with no git or filesystem side-effects — easy to test.
 pub fn auto_group_files(files: &[String]) -> Vec<ChunkPlan> {
+    let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (file, key) in files.iter().zip(directory_keys(files)) {
+        groups.entry(key).or_default().push(file.clone());
+    }
+
+    groups
+        .into_iter()
+        .map(|(name, mut files)| {
+            files.sort();
+            ChunkPlan { name, files, ..Default::default() }
+        })
+        .collect()
+}
+
+/// Group `files` by their first `depth` directories: with depth 3,
+/// `services/payments/internal/core/ledger.rs` lands in `internal`. Files in
+/// shallower directories group on the directory they are in, and files at the
+/// repository root go into `root`.
+///
+/// Chunks are named after the last of those directories; when two share that
+/// name (`payments/internal`, `billing/internal`), both use the whole path
+/// joined with `-` (`payments-internal`). Chunks and the files within them are
+/// sorted. Pure: no git or filesystem access.
+pub fn group_by_depth(files: &[String], depth: usize) -> Vec<ChunkPlan> {
     use std::collections::HashSet;
 
-    if files.is_empty() {
-        return vec![];
+    let mut prefixes: BTreeMap<Vec<String>, Vec<String>> = BTreeMap::new();
+    for file in files {
+        let mut dirs: Vec<String> = std::path::Path::new(file)
+            .components()
+            .filter_map(|c| match c {
+                std::path::Component::Normal(s) => s.to_str().map(str::to_string),
+                _ => None,
+            })
+            .collect();
+        dirs.pop();
+        dirs.truncate(depth);
+        prefixes.entry(dirs).or_default().push(file.clone());
+    }
+
+    let mut seen = HashSet::new();
+    let clashing: HashSet<String> = prefixes
+        .keys()
+        .filter_map(|p| p.last().cloned())
+        .filter(|name| !seen.insert(name.clone()))
+        .collect();
+
+    let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (prefix, files) in prefixes {
+        let name = match prefix.last() {
+            None => "root".to_string(),
+            Some(last) if clashing.contains(last) => prefix.join("-"),
+            Some(last) => last.clone(),
+        };
+        groups.entry(name).or_default().extend(files);
+    }
+
+    groups
+        .into_iter()
+        .map(|(name, mut files)| {
+            files.sort();
+            ChunkPlan { name, files, ..Default::default() }
+        })
+        .collect()
+}
+
+/// Group `files` by their first two directories, naming each chunk after
+/// both joined with `-`: `backend/api/users.rs` lands in `backend-api`, and
+/// `frontend/components/Nav.tsx` in `frontend-components`. Files one level
+/// deep group on their directory (`backend`), and files at the repository
+/// root go into `root`. When two directories would give the same name
+/// (`backend-api/` and `backend/api/`), the later one gets a `-2` suffix.
+///
+/// Chunks and the files within them are sorted. Pure: no git or filesystem access.
+pub fn group_by_two_levels(files: &[String]) -> Vec<ChunkPlan> {
+    let mut prefixes: BTreeMap<Vec<&str>, Vec<String>> = BTreeMap::new();
+    for file in files {
+        let mut dirs: Vec<&str> = file.split('/').filter(|c| !c.is_empty() && *c != ".").collect();
+        dirs.pop();
+        dirs.truncate(2);
+        prefixes.entry(dirs).or_default().push(file.clone());
+    }
+
+    let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (prefix, files) in prefixes {
+        let base = if prefix.is_empty() { "root".to_string() } else { prefix.join("-") };
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while groups.contains_key(&name) {
+            name = format!("{}-{}", base, suffix);
+            suffix += 1;
+        }
+        groups.insert(name, files);
     }
 
+    groups
+        .into_iter()
+        .map(|(name, mut files)| {
+            files.sort();
+            ChunkPlan { name, files, ..Default::default() }
+        })
+        .collect()
+}
+
+/// The directory chunk each of `files` falls in under [`auto_group_files`], in order.
+fn directory_keys(files: &[String]) -> Vec<String> {
+    use std::collections::HashSet;
+
     // Compute the top-level directory (or "root") for each file
     let top_dirs: HashSet<String> = files.iter().map(|f| top_dir(f)).collect();
     let non_root_tops: Vec<&String> = top_dirs.iter().filter(|d| d.as_str() != "root").collect();
@@ -46,21 +328,224 @@ pub fn auto_group_files(files: &[String]) -> Vec<ChunkPlan> {
     // If there is exactly one non-root top-level dir, look one level deeper
     let use_second_level = non_root_tops.len() == 1;
 
+    files.iter().map(|file| grouping_key(file, use_second_level)).collect()
+}
+
+/// Commits touching more files than this say nothing about which files belong
+/// together (bulk renames, reformatting) and are ignored by [`group_by_history`].
+const MAX_COMMIT_FILES: usize = 50;
+
+/// Two files are linked when at least this many commits changed both…
+const MIN_CO_CHANGES: usize = 2;
+
+/// …and those commits are at least this share of the commits changing either.
+const MIN_AFFINITY: f64 = 0.3;
+
+/// Group `files` by co-change affinity: files that were often modified in the
+/// same commits (`commits` lists each commit's files, as from
+/// [`git::commit_files`]) end up in the same chunk.
+///
+/// Linked files are clustered transitively; each cluster is named after the
+/// directory chunk most of its files would get from [`auto_group_files`].
+/// Files without enough history — typically files new on the branch — join the
+/// cluster sharing their directory name, or form a directory chunk of their own.
+///
+/// Chunks and the files within them are sorted. Pure: no git or filesystem access.
+pub fn group_by_history(files: &[String], commits: &[Vec<String>]) -> Vec<ChunkPlan> {
+    let index: std::collections::HashMap<&str, usize> =
+        files.iter().enumerate().map(|(i, f)| (f.as_str(), i)).collect();
+    let n = files.len();
+
+    let mut touched = vec![0usize; n];
+    let mut together: BTreeMap<(usize, usize), usize> = BTreeMap::new();
+    for commit in commits.iter().filter(|c| c.len() <= MAX_COMMIT_FILES) {
+        let mut ids: Vec<usize> = commit.iter().filter_map(|f| index.get(f.as_str()).copied()).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        for (k, &a) in ids.iter().enumerate() {
+            touched[a] += 1;
+            for &b in &ids[k + 1..] {
+                *together.entry((a, b)).or_default() += 1;
+            }
+        }
+    }
+
+    // Union-find over the linked pairs.
+    let mut cluster: Vec<usize> = (0..n).collect();
+    fn find(cluster: &mut [usize], i: usize) -> usize {
+        let mut root = i;
+        while cluster[root] != root {
+            root = cluster[root];
+        }
+        cluster[i] = root;
+        root
+    }
+    for (&(a, b), &count) in &together {
+        let either = touched[a] + touched[b] - count;
+        if count >= MIN_CO_CHANGES && count as f64 / either as f64 >= MIN_AFFINITY {
+            let (ra, rb) = (find(&mut cluster, a), find(&mut cluster, b));
+            cluster[ra.max(rb)] = ra.min(rb);
+        }
+    }
+
+    let keys = directory_keys(files);
+    let mut members: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for i in 0..n {
+        let root = find(&mut cluster, i);
+        members.entry(root).or_default().push(i);
+    }
+
+    // Name clusters after their most common directory key; singletons wait.
     let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
-    for file in files {
-        let key = grouping_key(file, use_second_level);
-        groups.entry(key).or_default().push(file.clone());
+    let mut loose = vec![];
+    for ids in members.into_values() {
+        if ids.len() == 1 {
+            loose.push(ids[0]);
+            continue;
+        }
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for &i in &ids {
+            *counts.entry(keys[i].as_str()).or_default() += 1;
+        }
+        let best = counts.values().copied().max().unwrap_or_default();
+        let base = counts.iter().find(|(_, c)| **c == best).map(|(k, _)| k.to_string()).unwrap_or_default();
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while groups.contains_key(&name) {
+            name = format!("{}-{}", base, suffix);
+            suffix += 1;
+        }
+        groups.insert(name, ids.iter().map(|&i| files[i].clone()).collect());
+    }
+    for i in loose {
+        groups.entry(keys[i].clone()).or_default().push(files[i].clone());
     }
 
     groups
         .into_iter()
         .map(|(name, mut files)| {
             files.sort();
-            ChunkPlan { name, files }
+            ChunkPlan { name, files, ..Default::default() }
+        })
+        .collect()
+}
+
+/// Group `files` into one chunk per language, named after it (`sql`,
+/// `typescript`, `docs`, …), so that say all migrations land in one chunk and
+/// all frontend components in another. See [`language_of`] for the mapping.
+///
+/// Chunks and the files within them are sorted, as with [`auto_group_files`].
+pub fn group_by_language(files: &[String]) -> Vec<ChunkPlan> {
+    let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for file in files {
+        groups.entry(language_of(file)).or_default().push(file.clone());
+    }
+    groups
+        .into_iter()
+        .map(|(name, mut files)| {
+            files.sort();
+            ChunkPlan { name, files, ..Default::default() }
         })
         .collect()
 }
 
+/// Language group for a file, from its extension. Related extensions share a
+/// group (`.ts`/`.tsx`, `.yml`/`.toml`/`.json` as `config`); unknown extensions
+/// are their own group, and files without one are `other`.
+pub fn language_of(file: &str) -> String {
+    let path = std::path::Path::new(file);
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or(file);
+    match name {
+        "Dockerfile" | "Containerfile" => return "docker".to_string(),
+        "Makefile" | "CMakeLists.txt" | "Justfile" => return "build".to_string(),
+        _ => {}
+    }
+    let Some(ext) = path.extension().and_then(|e| e.to_str()) else {
+        return "other".to_string();
+    };
+    let ext = ext.to_ascii_lowercase();
+    let language = match ext.as_str() {
+        "rs" => "rust",
+        "ts" | "tsx" | "mts" | "cts" => "typescript",
+        "js" | "jsx" | "mjs" | "cjs" => "javascript",
+        "css" | "scss" | "sass" | "less" => "styles",
+        "html" | "htm" => "html",
+        "sql" => "sql",
+        "py" | "pyi" => "python",
+        "go" => "go",
+        "java" => "java",
+        "kt" | "kts" => "kotlin",
+        "rb" | "erb" => "ruby",
+        "c" | "h" => "c",
+        "cc" | "cpp" | "cxx" | "hpp" | "hh" => "cpp",
+        "cs" => "csharp",
+        "sh" | "bash" | "zsh" => "shell",
+        "md" | "mdx" | "rst" | "adoc" | "txt" => "docs",
+        "json" | "yaml" | "yml" | "toml" | "ini" | "xml" | "lock" => "config",
+        "graphql" | "gql" => "graphql",
+        "tf" | "tfvars" => "terraform",
+        other => other,
+    };
+    language.to_string()
+}
+
+/// Split chunks with more than `max_files` files into consecutive parts named
+/// `name-1`, `name-2`, … in file order.
+pub fn cap_chunk_size(plan: Vec<ChunkPlan>, max_files: usize) -> Vec<ChunkPlan> {
+    let max_files = max_files.max(1);
+    let mut capped = vec![];
+    for chunk in plan {
+        if chunk.files.len() <= max_files {
+            capped.push(chunk);
+            continue;
+        }
+        for (part, files) in chunk.files.chunks(max_files).enumerate() {
+            capped.push(ChunkPlan {
+                name: format!("{}-{}", chunk.name, part + 1),
+                files: files.to_vec(),
+                ..chunk.clone()
+            });
+        }
+    }
+    capped
+}
+
+/// Merge the chunks at `indices` into one chunk called `name`, in place of the
+/// first of them. Its files are sorted; other fields come from that first chunk.
+pub fn merge_chunks(plan: &mut Vec<ChunkPlan>, indices: &[usize], name: String) {
+    let mut indices = indices.to_vec();
+    indices.sort_unstable();
+    indices.dedup();
+    let Some(&first) = indices.first() else { return };
+
+    let mut files = vec![];
+    for &i in indices.iter().skip(1).rev() {
+        files.extend(plan.remove(i).files);
+    }
+    let target = &mut plan[first];
+    target.name = name;
+    target.files.extend(files);
+    target.files.sort();
+}
+
+/// Move `files` from wherever they are in `plan` to the chunk at `to`. Chunks
+/// left without files are removed.
+pub fn move_files(plan: &mut Vec<ChunkPlan>, files: &[String], to: usize) {
+    for (i, chunk) in plan.iter_mut().enumerate() {
+        if i != to {
+            chunk.files.retain(|f| !files.contains(f));
+        }
+    }
+    let target = &mut plan[to].files;
+    for file in files {
+        if !target.contains(file) {
+            target.push(file.clone());
+        }
+    }
+    target.sort();
+    plan.retain(|chunk| !chunk.files.is_empty());
+}
+
 /// Return the first path component, or "root" for files with no parent directory.
 fn top_dir(file: &str) -> String {
     let path = std::path::Path::new(file);
@@ -104,9 +589,19 @@ fn grouping_key(file: &str, use_second_level: bool) -> String {
     }
 }
 
+/// Changed files between the base branch and HEAD, split into (included,
+/// excluded) by the state's exclude patterns. Excluded files are returned so
+/// callers can list them rather than drop them silently.
+pub fn changed_files(root: &std::path::Path, state: &MergesState) -> Result<(Vec<String>, Vec<String>)> {
+    let changed = git::changed_files(root, &state.base_branch)?;
+    Ok(pathspec::partition_excluded(changed, &state.exclude))
+}
+
 /// Apply a pre-built chunk plan to the repository atomically:
-/// 1. Validates that all files in the plan are actually in the diff vs base.
-/// 2. For each chunk, creates a branch from the merge-base, cherry-picks files, commits.
+/// 1. Expands directories and globs, then validates that all files in the plan
+///    are actually in the diff vs base.
+/// 2. For each chunk, creates a branch from the merge-base and commits its files
+///    from the source (see `commit_chunk`).
 /// 3. Returns to the original source branch.
 /// 4. Saves chunk definitions to the state file.
 ///
@@ -115,7 +610,10 @@ fn grouping_key(file: &str, use_second_level: bool) -> String {
 ///
 /// This is the testable core of `merges split`, used by both the interactive TUI
 /// and the MCP `merges_split` tool.
-pub fn apply_plan(root: &std::path::Path, plan: Vec<ChunkPlan>) -> Result<()> {
+///
+/// Returns the expansions of directory and glob entries so callers can report
+/// what they matched.
+pub fn apply_plan(root: &std::path::Path, plan: Vec<ChunkPlan>) -> Result<Vec<Expansion>> {
     if plan.is_empty() {
         bail!("Chunk plan is empty — provide at least one chunk with files.");
     }
@@ -123,18 +621,13 @@ pub fn apply_plan(root: &std::path::Path, plan: Vec<ChunkPlan>) -> Result<()> {
     let mut state = MergesState::load(root)?;
     let source_branch = state.source_branch.clone();
     let base_branch = state.base_branch.clone();
-    // Use explicit commit_prefix if set, otherwise auto-detect from source branch name.
-    let effective_prefix = state
-        .commit_prefix
-        .clone()
-        .or_else(|| git::ticket_prefix(&source_branch))
-        .unwrap_or_default();
-
     // Ensure .merges.json won't block branch checkouts (it must be gitignored)
     git::ensure_gitignored(root, ".merges.json")?;
 
     // Validate ALL files upfront before touching any branches
     let changed = git::changed_files(root, &base_branch)?;
+    let (included, _) = pathspec::partition_excluded(changed.clone(), &state.exclude);
+    let (plan, expansions) = expand_plan(plan, &changed, &included, &state)?;
 
     // 1. All files must be in the diff
     for chunk in &plan {
@@ -181,18 +674,33 @@ pub fn apply_plan(root: &std::path::Path, plan: Vec<ChunkPlan>) -> Result<()> {
         }
     }
 
+    // 4. No chunk branch may clash with a branch that already exists
+    for (i, chunk) in plan.iter().enumerate() {
+        let branch = state.chunk_branch(state.chunks.len() + i + 1, &chunk.name);
+        if let Some(clash) = branch_collision(root, &branch) {
+            bail!(
+                "Cannot create branch '{}' for chunk '{}': {}. Rename the chunk, remove that branch, \
+                 or set a branch namespace with `merges init --branch-namespace <name>`.",
+                branch,
+                chunk.name,
+                clash
+            );
+        }
+    }
+
     let base_sha = git::merge_base(root, &base_branch)?;
     let use_worktrees = state.use_worktrees;
 
     // Track branches we create so we can roll them back on failure.
     let mut created_branches: Vec<String> = Vec::new();
 
+    output::step_started("split", plan.len());
     let result = (|| -> Result<Vec<Chunk>> {
         let mut new_chunks = Vec::new();
         for chunk_plan in &plan {
             let n = state.chunks.len() + new_chunks.len() + 1;
-            let safe_name = chunk_plan.name.to_lowercase().replace(' ', "-");
-            let branch = format!("{}-chunk-{}-{}", source_branch, n, safe_name);
+            let branch = state.chunk_branch(n, &chunk_plan.name);
+            output::chunk_progress(&chunk_plan.name, format!("creating '{}'…", branch));
 
             let work_dir: std::path::PathBuf = if use_worktrees {
                 git::add_worktree(root, &branch, &base_sha)?;
@@ -203,20 +711,7 @@ pub fn apply_plan(root: &std::path::Path, plan: Vec<ChunkPlan>) -> Result<()> {
             };
             created_branches.push(branch.clone());
 
-            git::checkout_files_from(&work_dir, &source_branch, &chunk_plan.files)?;
-
-            let body = format!(
-                "chunk {} - {}\n\nFiles:\n{}",
-                n,
-                chunk_plan.name,
-                chunk_plan.files.join("\n")
-            );
-            let msg = if effective_prefix.is_empty() {
-                body
-            } else {
-                format!("{} {}", effective_prefix, body)
-            };
-            git::commit_all(&work_dir, &msg)?;
+            commit_chunk(&work_dir, &state, &base_sha, n, &chunk_plan.name, &chunk_plan.files, chunk_plan.commit_message.as_deref())?;
 
             // Classic mode: return to source branch after each chunk
             if !use_worktrees {
@@ -227,18 +722,45 @@ pub fn apply_plan(root: &std::path::Path, plan: Vec<ChunkPlan>) -> Result<()> {
                 name: chunk_plan.name.clone(),
                 branch,
                 files: chunk_plan.files.clone(),
+                description: chunk_plan.description.clone(),
                 pr_number: None,
                 pr_url: None,
+                pushed_sha: None,
+                pr_title: None,
+                custom_commit_message: chunk_plan.commit_message.clone(),
+                custom_pr_title: chunk_plan.pr_title.clone(),
+                custom_pr_body: chunk_plan.pr_body.clone(),
+                skip_ci: chunk_plan.skip_ci,
+                hold: false,
+                created_at: Some(Utc::now()),
+                last_pushed_at: None,
+                merged_at: None,
+                stack_comment_id: None,
+                routed: vec![],
+                milestone: None,
+                project_item_id: None,
+                project_status: None,
             });
+            output::chunk_done(&chunk_plan.name, "");
         }
         Ok(new_chunks)
     })();
+    output::step_completed("split");
 
     match result {
         Ok(new_chunks) => {
+            let summary = format!("{} chunk(s) from '{}'", new_chunks.len(), source_branch);
+            journal::record(root, "split", &summary, &new_chunks)?;
+            // Keep the recorded commit if the source was rewritten since, so
+            // the divergence stays visible.
+            if state.source_sha.as_ref().is_none_or(|sha| git::is_ancestor(root, sha, &source_branch)) {
+                state.source_sha = Some(git::rev_parse(root, &source_branch)?);
+            }
             state.chunks.extend(new_chunks);
             state.save(root)?;
-            Ok(())
+            let summary = summary::write(root, &state)?;
+            say!("Review summary for the chunks: {}", summary.display());
+            Ok(expansions)
         }
         Err(e) => {
             // Rollback: clean up any branches/worktrees we created.
@@ -255,3 +777,248 @@ pub fn apply_plan(root: &std::path::Path, plan: Vec<ChunkPlan>) -> Result<()> {
         }
     }
 }
+
+/// Commit chunk `n`'s `files` as they are on the source branch onto the chunk
+/// branch checked out in `work_dir`, which sits at `base_sha`. By default that
+/// is one commit with [`commit_message`]. With `preserve_history` each source
+/// commit since `base_sha` that touches the files is replayed, restricted to
+/// them, with its own message and author; changes no such commit carries (say,
+/// from a merge commit) go into a last commit with the generated message.
+fn commit_chunk(
+    work_dir: &std::path::Path,
+    state: &MergesState,
+    base_sha: &str,
+    n: usize,
+    name: &str,
+    files: &[String],
+    custom: Option<&str>,
+) -> Result<()> {
+    if state.preserve_history {
+        for commit in git::commits_touching(work_dir, base_sha, &state.source_branch, files)? {
+            git::replay_commit(work_dir, &commit, files)?;
+        }
+    }
+    // The index now holds the source's entries exactly; re-staging the
+    // working tree would run them through `.gitattributes` again.
+    git::checkout_files_from(work_dir, &state.source_branch, files)?;
+    if !state.preserve_history || git::has_staged_changes(work_dir)? {
+        git::commit_staged(work_dir, &commit_message(state, n, name, files, custom))?;
+    }
+
+    // An LFS clean filter that is missing or misconfigured in the worktree
+    // commits a file's content, or a fresh pointer, instead of the source's.
+    let pointers = git::lfs_pointers(work_dir, &state.source_branch, files)?;
+    let mismatched = git::differing_files(work_dir, "HEAD", &state.source_branch, &pointers)?;
+    if !mismatched.is_empty() {
+        bail!(
+            "Git LFS files in chunk '{}' were not committed as the same pointers as on '{}': {}. \
+             Check that `git lfs install` has been run for this repository.",
+            name,
+            state.source_branch,
+            mismatched.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Recommit chunk `i`'s branch, checked out in `work_dir`, with `files`: the
+/// branch is reset to where it forks from its parent and `files` are
+/// committed again as `split` would. `add` and `move` use this in place of
+/// amending when `preserve_history` is set, as the chunk then has more than
+/// one commit.
+pub fn recommit_chunk(
+    root: &std::path::Path,
+    work_dir: &std::path::Path,
+    state: &MergesState,
+    i: usize,
+    files: &[String],
+) -> Result<()> {
+    let chunk = &state.chunks[i];
+    let fork = fork_point(root, state, i)?;
+    let since = git::merge_base_of(root, &state.base_branch, &state.source_branch)?;
+    git::reset_hard(work_dir, &fork)?;
+    commit_chunk(work_dir, state, &since, i + 1, &chunk.name, files, chunk.custom_commit_message.as_deref())
+}
+
+/// The commit chunk `i`'s own commits sit on: where its branch forks from the
+/// previous chunk's branch when stacked, otherwise from the base branch (on
+/// origin when it has been fetched).
+pub fn fork_point(root: &std::path::Path, state: &MergesState, i: usize) -> Result<String> {
+    let remote_base = format!("origin/{}", state.base_branch);
+    let parent = match state.strategy {
+        Strategy::Stacked if i > 0 => state.chunks[i - 1].branch.clone(),
+        _ if git::rev_parse(root, &remote_base).is_ok() => remote_base,
+        _ => state.base_branch.clone(),
+    };
+    git::merge_base_of(root, &state.chunks[i].branch, &parent)
+}
+
+/// Commit message for chunk number `n`: its name and file list, after the
+/// ticket or commit prefix when there is one. A subject over the state's
+/// `max_subject_length` has the name shortened, and the full name moves into
+/// the body. A `custom` message from the plan replaces all of that but still
+/// gets the prefix.
+pub fn commit_message(state: &MergesState, n: usize, name: &str, files: &[String], custom: Option<&str>) -> String {
+    if let Some(message) = custom {
+        return with_prefix(state, message);
+    }
+    let head = match state.message_prefix() {
+        Some(prefix) => format!("{} chunk {} -", prefix, n),
+        None => format!("chunk {} -", n),
+    };
+    let subject = git::fit_subject(&head, name, state.max_subject_length);
+    let full_name =
+        if subject == git::fit_subject(&head, name, None) { String::new() } else { format!("Chunk: {}\n\n", name) };
+    format!("{}\n\n{}Files:\n{}", subject, full_name, files.join("\n"))
+}
+
+/// `text` behind the ticket or commit prefix, unless it already starts with it.
+pub fn with_prefix(state: &MergesState, text: &str) -> String {
+    match state.message_prefix() {
+        Some(prefix) if !text.starts_with(&prefix) => format!("{} {}", prefix, text),
+        _ => text.to_string(),
+    }
+}
+
+/// What `rebuild_chunks` changed besides the branches themselves.
+#[derive(Debug, Default)]
+pub struct Rebuild {
+    /// Files that dropped out of the source diff, per chunk name.
+    pub removed: Vec<(String, Vec<String>)>,
+    /// Changed files on the source that no chunk contains.
+    pub unassigned: Vec<String>,
+}
+
+/// Rebuild every chunk branch from the current source branch: each branch is
+/// reset to the merge-base with the base branch and recommitted with its files
+/// as they are on the source now. Files no longer in the diff are dropped from
+/// their chunk. Commits added to chunk branches since the split are replaced.
+///
+/// Must run with the source branch checked out. On failure every chunk branch
+/// is put back where it was and the state file is left unchanged.
+pub fn rebuild_chunks(root: &std::path::Path) -> Result<Rebuild> {
+    let mut state = MergesState::load(root)?;
+    let source_branch = state.source_branch.clone();
+    if git::current_branch(root)? != source_branch {
+        bail!("Check out the source branch '{}' before rebuilding its chunks.", source_branch);
+    }
+    let (included, _) = changed_files(root, &state)?;
+    let changed = git::changed_files(root, &state.base_branch)?;
+    let base_sha = git::merge_base(root, &state.base_branch)?;
+    let use_worktrees = state.use_worktrees;
+
+    let mut report = Rebuild::default();
+    let mut files: Vec<Vec<String>> = vec![];
+    for chunk in &state.chunks {
+        let (kept, removed): (Vec<String>, Vec<String>) = chunk.files.iter().cloned().partition(|f| changed.contains(f));
+        if !removed.is_empty() {
+            report.removed.push((chunk.name.clone(), removed));
+        }
+        files.push(kept);
+    }
+    report.unassigned = included.into_iter().filter(|f| !files.iter().any(|fs| fs.contains(f))).collect();
+
+    // Original tips, to put the branches back if a rebuild fails part-way.
+    let original: Vec<String> =
+        state.chunks.iter().map(|c| git::rev_parse(root, &c.branch)).collect::<Result<_>>()?;
+
+    let mut rebuilt = 0;
+    let result = (|| -> Result<()> {
+        for (i, chunk) in state.chunks.iter().enumerate() {
+            let work_dir = if use_worktrees {
+                git::worktree_path(root, &chunk.branch)
+            } else {
+                git::checkout(root, &chunk.branch)?;
+                root.to_path_buf()
+            };
+            rebuilt = i + 1;
+            git::reset_hard(&work_dir, &base_sha)?;
+            if !files[i].is_empty() {
+                commit_chunk(&work_dir, &state, &base_sha, i + 1, &chunk.name, &files[i], chunk.custom_commit_message.as_deref())?;
+            }
+            if !use_worktrees {
+                git::checkout(root, &source_branch)?;
+            }
+        }
+        Ok(())
+    })();
+
+    if let Err(e) = result {
+        for (chunk, sha) in state.chunks.iter().zip(&original).take(rebuilt) {
+            if use_worktrees {
+                let _ = git::reset_hard(&git::worktree_path(root, &chunk.branch), sha);
+            } else if git::current_branch(root).is_ok_and(|b| b == chunk.branch) {
+                let _ = git::reset_hard(root, sha);
+            } else {
+                let _ = git::update_ref(root, &format!("refs/heads/{}", chunk.branch), sha);
+            }
+        }
+        if !use_worktrees {
+            let _ = git::checkout(root, &source_branch);
+        }
+        return Err(e);
+    }
+
+    for (chunk, kept) in state.chunks.iter_mut().zip(files) {
+        chunk.files = kept;
+    }
+    state.source_sha = Some(git::rev_parse(root, &source_branch)?);
+    state.save(root)?;
+    journal::record(root, "rebuild", &format!("{} chunk(s) from '{}'", state.chunks.len(), source_branch), &state.chunks)?;
+    Ok(report)
+}
+
+/// Why `branch` cannot be created, if it cannot: it exists locally or on
+/// origin, or an existing branch is named like one of its parent directories
+/// (`merges` blocks `merges/feat/x/1-db`).
+pub fn branch_collision(root: &std::path::Path, branch: &str) -> Option<String> {
+    if git::rev_parse(root, &format!("refs/heads/{}", branch)).is_ok() {
+        return Some("the branch already exists".to_string());
+    }
+    if git::rev_parse(root, &format!("refs/remotes/origin/{}", branch)).is_ok() {
+        return Some("the branch already exists on origin".to_string());
+    }
+    let parts: Vec<&str> = branch.split('/').collect();
+    (1..parts.len())
+        .map(|i| parts[..i].join("/"))
+        .find(|parent| git::rev_parse(root, &format!("refs/heads/{}", parent)).is_ok())
+        .map(|parent| format!("branch '{}' is in the way", parent))
+}
+
+/// Replace directory and glob entries in `plan` with the changed files they
+/// match. Patterns skip excluded files and files already assigned to an
+/// existing chunk; exact paths are kept as written so validation can name them.
+fn expand_plan(
+    plan: Vec<ChunkPlan>,
+    changed: &[String],
+    included: &[String],
+    state: &MergesState,
+) -> Result<(Vec<ChunkPlan>, Vec<Expansion>)> {
+    let unassigned: Vec<String> = included
+        .iter()
+        .filter(|f| !state.chunks.iter().any(|c| c.files.contains(f)))
+        .cloned()
+        .collect();
+
+    let mut expansions = vec![];
+    let plan = plan
+        .into_iter()
+        .map(|chunk| {
+            let mut files = vec![];
+            for entry in &chunk.files {
+                let expansion = pathspec::resolve_in(entry, changed, &unassigned)?;
+                if expansion.files.is_empty() {
+                    // Left unexpanded so the diff check below reports it.
+                    files.push(entry.clone());
+                    continue;
+                }
+                files.extend(expansion.files.iter().cloned());
+                if !expansion.is_literal() {
+                    expansions.push(expansion);
+                }
+            }
+            Ok(ChunkPlan { files, ..chunk })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok((plan, expansions))
+}
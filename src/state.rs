@@ -1,10 +1,13 @@
 use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
+use crate::{project::Project, routing::Route, size::SizeLimits, split::RootFiles};
+
 pub const STATE_FILE: &str = ".merges.json";
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, clap::ValueEnum)]
 #[serde(rename_all = "snake_case")]
 pub enum Strategy {
     Stacked,
@@ -20,31 +23,219 @@ impl std::fmt::Display for Strategy {
     }
 }
 
+/// Hosting service the remote lives on; selects the forge backend.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum ForgeKind {
+    #[default]
+    Github,
+    Bitbucket,
+    BitbucketServer,
+    /// Gitea, Forgejo, and Codeberg.
+    Gitea,
+    AzureDevops,
+}
+
+impl ForgeKind {
+    pub fn is_github(&self) -> bool {
+        *self == ForgeKind::Github
+    }
+}
+
+impl std::fmt::Display for ForgeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ForgeKind::Github => write!(f, "GitHub"),
+            ForgeKind::Bitbucket => write!(f, "Bitbucket"),
+            ForgeKind::BitbucketServer => write!(f, "Bitbucket Server"),
+            ForgeKind::Gitea => write!(f, "Gitea"),
+            ForgeKind::AzureDevops => write!(f, "Azure DevOps"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Chunk {
     pub name: String,
     pub branch: String,
     pub files: Vec<String>,
+    /// Why the chunk exists, from the split plan; leads its PR body.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pr_number: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pr_url: Option<String>,
+    /// Commit the branch was at on origin when `merges push` last pushed it;
+    /// lets `merges push --resume` skip chunks that are already on origin.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pushed_sha: Option<String>,
+    /// Title `merges push` last gave the PR; a push retitles the PR when the
+    /// stack position in it has gone stale.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pr_title: Option<String>,
+    /// Commit message, PR title, and PR body given in the split plan, used in
+    /// place of the generated ones.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_commit_message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_pr_title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_pr_body: Option<String>,
+    /// The chunk needs no CI (docs, say); `merges push --skip-ci` gives its
+    /// tip commit a `skip-checks: true` trailer so workflows skip its PR.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub skip_ci: bool,
+    /// Branch-only for now: `merges push` pushes the branch but opens no PR
+    /// for it until `merges unhold` releases it.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub hold: bool,
+    /// When `merges split` created the chunk.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<DateTime<Utc>>,
+    /// When `merges push` last pushed the chunk branch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_pushed_at: Option<DateTime<Utc>>,
+    /// When the chunk's PR merged, recorded once `status` or `push` sees it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merged_at: Option<DateTime<Utc>>,
+    /// Comment `merges push` keeps on the PR with links to the rest of the stack.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stack_comment_id: Option<u64>,
+    /// Labels and reviewers (`label:db`, `reviewer:@alice`) the routing rules
+    /// have given the PR, so later pushes do not request them again.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub routed: Vec<String>,
+    /// Milestone `merges push` last gave the PR.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub milestone: Option<String>,
+    /// The PR's item in the configured project, and the status it was last set to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_item_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_status: Option<String>,
+}
+
+/// The original PR from the source branch, and the comment `merges push
+/// --source-pr` left on it pointing reviewers at the chunk PRs.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct SourcePr {
+    pub number: u64,
+    pub comment_id: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MergesState {
     pub base_branch: String,
     pub source_branch: String,
+    /// Source branch commit the chunk branches were built from, recorded by
+    /// `merges split` and `merges refresh --rebuild`. If it stops being an
+    /// ancestor of the source branch, the source was rewritten under the chunks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_sha: Option<String>,
     pub repo_owner: String,
     pub repo_name: String,
+    /// Forge hosting the repository (omitted from the file for GitHub).
+    #[serde(default, skip_serializing_if = "ForgeKind::is_github")]
+    pub forge: ForgeKind,
+    /// API base URL for self-hosted forges (e.g. Bitbucket Server).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forge_api_url: Option<String>,
     pub strategy: Strategy,
     #[serde(default)]
     pub use_worktrees: bool,
+    /// Paths, directories, or globs kept out of the changed-file list
+    /// (generated snapshots, compiled assets, vendored code).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+    /// Rules giving chunk PRs labels and reviewers by the files they change.
+    /// Set via `merges init --route 'migrations/** -> label:db, reviewer:@alice'`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub routes: Vec<Route>,
     /// Optional explicit commit/PR message prefix (overrides auto-detected ticket).
     /// Set via `merges init --commit-prefix JCLARK-97246`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub commit_prefix: Option<String>,
+    /// Leave the `[n/total]` stack position out of PR titles.
+    /// Set via `merges init --no-title-position`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub no_title_position: bool,
+    /// Longest allowed chunk commit subject, in characters; longer chunk
+    /// names are shortened. Set via `merges init --max-subject-length 72`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_subject_length: Option<usize>,
+    /// Longest allowed chunk PR title, in characters.
+    /// Set via `merges init --max-title-length`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_title_length: Option<usize>,
+    /// Directory level `merges split --auto` groups on, in place of the
+    /// one-or-two-level heuristic. Set via `merges init --auto-depth 3`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_depth: Option<usize>,
+    /// Where `merges split --auto` puts files at the repository root, instead
+    /// of a `root` chunk. Set via `merges init --root-files last`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root_files: Option<RootFiles>,
+    /// Leave the stack navigation comment off chunk PRs.
+    /// Set via `merges init --no-stack-comments`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub no_stack_comments: bool,
+    /// Namespace chunk branches are created under, as
+    /// `<namespace>/<source>/<n>-<name>` rather than `<source>-chunk-<n>-<name>`.
+    /// Set via `merges init --branch-namespace merges`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch_namespace: Option<String>,
+    /// Stash uncommitted changes around classic-mode commands that check out
+    /// chunk branches, as if `--autostash` were always passed.
+    /// Set via `merges init --autostash`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub autostash: bool,
+    /// Build chunk branches by replaying the source commits that touch each
+    /// chunk's files, keeping their messages and authors, instead of one
+    /// commit per chunk. Set via `merges init --preserve-history`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub preserve_history: bool,
+    /// Squash chunk branches into one commit each before every push, as if
+    /// `merges push --squash` were always passed.
+    /// Set via `merges init --squash-on-push`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub squash_on_push: bool,
+    /// Bring chunk branches up to date by merging the base branch into them
+    /// instead of rebasing, and push them without force, as if `merges sync
+    /// --merge` were always passed. Set via `merges init --sync-merge`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub sync_merge: bool,
+    /// Put the review summary (see [`crate::summary`]) in the source PR
+    /// comment and the tracking issue. Set via `merges init --post-summary`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub post_summary: bool,
+    /// GitHub Project (v2) every chunk PR is added to, its status following
+    /// the PR. Set via `merges init --project acme/12`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<Project>,
+    /// Milestone every chunk PR is given. Set via `merges init --milestone`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub milestone: Option<String>,
+    /// How big a chunk may get before `merges push` warns or refuses.
+    /// Set via `merges init --warn-lines 400 --max-lines 1000`.
+    #[serde(default, skip_serializing_if = "SizeLimits::is_empty")]
+    pub size_limits: SizeLimits,
+    /// GitHub issue tracking the whole stack, created by `merges push --tracking-issue`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tracking_issue: Option<u64>,
+    /// Source-branch PR commented on by `merges push --source-pr`; later
+    /// pushes keep that comment's index of chunk PRs current.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_pr: Option<SourcePr>,
+    /// Set while `merges push` runs and cleared when it finishes, so an
+    /// interrupted push can be picked up with `merges push --resume`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub push_interrupted: bool,
     pub chunks: Vec<Chunk>,
+    /// Chunks whose PRs merged into the base branch, taken off the stack by
+    /// `merges sync`; the stack is numbered without them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub merged: Vec<Chunk>,
 }
 
 impl MergesState {
@@ -65,6 +256,30 @@ impl MergesState {
     pub fn path(repo_root: &Path) -> PathBuf {
         repo_root.join(STATE_FILE)
     }
+
+    /// Prefix for chunk commit messages and PR titles: the explicit
+    /// `commit_prefix`, else the ticket detected from the source branch name.
+    pub fn message_prefix(&self) -> Option<String> {
+        self.commit_prefix.clone().or_else(|| crate::git::ticket_prefix(&self.source_branch))
+    }
+
+    /// Branch for the `n`th chunk of the stack, named after `name`.
+    pub fn chunk_branch(&self, n: usize, name: &str) -> String {
+        format!(
+            "{}{}-{}",
+            chunk_branch_prefix(&self.source_branch, self.branch_namespace.as_deref()),
+            n,
+            name.to_lowercase().replace(' ', "-")
+        )
+    }
+}
+
+/// What every chunk branch of `source` starts with, followed by `<n>-<name>`.
+pub fn chunk_branch_prefix(source: &str, namespace: Option<&str>) -> String {
+    match namespace {
+        Some(namespace) => format!("{}/{}/", namespace, source),
+        None => format!("{}-chunk-", source),
+    }
 }
 
 #[cfg(test)]
@@ -76,12 +291,36 @@ mod tests {
         MergesState {
             base_branch: "main".to_string(),
             source_branch: "feat/big-feature".to_string(),
+            source_sha: None,
             repo_owner: "acme".to_string(),
             repo_name: "myrepo".to_string(),
+            forge: ForgeKind::Github,
+            forge_api_url: None,
             strategy: Strategy::Stacked,
             use_worktrees: false,
+            exclude: vec![],
+            routes: vec![],
             commit_prefix: None,
+            no_title_position: false,
+            max_subject_length: None,
+            max_title_length: None,
+            auto_depth: None,
+            root_files: None,
+            no_stack_comments: false,
+            branch_namespace: None,
+            autostash: false,
+            preserve_history: false,
+            squash_on_push: false,
+            sync_merge: false,
+            post_summary: false,
+            project: None,
+            milestone: None,
+            size_limits: Default::default(),
+            tracking_issue: None,
+            source_pr: None,
+            push_interrupted: false,
             chunks: vec![],
+            merged: vec![],
         }
     }
 
@@ -90,8 +329,24 @@ mod tests {
             name: "models".to_string(),
             branch: "feat/big-feature-chunk-1-models".to_string(),
             files: vec!["src/models/user.rs".to_string()],
+            description: None,
             pr_number: None,
             pr_url: None,
+            pushed_sha: None,
+            pr_title: None,
+            custom_commit_message: None,
+            custom_pr_title: None,
+            custom_pr_body: None,
+            skip_ci: false,
+            hold: false,
+            created_at: None,
+            last_pushed_at: None,
+            merged_at: None,
+            stack_comment_id: None,
+            routed: vec![],
+            milestone: None,
+            project_item_id: None,
+            project_status: None,
         }
     }
 
@@ -100,11 +355,35 @@ mod tests {
             name: "api".to_string(),
             branch: "feat/big-feature-chunk-2-api".to_string(),
             files: vec!["src/api/routes.rs".to_string(), "src/api/handlers.rs".to_string()],
+            description: None,
             pr_number: Some(42),
             pr_url: Some("https://github.com/acme/myrepo/pull/42".to_string()),
+            pushed_sha: None,
+            pr_title: None,
+            custom_commit_message: None,
+            custom_pr_title: None,
+            custom_pr_body: None,
+            skip_ci: false,
+            hold: false,
+            created_at: None,
+            last_pushed_at: None,
+            merged_at: None,
+            stack_comment_id: None,
+            routed: vec![],
+            milestone: None,
+            project_item_id: None,
+            project_status: None,
         }
     }
 
+    #[test]
+    fn test_chunk_branch_uses_namespace_when_set() {
+        let mut state = sample_state();
+        assert_eq!(state.chunk_branch(2, "Api Routes"), "feat/big-feature-chunk-2-api-routes");
+        state.branch_namespace = Some("merges".to_string());
+        assert_eq!(state.chunk_branch(2, "Api Routes"), "merges/feat/big-feature/2-api-routes");
+    }
+
     // ── Strategy ─────────────────────────────────────────────────────────
 
     #[test]
@@ -316,6 +595,57 @@ mod tests {
         assert_eq!(loaded.commit_prefix, Some("JCLARK-97246".to_string()));
     }
 
+    // ── forge fields ──────────────────────────────────────────────────────
+
+    /// GitHub is the default forge and is not written to the state file.
+    #[test]
+    fn test_forge_defaults_to_github_and_is_omitted() {
+        let state = sample_state();
+        assert_eq!(state.forge, ForgeKind::Github);
+        let json = serde_json::to_string(&state).unwrap();
+        assert!(!json.contains("forge"), "GitHub forge should be omitted: {}", json);
+    }
+
+    #[test]
+    fn test_forge_bitbucket_server_roundtrips() {
+        let dir = TempDir::new().unwrap();
+        let mut state = sample_state();
+        state.forge = ForgeKind::BitbucketServer;
+        state.forge_api_url = Some("https://git.corp.example".to_string());
+        state.save(dir.path()).unwrap();
+
+        let loaded = MergesState::load(dir.path()).unwrap();
+        assert_eq!(loaded.forge, ForgeKind::BitbucketServer);
+        assert_eq!(loaded.forge_api_url.as_deref(), Some("https://git.corp.example"));
+    }
+
+    // ── push progress ─────────────────────────────────────────────────────
+
+    /// A finished push leaves no progress marker in the file.
+    #[test]
+    fn test_push_progress_omitted_when_unset() {
+        let mut state = sample_state();
+        state.chunks.push(sample_chunk_without_pr());
+        let json = serde_json::to_string(&state).unwrap();
+        assert!(!json.contains("push_interrupted"), "{}", json);
+        assert!(!json.contains("pushed_sha"), "{}", json);
+    }
+
+    #[test]
+    fn test_push_progress_roundtrips() {
+        let dir = TempDir::new().unwrap();
+        let mut state = sample_state();
+        let mut chunk = sample_chunk_without_pr();
+        chunk.pushed_sha = Some("abc123".to_string());
+        state.chunks.push(chunk);
+        state.push_interrupted = true;
+        state.save(dir.path()).unwrap();
+
+        let loaded = MergesState::load(dir.path()).unwrap();
+        assert!(loaded.push_interrupted);
+        assert_eq!(loaded.chunks[0].pushed_sha.as_deref(), Some("abc123"));
+    }
+
     /// Old state files without commit_prefix load without error.
     #[test]
     fn test_commit_prefix_backward_compatible() {
@@ -0,0 +1,182 @@
+//! Review summary of a split: every chunk with why it exists, its files, and
+//! where it comes in the recommended review order, for reviewers meeting the
+//! stack for the first time.
+//!
+//! `merges split` writes it once the chunks exist and `merges push` rewrites
+//! it with their PRs. Like the journal it lives in the git directory, so no
+//! branch can commit it. With `merges init --post-summary`, push also puts it
+//! in the source PR comment and the tracking issue.
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use crate::{
+    git, imports,
+    state::{Chunk, MergesState, Strategy},
+};
+
+pub const SUMMARY_FILE: &str = "merges-split.md";
+
+/// Path of the summary inside the repository's common git directory.
+pub fn path(root: &Path) -> Result<PathBuf> {
+    Ok(git::common_dir(root)?.join(SUMMARY_FILE))
+}
+
+/// Write the summary of `state`. Returns the file written.
+pub fn write(root: &Path, state: &MergesState) -> Result<PathBuf> {
+    let path = path(root)?;
+    std::fs::write(&path, render(state, &review_order(root, state)))
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+/// The order to review the chunks in, as indexes into `state.chunks`. A
+/// stack is reviewed bottom-up. Independent chunks come after the chunks
+/// whose files they import, so reviewers meet a module before its callers;
+/// otherwise, and within an import cycle, the split's order is kept.
+pub fn review_order(root: &Path, state: &MergesState) -> Vec<usize> {
+    if state.strategy == Strategy::Stacked {
+        return (0..state.chunks.len()).collect();
+    }
+    let files: Vec<String> = state.chunks.iter().flat_map(|c| c.files.iter().cloned()).collect();
+    dependency_order(&state.chunks, &imports::graph(root, &state.source_branch, &files))
+}
+
+fn dependency_order(chunks: &[Chunk], edges: &[(String, String)]) -> Vec<usize> {
+    let chunk_of = |file: &str| chunks.iter().position(|c| c.files.iter().any(|f| f == file));
+    let deps: Vec<(usize, usize)> = edges
+        .iter()
+        .filter_map(|(from, to)| Some((chunk_of(from)?, chunk_of(to)?)))
+        .filter(|(from, to)| from != to)
+        .collect();
+    let mut order = vec![];
+    while order.len() < chunks.len() {
+        let next = (0..chunks.len())
+            .filter(|i| !order.contains(i))
+            .find(|&i| deps.iter().all(|&(from, to)| from != i || order.contains(&to)))
+            .or_else(|| (0..chunks.len()).find(|i| !order.contains(i)))
+            .unwrap_or_default();
+        order.push(next);
+    }
+    order
+}
+
+/// The whole summary document, with the chunks in `order`.
+pub fn render(state: &MergesState, order: &[usize]) -> String {
+    let how = match state.strategy {
+        Strategy::Stacked => "They are stacked: review and merge them in this order.",
+        Strategy::Independent => {
+            "They are independent: each can be reviewed and merged on its own, \
+             but this order shows a module before the chunks that use it."
+        }
+    };
+    format!(
+        "# Split of `{}`\n\n`{}` is split into {} PRs against `{}`. {}\n\n{}\n---\n*Generated by [merges](https://github.com/merges-cli/merges)*\n",
+        state.source_branch,
+        state.source_branch,
+        state.chunks.len(),
+        state.base_branch,
+        how,
+        details(state, order)
+    )
+}
+
+/// The review order and a section per chunk: the part of the summary that
+/// `merges push` posts.
+pub fn details(state: &MergesState, order: &[usize]) -> String {
+    let pr = |chunk: &Chunk| match chunk.pr_number {
+        Some(n) => format!("#{}", n),
+        None => "no PR yet".to_string(),
+    };
+    let mut out = String::from("## Review order\n\n");
+    for (position, &i) in order.iter().enumerate() {
+        let chunk = &state.chunks[i];
+        out.push_str(&format!(
+            "{}. **{}** — {}, {} file(s)\n",
+            position + 1,
+            chunk.name,
+            pr(chunk),
+            chunk.files.len()
+        ));
+    }
+    for (position, &i) in order.iter().enumerate() {
+        let chunk = &state.chunks[i];
+        out.push_str(&format!("\n## {}. {}\n\n", position + 1, chunk.name));
+        if let Some(description) = &chunk.description {
+            out.push_str(&format!("{}\n\n", description.trim()));
+        }
+        out.push_str(&format!("- Branch: `{}`\n- PR: {}\n- Files:\n", chunk.branch, pr(chunk)));
+        for file in &chunk.files {
+            out.push_str(&format!("  - `{}`\n", file));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(name: &str, files: &[&str]) -> Chunk {
+        Chunk {
+            name: name.to_string(),
+            branch: format!("feat/big-chunk-{}", name),
+            files: files.iter().map(|f| f.to_string()).collect(),
+            description: None,
+            pr_number: None,
+            pr_url: None,
+            pushed_sha: None,
+            pr_title: None,
+            custom_commit_message: None,
+            custom_pr_title: None,
+            custom_pr_body: None,
+            skip_ci: false,
+            hold: false,
+            created_at: None,
+            last_pushed_at: None,
+            merged_at: None,
+            stack_comment_id: None,
+            routed: vec![],
+            milestone: None,
+            project_item_id: None,
+            project_status: None,
+        }
+    }
+
+    #[test]
+    fn test_dependency_order_puts_imported_chunks_first() {
+        let chunks = vec![chunk("api", &["api.rs"]), chunk("models", &["models.rs"]), chunk("docs", &["README.md"])];
+        let edges = vec![("api.rs".to_string(), "models.rs".to_string())];
+        assert_eq!(dependency_order(&chunks, &edges), vec![1, 0, 2]);
+    }
+
+    #[test]
+    fn test_dependency_order_keeps_the_split_order_in_a_cycle() {
+        let chunks = vec![chunk("a", &["a.rs"]), chunk("b", &["b.rs"])];
+        let edges = vec![("a.rs".to_string(), "b.rs".to_string()), ("b.rs".to_string(), "a.rs".to_string())];
+        assert_eq!(dependency_order(&chunks, &edges), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_details_list_each_chunk_with_its_rationale_and_files() {
+        let mut models = chunk("models", &["src/models.rs", "src/schema.rs"]);
+        models.description = Some("Data types the API needs.".to_string());
+        models.pr_number = Some(12);
+        let mut state: MergesState = serde_json::from_value(serde_json::json!({
+            "base_branch": "main",
+            "source_branch": "feat/big",
+            "repo_owner": "acme",
+            "repo_name": "myrepo",
+            "strategy": "stacked",
+            "chunks": []
+        }))
+        .unwrap();
+        state.chunks = vec![models, chunk("api", &["src/api.rs"])];
+
+        let text = details(&state, &[0, 1]);
+
+        assert!(text.contains("1. **models** — #12, 2 file(s)\n2. **api** — no PR yet, 1 file(s)"), "{}", text);
+        assert!(text.contains("## 1. models\n\nData types the API needs.\n\n- Branch: `feat/big-chunk-models`"), "{}", text);
+        assert!(text.contains("- Files:\n  - `src/models.rs`\n  - `src/schema.rs`\n"), "{}", text);
+    }
+}
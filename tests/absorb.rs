@@ -0,0 +1,91 @@
+//! Integration tests for `merges absorb`.
+
+use std::path::{Path, PathBuf};
+use std::process::Command as StdCommand;
+use tempfile::TempDir;
+
+fn git(root: &Path, args: &[&str]) -> String {
+    let out = StdCommand::new("git").args(args).current_dir(root).output().unwrap();
+    assert!(out.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&out.stderr));
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
+
+/// A split `feat/big` with stacked `models` and `api` chunks, checked out on
+/// `feat/big`.
+fn make_split_repo() -> (TempDir, PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().to_path_buf();
+
+    git(&root, &["init", "-b", "main"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    git(&root, &["config", "user.name", "Test"]);
+    std::fs::write(root.join("README.md"), "hello").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "init"]);
+    git(&root, &["checkout", "-q", "-b", "feat/big"]);
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(root.join("src/models.rs"), "struct User;\n").unwrap();
+    std::fs::write(root.join("src/api.rs"), "fn routes() {}\n").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "add feature files"]);
+
+    let state = serde_json::json!({
+        "base_branch": "main",
+        "source_branch": "feat/big",
+        "repo_owner": "acme",
+        "repo_name": "myrepo",
+        "strategy": "stacked",
+        "chunks": []
+    });
+    std::fs::write(root.join(".merges.json"), serde_json::to_string_pretty(&state).unwrap()).unwrap();
+    merges::split::apply_plan(&root, vec![
+        merges::split::ChunkPlan { name: "models".to_string(), files: vec!["src/models.rs".to_string()], ..Default::default() },
+        merges::split::ChunkPlan { name: "api".to_string(), files: vec!["src/api.rs".to_string()], ..Default::default() },
+    ])
+    .unwrap();
+    git(&root, &["rebase", "-q", "--onto", "feat/big-chunk-1-models", "main", "feat/big-chunk-2-api"]);
+    git(&root, &["checkout", "-q", "feat/big"]);
+
+    (dir, root)
+}
+
+#[test]
+fn test_absorb_routes_fix_to_owning_chunk_and_source() {
+    let (_dir, root) = make_split_repo();
+    std::fs::write(root.join("src/models.rs"), "struct User { id: u64 }\n").unwrap();
+
+    let absorbed = merges::commands::absorb::run(&root, None, false).unwrap();
+    assert_eq!(absorbed, vec!["models"]);
+
+    assert_eq!(git(&root, &["status", "--porcelain", "--untracked-files=no"]), "");
+    assert_eq!(git(&root, &["log", "-1", "--format=%s", "feat/big"]), "Fix src/models.rs");
+    assert_eq!(git(&root, &["log", "-1", "--format=%s", "feat/big-chunk-1-models"]), "Fix src/models.rs");
+    assert_eq!(git(&root, &["show", "feat/big-chunk-1-models:src/models.rs"]), "struct User { id: u64 }");
+    // The api chunk is restacked onto the fixed models chunk.
+    assert_eq!(git(&root, &["rev-parse", "feat/big-chunk-2-api^"]), git(&root, &["rev-parse", "feat/big-chunk-1-models"]));
+    assert_eq!(git(&root, &["rev-parse", "--abbrev-ref", "HEAD"]), "feat/big");
+}
+
+/// Changes no chunk owns are left alone; with them around, a classic-mode
+/// stack needs --autostash to restack.
+#[test]
+fn test_absorb_leaves_unowned_changes_uncommitted() {
+    let (_dir, root) = make_split_repo();
+    std::fs::write(root.join("src/api.rs"), "fn routes() { todo!() }\n").unwrap();
+    std::fs::write(root.join("README.md"), "hello, world").unwrap();
+
+    let err = merges::commands::absorb::run(&root, Some("Stub routes"), false).unwrap_err();
+    assert!(err.to_string().contains("README.md"), "{}", err);
+
+    merges::commands::absorb::run(&root, Some("Stub routes"), true).unwrap();
+    assert_eq!(git(&root, &["log", "-1", "--format=%s", "feat/big-chunk-2-api"]), "Stub routes");
+    assert_eq!(git(&root, &["status", "--porcelain", "--untracked-files=no"]), "M README.md");
+}
+
+#[test]
+fn test_absorb_requires_source_branch() {
+    let (_dir, root) = make_split_repo();
+    git(&root, &["checkout", "-q", "main"]);
+    let err = merges::commands::absorb::run(&root, None, false).unwrap_err();
+    assert!(err.to_string().contains("source branch 'feat/big'"), "{}", err);
+}
@@ -46,7 +46,7 @@ fn setup_with_chunk(root: &std::path::Path) {
 
     // Create chunk-1 with only src/a.rs
     merges::split::apply_plan(root, vec![
-        merges::split::ChunkPlan { name: "part-a".to_string(), files: vec!["src/a.rs".to_string()] },
+        merges::split::ChunkPlan { name: "part-a".to_string(), files: vec!["src/a.rs".to_string()], ..Default::default() },
     ]).unwrap();
 }
 
@@ -58,7 +58,7 @@ fn test_add_file_to_existing_chunk() {
     let (_dir, root) = make_repo();
     setup_with_chunk(&root);
 
-    merges::commands::add::run(&root, "part-a", &["src/b.rs".to_string()]).unwrap();
+    merges::commands::add::run(&root, "part-a", &["src/b.rs".to_string()], false).unwrap();
 
     // Check out chunk branch and verify both files are present
     merges::git::checkout(&root, "feat/big-chunk-1-part-a").unwrap();
@@ -74,7 +74,7 @@ fn test_add_updates_state_file() {
     let (_dir, root) = make_repo();
     setup_with_chunk(&root);
 
-    merges::commands::add::run(&root, "part-a", &["src/b.rs".to_string()]).unwrap();
+    merges::commands::add::run(&root, "part-a", &["src/b.rs".to_string()], false).unwrap();
 
     let state = merges::state::MergesState::load(&root).unwrap();
     let chunk = state.chunks.iter().find(|c| c.name == "part-a").unwrap();
@@ -89,7 +89,7 @@ fn test_add_idempotent_for_existing_file() {
     setup_with_chunk(&root);
 
     // src/a.rs is already in the chunk
-    merges::commands::add::run(&root, "part-a", &["src/a.rs".to_string()]).unwrap();
+    merges::commands::add::run(&root, "part-a", &["src/a.rs".to_string()], false).unwrap();
 
     let state = merges::state::MergesState::load(&root).unwrap();
     let chunk = state.chunks.iter().find(|c| c.name == "part-a").unwrap();
@@ -103,7 +103,7 @@ fn test_add_file_not_in_diff_returns_error() {
     let (_dir, root) = make_repo();
     setup_with_chunk(&root);
 
-    let result = merges::commands::add::run(&root, "part-a", &["src/nonexistent.rs".to_string()]);
+    let result = merges::commands::add::run(&root, "part-a", &["src/nonexistent.rs".to_string()], false);
     assert!(result.is_err(), "Adding nonexistent file should fail");
     let msg = result.unwrap_err().to_string();
     assert!(msg.contains("nonexistent.rs"), "Error should name the bad file: {}", msg);
@@ -115,7 +115,7 @@ fn test_add_to_nonexistent_chunk_returns_error() {
     let (_dir, root) = make_repo();
     setup_with_chunk(&root);
 
-    let result = merges::commands::add::run(&root, "no-such-chunk", &["src/b.rs".to_string()]);
+    let result = merges::commands::add::run(&root, "no-such-chunk", &["src/b.rs".to_string()], false);
     assert!(result.is_err());
     let msg = result.unwrap_err().to_string();
     assert!(msg.contains("no-such-chunk"), "Error should name the missing chunk: {}", msg);
@@ -127,7 +127,7 @@ fn test_add_restores_source_branch() {
     let (_dir, root) = make_repo();
     setup_with_chunk(&root);
 
-    merges::commands::add::run(&root, "part-a", &["src/c.rs".to_string()]).unwrap();
+    merges::commands::add::run(&root, "part-a", &["src/c.rs".to_string()], false).unwrap();
 
     let branch = merges::git::current_branch(&root).unwrap();
     assert_eq!(branch, "feat/big", "Source branch should be active after add");
@@ -139,10 +139,61 @@ fn test_add_multiple_files_at_once() {
     let (_dir, root) = make_repo();
     setup_with_chunk(&root);
 
-    merges::commands::add::run(&root, "part-a", &["src/b.rs".to_string(), "src/c.rs".to_string()]).unwrap();
+    merges::commands::add::run(&root, "part-a", &["src/b.rs".to_string(), "src/c.rs".to_string()], false).unwrap();
 
     let state = merges::state::MergesState::load(&root).unwrap();
     let chunk = state.chunks.iter().find(|c| c.name == "part-a").unwrap();
     assert!(chunk.files.contains(&"src/b.rs".to_string()));
     assert!(chunk.files.contains(&"src/c.rs".to_string()));
 }
+
+/// Glob patterns are expanded against the changed files, not by the shell.
+#[test]
+fn test_add_glob_pattern() {
+    let (_dir, root) = make_repo();
+    setup_with_chunk(&root);
+
+    merges::commands::add::run(&root, "part-a", &["src/[bc].rs".to_string()], false).unwrap();
+
+    let state = merges::state::MergesState::load(&root).unwrap();
+    let chunk = state.chunks.iter().find(|c| c.name == "part-a").unwrap();
+    let mut files = chunk.files.clone();
+    files.sort();
+    assert_eq!(files, vec!["src/a.rs", "src/b.rs", "src/c.rs"]);
+}
+
+/// A directory adds every changed file beneath it.
+#[test]
+fn test_add_directory() {
+    let (_dir, root) = make_repo();
+    setup_with_chunk(&root);
+
+    merges::commands::add::run(&root, "part-a", &["src/".to_string()], false).unwrap();
+
+    let state = merges::state::MergesState::load(&root).unwrap();
+    let chunk = state.chunks.iter().find(|c| c.name == "part-a").unwrap();
+    assert_eq!(chunk.files.len(), 4, "All four src files should be in the chunk: {:?}", chunk.files);
+}
+
+/// A directory or glob only takes files no other chunk owns; naming another
+/// chunk's file outright is refused, so no file ends up in two chunks.
+#[test]
+fn test_add_leaves_files_owned_by_other_chunks() {
+    let (_dir, root) = make_repo();
+    setup_with_chunk(&root);
+    merges::split::apply_plan(&root, vec![
+        merges::split::ChunkPlan { name: "part-b".to_string(), files: vec!["src/b.rs".to_string()], ..Default::default() },
+    ]).unwrap();
+
+    merges::commands::add::run(&root, "part-b", &["src/".to_string()], false).unwrap();
+    let state = merges::state::MergesState::load(&root).unwrap();
+    let chunk = state.chunks.iter().find(|c| c.name == "part-b").unwrap();
+    let mut files = chunk.files.clone();
+    files.sort();
+    assert_eq!(files, vec!["src/b.rs", "src/c.rs", "src/d.rs"], "src/a.rs belongs to part-a");
+
+    let err = merges::commands::add::run(&root, "part-b", &["src/a.rs".to_string()], false).unwrap_err();
+    assert!(err.to_string().contains("merges move"), "{}", err);
+    let err = merges::commands::add::run(&root, "part-a", &["src/[cd].rs".to_string()], false).unwrap_err();
+    assert!(err.to_string().contains("another chunk"), "{}", err);
+}
@@ -0,0 +1,101 @@
+//! Tests for `merges assign` — triage changed files that no chunk contains yet.
+
+use std::process::Command as StdCommand;
+use tempfile::TempDir;
+
+fn make_repo() -> (TempDir, std::path::PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().to_path_buf();
+
+    for args in [
+        vec!["init", "-b", "main"],
+        vec!["config", "user.email", "test@example.com"],
+        vec!["config", "user.name", "Test"],
+    ] {
+        StdCommand::new("git").args(&args).current_dir(&root).output().unwrap();
+    }
+
+    std::fs::write(root.join("README.md"), "hello").unwrap();
+    StdCommand::new("git").args(["add", "."]).current_dir(&root).output().unwrap();
+    StdCommand::new("git").args(["commit", "-m", "init"]).current_dir(&root).output().unwrap();
+
+    StdCommand::new("git").args(["checkout", "-b", "feat/big"]).current_dir(&root).output().unwrap();
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::create_dir_all(root.join("docs")).unwrap();
+    for name in ["src/a.rs", "src/b.rs", "src/c.rs", "docs/guide.md"] {
+        std::fs::write(root.join(name), format!("// {}", name)).unwrap();
+    }
+    StdCommand::new("git").args(["add", "."]).current_dir(&root).output().unwrap();
+    StdCommand::new("git").args(["commit", "-m", "add files"]).current_dir(&root).output().unwrap();
+
+    (dir, root)
+}
+
+fn setup_with_chunk(root: &std::path::Path) {
+    let state = serde_json::json!({
+        "base_branch": "main",
+        "source_branch": "feat/big",
+        "repo_owner": "acme",
+        "repo_name": "myrepo",
+        "strategy": "stacked",
+        "chunks": []
+    });
+    std::fs::write(root.join(".merges.json"), serde_json::to_string_pretty(&state).unwrap()).unwrap();
+
+    merges::split::apply_plan(root, vec![
+        merges::split::ChunkPlan { name: "part-a".to_string(), files: vec!["src/a.rs".to_string()], ..Default::default() },
+    ]).unwrap();
+}
+
+fn chunk_files(root: &std::path::Path) -> Vec<String> {
+    let state = merges::state::MergesState::load(root).unwrap();
+    let mut files = state.chunks[0].files.clone();
+    files.sort();
+    files
+}
+
+// ── Tests ────────────────────────────────────────────────────────────────────
+
+#[test]
+fn test_unassigned_files_lists_files_in_no_chunk() {
+    let (_dir, root) = make_repo();
+    setup_with_chunk(&root);
+
+    let state = merges::state::MergesState::load(&root).unwrap();
+    let mut files = merges::commands::assign::unassigned_files(&root, &state).unwrap();
+    files.sort();
+    assert_eq!(files, vec!["docs/guide.md", "src/b.rs", "src/c.rs"]);
+}
+
+/// `--to` with no files assigns every leftover file.
+#[test]
+fn test_assign_to_without_files_takes_all_unassigned() {
+    let (_dir, root) = make_repo();
+    setup_with_chunk(&root);
+
+    merges::commands::assign::run(&root, Some("part-a"), &[], false).unwrap();
+
+    assert_eq!(chunk_files(&root), vec!["docs/guide.md", "src/a.rs", "src/b.rs", "src/c.rs"]);
+}
+
+#[test]
+fn test_assign_to_with_pattern_takes_only_matches() {
+    let (_dir, root) = make_repo();
+    setup_with_chunk(&root);
+
+    merges::commands::assign::run(&root, Some("part-a"), &["src/*.rs".to_string()], false).unwrap();
+
+    assert_eq!(chunk_files(&root), vec!["src/a.rs", "src/b.rs", "src/c.rs"]);
+    assert_eq!(merges::git::current_branch(&root).unwrap(), "feat/big");
+}
+
+/// Files that already belong to a chunk can't be re-assigned through `assign`.
+#[test]
+fn test_assign_rejects_already_assigned_file() {
+    let (_dir, root) = make_repo();
+    setup_with_chunk(&root);
+
+    let result = merges::commands::assign::run(&root, Some("part-a"), &["src/a.rs".to_string()], false);
+    assert!(result.is_err());
+    assert!(result.unwrap_err().to_string().contains("src/a.rs"));
+}
@@ -61,10 +61,12 @@ fn test_apply_plan_rolls_back_on_partial_failure() {
         merges::split::ChunkPlan {
             name: "valid".to_string(),
             files: vec!["src/a.rs".to_string()],
+            ..Default::default()
         },
         merges::split::ChunkPlan {
             name: "invalid".to_string(),
             files: vec!["src/does_not_exist.rs".to_string()], // not in diff → triggers error
+            ..Default::default()
         },
     ];
 
@@ -104,10 +106,12 @@ fn test_apply_plan_rollback_restores_source_branch() {
         merges::split::ChunkPlan {
             name: "good".to_string(),
             files: vec!["src/a.rs".to_string()],
+            ..Default::default()
         },
         merges::split::ChunkPlan {
             name: "bad".to_string(),
             files: vec!["src/nonexistent.rs".to_string()],
+            ..Default::default()
         },
     ];
 
@@ -127,8 +131,8 @@ fn test_apply_plan_success_commits_all_chunks() {
     write_state(&root);
 
     let plan = vec![
-        merges::split::ChunkPlan { name: "part-a".to_string(), files: vec!["src/a.rs".to_string()] },
-        merges::split::ChunkPlan { name: "part-b".to_string(), files: vec!["src/b.rs".to_string(), "src/c.rs".to_string()] },
+        merges::split::ChunkPlan { name: "part-a".to_string(), files: vec!["src/a.rs".to_string()], ..Default::default() },
+        merges::split::ChunkPlan { name: "part-b".to_string(), files: vec!["src/b.rs".to_string(), "src/c.rs".to_string()], ..Default::default() },
     ];
 
     merges::split::apply_plan(&root, plan).unwrap();
@@ -156,10 +160,12 @@ fn test_apply_plan_rolls_back_mid_loop_failure() {
         merges::split::ChunkPlan {
             name: "first".to_string(),
             files: vec!["src/a.rs".to_string()],
+            ..Default::default()
         },
         merges::split::ChunkPlan {
             name: "second".to_string(), // branch already exists → create_branch will fail
             files: vec!["src/b.rs".to_string()],
+            ..Default::default()
         },
     ];
 
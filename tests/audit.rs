@@ -0,0 +1,102 @@
+//! Integration tests for `merges audit`.
+
+use std::path::{Path, PathBuf};
+use std::process::Command as StdCommand;
+use tempfile::TempDir;
+
+use merges::commands::audit;
+
+fn git(root: &Path, args: &[&str]) -> String {
+    let out = StdCommand::new("git").args(args).current_dir(root).output().unwrap();
+    assert!(out.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&out.stderr));
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
+
+/// `feat/big` changes `src/models.rs`, `src/api.rs`, and `docs/notes.md`;
+/// the chunks are built from `plan`.
+fn make_split_repo(plan: &[(&str, &[&str])]) -> (TempDir, PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().join("repo");
+    std::fs::create_dir_all(&root).unwrap();
+
+    git(&root, &["init", "-b", "main"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    git(&root, &["config", "user.name", "Test"]);
+    std::fs::write(root.join("README.md"), "hello").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "init"]);
+    git(&root, &["checkout", "-q", "-b", "feat/big"]);
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::create_dir_all(root.join("docs")).unwrap();
+    std::fs::write(root.join("src/models.rs"), "struct User;\n").unwrap();
+    std::fs::write(root.join("src/api.rs"), "fn routes() {}\n").unwrap();
+    std::fs::write(root.join("docs/notes.md"), "notes\n").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "add feature files"]);
+
+    let state = serde_json::json!({
+        "base_branch": "main",
+        "source_branch": "feat/big",
+        "repo_owner": "acme",
+        "repo_name": "myrepo",
+        "strategy": "stacked",
+        "chunks": []
+    });
+    std::fs::write(root.join(".merges.json"), serde_json::to_string_pretty(&state).unwrap()).unwrap();
+    let plan = plan
+        .iter()
+        .map(|(name, files)| merges::split::ChunkPlan {
+            name: name.to_string(),
+            files: files.iter().map(|f| f.to_string()).collect(),
+            ..Default::default()
+        })
+        .collect();
+    merges::split::apply_plan(&root, plan).unwrap();
+
+    (dir, root)
+}
+
+fn refs(root: &Path) -> String {
+    git(root, &["for-each-ref", "--format=%(refname) %(objectname)"])
+}
+
+#[test]
+fn test_a_consistent_stack_passes_and_nothing_changes() {
+    let (_dir, root) = make_split_repo(&[("models", &["src/models.rs"]), ("rest", &["src/api.rs", "docs/notes.md"])]);
+    let state_before = std::fs::read_to_string(root.join(".merges.json")).unwrap();
+    let refs_before = refs(&root);
+
+    let report = audit::run(&root, None).unwrap();
+    assert!(report.ok(), "{:?}", report);
+    assert_eq!(report.state, ".merges.json");
+
+    assert_eq!(std::fs::read_to_string(root.join(".merges.json")).unwrap(), state_before);
+    assert_eq!(refs(&root), refs_before);
+}
+
+#[test]
+fn test_a_file_in_no_chunk_fails_the_audit() {
+    let (_dir, root) = make_split_repo(&[("models", &["src/models.rs"]), ("api", &["src/api.rs"])]);
+
+    let report = audit::run(&root, None).unwrap();
+    assert!(!report.ok());
+    assert!(report.issues.is_empty(), "{:?}", report.issues);
+    assert_eq!(report.diffcheck.unwrap().missing, vec!["docs/notes.md"]);
+}
+
+/// A CI clone has the chunk branches only as `origin/...` and no
+/// `.merges.json`; the state comes from `--state`.
+#[test]
+fn test_a_fresh_clone_is_audited_against_origin_branches() {
+    let (dir, root) = make_split_repo(&[("models", &["src/models.rs"]), ("rest", &["src/api.rs", "docs/notes.md"])]);
+    let state_file = dir.path().join("stack.json");
+    std::fs::copy(root.join(".merges.json"), &state_file).unwrap();
+    let clone = dir.path().join("clone");
+    git(dir.path(), &["clone", "-q", "-b", "feat/big", root.to_str().unwrap(), clone.to_str().unwrap()]);
+
+    let report = audit::run(&clone, Some(&state_file)).unwrap();
+    assert!(report.ok(), "{:?}", report);
+    assert!(!clone.join(".merges.json").exists());
+
+    assert!(audit::run(&clone, None).is_err());
+}
@@ -105,3 +105,227 @@ fn test_auto_group_each_file_in_exactly_one_chunk() {
     let deduped: Vec<_> = all.iter().cloned().collect::<std::collections::HashSet<_>>().into_iter().collect();
     assert_eq!(all.len(), deduped.len(), "Each file should appear in exactly one chunk");
 }
+
+// ── group_by_language ───────────────────────────────────────────────────────
+
+#[test]
+fn test_group_by_language_ignores_directories() {
+    let files = vec![
+        "db/migrations/001_add_payments.sql".to_string(),
+        "db/seeds/payments.sql".to_string(),
+        "frontend/components/PaymentForm.tsx".to_string(),
+        "frontend/lib/api.ts".to_string(),
+        "src/api/payments.rs".to_string(),
+        "README.md".to_string(),
+    ];
+    let plans = merges::split::group_by_language(&files);
+    let names: Vec<&str> = plans.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, vec!["docs", "rust", "sql", "typescript"]);
+    assert_eq!(plans[3].files, vec!["frontend/components/PaymentForm.tsx", "frontend/lib/api.ts"]);
+}
+
+#[test]
+fn test_language_of_unknown_and_missing_extensions() {
+    use merges::split::language_of;
+    assert_eq!(language_of("proto/payments.proto"), "proto");
+    assert_eq!(language_of("bin/deploy"), "other");
+    assert_eq!(language_of("docker/Dockerfile"), "docker");
+    assert_eq!(language_of("config/App.YML"), "config");
+}
+
+// ── group_by_history ────────────────────────────────────────────────────────
+
+fn commit(files: &[&str]) -> Vec<String> {
+    files.iter().map(|f| f.to_string()).collect()
+}
+
+#[test]
+fn test_group_by_history_follows_co_changes_across_directories() {
+    use merges::split::group_by_history;
+    let files = vec![
+        "src/api/payments.rs".to_string(),
+        "src/api/users.rs".to_string(),
+        "src/db/migrations/001_payments.sql".to_string(),
+        "src/models/user.rs".to_string(),
+    ];
+    let commits = vec![
+        commit(&["src/api/payments.rs", "src/db/migrations/001_payments.sql"]),
+        commit(&["src/api/users.rs", "src/models/user.rs"]),
+        commit(&["src/api/payments.rs", "src/db/migrations/001_payments.sql"]),
+        commit(&["src/models/user.rs", "src/api/users.rs", "README.md"]),
+    ];
+    let plans = group_by_history(&files, &commits);
+    let names: Vec<&str> = plans.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, vec!["api", "api-2"]);
+    let groups: Vec<&Vec<String>> = plans.iter().map(|p| &p.files).collect();
+    assert!(groups.contains(&&vec!["src/api/payments.rs".to_string(), "src/db/migrations/001_payments.sql".to_string()]));
+    assert!(groups.contains(&&vec!["src/api/users.rs".to_string(), "src/models/user.rs".to_string()]));
+}
+
+/// One shared commit is not enough, and bulk commits don't count.
+#[test]
+fn test_group_by_history_needs_repeated_small_co_changes() {
+    use merges::split::group_by_history;
+    let files = vec!["docs/guide.md".to_string(), "src/lib.rs".to_string()];
+    let bulk: Vec<String> = (0..60).map(|i| format!("src/gen/{}.rs", i)).chain(files.clone()).collect();
+    let commits = vec![commit(&["docs/guide.md", "src/lib.rs"]), bulk.clone(), bulk];
+    let plans = group_by_history(&files, &commits);
+    assert_eq!(plans.len(), 2);
+    assert_eq!(plans[0].name, "docs");
+    assert_eq!(plans[1].name, "src");
+}
+
+/// Files new on the branch have no history and fall back to their directory chunk.
+#[test]
+fn test_group_by_history_places_new_files_by_directory() {
+    use merges::split::group_by_history;
+    let files = vec![
+        "lib/a.rs".to_string(),
+        "lib/b.rs".to_string(),
+        "lib/new.rs".to_string(),
+        "tools/new.sh".to_string(),
+    ];
+    let commits = vec![commit(&["lib/a.rs", "lib/b.rs"]), commit(&["lib/a.rs", "lib/b.rs"])];
+    let plans = group_by_history(&files, &commits);
+    assert_eq!(plans.len(), 2);
+    assert_eq!(plans[0].name, "lib");
+    assert_eq!(plans[0].files, vec!["lib/a.rs", "lib/b.rs", "lib/new.rs"]);
+    assert_eq!(plans[1].name, "tools");
+}
+
+// ── group_by_depth ──────────────────────────────────────────────────────────
+
+#[test]
+fn test_group_by_depth_groups_on_the_nth_directory() {
+    use merges::split::group_by_depth;
+    let files = vec![
+        "services/payments/internal/core/ledger.rs".to_string(),
+        "services/payments/internal/api.rs".to_string(),
+        "services/payments/cmd/main.rs".to_string(),
+        "services/users/handlers/login.rs".to_string(),
+        "services/README.md".to_string(),
+        "Cargo.toml".to_string(),
+    ];
+
+    let plans = group_by_depth(&files, 2);
+    let names: Vec<&str> = plans.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, vec!["payments", "root", "services", "users"]);
+    assert_eq!(plans[0].files.len(), 3);
+
+    let plans = group_by_depth(&files, 3);
+    let names: Vec<&str> = plans.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, vec!["cmd", "handlers", "internal", "root", "services"]);
+    assert_eq!(
+        plans[2].files,
+        vec!["services/payments/internal/api.rs", "services/payments/internal/core/ledger.rs"]
+    );
+}
+
+/// Directories with the same name under different parents stay apart.
+#[test]
+fn test_group_by_depth_disambiguates_clashing_names() {
+    use merges::split::group_by_depth;
+    let files = vec![
+        "services/billing/internal/a.rs".to_string(),
+        "services/payments/internal/b.rs".to_string(),
+        "services/payments/cmd/main.rs".to_string(),
+    ];
+    let plans = group_by_depth(&files, 3);
+    let names: Vec<&str> = plans.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, vec!["cmd", "services-billing-internal", "services-payments-internal"]);
+}
+
+// ── group_by_two_levels ─────────────────────────────────────────────────────
+
+#[test]
+fn test_group_by_two_levels_names_chunks_after_both_directories() {
+    use merges::split::group_by_two_levels;
+    let files = vec![
+        "backend/api/users.rs".to_string(),
+        "backend/api/v2/posts.rs".to_string(),
+        "backend/models/user.rs".to_string(),
+        "backend/main.rs".to_string(),
+        "frontend/components/Nav.tsx".to_string(),
+        "backend-api/client.ts".to_string(),
+        "Cargo.toml".to_string(),
+    ];
+    let plans = group_by_two_levels(&files);
+    let names: Vec<&str> = plans.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec!["backend", "backend-api", "backend-api-2", "backend-models", "frontend-components", "root"]
+    );
+    assert_eq!(plans[1].files, vec!["backend/api/users.rs", "backend/api/v2/posts.rs"]);
+    assert_eq!(plans[2].files, vec!["backend-api/client.ts"]);
+}
+
+// ── adjusting a plan ────────────────────────────────────────────────────────
+
+fn plan(chunks: &[(&str, &[&str])]) -> Vec<ChunkPlan> {
+    chunks
+        .iter()
+        .map(|(name, files)| ChunkPlan {
+            name: name.to_string(),
+            files: files.iter().map(|f| f.to_string()).collect(),
+            ..Default::default()
+        })
+        .collect()
+}
+
+#[test]
+fn test_merge_chunks_takes_the_first_position() {
+    let mut p = plan(&[("db", &["db/a.sql"]), ("api", &["api/x.rs"]), ("models", &["models/m.rs"])]);
+    merges::split::merge_chunks(&mut p, &[2, 0], "data".to_string());
+    let names: Vec<&str> = p.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["data", "api"]);
+    assert_eq!(p[0].files, vec!["db/a.sql", "models/m.rs"]);
+}
+
+#[test]
+fn test_move_files_drops_emptied_chunks() {
+    let mut p = plan(&[("db", &["db/a.sql"]), ("api", &["api/x.rs", "api/y.rs"]), ("new", &[])]);
+    merges::split::move_files(&mut p, &["api/y.rs".to_string()], 2);
+    merges::split::move_files(&mut p, &["db/a.sql".to_string()], 1);
+    let names: Vec<&str> = p.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["api", "new"]);
+    assert_eq!(p[0].files, vec!["api/x.rs", "db/a.sql"]);
+    assert_eq!(p[1].files, vec!["api/y.rs"]);
+}
+
+// ── root-level files ────────────────────────────────────────────────────────
+
+#[test]
+fn test_place_root_files_moves_them_out_of_the_root_chunk() {
+    use merges::split::{place_root_files, RootFiles};
+
+    let grouped = || plan(&[("api", &["api/x.rs"]), ("root", &["Cargo.toml", "README.md"]), ("web", &["web/a.ts"])]);
+    let names = |p: &[ChunkPlan]| p.iter().map(|c| c.name.clone()).collect::<Vec<_>>();
+
+    let last = place_root_files(grouped(), &RootFiles::Last, &[]);
+    assert_eq!(names(&last), vec!["api", "web"]);
+    assert_eq!(last[1].files, vec!["Cargo.toml", "README.md", "web/a.ts"]);
+
+    let named = place_root_files(grouped(), &"chunk:build".parse().unwrap(), &[]);
+    assert_eq!(names(&named), vec!["api", "web", "build"]);
+    assert_eq!(named[2].files, vec!["Cargo.toml", "README.md"]);
+
+    // Cargo.toml was committed with the api twice and the web chunk once; README.md has no history.
+    let commits = vec![
+        vec!["Cargo.toml".to_string(), "api/x.rs".to_string()],
+        vec!["Cargo.toml".to_string(), "api/x.rs".to_string(), "web/a.ts".to_string()],
+        vec!["Cargo.toml".to_string(), "web/b.ts".to_string()],
+        vec!["api/x.rs".to_string()],
+    ];
+    let related = place_root_files(
+        plan(&[("web", &["web/a.ts"]), ("root", &["Cargo.toml", "README.md"]), ("api", &["api/x.rs"])]),
+        &RootFiles::Related,
+        &commits,
+    );
+    assert_eq!(names(&related), vec!["web", "api"]);
+    assert_eq!(related[0].files, vec!["README.md", "web/a.ts"]);
+    assert_eq!(related[1].files, vec!["Cargo.toml", "api/x.rs"]);
+
+    let alone = place_root_files(plan(&[("root", &["Cargo.toml"])]), &RootFiles::Last, &[]);
+    assert_eq!(names(&alone), vec!["root"]);
+    assert!("first".parse::<RootFiles>().is_err());
+}
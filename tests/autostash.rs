@@ -0,0 +1,121 @@
+//! Tests for the dirty-tree guard used by `merges push` and `merges sync`.
+
+use std::path::{Path, PathBuf};
+use std::process::Command as StdCommand;
+use tempfile::TempDir;
+
+use merges::autostash::Autostash;
+use merges::state::MergesState;
+
+fn git(root: &Path, args: &[&str]) -> String {
+    let out = StdCommand::new("git").args(args).current_dir(root).output().unwrap();
+    assert!(out.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&out.stderr));
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
+
+/// A repo on `feat/big` with a `chunk` branch and an uncommitted edit to README.md.
+fn make_dirty_repo(use_worktrees: bool) -> (TempDir, PathBuf, MergesState) {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().to_path_buf();
+
+    git(&root, &["init", "-b", "main"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    git(&root, &["config", "user.name", "Test"]);
+    std::fs::write(root.join("README.md"), "hello").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "init"]);
+    git(&root, &["branch", "chunk"]);
+    git(&root, &["checkout", "-q", "-b", "feat/big"]);
+    std::fs::write(root.join("README.md"), "work in progress").unwrap();
+
+    let state: MergesState = serde_json::from_value(serde_json::json!({
+        "base_branch": "main",
+        "source_branch": "feat/big",
+        "repo_owner": "acme",
+        "repo_name": "myrepo",
+        "strategy": "stacked",
+        "use_worktrees": use_worktrees,
+        "chunks": []
+    }))
+    .unwrap();
+
+    (dir, root, state)
+}
+
+#[test]
+fn test_dirty_tree_is_refused_without_autostash() {
+    let (_dir, root, state) = make_dirty_repo(false);
+    let err = Autostash::begin(&root, &state, "sync", false).err().unwrap();
+    assert!(err.to_string().contains("--autostash"), "{}", err);
+    assert_eq!(std::fs::read_to_string(root.join("README.md")).unwrap(), "work in progress");
+}
+
+#[test]
+fn test_worktree_mode_skips_the_check() {
+    let (_dir, root, state) = make_dirty_repo(true);
+    let stash = Autostash::begin(&root, &state, "push", false).unwrap();
+    stash.finish(Ok(())).unwrap();
+    assert_eq!(git(&root, &["stash", "list"]), "");
+}
+
+/// The changes are stashed for the run and come back on the original branch,
+/// even when the operation fails on another branch.
+#[test]
+fn test_autostash_restores_changes_after_a_failed_run() {
+    let (_dir, root, state) = make_dirty_repo(false);
+    let stash = Autostash::begin(&root, &state, "sync", true).unwrap();
+    assert_eq!(std::fs::read_to_string(root.join("README.md")).unwrap(), "hello");
+
+    git(&root, &["checkout", "-q", "chunk"]);
+    let err = stash.finish::<()>(Err(anyhow::anyhow!("rebase failed"))).unwrap_err();
+    assert_eq!(err.to_string(), "rebase failed");
+
+    assert_eq!(git(&root, &["rev-parse", "--abbrev-ref", "HEAD"]), "feat/big");
+    assert_eq!(std::fs::read_to_string(root.join("README.md")).unwrap(), "work in progress");
+    assert_eq!(git(&root, &["stash", "list"]), "");
+}
+
+/// `merges init --autostash` makes stashing the default.
+#[test]
+fn test_autostash_setting_enables_stashing() {
+    let (_dir, root, mut state) = make_dirty_repo(false);
+    state.autostash = true;
+    let stash = Autostash::begin(&root, &state, "move", false).unwrap();
+    assert_eq!(std::fs::read_to_string(root.join("README.md")).unwrap(), "hello");
+    stash.finish(Ok(())).unwrap();
+    assert_eq!(std::fs::read_to_string(root.join("README.md")).unwrap(), "work in progress");
+}
+
+/// `merges add` amends the chunk branch with the changes stashed, then puts them back.
+#[test]
+fn test_add_with_autostash_keeps_uncommitted_changes() {
+    let (_dir, root, _) = make_dirty_repo(false);
+    git(&root, &["stash", "-q"]);
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(root.join("src/a.rs"), "// a").unwrap();
+    std::fs::write(root.join("src/b.rs"), "// b").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-q", "-m", "add files"]);
+    let state = serde_json::json!({
+        "base_branch": "main",
+        "source_branch": "feat/big",
+        "repo_owner": "acme",
+        "repo_name": "myrepo",
+        "strategy": "stacked",
+        "chunks": []
+    });
+    std::fs::write(root.join(".merges.json"), serde_json::to_string_pretty(&state).unwrap()).unwrap();
+    merges::split::apply_plan(&root, vec![
+        merges::split::ChunkPlan { name: "part-a".to_string(), files: vec!["src/a.rs".to_string()], ..Default::default() },
+    ])
+    .unwrap();
+    git(&root, &["stash", "pop", "-q"]);
+
+    let err = merges::commands::add::run(&root, "part-a", &["src/b.rs".to_string()], false).unwrap_err();
+    assert!(err.to_string().contains("uncommitted changes"), "{}", err);
+
+    merges::commands::add::run(&root, "part-a", &["src/b.rs".to_string()], true).unwrap();
+    assert_eq!(git(&root, &["show", "feat/big-chunk-1-part-a:src/b.rs"]), "// b");
+    assert_eq!(git(&root, &["rev-parse", "--abbrev-ref", "HEAD"]), "feat/big");
+    assert_eq!(std::fs::read_to_string(root.join("README.md")).unwrap(), "work in progress");
+}
@@ -0,0 +1,86 @@
+//! Integration tests for `merges backport`.
+
+use std::path::{Path, PathBuf};
+use std::process::Command as StdCommand;
+use tempfile::TempDir;
+
+fn git(root: &Path, args: &[&str]) -> String {
+    let out = StdCommand::new("git").args(args).current_dir(root).output().unwrap();
+    assert!(out.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&out.stderr));
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
+
+/// A split `feat/big` with `models` and `api` chunks, and a review fix
+/// committed on the `models` chunk branch.
+fn make_split_repo() -> (TempDir, PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().to_path_buf();
+
+    git(&root, &["init", "-b", "main"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    git(&root, &["config", "user.name", "Test"]);
+    std::fs::write(root.join("README.md"), "hello").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "init"]);
+    git(&root, &["checkout", "-q", "-b", "feat/big"]);
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(root.join("src/models.rs"), "struct User;\n").unwrap();
+    std::fs::write(root.join("src/api.rs"), "fn routes() {}\n").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "add feature files"]);
+
+    let state = serde_json::json!({
+        "base_branch": "main",
+        "source_branch": "feat/big",
+        "repo_owner": "acme",
+        "repo_name": "myrepo",
+        "strategy": "stacked",
+        "chunks": []
+    });
+    std::fs::write(root.join(".merges.json"), serde_json::to_string_pretty(&state).unwrap()).unwrap();
+    merges::split::apply_plan(&root, vec![
+        merges::split::ChunkPlan { name: "models".to_string(), files: vec!["src/models.rs".to_string()], ..Default::default() },
+        merges::split::ChunkPlan { name: "api".to_string(), files: vec!["src/api.rs".to_string()], ..Default::default() },
+    ])
+    .unwrap();
+
+    git(&root, &["checkout", "-q", "feat/big-chunk-1-models"]);
+    std::fs::write(root.join("src/models.rs"), "struct User { id: u64 }\n").unwrap();
+    git(&root, &["commit", "-q", "-am", "give users an id"]);
+    git(&root, &["checkout", "-q", "main"]);
+
+    (dir, root)
+}
+
+#[test]
+fn test_backport_picks_review_fix_onto_source() {
+    let (_dir, root) = make_split_repo();
+    let fix = git(&root, &["rev-parse", "feat/big-chunk-1-models"]);
+
+    let picked = merges::commands::backport::run(&root, "models", false).unwrap();
+    assert_eq!(picked, vec![fix.clone()]);
+
+    assert_eq!(git(&root, &["show", "feat/big:src/models.rs"]), "struct User { id: u64 }");
+    assert_eq!(git(&root, &["log", "-1", "--format=%s", "feat/big"]), "give users an id");
+    assert!(git(&root, &["log", "-1", "--format=%b", "feat/big"]).contains(&fix));
+    assert_eq!(git(&root, &["rev-parse", "--abbrev-ref", "HEAD"]), "main");
+}
+
+/// The split commit is not backported, and neither is a fix twice.
+#[test]
+fn test_backport_is_idempotent() {
+    let (_dir, root) = make_split_repo();
+    merges::commands::backport::run(&root, "models", false).unwrap();
+    let source = git(&root, &["rev-parse", "feat/big"]);
+
+    assert!(merges::commands::backport::run(&root, "models", false).unwrap().is_empty());
+    assert!(merges::commands::backport::run(&root, "api", false).unwrap().is_empty());
+    assert_eq!(git(&root, &["rev-parse", "feat/big"]), source);
+}
+
+#[test]
+fn test_backport_rejects_unknown_chunk() {
+    let (_dir, root) = make_split_repo();
+    let err = merges::commands::backport::run(&root, "nope", false).unwrap_err();
+    assert!(err.to_string().contains("No chunk named 'nope'"), "{}", err);
+}
@@ -0,0 +1,54 @@
+//! Tests for cancelling long-running commands between chunks.
+
+use std::path::Path;
+use std::process::Command as StdCommand;
+use tempfile::TempDir;
+
+use merges::cancel::CancelToken;
+
+fn git(root: &Path, args: &[&str]) -> String {
+    let out = StdCommand::new("git").args(args).current_dir(root).output().unwrap();
+    assert!(out.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&out.stderr));
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
+
+/// A cancelled sync starts no rebase and leaves the user on their branch.
+#[test]
+fn test_cancelled_sync_stops_before_the_first_chunk() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    git(root, &["init", "-b", "main"]);
+    git(root, &["config", "user.email", "test@example.com"]);
+    git(root, &["config", "user.name", "Test"]);
+    std::fs::write(root.join("README.md"), "hello").unwrap();
+    git(root, &["add", "."]);
+    git(root, &["commit", "-m", "init"]);
+    git(root, &["checkout", "-q", "-b", "feat/big"]);
+    std::fs::write(root.join("a.rs"), "// a").unwrap();
+    git(root, &["add", "."]);
+    git(root, &["commit", "-q", "-m", "add a"]);
+    let state = serde_json::json!({
+        "base_branch": "main",
+        "source_branch": "feat/big",
+        "repo_owner": "acme",
+        "repo_name": "myrepo",
+        "strategy": "stacked",
+        "chunks": []
+    });
+    std::fs::write(root.join(".merges.json"), serde_json::to_string_pretty(&state).unwrap()).unwrap();
+    merges::split::apply_plan(root, vec![
+        merges::split::ChunkPlan { name: "a".to_string(), files: vec!["a.rs".to_string()], ..Default::default() },
+    ])
+    .unwrap();
+    std::env::set_current_dir(root).unwrap();
+
+    let cancel = CancelToken::default();
+    cancel.cancel();
+    let err = merges::commands::sync::run(false, false, false, false, false, &[], &cancel).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Cancelled after rebasing 0 of 1 chunk(s) — run `merges sync` again to finish"
+    );
+    assert_eq!(git(root, &["rev-parse", "--abbrev-ref", "HEAD"]), "feat/big");
+    assert!(merges::journal::load(root).unwrap().iter().all(|e| e.operation != "sync"));
+}
@@ -0,0 +1,115 @@
+//! Predicting which workflows each chunk's PR runs from the path filters in
+//! `.github/workflows` on the source branch.
+
+use std::path::{Path, PathBuf};
+use std::process::Command as StdCommand;
+use tempfile::TempDir;
+
+fn git(root: &Path, args: &[&str]) -> String {
+    let out = StdCommand::new("git").args(args).current_dir(root).output().unwrap();
+    assert!(out.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&out.stderr));
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
+
+fn write(root: &Path, path: &str, content: &str) {
+    let path = root.join(path);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+/// `feat/big` adds a backend workflow filtered to `src/**`, a docs workflow
+/// filtered to `docs/**`, files under both, and a file neither matches.
+fn make_repo() -> (TempDir, PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().join("repo");
+    std::fs::create_dir_all(&root).unwrap();
+
+    git(&root, &["init", "-b", "main"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    git(&root, &["config", "user.name", "Test"]);
+    write(&root, "README.md", "hello\n");
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "init"]);
+
+    git(&root, &["checkout", "-q", "-b", "feat/big"]);
+    write(&root, ".github/workflows/backend.yml", "name: Backend\non:\n  pull_request:\n    paths: ['src/**']\n");
+    write(&root, ".github/workflows/docs.yml", "name: Docs\non:\n  pull_request:\n    paths:\n      - 'docs/**'\n");
+    write(&root, "src/lib.rs", "pub fn f() {}\n");
+    write(&root, "src/api.rs", "pub fn g() {}\n");
+    write(&root, "docs/guide.md", "# Guide\n");
+    write(&root, "notes.txt", "todo\n");
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "work"]);
+
+    let state = serde_json::json!({
+        "base_branch": "main",
+        "source_branch": "feat/big",
+        "repo_owner": "acme",
+        "repo_name": "myrepo",
+        "strategy": "independent",
+        "use_worktrees": true,
+        "chunks": []
+    });
+    std::fs::write(root.join(".merges.json"), serde_json::to_string_pretty(&state).unwrap()).unwrap();
+    (dir, root)
+}
+
+fn plan(name: &str, files: &[&str]) -> merges::split::ChunkPlan {
+    merges::split::ChunkPlan {
+        name: name.to_string(),
+        files: files.iter().map(|f| f.to_string()).collect(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_predict_and_warn_per_chunk() {
+    let (_dir, root) = make_repo();
+    merges::split::apply_plan(&root, vec![
+        plan("ci", &[".github/workflows/backend.yml", ".github/workflows/docs.yml", "notes.txt"]),
+        plan("code", &["src/lib.rs"]),
+        plan("api", &["src/api.rs", "docs/guide.md"]),
+    ])
+    .unwrap();
+    let state = merges::state::MergesState::load(&root).unwrap();
+
+    let workflows = merges::ci::workflows(&root, "feat/big");
+    let predicted: Vec<(String, Vec<String>)> =
+        merges::ci::predict(&state, &state.strategy, &workflows).into_iter().map(|p| (p.chunk, p.workflows)).collect();
+    assert_eq!(predicted, vec![
+        ("ci".to_string(), vec![]),
+        ("code".to_string(), vec!["Backend".to_string()]),
+        ("api".to_string(), vec!["Backend".to_string(), "Docs".to_string()]),
+    ]);
+
+    let warnings = merges::ci::warnings(&state, &state.strategy, &workflows);
+    assert_eq!(warnings.len(), 2, "{:?}", warnings);
+    assert!(warnings[0].contains("'ci' triggers no CI workflow"), "{}", warnings[0]);
+    assert!(warnings[1].contains("'api' triggers every CI workflow (Backend, Docs)"), "{}", warnings[1]);
+}
+
+#[test]
+fn test_no_workflows_means_no_prediction() {
+    let (_dir, root) = make_repo();
+    assert!(merges::ci::workflows(&root, "main").is_empty());
+}
+
+/// Stacked, a PR into the chunk below does not run a workflow limited to PRs
+/// into `main`, and its chunk is warned about.
+#[test]
+fn test_branch_filters_leave_out_stacked_prs() {
+    let (_dir, root) = make_repo();
+    merges::split::apply_plan(&root, vec![plan("code", &["src/lib.rs"]), plan("api", &["src/api.rs"])]).unwrap();
+    let state = merges::state::MergesState::load(&root).unwrap();
+    let workflows = vec![merges::ci::parse_workflow("ci.yml", "on:\n  pull_request:\n    branches: [main]\n").unwrap()];
+
+    let runs = |strategy| -> Vec<usize> {
+        merges::ci::predict(&state, &strategy, &workflows).iter().map(|p| p.workflows.len()).collect()
+    };
+    assert_eq!(runs(merges::state::Strategy::Independent), vec![1, 1]);
+    assert_eq!(runs(merges::state::Strategy::Stacked), vec![1, 0]);
+
+    let warnings = merges::ci::warnings(&state, &merges::state::Strategy::Stacked, &workflows);
+    assert_eq!(warnings.len(), 1, "{:?}", warnings);
+    assert!(warnings[0].contains("'api'") && warnings[0].contains("`branches:`"), "{}", warnings[0]);
+}
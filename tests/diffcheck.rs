@@ -0,0 +1,104 @@
+//! Integration tests for `merges diffcheck`.
+
+use std::path::{Path, PathBuf};
+use std::process::Command as StdCommand;
+use tempfile::TempDir;
+
+use merges::commands::diffcheck;
+use merges::state::MergesState;
+
+fn git(root: &Path, args: &[&str]) -> String {
+    let out = StdCommand::new("git").args(args).current_dir(root).output().unwrap();
+    assert!(out.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&out.stderr));
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
+
+/// `feat/big` changes `src/models.rs`, `src/api.rs`, and `docs/notes.md`;
+/// the chunks are built from `plan`.
+fn make_split_repo(plan: &[(&str, &[&str])]) -> (TempDir, PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().to_path_buf();
+
+    git(&root, &["init", "-b", "main"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    git(&root, &["config", "user.name", "Test"]);
+    std::fs::write(root.join("README.md"), "hello").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "init"]);
+    git(&root, &["checkout", "-q", "-b", "feat/big"]);
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::create_dir_all(root.join("docs")).unwrap();
+    std::fs::write(root.join("src/models.rs"), "struct User;\n").unwrap();
+    std::fs::write(root.join("src/api.rs"), "fn routes() {}\n").unwrap();
+    std::fs::write(root.join("docs/notes.md"), "notes\n").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "add feature files"]);
+
+    let state = serde_json::json!({
+        "base_branch": "main",
+        "source_branch": "feat/big",
+        "repo_owner": "acme",
+        "repo_name": "myrepo",
+        "strategy": "stacked",
+        "chunks": []
+    });
+    std::fs::write(root.join(".merges.json"), serde_json::to_string_pretty(&state).unwrap()).unwrap();
+    let plan = plan
+        .iter()
+        .map(|(name, files)| merges::split::ChunkPlan {
+            name: name.to_string(),
+            files: files.iter().map(|f| f.to_string()).collect(),
+            ..Default::default()
+        })
+        .collect();
+    merges::split::apply_plan(&root, plan).unwrap();
+
+    (dir, root)
+}
+
+#[test]
+fn test_chunks_that_cover_the_source_are_consistent() {
+    let (_dir, root) = make_split_repo(&[("models", &["src/models.rs"]), ("rest", &["src/api.rs", "docs/notes.md"])]);
+
+    let report = diffcheck::check(&root, &MergesState::load(&root).unwrap()).unwrap();
+    assert!(report.is_consistent(), "{:?}", report);
+}
+
+#[test]
+fn test_files_in_no_chunk_are_missing() {
+    let (_dir, root) = make_split_repo(&[("models", &["src/models.rs"]), ("api", &["src/api.rs"])]);
+
+    let report = diffcheck::check(&root, &MergesState::load(&root).unwrap()).unwrap();
+    assert!(!report.is_consistent());
+    assert_eq!(report.missing, vec!["docs/notes.md"]);
+}
+
+/// Excluded files are meant to stay out of every chunk.
+#[test]
+fn test_excluded_files_are_not_missing() {
+    let (_dir, root) = make_split_repo(&[("models", &["src/models.rs"]), ("api", &["src/api.rs"])]);
+    let mut state = MergesState::load(&root).unwrap();
+    state.exclude = vec!["docs".to_string()];
+
+    let report = diffcheck::check(&root, &state).unwrap();
+    assert!(report.is_consistent(), "{:?}", report);
+    assert_eq!(report.excluded, vec!["docs/notes.md"]);
+}
+
+#[test]
+fn test_chunk_edits_the_source_lacks_are_reported() {
+    let (_dir, root) = make_split_repo(&[("models", &["src/models.rs"]), ("rest", &["src/api.rs", "docs/notes.md"])]);
+    git(&root, &["checkout", "-q", "feat/big-chunk-1-models"]);
+    std::fs::write(root.join("src/models.rs"), "struct User;\nstruct Team;\n").unwrap();
+    std::fs::write(root.join("src/debug.rs"), "fn dump() {}\n").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-q", "-m", "wip"]);
+    git(&root, &["checkout", "-q", "feat/big"]);
+
+    let report = diffcheck::check(&root, &MergesState::load(&root).unwrap()).unwrap();
+    assert!(report.missing.is_empty(), "{:?}", report.missing);
+    assert_eq!(report.extra, vec![("models".to_string(), vec!["src/debug.rs".to_string()])]);
+    assert_eq!(report.mismatched.len(), 1);
+    assert_eq!(report.mismatched[0].file, "src/models.rs");
+    assert_eq!(report.mismatched[0].hunks, vec!["@@ -2 +1,0 @@"]);
+}
@@ -1,5 +1,6 @@
 //! TDD tests for `merges doctor` — RED phase.
 
+use merges::forge::{memory::MemoryForge, Access};
 use std::fs;
 use std::process::Command as StdCommand;
 use tempfile::TempDir;
@@ -167,3 +168,93 @@ fn test_doctor_detects_duplicate_files_in_state() {
     let has_dup = report.issues.iter().any(|i| i.contains("src/lib.rs") || i.contains("duplicate"));
     assert!(has_dup, "Should detect duplicate file across chunks: {:?}", report.issues);
 }
+
+/// With a branch namespace set, doctor flags chunk branches outside it and
+/// --repair renames them into it.
+#[test]
+fn test_doctor_repair_moves_branches_into_namespace() {
+    let (_dir, root) = make_repo_with_state();
+    StdCommand::new("git").args(["branch", "feat/big-chunk-1-models"]).current_dir(&root).output().unwrap();
+    write_state_with_chunk(&root, "feat/big-chunk-1-models");
+    merges::git::ensure_gitignored(&root, ".merges.json").unwrap();
+    let mut state = merges::state::MergesState::load(&root).unwrap();
+    state.branch_namespace = Some("merges".to_string());
+    state.save(&root).unwrap();
+
+    let report = merges::doctor::run(&root, false).unwrap();
+    assert!(report.issues.iter().any(|i| i.contains("outside namespace 'merges'")), "{:?}", report.issues);
+
+    let report = merges::doctor::run(&root, true).unwrap();
+    assert!(report.all_ok(), "{:?}", report.issues);
+    let state = merges::state::MergesState::load(&root).unwrap();
+    assert_eq!(state.chunks[0].branch, "merges/feat/big/1-models");
+    let out = StdCommand::new("git").args(["branch", "--list", "merges/feat/big/1-models"]).current_dir(&root).output().unwrap();
+    assert!(!String::from_utf8_lossy(&out.stdout).trim().is_empty(), "branch should be renamed");
+}
+
+/// The state from `make_repo_with_state`, healthy, with a bare `origin`
+/// that has `main`.
+fn make_repo_with_origin() -> (TempDir, std::path::PathBuf) {
+    let (dir, root) = make_repo_with_state();
+    let origin = dir.path().join("origin.git");
+    StdCommand::new("git").args(["init", "-q", "--bare", "-b", "main", origin.to_str().unwrap()]).output().unwrap();
+    StdCommand::new("git").args(["remote", "add", "origin", origin.to_str().unwrap()]).current_dir(&root).output().unwrap();
+    StdCommand::new("git").args(["push", "-q", "origin", "main"]).current_dir(&root).output().unwrap();
+    StdCommand::new("git").args(["branch", "feat/big-chunk-models"]).current_dir(&root).output().unwrap();
+    write_state_with_chunk(&root, "feat/big-chunk-models");
+    merges::git::ensure_gitignored(&root, ".merges.json").unwrap();
+    (dir, root)
+}
+
+/// A reachable origin with the base branch, rerere on, and credentials the
+/// forge accepts leave nothing to report.
+#[tokio::test]
+async fn test_doctor_environment_healthy() {
+    let (_dir, root) = make_repo_with_origin();
+    merges::git::enable_rerere(&root).unwrap();
+    let forge = MemoryForge::new();
+
+    let issues = merges::doctor::environment_with(&root, false, &forge).await.unwrap();
+    assert!(issues.is_empty(), "{:?}", issues);
+}
+
+/// rerere turned off is reported, and --repair turns it on.
+#[tokio::test]
+async fn test_doctor_environment_repairs_rerere() {
+    let (_dir, root) = make_repo_with_origin();
+    StdCommand::new("git").args(["config", "rerere.enabled", "false"]).current_dir(&root).output().unwrap();
+    let forge = MemoryForge::new();
+
+    let issues = merges::doctor::environment_with(&root, false, &forge).await.unwrap();
+    assert!(issues.iter().any(|i| i.contains("rerere is disabled")), "{:?}", issues);
+
+    let issues = merges::doctor::environment_with(&root, true, &forge).await.unwrap();
+    assert!(issues.is_empty(), "{:?}", issues);
+    assert_eq!(merges::git::config_value(&root, "rerere.enabled").as_deref(), Some("true"));
+}
+
+/// A base branch that origin does not have is reported.
+#[tokio::test]
+async fn test_doctor_environment_detects_base_missing_on_origin() {
+    let (_dir, root) = make_repo_with_origin();
+    merges::git::enable_rerere(&root).unwrap();
+    let mut state = merges::state::MergesState::load(&root).unwrap();
+    state.base_branch = "develop".to_string();
+    state.save(&root).unwrap();
+    let forge = MemoryForge::new();
+
+    let issues = merges::doctor::environment_with(&root, false, &forge).await.unwrap();
+    assert!(issues.iter().any(|i| i.contains("Base branch 'develop' does not exist on origin")), "{:?}", issues);
+}
+
+/// Credentials that may not open PRs are reported.
+#[tokio::test]
+async fn test_doctor_environment_detects_missing_permission() {
+    let (_dir, root) = make_repo_with_origin();
+    merges::git::enable_rerere(&root).unwrap();
+    let forge = MemoryForge::new();
+    forge.deny(Access::PullRequests);
+
+    let issues = merges::doctor::environment_with(&root, false, &forge).await.unwrap();
+    assert!(issues.iter().any(|i| i.contains("Credentials cannot create and update PRs")), "{:?}", issues);
+}
@@ -0,0 +1,91 @@
+//! Tests for detecting and dropping chunks left empty after a rebase.
+
+use std::path::{Path, PathBuf};
+use std::process::Command as StdCommand;
+use tempfile::TempDir;
+
+use merges::state::{MergesState, Strategy};
+
+fn git(root: &Path, args: &[&str]) -> String {
+    let out = StdCommand::new("git").args(args).current_dir(root).output().unwrap();
+    assert!(out.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&out.stderr));
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
+
+/// `feat/big` split into `part-a` (src/a.rs) and `part-b` (src/b.rs); then
+/// src/a.rs lands on main upstream and `part-a` is rebased onto it.
+fn make_repo_with_landed_chunk(strategy: &str) -> (TempDir, PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().to_path_buf();
+
+    git(&root, &["init", "-b", "main"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    git(&root, &["config", "user.name", "Test"]);
+    std::fs::write(root.join("README.md"), "hello").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "init"]);
+
+    git(&root, &["checkout", "-b", "feat/big"]);
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(root.join("src/a.rs"), "// a").unwrap();
+    std::fs::write(root.join("src/b.rs"), "// b").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "add files"]);
+
+    let state = serde_json::json!({
+        "base_branch": "main",
+        "source_branch": "feat/big",
+        "repo_owner": "acme",
+        "repo_name": "myrepo",
+        "strategy": strategy,
+        "chunks": []
+    });
+    std::fs::write(root.join(".merges.json"), serde_json::to_string_pretty(&state).unwrap()).unwrap();
+    merges::split::apply_plan(&root, vec![
+        merges::split::ChunkPlan { name: "part-a".to_string(), files: vec!["src/a.rs".to_string()], ..Default::default() },
+        merges::split::ChunkPlan { name: "part-b".to_string(), files: vec!["src/b.rs".to_string()], ..Default::default() },
+    ])
+    .unwrap();
+
+    git(&root, &["checkout", "-q", "main"]);
+    git(&root, &["checkout", "-q", "feat/big", "--", "src/a.rs"]);
+    git(&root, &["commit", "-q", "-m", "land a upstream"]);
+    let sha = git(&root, &["rev-parse", "main"]);
+    git(&root, &["update-ref", "refs/remotes/origin/main", &sha]);
+    git(&root, &["rebase", "-q", "origin/main", "feat/big-chunk-1-part-a"]);
+    git(&root, &["checkout", "-q", "feat/big"]);
+
+    (dir, root)
+}
+
+#[test]
+fn test_find_reports_chunk_whose_changes_landed() {
+    let (_dir, root) = make_repo_with_landed_chunk("independent");
+    let state = MergesState::load(&root).unwrap();
+    assert_eq!(merges::empty::find(&root, &state, &Strategy::Independent).unwrap(), vec![0]);
+}
+
+/// A stacked chunk is measured against the chunk below it, not the base.
+#[test]
+fn test_find_measures_stacked_chunks_against_previous_chunk() {
+    let (_dir, root) = make_repo_with_landed_chunk("stacked");
+    git(&root, &["branch", "-f", "feat/big-chunk-1-part-a", "feat/big"]);
+    git(&root, &["branch", "-f", "feat/big-chunk-2-part-b", "feat/big"]);
+    let state = MergesState::load(&root).unwrap();
+    assert_eq!(merges::empty::find(&root, &state, &Strategy::Stacked).unwrap(), vec![1]);
+    assert!(merges::empty::find(&root, &state, &Strategy::Independent).unwrap().is_empty());
+}
+
+#[test]
+fn test_drop_chunks_removes_branch_and_state() {
+    let (_dir, root) = make_repo_with_landed_chunk("independent");
+    let mut state = MergesState::load(&root).unwrap();
+    let dropped = merges::empty::drop_chunks(&root, &mut state, &[0]).unwrap();
+    assert_eq!(dropped[0].name, "part-a");
+
+    let saved = MergesState::load(&root).unwrap();
+    assert_eq!(saved.chunks.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(), vec!["part-b"]);
+    assert!(merges::git::rev_parse(&root, "refs/heads/feat/big-chunk-1-part-a").is_err());
+    let ops: Vec<String> = merges::journal::load(&root).unwrap().into_iter().map(|e| e.operation).collect();
+    assert_eq!(ops.last().map(String::as_str), Some("drop"));
+}
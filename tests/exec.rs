@@ -0,0 +1,133 @@
+//! Integration tests for `merges exec`.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command as StdCommand;
+use tempfile::TempDir;
+
+fn git(root: &Path, args: &[&str]) -> String {
+    let out = StdCommand::new("git").args(args).current_dir(root).output().unwrap();
+    assert!(out.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&out.stderr));
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
+
+/// A split `feat/big` with `models` and `api` chunks in worktrees.
+fn make_split_repo() -> (TempDir, PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().to_path_buf();
+
+    git(&root, &["init", "-b", "main"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    git(&root, &["config", "user.name", "Test"]);
+    std::fs::write(root.join("README.md"), "hello").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "init"]);
+    git(&root, &["checkout", "-q", "-b", "feat/big"]);
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(root.join("src/models.rs"), "struct User;").unwrap();
+    std::fs::write(root.join("src/api.rs"), "fn routes() {}").unwrap();
+    std::fs::write(root.join("src/api_types.rs"), "struct Req;").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "add feature files"]);
+
+    let state = serde_json::json!({
+        "base_branch": "main",
+        "source_branch": "feat/big",
+        "repo_owner": "acme",
+        "repo_name": "myrepo",
+        "strategy": "stacked",
+        "use_worktrees": true,
+        "chunks": []
+    });
+    std::fs::write(root.join(".merges.json"), serde_json::to_string_pretty(&state).unwrap()).unwrap();
+    merges::split::apply_plan(&root, vec![
+        merges::split::ChunkPlan { name: "models".to_string(), files: vec!["src/models.rs".to_string()], ..Default::default() },
+        merges::split::ChunkPlan {
+            name: "api".to_string(),
+            files: vec!["src/api.rs".to_string(), "src/api_types.rs".to_string()],
+            ..Default::default()
+        },
+    ])
+    .unwrap();
+    (dir, root)
+}
+
+fn sh(script: &str) -> Vec<String> {
+    vec!["sh".to_string(), "-c".to_string(), script.to_string()]
+}
+
+#[test]
+fn test_exec_runs_in_each_chunk_with_its_environment() {
+    let (_dir, root) = make_split_repo();
+    let out = TempDir::new().unwrap();
+    let script = format!(
+        "printf '%s\\n%s\\n%s\\n%s\\n%s' \"$MERGES_CHUNK_NAME\" \"$MERGES_CHUNK_BRANCH\" \"$MERGES_BASE_BRANCH\" \
+         \"$MERGES_WORKTREE\" \"$MERGES_CHUNK_FILES\" > '{}'/\"$MERGES_CHUNK_NAME\"; test -f src/$MERGES_CHUNK_NAME.rs",
+        out.path().display()
+    );
+
+    let failed = merges::commands::exec::run(&root, &sh(&script), &[], false).unwrap();
+    assert!(failed.is_empty(), "{:?}", failed);
+
+    let wt = merges::git::worktree_path(&root, "feat/big-chunk-2-api");
+    assert_eq!(
+        std::fs::read_to_string(out.path().join("api")).unwrap(),
+        format!("api\nfeat/big-chunk-2-api\nmain\n{}\nsrc/api.rs\nsrc/api_types.rs", wt.display())
+    );
+    assert!(out.path().join("models").exists());
+}
+
+#[test]
+fn test_exec_reports_the_chunks_it_failed_for() {
+    let (_dir, root) = make_split_repo();
+    let failed = merges::commands::exec::run(&root, &sh("test \"$MERGES_CHUNK_NAME\" != api"), &[], false).unwrap();
+    assert_eq!(failed, vec!["api"]);
+
+    let only = merges::commands::exec::run(&root, &sh("exit 1"), &["models".to_string()], false).unwrap();
+    assert_eq!(only, vec!["models"]);
+}
+
+#[test]
+fn test_path_names_the_chunk_worktree() {
+    let (_dir, root) = make_split_repo();
+    let path = merges::commands::shell::path(&root, "api").unwrap();
+    assert_eq!(path, merges::git::worktree_path(&root, "feat/big-chunk-2-api"));
+    assert!(merges::commands::shell::path(&root, "nope").is_err());
+}
+
+/// `merges shell` runs `$SHELL` in the worktree with the chunk's environment
+/// and exits with the shell's status.
+#[test]
+fn test_shell_starts_in_the_chunk_worktree() {
+    let (_dir, root) = make_split_repo();
+    let mut shell = StdCommand::new(env!("CARGO_BIN_EXE_merges"))
+        .args(["shell", "models"])
+        .current_dir(&root)
+        .env("SHELL", "sh")
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    shell.stdin.take().unwrap().write_all(b"echo \"$MERGES_CHUNK_NAME:$(pwd -P)\"; exit 3\n").unwrap();
+    let out = shell.wait_with_output().unwrap();
+
+    assert_eq!(out.status.code(), Some(3));
+    let wt = merges::git::worktree_path(&root, "feat/big-chunk-1-models").canonicalize().unwrap();
+    assert!(String::from_utf8_lossy(&out.stdout).contains(&format!("models:{}", wt.display())));
+}
+
+/// `merges edit` hands the editor the worktree, or the chunk's files.
+#[test]
+fn test_edit_opens_the_worktree_or_files_in_the_configured_editor() {
+    let (_dir, root) = make_split_repo();
+    let out = root.join(".git/opened");
+    git(&root, &["config", "merges.editor", &format!("printf '%s\\n' > '{}'", out.display())]);
+
+    merges::commands::edit::run(&root, "api", false).unwrap();
+    let wt = merges::git::worktree_path(&root, "feat/big-chunk-2-api");
+    assert_eq!(std::fs::read_to_string(&out).unwrap(), format!("{}\n", wt.display()));
+
+    merges::commands::edit::run(&root, "api", true).unwrap();
+    let files = format!("{}\n{}\n", wt.join("src/api.rs").display(), wt.join("src/api_types.rs").display());
+    assert_eq!(std::fs::read_to_string(&out).unwrap(), files);
+}
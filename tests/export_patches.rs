@@ -0,0 +1,116 @@
+//! Integration tests for `merges export --format-patch`.
+
+use std::process::Command as StdCommand;
+use tempfile::TempDir;
+
+fn make_repo_with_changes() -> (TempDir, std::path::PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().to_path_buf();
+
+    for args in [
+        vec!["init", "-b", "main"],
+        vec!["config", "user.email", "test@example.com"],
+        vec!["config", "user.name", "Test"],
+    ] {
+        StdCommand::new("git").args(&args).current_dir(&root).output().unwrap();
+    }
+
+    std::fs::write(root.join("README.md"), "hello").unwrap();
+    StdCommand::new("git").args(["add", "."]).current_dir(&root).output().unwrap();
+    StdCommand::new("git").args(["commit", "-m", "init"]).current_dir(&root).output().unwrap();
+
+    StdCommand::new("git").args(["checkout", "-b", "feat/big"]).current_dir(&root).output().unwrap();
+    std::fs::create_dir_all(root.join("src/models")).unwrap();
+    std::fs::create_dir_all(root.join("src/api")).unwrap();
+    std::fs::write(root.join("src/models/user.rs"), "struct User;").unwrap();
+    std::fs::write(root.join("src/api/routes.rs"), "fn routes() {}").unwrap();
+    StdCommand::new("git").args(["add", "."]).current_dir(&root).output().unwrap();
+    StdCommand::new("git").args(["commit", "-m", "add feature files"]).current_dir(&root).output().unwrap();
+
+    let state = serde_json::json!({
+        "base_branch": "main",
+        "source_branch": "feat/big",
+        "repo_owner": "acme",
+        "repo_name": "myrepo",
+        "strategy": "stacked",
+        "chunks": []
+    });
+    std::fs::write(root.join(".merges.json"), serde_json::to_string_pretty(&state).unwrap()).unwrap();
+
+    let plan = vec![
+        merges::split::ChunkPlan { name: "models".to_string(), files: vec!["src/models/user.rs".to_string()], ..Default::default() },
+        merges::split::ChunkPlan { name: "api".to_string(), files: vec!["src/api/routes.rs".to_string()], ..Default::default() },
+    ];
+    merges::split::apply_plan(&root, plan).unwrap();
+
+    (dir, root)
+}
+
+/// One numbered patch per chunk, in stack order.
+#[test]
+fn test_export_writes_one_numbered_patch_per_chunk() {
+    let (_dir, root) = make_repo_with_changes();
+    let out = TempDir::new().unwrap();
+
+    let written = merges::commands::export::run(&root, out.path(), false).unwrap();
+    assert_eq!(written.len(), 2);
+
+    let first = std::fs::read_to_string(&written[0]).unwrap();
+    let second = std::fs::read_to_string(&written[1]).unwrap();
+    assert!(written[0].file_name().unwrap().to_str().unwrap().starts_with("0001-"));
+    assert!(written[1].file_name().unwrap().to_str().unwrap().starts_with("0002-"));
+    assert!(first.contains("[PATCH 1/2]") && first.contains("src/models/user.rs"), "{}", first);
+    assert!(second.contains("[PATCH 2/2]") && second.contains("src/api/routes.rs"), "{}", second);
+}
+
+/// Review commits on a chunk branch are squashed into the chunk's single patch.
+#[test]
+fn test_export_squashes_extra_commits_on_chunk_branch() {
+    let (_dir, root) = make_repo_with_changes();
+    StdCommand::new("git").args(["checkout", "feat/big-chunk-1-models"]).current_dir(&root).output().unwrap();
+    std::fs::write(root.join("src/models/user.rs"), "struct User { id: u64 }").unwrap();
+    StdCommand::new("git").args(["commit", "-am", "review fix"]).current_dir(&root).output().unwrap();
+    StdCommand::new("git").args(["checkout", "feat/big"]).current_dir(&root).output().unwrap();
+
+    let out = TempDir::new().unwrap();
+    let written = merges::commands::export::run(&root, out.path(), false).unwrap();
+    assert_eq!(written.len(), 2, "still one patch per chunk");
+
+    let first = std::fs::read_to_string(&written[0]).unwrap();
+    assert!(first.contains("struct User { id: u64 }"), "{}", first);
+}
+
+/// The series applies cleanly, in order, on top of the base branch.
+#[test]
+fn test_export_series_applies_on_base() {
+    let (_dir, root) = make_repo_with_changes();
+    let out = TempDir::new().unwrap();
+    let written = merges::commands::export::run(&root, out.path(), false).unwrap();
+
+    StdCommand::new("git").args(["checkout", "-b", "apply-test", "main"]).current_dir(&root).output().unwrap();
+    for patch in &written {
+        let status = StdCommand::new("git")
+            .args(["am", patch.to_str().unwrap()])
+            .current_dir(&root)
+            .status()
+            .unwrap();
+        assert!(status.success(), "patch {} should apply", patch.display());
+    }
+    assert!(root.join("src/models/user.rs").exists());
+    assert!(root.join("src/api/routes.rs").exists());
+}
+
+/// --cover-letter adds a [PATCH 0/N] summary listing every chunk.
+#[test]
+fn test_export_cover_letter() {
+    let (_dir, root) = make_repo_with_changes();
+    let out = TempDir::new().unwrap();
+
+    let written = merges::commands::export::run(&root, out.path(), true).unwrap();
+    assert_eq!(written.len(), 3);
+    assert!(written[0].ends_with("0000-cover-letter.patch"));
+
+    let cover = std::fs::read_to_string(&written[0]).unwrap();
+    assert!(cover.contains("[PATCH 0/2]"), "{}", cover);
+    assert!(cover.contains("models") && cover.contains("api"), "{}", cover);
+}
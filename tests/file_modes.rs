@@ -0,0 +1,95 @@
+//! Chunks carry mode changes, symlinks, and type changes exactly as the
+//! source branch has them.
+#![cfg(unix)]
+
+use std::os::unix::fs::{symlink, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::process::Command as StdCommand;
+use tempfile::TempDir;
+
+fn git(root: &Path, args: &[&str]) -> String {
+    let out = StdCommand::new("git").args(args).current_dir(root).output().unwrap();
+    assert!(out.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&out.stderr));
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
+
+/// `feat/big` makes `run.sh` executable, turns `config.toml` into a symlink,
+/// replaces the `vendor` directory with a file, and adds a `latest` symlink.
+fn make_repo() -> (TempDir, PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().to_path_buf();
+
+    git(&root, &["init", "-b", "main"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    git(&root, &["config", "user.name", "Test"]);
+    std::fs::create_dir_all(root.join("vendor")).unwrap();
+    std::fs::write(root.join("vendor/lib.rs"), "fn lib() {}\n").unwrap();
+    std::fs::write(root.join("run.sh"), "#!/bin/sh\n").unwrap();
+    std::fs::write(root.join("config.toml"), "debug = true\n").unwrap();
+    std::fs::write(root.join("config.prod.toml"), "debug = false\n").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "init"]);
+
+    git(&root, &["checkout", "-q", "-b", "feat/big"]);
+    std::fs::set_permissions(root.join("run.sh"), std::fs::Permissions::from_mode(0o755)).unwrap();
+    std::fs::remove_file(root.join("config.toml")).unwrap();
+    symlink("config.prod.toml", root.join("config.toml")).unwrap();
+    std::fs::remove_dir_all(root.join("vendor")).unwrap();
+    std::fs::write(root.join("vendor"), "see Cargo.toml\n").unwrap();
+    symlink("run.sh", root.join("latest")).unwrap();
+    git(&root, &["add", "-A"]);
+    git(&root, &["commit", "-m", "modes and links"]);
+
+    let state = serde_json::json!({
+        "base_branch": "main",
+        "source_branch": "feat/big",
+        "repo_owner": "acme",
+        "repo_name": "myrepo",
+        "strategy": "stacked",
+        "chunks": []
+    });
+    std::fs::write(root.join(".merges.json"), serde_json::to_string_pretty(&state).unwrap()).unwrap();
+    (dir, root)
+}
+
+fn tree(root: &Path, rev: &str) -> String {
+    git(root, &["ls-tree", "-r", rev])
+}
+
+#[test]
+fn test_split_keeps_modes_symlinks_and_type_changes() {
+    let (_dir, root) = make_repo();
+    let files = git(&root, &["diff", "--name-only", "main...feat/big"]);
+    let files: Vec<String> = files.lines().map(String::from).collect();
+    assert_eq!(files.len(), 5, "{:?}", files);
+
+    merges::split::apply_plan(&root, vec![
+        merges::split::ChunkPlan { name: "all".to_string(), files, ..Default::default() },
+    ])
+    .unwrap();
+
+    assert_eq!(tree(&root, "feat/big-chunk-1-all"), tree(&root, "feat/big"));
+}
+
+/// With `core.fileMode` and `core.symlinks` off, the checkout cannot show an
+/// executable bit or a symlink; the chunk still gets them from the source.
+#[test]
+fn test_split_keeps_modes_when_the_checkout_cannot_show_them() {
+    let (_dir, root) = make_repo();
+    git(&root, &["config", "core.fileMode", "false"]);
+    git(&root, &["config", "core.symlinks", "false"]);
+
+    merges::split::apply_plan(&root, vec![
+        merges::split::ChunkPlan { name: "run".to_string(), files: vec!["run.sh".to_string()], ..Default::default() },
+        merges::split::ChunkPlan {
+            name: "links".to_string(),
+            files: vec!["config.toml".to_string(), "latest".to_string()],
+            ..Default::default()
+        },
+    ])
+    .unwrap();
+
+    assert!(git(&root, &["ls-tree", "feat/big-chunk-1-run", "run.sh"]).starts_with("100755 "));
+    let links = git(&root, &["ls-tree", "feat/big-chunk-2-links", "config.toml", "latest"]);
+    assert_eq!(links.lines().filter(|l| l.starts_with("120000 ")).count(), 2, "{}", links);
+}
@@ -0,0 +1,107 @@
+//! Integration tests for `merges fix`.
+
+use std::path::{Path, PathBuf};
+use std::process::Command as StdCommand;
+use tempfile::TempDir;
+
+fn git(root: &Path, args: &[&str]) -> String {
+    let out = StdCommand::new("git").args(args).current_dir(root).output().unwrap();
+    assert!(out.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&out.stderr));
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
+
+/// A split `feat/big` with stacked `models` and `api` chunks, checked out on
+/// `feat/big`.
+fn make_split_repo() -> (TempDir, PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().to_path_buf();
+
+    git(&root, &["init", "-b", "main"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    git(&root, &["config", "user.name", "Test"]);
+    std::fs::write(root.join("README.md"), "hello").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "init"]);
+    git(&root, &["checkout", "-q", "-b", "feat/big"]);
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(root.join("src/models.rs"), "struct User;\n").unwrap();
+    std::fs::write(root.join("src/api.rs"), "fn routes() {}\n").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "add feature files"]);
+
+    let state = serde_json::json!({
+        "base_branch": "main",
+        "source_branch": "feat/big",
+        "repo_owner": "acme",
+        "repo_name": "myrepo",
+        "strategy": "stacked",
+        "chunks": []
+    });
+    std::fs::write(root.join(".merges.json"), serde_json::to_string_pretty(&state).unwrap()).unwrap();
+    merges::split::apply_plan(&root, vec![
+        merges::split::ChunkPlan { name: "models".to_string(), files: vec!["src/models.rs".to_string()], ..Default::default() },
+        merges::split::ChunkPlan { name: "api".to_string(), files: vec!["src/api.rs".to_string()], ..Default::default() },
+    ])
+    .unwrap();
+    git(&root, &["rebase", "-q", "--onto", "feat/big-chunk-1-models", "main", "feat/big-chunk-2-api"]);
+    git(&root, &["checkout", "-q", "feat/big"]);
+
+    (dir, root)
+}
+
+fn commit_on_source(root: &Path, files: &[(&str, &str)], message: &str) {
+    for (path, content) in files {
+        std::fs::write(root.join(path), content).unwrap();
+        git(root, &["add", path]);
+    }
+    git(root, &["commit", "-q", "-m", message]);
+}
+
+#[test]
+fn test_fix_auto_amends_the_owning_chunk_and_restacks() {
+    let (_dir, root) = make_split_repo();
+    commit_on_source(&root, &[("src/models.rs", "struct User { id: u64 }\n")], "Address review");
+
+    let fixed = merges::commands::fix::run(&root, None, false).unwrap();
+
+    assert_eq!(fixed, vec!["models"]);
+    assert_eq!(git(&root, &["show", "feat/big-chunk-1-models:src/models.rs"]), "struct User { id: u64 }");
+    // Amended, not given a commit on top.
+    assert_eq!(git(&root, &["rev-parse", "feat/big-chunk-1-models^"]), git(&root, &["rev-parse", "main"]));
+    assert_eq!(git(&root, &["rev-parse", "feat/big-chunk-2-api^"]), git(&root, &["rev-parse", "feat/big-chunk-1-models"]));
+    assert_eq!(git(&root, &["show", "feat/big-chunk-2-api:src/api.rs"]), "fn routes() {}");
+    assert_eq!(git(&root, &["rev-parse", "--abbrev-ref", "HEAD"]), "feat/big");
+}
+
+/// `--chunk` leaves the other chunks' fixes alone and adopts new files.
+#[test]
+fn test_fix_chunk_fixes_only_that_chunk_and_takes_new_files() {
+    let (_dir, root) = make_split_repo();
+    commit_on_source(
+        &root,
+        &[
+            ("src/models.rs", "struct User { id: u64 }\n"),
+            ("src/api.rs", "fn routes() -> Vec<()> { vec![] }\n"),
+            ("src/api_test.rs", "#[test] fn routes_empty() {}\n"),
+        ],
+        "Address review",
+    );
+
+    let fixed = merges::commands::fix::run(&root, Some("api"), false).unwrap();
+
+    assert_eq!(fixed, vec!["api"]);
+    assert_eq!(git(&root, &["show", "feat/big-chunk-1-models:src/models.rs"]), "struct User;");
+    assert_eq!(git(&root, &["show", "feat/big-chunk-2-api:src/api.rs"]), "fn routes() -> Vec<()> { vec![] }");
+    assert_eq!(git(&root, &["show", "feat/big-chunk-2-api:src/api_test.rs"]), "#[test] fn routes_empty() {}");
+    let state = merges::state::MergesState::load(&root).unwrap();
+    assert_eq!(state.chunks[1].files, vec!["src/api.rs", "src/api_test.rs"]);
+}
+
+#[test]
+fn test_fix_with_nothing_changed_does_nothing() {
+    let (_dir, root) = make_split_repo();
+    let before = git(&root, &["rev-parse", "feat/big-chunk-2-api"]);
+
+    assert!(merges::commands::fix::run(&root, None, false).unwrap().is_empty());
+    assert_eq!(git(&root, &["rev-parse", "feat/big-chunk-2-api"]), before);
+}
@@ -0,0 +1,95 @@
+//! Tests for `merges graph` rendering.
+
+use merges::commands::graph::{ascii, build, dot, mermaid};
+use merges::state::MergesState;
+
+fn state(strategy: &str) -> MergesState {
+    serde_json::from_value(serde_json::json!({
+        "base_branch": "main",
+        "source_branch": "feat/big",
+        "repo_owner": "acme",
+        "repo_name": "myrepo",
+        "strategy": strategy,
+        "chunks": [
+            {
+                "name": "db",
+                "branch": "feat/big-chunk-1-db",
+                "files": ["src/db.rs"],
+                "pr_number": 101,
+                "merged_at": "2026-03-09T16:02:00Z"
+            },
+            {
+                "name": "models",
+                "branch": "feat/big-chunk-2-models",
+                "files": ["src/models.rs"],
+                "pr_number": 102
+            },
+            { "name": "api", "branch": "feat/big-chunk-3-api", "files": ["src/api.rs"] }
+        ]
+    }))
+    .unwrap()
+}
+
+fn edges() -> Vec<(String, String)> {
+    vec![
+        ("src/api.rs".to_string(), "src/models.rs".to_string()),
+        ("src/api.rs".to_string(), "src/db.rs".to_string()),
+        ("src/models.rs".to_string(), "src/models.rs".to_string()),
+        ("src/models.rs".to_string(), "src/untracked.rs".to_string()),
+    ]
+}
+
+/// Imports within a chunk or to files outside the stack are not dependencies.
+#[test]
+fn test_build_keeps_only_cross_chunk_imports() {
+    let graph = build(&state("stacked"), &edges());
+    assert_eq!(graph.parents, vec![None, Some(0), Some(1)]);
+    assert_eq!(graph.depends_on, vec![(2, 0), (2, 1)]);
+    assert!(graph.nodes[0].merged);
+    assert!(!graph.nodes[1].merged);
+}
+
+#[test]
+fn test_ascii_nests_a_stacked_stack() {
+    let out = ascii(&build(&state("stacked"), &edges()));
+    assert_eq!(
+        out,
+        "main\n\
+         └── [1/3] db  feat/big-chunk-1-db  (#101 merged)\n    \
+             └── [2/3] models  feat/big-chunk-2-models  (#102)\n        \
+                 └── [3/3] api  feat/big-chunk-3-api  (no PR)  imports: db, models\n"
+    );
+}
+
+#[test]
+fn test_ascii_puts_independent_chunks_side_by_side() {
+    let out = ascii(&build(&state("independent"), &[]));
+    assert_eq!(
+        out,
+        "main\n\
+         ├── [1/3] db  feat/big-chunk-1-db  (#101 merged)\n\
+         ├── [2/3] models  feat/big-chunk-2-models  (#102)\n\
+         └── [3/3] api  feat/big-chunk-3-api  (no PR)\n"
+    );
+}
+
+#[test]
+fn test_mermaid_draws_branch_and_import_edges() {
+    let out = mermaid(&build(&state("stacked"), &edges()));
+    assert!(out.starts_with("flowchart TD\n    base[\"main\"]\n"), "{}", out);
+    assert!(out.contains("    c1[\"1. db<br/>#101 merged\"]\n"), "{}", out);
+    assert!(out.contains("    base --> c1\n    c1 --> c2\n    c2 --> c3\n"), "{}", out);
+    assert!(out.contains("    c3 -.->|imports| c1\n    c3 -.->|imports| c2\n"), "{}", out);
+    assert!(out.contains("    class c1 merged\n"), "{}", out);
+}
+
+#[test]
+fn test_dot_is_a_digraph_with_the_same_edges() {
+    let out = dot(&build(&state("independent"), &edges()));
+    assert!(out.starts_with("digraph stack {\n"), "{}", out);
+    assert!(out.ends_with("}\n"), "{}", out);
+    for edge in ["base -> c1;", "base -> c2;", "base -> c3;", "c3 -> c2 [style=dashed, label=\"imports\"];"] {
+        assert!(out.contains(edge), "missing {}: {}", edge, out);
+    }
+    assert!(out.contains("c1 [label=\"1. db\\n#101 merged\", style=filled"), "{}", out);
+}
@@ -0,0 +1,81 @@
+//! Tests for the operation journal behind `merges history`.
+
+use std::process::Command as StdCommand;
+use tempfile::TempDir;
+
+fn make_repo() -> (TempDir, std::path::PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().to_path_buf();
+
+    for args in [
+        vec!["init", "-b", "main"],
+        vec!["config", "user.email", "test@example.com"],
+        vec!["config", "user.name", "Test"],
+    ] {
+        StdCommand::new("git").args(&args).current_dir(&root).output().unwrap();
+    }
+
+    std::fs::write(root.join("README.md"), "hello").unwrap();
+    StdCommand::new("git").args(["add", "."]).current_dir(&root).output().unwrap();
+    StdCommand::new("git").args(["commit", "-m", "init"]).current_dir(&root).output().unwrap();
+
+    StdCommand::new("git").args(["checkout", "-b", "feat/big"]).current_dir(&root).output().unwrap();
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    for name in ["a.rs", "b.rs"] {
+        std::fs::write(root.join(format!("src/{}", name)), format!("// {}", name)).unwrap();
+    }
+    StdCommand::new("git").args(["add", "."]).current_dir(&root).output().unwrap();
+    StdCommand::new("git").args(["commit", "-m", "add files"]).current_dir(&root).output().unwrap();
+
+    let state = serde_json::json!({
+        "base_branch": "main",
+        "source_branch": "feat/big",
+        "repo_owner": "acme",
+        "repo_name": "myrepo",
+        "strategy": "stacked",
+        "chunks": []
+    });
+    std::fs::write(root.join(".merges.json"), serde_json::to_string_pretty(&state).unwrap()).unwrap();
+
+    (dir, root)
+}
+
+fn rev_parse(root: &std::path::Path, rev: &str) -> String {
+    let out = StdCommand::new("git").args(["rev-parse", rev]).current_dir(root).output().unwrap();
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
+
+#[test]
+fn test_empty_journal_loads_as_no_entries() {
+    let (_dir, root) = make_repo();
+    assert!(merges::journal::load(&root).unwrap().is_empty());
+}
+
+/// Each state-changing command appends an entry with the resulting branch SHAs.
+#[test]
+fn test_split_and_add_are_journaled_with_branch_shas() {
+    let (_dir, root) = make_repo();
+    merges::split::apply_plan(&root, vec![
+        merges::split::ChunkPlan { name: "part-a".to_string(), files: vec!["src/a.rs".to_string()], ..Default::default() },
+    ])
+    .unwrap();
+    merges::commands::add::run(&root, "part-a", &["src/b.rs".to_string()], false).unwrap();
+
+    let entries = merges::journal::load(&root).unwrap();
+    let ops: Vec<&str> = entries.iter().map(|e| e.operation.as_str()).collect();
+    assert_eq!(ops, vec!["split", "add"]);
+    assert_eq!(entries[1].chunks, vec!["part-a"]);
+
+    let branch = "feat/big-chunk-1-part-a";
+    assert_eq!(entries[1].branches.get(branch), Some(&rev_parse(&root, branch)));
+    assert_ne!(entries[0].branches.get(branch), entries[1].branches.get(branch), "add amends the branch");
+}
+
+/// The journal lives in the git directory, so it never shows up as a change.
+#[test]
+fn test_journal_is_outside_the_working_tree() {
+    let (_dir, root) = make_repo();
+    let path = merges::journal::path(&root).unwrap();
+    let git_dir = path.parent().unwrap().canonicalize().unwrap();
+    assert_eq!(git_dir, root.canonicalize().unwrap().join(".git"), "{}", path.display());
+}
@@ -0,0 +1,117 @@
+//! Tests for re-running `merges init` on an existing state file.
+
+use merges::commands::init::{apply_settings, InitOptions};
+use merges::size::SizeLimits;
+use merges::state::{MergesState, Strategy};
+
+fn existing_state() -> MergesState {
+    serde_json::from_value(serde_json::json!({
+        "base_branch": "main",
+        "source_branch": "feat/big",
+        "repo_owner": "acme",
+        "repo_name": "myrepo",
+        "strategy": "stacked",
+        "exclude": ["*.snap"],
+        "chunks": [
+            { "name": "models", "branch": "feat/big-chunk-1-models", "files": ["src/models.rs"], "pr_number": 7 }
+        ]
+    }))
+    .unwrap()
+}
+
+/// Only the settings passed on this run change; chunks and PR links stay.
+#[test]
+fn test_apply_settings_updates_given_fields_only() {
+    let mut state = existing_state();
+    let opts = InitOptions {
+        base_branch: Some("develop".to_string()),
+        strategy: Some(Strategy::Independent),
+        exclude: vec!["*.snap".to_string(), "dist/".to_string()],
+        ..Default::default()
+    };
+
+    let changes = apply_settings(&mut state, &opts);
+    assert_eq!(changes, vec!["base: main → develop", "strategy: stacked → independent", "exclude: +dist/"]);
+    assert_eq!(state.base_branch, "develop");
+    assert_eq!(state.exclude, vec!["*.snap", "dist/"]);
+    assert!(state.commit_prefix.is_none());
+    assert_eq!(state.chunks[0].pr_number, Some(7));
+}
+
+#[test]
+fn test_apply_settings_with_nothing_new_reports_no_changes() {
+    let mut state = existing_state();
+    let opts = InitOptions { base_branch: Some("main".to_string()), ..Default::default() };
+    assert!(apply_settings(&mut state, &opts).is_empty());
+}
+
+#[test]
+fn test_apply_settings_sets_length_limits() {
+    let mut state = existing_state();
+    let opts = InitOptions { max_subject_length: Some(72), max_title_length: Some(72), ..Default::default() };
+    assert_eq!(apply_settings(&mut state, &opts), vec!["max subject length: 72", "max title length: 72"]);
+    assert_eq!(state.max_subject_length, Some(72));
+    assert!(apply_settings(&mut state, &opts).is_empty());
+}
+
+#[test]
+fn test_apply_settings_sets_auto_depth() {
+    let mut state = existing_state();
+    let opts = InitOptions { auto_depth: Some(3), ..Default::default() };
+    assert_eq!(apply_settings(&mut state, &opts), vec!["auto-split depth: 3"]);
+    assert_eq!(state.auto_depth, Some(3));
+}
+
+#[test]
+fn test_apply_settings_sets_root_file_placement() {
+    let mut state = existing_state();
+    let opts = InitOptions { root_files: Some("chunk:build".parse().unwrap()), ..Default::default() };
+    assert_eq!(apply_settings(&mut state, &opts), vec!["root-level files in auto splits: chunk:build"]);
+    assert!(apply_settings(&mut state, &opts).is_empty());
+    assert_eq!(state.root_files, Some(merges::split::RootFiles::Chunk("build".to_string())));
+}
+
+#[test]
+fn test_apply_settings_adds_routing_rules_once() {
+    let mut state = existing_state();
+    let route = "migrations/** -> label:db,reviewer:@acme/dba".to_string();
+    let opts = InitOptions { routes: vec![route], ..Default::default() };
+    assert_eq!(apply_settings(&mut state, &opts), vec!["routing: +migrations/** -> label:db, reviewer:@acme/dba"]);
+    assert_eq!(state.routes[0].reviewers, vec!["acme/dba"]);
+    assert!(apply_settings(&mut state, &opts).is_empty());
+}
+
+/// Statuses alone update the project already set; a new project leaves the
+/// old project's items behind.
+#[test]
+fn test_apply_settings_sets_project_and_milestone() {
+    let mut state = existing_state();
+    state.chunks[0].project_item_id = Some("PVTI_old".to_string());
+    let opts = InitOptions {
+        project: Some("acme/3".to_string()),
+        milestone: Some("v2.0".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(
+        apply_settings(&mut state, &opts),
+        vec!["project: acme/3 (open=In Progress, merged=Done)", "milestone: v2.0"]
+    );
+    assert!(state.chunks[0].project_item_id.is_none());
+    assert!(apply_settings(&mut state, &opts).is_empty());
+
+    let opts = InitOptions { project_statuses: vec!["merged=Shipped".to_string()], ..Default::default() };
+    assert_eq!(apply_settings(&mut state, &opts), vec!["project: acme/3 (merged=Shipped)"]);
+}
+
+/// Size limits given on a re-run join those already set.
+#[test]
+fn test_apply_settings_merges_size_limits() {
+    let mut state = existing_state();
+    let limits = |warn_lines, max_lines| SizeLimits { warn_lines, max_lines, ..Default::default() };
+    let opts = InitOptions { size_limits: limits(Some(400), None), ..Default::default() };
+    assert_eq!(apply_settings(&mut state, &opts), vec!["size limits: warn 400 lines"]);
+
+    let opts = InitOptions { size_limits: limits(None, Some(1000)), ..Default::default() };
+    assert_eq!(apply_settings(&mut state, &opts), vec!["size limits: warn 400 / max 1000 lines"]);
+    assert!(apply_settings(&mut state, &opts).is_empty());
+}
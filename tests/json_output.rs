@@ -0,0 +1,114 @@
+//! Tests for the global `--json` flag: stdout must hold exactly one JSON
+//! result object, whatever the command and git print along the way.
+
+use std::path::{Path, PathBuf};
+use std::process::Command as StdCommand;
+use tempfile::TempDir;
+
+fn git(root: &Path, args: &[&str]) {
+    let out = StdCommand::new("git").args(args).current_dir(root).output().unwrap();
+    assert!(out.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&out.stderr));
+}
+
+/// `feat/big` split into chunks `part-a` (src/a.rs, src/b.rs) and `part-b` (src/c.rs).
+fn make_split_repo() -> (TempDir, PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().to_path_buf();
+
+    git(&root, &["init", "-b", "main"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    git(&root, &["config", "user.name", "Test"]);
+    std::fs::write(root.join("README.md"), "hello").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "init"]);
+
+    git(&root, &["checkout", "-b", "feat/big"]);
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    for name in ["a.rs", "b.rs", "c.rs", "d.rs"] {
+        std::fs::write(root.join(format!("src/{}", name)), format!("// {}", name)).unwrap();
+    }
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "add files"]);
+
+    let state = serde_json::json!({
+        "base_branch": "main",
+        "source_branch": "feat/big",
+        "repo_owner": "acme",
+        "repo_name": "myrepo",
+        "strategy": "stacked",
+        "chunks": []
+    });
+    std::fs::write(root.join(".merges.json"), serde_json::to_string_pretty(&state).unwrap()).unwrap();
+    merges::split::apply_plan(&root, vec![
+        merges::split::ChunkPlan { name: "part-a".to_string(), files: vec!["src/a.rs".to_string(), "src/b.rs".to_string()], ..Default::default() },
+        merges::split::ChunkPlan { name: "part-b".to_string(), files: vec!["src/c.rs".to_string()], ..Default::default() },
+    ])
+    .unwrap();
+
+    (dir, root)
+}
+
+/// Run `merges --json <args>` in `root`; returns the parsed stdout and whether it succeeded.
+fn merges_json(root: &Path, args: &[&str]) -> (serde_json::Value, bool) {
+    let out = StdCommand::new(env!("CARGO_BIN_EXE_merges"))
+        .arg("--json")
+        .args(args)
+        .current_dir(root)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let value = serde_json::from_str(&stdout)
+        .unwrap_or_else(|e| panic!("stdout is not one JSON object ({}):\n{}", e, stdout));
+    (value, out.status.success())
+}
+
+#[test]
+fn test_move_prints_only_a_json_result() {
+    let (_dir, root) = make_split_repo();
+    let (result, ok) = merges_json(&root, &["move", "src/b.rs", "--from", "part-a", "--to", "part-b"]);
+    assert!(ok, "{}", result);
+    assert_eq!(result["ok"], true);
+    assert_eq!(result["operation"], "move");
+    assert_eq!(result["to"], "part-b");
+}
+
+#[test]
+fn test_add_reports_the_chunk_files() {
+    let (_dir, root) = make_split_repo();
+    let (result, ok) = merges_json(&root, &["add", "part-b", "src/d.rs"]);
+    assert!(ok, "{}", result);
+    assert_eq!(result["files"], serde_json::json!(["src/c.rs", "src/d.rs"]));
+}
+
+#[test]
+fn test_status_lists_chunks() {
+    let (_dir, root) = make_split_repo();
+    let (result, ok) = merges_json(&root, &["status"]);
+    assert!(ok, "{}", result);
+    let names: Vec<&str> = result["chunks"].as_array().unwrap().iter().map(|c| c["name"].as_str().unwrap()).collect();
+    assert_eq!(names, vec!["part-a", "part-b"]);
+}
+
+/// Doctor issues are reported in the result, with a failing exit status.
+#[test]
+fn test_doctor_issues_fail_with_a_json_result() {
+    let (_dir, root) = make_split_repo();
+    git(&root, &["branch", "-D", "feat/big-chunk-2-part-b"]);
+    let (result, ok) = merges_json(&root, &["doctor"]);
+    assert!(!ok);
+    assert_eq!(result["ok"], false);
+    assert!(result["issues"][0].as_str().unwrap().contains("feat/big-chunk-2-part-b"), "{}", result);
+}
+
+#[test]
+fn test_errors_are_reported_as_json() {
+    let (_dir, root) = make_split_repo();
+    let (result, ok) = merges_json(&root, &["move", "src/a.rs", "--from", "part-a", "--to", "nope"]);
+    assert!(!ok);
+    assert_eq!(result["ok"], false);
+    assert!(result["error"].as_str().unwrap().contains("nope"), "{}", result);
+
+    let (result, ok) = merges_json(&root, &["split", "--auto"]);
+    assert!(!ok);
+    assert!(result["error"].as_str().unwrap().contains("--json is supported by"), "{}", result);
+}
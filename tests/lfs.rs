@@ -0,0 +1,74 @@
+//! Chunks of a repository that stores large files with Git LFS.
+
+use std::path::{Path, PathBuf};
+use std::process::Command as StdCommand;
+use tempfile::TempDir;
+
+const POINTER: &str = "version https://git-lfs.github.com/spec/v1\n\
+    oid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393\n\
+    size 12345\n";
+
+fn git(root: &Path, args: &[&str]) -> String {
+    let out = StdCommand::new("git").args(args).current_dir(root).output().unwrap();
+    assert!(out.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&out.stderr));
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
+
+/// `*.bin` is tracked by LFS on `main`; `feat/big` adds `assets/model.bin`
+/// (as the pointer LFS commits for it) and `src/lib.rs`.
+fn make_repo() -> (TempDir, PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().to_path_buf();
+
+    git(&root, &["init", "-b", "main"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    git(&root, &["config", "user.name", "Test"]);
+    std::fs::write(root.join(".gitattributes"), "*.bin filter=lfs diff=lfs merge=lfs -text\n").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "init"]);
+    git(&root, &["checkout", "-q", "-b", "feat/big"]);
+    std::fs::create_dir_all(root.join("assets")).unwrap();
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(root.join("assets/model.bin"), POINTER).unwrap();
+    std::fs::write(root.join("src/lib.rs"), "fn lib() {}\n").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "add model"]);
+
+    let state = serde_json::json!({
+        "base_branch": "main",
+        "source_branch": "feat/big",
+        "repo_owner": "acme",
+        "repo_name": "myrepo",
+        "strategy": "stacked",
+        "use_worktrees": true,
+        "chunks": []
+    });
+    std::fs::write(root.join(".merges.json"), serde_json::to_string_pretty(&state).unwrap()).unwrap();
+    (dir, root)
+}
+
+#[test]
+fn test_lfs_pointers_are_found_by_content() {
+    let (_dir, root) = make_repo();
+    let files = vec!["assets/model.bin".to_string(), "src/lib.rs".to_string()];
+    assert_eq!(merges::git::lfs_pointers(&root, "feat/big", &files).unwrap(), vec!["assets/model.bin"]);
+    assert!(merges::git::lfs_pointers(&root, "main", &files).unwrap().is_empty());
+}
+
+#[test]
+fn test_chunks_commit_the_source_pointer() {
+    let (_dir, root) = make_repo();
+    merges::split::apply_plan(&root, vec![
+        merges::split::ChunkPlan { name: "model".to_string(), files: vec!["assets/model.bin".to_string()], ..Default::default() },
+        merges::split::ChunkPlan { name: "lib".to_string(), files: vec!["src/lib.rs".to_string()], ..Default::default() },
+    ])
+    .unwrap();
+
+    let branch = "feat/big-chunk-1-model";
+    assert_eq!(
+        git(&root, &["rev-parse", &format!("{}:assets/model.bin", branch)]),
+        git(&root, &["rev-parse", "feat/big:assets/model.bin"])
+    );
+    let wt = merges::git::worktree_path(&root, branch);
+    assert_eq!(std::fs::read_to_string(wt.join("assets/model.bin")).unwrap(), POINTER);
+}
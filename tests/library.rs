@@ -0,0 +1,101 @@
+//! Using merges as a library: commands driven through `run_with` with a
+//! forge supplied by the caller, following progress through a reporter.
+
+use merges::forge::{memory::MemoryForge, Forge, MergeMethod};
+use merges::output::Event;
+use std::path::{Path, PathBuf};
+use std::process::Command as StdCommand;
+use std::sync::{Arc, Mutex};
+use tempfile::TempDir;
+
+fn git(root: &Path, args: &[&str]) -> String {
+    let out = StdCommand::new("git").args(args).current_dir(root).output().unwrap();
+    assert!(out.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&out.stderr));
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
+
+/// A split `feat/big` whose `models` chunk has PR #1 and `api` chunk PR #2.
+fn make_split_repo() -> (TempDir, PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().to_path_buf();
+    git(&root, &["init", "-b", "main"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    git(&root, &["config", "user.name", "Test"]);
+    std::fs::write(root.join("README.md"), "hello").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "init"]);
+    git(&root, &["checkout", "-q", "-b", "feat/big"]);
+    std::fs::write(root.join("models.rs"), "struct User;").unwrap();
+    std::fs::write(root.join("api.rs"), "fn routes() {}").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "add feature files"]);
+
+    let state = serde_json::json!({
+        "base_branch": "main",
+        "source_branch": "feat/big",
+        "repo_owner": "acme",
+        "repo_name": "myrepo",
+        "strategy": "independent",
+        "chunks": []
+    });
+    std::fs::write(root.join(".merges.json"), serde_json::to_string_pretty(&state).unwrap()).unwrap();
+    merges::split::apply_plan(&root, vec![
+        merges::split::ChunkPlan { name: "models".to_string(), files: vec!["models.rs".to_string()], ..Default::default() },
+        merges::split::ChunkPlan { name: "api".to_string(), files: vec!["api.rs".to_string()], ..Default::default() },
+    ])
+    .unwrap();
+    let mut state = merges::state::MergesState::load(&root).unwrap();
+    state.chunks[0].pr_number = Some(1);
+    state.chunks[1].pr_number = Some(2);
+    state.save(&root).unwrap();
+    (dir, root)
+}
+
+/// A forge holding open PRs for both chunks, with `merged` merged into main.
+async fn forge_with_merged(merged: &[u64]) -> MemoryForge {
+    let forge = MemoryForge::new();
+    forge.create_pr("chunk 1 - models", "feat/big-chunk-1-models", "main", "").await.unwrap();
+    forge.create_pr("chunk 2 - api", "feat/big-chunk-2-api", "main", "").await.unwrap();
+    for &number in merged {
+        forge.merge_pr(number, MergeMethod::Squash).await.unwrap();
+    }
+    forge
+}
+
+#[tokio::test]
+async fn test_clean_uses_the_injected_forge_and_reports_progress() {
+    let (_dir, root) = make_split_repo();
+    let messages = Arc::new(Mutex::new(Vec::new()));
+    let sink = messages.clone();
+    merges::output::set_reporter(move |event: &Event| {
+        sink.lock().unwrap().push(event.clone());
+    });
+
+    let forge = forge_with_merged(&[1]).await;
+    let deleted = merges::commands::clean::run_with(&root, true, true, Some(&forge)).await.unwrap();
+
+    assert_eq!(deleted, vec!["feat/big-chunk-1-models"]);
+    assert!(git(&root, &["branch", "--list", "feat/big-chunk-1-models"]).is_empty());
+    assert!(!git(&root, &["branch", "--list", "feat/big-chunk-2-api"]).is_empty());
+    let messages = messages.lock().unwrap();
+    assert!(
+        messages.iter().any(|e| matches!(e, Event::Log { logger, message, .. }
+            if logger == "git" && message == "git branch -D feat/big-chunk-1-models")),
+        "{:?}",
+        messages
+    );
+    assert!(
+        messages.iter().any(|e| matches!(e, Event::Message { text } if text.contains("Cleaned 1 chunk(s)"))),
+        "{:?}",
+        messages
+    );
+}
+
+/// Without a forge nothing counts as merged, so nothing is deleted.
+#[tokio::test]
+async fn test_clean_merged_without_forge_keeps_every_branch() {
+    let (_dir, root) = make_split_repo();
+    let deleted = merges::commands::clean::run_with(&root, true, true, None).await.unwrap();
+    assert!(deleted.is_empty());
+    assert_eq!(merges::state::MergesState::load(&root).unwrap().chunks.len(), 2);
+}
@@ -0,0 +1,97 @@
+//! Chunks carry file content byte-for-byte as the source branch has it, even
+//! where `.gitattributes` would convert line endings on the way back in.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command as StdCommand, Stdio};
+use tempfile::TempDir;
+
+fn git(root: &Path, args: &[&str]) -> String {
+    let out = StdCommand::new("git").args(args).current_dir(root).output().unwrap();
+    assert!(out.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&out.stderr));
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
+
+/// Stage `content` at `path` as is, bypassing `.gitattributes` conversion.
+fn stage_raw(root: &Path, path: &str, content: &str) {
+    std::fs::write(root.join(path), content).unwrap();
+    let sha = git(root, &["hash-object", "-w", "--no-filters", path]);
+    git(root, &["update-index", "--add", "--cacheinfo", &format!("100644,{},{}", sha, path)]);
+}
+
+/// `main` marks every file as text; `feat/big` commits `legacy.bat` and
+/// `tools.bat` with CRLF line endings stored in the blobs, which re-staging
+/// the checkout would normalise to LF.
+fn make_repo() -> (TempDir, PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().join("repo");
+    std::fs::create_dir_all(&root).unwrap();
+
+    git(&root, &["init", "-b", "main"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    git(&root, &["config", "user.name", "Test"]);
+    std::fs::write(root.join(".gitattributes"), "* text\n").unwrap();
+    std::fs::write(root.join("README.md"), "hello\n").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "init"]);
+
+    git(&root, &["checkout", "-q", "-b", "feat/big"]);
+    stage_raw(&root, "legacy.bat", "@echo off\r\necho legacy\r\n");
+    stage_raw(&root, "tools.bat", "@echo off\r\necho tools\r\n");
+    git(&root, &["commit", "-m", "add batch files"]);
+
+    let state = serde_json::json!({
+        "base_branch": "main",
+        "source_branch": "feat/big",
+        "repo_owner": "acme",
+        "repo_name": "myrepo",
+        "strategy": "independent",
+        "use_worktrees": true,
+        "chunks": []
+    });
+    std::fs::write(root.join(".merges.json"), serde_json::to_string_pretty(&state).unwrap()).unwrap();
+    (dir, root)
+}
+
+fn blob(root: &Path, rev: &str, path: &str) -> String {
+    git(root, &["rev-parse", &format!("{}:{}", rev, path)])
+}
+
+fn assert_no_diff(root: &Path, chunk: &str, path: &str) {
+    let out = StdCommand::new("git")
+        .args(["diff", "--quiet", "feat/big", chunk, "--", path])
+        .current_dir(root)
+        .stdout(Stdio::null())
+        .status()
+        .unwrap();
+    assert!(out.success(), "{} differs from feat/big in {}", path, chunk);
+}
+
+#[test]
+fn test_split_keeps_crlf_blobs_the_attributes_would_normalise() {
+    let (_dir, root) = make_repo();
+
+    merges::split::apply_plan(&root, vec![
+        merges::split::ChunkPlan { name: "legacy".to_string(), files: vec!["legacy.bat".to_string()], ..Default::default() },
+        merges::split::ChunkPlan { name: "tools".to_string(), files: vec!["tools.bat".to_string()], ..Default::default() },
+    ])
+    .unwrap();
+
+    assert_eq!(blob(&root, "feat/big-chunk-1-legacy", "legacy.bat"), blob(&root, "feat/big", "legacy.bat"));
+    assert_no_diff(&root, "feat/big-chunk-2-tools", "tools.bat");
+}
+
+/// Moving a file amends both chunk commits without re-staging the checkout.
+#[test]
+fn test_move_keeps_crlf_blobs() {
+    let (_dir, root) = make_repo();
+    merges::split::apply_plan(&root, vec![
+        merges::split::ChunkPlan { name: "legacy".to_string(), files: vec!["legacy.bat".to_string()], ..Default::default() },
+        merges::split::ChunkPlan { name: "tools".to_string(), files: vec!["tools.bat".to_string()], ..Default::default() },
+    ])
+    .unwrap();
+
+    merges::commands::r#move::run(&root, "tools.bat", "tools", "legacy", false).unwrap();
+
+    assert_no_diff(&root, "feat/big-chunk-1-legacy", "legacy.bat");
+    assert_no_diff(&root, "feat/big-chunk-1-legacy", "tools.bat");
+}
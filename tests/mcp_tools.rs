@@ -188,3 +188,142 @@ fn test_mcp_status_includes_sync_info() {
     assert!(sync_label.contains("current") || sync_label.contains("behind"),
         "sync label should be meaningful: {}", sync_label);
 }
+
+// ── merges_init is non-interactive ────────────────────────────────────────────
+
+/// Re-initialising without overwrite updates settings and keeps the chunks.
+#[test]
+fn test_mcp_init_existing_state_keeps_chunks() {
+    let (_dir, root) = make_repo_with_two_chunks();
+    std::env::set_current_dir(&root).unwrap();
+
+    let result = merges::mcp::call_tool_sync(
+        "merges_init",
+        &serde_json::json!({"base_branch": "develop", "strategy": "stacked"}),
+    );
+    assert!(result.is_ok(), "merges_init should update existing state: {:?}", result);
+
+    let state = merges::state::MergesState::load(&root).unwrap();
+    assert_eq!(state.base_branch, "develop");
+    assert_eq!(state.strategy, merges::state::Strategy::Stacked);
+    assert_eq!(state.chunks.len(), 2, "chunks must survive a re-init");
+}
+
+/// overwrite + strategy replaces the state file with the requested topology.
+#[test]
+fn test_mcp_init_overwrite_sets_strategy() {
+    let (_dir, root) = make_repo_with_two_chunks();
+    std::env::set_current_dir(&root).unwrap();
+    StdCommand::new("git")
+        .args(["remote", "add", "origin", "https://github.com/acme/myrepo.git"])
+        .current_dir(&root)
+        .output()
+        .unwrap();
+
+    let result = merges::mcp::call_tool_sync(
+        "merges_init",
+        &serde_json::json!({"base_branch": "main", "strategy": "independent", "overwrite": true}),
+    );
+    assert!(result.is_ok(), "merges_init overwrite should succeed: {:?}", result);
+
+    let state = merges::state::MergesState::load(&root).unwrap();
+    assert_eq!(state.strategy, merges::state::Strategy::Independent);
+    assert!(state.chunks.is_empty(), "overwrite starts from a clean state");
+}
+
+// ── merges_plan_preview MCP tool ──────────────────────────────────────────────
+
+/// The preview groups the changed files with line counts and leaves state alone.
+#[test]
+fn test_mcp_plan_preview_is_read_only() {
+    let (_dir, root) = make_repo_with_two_chunks();
+    std::env::set_current_dir(&root).unwrap();
+    let before = fs::read_to_string(root.join(".merges.json")).unwrap();
+
+    let result = merges::mcp::call_tool_sync("merges_plan_preview", &serde_json::json!({"max_files": 1}));
+    let report: serde_json::Value = serde_json::from_str(&result.expect("merges_plan_preview should succeed")).unwrap();
+
+    let names: Vec<&str> = report["chunks"].as_array().unwrap().iter().map(|c| c["name"].as_str().unwrap()).collect();
+    assert_eq!(names, vec!["api", "models-1", "models-2"]);
+    assert_eq!(report["chunks"][0]["files"][0]["path"], "src/api/routes.rs");
+    assert_eq!(report["chunks"][0]["added"], 1);
+    assert_eq!(report["plan"].as_array().unwrap().len(), 3);
+    assert_eq!(fs::read_to_string(root.join(".merges.json")).unwrap(), before);
+}
+
+// ── merges_unassigned / merges_refresh MCP tools ─────────────────────────────
+
+/// A file committed after the split is listed, then placed in a chunk.
+#[test]
+fn test_mcp_unassigned_then_refresh_assigns_new_file() {
+    let (_dir, root) = make_repo_with_two_chunks();
+    std::env::set_current_dir(&root).unwrap();
+    fs::write(root.join("src/api/auth.rs"), "fn auth() {}\nfn check() {}").unwrap();
+    StdCommand::new("git").args(["add", "."]).current_dir(&root).output().unwrap();
+    StdCommand::new("git").args(["commit", "-m", "add auth"]).current_dir(&root).output().unwrap();
+
+    let result = merges::mcp::call_tool_sync("merges_unassigned", &serde_json::json!({}));
+    let report: serde_json::Value = serde_json::from_str(&result.expect("merges_unassigned should succeed")).unwrap();
+    assert_eq!(report["unassigned"], serde_json::json!([{"path": "src/api/auth.rs", "added": 2, "removed": 0}]));
+
+    let err = merges::mcp::call_tool_sync(
+        "merges_refresh",
+        &serde_json::json!({"assignments": [{"chunk": "nope", "files": ["src/api/auth.rs"]}]}),
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("No chunk named 'nope'"), "{}", err);
+
+    let result = merges::mcp::call_tool_sync(
+        "merges_refresh",
+        &serde_json::json!({"assignments": [{"chunk": "api", "files": ["src/api/*"]}]}),
+    );
+    let report: serde_json::Value = serde_json::from_str(&result.expect("merges_refresh should succeed")).unwrap();
+    assert_eq!(report["assigned"], serde_json::json!([{"chunk": "api", "files": ["src/api/auth.rs"]}]));
+    assert_eq!(report["unassigned"], serde_json::json!([]));
+    let state = merges::state::MergesState::load(&root).unwrap();
+    assert!(state.chunks[1].files.contains(&"src/api/auth.rs".to_string()));
+}
+
+/// A split preset takes the unassigned files it matches and leaves the rest.
+#[test]
+fn test_mcp_split_preset_plans_the_unassigned_files() {
+    let (_dir, root) = make_repo_with_two_chunks();
+    std::env::set_current_dir(&root).unwrap();
+    fs::write(root.join("src/api/auth.rs"), "fn auth() {}").unwrap();
+    fs::write(root.join("NOTES.md"), "notes").unwrap();
+    StdCommand::new("git").args(["add", "."]).current_dir(&root).output().unwrap();
+    StdCommand::new("git").args(["commit", "-m", "add auth"]).current_dir(&root).output().unwrap();
+    StdCommand::new("git")
+        .args(["config", "merges.preset.backend", "models=src/models; api=src/api/**"])
+        .current_dir(&root)
+        .output()
+        .unwrap();
+
+    let result = merges::mcp::call_tool_sync("merges_split", &serde_json::json!({"preset": "backend"}));
+    let report: serde_json::Value = serde_json::from_str(&result.expect("merges_split should succeed")).unwrap();
+    assert_eq!(report["plan"], serde_json::json!([{"name": "api", "files": ["src/api/auth.rs"]}]));
+    assert_eq!(report["unmatched_files"], serde_json::json!(["NOTES.md"]));
+
+    let err = merges::mcp::call_tool_sync("merges_split", &serde_json::json!({"preset": "frontend"})).unwrap_err();
+    assert!(err.to_string().contains("No split preset named 'frontend'"), "{}", err);
+}
+
+// ── tool annotations ──────────────────────────────────────────────────────────
+
+/// Every tool carries hints; reads are marked read-only and branch deletion destructive.
+#[test]
+fn test_tools_list_carries_annotations() {
+    let tools = serde_json::to_value(merges::mcp::tools::all_tools()).unwrap();
+    let annotations = |name: &str| {
+        tools.as_array().unwrap().iter().find(|t| t["name"] == name).unwrap()["annotations"].clone()
+    };
+    assert_eq!(annotations("merges_status")["readOnlyHint"], true);
+    assert_eq!(annotations("merges_doctor")["destructiveHint"], true);
+    assert_eq!(annotations("merges_doctor")["openWorldHint"], true);
+    assert_eq!(annotations("merges_clean")["destructiveHint"], true);
+    assert_eq!(annotations("merges_push")["destructiveHint"], true);
+    assert_eq!(annotations("merges_push")["idempotentHint"], true);
+    assert_eq!(annotations("merges_split")["destructiveHint"], false);
+    assert_eq!(annotations("merges_unassigned")["readOnlyHint"], true);
+    assert!(tools.as_array().unwrap().iter().all(|t| t["annotations"]["openWorldHint"].is_boolean()));
+}
@@ -0,0 +1,478 @@
+//! `push`, `status`, and `clean` end to end against the in-memory forge and
+//! a bare `origin`, with no hosting service involved.
+
+use merges::cancel::CancelToken;
+use merges::commands::push::PushOptions;
+use merges::commands::status::{SortKey, StateFilter, StatusOptions};
+use merges::commands::sync::{ChunkSync, SyncOutcome};
+use merges::forge::{memory::MemoryForge, Access, Forge, MergeMethod, ReviewComment, ReviewThread};
+use merges::state::MergesState;
+use std::path::{Path, PathBuf};
+use std::process::Command as StdCommand;
+use tempfile::TempDir;
+
+fn git(root: &Path, args: &[&str]) -> String {
+    let out = StdCommand::new("git").args(args).current_dir(root).output().unwrap();
+    assert!(out.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&out.stderr));
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
+
+/// `feat/big` split into `models` and `api` chunks, with a bare `origin`
+/// that has `main`.
+fn make_split_repo() -> (TempDir, PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let origin = dir.path().join("origin.git");
+    let root = dir.path().join("repo");
+    std::fs::create_dir_all(&root).unwrap();
+    git(dir.path(), &["init", "-q", "--bare", "-b", "main", origin.to_str().unwrap()]);
+    git(&root, &["init", "-q", "-b", "main"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    git(&root, &["config", "user.name", "Test"]);
+    git(&root, &["remote", "add", "origin", origin.to_str().unwrap()]);
+    std::fs::write(root.join("README.md"), "hello\n").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-q", "-m", "init"]);
+    git(&root, &["push", "-q", "origin", "main"]);
+    git(&root, &["checkout", "-q", "-b", "feat/big"]);
+    std::fs::write(root.join("models.rs"), "struct User;\n").unwrap();
+    std::fs::write(root.join("api.rs"), "fn routes() {}\n").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-q", "-m", "add feature files"]);
+
+    let state = serde_json::json!({
+        "base_branch": "main",
+        "source_branch": "feat/big",
+        "repo_owner": "acme",
+        "repo_name": "myrepo",
+        "strategy": "stacked",
+        "chunks": []
+    });
+    std::fs::write(root.join(".merges.json"), serde_json::to_string_pretty(&state).unwrap()).unwrap();
+    merges::split::apply_plan(&root, vec![
+        merges::split::ChunkPlan { name: "models".to_string(), files: vec!["models.rs".to_string()], ..Default::default() },
+        merges::split::ChunkPlan { name: "api".to_string(), files: vec!["api.rs".to_string()], ..Default::default() },
+    ])
+    .unwrap();
+    (dir, root)
+}
+
+#[tokio::test]
+async fn test_push_opens_a_stacked_pr_per_chunk() {
+    let (_dir, root) = make_split_repo();
+    let forge = MemoryForge::new();
+
+    merges::commands::push::run_with(&root, PushOptions::default(), &forge, &CancelToken::default()).await.unwrap();
+
+    let prs = forge.prs();
+    assert_eq!(prs.len(), 2);
+    assert_eq!((prs[0].head.as_str(), prs[0].base.as_str()), ("feat/big-chunk-1-models", "main"));
+    assert_eq!((prs[1].head.as_str(), prs[1].base.as_str()), ("feat/big-chunk-2-api", "feat/big-chunk-1-models"));
+    assert!(prs.iter().all(|pr| pr.comments.len() == 1), "{:?}", prs);
+
+    let state = MergesState::load(&root).unwrap();
+    assert_eq!(state.chunks.iter().map(|c| c.pr_number).collect::<Vec<_>>(), vec![Some(1), Some(2)]);
+    assert!(!state.push_interrupted);
+    let remote = git(&root, &["ls-remote", "--heads", "origin"]);
+    assert!(remote.contains("refs/heads/feat/big-chunk-2-api"), "{}", remote);
+    assert_eq!(git(&root, &["rev-parse", "--abbrev-ref", "HEAD"]), "feat/big");
+}
+
+/// A second push updates the PRs and comments it made instead of adding more.
+#[tokio::test]
+async fn test_push_again_reuses_prs_and_comments() {
+    let (_dir, root) = make_split_repo();
+    let forge = MemoryForge::new();
+    let cancel = CancelToken::default();
+
+    merges::commands::push::run_with(&root, PushOptions::default(), &forge, &cancel).await.unwrap();
+    forge.update_pr_base(2, "main").await.unwrap();
+    merges::commands::push::run_with(&root, PushOptions::default(), &forge, &cancel).await.unwrap();
+
+    let prs = forge.prs();
+    assert_eq!(prs.len(), 2);
+    assert_eq!(prs[1].base, "feat/big-chunk-1-models");
+    assert!(prs.iter().all(|pr| pr.comments.len() == 1), "{:?}", prs);
+}
+
+/// Rewriting a chunk that already has a PR gets its PR a comment with the
+/// range-diff since the last push; the untouched chunk's PR gets none.
+#[tokio::test]
+async fn test_push_comments_range_diff_after_a_rewrite() {
+    let (_dir, root) = make_split_repo();
+    let forge = MemoryForge::new();
+    let cancel = CancelToken::default();
+    merges::commands::push::run_with(&root, PushOptions::default(), &forge, &cancel).await.unwrap();
+
+    git(&root, &["checkout", "-q", "feat/big-chunk-2-api"]);
+    std::fs::write(root.join("api.rs"), "fn routes() -> Vec<&'static str> { vec![] }\n").unwrap();
+    git(&root, &["commit", "-q", "-a", "--amend", "--no-edit"]);
+    git(&root, &["checkout", "-q", "feat/big"]);
+    merges::commands::push::run_with(&root, PushOptions::default(), &forge, &cancel).await.unwrap();
+
+    let prs = forge.prs();
+    assert_eq!((prs[0].comments.len(), prs[1].comments.len()), (1, 2), "{:?}", prs);
+    let comment = &prs[1].comments[1].1;
+    assert!(comment.starts_with("**Force-pushed**"), "{}", comment);
+    assert!(comment.contains("What changed since the last push"), "{}", comment);
+    assert!(comment.contains("Vec<&'static str>"), "{}", comment);
+}
+
+/// When the base moves after the stack was last lined up, push rebases each
+/// chunk onto the one below it, so chunk 2 does not carry a second copy of
+/// chunk 1's commit.
+#[tokio::test]
+async fn test_push_restacks_chunks_after_the_base_moves() {
+    let (_dir, root) = make_split_repo();
+    let forge = MemoryForge::new();
+    let cancel = CancelToken::default();
+    merges::commands::push::run_with(&root, PushOptions::default(), &forge, &cancel).await.unwrap();
+
+    git(&root, &["checkout", "-q", "main"]);
+    std::fs::write(root.join("upstream.rs"), "// upstream\n").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-q", "-m", "upstream"]);
+    git(&root, &["push", "-q", "origin", "main"]);
+    git(&root, &["checkout", "-q", "feat/big"]);
+    merges::commands::push::run_with(&root, PushOptions::default(), &forge, &cancel).await.unwrap();
+
+    let main = git(&root, &["rev-parse", "origin/main"]);
+    let models = git(&root, &["rev-parse", "origin/feat/big-chunk-1-models"]);
+    assert_eq!(git(&root, &["rev-parse", "origin/feat/big-chunk-1-models^"]), main);
+    assert_eq!(git(&root, &["rev-parse", "origin/feat/big-chunk-2-api^"]), models);
+    assert_eq!(git(&root, &["rev-list", "--count", "origin/main..origin/feat/big-chunk-2-api"]), "2");
+}
+
+/// Stacked, only the PR into the base gets auto-merge: the one above it
+/// would merge into the chunk below.
+#[tokio::test]
+async fn test_push_enables_auto_merge_on_prs_into_the_base() {
+    let (_dir, root) = make_split_repo();
+    let forge = MemoryForge::new();
+    let opts = PushOptions { auto_merge: Some(MergeMethod::Squash), ..Default::default() };
+
+    merges::commands::push::run_with(&root, opts, &forge, &CancelToken::default()).await.unwrap();
+
+    let auto_merge: Vec<_> = forge.prs().iter().map(|pr| pr.auto_merge).collect();
+    assert_eq!(auto_merge, vec![Some(MergeMethod::Squash), None]);
+}
+
+#[tokio::test]
+async fn test_push_enables_auto_merge_on_every_independent_pr() {
+    let (_dir, root) = make_split_repo();
+    let forge = MemoryForge::new();
+    let opts = PushOptions { independent: true, auto_merge: Some(MergeMethod::Squash), ..Default::default() };
+
+    merges::commands::push::run_with(&root, opts, &forge, &CancelToken::default()).await.unwrap();
+
+    assert_eq!(forge.prs().len(), 2);
+    assert!(forge.prs().iter().all(|pr| pr.auto_merge == Some(MergeMethod::Squash)));
+}
+
+/// With `skip_ci`, a chunk marked as needing no CI is pushed with a
+/// `skip-checks: true` trailer, and the chunk stacked on it keeps building on it.
+#[tokio::test]
+async fn test_push_skip_ci_marks_the_chunk_tip() {
+    let (_dir, root) = make_split_repo();
+    let mut state = MergesState::load(&root).unwrap();
+    state.chunks[0].skip_ci = true;
+    state.save(&root).unwrap();
+    let opts = PushOptions { skip_ci: true, ..Default::default() };
+
+    merges::commands::push::run_with(&root, opts, &MemoryForge::new(), &CancelToken::default()).await.unwrap();
+
+    let message = git(&root, &["log", "-1", "--format=%B", "origin/feat/big-chunk-1-models"]);
+    assert!(message.ends_with("\n\nskip-checks: true"), "{}", message);
+    assert!(!git(&root, &["log", "-1", "--format=%B", "origin/feat/big-chunk-2-api"]).contains("skip-checks"));
+    git(&root, &["merge-base", "--is-ancestor", "origin/feat/big-chunk-1-models", "origin/feat/big-chunk-2-api"]);
+}
+
+/// A held chunk's branch is pushed without a PR until it is released.
+#[tokio::test]
+async fn test_held_chunk_gets_no_pr_until_released() {
+    let (_dir, root) = make_split_repo();
+    let forge = MemoryForge::new();
+    let cancel = CancelToken::default();
+    assert!(merges::commands::hold::run(&root, "api", true).unwrap());
+
+    merges::commands::push::run_with(&root, PushOptions::default(), &forge, &cancel).await.unwrap();
+
+    let prs = forge.prs();
+    assert_eq!(prs.len(), 1);
+    assert!(prs[0].body.contains("| held |"), "{}", prs[0].body);
+    assert!(git(&root, &["ls-remote", "--heads", "origin"]).contains("refs/heads/feat/big-chunk-2-api"));
+
+    assert!(merges::commands::hold::run(&root, "api", false).unwrap());
+    merges::commands::push::run_with(&root, PushOptions::default(), &forge, &cancel).await.unwrap();
+    assert_eq!(forge.prs().len(), 2);
+}
+
+/// Routing rules label each PR and request its reviewers by the chunk's
+/// files, once: a second push asks nobody again.
+#[tokio::test]
+async fn test_push_routes_prs_by_file_pattern() {
+    let (_dir, root) = make_split_repo();
+    let mut state = MergesState::load(&root).unwrap();
+    state.routes = vec![
+        merges::routing::Route::parse("models.rs -> label:db, reviewer:@acme/db-team").unwrap(),
+        merges::routing::Route::parse("*.rs -> label:rust").unwrap(),
+    ];
+    state.save(&root).unwrap();
+    let forge = MemoryForge::new();
+    let cancel = CancelToken::default();
+
+    merges::commands::push::run_with(&root, PushOptions::default(), &forge, &cancel).await.unwrap();
+    merges::commands::push::run_with(&root, PushOptions::default(), &forge, &cancel).await.unwrap();
+
+    let prs = forge.prs();
+    assert_eq!((prs[0].labels.clone(), prs[0].requested_reviewers.clone()), (
+        vec!["db".to_string(), "rust".to_string()],
+        vec!["acme/db-team".to_string()]
+    ));
+    assert_eq!((prs[1].labels.clone(), prs[1].requested_reviewers.len()), (vec!["rust".to_string()], 0));
+    let state = MergesState::load(&root).unwrap();
+    assert_eq!(state.chunks[0].routed, vec!["label:db", "label:rust", "reviewer:@acme/db-team"]);
+}
+
+/// With a project and milestone configured, push files each chunk PR once
+/// and moves its project item along as the PR is approved.
+#[tokio::test]
+async fn test_push_files_prs_on_the_project_and_milestone() {
+    let (_dir, root) = make_split_repo();
+    let mut state = MergesState::load(&root).unwrap();
+    let statuses = vec!["open=In Progress".to_string(), "approved=In Review".to_string()];
+    state.project = Some(merges::project::Project::parse("acme/7", &statuses).unwrap());
+    state.milestone = Some("v2.0".to_string());
+    state.save(&root).unwrap();
+    let forge = MemoryForge::new();
+    let cancel = CancelToken::default();
+
+    merges::commands::push::run_with(&root, PushOptions::default(), &forge, &cancel).await.unwrap();
+    forge.set_review(1, "approved").unwrap();
+    merges::commands::push::run_with(&root, PushOptions::default(), &forge, &cancel).await.unwrap();
+
+    let prs = forge.prs();
+    assert_eq!(prs[0].milestone.as_deref(), Some("v2.0"));
+    assert_eq!(prs[0].project.as_deref(), Some("acme/7"));
+    assert_eq!(prs[0].project_statuses, vec!["In Progress", "In Review"]);
+    assert_eq!(prs[1].project_statuses, vec!["In Progress"]);
+    let state = MergesState::load(&root).unwrap();
+    assert_eq!(state.chunks[0].project_item_id.as_deref(), Some("item-1"));
+    assert_eq!(state.chunks[0].project_status.as_deref(), Some("In Review"));
+}
+
+/// A chunk over a hard size limit stops the push before anything reaches
+/// origin; `allow_large` pushes it anyway.
+#[tokio::test]
+async fn test_push_refuses_chunks_over_the_hard_size_limit() {
+    let (_dir, root) = make_split_repo();
+    git(&root, &["checkout", "-q", "feat/big-chunk-2-api"]);
+    std::fs::write(root.join("api.rs"), "fn routes() {}\nfn health() {}\nfn login() {}\n").unwrap();
+    git(&root, &["commit", "-q", "-am", "more routes"]);
+    git(&root, &["checkout", "-q", "feat/big"]);
+    let mut state = MergesState::load(&root).unwrap();
+    state.size_limits = merges::size::SizeLimits { max_lines: Some(2), ..Default::default() };
+    state.save(&root).unwrap();
+    let forge = MemoryForge::new();
+    let cancel = CancelToken::default();
+
+    // The api chunk stacks on models, so only its own lines count.
+    let err = merges::commands::push::run_with(&root, PushOptions::default(), &forge, &cancel).await.unwrap_err();
+    assert!(err.to_string().contains("'api' is 3 lines changed (limit 2)"), "{}", err);
+    assert!(!err.to_string().contains("'models'"), "{}", err);
+    assert!(forge.prs().is_empty());
+    assert!(!git(&root, &["ls-remote", "--heads", "origin"]).contains("chunk"));
+
+    let opts = PushOptions { allow_large: true, ..Default::default() };
+    merges::commands::push::run_with(&root, opts, &forge, &cancel).await.unwrap();
+    assert_eq!(forge.prs().len(), 2);
+}
+
+/// Credentials that cannot open the tracking issue stop the push before
+/// anything is pushed or opened.
+#[tokio::test]
+async fn test_push_refused_when_credentials_lack_a_permission() {
+    let (_dir, root) = make_split_repo();
+    let forge = MemoryForge::new();
+    forge.deny(Access::Issues);
+    let opts = PushOptions { tracking_issue: true, ..Default::default() };
+
+    let err = merges::commands::push::run_with(&root, opts, &forge, &CancelToken::default()).await.unwrap_err();
+
+    assert!(err.to_string().contains("open the tracking issue — denied"), "{}", err);
+    assert!(forge.prs().is_empty());
+    assert!(!git(&root, &["ls-remote", "--heads", "origin"]).contains("chunk"));
+    assert!(!MergesState::load(&root).unwrap().push_interrupted);
+}
+
+/// Split and push keep the review summary in the git directory; with
+/// `post_summary` set, the tracking issue carries it too.
+#[tokio::test]
+async fn test_push_writes_and_posts_the_review_summary() {
+    let (_dir, root) = make_split_repo();
+    let path = merges::summary::path(&root).unwrap();
+    let written = std::fs::read_to_string(&path).unwrap();
+    assert!(written.contains("1. **models** — no PR yet, 1 file(s)"), "{}", written);
+
+    let mut state = MergesState::load(&root).unwrap();
+    state.post_summary = true;
+    state.save(&root).unwrap();
+    let forge = MemoryForge::new();
+    let opts = PushOptions { tracking_issue: true, ..Default::default() };
+    merges::commands::push::run_with(&root, opts, &forge, &CancelToken::default()).await.unwrap();
+
+    let written = std::fs::read_to_string(&path).unwrap();
+    assert!(written.contains("1. **models** — #1, 1 file(s)\n2. **api** — #2, 1 file(s)"), "{}", written);
+    let body = &forge.issues()[0].body;
+    assert!(body.contains("## 2. api\n\n- Branch: `feat/big-chunk-2-api`"), "{}", body);
+    assert!(body.trim_end().ends_with("*Generated by [merges](https://github.com/merges-cli/merges)*"), "{}", body);
+}
+
+/// Once sync takes the last chunks off the stack, it ticks them off the
+/// tracking issue and closes it, since no push is left to do so.
+#[tokio::test]
+async fn test_sync_closes_the_tracking_issue_when_the_stack_lands() {
+    let (_dir, root) = make_split_repo();
+    let forge = MemoryForge::new();
+    let opts = PushOptions { tracking_issue: true, ..Default::default() };
+    merges::commands::push::run_with(&root, opts, &forge, &CancelToken::default()).await.unwrap();
+
+    // What sync's `take_merged` leaves behind once both PRs have merged.
+    let mut state = MergesState::load(&root).unwrap();
+    let landed: Vec<_> = state.chunks.drain(..).collect();
+    let results: Vec<ChunkSync> = landed
+        .iter()
+        .map(|c| ChunkSync { name: c.name.clone(), branch: c.branch.clone(), outcome: SyncOutcome::Merged })
+        .collect();
+    state.merged = landed;
+    state.save(&root).unwrap();
+
+    merges::commands::sync::close_tracking_issue_with(&root, &results, &forge).await.unwrap();
+
+    let issue = &forge.issues()[0];
+    assert!(issue.closed);
+    assert!(issue.body.contains("**Progress:** 2/2 merged"), "{}", issue.body);
+    assert!(issue.body.contains("- [x] #1 models\n- [x] #2 api"), "{}", issue.body);
+}
+
+/// Editing a PR body replaces the prose above the stack table, and a later
+/// `push --update-prs` keeps the new prose while rebuilding the table.
+#[tokio::test]
+async fn test_pr_body_edit_keeps_the_stack_table() {
+    let (_dir, root) = make_split_repo();
+    let forge = MemoryForge::new();
+    merges::commands::push::run_with(&root, PushOptions::default(), &forge, &CancelToken::default()).await.unwrap();
+
+    let mut seen = String::new();
+    merges::commands::pr_body::run_with(&root, "api", &forge, |prose| {
+        seen = prose.to_string();
+        Ok(Some("Routes for the payment API.\n\nReview after #1.\n".to_string()))
+    })
+    .await
+    .unwrap();
+
+    assert!(seen.starts_with("## Chunk 2/2: api"), "{}", seen);
+    assert!(!seen.contains("### Stack"), "{}", seen);
+    let body = forge.pr(2).unwrap().body;
+    assert!(body.starts_with("Routes for the payment API.\n\nReview after #1.\n\n<!-- merges:stack -->"), "{}", body);
+    assert!(body.contains("| 1 | models | #1 |\n| **2** | **api** | **#2** |\n<!-- /merges:stack -->"), "{}", body);
+
+    let opts = PushOptions { update_prs: true, ..Default::default() };
+    merges::commands::push::run_with(&root, opts, &forge, &CancelToken::default()).await.unwrap();
+    assert_eq!(forge.pr(2).unwrap().body, body);
+
+    let unchanged = merges::commands::pr_body::run_with(&root, "api", &forge, |prose| Ok(Some(prose.to_string())));
+    assert!(unchanged.await.unwrap().is_none());
+}
+
+/// Reviews lists the open threads of each chunk PR, sorted by file and line,
+/// and leaves out chunks without any.
+#[tokio::test]
+async fn test_reviews_group_unresolved_threads_by_chunk() {
+    let (_dir, root) = make_split_repo();
+    let forge = MemoryForge::new();
+    merges::commands::push::run_with(&root, PushOptions::default(), &forge, &CancelToken::default()).await.unwrap();
+    let thread = |path: &str, line, body: &str| ReviewThread {
+        path: path.to_string(),
+        line,
+        comments: vec![ReviewComment {
+            author: "ana".to_string(),
+            body: body.to_string(),
+            url: "https://forge.invalid/pull/2#r1".to_string(),
+        }],
+    };
+    forge.add_review_thread(2, thread("api.rs", Some(9), "Return a Result here.")).unwrap();
+    forge.add_review_thread(2, thread("api.rs", Some(1), "Missing docs.")).unwrap();
+
+    let reviews = merges::commands::reviews::collect(&root, &forge).await.unwrap();
+
+    assert_eq!(reviews.len(), 1);
+    assert_eq!((reviews[0].chunk.as_str(), reviews[0].pr_number), ("api", 2));
+    let lines: Vec<Option<u64>> = reviews[0].threads.iter().map(|t| t.line).collect();
+    assert_eq!(lines, vec![Some(1), Some(9)]);
+}
+
+/// Status filters by state — merged as status records it, conflicts as a
+/// merge into the base would hit them — and sorts instead of stack order.
+#[tokio::test]
+async fn test_status_selects_chunks_by_state_and_order() {
+    let (_dir, root) = make_split_repo();
+    git(&root, &["checkout", "-q", "main"]);
+    std::fs::write(root.join("api.rs"), "fn other() {}\n").unwrap();
+    git(&root, &["add", "api.rs"]);
+    git(&root, &["commit", "-q", "-m", "api on main"]);
+    git(&root, &["push", "-q", "origin", "main"]);
+    git(&root, &["checkout", "-q", "feat/big"]);
+    let mut state = MergesState::load(&root).unwrap();
+    state.chunks[0].merged_at = Some(chrono::Utc::now());
+    state.chunks[1].files.push("docs.md".to_string());
+    let counts = vec![Default::default(); 2];
+
+    let select = |opts: StatusOptions| merges::commands::status::select(&root, &state, &counts, &opts).unwrap();
+    assert_eq!(select(StatusOptions { filter: Some(StateFilter::Merged), ..Default::default() }), vec![0]);
+    assert_eq!(select(StatusOptions { filter: Some(StateFilter::Open), ..Default::default() }), vec![1]);
+    // The api chunk adds api.rs, which main now has too.
+    assert_eq!(select(StatusOptions { filter: Some(StateFilter::Conflict), ..Default::default() }), vec![1]);
+    assert_eq!(select(StatusOptions { sort: Some(SortKey::Size), ..Default::default() }), vec![1, 0]);
+    assert_eq!(select(StatusOptions { chunk: Some("models".to_string()), ..Default::default() }), vec![0]);
+    assert!("state=closed".parse::<StateFilter>().is_err());
+}
+
+/// Status records the merge it sees; clean then deletes only that branch.
+#[tokio::test]
+async fn test_status_and_clean_follow_merged_prs() {
+    let (_dir, root) = make_split_repo();
+    let forge = MemoryForge::new();
+    merges::commands::push::run_with(&root, PushOptions::default(), &forge, &CancelToken::default()).await.unwrap();
+    forge.merge_pr(1, MergeMethod::Squash).await.unwrap();
+    forge.set_checks(2, "failure", &["lint"]).unwrap();
+
+    let opts = StatusOptions { verbose: true, ..Default::default() };
+    merges::commands::status::run_with(&root, opts, Some(&forge)).await.unwrap();
+    let state = MergesState::load(&root).unwrap();
+    assert!(state.chunks[0].merged_at.is_some());
+    assert!(state.chunks[1].merged_at.is_none());
+
+    let deleted = merges::commands::clean::run_with(&root, true, true, Some(&forge)).await.unwrap();
+    assert_eq!(deleted, vec!["feat/big-chunk-1-models"]);
+    let state = MergesState::load(&root).unwrap();
+    assert_eq!(state.chunks.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(), vec!["api"]);
+}
+
+/// A merged PR whose required checks have not passed yet keeps its branch
+/// until they do.
+#[tokio::test]
+async fn test_clean_waits_for_required_checks() {
+    let (_dir, root) = make_split_repo();
+    let forge = MemoryForge::new();
+    merges::commands::push::run_with(&root, PushOptions::default(), &forge, &CancelToken::default()).await.unwrap();
+    forge.require_checks("main", &["build", "test"]);
+    forge.merge_pr(1, MergeMethod::Squash).await.unwrap();
+    forge.pass_checks(1, &["build"]).unwrap();
+
+    let deleted = merges::commands::clean::run_with(&root, true, true, Some(&forge)).await.unwrap();
+    assert!(deleted.is_empty(), "{:?}", deleted);
+
+    forge.pass_checks(1, &["test"]).unwrap();
+    let deleted = merges::commands::clean::run_with(&root, true, true, Some(&forge)).await.unwrap();
+    assert_eq!(deleted, vec!["feat/big-chunk-1-models"]);
+}
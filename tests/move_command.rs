@@ -81,10 +81,12 @@ fn setup_two_chunks(root: &std::path::Path) {
             merges::split::ChunkPlan {
                 name: "chunk-a".to_string(),
                 files: vec!["src/a.rs".to_string(), "src/b.rs".to_string()],
+                ..Default::default()
             },
             merges::split::ChunkPlan {
                 name: "chunk-b".to_string(),
                 files: vec!["src/c.rs".to_string()],
+                ..Default::default()
             },
         ],
     )
@@ -99,7 +101,7 @@ fn test_move_removes_from_source_chunk() {
     let (_dir, root) = make_repo();
     setup_two_chunks(&root);
 
-    merges::commands::r#move::run(&root, "src/b.rs", "chunk-a", "chunk-b").unwrap();
+    merges::commands::r#move::run(&root, "src/b.rs", "chunk-a", "chunk-b", false).unwrap();
 
     let state = merges::state::MergesState::load(&root).unwrap();
     let chunk_a = state.chunks.iter().find(|c| c.name == "chunk-a").unwrap();
@@ -116,7 +118,7 @@ fn test_move_adds_to_dest_chunk() {
     let (_dir, root) = make_repo();
     setup_two_chunks(&root);
 
-    merges::commands::r#move::run(&root, "src/b.rs", "chunk-a", "chunk-b").unwrap();
+    merges::commands::r#move::run(&root, "src/b.rs", "chunk-a", "chunk-b", false).unwrap();
 
     let state = merges::state::MergesState::load(&root).unwrap();
     let chunk_b = state.chunks.iter().find(|c| c.name == "chunk-b").unwrap();
@@ -133,7 +135,7 @@ fn test_move_source_branch_no_longer_has_file() {
     let (_dir, root) = make_repo();
     setup_two_chunks(&root);
 
-    merges::commands::r#move::run(&root, "src/b.rs", "chunk-a", "chunk-b").unwrap();
+    merges::commands::r#move::run(&root, "src/b.rs", "chunk-a", "chunk-b", false).unwrap();
 
     merges::git::checkout(&root, "feat/big-chunk-1-chunk-a").unwrap();
     let files = merges::git::changed_files(&root, "main").unwrap();
@@ -150,7 +152,7 @@ fn test_move_dest_branch_has_file() {
     let (_dir, root) = make_repo();
     setup_two_chunks(&root);
 
-    merges::commands::r#move::run(&root, "src/b.rs", "chunk-a", "chunk-b").unwrap();
+    merges::commands::r#move::run(&root, "src/b.rs", "chunk-a", "chunk-b", false).unwrap();
 
     merges::git::checkout(&root, "feat/big-chunk-2-chunk-b").unwrap();
     let mut files = merges::git::changed_files(&root, "main").unwrap();
@@ -168,7 +170,7 @@ fn test_move_restores_source_branch() {
     let (_dir, root) = make_repo();
     setup_two_chunks(&root);
 
-    merges::commands::r#move::run(&root, "src/b.rs", "chunk-a", "chunk-b").unwrap();
+    merges::commands::r#move::run(&root, "src/b.rs", "chunk-a", "chunk-b", false).unwrap();
 
     let branch = merges::git::current_branch(&root).unwrap();
     assert_eq!(branch, "feat/big", "Source branch should be restored after move");
@@ -180,7 +182,7 @@ fn test_move_file_not_in_source_chunk_errors() {
     let (_dir, root) = make_repo();
     setup_two_chunks(&root);
 
-    let result = merges::commands::r#move::run(&root, "src/c.rs", "chunk-a", "chunk-b");
+    let result = merges::commands::r#move::run(&root, "src/c.rs", "chunk-a", "chunk-b", false);
     assert!(result.is_err(), "Should fail when file is not in source chunk");
     let msg = result.unwrap_err().to_string();
     assert!(
@@ -196,7 +198,7 @@ fn test_move_to_nonexistent_chunk_errors() {
     let (_dir, root) = make_repo();
     setup_two_chunks(&root);
 
-    let result = merges::commands::r#move::run(&root, "src/b.rs", "chunk-a", "no-such-chunk");
+    let result = merges::commands::r#move::run(&root, "src/b.rs", "chunk-a", "no-such-chunk", false);
     assert!(result.is_err(), "Should fail when dest chunk doesn't exist");
 }
 
@@ -206,6 +208,51 @@ fn test_move_from_nonexistent_chunk_errors() {
     let (_dir, root) = make_repo();
     setup_two_chunks(&root);
 
-    let result = merges::commands::r#move::run(&root, "src/b.rs", "no-such-chunk", "chunk-b");
+    let result = merges::commands::r#move::run(&root, "src/b.rs", "no-such-chunk", "chunk-b", false);
     assert!(result.is_err(), "Should fail when src chunk doesn't exist");
 }
+
+/// A glob moves every matching file in the source chunk at once.
+#[test]
+fn test_move_glob_moves_all_matches() {
+    let (_dir, root) = make_repo();
+    setup_two_chunks(&root);
+
+    merges::commands::r#move::run(&root, "src/*.rs", "chunk-a", "chunk-b", false).unwrap();
+
+    let state = merges::state::MergesState::load(&root).unwrap();
+    let a = state.chunks.iter().find(|c| c.name == "chunk-a").unwrap();
+    let b = state.chunks.iter().find(|c| c.name == "chunk-b").unwrap();
+    assert!(a.files.is_empty(), "chunk-a should be empty: {:?}", a.files);
+    let mut files = b.files.clone();
+    files.sort();
+    assert_eq!(files, vec!["src/a.rs", "src/b.rs", "src/c.rs"]);
+
+    merges::git::checkout(&root, "feat/big-chunk-2-chunk-b").unwrap();
+    let mut on_branch = merges::git::changed_files(&root, "main").unwrap();
+    on_branch.sort();
+    assert_eq!(on_branch, vec!["src/a.rs", "src/b.rs", "src/c.rs"]);
+}
+
+/// Both chunk commits are reworded with the configured commit prefix.
+#[test]
+fn test_move_rewords_commits_with_commit_prefix() {
+    let (_dir, root) = make_repo();
+    setup_two_chunks(&root);
+    let mut state = merges::state::MergesState::load(&root).unwrap();
+    state.commit_prefix = Some("PAY-7".to_string());
+    state.save(&root).unwrap();
+
+    merges::commands::r#move::run(&root, "src/b.rs", "chunk-a", "chunk-b", false).unwrap();
+
+    let subject = |branch: &str| {
+        let out = StdCommand::new("git")
+            .args(["log", "-1", "--format=%B", branch])
+            .current_dir(&root)
+            .output()
+            .unwrap();
+        String::from_utf8_lossy(&out.stdout).trim().to_string()
+    };
+    assert_eq!(subject("feat/big-chunk-1-chunk-a"), "PAY-7 chunk 1 - chunk-a\n\nFiles:\nsrc/a.rs");
+    assert_eq!(subject("feat/big-chunk-2-chunk-b"), "PAY-7 chunk 2 - chunk-b\n\nFiles:\nsrc/c.rs\nsrc/b.rs");
+}
@@ -0,0 +1,71 @@
+//! Progress events reported while commands run. The reporter is global, so
+//! this file holds a single test.
+
+use merges::output::Event;
+use std::path::Path;
+use std::process::Command as StdCommand;
+use std::sync::{Arc, Mutex};
+use tempfile::TempDir;
+
+fn git(root: &Path, args: &[&str]) -> String {
+    let out = StdCommand::new("git").args(args).current_dir(root).output().unwrap();
+    assert!(out.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&out.stderr));
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
+
+#[test]
+fn test_split_reports_a_step_with_progress_per_chunk() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = events.clone();
+    merges::output::set_reporter(move |event: &Event| {
+        if !matches!(event, Event::Log { .. }) {
+            sink.lock().unwrap().push(event.clone());
+        }
+    });
+
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    git(root, &["init", "-b", "main"]);
+    git(root, &["config", "user.email", "test@example.com"]);
+    git(root, &["config", "user.name", "Test"]);
+    std::fs::write(root.join("README.md"), "hello").unwrap();
+    git(root, &["add", "."]);
+    git(root, &["commit", "-m", "init"]);
+    git(root, &["checkout", "-q", "-b", "feat/big"]);
+    std::fs::write(root.join("models.rs"), "struct User;").unwrap();
+    std::fs::write(root.join("api.rs"), "fn routes() {}").unwrap();
+    git(root, &["add", "."]);
+    git(root, &["commit", "-m", "add feature files"]);
+    let state = serde_json::json!({
+        "base_branch": "main",
+        "source_branch": "feat/big",
+        "repo_owner": "acme",
+        "repo_name": "myrepo",
+        "strategy": "stacked",
+        "chunks": []
+    });
+    std::fs::write(root.join(".merges.json"), serde_json::to_string_pretty(&state).unwrap()).unwrap();
+
+    merges::split::apply_plan(root, vec![
+        merges::split::ChunkPlan { name: "models".to_string(), files: vec!["models.rs".to_string()], ..Default::default() },
+        merges::split::ChunkPlan { name: "api".to_string(), files: vec!["api.rs".to_string()], ..Default::default() },
+    ])
+    .unwrap();
+
+    let progress = |chunk: &str, message: &str, done: bool| Event::ChunkProgress {
+        chunk: chunk.to_string(),
+        message: message.to_string(),
+        done,
+    };
+    assert_eq!(*events.lock().unwrap(), vec![
+        Event::StepStarted { step: "split".to_string(), total: 2 },
+        progress("models", "creating 'feat/big-chunk-1-models'…", false),
+        progress("models", "", true),
+        progress("api", "creating 'feat/big-chunk-2-api'…", false),
+        progress("api", "", true),
+        Event::StepCompleted { step: "split".to_string() },
+        Event::Message {
+            text: format!("Review summary for the chunks: {}", merges::summary::path(root).unwrap().display()),
+        },
+    ]);
+}
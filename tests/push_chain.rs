@@ -0,0 +1,263 @@
+//! Tests for the stacked PR chain, PR metadata, and resume bookkeeping of `merges push`.
+
+use merges::commands::push::{
+    already_pushed, body_prose, expected_pr_base, parse_duration, pr_body, pr_title, range_diff_comment,
+    stack_comment,
+};
+use merges::git::FileStat;
+use merges::state::{Chunk, ForgeKind, MergesState, Strategy};
+use std::time::Duration;
+
+fn chunk(name: &str) -> Chunk {
+    Chunk {
+        name: name.to_string(),
+        branch: format!("feat/big-chunk-{}", name),
+        files: vec![],
+        description: None,
+        pr_number: None,
+        pr_url: None,
+        pushed_sha: None,
+        pr_title: None,
+        custom_commit_message: None,
+        custom_pr_title: None,
+        custom_pr_body: None,
+        skip_ci: false,
+        hold: false,
+        created_at: None,
+        last_pushed_at: None,
+        merged_at: None,
+        stack_comment_id: None,
+        routed: vec![],
+        milestone: None,
+        project_item_id: None,
+        project_status: None,
+    }
+}
+
+fn state_with(names: &[&str]) -> MergesState {
+    MergesState {
+        base_branch: "main".to_string(),
+        source_branch: "feat/big".to_string(),
+        source_sha: None,
+        repo_owner: "acme".to_string(),
+        repo_name: "myrepo".to_string(),
+        forge: ForgeKind::Github,
+        forge_api_url: None,
+        strategy: Strategy::Stacked,
+        use_worktrees: false,
+        exclude: vec![],
+        routes: vec![],
+        commit_prefix: None,
+        no_title_position: false,
+        max_subject_length: None,
+        max_title_length: None,
+        auto_depth: None,
+        root_files: None,
+        no_stack_comments: false,
+        branch_namespace: None,
+        autostash: false,
+        preserve_history: false,
+        squash_on_push: false,
+        sync_merge: false,
+        post_summary: false,
+        project: None,
+        milestone: None,
+        size_limits: Default::default(),
+        tracking_issue: None,
+        source_pr: None,
+        push_interrupted: false,
+        chunks: names.iter().map(|n| chunk(n)).collect(),
+        merged: vec![],
+    }
+}
+
+/// The first chunk of a stack always targets the base branch.
+#[test]
+fn test_stacked_first_chunk_targets_base() {
+    let state = state_with(&["db", "models", "api"]);
+    assert_eq!(expected_pr_base(&state, 0, &Strategy::Stacked), "main");
+}
+
+/// Chunk N targets chunk N-1's branch in stacked mode.
+#[test]
+fn test_stacked_chunk_targets_previous_chunk() {
+    let state = state_with(&["db", "models", "api"]);
+    assert_eq!(expected_pr_base(&state, 1, &Strategy::Stacked), "feat/big-chunk-db");
+    assert_eq!(expected_pr_base(&state, 2, &Strategy::Stacked), "feat/big-chunk-models");
+}
+
+/// Reordering chunks changes the expected chain — push uses this to retarget drifted PRs.
+#[test]
+fn test_stacked_chain_follows_reordered_chunks() {
+    let mut state = state_with(&["db", "models", "api"]);
+    state.chunks.swap(0, 2);
+    assert_eq!(expected_pr_base(&state, 0, &Strategy::Stacked), "main");
+    assert_eq!(expected_pr_base(&state, 1, &Strategy::Stacked), "feat/big-chunk-api");
+    assert_eq!(expected_pr_base(&state, 2, &Strategy::Stacked), "feat/big-chunk-models");
+}
+
+/// Independent mode points every PR at the base branch.
+#[test]
+fn test_independent_chunks_all_target_base() {
+    let state = state_with(&["db", "models", "api"]);
+    for i in 0..3 {
+        assert_eq!(expected_pr_base(&state, i, &Strategy::Independent), "main");
+    }
+}
+
+/// A resumed push skips a chunk only while its branch is still at the pushed commit.
+#[test]
+fn test_already_pushed_compares_recorded_sha() {
+    let mut c = chunk("db");
+    assert!(!already_pushed(&c, "abc123"), "never-pushed chunk must be pushed");
+    c.pushed_sha = Some("abc123".to_string());
+    assert!(already_pushed(&c, "abc123"));
+    assert!(!already_pushed(&c, "def456"), "branch moved since the push");
+}
+
+/// Titles follow the current stack, so dropping a chunk renumbers the rest.
+#[test]
+fn test_pr_title_tracks_stack_position() {
+    let mut state = state_with(&["db", "models", "api"]);
+    assert_eq!(pr_title(&state, 2), "[3/3] api");
+    state.chunks.remove(0);
+    assert_eq!(pr_title(&state, 1), "[2/2] api");
+    state.commit_prefix = Some("PAY-12".to_string());
+    assert_eq!(pr_title(&state, 0), "PAY-12 [1/2] models");
+}
+
+/// Teams that opt out get the bare chunk name.
+#[test]
+fn test_pr_title_without_position() {
+    let mut state = state_with(&["db", "models"]);
+    state.no_title_position = true;
+    assert_eq!(pr_title(&state, 1), "models");
+}
+
+/// Long chunk names are cut to fit `max_title_length`; prefix and position stay.
+#[test]
+fn test_pr_title_respects_max_length() {
+    let mut state = state_with(&["db", "payments-webhook-retry-handling"]);
+    state.commit_prefix = Some("PAY-12".to_string());
+    state.max_title_length = Some(33);
+    assert_eq!(pr_title(&state, 1), "PAY-12 [2/2] payments-webhook...");
+    assert_eq!(pr_title(&state, 0), "PAY-12 [1/2] db");
+}
+
+/// The stack table lists every chunk's PR and highlights the current one.
+#[test]
+fn test_pr_body_includes_stack_table() {
+    let mut state = state_with(&["db", "models"]);
+    state.chunks[0].pr_number = Some(101);
+    let body = pr_body(&state, 1, &Strategy::Stacked, &[]);
+    assert!(body.contains("## Chunk 2/2: models"), "{}", body);
+    assert!(body.contains("| 1 | db | #101 |"), "{}", body);
+    assert!(body.contains("| **2** | **models** | **—** |"), "{}", body);
+}
+
+/// Wording from the split plan replaces the chunk name and summary, keeping
+/// the prefix, stack position, and stack table.
+#[test]
+fn test_custom_pr_title_and_body() {
+    let mut state = state_with(&["db", "models"]);
+    state.commit_prefix = Some("PAY-12".to_string());
+    state.chunks[1].custom_pr_title = Some("PAY-12 Payment models".to_string());
+    state.chunks[1].custom_pr_body = Some("Adds the payment models.\n".to_string());
+    assert_eq!(pr_title(&state, 1), "PAY-12 [2/2] Payment models");
+
+    let body = pr_body(&state, 1, &Strategy::Stacked, &[]);
+    assert!(body.starts_with("Adds the payment models.\n\n<!-- merges:stack -->\n### Stack"), "{}", body);
+    assert!(!body.contains("Files included"));
+}
+
+/// The prose of a body is what precedes its stack table, with or without the
+/// markers around it.
+#[test]
+fn test_body_prose_stops_at_the_stack_table() {
+    let mut state = state_with(&["db", "models"]);
+    state.chunks[1].custom_pr_body = Some("Adds the payment models.".to_string());
+    assert_eq!(body_prose(&pr_body(&state, 1, &Strategy::Stacked, &[])), "Adds the payment models.");
+    assert_eq!(body_prose("Old body.\n\n### Stack\n| # | Chunk | PR |"), "Old body.");
+    assert_eq!(body_prose("Written by hand.\n"), "Written by hand.");
+}
+
+#[test]
+fn test_parse_duration_accepts_seconds_minutes_and_hours() {
+    assert_eq!(parse_duration("90"), Ok(Duration::from_secs(90)));
+    assert_eq!(parse_duration("2m"), Ok(Duration::from_secs(120)));
+    assert_eq!(parse_duration("1h"), Ok(Duration::from_secs(3600)));
+    assert!(parse_duration("2 weeks").is_err());
+    assert!(parse_duration("m").is_err());
+}
+
+/// A rebase that leaves every commit as it was is called out as such.
+#[test]
+fn test_range_diff_comment_spots_a_plain_rebase() {
+    let rebased = range_diff_comment("1111111aaaa", "2222222bbbb", "1:  1111111 = 1:  2222222 Add models");
+    assert!(rebased.starts_with("**Force-pushed** `1111111` → `2222222`: rebased only"), "{}", rebased);
+
+    let changed = "1:  1111111 ! 1:  2222222 Add models
+    @@ models.rs
+    -+struct User;
+    ++struct User { id: u64 }";
+    let body = range_diff_comment("1111111", "2222222", changed);
+    assert!(body.contains("What changed since the last push"), "{}", body);
+    assert!(body.contains("++struct User { id: u64 }"), "{}", body);
+}
+
+/// A chunk's description leads its generated PR body.
+#[test]
+fn test_pr_body_leads_with_description() {
+    let mut state = state_with(&["db", "models"]);
+    state.chunks[0].description = Some("Tables first, so the models can land on their own.".to_string());
+    let body = pr_body(&state, 0, &Strategy::Stacked, &[]);
+    assert!(
+        body.starts_with("## Chunk 1/2: db\n\nTables first, so the models can land on their own.\n\n**Strategy:**"),
+        "{}",
+        body
+    );
+    assert!(pr_body(&state, 1, &Strategy::Stacked, &[]).starts_with("## Chunk 2/2: models\n\n**Strategy:**"));
+}
+
+/// Files are listed as a collapsed tree with line counts, and the body links
+/// to the whole source branch comparison.
+#[test]
+fn test_pr_body_file_tree_and_stats() {
+    let mut state = state_with(&["api"]);
+    state.chunks[0].files = vec!["src/api/routes.rs".into(), "src/api/mod.rs".into(), "logo.png".into()];
+    let stat = |path: &str, added, removed| FileStat { path: path.to_string(), added, removed };
+    let stats = vec![
+        stat("src/api/routes.rs", Some(40), Some(2)),
+        stat("src/api/mod.rs", Some(1), Some(0)),
+        stat("logo.png", None, None),
+    ];
+
+    let body = pr_body(&state, 0, &Strategy::Stacked, &stats);
+    assert!(
+        body.contains("**Whole change:** [`main...feat/big`](https://github.com/acme/myrepo/compare/main...feat/big)"),
+        "{}",
+        body
+    );
+    assert!(body.contains("<summary>3 file(s), +41 -2</summary>"), "{}", body);
+    assert!(
+        body.contains("```\nlogo.png  binary\nsrc/\n  api/\n    mod.rs  +1 -0\n    routes.rs  +40 -2\n```"),
+        "{}",
+        body
+    );
+}
+
+/// The navigation comment links both neighbours and lists the stack with the
+/// current PR in bold and merged PRs marked.
+#[test]
+fn test_stack_comment_links_neighbours() {
+    let mut state = state_with(&["db", "models", "api"]);
+    state.chunks[0].pr_number = Some(101);
+    state.chunks[0].merged_at = Some(chrono::Utc::now());
+    state.chunks[1].pr_number = Some(102);
+    let body = stack_comment(&state, 1);
+    assert!(body.contains("← Previous: #101 db | Next: api (no PR yet) →"), "{}", body);
+    assert!(body.contains("1. #101 db ✓ merged\n2. **#102 models** (this PR)\n3. api (no PR yet)"), "{}", body);
+
+    let first = stack_comment(&state, 0);
+    assert!(first.contains("← Previous: none, this is the first | Next: #102 models →"), "{}", first);
+}
@@ -0,0 +1,91 @@
+//! Tests for refusing to overwrite commits pushed to a chunk branch by someone else.
+
+use std::path::{Path, PathBuf};
+use std::process::Command as StdCommand;
+use tempfile::TempDir;
+
+use merges::commands::push::foreign_commits;
+use merges::state::Chunk;
+
+fn git(root: &Path, args: &[&str]) -> String {
+    let out = StdCommand::new("git").args(args).current_dir(root).output().unwrap();
+    assert!(out.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&out.stderr));
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
+
+/// A repo with a bare `origin`, a chunk branch pushed to it, and a second
+/// clone (the teammate's) next to it.
+fn make_repo() -> (TempDir, PathBuf, PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let origin = dir.path().join("origin.git");
+    let root = dir.path().join("repo");
+    std::fs::create_dir_all(&root).unwrap();
+    git(dir.path(), &["init", "-q", "--bare", "-b", "main", origin.to_str().unwrap()]);
+    git(&root, &["init", "-q", "-b", "main"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    git(&root, &["config", "user.name", "Test"]);
+    git(&root, &["remote", "add", "origin", origin.to_str().unwrap()]);
+    std::fs::write(root.join("README.md"), "hello\n").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-q", "-m", "init"]);
+    git(&root, &["push", "-q", "origin", "main"]);
+    git(&root, &["checkout", "-q", "-b", "chunk"]);
+    std::fs::write(root.join("a.rs"), "// a\n").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-q", "-m", "add a"]);
+    merges::git::push_branch(&root, "chunk", true).unwrap();
+
+    let teammate = dir.path().join("teammate");
+    git(dir.path(), &["clone", "-q", "-b", "chunk", origin.to_str().unwrap(), teammate.to_str().unwrap()]);
+    git(&teammate, &["config", "user.email", "pat@example.com"]);
+    git(&teammate, &["config", "user.name", "Pat"]);
+    (dir, root, teammate)
+}
+
+fn chunk(pushed_sha: Option<String>) -> Chunk {
+    serde_json::from_value(serde_json::json!({
+        "name": "a",
+        "branch": "chunk",
+        "files": ["a.rs"],
+        "pushed_sha": pushed_sha
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_commit_pushed_by_someone_else_is_reported() {
+    let (_dir, root, teammate) = make_repo();
+    let pushed = git(&root, &["rev-parse", "chunk"]);
+    std::fs::write(teammate.join("a.rs"), "// a, reviewed\n").unwrap();
+    git(&teammate, &["commit", "-q", "-am", "address review"]);
+    git(&teammate, &["push", "-q"]);
+    git(&root, &["fetch", "-q", "origin"]);
+
+    let summary = foreign_commits(&root, &chunk(Some(pushed))).unwrap().expect("the teammate's commit is reported");
+    assert!(summary.contains("'chunk' has 1 commit(s) on origin"), "{}", summary);
+    assert!(summary.contains("address review (Pat)"), "{}", summary);
+    assert!(summary.contains("a.rs"), "{}", summary);
+}
+
+/// Rebasing the local branch leaves its old commits on origin; those are
+/// ours and do not count.
+#[test]
+fn test_own_rebased_commits_are_not_reported() {
+    let (_dir, root, _teammate) = make_repo();
+    let pushed = git(&root, &["rev-parse", "chunk"]);
+    git(&root, &["checkout", "-q", "main"]);
+    git(&root, &["commit", "-q", "--allow-empty", "-m", "upstream"]);
+    git(&root, &["rebase", "-q", "main", "chunk"]);
+
+    assert_eq!(foreign_commits(&root, &chunk(Some(pushed))).unwrap(), None);
+    assert_eq!(foreign_commits(&root, &chunk(None)).unwrap(), None);
+}
+
+#[test]
+fn test_unpushed_branch_has_no_foreign_commits() {
+    let (_dir, root, _teammate) = make_repo();
+    git(&root, &["checkout", "-q", "-b", "other"]);
+    let mut other = chunk(None);
+    other.branch = "other".to_string();
+    assert_eq!(foreign_commits(&root, &other).unwrap(), None);
+}
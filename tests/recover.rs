@@ -0,0 +1,119 @@
+//! Tests for `merges recover`: rebuilding state from chunk branches on origin.
+
+use std::path::{Path, PathBuf};
+use std::process::Command as StdCommand;
+use tempfile::TempDir;
+
+use merges::forge::RemoteRepo;
+use merges::state::{ForgeKind, Strategy};
+
+fn git(root: &Path, args: &[&str]) -> String {
+    let out = StdCommand::new("git").args(args).current_dir(root).output().unwrap();
+    assert!(out.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&out.stderr));
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
+
+fn remote() -> RemoteRepo {
+    RemoteRepo { kind: ForgeKind::Github, owner: "acme".to_string(), repo: "myrepo".to_string(), api_url: None }
+}
+
+/// Split `feat/big` into two chunks, then make it look like a fresh clone:
+/// every branch only exists as `origin/<branch>` and there is no state file.
+fn make_clone_of_split_stack() -> (TempDir, PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().to_path_buf();
+
+    git(&root, &["init", "-b", "main"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    git(&root, &["config", "user.name", "Test"]);
+    git(&root, &["remote", "add", "origin", "https://github.com/acme/myrepo.git"]);
+    std::fs::write(root.join("README.md"), "hello").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "init"]);
+
+    git(&root, &["checkout", "-b", "feat/big"]);
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    for name in ["a.rs", "b.rs", "c.rs"] {
+        std::fs::write(root.join(format!("src/{}", name)), format!("// {}", name)).unwrap();
+    }
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "add files"]);
+
+    let state = serde_json::json!({
+        "base_branch": "main",
+        "source_branch": "feat/big",
+        "repo_owner": "acme",
+        "repo_name": "myrepo",
+        "strategy": "stacked",
+        "chunks": []
+    });
+    std::fs::write(root.join(".merges.json"), serde_json::to_string_pretty(&state).unwrap()).unwrap();
+    merges::split::apply_plan(&root, vec![
+        merges::split::ChunkPlan { name: "api".to_string(), files: vec!["src/a.rs".to_string()], ..Default::default() },
+        merges::split::ChunkPlan { name: "models".to_string(), files: vec!["src/b.rs".to_string(), "src/c.rs".to_string()], ..Default::default() },
+    ])
+    .unwrap();
+    std::fs::remove_file(root.join(".merges.json")).unwrap();
+
+    for branch in ["main", "feat/big", "feat/big-chunk-1-api", "feat/big-chunk-2-models"] {
+        let sha = git(&root, &["rev-parse", branch]);
+        git(&root, &["update-ref", &format!("refs/remotes/origin/{}", branch), &sha]);
+    }
+    git(&root, &["branch", "-D", "feat/big-chunk-1-api", "feat/big-chunk-2-models"]);
+
+    (dir, root)
+}
+
+#[test]
+fn test_discover_rebuilds_chunks_from_remote_branches() {
+    let (_dir, root) = make_clone_of_split_stack();
+    let state = merges::commands::recover::discover(&root, "feat/big", "main", None, remote()).unwrap();
+
+    let chunks: Vec<(&str, &str, Vec<&str>)> = state
+        .chunks
+        .iter()
+        .map(|c| (c.name.as_str(), c.branch.as_str(), c.files.iter().map(String::as_str).collect()))
+        .collect();
+    assert_eq!(chunks, vec![
+        ("api", "feat/big-chunk-1-api", vec!["src/a.rs"]),
+        ("models", "feat/big-chunk-2-models", vec!["src/b.rs", "src/c.rs"]),
+    ]);
+    assert_eq!(state.strategy, Strategy::Independent, "chunks branch off base independently");
+    assert_eq!((state.repo_owner.as_str(), state.base_branch.as_str()), ("acme", "main"));
+}
+
+/// A chunk built on top of the previous one only claims its own files.
+#[test]
+fn test_discover_detects_stacked_chunks() {
+    let (_dir, root) = make_clone_of_split_stack();
+    git(&root, &["checkout", "-q", "-b", "restack", "origin/feat/big-chunk-1-api"]);
+    git(&root, &["checkout", "-q", "feat/big", "--", "src/b.rs", "src/c.rs"]);
+    git(&root, &["commit", "-q", "-m", "chunk 2 - models"]);
+    let sha = git(&root, &["rev-parse", "HEAD"]);
+    git(&root, &["update-ref", "refs/remotes/origin/feat/big-chunk-2-models", &sha]);
+
+    let state = merges::commands::recover::discover(&root, "feat/big", "main", None, remote()).unwrap();
+    assert_eq!(state.strategy, Strategy::Stacked);
+    assert_eq!(state.chunks[1].files, vec!["src/b.rs", "src/c.rs"]);
+}
+
+#[test]
+fn test_discover_without_chunk_branches_fails() {
+    let (_dir, root) = make_clone_of_split_stack();
+    let err = merges::commands::recover::discover(&root, "feat/other", "main", None, remote()).unwrap_err();
+    assert!(err.to_string().contains("git fetch"), "{}", err);
+}
+
+/// Restoring the recovered state creates local branches for the chunks.
+#[test]
+fn test_recovered_state_restores_local_branches() {
+    let (_dir, root) = make_clone_of_split_stack();
+    let state = merges::commands::recover::discover(&root, "feat/big", "main", None, remote()).unwrap();
+    merges::commands::state::restore(&root, state, "recover", "origin", false).unwrap();
+
+    let saved = merges::state::MergesState::load(&root).unwrap();
+    assert_eq!(saved.chunks.len(), 2);
+    assert!(merges::git::rev_parse(&root, "refs/heads/feat/big-chunk-2-models").is_ok());
+    let ops: Vec<String> = merges::journal::load(&root).unwrap().into_iter().map(|e| e.operation).collect();
+    assert_eq!(ops.last().map(String::as_str), Some("recover"));
+}
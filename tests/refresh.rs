@@ -0,0 +1,108 @@
+//! Tests for source-branch divergence detection and `merges refresh --rebuild`.
+
+use std::path::{Path, PathBuf};
+use std::process::Command as StdCommand;
+use tempfile::TempDir;
+
+use merges::state::MergesState;
+
+fn git(root: &Path, args: &[&str]) -> String {
+    let out = StdCommand::new("git").args(args).current_dir(root).output().unwrap();
+    assert!(out.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&out.stderr));
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
+
+/// `feat/big` split into `part-a` (src/a.rs) and `part-b` (src/b.rs, src/c.rs).
+fn make_split_repo() -> (TempDir, PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().to_path_buf();
+
+    git(&root, &["init", "-b", "main"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    git(&root, &["config", "user.name", "Test"]);
+    std::fs::write(root.join("README.md"), "hello").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "init"]);
+
+    git(&root, &["checkout", "-b", "feat/big"]);
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    for name in ["a.rs", "b.rs", "c.rs"] {
+        std::fs::write(root.join(format!("src/{}", name)), format!("// {}", name)).unwrap();
+    }
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "add files"]);
+
+    let state = serde_json::json!({
+        "base_branch": "main",
+        "source_branch": "feat/big",
+        "repo_owner": "acme",
+        "repo_name": "myrepo",
+        "strategy": "stacked",
+        "chunks": []
+    });
+    std::fs::write(root.join(".merges.json"), serde_json::to_string_pretty(&state).unwrap()).unwrap();
+    merges::split::apply_plan(&root, vec![
+        merges::split::ChunkPlan { name: "part-a".to_string(), files: vec!["src/a.rs".to_string()], ..Default::default() },
+        merges::split::ChunkPlan { name: "part-b".to_string(), files: vec!["src/b.rs".to_string(), "src/c.rs".to_string()], ..Default::default() },
+    ])
+    .unwrap();
+
+    (dir, root)
+}
+
+/// Squash the source into one new commit: src/a.rs changes and src/c.rs
+/// leaves the diff.
+fn rewrite_source(root: &Path) {
+    std::fs::write(root.join("src/a.rs"), "// a, reworked").unwrap();
+    std::fs::remove_file(root.join("src/c.rs")).unwrap();
+    git(root, &["reset", "-q", "--soft", "main"]);
+    git(root, &["add", "-A"]);
+    git(root, &["commit", "-q", "-m", "add files (rebased)"]);
+}
+
+#[test]
+fn test_split_records_source_commit() {
+    let (_dir, root) = make_split_repo();
+    let state = MergesState::load(&root).unwrap();
+    assert_eq!(state.source_sha.as_deref(), Some(git(&root, &["rev-parse", "feat/big"]).as_str()));
+    assert!(merges::doctor::source_divergence(&root, &state).is_none());
+}
+
+/// New commits on the source are not divergence; rewritten history is.
+#[test]
+fn test_divergence_only_for_rewritten_source() {
+    let (_dir, root) = make_split_repo();
+    std::fs::write(root.join("src/a.rs"), "// a, more").unwrap();
+    git(&root, &["commit", "-q", "-am", "more work"]);
+    let state = MergesState::load(&root).unwrap();
+    assert!(merges::doctor::source_divergence(&root, &state).is_none());
+
+    rewrite_source(&root);
+    let issue = merges::doctor::source_divergence(&root, &state).unwrap();
+    assert!(issue.contains("merges refresh --rebuild"), "{}", issue);
+    let report = merges::doctor::run(&root, false).unwrap();
+    assert!(report.issues.iter().any(|i| i.contains("was rewritten")), "{:?}", report.issues);
+}
+
+#[test]
+fn test_rebuild_reconstructs_chunks_from_current_source() {
+    let (_dir, root) = make_split_repo();
+    rewrite_source(&root);
+
+    let report = merges::split::rebuild_chunks(&root).unwrap();
+    assert_eq!(report.removed, vec![("part-b".to_string(), vec!["src/c.rs".to_string()])]);
+
+    assert_eq!(git(&root, &["show", "feat/big-chunk-1-part-a:src/a.rs"]), "// a, reworked");
+    let state = MergesState::load(&root).unwrap();
+    assert_eq!(state.chunks[1].files, vec!["src/b.rs"]);
+    assert!(merges::doctor::source_divergence(&root, &state).is_none());
+    assert_eq!(git(&root, &["rev-parse", "--abbrev-ref", "HEAD"]), "feat/big");
+}
+
+#[test]
+fn test_rebuild_requires_source_branch_checked_out() {
+    let (_dir, root) = make_split_repo();
+    git(&root, &["checkout", "-q", "main"]);
+    let err = merges::split::rebuild_chunks(&root).unwrap_err();
+    assert!(err.to_string().contains("Check out the source branch"), "{}", err);
+}
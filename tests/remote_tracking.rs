@@ -0,0 +1,136 @@
+//! Tests for chunk branches tracking their copy on origin.
+
+use std::path::{Path, PathBuf};
+use std::process::Command as StdCommand;
+use tempfile::TempDir;
+
+fn git(root: &Path, args: &[&str]) -> String {
+    let out = StdCommand::new("git").args(args).current_dir(root).output().unwrap();
+    assert!(out.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&out.stderr));
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
+
+/// A repo with a bare `origin` and a local `chunk` branch one commit past `main`.
+fn make_repo() -> (TempDir, PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let origin = dir.path().join("origin.git");
+    let root = dir.path().join("repo");
+    std::fs::create_dir_all(&root).unwrap();
+    git(dir.path(), &["init", "-q", "--bare", "-b", "main", origin.to_str().unwrap()]);
+    git(&root, &["init", "-q", "-b", "main"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    git(&root, &["config", "user.name", "Test"]);
+    git(&root, &["remote", "add", "origin", origin.to_str().unwrap()]);
+    std::fs::write(root.join("README.md"), "hello\n").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-q", "-m", "init"]);
+    git(&root, &["push", "-q", "origin", "main"]);
+    git(&root, &["checkout", "-q", "-b", "chunk"]);
+    std::fs::write(root.join("a.rs"), "// a\n").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-q", "-m", "add a"]);
+    (dir, root)
+}
+
+#[test]
+fn test_push_branch_sets_upstream() {
+    let (_dir, root) = make_repo();
+    assert_eq!(merges::git::upstream(&root, "chunk"), None);
+
+    merges::git::push_branch(&root, "chunk", true).unwrap();
+    assert_eq!(git(&root, &["rev-parse", "--abbrev-ref", "chunk@{upstream}"]), "origin/chunk");
+    assert_eq!(merges::git::upstream(&root, "chunk").as_deref(), Some("origin/chunk"));
+    assert_eq!(merges::git::ahead_behind(&root, "chunk", "origin/chunk").unwrap(), (0, 0));
+}
+
+#[test]
+fn test_ahead_behind_counts_both_sides() {
+    let (_dir, root) = make_repo();
+    merges::git::push_branch(&root, "chunk", true).unwrap();
+    std::fs::write(root.join("a.rs"), "// a, amended\n").unwrap();
+    git(&root, &["commit", "-q", "--amend", "-am", "add a"]);
+    git(&root, &["commit", "-q", "--allow-empty", "-m", "more"]);
+
+    assert_eq!(merges::git::ahead_behind(&root, "chunk", "origin/chunk").unwrap(), (2, 1));
+}
+
+/// Without `force` origin only takes a fast-forward, so a rewritten branch
+/// is refused and a branch with new commits on top goes through.
+#[test]
+fn test_push_branch_without_force_only_fast_forwards() {
+    let (_dir, root) = make_repo();
+    merges::git::push_branch(&root, "chunk", false).unwrap();
+    git(&root, &["commit", "-q", "--allow-empty", "-m", "more"]);
+    merges::git::push_branch(&root, "chunk", false).unwrap();
+    assert_eq!(git(&root, &["rev-parse", "origin/chunk"]), git(&root, &["rev-parse", "chunk"]));
+
+    git(&root, &["commit", "-q", "--amend", "--allow-empty", "-m", "more, reworded"]);
+    assert!(merges::git::push_branch(&root, "chunk", false).is_err());
+    merges::git::push_branch(&root, "chunk", true).unwrap();
+    assert_eq!(git(&root, &["rev-parse", "origin/chunk"]), git(&root, &["rev-parse", "chunk"]));
+}
+
+/// Branches pushed before upstreams were set still compare against origin.
+#[test]
+fn test_upstream_falls_back_to_origin_branch() {
+    let (_dir, root) = make_repo();
+    git(&root, &["push", "-q", "origin", "chunk"]);
+    assert_eq!(merges::git::upstream(&root, "chunk").as_deref(), Some("origin/chunk"));
+}
+
+/// The batched counts agree with the per-branch ones, and a missing branch
+/// gets empty counts instead of failing the others.
+#[test]
+fn test_branch_counts_match_per_branch_counts() {
+    let (_dir, root) = make_repo();
+    merges::git::push_branch(&root, "chunk", true).unwrap();
+    git(&root, &["commit", "-q", "--allow-empty", "-m", "more"]);
+    git(&root, &["checkout", "-q", "-b", "unpushed", "main"]);
+    git(&root, &["checkout", "-q", "main"]);
+    git(&root, &["commit", "-q", "--allow-empty", "-m", "base moves"]);
+
+    let branches = vec!["chunk".to_string(), "unpushed".to_string(), "gone".to_string()];
+    let counts = merges::git::branch_counts(&root, &branches, "main").unwrap();
+
+    assert_eq!(counts[0].behind_base, merges::git::commits_behind(&root, "chunk", "main").unwrap());
+    assert_eq!(counts[0].behind_base, 1);
+    assert_eq!(counts[0].upstream.as_deref(), Some("origin/chunk"));
+    assert_eq!(counts[0].tracking, Some((1, 0)));
+    assert_eq!(counts[1], merges::git::BranchCounts { behind_base: 1, upstream: None, tracking: None });
+    assert_eq!(counts[2], merges::git::BranchCounts::default());
+}
+
+/// The prefetch picks up what others pushed to the base branch, and chunks
+/// are then counted behind it.
+#[tokio::test]
+async fn test_prefetch_updates_the_remote_base() {
+    let (dir, root) = make_repo();
+    let other = dir.path().join("other");
+    git(dir.path(), &["clone", "-q", dir.path().join("origin.git").to_str().unwrap(), other.to_str().unwrap()]);
+    git(&other, &["-c", "user.email=o@example.com", "-c", "user.name=O", "commit", "-q", "--allow-empty", "-m", "theirs"]);
+    git(&other, &["push", "-q", "origin", "main"]);
+    assert_eq!(merges::git::base_ref(&root, "main"), "origin/main");
+    assert_eq!(merges::git::commits_behind(&root, "chunk", "origin/main").unwrap(), 0);
+
+    assert!(merges::git::Prefetch::start(&root, "main").finish(std::time::Duration::from_secs(30)).await);
+    assert_eq!(git(&root, &["rev-parse", "origin/main"]), git(&other, &["rev-parse", "HEAD"]));
+    assert_eq!(merges::git::commits_behind(&root, "chunk", "origin/main").unwrap(), 1);
+}
+
+/// Without an origin the prefetch just reports that it did not fetch.
+#[tokio::test]
+async fn test_prefetch_without_origin_is_not_an_error() {
+    let (_dir, root) = make_repo();
+    git(&root, &["remote", "remove", "origin"]);
+    assert!(!merges::git::Prefetch::start(&root, "main").finish(std::time::Duration::from_secs(30)).await);
+    assert_eq!(merges::git::base_ref(&root, "main"), "main");
+}
+
+#[test]
+fn test_remote_status_labels() {
+    assert_eq!(merges::git::remote_status(None), "not pushed");
+    assert_eq!(merges::git::remote_status(Some((0, 0))), "✓ pushed");
+    assert_eq!(merges::git::remote_status(Some((2, 0))), "↑ 2 ahead");
+    assert_eq!(merges::git::remote_status(Some((0, 3))), "↓ 3 behind");
+    assert_eq!(merges::git::remote_status(Some((2, 1))), "↑ 2 ↓ 1");
+}
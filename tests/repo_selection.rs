@@ -0,0 +1,51 @@
+//! Choosing the repository to work on: `--repo` and submodule detection.
+
+use std::path::{Path, PathBuf};
+use std::process::Command as StdCommand;
+use tempfile::TempDir;
+
+fn git(root: &Path, args: &[&str]) -> String {
+    let out = StdCommand::new("git").args(args).current_dir(root).output().unwrap();
+    assert!(out.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&out.stderr));
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
+
+fn make_repo(root: &Path) {
+    std::fs::create_dir_all(root).unwrap();
+    git(root, &["init", "-q", "-b", "main"]);
+    git(root, &["config", "user.email", "test@example.com"]);
+    git(root, &["config", "user.name", "Test"]);
+    std::fs::write(root.join("README.md"), "hello").unwrap();
+    git(root, &["add", "."]);
+    git(root, &["commit", "-q", "-m", "init"]);
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap()
+}
+
+/// `--repo` may name any directory inside the repository.
+#[test]
+fn test_set_repo_picks_the_repository_containing_the_path() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().join("repo");
+    make_repo(&root);
+    std::fs::create_dir_all(root.join("src/deep")).unwrap();
+
+    merges::git::set_repo(&root.join("src/deep"));
+    assert_eq!(canonical(&merges::git::repo_root().unwrap()), canonical(&root));
+}
+
+#[test]
+fn test_superproject_of_a_submodule() {
+    let dir = TempDir::new().unwrap();
+    let (outer, library) = (dir.path().join("outer"), dir.path().join("library"));
+    make_repo(&outer);
+    make_repo(&library);
+    git(&outer, &["-c", "protocol.file.allow=always", "submodule", "add", "-q", library.to_str().unwrap(), "vendor/library"]);
+
+    let found = merges::git::superproject(&outer.join("vendor/library")).unwrap();
+    assert_eq!(canonical(&found), canonical(&outer));
+    assert_eq!(merges::git::superproject(&outer), None);
+    assert_eq!(merges::git::superproject(&library), None);
+}
@@ -0,0 +1,109 @@
+//! Tests for sharing rerere resolutions: `merges rerere export/import` and
+//! the doctor check on chunk worktrees.
+
+use std::path::{Path, PathBuf};
+use std::process::Command as StdCommand;
+use tempfile::TempDir;
+
+const ID: &str = "5f0e7c2a9b1d4e6f8a0b2c4d6e8f0a1b3c5d7e9f";
+
+fn git(root: &Path, args: &[&str]) -> String {
+    let out = StdCommand::new("git").args(args).current_dir(root).output().unwrap();
+    assert!(out.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&out.stderr));
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
+
+fn make_repo() -> (TempDir, PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().to_path_buf();
+    git(&root, &["init", "-b", "main"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    git(&root, &["config", "user.name", "Test"]);
+    std::fs::write(root.join("README.md"), "hello").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "init"]);
+    (dir, root)
+}
+
+/// Record a resolution the way rerere lays it out in `rr-cache`.
+fn record_resolution(root: &Path, id: &str, resolved: &str) {
+    let entry = root.join(".git/rr-cache").join(id);
+    std::fs::create_dir_all(&entry).unwrap();
+    std::fs::write(entry.join("preimage"), "<<<<<<<\na\n=======\nb\n>>>>>>>\n").unwrap();
+    std::fs::write(entry.join("postimage"), resolved).unwrap();
+}
+
+#[test]
+fn test_export_and_import_carry_resolutions_to_another_clone() {
+    let (_a, alice) = make_repo();
+    let (_b, bob) = make_repo();
+    record_resolution(&alice, ID, "a and b\n");
+    let out = TempDir::new().unwrap();
+    let tarball = out.path().join("rerere.tar.gz");
+
+    assert_eq!(merges::commands::rerere::export(&alice, &tarball).unwrap(), 1);
+    assert_eq!(merges::commands::rerere::import(&bob, &tarball).unwrap(), 1);
+
+    let postimage = bob.join(".git/rr-cache").join(ID).join("postimage");
+    assert_eq!(std::fs::read_to_string(postimage).unwrap(), "a and b\n");
+    assert_eq!(git(&bob, &["config", "rerere.enabled"]), "true");
+    assert!(!bob.join(".git/merges-rerere-import").exists());
+}
+
+/// Resolutions already recorded locally win over imported ones.
+#[test]
+fn test_import_keeps_existing_resolutions() {
+    let (_a, alice) = make_repo();
+    let (_b, bob) = make_repo();
+    record_resolution(&alice, ID, "theirs\n");
+    record_resolution(&bob, ID, "mine\n");
+    let out = TempDir::new().unwrap();
+    let tarball = out.path().join("rerere.tar.gz");
+
+    merges::commands::rerere::export(&alice, &tarball).unwrap();
+    assert_eq!(merges::commands::rerere::import(&bob, &tarball).unwrap(), 0);
+    let postimage = bob.join(".git/rr-cache").join(ID).join("postimage");
+    assert_eq!(std::fs::read_to_string(postimage).unwrap(), "mine\n");
+}
+
+#[test]
+fn test_export_without_resolutions_fails() {
+    let (_dir, root) = make_repo();
+    let err = merges::commands::rerere::export(&root, &root.join("rerere.tar.gz")).unwrap_err();
+    assert!(err.to_string().contains("No recorded conflict resolutions"), "{}", err);
+}
+
+/// A chunk worktree with rerere turned off on its own is flagged and repaired.
+#[test]
+fn test_doctor_turns_rerere_on_in_chunk_worktrees() {
+    let (_dir, root) = make_repo();
+    git(&root, &["checkout", "-q", "-b", "feat/big"]);
+    std::fs::write(root.join("lib.rs"), "fn lib() {}").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "add lib"]);
+    let state = serde_json::json!({
+        "base_branch": "main",
+        "source_branch": "feat/big",
+        "repo_owner": "acme",
+        "repo_name": "myrepo",
+        "strategy": "stacked",
+        "use_worktrees": true,
+        "chunks": []
+    });
+    std::fs::write(root.join(".merges.json"), serde_json::to_string_pretty(&state).unwrap()).unwrap();
+    merges::split::apply_plan(&root, vec![
+        merges::split::ChunkPlan { name: "lib".to_string(), files: vec!["lib.rs".to_string()], ..Default::default() },
+    ])
+    .unwrap();
+    merges::git::enable_rerere(&root).unwrap();
+    let wt = merges::git::worktree_path(&root, "feat/big-chunk-1-lib");
+    git(&root, &["config", "extensions.worktreeConfig", "true"]);
+    git(&wt, &["config", "--worktree", "rerere.enabled", "false"]);
+
+    let report = merges::doctor::run(&root, false).unwrap();
+    assert!(report.issues.iter().any(|i| i.contains("rerere is disabled in the worktree")), "{:?}", report.issues);
+
+    let report = merges::doctor::run(&root, true).unwrap();
+    assert!(report.all_ok(), "{:?}", report.issues);
+    assert_eq!(git(&wt, &["config", "rerere.enabled"]), "true");
+}
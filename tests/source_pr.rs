@@ -0,0 +1,38 @@
+//! Tests for the chunk index `merges push --source-pr` leaves on the source branch's PR.
+
+use merges::commands::push::source_pr_comment;
+use merges::state::MergesState;
+
+fn state(strategy: &str, tracking_issue: Option<u64>) -> MergesState {
+    serde_json::from_value(serde_json::json!({
+        "base_branch": "main",
+        "source_branch": "feat/big",
+        "repo_owner": "acme",
+        "repo_name": "myrepo",
+        "strategy": strategy,
+        "tracking_issue": tracking_issue,
+        "chunks": [
+            { "name": "db", "branch": "feat/big-chunk-1-db", "files": [], "pr_number": 101 },
+            { "name": "api", "branch": "feat/big-chunk-2-api", "files": [] }
+        ]
+    }))
+    .unwrap()
+}
+
+/// Chunk PRs are listed in stack order; chunks without a PR still appear.
+#[test]
+fn test_source_pr_comment_lists_chunk_prs_in_order() {
+    let body = source_pr_comment(&state("stacked", None));
+    assert!(body.contains("split into 2 smaller PRs"), "{}", body);
+    assert!(body.contains("1. #101 db\n2. api (no PR yet)"), "{}", body);
+    assert!(body.contains("merge in this order"), "{}", body);
+    assert!(!body.contains("tracked in"), "{}", body);
+}
+
+/// The tracking issue is linked when there is one; independent stacks carry no order note.
+#[test]
+fn test_source_pr_comment_links_tracking_issue() {
+    let body = source_pr_comment(&state("independent", Some(7)));
+    assert!(body.contains("Progress is tracked in #7."), "{}", body);
+    assert!(!body.contains("merge in this order"), "{}", body);
+}
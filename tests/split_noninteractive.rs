@@ -156,6 +156,7 @@ fn test_apply_plan_file_not_in_diff_returns_error() {
     let plan = vec![merges::split::ChunkPlan {
         name: "nonexistent".to_string(),
         files: vec!["does/not/exist.rs".to_string()],
+        ..Default::default()
     }];
     let result = merges::split::apply_plan(&root, plan);
     assert!(result.is_err(), "Plan with files not in diff should fail");
@@ -180,6 +181,7 @@ fn test_apply_plan_rejects_file_already_in_existing_chunk() {
         merges::split::ChunkPlan {
             name: "first".to_string(),
             files: vec!["src/models/user.rs".to_string()],
+            ..Default::default()
         },
     ]).unwrap();
 
@@ -188,6 +190,7 @@ fn test_apply_plan_rejects_file_already_in_existing_chunk() {
         merges::split::ChunkPlan {
             name: "second".to_string(),
             files: vec!["src/models/user.rs".to_string()],
+            ..Default::default()
         },
     ]);
 
@@ -206,6 +209,7 @@ fn test_apply_plan_rejects_duplicate_file_within_chunk() {
         merges::split::ChunkPlan {
             name: "a".to_string(),
             files: vec!["src/models/user.rs".to_string(), "src/models/user.rs".to_string()],
+            ..Default::default()
         },
     ]);
 
@@ -219,11 +223,200 @@ fn test_apply_plan_rejects_duplicate_file_across_chunks() {
     write_state(&root);
 
     let result = merges::split::apply_plan(&root, vec![
-        merges::split::ChunkPlan { name: "a".to_string(), files: vec!["src/models/user.rs".to_string()] },
-        merges::split::ChunkPlan { name: "b".to_string(), files: vec!["src/models/user.rs".to_string()] },
+        merges::split::ChunkPlan { name: "a".to_string(), files: vec!["src/models/user.rs".to_string()], ..Default::default() },
+        merges::split::ChunkPlan { name: "b".to_string(), files: vec!["src/models/user.rs".to_string()], ..Default::default() },
     ]);
 
     assert!(result.is_err(), "Should reject duplicate file across chunks");
     let msg = result.unwrap_err().to_string();
     assert!(msg.contains("src/models/user.rs"), "Error should name the duplicate file: {}", msg);
 }
+
+// ── Directory and glob entries ────────────────────────────────────────────────
+
+/// Plan entries may name a directory or a glob instead of listing every file.
+#[test]
+fn test_apply_plan_expands_directories_and_globs() {
+    let (_dir, root) = make_repo_with_changes();
+    write_state(&root);
+
+    let expansions = merges::split::apply_plan(&root, vec![
+        merges::split::ChunkPlan { name: "models".to_string(), files: vec!["src/models".to_string()], ..Default::default() },
+        merges::split::ChunkPlan { name: "api".to_string(), files: vec!["src/api/*.rs".to_string()], ..Default::default() },
+    ])
+    .unwrap();
+
+    assert_eq!(expansions.len(), 2, "Both entries should be reported");
+    let state = merges::state::MergesState::load(&root).unwrap();
+    let mut models = state.chunks[0].files.clone();
+    models.sort();
+    assert_eq!(models, vec!["src/models/post.rs", "src/models/user.rs"]);
+    assert_eq!(state.chunks[1].files.len(), 2);
+}
+
+/// A glob that matches nothing is an error naming the pattern.
+#[test]
+fn test_apply_plan_unmatched_glob_returns_error() {
+    let (_dir, root) = make_repo_with_changes();
+    write_state(&root);
+
+    let result = merges::split::apply_plan(&root, vec![
+        merges::split::ChunkPlan { name: "docs".to_string(), files: vec!["docs/*.md".to_string()], ..Default::default() },
+    ]);
+    assert!(result.is_err());
+    let msg = result.unwrap_err().to_string();
+    assert!(msg.contains("docs/*.md"), "Error should name the pattern: {}", msg);
+}
+
+// ── Exclude patterns ──────────────────────────────────────────────────────────
+
+/// Excluded files are reported separately and never swept up by plan patterns.
+#[test]
+fn test_exclude_patterns_filter_changed_files_and_plan_globs() {
+    let (_dir, root) = make_repo_with_changes();
+    write_state(&root);
+    let mut state = merges::state::MergesState::load(&root).unwrap();
+    state.exclude = vec!["handlers.rs".to_string()];
+    state.save(&root).unwrap();
+
+    let (included, excluded) = merges::split::changed_files(&root, &state).unwrap();
+    assert_eq!(excluded, vec!["src/api/handlers.rs"]);
+    assert!(!included.contains(&"src/api/handlers.rs".to_string()));
+
+    merges::split::apply_plan(&root, vec![
+        merges::split::ChunkPlan { name: "api".to_string(), files: vec!["src/api".to_string()], ..Default::default() },
+    ])
+    .unwrap();
+    let state = merges::state::MergesState::load(&root).unwrap();
+    assert_eq!(state.chunks[0].files, vec!["src/api/routes.rs"]);
+}
+
+/// Each new chunk records when it was created, for the status Age column.
+#[test]
+fn test_apply_plan_records_created_at() {
+    let (_dir, root) = make_repo_with_changes();
+    write_state(&root);
+
+    let before = chrono::Utc::now();
+    let plan: Vec<merges::split::ChunkPlan> = serde_json::from_str(&chunk_plan_json()).unwrap();
+    merges::split::apply_plan(&root, plan).unwrap();
+
+    let state = merges::state::MergesState::load(&root).unwrap();
+    for chunk in &state.chunks {
+        let created = chunk.created_at.expect("created_at should be set by split");
+        assert!(created >= before, "{}: {}", chunk.name, created);
+        assert!(chunk.last_pushed_at.is_none() && chunk.merged_at.is_none());
+    }
+}
+
+/// Over `max_subject_length`, the subject keeps the ticket and chunk number and
+/// the full chunk name moves into the body.
+#[test]
+fn test_apply_plan_shortens_long_commit_subjects() {
+    let (_dir, root) = make_repo_with_changes();
+    write_state(&root);
+    let mut state = merges::state::MergesState::load(&root).unwrap();
+    state.commit_prefix = Some("PAY-7".to_string());
+    state.max_subject_length = Some(32);
+    state.save(&root).unwrap();
+
+    merges::split::apply_plan(&root, vec![
+        merges::split::ChunkPlan {
+            name: "user-and-post-models-with-validation".to_string(),
+            files: vec!["src/models".to_string()],
+            ..Default::default()
+        },
+    ])
+    .unwrap();
+
+    let out = StdCommand::new("git")
+        .args(["log", "-1", "--format=%B", "feat/big-chunk-1-user-and-post-models-with-validation"])
+        .current_dir(&root)
+        .output()
+        .unwrap();
+    let message = String::from_utf8_lossy(&out.stdout);
+    let mut lines = message.lines();
+    assert_eq!(lines.next(), Some("PAY-7 chunk 1 - user-and-post..."));
+    assert_eq!(lines.nth(1), Some("Chunk: user-and-post-models-with-validation"));
+}
+
+/// A plan's commit message replaces the generated one and is kept in state.
+#[test]
+fn test_apply_plan_uses_custom_commit_message() {
+    let (_dir, root) = make_repo_with_changes();
+    write_state(&root);
+
+    let plan: Vec<merges::split::ChunkPlan> = serde_json::from_value(serde_json::json!([
+        { "name": "models", "files": ["src/models"], "commit_message": "Add user and post models", "pr_title": "User models" }
+    ]))
+    .unwrap();
+    merges::split::apply_plan(&root, plan).unwrap();
+
+    let out = StdCommand::new("git")
+        .args(["log", "-1", "--format=%B", "feat/big-chunk-1-models"])
+        .current_dir(&root)
+        .output()
+        .unwrap();
+    assert_eq!(String::from_utf8_lossy(&out.stdout).trim(), "Add user and post models");
+    let state = merges::state::MergesState::load(&root).unwrap();
+    assert_eq!(state.chunks[0].custom_pr_title.as_deref(), Some("User models"));
+}
+
+/// With a branch namespace, chunk branches are created under it.
+#[test]
+fn test_apply_plan_creates_branches_under_namespace() {
+    let (_dir, root) = make_repo_with_changes();
+    write_state(&root);
+    let mut state = merges::state::MergesState::load(&root).unwrap();
+    state.branch_namespace = Some("merges".to_string());
+    state.save(&root).unwrap();
+
+    let plan: Vec<merges::split::ChunkPlan> = serde_json::from_str(&chunk_plan_json()).unwrap();
+    merges::split::apply_plan(&root, plan).unwrap();
+
+    let state = merges::state::MergesState::load(&root).unwrap();
+    let branches: Vec<&str> = state.chunks.iter().map(|c| c.branch.as_str()).collect();
+    assert_eq!(branches, vec!["merges/feat/big/1-models", "merges/feat/big/2-api"]);
+}
+
+/// A chunk branch that already exists is refused before any branch is created.
+#[test]
+fn test_apply_plan_rejects_existing_branch_name() {
+    let (_dir, root) = make_repo_with_changes();
+    write_state(&root);
+    StdCommand::new("git").args(["branch", "feat/big-chunk-2-api"]).current_dir(&root).output().unwrap();
+
+    let plan: Vec<merges::split::ChunkPlan> = serde_json::from_str(&chunk_plan_json()).unwrap();
+    let err = merges::split::apply_plan(&root, plan).unwrap_err();
+    assert!(err.to_string().contains("'feat/big-chunk-2-api' for chunk 'api'"), "{}", err);
+
+    let branches = StdCommand::new("git").args(["branch", "--list", "feat/big-chunk-1-*"]).current_dir(&root).output().unwrap();
+    assert!(String::from_utf8_lossy(&branches.stdout).trim().is_empty(), "no chunk branch should be created");
+}
+
+/// With `preserve_history`, each chunk replays the source commits touching its
+/// files, with their messages, and a commit keeps only that chunk's files.
+#[test]
+fn test_apply_plan_preserves_source_history() {
+    let (_dir, root) = make_repo_with_changes();
+    std::fs::write(root.join("src/models/user.rs"), "struct User { id: u64 }").unwrap();
+    std::fs::write(root.join("src/api/routes.rs"), "fn routes() { users() }").unwrap();
+    StdCommand::new("git").args(["commit", "-q", "-am", "wire users through"]).current_dir(&root).output().unwrap();
+    write_state(&root);
+    let mut state = merges::state::MergesState::load(&root).unwrap();
+    state.preserve_history = true;
+    state.save(&root).unwrap();
+
+    let plan: Vec<merges::split::ChunkPlan> = serde_json::from_str(&chunk_plan_json()).unwrap();
+    merges::split::apply_plan(&root, plan).unwrap();
+
+    let git = |args: &[&str]| {
+        let out = StdCommand::new("git").args(args).current_dir(&root).output().unwrap();
+        String::from_utf8_lossy(&out.stdout).trim().to_string()
+    };
+    assert_eq!(git(&["log", "--format=%s", "main..feat/big-chunk-1-models"]), "wire users through\nadd feature files");
+    assert_eq!(git(&["show", "--name-only", "--format=", "feat/big-chunk-1-models"]), "src/models/user.rs");
+    assert_eq!(git(&["show", "feat/big-chunk-1-models:src/models/user.rs"]), "struct User { id: u64 }");
+    assert_eq!(git(&["log", "--format=%s", "main..feat/big-chunk-2-api"]), "wire users through\nadd feature files");
+    assert_eq!(git(&["show", "--name-only", "--format=", "feat/big-chunk-2-api"]), "src/api/routes.rs");
+}
@@ -0,0 +1,89 @@
+//! Integration tests for `merges squash`.
+
+use std::path::{Path, PathBuf};
+use std::process::Command as StdCommand;
+use tempfile::TempDir;
+
+fn git(root: &Path, args: &[&str]) -> String {
+    let out = StdCommand::new("git").args(args).current_dir(root).output().unwrap();
+    assert!(out.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&out.stderr));
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
+
+/// A split `feat/big` with `models` and `api` chunks, and a fixup commit on
+/// the `models` chunk branch.
+fn make_split_repo() -> (TempDir, PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().to_path_buf();
+
+    git(&root, &["init", "-b", "main"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    git(&root, &["config", "user.name", "Test"]);
+    std::fs::write(root.join("README.md"), "hello").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "init"]);
+    git(&root, &["checkout", "-q", "-b", "feat/big"]);
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(root.join("src/models.rs"), "struct User;").unwrap();
+    std::fs::write(root.join("src/api.rs"), "fn routes() {}").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "add feature files"]);
+
+    let state = serde_json::json!({
+        "base_branch": "main",
+        "source_branch": "feat/big",
+        "repo_owner": "acme",
+        "repo_name": "myrepo",
+        "strategy": "stacked",
+        "chunks": []
+    });
+    std::fs::write(root.join(".merges.json"), serde_json::to_string_pretty(&state).unwrap()).unwrap();
+    merges::split::apply_plan(&root, vec![
+        merges::split::ChunkPlan { name: "models".to_string(), files: vec!["src/models.rs".to_string()], ..Default::default() },
+        merges::split::ChunkPlan { name: "api".to_string(), files: vec!["src/api.rs".to_string()], ..Default::default() },
+    ])
+    .unwrap();
+
+    git(&root, &["checkout", "-q", "feat/big-chunk-1-models"]);
+    std::fs::write(root.join("src/models.rs"), "struct User { id: u64 }").unwrap();
+    git(&root, &["commit", "-q", "-am", "give users an id"]);
+    git(&root, &["checkout", "-q", "feat/big"]);
+
+    (dir, root)
+}
+
+#[test]
+fn test_squash_folds_fixups_into_one_commit() {
+    let (_dir, root) = make_split_repo();
+
+    let squashed = merges::commands::squash::run(&root, &[], false).unwrap();
+    assert_eq!(squashed, vec!["models"]);
+
+    assert_eq!(git(&root, &["rev-list", "--count", "main..feat/big-chunk-1-models"]), "1");
+    assert_eq!(git(&root, &["show", "feat/big-chunk-1-models:src/models.rs"]), "struct User { id: u64 }");
+    let message = git(&root, &["log", "-1", "--format=%B", "feat/big-chunk-1-models"]);
+    assert!(message.starts_with("chunk 1 - models\n"), "{}", message);
+    assert!(message.ends_with("Squashed commits:\n- give users an id"), "{}", message);
+    assert_eq!(git(&root, &["rev-parse", "--abbrev-ref", "HEAD"]), "feat/big");
+}
+
+/// A chunk branch built on the squashed one is moved onto the new commit.
+#[test]
+fn test_squash_rebases_chunks_stacked_on_it() {
+    let (_dir, root) = make_split_repo();
+    git(&root, &["rebase", "-q", "--onto", "feat/big-chunk-1-models", "main", "feat/big-chunk-2-api"]);
+    git(&root, &["checkout", "-q", "feat/big"]);
+
+    merges::commands::squash::run(&root, &["models".to_string()], false).unwrap();
+
+    assert_eq!(git(&root, &["rev-parse", "feat/big-chunk-2-api^"]), git(&root, &["rev-parse", "feat/big-chunk-1-models"]));
+    assert_eq!(git(&root, &["rev-list", "--count", "main..feat/big-chunk-2-api"]), "2");
+    assert_eq!(git(&root, &["show", "feat/big-chunk-2-api:src/api.rs"]), "fn routes() {}");
+}
+
+#[test]
+fn test_squash_rejects_unknown_chunk() {
+    let (_dir, root) = make_split_repo();
+    let err = merges::commands::squash::run(&root, &["nope".to_string()], false).unwrap_err();
+    assert!(err.to_string().contains("No chunk named 'nope'"), "{}", err);
+}
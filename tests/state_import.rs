@@ -0,0 +1,128 @@
+//! Tests for `merges state export` / `merges state import`.
+
+use std::process::Command as StdCommand;
+use tempfile::TempDir;
+
+fn git(root: &std::path::Path, args: &[&str]) {
+    let out = StdCommand::new("git").args(args).current_dir(root).output().unwrap();
+    assert!(out.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&out.stderr));
+}
+
+/// A repo on `feat/big` with one chunk split off into `feat/big-chunk-1-part-a`.
+fn make_split_repo() -> (TempDir, std::path::PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().join("work");
+    std::fs::create_dir_all(&root).unwrap();
+
+    git(&root, &["init", "-b", "main"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    git(&root, &["config", "user.name", "Test"]);
+    std::fs::write(root.join("README.md"), "hello").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "init"]);
+
+    git(&root, &["checkout", "-b", "feat/big"]);
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    for name in ["a.rs", "b.rs"] {
+        std::fs::write(root.join(format!("src/{}", name)), format!("// {}", name)).unwrap();
+    }
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "add files"]);
+
+    let state = serde_json::json!({
+        "base_branch": "main",
+        "source_branch": "feat/big",
+        "repo_owner": "acme",
+        "repo_name": "myrepo",
+        "strategy": "stacked",
+        "chunks": []
+    });
+    std::fs::write(root.join(".merges.json"), serde_json::to_string_pretty(&state).unwrap()).unwrap();
+    merges::split::apply_plan(&root, vec![
+        merges::split::ChunkPlan { name: "part-a".to_string(), files: vec!["src/a.rs".to_string()], ..Default::default() },
+    ])
+    .unwrap();
+
+    (dir, root)
+}
+
+#[test]
+fn test_export_then_import_round_trips() {
+    let (dir, root) = make_split_repo();
+    let backup = dir.path().join("backup.json");
+    std::fs::write(&backup, merges::commands::state::export(&root).unwrap()).unwrap();
+
+    merges::commands::add::run(&root, "part-a", &["src/b.rs".to_string()], false).unwrap();
+    let imported = merges::commands::state::import(&root, &backup, true).unwrap();
+
+    assert_eq!(imported.chunks[0].files, vec!["src/a.rs"]);
+    let restored = merges::state::MergesState::load(&root).unwrap();
+    assert_eq!(restored.chunks[0].files, vec!["src/a.rs"]);
+    let ops: Vec<String> = merges::journal::load(&root).unwrap().into_iter().map(|e| e.operation).collect();
+    assert_eq!(ops.last().map(String::as_str), Some("import"));
+}
+
+#[test]
+fn test_import_refuses_to_replace_state_without_force() {
+    let (dir, root) = make_split_repo();
+    let backup = dir.path().join("backup.json");
+    std::fs::write(&backup, merges::commands::state::export(&root).unwrap()).unwrap();
+
+    let err = merges::commands::state::import(&root, &backup, false).unwrap_err();
+    assert!(err.to_string().contains("--force"), "{}", err);
+}
+
+/// Nothing is written when the file does not match the repository.
+#[test]
+fn test_import_rejects_state_that_does_not_match_the_repo() {
+    let (dir, root) = make_split_repo();
+    let mut state: serde_json::Value = serde_json::from_str(&merges::commands::state::export(&root).unwrap()).unwrap();
+    state["chunks"][0]["branch"] = "feat/big-chunk-9-gone".into();
+    state["chunks"].as_array_mut().unwrap().push(serde_json::json!({
+        "name": "part-b",
+        "branch": "feat/big",
+        "files": ["src/a.rs"]
+    }));
+    let backup = dir.path().join("backup.json");
+    std::fs::write(&backup, state.to_string()).unwrap();
+
+    let before = std::fs::read_to_string(root.join(".merges.json")).unwrap();
+    let err = merges::commands::state::import(&root, &backup, true).unwrap_err().to_string();
+    assert!(err.contains("feat/big-chunk-9-gone"), "{}", err);
+    assert!(err.contains("src/a.rs"), "{}", err);
+    assert_eq!(std::fs::read_to_string(root.join(".merges.json")).unwrap(), before);
+}
+
+#[test]
+fn test_import_rejects_non_state_file() {
+    let (dir, root) = make_split_repo();
+    let backup = dir.path().join("backup.json");
+    std::fs::write(&backup, "{\"hello\": 1}").unwrap();
+    let err = merges::commands::state::import(&root, &backup, true).unwrap_err();
+    assert!(err.to_string().contains("not a merges state file"), "{}", err);
+}
+
+/// A teammate's fresh clone has the chunk branches only on origin; import
+/// creates local branches for them.
+#[test]
+fn test_import_creates_local_branches_from_origin() {
+    let (dir, root) = make_split_repo();
+    let backup = dir.path().join("backup.json");
+    std::fs::write(&backup, merges::commands::state::export(&root).unwrap()).unwrap();
+
+    let clone = dir.path().join("clone");
+    let out = StdCommand::new("git")
+        .args(["clone", "--quiet", root.to_str().unwrap(), clone.to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    assert!(merges::git::rev_parse(&clone, "refs/heads/feat/big-chunk-1-part-a").is_err());
+
+    merges::commands::state::import(&clone, &backup, false).unwrap();
+
+    assert_eq!(
+        merges::git::rev_parse(&clone, "refs/heads/feat/big-chunk-1-part-a").unwrap(),
+        merges::git::rev_parse(&root, "feat/big-chunk-1-part-a").unwrap()
+    );
+    assert!(clone.join(".merges.json").exists());
+}
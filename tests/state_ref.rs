@@ -0,0 +1,139 @@
+//! Tests for sharing state between machines via `merges state push/pull`.
+
+use std::path::{Path, PathBuf};
+use std::process::Command as StdCommand;
+use tempfile::TempDir;
+
+fn git(root: &Path, args: &[&str]) {
+    let out = StdCommand::new("git").args(args).current_dir(root).output().unwrap();
+    assert!(out.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&out.stderr));
+}
+
+fn identify(root: &Path) {
+    git(root, &["config", "user.email", "test@example.com"]);
+    git(root, &["config", "user.name", "Test"]);
+}
+
+/// A bare `origin` plus a "laptop" clone that has split `feat/big` into one
+/// chunk and pushed every branch.
+fn make_laptop() -> (TempDir, PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let origin = dir.path().join("origin.git");
+    let laptop = dir.path().join("laptop");
+    std::fs::create_dir_all(&laptop).unwrap();
+
+    git(dir.path(), &["init", "--quiet", "--bare", "-b", "main", origin.to_str().unwrap()]);
+    git(&laptop, &["init", "-b", "main"]);
+    identify(&laptop);
+    git(&laptop, &["remote", "add", "origin", origin.to_str().unwrap()]);
+    std::fs::write(laptop.join("README.md"), "hello").unwrap();
+    git(&laptop, &["add", "."]);
+    git(&laptop, &["commit", "-m", "init"]);
+
+    git(&laptop, &["checkout", "-b", "feat/big"]);
+    std::fs::create_dir_all(laptop.join("src")).unwrap();
+    for name in ["a.rs", "b.rs"] {
+        std::fs::write(laptop.join(format!("src/{}", name)), format!("// {}", name)).unwrap();
+    }
+    git(&laptop, &["add", "."]);
+    git(&laptop, &["commit", "-m", "add files"]);
+
+    let state = serde_json::json!({
+        "base_branch": "main",
+        "source_branch": "feat/big",
+        "repo_owner": "acme",
+        "repo_name": "myrepo",
+        "strategy": "stacked",
+        "chunks": []
+    });
+    std::fs::write(laptop.join(".merges.json"), serde_json::to_string_pretty(&state).unwrap()).unwrap();
+    merges::split::apply_plan(&laptop, vec![
+        merges::split::ChunkPlan { name: "part-a".to_string(), files: vec!["src/a.rs".to_string()], ..Default::default() },
+    ])
+    .unwrap();
+    git(&laptop, &["push", "--quiet", "origin", "main", "feat/big", "feat/big-chunk-1-part-a"]);
+
+    (dir, laptop)
+}
+
+fn clone(dir: &TempDir, name: &str) -> PathBuf {
+    let path = dir.path().join(name);
+    git(dir.path(), &["clone", "--quiet", dir.path().join("origin.git").to_str().unwrap(), name]);
+    identify(&path);
+    path
+}
+
+fn chunk_files(root: &Path) -> Vec<Vec<String>> {
+    let state = merges::state::MergesState::load(root).unwrap();
+    state.chunks.into_iter().map(|c| c.files).collect()
+}
+
+#[test]
+fn test_push_then_pull_on_another_machine() {
+    let (dir, laptop) = make_laptop();
+    merges::commands::state::push(&laptop, false).unwrap();
+
+    let desktop = clone(&dir, "desktop");
+    merges::commands::state::pull(&desktop, false).unwrap();
+
+    assert_eq!(chunk_files(&desktop), vec![vec!["src/a.rs"]]);
+    assert!(merges::git::rev_parse(&desktop, "refs/heads/feat/big-chunk-1-part-a").is_ok());
+    let out = StdCommand::new("git").args(["status", "--porcelain"]).current_dir(&desktop).output().unwrap();
+    assert!(String::from_utf8_lossy(&out.stdout).trim().is_empty(), ".merges.json must stay out of git status");
+}
+
+/// Changes made on one machine reach the other, and pushing again is a no-op.
+#[test]
+fn test_changes_round_trip_between_machines() {
+    let (dir, laptop) = make_laptop();
+    merges::commands::state::push(&laptop, false).unwrap();
+    let desktop = clone(&dir, "desktop");
+    merges::commands::state::pull(&desktop, false).unwrap();
+    // A fresh clone is on `main`; chunks are edited from the source branch.
+    git(&desktop, &["checkout", "--quiet", "feat/big"]);
+
+    merges::commands::add::run(&desktop, "part-a", &["src/b.rs".to_string()], false).unwrap();
+    git(&desktop, &["push", "--quiet", "--force", "origin", "feat/big-chunk-1-part-a"]);
+    let first = merges::commands::state::push(&desktop, false).unwrap();
+    assert_eq!(merges::commands::state::push(&desktop, false).unwrap(), first);
+
+    merges::commands::state::pull(&laptop, false).unwrap();
+    assert_eq!(chunk_files(&laptop), vec![vec!["src/a.rs", "src/b.rs"]]);
+}
+
+/// Pushing over state the other machine published is rejected until pulled.
+#[test]
+fn test_push_is_rejected_when_origin_has_newer_state() {
+    let (dir, laptop) = make_laptop();
+    merges::commands::state::push(&laptop, false).unwrap();
+    let desktop = clone(&dir, "desktop");
+    merges::commands::state::pull(&desktop, false).unwrap();
+
+    let mut state = merges::state::MergesState::load(&desktop).unwrap();
+    state.no_title_position = true;
+    state.save(&desktop).unwrap();
+    merges::commands::state::push(&desktop, false).unwrap();
+
+    let mut state = merges::state::MergesState::load(&laptop).unwrap();
+    state.commit_prefix = Some("ABC-1".to_string());
+    state.save(&laptop).unwrap();
+    let err = merges::commands::state::push(&laptop, false).unwrap_err();
+    assert!(format!("{:#}", err).contains("merges state pull"), "{:#}", err);
+
+    // The laptop's own edit was never pushed, so pull keeps it without --force.
+    let err = merges::commands::state::pull(&laptop, false).unwrap_err();
+    assert!(err.to_string().contains("never pushed"), "{}", err);
+
+    merges::commands::state::pull(&laptop, true).unwrap();
+    let state = merges::state::MergesState::load(&laptop).unwrap();
+    assert!(state.no_title_position);
+    assert_eq!(state.commit_prefix, None);
+}
+
+#[test]
+fn test_pull_without_shared_state_fails() {
+    let (dir, _laptop) = make_laptop();
+    let desktop = clone(&dir, "desktop");
+    let err = merges::commands::state::pull(&desktop, false).unwrap_err();
+    assert!(err.to_string().contains("merges state push"), "{}", err);
+}
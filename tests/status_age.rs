@@ -0,0 +1,26 @@
+//! Tests for the Age / Last push columns of `merges status`.
+
+use chrono::{Duration, TimeZone, Utc};
+use merges::commands::status::format_age;
+
+#[test]
+fn test_format_age_picks_the_largest_whole_unit() {
+    let now = Utc.with_ymd_and_hms(2026, 3, 20, 12, 0, 0).unwrap();
+    assert_eq!(format_age(now - Duration::seconds(20), now), "just now");
+    assert_eq!(format_age(now - Duration::minutes(45), now), "45m ago");
+    assert_eq!(format_age(now - Duration::hours(5), now), "5h ago");
+    assert_eq!(format_age(now - Duration::days(9), now), "9d ago");
+    assert_eq!(format_age(now - Duration::days(36), now), "5w ago");
+}
+
+/// State files written before timestamps existed still load.
+#[test]
+fn test_chunks_without_timestamps_load() {
+    let chunk: merges::state::Chunk = serde_json::from_value(serde_json::json!({
+        "name": "db",
+        "branch": "feat/big-chunk-1-db",
+        "files": []
+    }))
+    .unwrap();
+    assert!(chunk.created_at.is_none() && chunk.last_pushed_at.is_none() && chunk.merged_at.is_none());
+}
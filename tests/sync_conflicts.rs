@@ -0,0 +1,76 @@
+//! Tests for `merges sync` reporting per-chunk outcomes.
+
+use std::path::Path;
+use std::process::Command as StdCommand;
+use tempfile::TempDir;
+
+use merges::cancel::CancelToken;
+use merges::commands::sync::{self, SyncOutcome};
+
+fn git(root: &Path, args: &[&str]) -> String {
+    let out = StdCommand::new("git").args(args).current_dir(root).output().unwrap();
+    assert!(out.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&out.stderr));
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
+
+/// A conflicting chunk is reported with its files and left untouched; the
+/// other chunk is still rebased. `--chunk` limits the sync to named chunks.
+#[test]
+fn test_sync_reports_conflicts_per_chunk() {
+    let dir = TempDir::new().unwrap();
+    let origin = dir.path().join("origin.git");
+    let root = dir.path().join("repo");
+    std::fs::create_dir_all(&root).unwrap();
+    git(dir.path(), &["init", "-q", "--bare", "-b", "main", origin.to_str().unwrap()]);
+    git(&root, &["init", "-q", "-b", "main"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    git(&root, &["config", "user.name", "Test"]);
+    git(&root, &["remote", "add", "origin", origin.to_str().unwrap()]);
+    std::fs::write(root.join("a.rs"), "one\n").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-q", "-m", "init"]);
+    git(&root, &["push", "-q", "origin", "main"]);
+
+    git(&root, &["checkout", "-q", "-b", "feat/big"]);
+    std::fs::write(root.join("a.rs"), "two\n").unwrap();
+    std::fs::write(root.join("b.rs"), "// b\n").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-q", "-m", "work"]);
+    let state = serde_json::json!({
+        "base_branch": "main",
+        "source_branch": "feat/big",
+        "repo_owner": "acme",
+        "repo_name": "myrepo",
+        "strategy": "independent",
+        "chunks": []
+    });
+    std::fs::write(root.join(".merges.json"), serde_json::to_string_pretty(&state).unwrap()).unwrap();
+    merges::split::apply_plan(&root, vec![
+        merges::split::ChunkPlan { name: "a".to_string(), files: vec!["a.rs".to_string()], ..Default::default() },
+        merges::split::ChunkPlan { name: "b".to_string(), files: vec!["b.rs".to_string()], ..Default::default() },
+    ])
+    .unwrap();
+
+    // main moves on and changes the same line chunk "a" does.
+    git(&root, &["checkout", "-q", "main"]);
+    std::fs::write(root.join("a.rs"), "three\n").unwrap();
+    git(&root, &["commit", "-q", "-am", "upstream"]);
+    git(&root, &["push", "-q", "origin", "main"]);
+    git(&root, &["checkout", "-q", "feat/big"]);
+    std::env::set_current_dir(&root).unwrap();
+
+    let before = git(&root, &["rev-parse", "feat/big-chunk-1-a"]);
+    let results = sync::run(false, false, false, false, false, &[], &CancelToken::default()).unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].outcome, SyncOutcome::Conflicted { files: vec!["a.rs".to_string()] });
+    assert_eq!(results[1].outcome, SyncOutcome::Rebased);
+    assert_eq!(git(&root, &["rev-parse", "feat/big-chunk-1-a"]), before);
+    assert_eq!(git(&root, &["rev-parse", "--abbrev-ref", "HEAD"]), "feat/big");
+    assert!(!root.join(".git/rebase-merge").exists(), "the conflicted rebase is aborted");
+    assert!(sync::check(&results).unwrap_err().to_string().contains("a: conflicts in a.rs"));
+
+    let results = sync::run(false, false, false, false, false, &["b".to_string()], &CancelToken::default()).unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].name, "b");
+    assert!(sync::run(false, false, false, false, false, &["nope".to_string()], &CancelToken::default()).is_err());
+}
@@ -0,0 +1,80 @@
+//! Tests for `merges sync` taking chunks whose PRs merged off the stack.
+
+use std::path::Path;
+use std::process::Command as StdCommand;
+use tempfile::TempDir;
+
+use merges::cancel::CancelToken;
+use merges::commands::sync::{self, SyncOutcome};
+use merges::state::MergesState;
+
+fn git(root: &Path, args: &[&str]) -> String {
+    let out = StdCommand::new("git").args(args).current_dir(root).output().unwrap();
+    assert!(out.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&out.stderr));
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
+
+/// The bottom chunk's PR was squash-merged: sync takes it off the stack,
+/// deletes its branch with `delete_merged`, and rebases the next chunk
+/// straight onto the base branch.
+#[test]
+fn test_sync_takes_squash_merged_chunk_off_the_stack() {
+    let dir = TempDir::new().unwrap();
+    let origin = dir.path().join("origin.git");
+    let root = dir.path().join("repo");
+    std::fs::create_dir_all(&root).unwrap();
+    git(dir.path(), &["init", "-q", "--bare", "-b", "main", origin.to_str().unwrap()]);
+    git(&root, &["init", "-q", "-b", "main"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    git(&root, &["config", "user.name", "Test"]);
+    git(&root, &["remote", "add", "origin", origin.to_str().unwrap()]);
+    std::fs::write(root.join("README.md"), "hello\n").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-q", "-m", "init"]);
+    git(&root, &["push", "-q", "origin", "main"]);
+
+    git(&root, &["checkout", "-q", "-b", "feat/big"]);
+    std::fs::write(root.join("a.rs"), "// a\n").unwrap();
+    std::fs::write(root.join("b.rs"), "// b\n").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-q", "-m", "work"]);
+    let state = serde_json::json!({
+        "base_branch": "main",
+        "source_branch": "feat/big",
+        "repo_owner": "acme",
+        "repo_name": "myrepo",
+        "strategy": "stacked",
+        "chunks": []
+    });
+    std::fs::write(root.join(".merges.json"), serde_json::to_string_pretty(&state).unwrap()).unwrap();
+    merges::split::apply_plan(&root, vec![
+        merges::split::ChunkPlan { name: "a".to_string(), files: vec!["a.rs".to_string()], ..Default::default() },
+        merges::split::ChunkPlan { name: "b".to_string(), files: vec!["b.rs".to_string()], ..Default::default() },
+    ])
+    .unwrap();
+    git(&root, &["rebase", "-q", "--onto", "feat/big-chunk-1-a", "main", "feat/big-chunk-2-b"]);
+    let mut state = MergesState::load(&root).unwrap();
+    state.chunks[0].pr_number = Some(101);
+    state.chunks[0].merged_at = Some(chrono::Utc::now());
+    state.chunks[1].pr_number = Some(102);
+    state.save(&root).unwrap();
+
+    // PR #101 is squash-merged: main gets chunk a's changes as a new commit.
+    git(&root, &["checkout", "-q", "main"]);
+    git(&root, &["merge", "-q", "--squash", "feat/big-chunk-1-a"]);
+    git(&root, &["commit", "-q", "-m", "chunk a (#101)"]);
+    git(&root, &["push", "-q", "origin", "main"]);
+    git(&root, &["checkout", "-q", "feat/big"]);
+    std::env::set_current_dir(&root).unwrap();
+
+    let results = sync::run(false, false, true, false, false, &[], &CancelToken::default()).unwrap();
+    assert_eq!(results[0].outcome, SyncOutcome::Merged);
+    assert_eq!(results[1].outcome, SyncOutcome::Rebased);
+
+    let state = MergesState::load(&root).unwrap();
+    assert_eq!(state.chunks.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(), vec!["b"]);
+    assert_eq!(state.merged.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(), vec!["a"]);
+    assert!(merges::git::rev_parse(&root, "refs/heads/feat/big-chunk-1-a").is_err());
+    assert_eq!(git(&root, &["rev-parse", "feat/big-chunk-2-b^"]), git(&root, &["rev-parse", "origin/main"]));
+    assert_eq!(git(&root, &["rev-list", "--count", "origin/main..feat/big-chunk-2-b"]), "1");
+}
@@ -0,0 +1,100 @@
+//! Tests for finishing an interrupted `merges sync` with `--resume`. Kept
+//! apart from the other sync tests because `sync::run` works from the
+//! current directory.
+
+use std::path::Path;
+use std::process::Command as StdCommand;
+use tempfile::TempDir;
+
+use merges::cancel::CancelToken;
+use merges::commands::sync::{self, SyncOutcome};
+use merges::journal;
+use merges::state::MergesState;
+
+fn git(root: &Path, args: &[&str]) -> String {
+    let out = StdCommand::new("git").args(args).current_dir(root).output().unwrap();
+    assert!(out.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&out.stderr));
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
+
+/// A sync killed after rebasing the bottom chunk of a three-chunk stack is
+/// refused until resumed, and resuming rebases the other two from where they
+/// forked before it began — not from the rebased bottom chunk, which would
+/// replay its old commit on top of its new one.
+#[test]
+fn test_resume_finishes_a_sync_killed_halfway() {
+    let dir = TempDir::new().unwrap();
+    let origin = dir.path().join("origin.git");
+    let root = dir.path().join("repo");
+    std::fs::create_dir_all(&root).unwrap();
+    git(dir.path(), &["init", "-q", "--bare", "-b", "main", origin.to_str().unwrap()]);
+    git(&root, &["init", "-q", "-b", "main"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    git(&root, &["config", "user.name", "Test"]);
+    git(&root, &["remote", "add", "origin", origin.to_str().unwrap()]);
+    std::fs::write(root.join("README.md"), "hello\n").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-q", "-m", "init"]);
+    git(&root, &["push", "-q", "origin", "main"]);
+
+    git(&root, &["checkout", "-q", "-b", "feat/big"]);
+    for file in ["a.rs", "b.rs", "c.rs"] {
+        std::fs::write(root.join(file), format!("// {}\n", file)).unwrap();
+    }
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-q", "-m", "work"]);
+    let state = serde_json::json!({
+        "base_branch": "main",
+        "source_branch": "feat/big",
+        "repo_owner": "acme",
+        "repo_name": "myrepo",
+        "strategy": "stacked",
+        "chunks": []
+    });
+    std::fs::write(root.join(".merges.json"), serde_json::to_string_pretty(&state).unwrap()).unwrap();
+    merges::split::apply_plan(&root, ["a", "b", "c"].iter().map(|name| merges::split::ChunkPlan {
+        name: name.to_string(),
+        files: vec![format!("{}.rs", name)],
+        ..Default::default()
+    }).collect())
+    .unwrap();
+    git(&root, &["rebase", "-q", "--onto", "feat/big-chunk-1-a", "main", "feat/big-chunk-2-b"]);
+    git(&root, &["rebase", "-q", "--onto", "feat/big-chunk-2-b", "main", "feat/big-chunk-3-c"]);
+
+    git(&root, &["checkout", "-q", "main"]);
+    std::fs::write(root.join("up.rs"), "// upstream\n").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-q", "-m", "upstream"]);
+    git(&root, &["push", "-q", "origin", "main"]);
+    git(&root, &["fetch", "-q", "origin"]);
+    git(&root, &["checkout", "-q", "feat/big"]);
+
+    // What a sync records before it is killed right after rebasing 'a'.
+    let chunks = MergesState::load(&root).unwrap().chunks;
+    let mut branches: std::collections::BTreeMap<String, String> =
+        chunks.iter().map(|c| (c.branch.clone(), git(&root, &["rev-parse", &c.branch]))).collect();
+    branches.insert("origin/main".to_string(), git(&root, &["rev-parse", "origin/main"]));
+    journal::append(&root, &journal::Entry {
+        at: chrono::Utc::now(),
+        operation: "sync-start".to_string(),
+        chunks: chunks.iter().map(|c| c.name.clone()).collect(),
+        branches,
+        summary: "onto origin/main".to_string(),
+    })
+    .unwrap();
+    git(&root, &["rebase", "-q", "--onto", "origin/main", "main~1", "feat/big-chunk-1-a"]);
+    git(&root, &["checkout", "-q", "feat/big"]);
+    journal::record(&root, "sync-chunk", "rebased onto origin/main", &chunks[..1]).unwrap();
+    std::env::set_current_dir(&root).unwrap();
+
+    let err = sync::run(false, false, false, false, false, &[], &CancelToken::default()).unwrap_err();
+    assert!(err.to_string().contains("stopped after rebasing 1 of 3 chunk(s) (a)"), "{}", err);
+
+    let results = sync::run(false, false, false, true, false, &[], &CancelToken::default()).unwrap();
+    assert_eq!(results.iter().map(|r| r.name.as_str()).collect::<Vec<_>>(), vec!["b", "c"]);
+    assert!(results.iter().all(|r| r.outcome == SyncOutcome::Rebased), "{:?}", results);
+    assert_eq!(git(&root, &["rev-parse", "feat/big-chunk-2-b^"]), git(&root, &["rev-parse", "feat/big-chunk-1-a"]));
+    assert_eq!(git(&root, &["rev-parse", "feat/big-chunk-3-c^"]), git(&root, &["rev-parse", "feat/big-chunk-2-b"]));
+    assert_eq!(git(&root, &["rev-list", "--count", "origin/main..feat/big-chunk-3-c"]), "3");
+    assert!(sync::interrupted(&root).unwrap().is_none());
+}
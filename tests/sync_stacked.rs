@@ -0,0 +1,70 @@
+//! Tests for `merges sync` in stacked mode. Kept apart from the other sync
+//! tests because `sync::run` works from the current directory.
+
+use std::path::Path;
+use std::process::Command as StdCommand;
+use tempfile::TempDir;
+
+use merges::cancel::CancelToken;
+use merges::commands::sync::{self, SyncOutcome};
+
+fn git(root: &Path, args: &[&str]) -> String {
+    let out = StdCommand::new("git").args(args).current_dir(root).output().unwrap();
+    assert!(out.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&out.stderr));
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
+
+/// Stacked chunks are rebased onto the chunk below them, in order, so the
+/// chain survives and each branch carries only its own commit on top.
+#[test]
+fn test_sync_rebases_stacked_chunks_onto_their_parent() {
+    let dir = TempDir::new().unwrap();
+    let origin = dir.path().join("origin.git");
+    let root = dir.path().join("repo");
+    std::fs::create_dir_all(&root).unwrap();
+    git(dir.path(), &["init", "-q", "--bare", "-b", "main", origin.to_str().unwrap()]);
+    git(&root, &["init", "-q", "-b", "main"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    git(&root, &["config", "user.name", "Test"]);
+    git(&root, &["remote", "add", "origin", origin.to_str().unwrap()]);
+    std::fs::write(root.join("README.md"), "hello\n").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-q", "-m", "init"]);
+    git(&root, &["push", "-q", "origin", "main"]);
+
+    git(&root, &["checkout", "-q", "-b", "feat/big"]);
+    std::fs::write(root.join("a.rs"), "// a\n").unwrap();
+    std::fs::write(root.join("b.rs"), "// b\n").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-q", "-m", "work"]);
+    let state = serde_json::json!({
+        "base_branch": "main",
+        "source_branch": "feat/big",
+        "repo_owner": "acme",
+        "repo_name": "myrepo",
+        "strategy": "stacked",
+        "chunks": []
+    });
+    std::fs::write(root.join(".merges.json"), serde_json::to_string_pretty(&state).unwrap()).unwrap();
+    merges::split::apply_plan(&root, vec![
+        merges::split::ChunkPlan { name: "a".to_string(), files: vec!["a.rs".to_string()], ..Default::default() },
+        merges::split::ChunkPlan { name: "b".to_string(), files: vec!["b.rs".to_string()], ..Default::default() },
+    ])
+    .unwrap();
+    git(&root, &["rebase", "-q", "--onto", "feat/big-chunk-1-a", "main", "feat/big-chunk-2-b"]);
+
+    git(&root, &["checkout", "-q", "main"]);
+    std::fs::write(root.join("c.rs"), "// c\n").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-q", "-m", "upstream"]);
+    git(&root, &["push", "-q", "origin", "main"]);
+    git(&root, &["checkout", "-q", "feat/big"]);
+    std::env::set_current_dir(&root).unwrap();
+
+    let results = sync::run(false, false, false, false, false, &[], &CancelToken::default()).unwrap();
+    assert!(results.iter().all(|r| r.outcome == SyncOutcome::Rebased), "{:?}", results);
+    assert_eq!(git(&root, &["rev-parse", "feat/big-chunk-1-a^"]), git(&root, &["rev-parse", "origin/main"]));
+    assert_eq!(git(&root, &["rev-parse", "feat/big-chunk-2-b^"]), git(&root, &["rev-parse", "feat/big-chunk-1-a"]));
+    assert_eq!(git(&root, &["rev-list", "--count", "origin/main..feat/big-chunk-2-b"]), "2");
+    assert_eq!(git(&root, &["rev-parse", "--abbrev-ref", "HEAD"]), "feat/big");
+}
@@ -0,0 +1,101 @@
+//! Tests for the umbrella tracking issue body rendered by `merges push --tracking-issue`.
+
+use merges::commands::push::tracking_issue_body;
+use merges::state::{Chunk, ForgeKind, MergesState, Strategy};
+
+fn state() -> MergesState {
+    let chunk = |name: &str, pr: Option<u64>| Chunk {
+        name: name.to_string(),
+        branch: format!("feat/big-chunk-{}", name),
+        files: vec![],
+        description: None,
+        pr_number: pr,
+        pr_url: None,
+        pushed_sha: None,
+        pr_title: None,
+        custom_commit_message: None,
+        custom_pr_title: None,
+        custom_pr_body: None,
+        skip_ci: false,
+        hold: false,
+        created_at: None,
+        last_pushed_at: None,
+        merged_at: None,
+        stack_comment_id: None,
+        routed: vec![],
+        milestone: None,
+        project_item_id: None,
+        project_status: None,
+    };
+    MergesState {
+        base_branch: "main".to_string(),
+        source_branch: "feat/big".to_string(),
+        source_sha: None,
+        repo_owner: "acme".to_string(),
+        repo_name: "myrepo".to_string(),
+        forge: ForgeKind::Github,
+        forge_api_url: None,
+        strategy: Strategy::Stacked,
+        use_worktrees: false,
+        exclude: vec![],
+        routes: vec![],
+        commit_prefix: None,
+        no_title_position: false,
+        max_subject_length: None,
+        max_title_length: None,
+        auto_depth: None,
+        root_files: None,
+        no_stack_comments: false,
+        branch_namespace: None,
+        autostash: false,
+        preserve_history: false,
+        squash_on_push: false,
+        sync_merge: false,
+        post_summary: false,
+        project: None,
+        milestone: None,
+        size_limits: Default::default(),
+        tracking_issue: None,
+        source_pr: None,
+        push_interrupted: false,
+        chunks: vec![chunk("db", Some(101)), chunk("models", Some(102)), chunk("api", None)],
+        merged: vec![],
+    }
+}
+
+/// Merged PRs are checked off; open PRs are not.
+#[test]
+fn test_tracking_issue_checks_off_merged_prs() {
+    let body = tracking_issue_body(&state(), &[101]);
+    assert!(body.contains("- [x] #101 db"), "{}", body);
+    assert!(body.contains("- [ ] #102 models"), "{}", body);
+}
+
+/// Chunks without a PR still appear so the checklist covers the whole stack.
+#[test]
+fn test_tracking_issue_lists_chunks_without_pr() {
+    let body = tracking_issue_body(&state(), &[]);
+    assert!(body.contains("- [ ] api (no PR yet)"), "{}", body);
+}
+
+/// Items follow stack order and the progress line counts merged PRs.
+#[test]
+fn test_tracking_issue_order_and_progress() {
+    let body = tracking_issue_body(&state(), &[101, 102]);
+    let db = body.find("db").unwrap();
+    let models = body.find("models").unwrap();
+    let api = body.find("api (no PR").unwrap();
+    assert!(db < models && models < api, "chunks must be in stack order: {}", body);
+    assert!(body.contains("2/3 merged"), "{}", body);
+}
+
+/// Chunks already taken off the stack lead the checklist, checked off and
+/// counted in the progress line.
+#[test]
+fn test_tracking_issue_counts_chunks_taken_off_the_stack() {
+    let mut state = state();
+    state.merged = state.chunks.drain(..1).collect();
+    let body = tracking_issue_body(&state, &[102]);
+    assert!(body.contains("- [x] #101 db\n- [x] #102 models\n- [ ] api (no PR yet)"), "{}", body);
+    assert!(body.contains("2/3 merged"), "{}", body);
+}
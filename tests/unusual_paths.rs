@@ -0,0 +1,104 @@
+//! Paths git would quote or read as a pattern: spaces, quotes, non-ASCII
+//! characters, and glob characters.
+
+use std::path::{Path, PathBuf};
+use std::process::Command as StdCommand;
+use tempfile::TempDir;
+
+fn git(root: &Path, args: &[&str]) -> String {
+    let out = StdCommand::new("git").args(args).current_dir(root).output().unwrap();
+    assert!(out.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&out.stderr));
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
+
+fn make_repo() -> (TempDir, PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().to_path_buf();
+
+    git(&root, &["init", "-b", "main"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    git(&root, &["config", "user.name", "Test"]);
+    git(&root, &["config", "core.quotePath", "true"]);
+    std::fs::write(root.join("README.md"), "hello").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "init"]);
+    git(&root, &["checkout", "-q", "-b", "feat/big"]);
+    std::fs::create_dir_all(root.join("docs/user guide")).unwrap();
+    std::fs::write(root.join("docs/user guide/\"quoted\".md"), "quoted").unwrap();
+    std::fs::write(root.join("docs/résumé.md"), "résumé").unwrap();
+    std::fs::write(root.join("src[1].rs"), "one").unwrap();
+    std::fs::write(root.join("src1.rs"), "other").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "add unusual paths"]);
+
+    let state = serde_json::json!({
+        "base_branch": "main",
+        "source_branch": "feat/big",
+        "repo_owner": "acme",
+        "repo_name": "myrepo",
+        "strategy": "stacked",
+        "chunks": []
+    });
+    std::fs::write(root.join(".merges.json"), serde_json::to_string_pretty(&state).unwrap()).unwrap();
+    (dir, root)
+}
+
+#[test]
+fn test_changed_files_are_listed_verbatim() {
+    let (_dir, root) = make_repo();
+    let mut files = merges::git::changed_files(&root, "main").unwrap();
+    files.sort();
+    assert_eq!(files, vec!["docs/résumé.md", "docs/user guide/\"quoted\".md", "src1.rs", "src[1].rs"]);
+}
+
+/// `src[1].rs` is taken as that file, not as a pattern that matches `src1.rs`.
+#[test]
+fn test_split_takes_unusual_paths_literally() {
+    let (_dir, root) = make_repo();
+    merges::split::apply_plan(&root, vec![
+        merges::split::ChunkPlan {
+            name: "docs".to_string(),
+            files: vec!["docs/résumé.md".to_string(), "docs/user guide/\"quoted\".md".to_string()],
+            ..Default::default()
+        },
+        merges::split::ChunkPlan { name: "one".to_string(), files: vec!["src[1].rs".to_string()], ..Default::default() },
+        merges::split::ChunkPlan { name: "other".to_string(), files: vec!["src1.rs".to_string()], ..Default::default() },
+    ])
+    .unwrap();
+
+    let docs = git(&root, &["ls-tree", "-r", "-z", "--name-only", "feat/big-chunk-1-docs"]);
+    let docs: Vec<&str> = docs.split('\0').filter(|p| !p.is_empty()).collect();
+    assert_eq!(docs, vec!["README.md", "docs/résumé.md", "docs/user guide/\"quoted\".md"]);
+    let one = git(&root, &["ls-tree", "-r", "--name-only", "feat/big-chunk-2-one"]);
+    assert_eq!(one.lines().collect::<Vec<_>>(), vec!["README.md", "src[1].rs"]);
+}
+
+/// A path that is not UTF-8 is left out of the changed files instead of
+/// failing the listing for every other file, and diffcheck reports it as
+/// uncovered rather than calling the stack complete without it.
+#[cfg(target_os = "linux")]
+#[test]
+fn test_non_utf8_paths_are_left_out() {
+    use std::os::unix::ffi::OsStrExt;
+
+    let (_dir, root) = make_repo();
+    std::fs::write(root.join(std::ffi::OsStr::from_bytes(b"latin1-\xe9t\xe9.txt")), "bytes").unwrap();
+    git(&root, &["add", "--", "latin1-*"]);
+    git(&root, &["commit", "-m", "add a latin-1 name"]);
+
+    let mut files = merges::git::changed_files(&root, "main").unwrap();
+    files.sort();
+    assert_eq!(files, vec!["docs/résumé.md", "docs/user guide/\"quoted\".md", "src1.rs", "src[1].rs"]);
+
+    merges::split::apply_plan(&root, vec![merges::split::ChunkPlan {
+        name: "all".to_string(),
+        files,
+        ..Default::default()
+    }])
+    .unwrap();
+    let state = merges::state::MergesState::load(&root).unwrap();
+    let report = merges::commands::diffcheck::check(&root, &state).unwrap();
+    assert!(report.missing.is_empty(), "{:?}", report.missing);
+    assert_eq!(report.unsupported, vec!["latin1-\u{FFFD}t\u{FFFD}.txt"]);
+    assert!(!report.is_consistent());
+}
@@ -0,0 +1,99 @@
+//! Integration tests for `merges verify`.
+
+use merges::commands::verify::{SuggestedMove, VerifyOptions};
+use std::path::{Path, PathBuf};
+use std::process::Command as StdCommand;
+use tempfile::TempDir;
+
+fn git(root: &Path, args: &[&str]) -> String {
+    let out = StdCommand::new("git").args(args).current_dir(root).output().unwrap();
+    assert!(out.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&out.stderr));
+    String::from_utf8_lossy(&out.stdout).trim().to_string()
+}
+
+/// A split `feat/big` whose `api` chunk has `lib.rs` declaring `mod models;`
+/// while the `models` chunk owns `models.rs`, so `api` cannot build alone.
+fn make_split_repo() -> (TempDir, PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().to_path_buf();
+
+    git(&root, &["init", "-b", "main"]);
+    git(&root, &["config", "user.email", "test@example.com"]);
+    git(&root, &["config", "user.name", "Test"]);
+    std::fs::write(root.join("README.md"), "hello").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "init"]);
+    git(&root, &["checkout", "-q", "-b", "feat/big"]);
+    std::fs::write(root.join("lib.rs"), "mod models;\n").unwrap();
+    std::fs::write(root.join("models.rs"), "pub struct User;\n").unwrap();
+    std::fs::write(root.join("schema.rs"), "pub struct Table;\n").unwrap();
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-m", "add feature files"]);
+
+    let state = serde_json::json!({
+        "base_branch": "main",
+        "source_branch": "feat/big",
+        "repo_owner": "acme",
+        "repo_name": "myrepo",
+        "strategy": "independent",
+        "chunks": []
+    });
+    std::fs::write(root.join(".merges.json"), serde_json::to_string_pretty(&state).unwrap()).unwrap();
+    merges::split::apply_plan(&root, vec![
+        merges::split::ChunkPlan {
+            name: "models".to_string(),
+            files: vec!["models.rs".to_string(), "schema.rs".to_string()],
+            ..Default::default()
+        },
+        merges::split::ChunkPlan { name: "api".to_string(), files: vec!["lib.rs".to_string()], ..Default::default() },
+    ])
+    .unwrap();
+    (dir, root)
+}
+
+/// A stand-in for `cargo check`: fails the way rustc does when `lib.rs`
+/// declares a module whose file is missing.
+fn check() -> Vec<String> {
+    let script = "test ! -f lib.rs || test -f models.rs || { \
+        echo 'error[E0583]: file not found for module `models`' >&2; \
+        echo ' --> lib.rs:1:1' >&2; exit 101; }";
+    vec!["sh".to_string(), "-c".to_string(), script.to_string()]
+}
+
+#[test]
+fn test_verify_reports_failing_chunks_without_suggestions() {
+    let (_dir, root) = make_split_repo();
+    let report = merges::commands::verify::run(&root, &check(), &[], VerifyOptions::default()).unwrap();
+    assert_eq!(report.failed, vec!["api"]);
+    assert!(report.moves.is_empty());
+    assert_eq!(git(&root, &["rev-parse", "--abbrev-ref", "HEAD"]), "feat/big");
+}
+
+#[test]
+fn test_verify_suggests_moving_the_missing_module_into_the_chunk() {
+    let (_dir, root) = make_split_repo();
+    let opts = VerifyOptions { suggest_moves: true, ..Default::default() };
+
+    let report = merges::commands::verify::run(&root, &check(), &[], opts).unwrap();
+
+    assert_eq!(
+        report.moves,
+        vec![SuggestedMove { file: "models.rs".to_string(), from: "models".to_string(), to: "api".to_string() }]
+    );
+    let state = merges::state::MergesState::load(&root).unwrap();
+    assert_eq!(state.chunks[1].files, vec!["lib.rs"], "suggesting must not move anything");
+}
+
+#[test]
+fn test_verify_fix_moves_the_file_so_the_chunk_builds() {
+    let (_dir, root) = make_split_repo();
+    let opts = VerifyOptions { fix: true, ..Default::default() };
+
+    merges::commands::verify::run(&root, &check(), &[], opts).unwrap();
+
+    let state = merges::state::MergesState::load(&root).unwrap();
+    assert_eq!(state.chunks[0].files, vec!["schema.rs"]);
+    assert!(state.chunks[1].files.contains(&"models.rs".to_string()), "{:?}", state.chunks[1].files);
+    let report = merges::commands::verify::run(&root, &check(), &[], VerifyOptions::default()).unwrap();
+    assert!(report.failed.is_empty(), "{:?}", report.failed);
+}
@@ -155,8 +155,8 @@ fn test_apply_plan_worktrees_creates_worktree_dirs() {
     merges::split::apply_plan(
         &root,
         vec![
-            merges::split::ChunkPlan { name: "part-a".to_string(), files: vec!["src/a.rs".to_string()] },
-            merges::split::ChunkPlan { name: "part-b".to_string(), files: vec!["src/b.rs".to_string()] },
+            merges::split::ChunkPlan { name: "part-a".to_string(), files: vec!["src/a.rs".to_string()], ..Default::default() },
+            merges::split::ChunkPlan { name: "part-b".to_string(), files: vec!["src/b.rs".to_string()], ..Default::default() },
         ],
     ).unwrap();
 
@@ -176,7 +176,7 @@ fn test_apply_plan_worktrees_does_not_switch_branch() {
     merges::split::apply_plan(
         &root,
         vec![
-            merges::split::ChunkPlan { name: "part-a".to_string(), files: vec!["src/a.rs".to_string()] },
+            merges::split::ChunkPlan { name: "part-a".to_string(), files: vec!["src/a.rs".to_string()], ..Default::default() },
         ],
     ).unwrap();
 
@@ -193,8 +193,8 @@ fn test_apply_plan_worktrees_each_chunk_has_correct_files() {
     merges::split::apply_plan(
         &root,
         vec![
-            merges::split::ChunkPlan { name: "part-a".to_string(), files: vec!["src/a.rs".to_string()] },
-            merges::split::ChunkPlan { name: "part-b".to_string(), files: vec!["src/b.rs".to_string()] },
+            merges::split::ChunkPlan { name: "part-a".to_string(), files: vec!["src/a.rs".to_string()], ..Default::default() },
+            merges::split::ChunkPlan { name: "part-b".to_string(), files: vec!["src/b.rs".to_string()], ..Default::default() },
         ],
     ).unwrap();
 
@@ -214,8 +214,8 @@ fn test_apply_plan_worktrees_each_chunk_has_correct_files() {
 fn setup_worktree_chunks(root: &std::path::Path) {
     write_state(root, true);
     merges::split::apply_plan(root, vec![
-        merges::split::ChunkPlan { name: "part-a".to_string(), files: vec!["src/a.rs".to_string()] },
-        merges::split::ChunkPlan { name: "part-b".to_string(), files: vec!["src/b.rs".to_string()] },
+        merges::split::ChunkPlan { name: "part-a".to_string(), files: vec!["src/a.rs".to_string()], ..Default::default() },
+        merges::split::ChunkPlan { name: "part-b".to_string(), files: vec!["src/b.rs".to_string()], ..Default::default() },
     ]).unwrap();
 }
 
@@ -254,7 +254,7 @@ fn test_add_worktrees_does_not_switch_branch() {
     let (_dir, root) = make_repo();
     setup_worktree_chunks(&root);
 
-    merges::commands::add::run(&root, "part-a", &["src/c.rs".to_string()]).unwrap();
+    merges::commands::add::run(&root, "part-a", &["src/c.rs".to_string()], false).unwrap();
 
     let branch = merges::git::current_branch(&root).unwrap();
     assert_eq!(branch, "feat/big",
@@ -267,7 +267,7 @@ fn test_add_worktrees_file_in_worktree_diff() {
     let (_dir, root) = make_repo();
     setup_worktree_chunks(&root);
 
-    merges::commands::add::run(&root, "part-a", &["src/c.rs".to_string()]).unwrap();
+    merges::commands::add::run(&root, "part-a", &["src/c.rs".to_string()], false).unwrap();
 
     let wt = merges::git::worktree_path(&root, "feat/big-chunk-1-part-a");
     let mut files = merges::git::changed_files(&wt, "main").unwrap();
@@ -282,7 +282,7 @@ fn test_add_worktrees_updates_state() {
     let (_dir, root) = make_repo();
     setup_worktree_chunks(&root);
 
-    merges::commands::add::run(&root, "part-a", &["src/c.rs".to_string()]).unwrap();
+    merges::commands::add::run(&root, "part-a", &["src/c.rs".to_string()], false).unwrap();
 
     let state = merges::state::MergesState::load(&root).unwrap();
     let chunk = state.chunks.iter().find(|c| c.name == "part-a").unwrap();
@@ -298,7 +298,7 @@ fn test_move_worktrees_does_not_switch_branch() {
     let (_dir, root) = make_repo();
     setup_worktree_chunks(&root);
 
-    merges::commands::r#move::run(&root, "src/b.rs", "part-b", "part-a").unwrap();
+    merges::commands::r#move::run(&root, "src/b.rs", "part-b", "part-a", false).unwrap();
 
     let branch = merges::git::current_branch(&root).unwrap();
     assert_eq!(branch, "feat/big",
@@ -311,7 +311,7 @@ fn test_move_worktrees_removes_from_source_worktree() {
     let (_dir, root) = make_repo();
     setup_worktree_chunks(&root);
 
-    merges::commands::r#move::run(&root, "src/b.rs", "part-b", "part-a").unwrap();
+    merges::commands::r#move::run(&root, "src/b.rs", "part-b", "part-a", false).unwrap();
 
     let wt_b = merges::git::worktree_path(&root, "feat/big-chunk-2-part-b");
     let files = merges::git::changed_files(&wt_b, "main").unwrap();
@@ -325,7 +325,7 @@ fn test_move_worktrees_adds_to_dest_worktree() {
     let (_dir, root) = make_repo();
     setup_worktree_chunks(&root);
 
-    merges::commands::r#move::run(&root, "src/b.rs", "part-b", "part-a").unwrap();
+    merges::commands::r#move::run(&root, "src/b.rs", "part-b", "part-a", false).unwrap();
 
     let wt_a = merges::git::worktree_path(&root, "feat/big-chunk-1-part-a");
     let mut files = merges::git::changed_files(&wt_a, "main").unwrap();